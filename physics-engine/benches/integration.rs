@@ -19,18 +19,19 @@
 //! - Memory efficiency (buffer reuse, allocations)
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use physics_engine::Real;
 use physics_engine::ecs::components::{Position, Velocity, Mass, Acceleration};
 use physics_engine::ecs::systems::{ForceRegistry, ForceProvider, Force};
-use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage};
+use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage, World};
 use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator};
 
 // Spring force provider for harmonic oscillator tests
 struct SpringForce {
-    spring_constant: f64,
+    spring_constant: Real,
 }
 
 impl SpringForce {
-    fn new(spring_constant: f64) -> Self {
+    fn new(spring_constant: Real) -> Self {
         SpringForce { spring_constant }
     }
 }
@@ -54,10 +55,11 @@ impl ForceProvider for SpringForce {
 }
 
 // Create a simple harmonic oscillator system
+#[allow(clippy::type_complexity)]
 fn setup_harmonic_oscillator(
     entity_count: usize,
-    spring_constant: f64,
-    mass: f64,
+    spring_constant: Real,
+    mass: Real,
 ) -> (
     Vec<Entity>,
     HashMapStorage<Position>,
@@ -66,6 +68,7 @@ fn setup_harmonic_oscillator(
     HashMapStorage<Mass>,
     ForceRegistry,
 ) {
+    let mut world = World::with_capacity(entity_count);
     let mut entities = Vec::new();
     let mut positions = HashMapStorage::<Position>::new();
     let mut velocities = HashMapStorage::<Velocity>::new();
@@ -74,11 +77,11 @@ fn setup_harmonic_oscillator(
 
     // Create entities with varying initial conditions
     for i in 0..entity_count {
-        let entity = Entity::new(i as u64, 0);
+        let entity = world.create_entity();
         entities.push(entity);
 
         // Vary initial displacement slightly to avoid perfect symmetry
-        let x0 = 1.0 + (i as f64) * 0.01;
+        let x0 = 1.0 + (i as Real) * 0.01;
         positions.insert(entity, Position::new(x0, 0.0, 0.0));
         velocities.insert(entity, Velocity::new(0.0, 0.0, 0.0));
         masses.insert(entity, Mass::new(mass));
@@ -152,10 +155,10 @@ fn bench_integrator_accuracy(c: &mut Criterion) {
     let mut group = c.benchmark_group("integrator_accuracy");
     group.sample_size(20); // Fewer samples for accuracy tests
 
-    let k = 100.0_f64; // spring constant
-    let m = 1.0_f64; // mass
+    let k: Real = 100.0; // spring constant
+    let m: Real = 1.0; // mass
     let omega = (k / m).sqrt(); // angular frequency
-    let period = 2.0 * std::f64::consts::PI / omega; // period of oscillation
+    let period = 2.0 * physics_engine::real::consts::PI / omega; // period of oscillation
     let dt = period / 100.0; // timestep = 1/100 of period
     let steps = 100; // simulate one full period
 
@@ -215,8 +218,8 @@ fn bench_free_motion(c: &mut Criterion) {
 
     // Benchmark Verlet with no forces (cheapest case)
     group.bench_function("verlet_free", |b| {
-        let entity = Entity::new(1, 0);
-        let entities = vec![entity];
+        let entity = World::new().create_entity();
+        let entities = [entity];
         let mut positions = HashMapStorage::<Position>::new();
         positions.insert(entity, Position::new(0.0, 0.0, 0.0));
         let mut velocities = HashMapStorage::<Velocity>::new();
@@ -242,8 +245,8 @@ fn bench_free_motion(c: &mut Criterion) {
 
     // Benchmark RK4 with no forces
     group.bench_function("rk4_free", |b| {
-        let entity = Entity::new(1, 0);
-        let entities = vec![entity];
+        let entity = World::new().create_entity();
+        let entities = [entity];
         let mut positions = HashMapStorage::<Position>::new();
         positions.insert(entity, Position::new(0.0, 0.0, 0.0));
         let mut velocities = HashMapStorage::<Velocity>::new();
@@ -270,6 +273,83 @@ fn bench_free_motion(c: &mut Criterion) {
     group.finish();
 }
 
+/// Dense Verlet steps at scale: serial chunks, parallel chunks, and the
+/// kick/drift kernels alone on parallel chunks through the SIMD helpers
+///
+/// `parallel_simd` skips force evaluation, so it bounds what the integrator
+/// could reach; build with `--features simd` for the vectorized kernels.
+fn bench_parallel_integration(c: &mut Criterion) {
+    use physics_engine::ecs::{AccelerationSoAStorage, MassSoAStorage, PositionSoAStorage, VelocitySoAStorage};
+    use physics_engine::integration::{simd_update_positions, simd_update_velocities};
+    use rayon::prelude::*;
+
+    const CHUNK: usize = 4096;
+    let dt = 0.01;
+
+    let mut group = c.benchmark_group("parallel_integration");
+    group.sample_size(10);
+
+    for size in [100_000, 1_000_000] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        let mut world = World::with_capacity(size);
+        let entities: Vec<Entity> = (0..size).map(|_| world.create_entity()).collect();
+        let mut positions = PositionSoAStorage::with_capacity(size);
+        let mut velocities = VelocitySoAStorage::with_capacity(size);
+        let mut accelerations = AccelerationSoAStorage::with_capacity(size);
+        let mut masses = MassSoAStorage::with_capacity(size);
+        for (i, entity) in entities.iter().enumerate() {
+            let t = i as Real;
+            positions.insert(*entity, Position::new(t, 0.0, 0.0));
+            velocities.insert(*entity, Velocity::new(1.0, 0.5, 0.0));
+            accelerations.insert(*entity, Acceleration::new(0.0, -9.81, 0.0));
+            masses.insert(*entity, Mass::new(1.0));
+        }
+        let mut force_registry = ForceRegistry::new();
+
+        for (label, parallel) in [("serial", false), ("parallel", true)] {
+            let mut integrator = VelocityVerletIntegrator::new(dt);
+            integrator.set_parallel(parallel);
+            group.bench_with_input(BenchmarkId::new(label, size), &size, |b, _| {
+                b.iter(|| {
+                    integrator.integrate(
+                        black_box(entities.iter()),
+                        black_box(&mut positions),
+                        black_box(&mut velocities),
+                        black_box(&accelerations),
+                        black_box(&masses),
+                        black_box(&mut force_registry),
+                        false,
+                    )
+                });
+            });
+        }
+
+        group.bench_with_input(BenchmarkId::new("parallel_simd", size), &size, |b, &size| {
+            let (mut px, mut py, mut pz) = (vec![0.0; size], vec![0.0; size], vec![0.0; size]);
+            let (mut vx, mut vy, mut vz) = (vec![1.0; size], vec![0.5; size], vec![0.0; size]);
+            let (ax, ay, az) = (vec![0.0; size], vec![-9.81; size], vec![0.0; size]);
+            b.iter(|| {
+                px.par_chunks_mut(CHUNK)
+                    .zip(py.par_chunks_mut(CHUNK))
+                    .zip(pz.par_chunks_mut(CHUNK))
+                    .zip(vx.par_chunks_mut(CHUNK))
+                    .zip(vy.par_chunks_mut(CHUNK))
+                    .zip(vz.par_chunks_mut(CHUNK))
+                    .enumerate()
+                    .for_each(|(chunk, (((((px, py), pz), vx), vy), vz))| {
+                        let range = chunk * CHUNK..chunk * CHUNK + px.len();
+                        let (ax, ay, az) = (&ax[range.clone()], &ay[range.clone()], &az[range]);
+                        simd_update_positions(px, py, pz, vx, vy, vz, ax, ay, az, black_box(dt));
+                        simd_update_velocities(vx, vy, vz, ax, ay, az, black_box(dt));
+                    });
+            });
+        });
+    }
+
+    group.finish();
+}
+
 #[cfg(feature = "simd")]
 fn bench_simd_operations(c: &mut Criterion) {
     use physics_engine::integration::{simd_update_velocities, simd_update_positions, simd_accumulate_forces};
@@ -370,9 +450,52 @@ fn bench_simd_operations(c: &mut Criterion) {
 }
 
 #[cfg(feature = "simd")]
-criterion_group!(benches, bench_integrator_throughput, bench_integrator_accuracy, bench_free_motion, bench_simd_operations);
+fn bench_dense_force_pipeline(c: &mut Criterion) {
+    use physics_engine::integration::dense_force_pipeline_with_backend;
+    use physics_engine::simd::{select_backend, ScalarBackend, SimdBackend};
+    
+    let mut group = c.benchmark_group("dense_force_pipeline");
+    let size = 100_000;
+    group.throughput(Throughput::Elements(size as u64));
+    
+    // Three providers' worth of per-entity forces
+    let providers: Vec<Vec<Real>> = (0..9)
+        .map(|k| (0..size).map(|i| ((i + k) as Real * 0.01).sin()).collect())
+        .collect();
+    let contributions: Vec<[&[Real]; 3]> = providers
+        .chunks_exact(3)
+        .map(|p| [&p[0][..], &p[1][..], &p[2][..]])
+        .collect();
+    let inv_mass: Vec<Real> = (0..size).map(|i| 1.0 / (1.0 + i as Real)).collect();
+    
+    let selected = select_backend();
+    let backends: [(&str, &dyn SimdBackend); 2] = [("scalar", &ScalarBackend), ("simd", selected.as_ref())];
+    for (label, backend) in backends {
+        group.bench_function(BenchmarkId::new(label, size), |b| {
+            let (mut fx, mut fy, mut fz) = (vec![0.0; size], vec![0.0; size], vec![0.0; size]);
+            let (mut ax, mut ay, mut az) = (vec![0.0; size], vec![0.0; size], vec![0.0; size]);
+            b.iter(|| {
+                fx.fill(0.0);
+                fy.fill(0.0);
+                fz.fill(0.0);
+                dense_force_pipeline_with_backend(
+                    black_box(backend),
+                    black_box(&contributions),
+                    black_box(&inv_mass),
+                    [&mut fx, &mut fy, &mut fz],
+                    [&mut ax, &mut ay, &mut az],
+                )
+            });
+        });
+    }
+    
+    group.finish();
+}
+
+#[cfg(feature = "simd")]
+criterion_group!(benches, bench_integrator_throughput, bench_integrator_accuracy, bench_free_motion, bench_parallel_integration, bench_simd_operations, bench_dense_force_pipeline);
 
 #[cfg(not(feature = "simd"))]
-criterion_group!(benches, bench_integrator_throughput, bench_integrator_accuracy, bench_free_motion);
+criterion_group!(benches, bench_integrator_throughput, bench_integrator_accuracy, bench_free_motion, bench_parallel_integration);
 
 criterion_main!(benches);
@@ -16,6 +16,7 @@
 //! Measures the impact of memory pooling on allocation churn and frame times.
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use physics_engine::Real;
 use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage, World};
 use physics_engine::ecs::components::{Position, Velocity, Acceleration, Mass};
 use physics_engine::ecs::systems::{ForceRegistry, ForceProvider, Force};
@@ -37,6 +38,7 @@ impl ForceProvider for ConstantForce {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn setup_simulation(n_entities: usize) -> (
     Vec<Entity>,
     HashMapStorage<Position>,
@@ -54,7 +56,7 @@ fn setup_simulation(n_entities: usize) -> (
     let entities: Vec<Entity> = (0..n_entities)
         .map(|i| {
             let entity = world.create_entity();
-            positions.insert(entity, Position::new(i as f64, 0.0, 0.0));
+            positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
             velocities.insert(entity, Velocity::new(1.0, 0.0, 0.0));
             accelerations.insert(entity, Acceleration::new(0.1, 0.0, 0.0));
             masses.insert(entity, Mass::new(1.0));
@@ -0,0 +1,151 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Benchmarks for neighborhood queries
+//!
+//! Compares `NeighborSearch` radius and k-nearest queries against a brute
+//! force scan at 100k points, and measures the cost of rebuilding the tree.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use physics_engine::Real;
+use physics_engine::ecs::components::Position;
+use physics_engine::ecs::{ComponentStorage, Entity, HashMapStorage, PositionSoAStorage, World};
+use physics_engine::scenarios::DeterministicRng;
+use physics_engine::spatial::NeighborSearch;
+
+const POINT_COUNT: usize = 100_000;
+const QUERY_COUNT: usize = 100;
+
+/// Half-width of the cube holding the points
+const HALF_WIDTH: Real = 100.0;
+
+/// Radius enclosing about 40 points on average
+const RADIUS: Real = 6.0;
+
+const K: usize = 16;
+
+fn random_position(rng: &mut DeterministicRng) -> Position {
+    let [x, y, z] = [0; 3].map(|_| rng.next_f64_range(-HALF_WIDTH, HALF_WIDTH));
+    Position::new(x, y, z)
+}
+
+fn setup() -> (Vec<Entity>, HashMapStorage<Position>, Vec<Position>) {
+    let mut world = World::with_capacity(POINT_COUNT);
+    let mut rng = DeterministicRng::new(42);
+    let mut positions = HashMapStorage::new();
+    let entities = (0..POINT_COUNT)
+        .map(|_| {
+            let entity = world.create_entity();
+            positions.insert(entity, random_position(&mut rng));
+            entity
+        })
+        .collect();
+    let queries = (0..QUERY_COUNT).map(|_| random_position(&mut rng)).collect();
+    (entities, positions, queries)
+}
+
+fn distance_sq(a: &Position, b: &Position) -> Real {
+    let (dx, dy, dz) = (a.x() - b.x(), a.y() - b.y(), a.z() - b.z());
+    dx * dx + dy * dy + dz * dz
+}
+
+fn brute_force_within(points: &[(Entity, Position)], query: &Position, radius: Real) -> Vec<Entity> {
+    points
+        .iter()
+        .filter(|(_, p)| distance_sq(p, query) <= radius * radius)
+        .map(|(e, _)| *e)
+        .collect()
+}
+
+fn brute_force_nearest(points: &[(Entity, Position)], query: &Position, k: usize) -> Vec<(Entity, Real)> {
+    let mut distances: Vec<(Entity, Real)> = points.iter().map(|(e, p)| (*e, distance_sq(p, query))).collect();
+    distances.select_nth_unstable_by(k - 1, |a, b| a.1.total_cmp(&b.1));
+    distances.truncate(k);
+    distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+    distances.into_iter().map(|(e, d)| (e, d.sqrt())).collect()
+}
+
+/// Benchmark: radius and k-nearest queries, tree vs brute force
+fn bench_queries(c: &mut Criterion) {
+    let (entities, positions, queries) = setup();
+    let points: Vec<(Entity, Position)> = entities.iter().map(|&e| (e, *positions.get(e).unwrap())).collect();
+    let search = NeighborSearch::build(entities.iter(), &positions);
+
+    let mut group = c.benchmark_group("neighbor_queries");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(QUERY_COUNT as u64));
+
+    group.bench_function(BenchmarkId::new("within_radius/tree", POINT_COUNT), |b| {
+        let mut found = Vec::new();
+        b.iter(|| {
+            for query in &queries {
+                found.clear();
+                search.within_radius_into(query, RADIUS, &mut found);
+                black_box(&found);
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("within_radius/brute_force", POINT_COUNT), |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(brute_force_within(&points, query, RADIUS));
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("k_nearest/tree", POINT_COUNT), |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(search.k_nearest(query, K));
+            }
+        });
+    });
+    group.bench_function(BenchmarkId::new("k_nearest/brute_force", POINT_COUNT), |b| {
+        b.iter(|| {
+            for query in &queries {
+                black_box(brute_force_nearest(&points, query, K));
+            }
+        });
+    });
+    group.finish();
+}
+
+/// Benchmark: rebuilding the tree in place from AoS and SoA storages
+fn bench_rebuild(c: &mut Criterion) {
+    let (entities, positions, _) = setup();
+    let mut soa = PositionSoAStorage::new();
+    for &entity in &entities {
+        soa.insert(entity, *positions.get(entity).unwrap());
+    }
+
+    let mut group = c.benchmark_group("neighbor_rebuild");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(POINT_COUNT as u64));
+
+    let mut search = NeighborSearch::build(entities.iter(), &positions);
+    group.bench_function(BenchmarkId::new("HashMap", POINT_COUNT), |b| {
+        b.iter(|| {
+            search.rebuild(entities.iter(), &positions);
+            black_box(search.len());
+        });
+    });
+    group.bench_function(BenchmarkId::new("SoA", POINT_COUNT), |b| {
+        b.iter(|| {
+            search.rebuild(entities.iter(), &soa);
+            black_box(search.len());
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_queries, bench_rebuild);
+criterion_main!(benches);
@@ -18,10 +18,18 @@
 //! - Insert/remove/get performance
 //! - Bulk iteration throughput
 //! - Memory footprint differences
+//! - HashMap vs sparse-set entity lookup in gravity-style inner loops
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use physics_engine::ecs::components::Position;
-use physics_engine::ecs::{Entity, HashMapStorage, SoAStorage, ComponentStorage};
+use physics_engine::Real;
+use physics_engine::ecs::components::{Position, Mass};
+use physics_engine::ecs::{Entity, HashMapStorage, SoAStorage, SparseSoAStorage, ComponentStorage, World};
+
+/// Create `count` entities through a World
+fn spawn_entities(count: usize) -> Vec<Entity> {
+    let mut world = World::with_capacity(count);
+    (0..count).map(|_| world.create_entity()).collect()
+}
 
 /// Benchmark: Insert N entities into storage
 fn bench_storage_insert(c: &mut Criterion) {
@@ -35,11 +43,11 @@ fn bench_storage_insert(c: &mut Criterion) {
             BenchmarkId::new("HashMap", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter(|| {
                     let mut storage = HashMapStorage::<Position>::new();
-                    for i in 0..count {
-                        let entity = Entity::new(i as u64, 0);
-                        storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                    for (i, &entity) in entities.iter().enumerate() {
+                        storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                     }
                     black_box(storage);
                 });
@@ -51,11 +59,11 @@ fn bench_storage_insert(c: &mut Criterion) {
             BenchmarkId::new("SoA", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter(|| {
                     let mut storage = SoAStorage::<Position>::new();
-                    for i in 0..count {
-                        let entity = Entity::new(i as u64, 0);
-                        storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                    for (i, &entity) in entities.iter().enumerate() {
+                        storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                     }
                     black_box(storage);
                 });
@@ -78,19 +86,18 @@ fn bench_storage_random_access(c: &mut Criterion) {
             BenchmarkId::new("HashMap", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = HashMapStorage::<Position>::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
                         storage
                     },
                     |storage| {
                         let mut sum = 0.0;
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
+                        for &entity in &entities {
                             if let Some(pos) = storage.get(entity) {
                                 sum += pos.x() + pos.y() + pos.z();
                             }
@@ -107,19 +114,18 @@ fn bench_storage_random_access(c: &mut Criterion) {
             BenchmarkId::new("SoA", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = SoAStorage::<Position>::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
                         storage
                     },
                     |storage| {
                         let mut sum = 0.0;
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
+                        for &entity in &entities {
                             if let Some(pos) = storage.get(entity) {
                                 sum += pos.x() + pos.y() + pos.z();
                             }
@@ -135,6 +141,67 @@ fn bench_storage_random_access(c: &mut Criterion) {
     group.finish();
 }
 
+/// Pairwise inner loop mirroring gravity: two position and two mass lookups per pair
+fn pairwise_lookups(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    masses: &impl ComponentStorage<Component = Mass>,
+) -> Real {
+    let mut sum = 0.0;
+    for &e1 in entities {
+        for &e2 in entities {
+            if let (Some(p1), Some(p2), Some(m1), Some(m2)) =
+                (positions.get(e1), positions.get(e2), masses.get(e1), masses.get(e2))
+            {
+                let dx = p2.x() - p1.x();
+                let dy = p2.y() - p1.y();
+                let dz = p2.z() - p1.z();
+                sum += m1.value() * m2.value() / (dx * dx + dy * dy + dz * dz + 1.0);
+            }
+        }
+    }
+    sum
+}
+
+/// Benchmark: Lookup-heavy N² workload, HashMap index vs sparse-set index
+fn bench_storage_lookup_heavy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("storage_lookup_heavy");
+
+    for entity_count in [100, 500].iter() {
+        let pairs = (*entity_count * *entity_count) as u64;
+        group.throughput(Throughput::Elements(pairs));
+        let entities = spawn_entities(*entity_count);
+
+        // Dense storage with HashMap entity index
+        let mut positions = SoAStorage::<Position>::new();
+        let mut masses = SoAStorage::<Mass>::new();
+        for (i, &entity) in entities.iter().enumerate() {
+            positions.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
+            masses.insert(entity, Mass::new(1.0 + i as Real));
+        }
+        group.bench_with_input(
+            BenchmarkId::new("HashMapIndex", entity_count),
+            &entities,
+            |b, entities| b.iter(|| black_box(pairwise_lookups(entities, &positions, &masses))),
+        );
+
+        // Dense storage with sparse-set entity index
+        let mut positions = SparseSoAStorage::<Position>::new();
+        let mut masses = SparseSoAStorage::<Mass>::new();
+        for (i, &entity) in entities.iter().enumerate() {
+            positions.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
+            masses.insert(entity, Mass::new(1.0 + i as Real));
+        }
+        group.bench_with_input(
+            BenchmarkId::new("SparseSetIndex", entity_count),
+            &entities,
+            |b, entities| b.iter(|| black_box(pairwise_lookups(entities, &positions, &masses))),
+        );
+    }
+
+    group.finish();
+}
+
 /// Benchmark: Sequential iteration over all components
 fn bench_storage_sequential_iteration(c: &mut Criterion) {
     let mut group = c.benchmark_group("storage_sequential_iteration");
@@ -147,18 +214,16 @@ fn bench_storage_sequential_iteration(c: &mut Criterion) {
             BenchmarkId::new("HashMap_via_entities", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = HashMapStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(storage, entities)| {
+                    |storage| {
                         let mut sum = 0.0;
                         for entity in &entities {
                             if let Some(pos) = storage.get(*entity) {
@@ -177,18 +242,16 @@ fn bench_storage_sequential_iteration(c: &mut Criterion) {
             BenchmarkId::new("SoA_via_entities", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = SoAStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(storage, entities)| {
+                    |storage| {
                         let mut sum = 0.0;
                         for entity in &entities {
                             if let Some(pos) = storage.get(*entity) {
@@ -207,12 +270,12 @@ fn bench_storage_sequential_iteration(c: &mut Criterion) {
             BenchmarkId::new("SoA_direct_array", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = SoAStorage::<Position>::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
                         storage
                     },
@@ -244,19 +307,17 @@ fn bench_storage_bulk_update(c: &mut Criterion) {
             BenchmarkId::new("HashMap", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 // Setup inside iter_batched to avoid measuring setup time
                 b.iter_batched(
                     || {
                         let mut storage = HashMapStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(mut storage, entities)| {
+                    |mut storage| {
                         for entity in &entities {
                             if let Some(pos) = storage.get_mut(*entity) {
                                 pos.set_x(pos.x() + 1.0);
@@ -276,18 +337,16 @@ fn bench_storage_bulk_update(c: &mut Criterion) {
             BenchmarkId::new("SoA", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = SoAStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(mut storage, entities)| {
+                    |mut storage| {
                         for entity in &entities {
                             if let Some(pos) = storage.get_mut(*entity) {
                                 pos.set_x(pos.x() + 1.0);
@@ -318,19 +377,17 @@ fn bench_storage_remove(c: &mut Criterion) {
             BenchmarkId::new("HashMap", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = HashMapStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(mut storage, entities)| {
-                        for entity in entities {
+                    |mut storage| {
+                        for &entity in &entities {
                             storage.remove(entity);
                         }
                         black_box(storage);
@@ -345,19 +402,17 @@ fn bench_storage_remove(c: &mut Criterion) {
             BenchmarkId::new("SoA", entity_count),
             entity_count,
             |b, &count| {
+                let entities = spawn_entities(count);
                 b.iter_batched(
                     || {
                         let mut storage = SoAStorage::<Position>::new();
-                        let mut entities = Vec::new();
-                        for i in 0..count {
-                            let entity = Entity::new(i as u64, 0);
-                            entities.push(entity);
-                            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+                        for (i, &entity) in entities.iter().enumerate() {
+                            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
                         }
-                        (storage, entities)
+                        storage
                     },
-                    |(mut storage, entities)| {
-                        for entity in entities {
+                    |mut storage| {
+                        for &entity in &entities {
                             storage.remove(entity);
                         }
                         black_box(storage);
@@ -377,6 +432,7 @@ criterion_group!(
     bench_storage_random_access,
     bench_storage_sequential_iteration,
     bench_storage_bulk_update,
-    bench_storage_remove
+    bench_storage_remove,
+    bench_storage_lookup_heavy
 );
 criterion_main!(storage_benches);
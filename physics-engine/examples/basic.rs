@@ -89,8 +89,8 @@ fn main() {
 
     // Query entities with position components
     println!("\nEntities with Position component:");
-    for entity in world.entities() {
-        if let Some(pos) = positions.get(*entity) {
+    for entity in world.iter_entities() {
+        if let Some(pos) = positions.get(entity) {
             println!("  {} -> Position({:.1}, {:.1}, {:.1})", 
                      entity, pos.x, pos.y, pos.z);
         }
@@ -98,8 +98,8 @@ fn main() {
 
     // Simulate a simple update loop
     println!("\nSimulating movement (entities with both Position and Velocity):");
-    for entity in world.entities() {
-        if let (Some(pos), Some(vel)) = (positions.get_mut(*entity), velocities.get(*entity)) {
+    for entity in world.iter_entities() {
+        if let (Some(pos), Some(vel)) = (positions.get_mut(entity), velocities.get(entity)) {
             pos.x += vel.dx;
             pos.y += vel.dy;
             pos.z += vel.dz;
@@ -35,69 +35,41 @@
 //!
 //! # Use RK4 integrator
 //! cargo run --example particle_collision --release -- --integrator rk4
+//!
+//! # Log energy, spread and the strongest forces every 50 steps
+//! cargo run --example particle_collision --release -- --diagnostics
+//!
+//! # Load initial conditions (mass x y z vx vy vz table, or .bin) instead
+//! cargo run --example particle_collision --release --features io -- --import bodies.txt
 //! ```
 
+use physics_engine::Real;
+use physics_engine::diagnostics::{analyze_forces, detect_force_outliers};
 use physics_engine::ecs::{World, Entity, ComponentStorage, HashMapStorage};
 use physics_engine::ecs::components::{Position, Velocity, Mass, Acceleration};
-use physics_engine::ecs::systems::{ForceRegistry, apply_forces_to_acceleration};
-use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator};
-use physics_engine::plugins::gravity::{GravityPlugin, GravitySystem};
+use physics_engine::ecs::systems::{ForceRegistry, apply_forces_to_acceleration, initialize_accelerations};
+use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator, TimestepSuggestion};
+use physics_engine::plugins::gravity::{suggest_timestep, GravityPlugin, GravitySystem};
+use physics_engine::scenarios::{self, CubeRanges};
+use physics_engine::util::RunningStats;
 use std::time::Instant;
-
-/// Simple pseudo-random number generator for deterministic results
-/// Uses a linear congruential generator (LCG) with parameters from
-/// Numerical Recipes (Press et al., 2007), specifically:
-/// - Multiplier: 6364136223846793005 (Knuth's 64-bit multiplier)
-/// - Increment: 1442695040888963407
-/// These parameters provide good statistical properties for 64-bit integers.
-struct SimpleRng {
-    state: u64,
-}
-
-/// Maximum value for 53-bit mantissa (2^53) used in float conversion
-const F64_MANTISSA_MAX: f64 = 9007199254740992.0;
-
-impl SimpleRng {
-    fn new(seed: u64) -> Self {
-        SimpleRng { state: seed }
-    }
-
-    fn next_u64(&mut self) -> u64 {
-        // LCG: X_{n+1} = (a * X_n + c) mod m
-        // Using parameters from Numerical Recipes / Knuth MMIX
-        self.state = self.state.wrapping_mul(6364136223846793005)
-            .wrapping_add(1442695040888963407);
-        self.state
-    }
-
-    fn next_f64(&mut self) -> f64 {
-        // Generate a float in [0, 1) using upper 53 bits
-        // This follows the standard approach for converting uniform integers to floats
-        // Shift right by 11 to get 53 bits (64 - 11 = 53)
-        // Divide by 2^53 to normalize to [0, 1)
-        // This ensures uniform distribution across the entire [0, 1) range
-        (self.next_u64() >> 11) as f64 / F64_MANTISSA_MAX
-    }
-
-    fn next_f64_range(&mut self, min: f64, max: f64) -> f64 {
-        min + (max - min) * self.next_f64()
-    }
-}
+use std::collections::HashMap;
 
 /// Simulation configuration
 struct SimulationConfig {
     num_particles: usize,
     integrator_name: String,
-    timestep: f64,      // seconds
-    duration: f64,      // seconds
-    output_interval: f64, // seconds
-    g_scale: f64,       // Gravitational constant scale factor
-    mass_range: (f64, f64),  // kg
-    position_range: f64,      // meters
-    velocity_range: f64,      // m/s
-    softening: f64,           // meters
+    timestep: Real,      // seconds
+    duration: Real,      // seconds
+    output_interval: Real, // seconds
+    g_scale: Real,       // Gravitational constant scale factor
+    mass_range: (Real, Real),  // kg
+    position_range: Real,      // meters
+    velocity_range: Real,      // m/s
+    softening: Real,           // meters
     seed: u64,
     diagnostic_mode: bool,    // Enable detailed per-step diagnostics
+    import_path: Option<String>, // Initial-conditions file replacing random particles
 }
 
 impl Default for SimulationConfig {
@@ -115,68 +87,94 @@ impl Default for SimulationConfig {
             softening: 1.0,            // 1 m softening
             seed: 12345,
             diagnostic_mode: false,
+            import_path: None,
         }
     }
 }
 
 /// Create random particles with deterministic seed
+///
+/// Placement comes from `scenarios::random_cube()`; the example keeps its
+/// own storages and gravity settings.
 fn create_particles(
     world: &mut World,
     positions: &mut HashMapStorage<Position>,
     velocities: &mut HashMapStorage<Velocity>,
     masses: &mut HashMapStorage<Mass>,
     config: &SimulationConfig,
-) -> Vec<Entity> {
-    let mut rng = SimpleRng::new(config.seed);
-    let mut entities = Vec::new();
+) {
+    let ranges = CubeRanges::default()
+        .with_position(config.position_range)
+        .with_velocity(config.velocity_range)
+        .with_mass(config.mass_range.0, config.mass_range.1);
+    let scenario = scenarios::random_cube(world, config.num_particles, config.seed, &ranges);
 
-    for i in 0..config.num_particles {
-        let entity = world.create_entity();
-        
-        // Random position in a cube
-        let pos = Position::new(
-            rng.next_f64_range(-config.position_range, config.position_range),
-            rng.next_f64_range(-config.position_range, config.position_range),
-            rng.next_f64_range(-config.position_range, config.position_range),
-        );
-        
-        // Random velocity
-        let vel = Velocity::new(
-            rng.next_f64_range(-config.velocity_range, config.velocity_range),
-            rng.next_f64_range(-config.velocity_range, config.velocity_range),
-            rng.next_f64_range(-config.velocity_range, config.velocity_range),
-        );
-        
-        // Random mass
-        let mass_value = rng.next_f64_range(config.mass_range.0, config.mass_range.1);
-        let mass = Mass::new(mass_value);
+    for (i, &entity) in scenario.entities.iter().enumerate() {
+        let pos = *scenario.state.positions().get(entity).unwrap();
+        let vel = *scenario.state.velocities().get(entity).unwrap();
+        let mass = *scenario.state.masses().get(entity).unwrap();
 
         positions.insert(entity, pos);
         velocities.insert(entity, vel);
         masses.insert(entity, mass);
 
-        entities.push(entity);
-
         if i < 5 || i == config.num_particles - 1 {
             println!("  Particle {}: pos=({:.1}, {:.1}, {:.1}) m, vel=({:.1}, {:.1}, {:.1}) m/s, mass={:.1} kg",
-                     i, pos.x(), pos.y(), pos.z(), vel.dx(), vel.dy(), vel.dz(), mass_value);
+                     i, pos.x(), pos.y(), pos.z(), vel.dx(), vel.dy(), vel.dz(), mass.value());
         } else if i == 5 {
             println!("  ... ({} more particles) ...", config.num_particles - 6);
         }
     }
+}
+
+/// Create particles from an initial-conditions file
+#[cfg(feature = "io")]
+fn import_particles(
+    path: &str,
+    world: &mut World,
+    positions: &mut HashMapStorage<Position>,
+    velocities: &mut HashMapStorage<Velocity>,
+    masses: &mut HashMapStorage<Mass>,
+) {
+    use physics_engine::io::{import, UnitScaling};
+
+    let bodies = match import::load(path, &UnitScaling::default()) {
+        Ok(bodies) => bodies,
+        Err(err) => {
+            eprintln!("Error: Failed to import '{}': {}", path, err);
+            std::process::exit(1);
+        }
+    };
+
+    for (mass, pos, vel) in bodies {
+        let entity = world.create_entity();
+        positions.insert(entity, pos);
+        velocities.insert(entity, vel);
+        masses.insert(entity, mass);
+    }
+}
 
-    entities
+/// Create particles from an initial-conditions file
+#[cfg(not(feature = "io"))]
+fn import_particles(
+    _path: &str,
+    _world: &mut World,
+    _positions: &mut HashMapStorage<Position>,
+    _velocities: &mut HashMapStorage<Velocity>,
+    _masses: &mut HashMapStorage<Mass>,
+) {
+    eprintln!("Error: --import requires the io feature (run with --features io)");
+    std::process::exit(1);
 }
 
 /// Calculate total kinetic energy
 fn calculate_kinetic_energy(
-    entities: &[Entity],
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
-) -> f64 {
+) -> Real {
     let mut ke = 0.0;
-    for entity in entities {
-        if let (Some(vel), Some(mass)) = (velocities.get(*entity), masses.get(*entity)) {
+    for (entity, vel) in velocities.iter() {
+        if let Some(mass) = masses.get(entity) {
             let v_sq = vel.dx() * vel.dx() + vel.dy() * vel.dy() + vel.dz() * vel.dz();
             ke += 0.5 * mass.value() * v_sq;
         }
@@ -186,17 +184,16 @@ fn calculate_kinetic_energy(
 
 /// Calculate center of mass
 fn calculate_center_of_mass(
-    entities: &[Entity],
     positions: &HashMapStorage<Position>,
     masses: &HashMapStorage<Mass>,
-) -> (f64, f64, f64) {
+) -> (Real, Real, Real) {
     let mut total_mass = 0.0;
     let mut cm_x = 0.0;
     let mut cm_y = 0.0;
     let mut cm_z = 0.0;
 
-    for entity in entities {
-        if let (Some(pos), Some(mass)) = (positions.get(*entity), masses.get(*entity)) {
+    for (entity, pos) in positions.iter() {
+        if let Some(mass) = masses.get(entity) {
             let m = mass.value();
             total_mass += m;
             cm_x += pos.x() * m;
@@ -216,8 +213,8 @@ fn calculate_center_of_mass(
 fn calculate_spread(
     entities: &[Entity],
     positions: &HashMapStorage<Position>,
-    center: (f64, f64, f64),
-) -> f64 {
+    center: (Real, Real, Real),
+) -> Real {
     let mut max_dist = 0.0;
 
     for entity in entities {
@@ -237,14 +234,14 @@ fn calculate_spread(
 
 /// Print system state
 fn print_state(
-    time: f64,
+    time: Real,
     entities: &[Entity],
     positions: &HashMapStorage<Position>,
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
 ) {
-    let ke = calculate_kinetic_energy(entities, velocities, masses);
-    let cm = calculate_center_of_mass(entities, positions, masses);
+    let ke = calculate_kinetic_energy(velocities, masses);
+    let cm = calculate_center_of_mass(positions, masses);
     let spread = calculate_spread(entities, positions, cm);
     
     println!("\nTime: {:.2} s", time);
@@ -256,19 +253,39 @@ fn print_state(
 /// CSV header for diagnostic output
 const DIAG_HEADER: &str = "DIAG,step,time_s,dt_s,KE_J,ke_change_frac,cm_x_m,cm_y_m,cm_z_m,spread_m";
 
+/// CSV header for the strongest forces printed with each diagnostic sample
+const TOP_HEADER: &str = "TOP,step,rank,entity,force_N,outlier";
+
+/// Number of strongest forces printed with each diagnostic sample
+const TOP_FORCES: usize = 5;
+
+/// Outlier threshold in median absolute deviations above the median force
+const OUTLIER_MADS: Real = 10.0;
+
+/// Print the strongest accumulated forces, marking median-based outliers
+fn print_top_forces(step: usize, force_registry: &ForceRegistry, entities: &[Entity]) {
+    let histogram = analyze_forces(force_registry, entities, TOP_FORCES);
+    let report = detect_force_outliers(force_registry, entities, OUTLIER_MADS);
+    for (rank, (entity, magnitude)) in histogram.top().iter().enumerate() {
+        let outlier = report.outliers.iter().any(|(e, _)| e == entity);
+        println!("TOP,{},{},{},{:.6e},{}", step, rank + 1, entity.id().raw(), magnitude, outlier);
+    }
+}
+
 /// Print detailed diagnostic information for failure analysis
+#[allow(clippy::too_many_arguments)]
 fn print_diagnostics(
     step: usize,
-    time: f64,
-    dt: f64,
+    time: Real,
+    dt: Real,
     entities: &[Entity],
     positions: &HashMapStorage<Position>,
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
-    initial_ke: f64,
+    initial_ke: Real,
 ) {
-    let ke = calculate_kinetic_energy(entities, velocities, masses);
-    let cm = calculate_center_of_mass(entities, positions, masses);
+    let ke = calculate_kinetic_energy(velocities, masses);
+    let cm = calculate_center_of_mass(positions, masses);
     let spread = calculate_spread(entities, positions, cm);
     let ke_change = if initial_ke.abs() > 1e-9 {
         (ke - initial_ke) / initial_ke
@@ -321,7 +338,7 @@ fn main() {
             }
             "--timestep" => {
                 if i + 1 < args.len() {
-                    match args[i + 1].parse::<f64>() {
+                    match args[i + 1].parse::<Real>() {
                         Ok(value) => config.timestep = value,
                         Err(_) => {
                             eprintln!("Warning: Invalid timestep '{}', using default 0.01 s", 
@@ -337,7 +354,7 @@ fn main() {
             }
             "--duration" => {
                 if i + 1 < args.len() {
-                    match args[i + 1].parse::<f64>() {
+                    match args[i + 1].parse::<Real>() {
                         Ok(value) => config.duration = value,
                         Err(_) => {
                             eprintln!("Warning: Invalid duration '{}', using default 10.0 s", 
@@ -371,6 +388,15 @@ fn main() {
                 config.diagnostic_mode = true;
                 i += 1;
             }
+            "--import" => {
+                if i + 1 < args.len() {
+                    config.import_path = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("Error: --import requires an argument");
+                    std::process::exit(1);
+                }
+            }
             _ => {
                 i += 1;
             }
@@ -378,7 +404,10 @@ fn main() {
     }
 
     println!("Simulation Configuration:");
-    println!("  Particles: {}", config.num_particles);
+    match &config.import_path {
+        Some(path) => println!("  Particles: from {}", path),
+        None => println!("  Particles: {}", config.num_particles),
+    }
     println!("  Integrator: {}", config.integrator_name);
     println!("  Timestep: {:.3} s", config.timestep);
     println!("  Duration: {:.1} s", config.duration);
@@ -394,14 +423,21 @@ fn main() {
     let mut masses = HashMapStorage::<Mass>::new();
     let mut accelerations = HashMapStorage::<Acceleration>::new();
 
-    println!("Creating {} particles...", config.num_particles);
-    let entities = create_particles(&mut world, &mut positions, &mut velocities, &mut masses, &config);
+    match config.import_path.clone() {
+        Some(path) => {
+            import_particles(&path, &mut world, &mut positions, &mut velocities, &mut masses);
+            println!("Imported {} particles from {}", world.entity_count(), path);
+            config.num_particles = world.entity_count();
+        }
+        None => {
+            println!("Creating {} particles...", config.num_particles);
+            create_particles(&mut world, &mut positions, &mut velocities, &mut masses, &config);
+        }
+    }
     println!();
 
-    // Initialize accelerations to zero
-    for entity in &entities {
-        accelerations.insert(*entity, Acceleration::zero());
-    }
+    // Particles are never destroyed, so one snapshot serves the whole run
+    let entities = world.live_entities_snapshot();
 
     // Create gravity system with scaled G and warning suppression
     let mut gravity_plugin = GravityPlugin::with_scaled_g(config.g_scale);
@@ -409,6 +445,7 @@ fn main() {
     // Suppress warnings for expected high-force scenarios in dense particle clouds
     gravity_plugin.set_warn_on_high_forces(false);
     gravity_plugin.set_warn_on_invalid(false);
+    let (g, softening) = (gravity_plugin.gravitational_constant(), gravity_plugin.softening());
     let gravity_system = GravitySystem::new(gravity_plugin);
 
     // Create integrator
@@ -425,19 +462,16 @@ fn main() {
             }
         }
 
-        fn integrate<'a>(
-            &mut self,
+        fn suggest_timestep<'a>(
+            &self,
             entities: impl Iterator<Item = &'a Entity>,
-            positions: &mut impl ComponentStorage<Component = Position>,
-            velocities: &mut impl ComponentStorage<Component = Velocity>,
+            positions: &impl ComponentStorage<Component = Position>,
             accelerations: &impl ComponentStorage<Component = Acceleration>,
             masses: &impl ComponentStorage<Component = Mass>,
-            force_registry: &mut ForceRegistry,
-            warn_on_missing: bool,
-        ) -> usize {
+        ) -> Option<TimestepSuggestion> {
             match self {
-                IntegratorWrapper::Verlet(i) => i.integrate(entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing),
-                IntegratorWrapper::RK4(i) => i.integrate(entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing),
+                IntegratorWrapper::Verlet(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+                IntegratorWrapper::RK4(i) => i.suggest_timestep(entities, positions, accelerations, masses),
             }
         }
     }
@@ -451,10 +485,40 @@ fn main() {
         }
     };
 
+
+    // Fill in accelerations from the initial forces, so the first Verlet
+    // half-kick is correct, and estimate a stable timestep from them
+    {
+        let mut initial_registry = ForceRegistry::new();
+        initial_registry.set_max_force_magnitude(1e10);
+        initial_registry.set_warn_on_missing_components(false);
+        let mut initial_forces = HashMap::new();
+        gravity_system.compute_forces_into(&entities, &positions, &masses, &mut initial_forces);
+        initial_registry.register_forces(&initial_forces);
+        initialize_accelerations(entities.iter(), &mut initial_registry, &masses, &mut accelerations, false);
+
+        println!("Timestep Check:");
+        match integrator.suggest_timestep(entities.iter(), &positions, &accelerations, &masses) {
+            Some(suggestion) => {
+                println!("  Max angular frequency: {:.3e} rad/s", suggestion.max_angular_frequency);
+                println!("  Stability limit: {:.3e} s", suggestion.max_stable_timestep);
+                println!("  Suggested timestep: {:.3e} s", suggestion.suggested_timestep);
+                if let Some(warning) = &suggestion.warning {
+                    println!("  Warning: {}", warning);
+                }
+            }
+            None => println!("  No estimate available (no forces acting)"),
+        }
+        if let Some(dt) = suggest_timestep(&entities, &positions, &masses, g, softening, 0.01) {
+            println!("  Dynamical timestep (1% of shortest pair timescale): {:.3e} s", dt);
+        }
+        println!();
+    }
+
     println!("Starting simulation with {} integrator...", integrator.name());
 
     // Initial state
-    let initial_energy = calculate_kinetic_energy(&entities, &velocities, &masses);
+    let initial_energy = calculate_kinetic_energy(&velocities, &masses);
     print_state(0.0, &entities, &positions, &velocities, &masses);
 
     // Diagnostic mode header
@@ -462,6 +526,7 @@ fn main() {
         println!();
         println!("=== DIAGNOSTIC MODE ENABLED ===");
         println!("CSV Header: {}", DIAG_HEADER);
+        println!("CSV Header: {}", TOP_HEADER);
         println!();
     }
 
@@ -476,50 +541,67 @@ fn main() {
     println!();
 
     let start_time = Instant::now();
-    let mut step_times = Vec::new();
+    let mut step_times = RunningStats::new();
 
     // Create force registry once and reuse across iterations
     let mut force_registry = ForceRegistry::new();
-    force_registry.max_force_magnitude = 1e10;
-    force_registry.warn_on_missing_components = false;
+    force_registry.set_max_force_magnitude(1e10);
+    force_registry.set_warn_on_missing_components(false);
+    let mut gravity_forces = HashMap::new();
 
     for step in 0..num_steps {
         let step_start = Instant::now();
 
-        // Clear force registry for this step (avoids reallocation)
-        force_registry.clear();
-
-        // Compute gravitational forces at current positions
-        gravity_system.compute_forces(&entities, &positions, &masses, &mut force_registry);
-        
-        // Accumulate forces from registered providers
-        for entity in &entities {
-            force_registry.accumulate_for_entity(*entity);
+        match &mut integrator {
+            IntegratorWrapper::Verlet(verlet) => {
+                // a(t) is left over from the end of the previous step, so
+                // gravity is only evaluated at the new positions
+                verlet.integrate_reusing_accelerations(
+                    entities.iter(),
+                    &mut positions,
+                    &mut velocities,
+                    &mut accelerations,
+                    &masses,
+                    &mut force_registry,
+                    false,
+                    |entities, positions, registry| {
+                        // Clearing the registry avoids reallocation
+                        registry.clear();
+                        gravity_system.compute_forces_into(entities, positions, &masses, &mut gravity_forces);
+                        registry.register_forces(&gravity_forces);
+                    },
+                );
+            }
+            IntegratorWrapper::RK4(rk4) => {
+                // Clear force registry for this step (avoids reallocation)
+                force_registry.clear();
+
+                // Compute gravitational forces at current positions and hand them to the registry
+                gravity_system.compute_forces_into(&entities, &positions, &masses, &mut gravity_forces);
+                force_registry.register_forces(&gravity_forces);
+
+                // Apply forces to compute accelerations at current positions
+                apply_forces_to_acceleration(
+                    entities.iter(),
+                    &force_registry,
+                    &masses,
+                    &mut accelerations,
+                    false,
+                );
+
+                // The registered forces are held fixed across the RK4 stages
+                rk4.integrate(
+                    entities.iter(),
+                    &mut positions,
+                    &mut velocities,
+                    &accelerations,
+                    &masses,
+                    &mut force_registry,
+                    false,
+                );
+            }
         }
 
-        // Apply forces to compute accelerations at current positions
-        // Note: The Verlet integrator will recompute accelerations at new positions internally
-        apply_forces_to_acceleration(
-            entities.iter(),
-            &force_registry,
-            &masses,
-            &mut accelerations,
-            false,
-        );
-        
-        // Use the integrator to update positions and velocities
-        // The force_registry retains its registered providers, allowing the integrator
-        // to recompute forces at the updated positions (needed for Verlet algorithm)
-        integrator.integrate(
-            entities.iter(),
-            &mut positions,
-            &mut velocities,
-            &accelerations,
-            &masses,
-            &mut force_registry,
-            false,
-        );
-
         time += config.timestep;
         step_times.push(step_start.elapsed().as_secs_f64());
 
@@ -535,6 +617,7 @@ fn main() {
                 &masses,
                 initial_energy,
             );
+            print_top_forces(step, &force_registry, &entities);
         }
 
         // Output at intervals
@@ -554,7 +637,7 @@ fn main() {
     print_state(time, &entities, &positions, &velocities, &masses);
 
     // Energy conservation
-    let final_energy = calculate_kinetic_energy(&entities, &velocities, &masses);
+    let final_energy = calculate_kinetic_energy(&velocities, &masses);
     let energy_drift = if initial_energy != 0.0 {
         ((final_energy - initial_energy) / initial_energy).abs()
     } else {
@@ -572,14 +655,18 @@ fn main() {
     println!("Performance Statistics:");
     println!("  Total time: {:.2} s", total_time.as_secs_f64());
     println!("  Steps completed: {}", num_steps);
-    println!("  Average step time: {:.3} ms", 
-             step_times.iter().sum::<f64>() / step_times.len() as f64 * 1000.0);
+    if let (Some(mean), Some(stddev), Some(min), Some(max)) =
+        (step_times.mean(), step_times.stddev(), step_times.min(), step_times.max())
+    {
+        println!("  Average step time: {:.3} ms (σ {:.3} ms)", mean * 1000.0, stddev * 1000.0);
+        println!("  Step time range: {:.3} - {:.3} ms", min * 1000.0, max * 1000.0);
+    }
     
     let interactions_per_step = config.num_particles * (config.num_particles - 1) / 2;
     let total_interactions = interactions_per_step * num_steps;
     let interactions_per_second = total_interactions as f64 / total_time.as_secs_f64();
     
-    println!("  Total pairwise interactions: {:.2e}", total_interactions as f64);
+    println!("  Total pairwise interactions: {:.2e}", total_interactions as Real);
     println!("  Interactions/second: {:.2e}", interactions_per_second);
 
     #[cfg(feature = "parallel")]
@@ -34,8 +34,9 @@
 //! cargo run --example soa_demo --release
 //! ```
 
+use physics_engine::Real;
 use physics_engine::ecs::{
-    ComponentStorage, Entity, HashMapStorage, PositionSoAStorage, SoAStorage, VelocitySoAStorage,
+    ComponentStorage, HashMapStorage, PositionSoAStorage, SoAStorage, VelocitySoAStorage,
     World,
 };
 use physics_engine::ecs::components::{Position, Velocity};
@@ -113,7 +114,7 @@ fn demonstrate_field_arrays() {
         let entity = world.create_entity();
         storage.insert(
             entity,
-            Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0),
+            Position::new(i as Real, i as Real * 2.0, i as Real * 3.0),
         );
     }
 
@@ -155,7 +156,7 @@ fn demonstrate_bulk_operations() {
     // Create 1000 entities with positions and velocities
     for i in 0..1000 {
         let entity = world.create_entity();
-        positions.insert(entity, Position::new(i as f64, 0.0, 0.0));
+        positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
         velocities.insert(entity, Velocity::new(1.0, 0.0, 0.0));
     }
 
@@ -195,7 +196,7 @@ fn performance_comparison() {
     let mut aos_storage = SoAStorage::<Position>::with_capacity(ENTITY_COUNT);
     for i in 0..ENTITY_COUNT {
         let entity = world.create_entity();
-        aos_storage.insert(entity, Position::new(i as f64, 0.0, 0.0));
+        aos_storage.insert(entity, Position::new(i as Real, 0.0, 0.0));
     }
 
     // Setup true SoA storage
@@ -203,7 +204,7 @@ fn performance_comparison() {
     world.clear();
     for i in 0..ENTITY_COUNT {
         let entity = world.create_entity();
-        soa_storage.insert(entity, Position::new(i as f64, 0.0, 0.0));
+        soa_storage.insert(entity, Position::new(i as Real, 0.0, 0.0));
     }
 
     // Benchmark AoS (via components())
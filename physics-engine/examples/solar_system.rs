@@ -19,6 +19,7 @@
 //! - Newton's law of universal gravitation
 //! - Comparison between Verlet and RK4 integrators
 //! - Energy conservation tracking
+//! - Per-planet osculating orbital elements
 //! - Deterministic simulation results
 //!
 //! # Physical Constants
@@ -40,27 +41,22 @@
 //! cargo run --example solar_system --release -- --years 10 --timestep 3600
 //! ```
 
+use physics_engine::Real;
 use physics_engine::ecs::{World, Entity, ComponentStorage, HashMapStorage};
 use physics_engine::ecs::components::{Position, Velocity, Mass, Acceleration};
-use physics_engine::ecs::systems::{ForceRegistry, apply_forces_to_acceleration};
-use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator};
+use physics_engine::ecs::systems::{ForceRegistry, apply_forces_to_acceleration, initialize_accelerations};
+use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator, TimestepSuggestion};
+use physics_engine::orbits::SystemOrbitReport;
 use physics_engine::plugins::gravity::{GravityPlugin, GravitySystem, GRAVITATIONAL_CONSTANT, DEFAULT_SOFTENING};
-
-/// Astronomical Unit in meters (average Earth-Sun distance)
-const AU: f64 = 1.495978707e11;
-
-/// One Earth day in seconds
-const DAY: f64 = 86400.0;
-
-/// One Earth year in seconds (365.25 days)
-const YEAR: f64 = 365.25 * DAY;
+use physics_engine::units::{ASTRONOMICAL_UNIT as AU, DAY, SOLAR_MASS, YEAR};
+use std::collections::HashMap;
 
 /// Celestial body data structure
 struct CelestialBody {
     name: &'static str,
-    mass: f64,              // kg
-    distance: f64,          // m (semi-major axis)
-    orbital_velocity: f64,  // m/s (approximate circular orbit velocity)
+    mass: Real,              // kg
+    distance: Real,          // m (semi-major axis)
+    orbital_velocity: Real,  // m/s (approximate circular orbit velocity)
 }
 
 /// Solar system body data from NASA
@@ -68,7 +64,7 @@ struct CelestialBody {
 const SOLAR_BODIES: &[CelestialBody] = &[
     CelestialBody {
         name: "Sun",
-        mass: 1.989e30,
+        mass: SOLAR_MASS,
         distance: 0.0,
         orbital_velocity: 0.0,
     },
@@ -101,9 +97,9 @@ const SOLAR_BODIES: &[CelestialBody] = &[
 /// Simulation configuration
 struct SimulationConfig {
     integrator_name: String,
-    timestep: f64,      // seconds
-    duration: f64,      // seconds
-    output_interval: f64, // seconds
+    timestep: Real,      // seconds
+    duration: Real,      // seconds
+    output_interval: Real, // seconds
     diagnostic_mode: bool, // Enable detailed per-step diagnostics
 }
 
@@ -198,13 +194,12 @@ fn create_solar_system(
 
 /// Calculate total kinetic energy of the system
 fn calculate_kinetic_energy(
-    entities: &[(Entity, &str)],
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
-) -> f64 {
+) -> Real {
     let mut ke = 0.0;
-    for (entity, _) in entities {
-        if let (Some(vel), Some(mass)) = (velocities.get(*entity), masses.get(*entity)) {
+    for (entity, vel) in velocities.iter() {
+        if let Some(mass) = masses.get(entity) {
             let v_sq = vel.dx() * vel.dx() + vel.dy() * vel.dy() + vel.dz() * vel.dz();
             ke += 0.5 * mass.value() * v_sq;
         }
@@ -217,7 +212,7 @@ fn calculate_potential_energy(
     entities: &[(Entity, &str)],
     positions: &HashMapStorage<Position>,
     masses: &HashMapStorage<Mass>,
-) -> f64 {
+) -> Real {
     let mut pe = 0.0;
     let n = entities.len();
     
@@ -255,13 +250,13 @@ fn calculate_potential_energy(
 
 /// Print system state with optional diagnostics
 fn print_state(
-    time: f64,
+    time: Real,
     entities: &[(Entity, &str)],
     positions: &HashMapStorage<Position>,
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
 ) {
-    let ke = calculate_kinetic_energy(entities, velocities, masses);
+    let ke = calculate_kinetic_energy(velocities, masses);
     let pe = calculate_potential_energy(entities, positions, masses);
     let total_energy = ke + pe;
     
@@ -277,24 +272,36 @@ fn print_state(
             println!("Earth distance from Sun: {:.3e} m ({:.3} AU)", r, r / AU);
         }
     }
+
+    // Per-planet osculating elements about the dominant mass
+    let bodies: Vec<Entity> = entities.iter().map(|(entity, _)| *entity).collect();
+    let name_of = |entity: Entity| entities.iter().find(|(e, _)| *e == entity).map_or("?", |(_, name)| *name);
+    if let Some(report) = SystemOrbitReport::compute(&bodies, positions, velocities, masses, GRAVITATIONAL_CONSTANT) {
+        println!("Orbits about the {}:", name_of(report.central()));
+        for (entity, elements) in report.orbits() {
+            println!("  {:<8} a = {:.5} AU, e = {:.5}, i = {:.3}°",
+                     name_of(*entity), elements.a / AU, elements.e, elements.i.to_degrees());
+        }
+    }
 }
 
 /// CSV header for diagnostic output
 const DIAG_HEADER: &str = "DIAG,step,time_s,dt_s,KE_J,PE_J,E_total_J,drift_frac,earth_AU,earth_v_ms,earth_a_ms2";
 
 /// Print detailed diagnostic information for failure analysis
+#[allow(clippy::too_many_arguments)]
 fn print_diagnostics(
     step: usize,
-    time: f64,
-    dt: f64,
+    time: Real,
+    dt: Real,
     entities: &[(Entity, &str)],
     positions: &HashMapStorage<Position>,
     velocities: &HashMapStorage<Velocity>,
     accelerations: &HashMapStorage<Acceleration>,
     masses: &HashMapStorage<Mass>,
-    initial_energy: f64,
+    initial_energy: Real,
 ) {
-    let ke = calculate_kinetic_energy(entities, velocities, masses);
+    let ke = calculate_kinetic_energy(velocities, masses);
     let pe = calculate_potential_energy(entities, positions, masses);
     let total_energy = ke + pe;
     let energy_drift = if initial_energy != 0.0 {
@@ -353,7 +360,7 @@ fn main() {
             }
             "--timestep" => {
                 if i + 1 < args.len() {
-                    match args[i + 1].parse::<f64>() {
+                    match args[i + 1].parse::<Real>() {
                         Ok(value) => config.timestep = value,
                         Err(_) => {
                             eprintln!("Warning: Invalid timestep '{}', using default {:.0} s", 
@@ -369,7 +376,7 @@ fn main() {
             }
             "--years" => {
                 if i + 1 < args.len() {
-                    match args[i + 1].parse::<f64>() {
+                    match args[i + 1].parse::<Real>() {
                         Ok(years) => config.duration = years * YEAR,
                         Err(_) => {
                             eprintln!("Warning: Invalid years '{}', using default 1.0 year", 
@@ -411,11 +418,6 @@ fn main() {
     println!();
     let entities = create_solar_system(&mut world, &mut positions, &mut velocities, &mut masses);
 
-    // Initialize accelerations to zero
-    for (entity, _) in &entities {
-        accelerations.insert(*entity, Acceleration::zero());
-    }
-
     // Create gravity system with appropriate configuration for solar system
     let mut gravity_plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
     // Set expected force magnitude for planetary interactions (up to 2e23 N for close approaches)
@@ -438,6 +440,7 @@ fn main() {
             }
         }
 
+        #[allow(clippy::too_many_arguments)]
         fn integrate<'a>(
             &mut self,
             entities: impl Iterator<Item = &'a Entity>,
@@ -453,6 +456,19 @@ fn main() {
                 IntegratorWrapper::RK4(i) => i.integrate(entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing),
             }
         }
+
+        fn suggest_timestep<'a>(
+            &self,
+            entities: impl Iterator<Item = &'a Entity>,
+            positions: &impl ComponentStorage<Component = Position>,
+            accelerations: &impl ComponentStorage<Component = Acceleration>,
+            masses: &impl ComponentStorage<Component = Mass>,
+        ) -> Option<TimestepSuggestion> {
+            match self {
+                IntegratorWrapper::Verlet(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+                IntegratorWrapper::RK4(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+            }
+        }
     }
 
     let mut integrator = match config.integrator_name.as_str() {
@@ -464,13 +480,40 @@ fn main() {
         }
     };
 
+
+    // Fill in accelerations from the initial forces, so the first Verlet
+    // half-kick is correct, and estimate a stable timestep from them
+    {
+        let initial_entities = world.live_entities_snapshot();
+        let mut initial_registry = ForceRegistry::new();
+        initial_registry.set_max_force_magnitude(1e24);
+        initial_registry.set_warn_on_missing_components(false);
+        let mut initial_forces = HashMap::new();
+        gravity_system.compute_forces_into(&initial_entities, &positions, &masses, &mut initial_forces);
+        initial_registry.register_forces(&initial_forces);
+        initialize_accelerations(initial_entities.iter(), &mut initial_registry, &masses, &mut accelerations, false);
+
+        println!();
+        println!("Timestep Check:");
+        match integrator.suggest_timestep(initial_entities.iter(), &positions, &accelerations, &masses) {
+            Some(suggestion) => {
+                println!("  Max angular frequency: {:.3e} rad/s", suggestion.max_angular_frequency);
+                println!("  Stability limit: {:.3e} s", suggestion.max_stable_timestep);
+                println!("  Suggested timestep: {:.3e} s", suggestion.suggested_timestep);
+                if let Some(warning) = &suggestion.warning {
+                    println!("  Warning: {}", warning);
+                }
+            }
+            None => println!("  No estimate available (no forces acting)"),
+        }
+    }
     println!();
     println!("Starting simulation with {} integrator...", integrator.name());
     println!();
 
     // Initial state
     let initial_energy = {
-        let ke = calculate_kinetic_energy(&entities, &velocities, &masses);
+        let ke = calculate_kinetic_energy(&velocities, &masses);
         let pe = calculate_potential_energy(&entities, &positions, &masses);
         ke + pe
     };
@@ -496,22 +539,19 @@ fn main() {
 
     // Create force registry once and reuse across iterations
     let mut force_registry = ForceRegistry::new();
-    force_registry.max_force_magnitude = 1e24;
-    force_registry.warn_on_missing_components = false;
+    force_registry.set_max_force_magnitude(1e24);
+    force_registry.set_warn_on_missing_components(false);
+    let mut gravity_forces = HashMap::new();
 
     for step in 0..num_steps {
-        let entity_vec: Vec<Entity> = entities.iter().map(|(e, _)| *e).collect();
+        let entity_vec = world.live_entities_snapshot();
         
         // Clear force registry for this step (avoids reallocation)
         force_registry.clear();
         
-        // Compute gravitational forces at current positions
-        gravity_system.compute_forces(&entity_vec, &positions, &masses, &mut force_registry);
-        
-        // Accumulate forces from registered providers
-        for entity in &entity_vec {
-            force_registry.accumulate_for_entity(*entity);
-        }
+        // Compute gravitational forces at current positions and hand them to the registry
+        gravity_system.compute_forces_into(&entity_vec, &positions, &masses, &mut gravity_forces);
+        force_registry.register_forces(&gravity_forces);
 
         // Apply forces to compute accelerations at current positions
         // Note: The Verlet integrator will recompute accelerations at new positions internally
@@ -569,7 +609,7 @@ fn main() {
 
     // Energy conservation check
     let final_energy = {
-        let ke = calculate_kinetic_energy(&entities, &velocities, &masses);
+        let ke = calculate_kinetic_energy(&velocities, &masses);
         let pe = calculate_potential_energy(&entities, &positions, &masses);
         ke + pe
     };
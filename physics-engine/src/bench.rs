@@ -0,0 +1,205 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Headless simulation runs for benchmarking
+//!
+//! This module provides a stable entry point for timing integrators on a
+//! reproducible N-body workload without any console output, suitable for
+//! criterion harnesses and ad-hoc performance comparisons.
+//!
+//! # Example
+//!
+//! ```
+//! use physics_engine::bench::run_nbody;
+//! use physics_engine::integration::VelocityVerletIntegrator;
+//!
+//! let mut integrator = VelocityVerletIntegrator::new(0.01);
+//! let stats = run_nbody(10, 20, &mut integrator, 42);
+//! assert!(stats.steps_per_second > 0.0);
+//! ```
+
+use crate::real::Real;
+use crate::ecs::{ComponentStorage, Entity, HashMapStorage, World};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+use crate::ecs::systems::{apply_forces_to_acceleration, ForceRegistry};
+use crate::diagnostics::gravitational_potential_energy;
+use crate::integration::{calculate_total_kinetic_energy, Integrator};
+use crate::plugins::gravity::{GravityPlugin, GravitySystem, GRAVITATIONAL_CONSTANT};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Scale factor applied to G so that meter-scale bodies interact visibly
+const G_SCALE: Real = 1e10;
+
+/// Softening length for the benchmark workload (meters)
+const SOFTENING: Real = 1.0;
+
+/// Half-width of the cube bodies are placed in (meters)
+const POSITION_RANGE: Real = 100.0;
+
+/// Maximum initial speed along each axis (m/s)
+const VELOCITY_RANGE: Real = 1.0;
+
+/// Range of body masses (kg)
+const MASS_RANGE: (Real, Real) = (1.0, 10.0);
+
+pub use crate::scenarios::DeterministicRng;
+
+/// Statistics from a headless simulation run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunStats {
+    /// Number of integration steps executed
+    pub steps: usize,
+    /// Wall-clock time spent in the simulation loop
+    pub wall_time: Duration,
+    /// Throughput in steps per second
+    pub steps_per_second: f64,
+    /// Total energy (kinetic + potential) before the first step
+    pub initial_energy: Real,
+    /// Total energy after the last step
+    pub final_energy: Real,
+    /// Relative energy drift |E_final - E_initial| / |E_initial|
+    pub energy_drift: Real,
+}
+
+/// Run a gravitational N-body simulation without output and report timing stats
+///
+/// Bodies are placed with `DeterministicRng`, so the same `num_bodies` and
+/// `seed` always produce the same initial conditions. The integrator's
+/// configured timestep is used for every step. Only the simulation loop
+/// (force computation and integration) is timed; setup and energy
+/// calculations are excluded.
+///
+/// # Arguments
+///
+/// * `num_bodies` - Number of bodies to simulate
+/// * `steps` - Number of integration steps
+/// * `integrator` - Integrator to benchmark
+/// * `seed` - Seed for the initial conditions
+pub fn run_nbody<T: Integrator>(
+    num_bodies: usize,
+    steps: usize,
+    integrator: &mut T,
+    seed: u64,
+) -> RunStats {
+    let mut world = World::new();
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut velocities = HashMapStorage::<Velocity>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let mut masses = HashMapStorage::<Mass>::new();
+    let mut rng = DeterministicRng::new(seed);
+
+    let mut entities = Vec::with_capacity(num_bodies);
+    for _ in 0..num_bodies {
+        let entity = world.create_entity();
+        positions.insert(entity, Position::new(
+            rng.next_f64_range(-POSITION_RANGE, POSITION_RANGE),
+            rng.next_f64_range(-POSITION_RANGE, POSITION_RANGE),
+            rng.next_f64_range(-POSITION_RANGE, POSITION_RANGE),
+        ));
+        velocities.insert(entity, Velocity::new(
+            rng.next_f64_range(-VELOCITY_RANGE, VELOCITY_RANGE),
+            rng.next_f64_range(-VELOCITY_RANGE, VELOCITY_RANGE),
+            rng.next_f64_range(-VELOCITY_RANGE, VELOCITY_RANGE),
+        ));
+        masses.insert(entity, Mass::new(rng.next_f64_range(MASS_RANGE.0, MASS_RANGE.1)));
+        accelerations.insert(entity, Acceleration::zero());
+        entities.push(entity);
+    }
+
+    let mut gravity_plugin = GravityPlugin::with_scaled_g(G_SCALE);
+    gravity_plugin.set_softening(SOFTENING);
+    gravity_plugin.set_warn_on_high_forces(false);
+    gravity_plugin.set_warn_on_invalid(false);
+    let gravity_system = GravitySystem::new(gravity_plugin);
+
+    let mut force_registry = ForceRegistry::new();
+    force_registry.set_warn_on_missing_components(false);
+    let mut gravity_forces = HashMap::with_capacity(num_bodies);
+
+    let initial_energy = total_energy(&entities, &positions, &velocities, &masses);
+
+    let start = Instant::now();
+    for _ in 0..steps {
+        force_registry.clear();
+        gravity_system.compute_forces_into(&entities, &positions, &masses, &mut gravity_forces);
+        force_registry.register_forces(&gravity_forces);
+        apply_forces_to_acceleration(entities.iter(), &force_registry, &masses, &mut accelerations, false);
+        integrator.integrate(
+            entities.iter(),
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &masses,
+            &mut force_registry,
+            false,
+        );
+    }
+    let wall_time = start.elapsed();
+
+    let final_energy = total_energy(&entities, &positions, &velocities, &masses);
+    let energy_drift = if initial_energy != 0.0 {
+        ((final_energy - initial_energy) / initial_energy).abs()
+    } else {
+        (final_energy - initial_energy).abs()
+    };
+
+    RunStats {
+        steps,
+        wall_time,
+        steps_per_second: steps as f64 / wall_time.as_secs_f64().max(f64::MIN_POSITIVE),
+        initial_energy,
+        final_energy,
+        energy_drift,
+    }
+}
+
+/// Total kinetic plus softened gravitational potential energy
+fn total_energy(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    velocities: &impl ComponentStorage<Component = Velocity>,
+    masses: &impl ComponentStorage<Component = Mass>,
+) -> Real {
+    let g = GRAVITATIONAL_CONSTANT * G_SCALE;
+    calculate_total_kinetic_energy(entities.iter(), velocities, masses)
+        + gravitational_potential_energy(entities, positions, masses, g, SOFTENING)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integration::{RK4Integrator, VelocityVerletIntegrator};
+
+    #[test]
+    fn test_run_nbody_smoke() {
+        let mut integrator = VelocityVerletIntegrator::new(0.001);
+        let stats = run_nbody(8, 50, &mut integrator, 12345);
+
+        assert_eq!(stats.steps, 50);
+        assert!(stats.wall_time > Duration::ZERO);
+        assert!(stats.steps_per_second.is_finite() && stats.steps_per_second > 0.0);
+        assert!(stats.initial_energy.is_finite());
+        assert!(stats.final_energy.is_finite());
+        assert!(stats.energy_drift < 0.1, "Energy drift too large: {}", stats.energy_drift);
+    }
+
+    #[test]
+    fn test_run_nbody_same_seed_same_result() {
+        let a = run_nbody(5, 10, &mut RK4Integrator::new(0.001), 99);
+        let b = run_nbody(5, 10, &mut RK4Integrator::new(0.001), 99);
+
+        assert_eq!(a.initial_energy, b.initial_energy);
+        assert_eq!(a.final_energy, b.final_energy);
+    }
+}
@@ -0,0 +1,587 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Whole-system diagnostics for gravitating bodies
+//!
+//! Energies and ratios computed over a set of entities, used to judge
+//! whether a simulated cluster is bound, collapsing or in equilibrium.
+//!
+//! # Sign Conventions
+//!
+//! Gravitational potential energy is negative for any set of massive
+//! bodies at finite separation and approaches zero as they move apart.
+//! Kinetic energy is never negative.
+//!
+//! # Virial Ratio
+//!
+//! A self-gravitating system in equilibrium satisfies the virial theorem
+//! 2·KE + PE = 0, so the ratio Q = 2·KE/|PE| is:
+//! - near 0 for a cold system that is about to collapse
+//! - near 1 for a system in virial equilibrium
+//! - above 1 for a system that is expanding or unbound (Q > 2)
+//!
+//! # Bounding Boxes
+//!
+//! `compute_aabb()` returns the axis-aligned box around a set of positions
+//! as `(min, max)` corners, for camera framing and broadphase sizing. An
+//! empty set yields `EMPTY_AABB`, an inverted box (min = +∞, max = −∞) that
+//! any point grows into a valid one.
+//!
+//! # Force Statistics
+//!
+//! After forces are accumulated, `analyze_forces()` bins the per-entity
+//! force magnitudes by decade and lists the strongest, and
+//! `detect_force_outliers()` flags entities whose force sits far above the
+//! median. A handful of extreme pairwise forces is the usual reason a
+//! cluster blows apart.
+//!
+//! # Comparing Trajectories
+//!
+//! `phase_space_distance()` measures how far apart two states of the same
+//! bodies are, combining position and velocity differences into one L2
+//! norm. Comparing an integrator's output with a run at a much finer
+//! timestep gives a single error figure to rank integrators by.
+
+use crate::real::Real;
+use crate::ecs::{ComponentStorage, Entity, PositionSoAStorage};
+use crate::ecs::components::{Mass, Position, Velocity};
+use crate::ecs::systems::ForceRegistry;
+use crate::integration::calculate_total_kinetic_energy;
+
+/// Total softened gravitational potential energy of a set of bodies
+///
+/// PE = −Σ G·mᵢ·mⱼ / √(r²ᵢⱼ + ε²) over every pair, where ε is the softening
+/// length. Use the softening the gravity plugin was configured with so the
+/// energy matches the forces being integrated, or 0.0 for the Newtonian
+/// potential. Entities without a position or mass are skipped.
+pub fn gravitational_potential_energy(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    g: Real,
+    softening: Real,
+) -> Real {
+    let softening_squared = softening * softening;
+    let mut potential = 0.0;
+
+    for (i, &e1) in entities.iter().enumerate() {
+        let (p1, m1) = match (positions.get(e1), masses.get(e1)) {
+            (Some(p), Some(m)) => (p, m),
+            _ => continue,
+        };
+        for &e2 in &entities[i + 1..] {
+            if let (Some(p2), Some(m2)) = (positions.get(e2), masses.get(e2)) {
+                let dx = p2.x() - p1.x();
+                let dy = p2.y() - p1.y();
+                let dz = p2.z() - p1.z();
+                let r = (dx * dx + dy * dy + dz * dz + softening_squared).sqrt();
+                potential -= g * m1.value() * m2.value() / r;
+            }
+        }
+    }
+
+    potential
+}
+
+/// Virial ratio Q = 2·KE/|PE| of a set of bodies
+///
+/// Combines `calculate_total_kinetic_energy()` with
+/// `gravitational_potential_energy()`. A value near 1 indicates virial
+/// equilibrium; see the module documentation for how to read other values.
+///
+/// When the potential energy is zero (fewer than two massive bodies) the
+/// ratio is infinite if anything is moving and 0.0 otherwise.
+pub fn virial_ratio(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    velocities: &impl ComponentStorage<Component = Velocity>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    g: Real,
+    softening: Real,
+) -> Real {
+    let kinetic = calculate_total_kinetic_energy(entities.iter(), velocities, masses);
+    let potential = gravitational_potential_energy(entities, positions, masses, g, softening);
+
+    if potential == 0.0 {
+        return if kinetic > 0.0 { Real::INFINITY } else { 0.0 };
+    }
+    2.0 * kinetic / potential.abs()
+}
+
+/// Combined position and velocity L2 distance between two states
+///
+/// √(Σ |xₐ − x_b|² + |vₐ − v_b|²) over the entities present in all four
+/// storages; the others are skipped. Positions and velocities are added
+/// as-is, so compare states in units where both have similar magnitudes
+/// (for example G = 1 N-body units), or velocity errors will be drowned out
+/// or dominate.
+pub fn phase_space_distance(
+    entities: &[Entity],
+    positions_a: &impl ComponentStorage<Component = Position>,
+    velocities_a: &impl ComponentStorage<Component = Velocity>,
+    positions_b: &impl ComponentStorage<Component = Position>,
+    velocities_b: &impl ComponentStorage<Component = Velocity>,
+) -> Real {
+    let mut sum_squared = 0.0;
+    for &entity in entities {
+        let states = (
+            positions_a.get(entity),
+            velocities_a.get(entity),
+            positions_b.get(entity),
+            velocities_b.get(entity),
+        );
+        if let (Some(pa), Some(va), Some(pb), Some(vb)) = states {
+            let a = pa.as_array().into_iter().chain(va.as_array());
+            let b = pb.as_array().into_iter().chain(vb.as_array());
+            sum_squared += a.zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<Real>();
+        }
+    }
+    sum_squared.sqrt()
+}
+
+/// Inverted bounding box returned for an empty set of positions
+///
+/// Every component of `min` is +∞ and every component of `max` is −∞, so
+/// `min[k] > max[k]` identifies it and merging in any point yields that
+/// point's degenerate box.
+pub const EMPTY_AABB: ([Real; 3], [Real; 3]) = ([Real::INFINITY; 3], [Real::NEG_INFINITY; 3]);
+
+/// Axis-aligned bounding box of a set of entities
+///
+/// Returns the `(min, max)` corners over every entity with a position, or
+/// `EMPTY_AABB` when none has one. A single point gives `min == max`. NaN
+/// coordinates are ignored.
+pub fn compute_aabb(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+) -> ([Real; 3], [Real; 3]) {
+    let (mut min, mut max) = EMPTY_AABB;
+    for position in entities.iter().filter_map(|entity| positions.get(*entity)) {
+        for (k, value) in position.as_array().into_iter().enumerate() {
+            min[k] = min[k].min(value);
+            max[k] = max[k].max(value);
+        }
+    }
+    (min, max)
+}
+
+/// Axis-aligned bounding box of every position in an SoA storage
+///
+/// Same result as `compute_aabb()` over the storage's entities, computed
+/// as independent min/max reductions over the contiguous x, y and z arrays
+/// so the compiler can vectorize them.
+pub fn compute_aabb_soa(positions: &PositionSoAStorage) -> ([Real; 3], [Real; 3]) {
+    let (mut min, mut max) = EMPTY_AABB;
+    if let Some(arrays) = positions.field_arrays() {
+        let (x, y, z) = arrays.as_position_arrays();
+        for (k, column) in [x, y, z].into_iter().enumerate() {
+            min[k] = column.iter().copied().fold(Real::INFINITY, Real::min);
+            max[k] = column.iter().copied().fold(Real::NEG_INFINITY, Real::max);
+        }
+    }
+    (min, max)
+}
+
+/// One decade of force magnitudes in a `ForceHistogram`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramBin {
+    /// Inclusive lower edge (N)
+    pub lower: Real,
+    /// Exclusive upper edge (N), ten times `lower`
+    pub upper: Real,
+    /// Number of entities whose force falls in the bin
+    pub count: usize,
+    /// Mean force magnitude in the bin, 0.0 when empty
+    pub mean: Real,
+    /// Largest force magnitude in the bin, 0.0 when empty
+    pub max: Real,
+}
+
+/// Distribution of accumulated force magnitudes over a set of entities
+///
+/// Built by `analyze_forces()`. Bins cover every decade from the weakest to
+/// the strongest non-zero force, including empty decades in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForceHistogram {
+    bins: Vec<HistogramBin>,
+    zero_count: usize,
+    top: Vec<(Entity, Real)>,
+}
+
+impl ForceHistogram {
+    /// Get the logarithmic bins, weakest first
+    pub fn bins(&self) -> &[HistogramBin] {
+        &self.bins
+    }
+
+    /// Get the number of entities with an exactly zero force
+    ///
+    /// These have no decade and are not in any bin.
+    pub fn zero_count(&self) -> usize {
+        self.zero_count
+    }
+
+    /// Get the strongest forces as `(entity, magnitude)`, strongest first
+    pub fn top(&self) -> &[(Entity, Real)] {
+        &self.top
+    }
+
+    /// Get the total number of entities counted, zero forces included
+    pub fn total(&self) -> usize {
+        self.zero_count + self.bins.iter().map(|bin| bin.count).sum::<usize>()
+    }
+}
+
+/// Entities whose force is far above the median, from `detect_force_outliers()`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlierReport {
+    /// Median force magnitude (N)
+    pub median: Real,
+    /// Median absolute deviation of the magnitudes from the median (N)
+    pub mad: Real,
+    /// Magnitude above which a force is an outlier: median + N·MAD
+    pub threshold: Real,
+    /// Outlying `(entity, magnitude)` pairs, strongest first
+    pub outliers: Vec<(Entity, Real)>,
+}
+
+/// Accumulated force magnitudes of `entities`, skipping those without a force
+fn force_magnitudes(registry: &ForceRegistry, entities: &[Entity]) -> Vec<(Entity, Real)> {
+    entities
+        .iter()
+        .filter_map(|&entity| registry.get_force(entity).map(|force| (entity, force.magnitude())))
+        .collect()
+}
+
+/// Sort `(entity, magnitude)` pairs strongest first
+fn sort_strongest_first(magnitudes: &mut [(Entity, Real)]) {
+    magnitudes.sort_by(|a, b| b.1.total_cmp(&a.1));
+}
+
+/// Median of a sorted slice, 0.0 when empty
+fn median_of_sorted(values: &[Real]) -> Real {
+    let n = values.len();
+    match n {
+        0 => 0.0,
+        _ if n % 2 == 1 => values[n / 2],
+        _ => 0.5 * (values[n / 2 - 1] + values[n / 2]),
+    }
+}
+
+/// Decade exponent k with 10^k ≤ magnitude < 10^(k+1)
+fn decade(magnitude: Real) -> i32 {
+    // log10 can land on the wrong side of an exact power of ten
+    let mut k = magnitude.log10().floor() as i32;
+    if (10.0 as Real).powi(k) > magnitude {
+        k -= 1;
+    } else if (10.0 as Real).powi(k + 1) <= magnitude {
+        k += 1;
+    }
+    k
+}
+
+/// Histogram the accumulated forces of `entities` and list the `top_k` strongest
+///
+/// Reads the forces the registry holds now, so call it after accumulation
+/// (and before `clear_forces()`). Entities without an accumulated force are
+/// skipped; non-finite forces never reach the registry.
+pub fn analyze_forces(registry: &ForceRegistry, entities: &[Entity], top_k: usize) -> ForceHistogram {
+    let mut magnitudes = force_magnitudes(registry, entities);
+    let zero_count = magnitudes.iter().filter(|(_, m)| *m == 0.0).count();
+
+    let decades: Vec<i32> = magnitudes.iter().filter(|(_, m)| *m > 0.0).map(|(_, m)| decade(*m)).collect();
+    let mut bins = Vec::new();
+    if let (Some(&lowest), Some(&highest)) = (decades.iter().min(), decades.iter().max()) {
+        bins = (lowest..=highest)
+            .map(|k| HistogramBin {
+                lower: (10.0 as Real).powi(k),
+                upper: (10.0 as Real).powi(k + 1),
+                count: 0,
+                mean: 0.0,
+                max: 0.0,
+            })
+            .collect();
+        for (k, (_, magnitude)) in decades.iter().zip(magnitudes.iter().filter(|(_, m)| *m > 0.0)) {
+            let bin = &mut bins[(k - lowest) as usize];
+            bin.count += 1;
+            bin.mean += magnitude;
+            bin.max = bin.max.max(*magnitude);
+        }
+        for bin in bins.iter_mut().filter(|bin| bin.count > 0) {
+            bin.mean /= bin.count as Real;
+        }
+    }
+
+    sort_strongest_first(&mut magnitudes);
+    magnitudes.truncate(top_k);
+    ForceHistogram { bins, zero_count, top: magnitudes }
+}
+
+/// Flag entities whose force exceeds the median by more than `mads` MADs
+///
+/// The median absolute deviation is robust to the outliers themselves, so a
+/// few runaway forces don't raise their own threshold. When most forces are
+/// equal the MAD is zero and every force above the median is flagged.
+///
+/// # Panics
+///
+/// Panics if `mads` is negative or not finite.
+pub fn detect_force_outliers(registry: &ForceRegistry, entities: &[Entity], mads: Real) -> OutlierReport {
+    assert!(mads >= 0.0 && mads.is_finite(), "MAD multiplier must be non-negative and finite");
+
+    let mut magnitudes = force_magnitudes(registry, entities);
+    let mut sorted: Vec<Real> = magnitudes.iter().map(|(_, m)| *m).collect();
+    sorted.sort_by(Real::total_cmp);
+    let median = median_of_sorted(&sorted);
+    let mut deviations: Vec<Real> = sorted.iter().map(|m| (m - median).abs()).collect();
+    deviations.sort_by(Real::total_cmp);
+    let mad = median_of_sorted(&deviations);
+    let threshold = median + mads * mad;
+
+    magnitudes.retain(|(_, m)| *m > threshold);
+    sort_strongest_first(&mut magnitudes);
+    OutlierReport { median, mad, threshold, outliers: magnitudes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::HashMapStorage;
+    use crate::real::RealExt;
+
+    struct Bodies {
+        entities: Vec<Entity>,
+        positions: HashMapStorage<Position>,
+        velocities: HashMapStorage<Velocity>,
+        masses: HashMapStorage<Mass>,
+    }
+
+    impl Bodies {
+        fn new() -> Self {
+            Bodies {
+                entities: Vec::new(),
+                positions: HashMapStorage::new(),
+                velocities: HashMapStorage::new(),
+                masses: HashMapStorage::new(),
+            }
+        }
+
+        fn add(&mut self, position: Position, velocity: Velocity, mass: Real) {
+            let entity = Entity::new(self.entities.len() as u64 + 1, 0);
+            self.entities.push(entity);
+            self.positions.insert(entity, position);
+            self.velocities.insert(entity, velocity);
+            self.masses.insert(entity, Mass::new(mass));
+        }
+
+        fn virial_ratio(&self, g: Real, softening: Real) -> Real {
+            virial_ratio(&self.entities, &self.positions, &self.velocities, &self.masses, g, softening)
+        }
+    }
+
+    #[test]
+    fn test_potential_energy_sign_and_magnitude() {
+        let mut bodies = Bodies::new();
+        bodies.add(Position::zero(), Velocity::zero(), 2.0);
+        bodies.add(Position::new(3.0, 4.0, 0.0), Velocity::zero(), 5.0);
+
+        // −G·m₁·m₂/r with r = 5
+        let pe = gravitational_potential_energy(&bodies.entities, &bodies.positions, &bodies.masses, 1.5, 0.0);
+        assert!((pe - (-1.5 * 2.0 * 5.0 / 5.0)).abs() < Real::TOLERANCE);
+
+        // Softening lengthens the effective separation to √(25 + 144) = 13
+        let softened =
+            gravitational_potential_energy(&bodies.entities, &bodies.positions, &bodies.masses, 1.5, 12.0);
+        assert!((softened - (-1.5 * 2.0 * 5.0 / 13.0)).abs() < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_cold_cluster_has_zero_virial_ratio() {
+        let mut bodies = Bodies::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                bodies.add(Position::new(i as Real, j as Real, (i * j) as Real), Velocity::zero(), 1.0 + i as Real);
+            }
+        }
+
+        assert_eq!(bodies.virial_ratio(1.0, 0.1), 0.0);
+    }
+
+    #[test]
+    fn test_circular_pair_is_in_virial_equilibrium() {
+        // Equal masses m on a circular orbit of separation d about their
+        // common centre: each moves at v² = G·m / (2d), so 2·KE = |PE|
+        let g: Real = 2.0;
+        let m = 3.0;
+        let d = 4.0;
+        let v = (g * m / (2.0 * d)).sqrt();
+
+        let mut bodies = Bodies::new();
+        bodies.add(Position::new(-d / 2.0, 0.0, 0.0), Velocity::new(0.0, -v, 0.0), m);
+        bodies.add(Position::new(d / 2.0, 0.0, 0.0), Velocity::new(0.0, v, 0.0), m);
+
+        let ratio = bodies.virial_ratio(g, 0.0);
+        assert!((ratio - 1.0).abs() < Real::TOLERANCE, "Virial ratio {}", ratio);
+
+        // A small softening barely shifts the ratio
+        let softened = bodies.virial_ratio(g, 0.01);
+        assert!(softened > 1.0 && softened < 1.001, "Softened virial ratio {}", softened);
+    }
+
+    #[test]
+    fn test_phase_space_distance_combines_position_and_velocity() {
+        let mut a = Bodies::new();
+        a.add(Position::new(1.0, 2.0, 3.0), Velocity::new(0.5, 0.0, 0.0), 1.0);
+        a.add(Position::zero(), Velocity::new(0.0, -1.0, 0.0), 1.0);
+        let mut b = HashMapStorage::<Position>::new();
+        let mut bv = HashMapStorage::<Velocity>::new();
+        // First body off by (3, 0, 0) in position, second by (0, 0, 4) in velocity
+        b.insert(a.entities[0], Position::new(4.0, 2.0, 3.0));
+        bv.insert(a.entities[0], Velocity::new(0.5, 0.0, 0.0));
+        b.insert(a.entities[1], Position::zero());
+        bv.insert(a.entities[1], Velocity::new(0.0, -1.0, 4.0));
+
+        let distance = phase_space_distance(&a.entities, &a.positions, &a.velocities, &b, &bv);
+        assert!((distance - 5.0).abs() < Real::TOLERANCE);
+        assert_eq!(phase_space_distance(&a.entities, &a.positions, &a.velocities, &a.positions, &a.velocities), 0.0);
+
+        // Entities missing from either state don't contribute
+        bv.remove(a.entities[1]);
+        let mut entities = a.entities.clone();
+        entities.push(Entity::new(99, 0));
+        let distance = phase_space_distance(&entities, &a.positions, &a.velocities, &b, &bv);
+        assert!((distance - 3.0).abs() < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_aabb_of_known_points() {
+        let mut bodies = Bodies::new();
+        let mut soa = PositionSoAStorage::new();
+        for (x, y, z) in [(1.0, -2.0, 3.0), (-4.0, 5.0, 0.5), (2.5, 0.0, -6.0)] {
+            bodies.add(Position::new(x, y, z), Velocity::zero(), 1.0);
+            soa.insert(*bodies.entities.last().unwrap(), Position::new(x, y, z));
+        }
+
+        let expected = ([-4.0, -2.0, -6.0], [2.5, 5.0, 3.0]);
+        assert_eq!(compute_aabb(&bodies.entities, &bodies.positions), expected);
+        assert_eq!(compute_aabb_soa(&soa), expected);
+
+        // Entities without a position don't widen the box
+        let mut entities = bodies.entities.clone();
+        entities.push(Entity::new(99, 0));
+        assert_eq!(compute_aabb(&entities, &bodies.positions), expected);
+    }
+
+    #[test]
+    fn test_aabb_of_single_point_is_degenerate() {
+        let mut bodies = Bodies::new();
+        bodies.add(Position::new(7.0, -1.0, 0.25), Velocity::zero(), 1.0);
+        let mut soa = PositionSoAStorage::new();
+        soa.insert(bodies.entities[0], Position::new(7.0, -1.0, 0.25));
+
+        let (min, max) = compute_aabb(&bodies.entities, &bodies.positions);
+        assert_eq!(min, max);
+        assert_eq!(min, [7.0, -1.0, 0.25]);
+        assert_eq!(compute_aabb_soa(&soa), (min, max));
+    }
+
+    #[test]
+    fn test_aabb_of_nothing_is_empty() {
+        let bodies = Bodies::new();
+        let (min, max) = compute_aabb(&bodies.entities, &bodies.positions);
+        assert_eq!((min, max), EMPTY_AABB);
+        assert!((0..3).all(|k| min[k] > max[k]));
+        assert_eq!(compute_aabb_soa(&PositionSoAStorage::new()), EMPTY_AABB);
+    }
+
+    /// Registry holding a fixed x-directed force of each magnitude
+    fn registry_with_forces(magnitudes: &[Real]) -> (ForceRegistry, Vec<Entity>) {
+        use crate::ecs::systems::Force;
+
+        let forces: Vec<(Entity, Force)> = magnitudes
+            .iter()
+            .enumerate()
+            .map(|(i, &m)| (Entity::new(i as u64 + 1, 0), Force::new(m, 0.0, 0.0)))
+            .collect();
+        let mut registry = ForceRegistry::new();
+        registry.register_ordered_forces(&forces);
+        let entities: Vec<Entity> = forces.iter().map(|(entity, _)| *entity).collect();
+        for entity in &entities {
+            registry.accumulate_for_entity(*entity);
+        }
+        (registry, entities)
+    }
+
+    #[test]
+    fn test_force_histogram_bins_by_decade() {
+        let (registry, entities) = registry_with_forces(&[0.0, 2.0, 5.0, 10.0, 40.0, 1000.0]);
+        let histogram = analyze_forces(&registry, &entities, 3);
+
+        // Decades 10^0 .. 10^3, with 10^2 empty
+        let bins = histogram.bins();
+        assert_eq!(bins.len(), 4);
+        assert_eq!(bins.iter().map(|bin| bin.count).collect::<Vec<_>>(), vec![2, 2, 0, 1]);
+        assert_eq!(bins[0].lower, 1.0);
+        assert_eq!(bins[3].upper, 10000.0);
+        assert!((bins[0].mean - 3.5).abs() < Real::TOLERANCE);
+        assert_eq!(bins[1].max, 40.0);
+        assert_eq!(bins[2].mean, 0.0);
+        assert_eq!(histogram.zero_count(), 1);
+        assert_eq!(histogram.total(), 6);
+
+        // Strongest first, truncated to k
+        let top: Vec<(Entity, Real)> = histogram.top().to_vec();
+        assert_eq!(top, vec![(entities[5], 1000.0), (entities[4], 40.0), (entities[3], 10.0)]);
+    }
+
+    #[test]
+    fn test_force_histogram_of_no_forces_is_empty() {
+        let (registry, _) = registry_with_forces(&[]);
+        let histogram = analyze_forces(&registry, &[Entity::new(1, 0)], 5);
+        assert!(histogram.bins().is_empty());
+        assert!(histogram.top().is_empty());
+        assert_eq!(histogram.total(), 0);
+    }
+
+    #[test]
+    fn test_force_outliers_use_median_absolute_deviation() {
+        // Median 10, deviations {0, 0, 1, 1, 2, 2, 90} → MAD 1
+        let (registry, entities) = registry_with_forces(&[8.0, 9.0, 10.0, 11.0, 12.0, 100.0, 10.0]);
+        let report = detect_force_outliers(&registry, &entities, 3.0);
+        assert_eq!(report.median, 10.0);
+        assert_eq!(report.mad, 1.0);
+        assert_eq!(report.threshold, 13.0);
+        assert_eq!(report.outliers, vec![(entities[5], 100.0)]);
+
+        // Tightening the multiplier pulls in the edges of the bulk
+        let tight = detect_force_outliers(&registry, &entities, 1.5);
+        assert_eq!(tight.threshold, 11.5);
+        assert_eq!(tight.outliers, vec![(entities[5], 100.0), (entities[4], 12.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "MAD multiplier must be non-negative and finite")]
+    fn test_force_outliers_reject_negative_multiplier() {
+        let (registry, entities) = registry_with_forces(&[1.0]);
+        detect_force_outliers(&registry, &entities, -1.0);
+    }
+
+    #[test]
+    fn test_lone_body_has_no_binding_energy() {
+        let mut bodies = Bodies::new();
+        assert_eq!(bodies.virial_ratio(1.0, 0.0), 0.0);
+
+        bodies.add(Position::zero(), Velocity::new(1.0, 0.0, 0.0), 1.0);
+        assert_eq!(bodies.virial_ratio(1.0, 0.0), Real::INFINITY);
+    }
+}
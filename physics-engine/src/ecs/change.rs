@@ -0,0 +1,386 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Change detection for component storages
+//!
+//! Incremental systems, such as a neighbor search that only rebuilds when
+//! positions move, need to know which components changed since they last
+//! looked. `ChangeTracking` wraps any `ComponentStorage` and records the
+//! entities whose component may have been modified:
+//!
+//! - `insert()`, `get_mut()` and `remove()` mark the entity, whether or not
+//!   the caller writes through the returned reference
+//! - `field_arrays_mut()` marks every entity in the storage, since the
+//!   columns can be written at any index
+//! - `clear()` can't name the entities it drops and sets `cleared()` instead
+//!
+//! Marks accumulate until `clear_changes()`. The wrapper is itself a
+//! `ComponentStorage`, so systems and integrators take it in place of the
+//! storage it wraps.
+//!
+//! The true SoA storages (`PositionSoAStorage` and friends) track changes
+//! themselves, without a wrapper, as a per-index "last modified" tick; see
+//! `PositionSoAStorage::changed_since()`.
+//!
+//! ```
+//! use physics_engine::ecs::{ChangeTracking, ComponentStorage, HashMapStorage, World};
+//! use physics_engine::ecs::components::Position;
+//!
+//! let mut world = World::new();
+//! let (a, b) = (world.create_entity(), world.create_entity());
+//! let mut positions = ChangeTracking::new(HashMapStorage::new());
+//! positions.insert(a, Position::zero());
+//! positions.insert(b, Position::zero());
+//! positions.clear_changes();
+//!
+//! positions.get_mut(b).unwrap().set_x(1.0);
+//! assert_eq!(positions.changed().collect::<Vec<_>>(), vec![b]);
+//! ```
+
+use crate::ecs::{ComponentStorage, Entity, FieldArrays, FieldArraysMut, MemoryReport};
+use crate::ecs::component::hash_map_bytes;
+use std::collections::HashSet;
+
+/// Storage wrapper that records which entities' components were mutated
+#[derive(Debug, Clone, Default)]
+pub struct ChangeTracking<S> {
+    storage: S,
+    /// Marked entities in the order they were first marked
+    changed: Vec<Entity>,
+    marked: HashSet<Entity>,
+    cleared: bool,
+}
+
+impl<S: ComponentStorage> ChangeTracking<S> {
+    /// Track changes to `storage`, starting with nothing marked
+    pub fn new(storage: S) -> Self {
+        ChangeTracking {
+            storage,
+            changed: Vec::new(),
+            marked: HashSet::new(),
+            cleared: false,
+        }
+    }
+
+    /// Get the wrapped storage
+    pub fn inner(&self) -> &S {
+        &self.storage
+    }
+
+    /// Unwrap the storage, discarding the change record
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    /// Iterate over the entities marked since the last `clear_changes()`
+    ///
+    /// Each entity appears once, in the order it was first marked. Removed
+    /// entities are included; check `contains()` to tell them apart.
+    pub fn changed(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.changed.iter().copied()
+    }
+
+    /// Number of entities marked since the last `clear_changes()`
+    pub fn changed_count(&self) -> usize {
+        self.changed.len()
+    }
+
+    /// Check whether an entity was marked since the last `clear_changes()`
+    pub fn is_changed(&self, entity: Entity) -> bool {
+        self.marked.contains(&entity)
+    }
+
+    /// Check whether `clear()` was called since the last `clear_changes()`
+    pub fn cleared(&self) -> bool {
+        self.cleared
+    }
+
+    /// Mark an entity as changed without touching its component
+    ///
+    /// For mutations the wrapper can't see, such as writes through columns
+    /// obtained before tracking started.
+    pub fn mark_changed(&mut self, entity: Entity) {
+        if self.marked.insert(entity) {
+            self.changed.push(entity);
+        }
+    }
+
+    /// Forget every mark, keeping the allocations for the next round
+    pub fn clear_changes(&mut self) {
+        self.changed.clear();
+        self.marked.clear();
+        self.cleared = false;
+    }
+}
+
+impl<S: ComponentStorage> ComponentStorage for ChangeTracking<S> {
+    type Component = S::Component;
+
+    fn insert(&mut self, entity: Entity, component: Self::Component) {
+        self.mark_changed(entity);
+        self.storage.insert(entity, component);
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<Self::Component> {
+        let removed = self.storage.remove(entity);
+        if removed.is_some() {
+            self.mark_changed(entity);
+        }
+        removed
+    }
+
+    fn get(&self, entity: Entity) -> Option<&Self::Component> {
+        self.storage.get(entity)
+    }
+
+    fn get_mut(&mut self, entity: Entity) -> Option<&mut Self::Component> {
+        if self.storage.contains(entity) {
+            self.mark_changed(entity);
+        }
+        self.storage.get_mut(entity)
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.storage.contains(entity)
+    }
+
+    fn clear(&mut self) {
+        self.storage.clear();
+        self.cleared = true;
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        let mut report = self.storage.memory_report();
+        report.map_overhead_estimate += self.changed.capacity() * std::mem::size_of::<Entity>()
+            + hash_map_bytes::<Entity, ()>(self.marked.capacity());
+        report
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+        self.changed.shrink_to_fit();
+        self.marked.shrink_to_fit();
+    }
+
+    fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
+        self.storage.field_arrays()
+    }
+
+    fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
+        if let Some(order) = self.storage.dense_order() {
+            for &entity in order {
+                if self.marked.insert(entity) {
+                    self.changed.push(entity);
+                }
+            }
+        }
+        self.storage.field_arrays_mut()
+    }
+
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        if let Some(order) = self.storage.dense_order() {
+            for (&entity, _) in order.iter().zip(written).filter(|(_, &written)| written) {
+                if self.marked.insert(entity) {
+                    self.changed.push(entity);
+                }
+            }
+        }
+        self.storage.field_arrays_mut_masked(written)
+    }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        self.storage.dense_order()
+    }
+}
+
+/// Per-index modification ticks for the true SoA storages
+///
+/// Every write through the storage stamps the entry's index with the
+/// storage's current tick, so an unqueried tracker costs one array write per
+/// modification. The tick only moves forward.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChangeTicks {
+    tick: u64,
+    /// Last modified tick, in the storage's dense order
+    ticks: Vec<u64>,
+}
+
+impl ChangeTicks {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        ChangeTicks { tick: 0, ticks: Vec::with_capacity(capacity) }
+    }
+
+    pub(crate) fn current(&self) -> u64 {
+        self.tick
+    }
+
+    pub(crate) fn advance(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    pub(crate) fn set(&mut self, tick: u64) {
+        assert!(tick >= self.tick, "Change tick must not decrease");
+        self.tick = tick;
+    }
+
+    /// Stamp a newly appended index
+    pub(crate) fn push(&mut self) {
+        self.ticks.push(self.tick);
+    }
+
+    pub(crate) fn mark(&mut self, index: usize) {
+        self.ticks[index] = self.tick;
+    }
+
+    pub(crate) fn mark_all(&mut self) {
+        self.ticks.fill(self.tick);
+    }
+
+    /// Stamp the indices where `written` is true
+    pub(crate) fn mark_masked(&mut self, written: &[bool]) {
+        assert_eq!(written.len(), self.ticks.len(), "Write mask must cover every dense index");
+        for (tick, _) in self.ticks.iter_mut().zip(written).filter(|(_, &written)| written) {
+            *tick = self.tick;
+        }
+    }
+
+    /// Mirror the storage's swap-remove of `index`
+    pub(crate) fn swap_remove(&mut self, index: usize) {
+        self.ticks.swap_remove(index);
+    }
+
+    pub(crate) fn get(&self, index: usize) -> u64 {
+        self.ticks[index]
+    }
+
+    /// Dense indices modified after `tick`
+    pub(crate) fn changed_since(&self, tick: u64) -> impl Iterator<Item = usize> + '_ {
+        self.ticks.iter().enumerate().filter(move |(_, &modified)| modified > tick).map(|(index, _)| index)
+    }
+
+    /// Drop every entry; the tick keeps its value
+    pub(crate) fn clear(&mut self) {
+        self.ticks.clear();
+    }
+
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.ticks.capacity() * std::mem::size_of::<u64>()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.ticks.shrink_to_fit();
+    }
+
+    /// Check that there is one stamp per dense index and none is in the future
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub(crate) fn check_invariants(&self, len: usize) -> Result<(), String> {
+        if self.ticks.len() != len {
+            return Err(format!("Length mismatch: ticks={}, dense entries={}", self.ticks.len(), len));
+        }
+        match self.ticks.iter().position(|&modified| modified > self.tick) {
+            Some(index) => Err(format!(
+                "Index {} stamped with tick {} after the current tick {}",
+                index, self.ticks[index], self.tick
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, PositionSoAStorage, World};
+    use crate::ecs::components::Position;
+
+    fn tracked_positions<S: ComponentStorage<Component = Position>>(storage: S) -> (Vec<Entity>, ChangeTracking<S>) {
+        let mut world = World::new();
+        let mut positions = ChangeTracking::new(storage);
+        let entities: Vec<Entity> = (0..5)
+            .map(|i| {
+                let entity = world.create_entity();
+                positions.insert(entity, Position::new(i as crate::real::Real, 0.0, 0.0));
+                entity
+            })
+            .collect();
+        (entities, positions)
+    }
+
+    #[test]
+    fn test_mutating_two_of_five_reports_those_two() {
+        let (entities, mut positions) = tracked_positions(HashMapStorage::new());
+        assert_eq!(positions.changed_count(), 5);
+        positions.clear_changes();
+        assert_eq!(positions.changed().count(), 0);
+
+        positions.get_mut(entities[3]).unwrap().set_y(1.0);
+        positions.insert(entities[1], Position::new(9.0, 9.0, 9.0));
+        // Reads and repeated writes don't add entries
+        let _ = positions.get(entities[0]);
+        positions.get_mut(entities[3]).unwrap().set_z(2.0);
+
+        assert_eq!(positions.changed().collect::<Vec<_>>(), vec![entities[3], entities[1]]);
+        assert!(positions.is_changed(entities[1]));
+        assert!(!positions.is_changed(entities[0]));
+
+        positions.clear_changes();
+        assert_eq!(positions.changed_count(), 0);
+        assert!(!positions.is_changed(entities[3]));
+        assert_eq!(positions.get(entities[3]).unwrap().z(), 2.0);
+    }
+
+    #[test]
+    fn test_removal_and_clear_are_tracked() {
+        let (entities, mut positions) = tracked_positions(HashMapStorage::new());
+        positions.clear_changes();
+
+        assert!(positions.remove(entities[2]).is_some());
+        assert!(positions.remove(entities[2]).is_none());
+        assert!(positions.get_mut(entities[2]).is_none());
+        assert_eq!(positions.changed().collect::<Vec<_>>(), vec![entities[2]]);
+        assert!(!positions.contains(entities[2]));
+
+        assert!(!positions.cleared());
+        positions.clear();
+        assert!(positions.cleared());
+        positions.clear_changes();
+        assert!(!positions.cleared());
+    }
+
+    #[test]
+    fn test_mutable_field_arrays_mark_every_entity() {
+        let (entities, mut positions) = tracked_positions(PositionSoAStorage::new());
+        positions.clear_changes();
+        assert_eq!(positions.dense_order(), Some(&entities[..]));
+
+        positions.field_arrays_mut().unwrap().as_position_arrays_mut().0[4] = -1.0;
+        assert_eq!(positions.changed().collect::<Vec<_>>(), entities);
+        assert_eq!(positions.field_arrays().unwrap().as_position_arrays().0[4], -1.0);
+        assert_eq!(positions.inner().len(), 5);
+    }
+
+    #[test]
+    fn test_masked_field_arrays_mark_masked_entities() {
+        let (entities, positions) = tracked_positions(PositionSoAStorage::new());
+        // Re-wrap so the storage's own ticks start after the inserts too
+        let mut storage = positions.into_inner();
+        let seen = storage.current_tick();
+        storage.advance_tick();
+        let mut positions = ChangeTracking::new(storage);
+
+        positions.field_arrays_mut_masked(&[false, true, false, false, true]).unwrap();
+        assert_eq!(positions.changed().collect::<Vec<_>>(), vec![entities[1], entities[4]]);
+        assert_eq!(positions.inner().changed_since(seen).collect::<Vec<_>>(), vec![entities[1], entities[4]]);
+    }
+}
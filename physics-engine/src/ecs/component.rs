@@ -17,9 +17,163 @@
 //! This module provides traits and storage mechanisms optimized for
 //! cache-friendly access patterns.
 
+use crate::real::Real;
+use crate::ecs::change::ChangeTicks;
 use crate::ecs::Entity;
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
 use std::any::TypeId;
 use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Entity → dense index mapping used by the true SoA storages
+///
+/// Backed by `HashMap` by default, or by a sparse array indexed by entity ID
+/// with the `sparse-set` feature (see `SparseEntityIndex`).
+#[cfg(not(feature = "sparse-set"))]
+pub(crate) type EntityIndexMap = HashMap<Entity, usize>;
+#[cfg(feature = "sparse-set")]
+pub(crate) type EntityIndexMap = crate::ecs::sparse::SparseEntityIndex;
+
+/// Approximate heap memory held by a storage
+///
+/// Figures are estimates computed from allocation capacities, not exact
+/// allocator measurements, but they scale with capacity so they show growth
+/// and the effect of `shrink_to_fit()`.
+///
+/// Reports from several storages can be combined with `+` or summed:
+///
+/// ```
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, MemoryReport};
+/// use physics_engine::ecs::components::{Position, Mass};
+///
+/// let positions = HashMapStorage::<Position>::new();
+/// let masses = HashMapStorage::<Mass>::new();
+/// let total: MemoryReport = [positions.memory_report(), masses.memory_report()]
+///     .into_iter()
+///     .sum();
+/// assert_eq!(total, positions.memory_report() + masses.memory_report());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryReport {
+    /// Number of entities the storage can hold without reallocating
+    pub entity_capacity: usize,
+    /// Bytes reserved for component data, including unused capacity
+    pub component_bytes: usize,
+    /// Estimated bytes used by entity lookup structures
+    pub map_overhead_estimate: usize,
+}
+
+impl MemoryReport {
+    /// Total estimated bytes
+    pub fn total_bytes(&self) -> usize {
+        self.component_bytes + self.map_overhead_estimate
+    }
+}
+
+impl std::ops::Add for MemoryReport {
+    type Output = MemoryReport;
+
+    fn add(self, other: MemoryReport) -> MemoryReport {
+        MemoryReport {
+            entity_capacity: self.entity_capacity + other.entity_capacity,
+            component_bytes: self.component_bytes + other.component_bytes,
+            map_overhead_estimate: self.map_overhead_estimate + other.map_overhead_estimate,
+        }
+    }
+}
+
+impl std::ops::AddAssign for MemoryReport {
+    fn add_assign(&mut self, other: MemoryReport) {
+        *self = *self + other;
+    }
+}
+
+impl std::iter::Sum for MemoryReport {
+    fn sum<I: Iterator<Item = MemoryReport>>(iter: I) -> MemoryReport {
+        iter.fold(MemoryReport::default(), |acc, report| acc + report)
+    }
+}
+
+/// Estimated heap bytes of a `HashMap` with the given capacity
+///
+/// Counts one entry plus one control byte per slot, ignoring load-factor slack.
+pub(crate) fn hash_map_bytes<K, V>(capacity: usize) -> usize {
+    capacity * (std::mem::size_of::<(K, V)>() + 1)
+}
+
+/// Fraction of a map's capacity in use, or 0.0 for an unallocated map
+fn load_factor(len: usize, capacity: usize) -> Real {
+    if capacity == 0 {
+        0.0
+    } else {
+        len as Real / capacity as Real
+    }
+}
+
+/// Estimated heap bytes of an entity index map
+#[cfg(not(feature = "sparse-set"))]
+fn index_map_bytes(map: &EntityIndexMap) -> usize {
+    hash_map_bytes::<Entity, usize>(map.capacity())
+}
+
+/// Estimated heap bytes of an entity index map
+#[cfg(feature = "sparse-set")]
+fn index_map_bytes(map: &EntityIndexMap) -> usize {
+    map.heap_bytes()
+}
+
+/// Estimated heap bytes of a dense SoA storage's lookup structures
+///
+/// `entity_list_capacity` is the capacity of the dense-index → entity list.
+pub(crate) fn dense_index_bytes(map: &EntityIndexMap, entity_list_capacity: usize) -> usize {
+    index_map_bytes(map) + entity_list_capacity * std::mem::size_of::<Entity>()
+}
+
+/// Check a true SoA storage's index map, entity list, change ticks and field
+/// columns against each other
+///
+/// Every column and the ticks must have one entry per dense entity, and each
+/// dense entity must map back to its own index. With equal lengths that
+/// makes the mapping a bijection, so no entity appears twice.
+#[cfg(any(test, feature = "debug-invariants"))]
+fn check_dense_invariants(
+    entity_to_index: &EntityIndexMap,
+    index_to_entity: &[Entity],
+    ticks: &ChangeTicks,
+    columns: &[&[Real]],
+) -> Result<(), String> {
+    let len = index_to_entity.len();
+    if entity_to_index.len() != len {
+        return Err(format!(
+            "Length mismatch: entity_to_index={}, index_to_entity={}",
+            entity_to_index.len(),
+            len
+        ));
+    }
+    for (column, values) in columns.iter().enumerate() {
+        if values.len() != len {
+            return Err(format!(
+                "Length mismatch: field column {}={}, index_to_entity={}",
+                column,
+                values.len(),
+                len
+            ));
+        }
+    }
+    ticks.check_invariants(len)?;
+    for (i, entity) in index_to_entity.iter().enumerate() {
+        if entity_to_index.get(entity) != Some(&i) {
+            return Err(format!(
+                "Duplicate or unmapped entity {:?} at index {}",
+                entity, i
+            ));
+        }
+    }
+    Ok(())
+}
 
 /// Trait that all components must implement
 ///
@@ -102,6 +256,35 @@ pub trait ComponentStorage: Send + Sync {
     /// Clear all components
     fn clear(&mut self);
 
+    /// Report approximate memory held by this storage
+    ///
+    /// The default implementation reports nothing; storages that own
+    /// allocations should override it.
+    fn memory_report(&self) -> MemoryReport {
+        MemoryReport::default()
+    }
+
+    /// Get the number of entities the storage can hold without reallocating
+    ///
+    /// Always at least the number of stored components.
+    fn capacity(&self) -> usize {
+        self.memory_report().entity_capacity
+    }
+
+    /// Estimate the heap bytes held by this storage
+    ///
+    /// Covers component data and entity lookup structures, including unused
+    /// capacity. Shorthand for `memory_report().total_bytes()`.
+    fn memory_bytes(&self) -> usize {
+        self.memory_report().total_bytes()
+    }
+
+    /// Release unused capacity
+    ///
+    /// Storages only grow as components are inserted. Call this after mass
+    /// removals to return memory to the allocator. The default does nothing.
+    fn shrink_to_fit(&mut self) {}
+
     /// Get read-only access to field arrays for SoA-style iteration
     ///
     /// This method enables SIMD-friendly bulk operations by exposing separate
@@ -132,14 +315,111 @@ pub trait ComponentStorage: Send + Sync {
     /// contiguous arrays for each component field. Returns `None` for storage
     /// implementations that don't support SoA.
     ///
-    /// # Safety and Borrowing
+    /// # Borrowing
+    ///
+    /// The returned `FieldArraysMut` borrows the storage mutably for as long
+    /// as it is alive, so the compiler rejects every other use of the
+    /// storage until it is dropped. Columns can't be reallocated by
+    /// `insert()` or reordered by `remove()` underneath the borrow, and no
+    /// storage needs a runtime guard for it:
+    ///
+    /// ```
+    /// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage, World};
+    /// use physics_engine::ecs::components::Position;
+    ///
+    /// let mut world = World::new();
+    /// let (a, b) = (world.create_entity(), world.create_entity());
+    /// let mut storage = PositionSoAStorage::new();
+    /// storage.insert(a, Position::new(1.0, 0.0, 0.0));
+    ///
+    /// {
+    ///     let mut arrays = storage.field_arrays_mut().unwrap();
+    ///     arrays.as_position_arrays_mut().0[0] = 2.0;
+    /// } // borrow released here
+    ///
+    /// storage.insert(b, Position::zero());
+    /// let index = storage.get_index(a).unwrap();
+    /// assert_eq!(storage.field_arrays().unwrap().as_position_arrays().0[index], 2.0);
+    /// ```
+    ///
+    /// Structural changes while the arrays are borrowed don't compile:
+    ///
+    /// ```compile_fail
+    /// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage, World};
+    /// use physics_engine::ecs::components::Position;
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.create_entity();
+    /// let mut storage = PositionSoAStorage::new();
+    /// let mut arrays = storage.field_arrays_mut().unwrap();
+    /// storage.insert(entity, Position::zero()); // may reallocate the columns
+    /// arrays.as_position_arrays_mut().0[0] = 1.0;
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage, World};
+    /// use physics_engine::ecs::components::Position;
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.create_entity();
+    /// let mut storage = PositionSoAStorage::new();
+    /// storage.insert(entity, Position::zero());
+    /// let mut arrays = storage.field_arrays_mut().unwrap();
+    /// storage.remove(entity); // swaps another entity into the hole
+    /// arrays.as_position_arrays_mut().0[0] = 1.0;
+    /// ```
+    ///
+    /// Neither do reads, through the field arrays or per entity:
+    ///
+    /// ```compile_fail
+    /// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage};
+    ///
+    /// let mut storage = PositionSoAStorage::new();
+    /// let mut writing = storage.field_arrays_mut().unwrap();
+    /// let reading = storage.field_arrays().unwrap();
+    /// writing.as_position_arrays_mut().0[0] = reading.as_position_arrays().0[0];
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage, World};
     ///
-    /// The returned `FieldArraysMut` holds exclusive mutable borrows of the
-    /// underlying field arrays. While held, `get()`, `get_mut()`, `insert()`,
-    /// and `remove()` operations will fail to prevent aliasing violations.
+    /// let mut world = World::new();
+    /// let entity = world.create_entity();
+    /// let mut storage = PositionSoAStorage::new();
+    /// let mut arrays = storage.field_arrays_mut().unwrap();
+    /// let index = storage.get_index(entity);
+    /// arrays.as_position_arrays_mut().0[index.unwrap_or(0)] = 1.0;
+    /// ```
     fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
         None
     }
+
+    /// Get mutable field arrays, declaring which dense indices will be written
+    ///
+    /// For kernels that know their writes in advance, such as integrators
+    /// skipping immovable bodies. Storages that track changes record only the
+    /// indices where `written` is true, where `field_arrays_mut()` has to
+    /// assume every index changed. Writing elsewhere goes unrecorded.
+    ///
+    /// The default forwards to `field_arrays_mut()`.
+    ///
+    /// # Panics
+    ///
+    /// Tracking storages panic if `written` is not as long as the arrays.
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        let _ = written;
+        self.field_arrays_mut()
+    }
+
+    /// Get the entities in dense-index order, for storages with field arrays
+    ///
+    /// Entity `i` owns index `i` of every array returned by `field_arrays()`.
+    /// Kernels that zip several storages compare these slices to check
+    /// alignment (see `PositionSoAStorage`). Returns `None` for storages
+    /// without dense access.
+    fn dense_order(&self) -> Option<&[Entity]> {
+        None
+    }
 }
 
 /// Read-only access to component field arrays in Structure-of-Arrays layout
@@ -150,12 +430,16 @@ pub trait ComponentStorage: Send + Sync {
 ///
 /// # Type-Specific Access
 ///
-/// Each component type provides accessor methods for its fields:
+/// Accessors are keyed on the component type, so asking for the wrong
+/// fields is a compile error rather than a runtime panic:
 /// - `Position`: `as_position_arrays()` → `(&[f64], &[f64], &[f64])` for x, y, z
 /// - `Velocity`: `as_velocity_arrays()` → `(&[f64], &[f64], &[f64])` for dx, dy, dz
 /// - `Acceleration`: `as_acceleration_arrays()` → `(&[f64], &[f64], &[f64])` for ax, ay, az
 /// - `Mass`: `as_mass_array()` → `&[f64]` for values
 ///
+/// Storages build them with the matching constructor (`position()`,
+/// `velocity()`, `acceleration()` or `mass()`).
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -168,149 +452,218 @@ pub trait ComponentStorage: Send + Sync {
 ///     // Process x, y, z with SIMD operations
 /// }
 /// ```
-pub enum FieldArrays<'a, T: Component> {
-    /// Position component field arrays (x, y, z)
-    Position(&'a [f64], &'a [f64], &'a [f64]),
-    /// Velocity component field arrays (dx, dy, dz)
-    Velocity(&'a [f64], &'a [f64], &'a [f64]),
-    /// Acceleration component field arrays (ax, ay, az)
-    Acceleration(&'a [f64], &'a [f64], &'a [f64]),
-    /// Mass component field array (value)
-    Mass(&'a [f64]),
-    /// Marker to use the generic type parameter
-    _Phantom(std::marker::PhantomData<T>),
+///
+/// ```compile_fail
+/// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage};
+///
+/// let storage = PositionSoAStorage::new();
+/// let arrays = storage.field_arrays().unwrap();
+/// let (dx, dy, dz) = arrays.as_velocity_arrays(); // not Velocity arrays
+/// ```
+pub struct FieldArrays<'a, T: Component> {
+    /// Field columns in declaration order; unused columns are empty
+    columns: [&'a [Real]; 3],
+    _component: PhantomData<T>,
 }
 
 impl<'a, T: Component> FieldArrays<'a, T> {
-    /// Access Position field arrays (x, y, z)
-    ///
-    /// # Panics
-    ///
-    /// Panics if this is not a Position field array
-    pub fn as_position_arrays(&self) -> (&'a [f64], &'a [f64], &'a [f64]) {
-        match self {
-            FieldArrays::Position(x, y, z) => (*x, *y, *z),
-            _ => panic!("Expected Position field arrays"),
+    pub(crate) fn from_columns(columns: [&'a [Real]; 3]) -> Self {
+        let len = columns[0].len();
+        assert!(
+            columns.iter().all(|column| column.is_empty() || column.len() == len),
+            "Field arrays must all have the same length"
+        );
+        FieldArrays {
+            columns,
+            _component: PhantomData,
         }
     }
+}
 
-    /// Access Velocity field arrays (dx, dy, dz)
+impl<'a> FieldArrays<'a, Position> {
+    /// Wrap Position field arrays (x, y, z)
     ///
     /// # Panics
     ///
-    /// Panics if this is not a Velocity field array
-    pub fn as_velocity_arrays(&self) -> (&'a [f64], &'a [f64], &'a [f64]) {
-        match self {
-            FieldArrays::Velocity(dx, dy, dz) => (*dx, *dy, *dz),
-            _ => panic!("Expected Velocity field arrays"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn position(x: &'a [Real], y: &'a [Real], z: &'a [Real]) -> Self {
+        Self::from_columns([x, y, z])
     }
 
-    /// Access Acceleration field arrays (ax, ay, az)
+    /// Access Position field arrays (x, y, z)
+    pub fn as_position_arrays(&self) -> (&'a [Real], &'a [Real], &'a [Real]) {
+        let [x, y, z] = self.columns;
+        (x, y, z)
+    }
+}
+
+impl<'a> FieldArrays<'a, Velocity> {
+    /// Wrap Velocity field arrays (dx, dy, dz)
     ///
     /// # Panics
     ///
-    /// Panics if this is not an Acceleration field array
-    pub fn as_acceleration_arrays(&self) -> (&'a [f64], &'a [f64], &'a [f64]) {
-        match self {
-            FieldArrays::Acceleration(ax, ay, az) => (*ax, *ay, *az),
-            _ => panic!("Expected Acceleration field arrays"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn velocity(dx: &'a [Real], dy: &'a [Real], dz: &'a [Real]) -> Self {
+        Self::from_columns([dx, dy, dz])
     }
 
-    /// Access Mass field array (value)
+    /// Access Velocity field arrays (dx, dy, dz)
+    pub fn as_velocity_arrays(&self) -> (&'a [Real], &'a [Real], &'a [Real]) {
+        let [dx, dy, dz] = self.columns;
+        (dx, dy, dz)
+    }
+}
+
+impl<'a> FieldArrays<'a, Acceleration> {
+    /// Wrap Acceleration field arrays (ax, ay, az)
     ///
     /// # Panics
     ///
-    /// Panics if this is not a Mass field array
-    pub fn as_mass_array(&self) -> &'a [f64] {
-        match self {
-            FieldArrays::Mass(values) => *values,
-            _ => panic!("Expected Mass field array"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn acceleration(ax: &'a [Real], ay: &'a [Real], az: &'a [Real]) -> Self {
+        Self::from_columns([ax, ay, az])
+    }
+
+    /// Access Acceleration field arrays (ax, ay, az)
+    pub fn as_acceleration_arrays(&self) -> (&'a [Real], &'a [Real], &'a [Real]) {
+        let [ax, ay, az] = self.columns;
+        (ax, ay, az)
+    }
+}
+
+impl<'a> FieldArrays<'a, Mass> {
+    /// Wrap the Mass field array (value)
+    pub fn mass(values: &'a [Real]) -> Self {
+        Self::from_columns([values, &[], &[]])
+    }
+
+    /// Access Mass field array (value)
+    pub fn as_mass_array(&self) -> &'a [Real] {
+        self.columns[0]
     }
 }
 
 /// Mutable access to component field arrays in Structure-of-Arrays layout
 ///
 /// This type provides mutable access to separate contiguous arrays for each field
-/// of a component type, enabling SIMD-friendly bulk mutations.
+/// of a component type, enabling SIMD-friendly bulk mutations. Like
+/// `FieldArrays`, accessors are keyed on the component type.
 ///
-/// # Safety and Borrowing
+/// # Borrowing
 ///
 /// While this object exists, it holds exclusive mutable borrows of the underlying
-/// field arrays. Other storage operations (get, insert, remove) must not be called
-/// until this is dropped.
-pub enum FieldArraysMut<'a, T: Component> {
-    /// Position component field arrays (x, y, z)
-    Position(&'a mut [f64], &'a mut [f64], &'a mut [f64]),
-    /// Velocity component field arrays (dx, dy, dz)
-    Velocity(&'a mut [f64], &'a mut [f64], &'a mut [f64]),
-    /// Acceleration component field arrays (ax, ay, az)
-    Acceleration(&'a mut [f64], &'a mut [f64], &'a mut [f64]),
-    /// Mass component field array (value)
-    Mass(&'a mut [f64]),
-    /// Marker to use the generic type parameter
-    _Phantom(std::marker::PhantomData<T>),
+/// field arrays, and the borrow checker rejects any other storage operation
+/// (get, insert, remove) until it is dropped. See
+/// `ComponentStorage::field_arrays_mut()` for examples.
+pub struct FieldArraysMut<'a, T: Component> {
+    /// Field columns in declaration order; unused columns are empty
+    columns: [&'a mut [Real]; 3],
+    _component: PhantomData<T>,
 }
 
 impl<'a, T: Component> FieldArraysMut<'a, T> {
-    /// Access Position field arrays mutably (x, y, z)
-    ///
-    /// # Panics
-    ///
-    /// Panics if this is not a Position field array
-    pub fn as_position_arrays_mut(&mut self) -> (&mut [f64], &mut [f64], &mut [f64]) {
-        match self {
-            FieldArraysMut::Position(x, y, z) => (x, y, z),
-            _ => panic!("Expected Position field arrays"),
+    pub(crate) fn from_columns(columns: [&'a mut [Real]; 3]) -> Self {
+        let len = columns[0].len();
+        assert!(
+            columns.iter().all(|column| column.is_empty() || column.len() == len),
+            "Field arrays must all have the same length"
+        );
+        FieldArraysMut {
+            columns,
+            _component: PhantomData,
         }
     }
 
-    /// Access Velocity field arrays mutably (dx, dy, dz)
+    fn triple(&mut self) -> (&mut [Real], &mut [Real], &mut [Real]) {
+        let [a, b, c] = &mut self.columns;
+        (a, b, c)
+    }
+}
+
+impl<'a> FieldArraysMut<'a, Position> {
+    /// Wrap Position field arrays (x, y, z)
     ///
     /// # Panics
     ///
-    /// Panics if this is not a Velocity field array
-    pub fn as_velocity_arrays_mut(&mut self) -> (&mut [f64], &mut [f64], &mut [f64]) {
-        match self {
-            FieldArraysMut::Velocity(dx, dy, dz) => (dx, dy, dz),
-            _ => panic!("Expected Velocity field arrays"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn position(x: &'a mut [Real], y: &'a mut [Real], z: &'a mut [Real]) -> Self {
+        Self::from_columns([x, y, z])
     }
 
-    /// Access Acceleration field arrays mutably (ax, ay, az)
+    /// Access Position field arrays mutably (x, y, z)
+    pub fn as_position_arrays_mut(&mut self) -> (&mut [Real], &mut [Real], &mut [Real]) {
+        self.triple()
+    }
+}
+
+impl<'a> FieldArraysMut<'a, Velocity> {
+    /// Wrap Velocity field arrays (dx, dy, dz)
     ///
     /// # Panics
     ///
-    /// Panics if this is not an Acceleration field array
-    pub fn as_acceleration_arrays_mut(&mut self) -> (&mut [f64], &mut [f64], &mut [f64]) {
-        match self {
-            FieldArraysMut::Acceleration(ax, ay, az) => (ax, ay, az),
-            _ => panic!("Expected Acceleration field arrays"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn velocity(dx: &'a mut [Real], dy: &'a mut [Real], dz: &'a mut [Real]) -> Self {
+        Self::from_columns([dx, dy, dz])
     }
 
-    /// Access Mass field array mutably (value)
+    /// Access Velocity field arrays mutably (dx, dy, dz)
+    pub fn as_velocity_arrays_mut(&mut self) -> (&mut [Real], &mut [Real], &mut [Real]) {
+        self.triple()
+    }
+}
+
+impl<'a> FieldArraysMut<'a, Acceleration> {
+    /// Wrap Acceleration field arrays (ax, ay, az)
     ///
     /// # Panics
     ///
-    /// Panics if this is not a Mass field array
-    pub fn as_mass_array_mut(&mut self) -> &mut [f64] {
-        match self {
-            FieldArraysMut::Mass(values) => *values,
-            _ => panic!("Expected Mass field array"),
-        }
+    /// Panics if the arrays have different lengths.
+    pub fn acceleration(ax: &'a mut [Real], ay: &'a mut [Real], az: &'a mut [Real]) -> Self {
+        Self::from_columns([ax, ay, az])
+    }
+
+    /// Access Acceleration field arrays mutably (ax, ay, az)
+    pub fn as_acceleration_arrays_mut(&mut self) -> (&mut [Real], &mut [Real], &mut [Real]) {
+        self.triple()
+    }
+}
+
+impl<'a> FieldArraysMut<'a, Mass> {
+    /// Wrap the Mass field array (value)
+    pub fn mass(values: &'a mut [Real]) -> Self {
+        Self::from_columns([values, &mut [], &mut []])
+    }
+
+    /// Access Mass field array mutably (value)
+    pub fn as_mass_array_mut(&mut self) -> &mut [Real] {
+        &mut *self.columns[0]
     }
 }
 
+/// Sort `(entity, item)` pairs by the entities' insertion sequence numbers
+fn sort_by_sequence<X>(sequence: &HashMap<Entity, u64>, mut items: Vec<(Entity, X)>) -> Vec<(Entity, X)> {
+    items.sort_unstable_by_key(|(entity, _)| sequence[entity]);
+    items
+}
+
 /// Simple HashMap-based component storage
 ///
 /// Note: This implementation prioritizes simplicity for the initial release.
 /// Future versions will optimize with Structure-of-Arrays (SoA) layouts for
 /// improved cache performance and SIMD opportunities.
+///
+/// # Iteration Order
+///
+/// `iter()`, `iter_mut()`, `retain()` and `drain()` visit components in the
+/// map's order, which is arbitrary and differs between runs. Results that
+/// depend on the order, such as floating-point sums, can then differ in the
+/// last bits. Storages created with `with_insertion_order()` visit
+/// components in the order their entities were first inserted instead.
 pub struct HashMapStorage<T: Component> {
     components: HashMap<Entity, T>,
+    /// Insertion sequence number per entity, in insertion-ordered mode
+    sequence: Option<HashMap<Entity, u64>>,
+    next_sequence: u64,
 }
 
 impl<T: Component> HashMapStorage<T> {
@@ -318,7 +671,161 @@ impl<T: Component> HashMapStorage<T> {
     pub fn new() -> Self {
         HashMapStorage {
             components: HashMap::new(),
+            sequence: None,
+            next_sequence: 0,
+        }
+    }
+
+    /// Create a new empty storage with room for `capacity` components
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashMapStorage {
+            components: HashMap::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Create a new empty storage that iterates in insertion order
+    ///
+    /// Re-inserting an entity that is already stored keeps its place. The
+    /// order costs a sequence number per entity and a sort on every
+    /// iteration, O(n log n); lookups are unaffected.
+    pub fn with_insertion_order() -> Self {
+        HashMapStorage {
+            sequence: Some(HashMap::new()),
+            ..Self::new()
+        }
+    }
+
+    /// Check if the storage iterates in insertion order
+    pub fn is_insertion_ordered(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Get the number of components stored
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Check if the storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Fraction of the map's capacity in use, between 0.0 and 1.0
+    ///
+    /// Low values after mass removals mean `shrink_to_fit()` would return
+    /// memory. An unallocated map reports 0.0.
+    pub fn load_factor(&self) -> Real {
+        load_factor(self.components.len(), self.components.capacity())
+    }
+
+    /// Iterate over every entity and its component
+    ///
+    /// See the type documentation for the iteration order.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> + '_ {
+        let pairs = self.components.iter().map(|(&entity, component)| (entity, component));
+        // Exactly one of the two halves is non-empty
+        let (ordered, unordered) = match &self.sequence {
+            Some(sequence) => (Some(sort_by_sequence(sequence, pairs.collect())), None),
+            None => (None, Some(pairs)),
+        };
+        ordered.into_iter().flatten().chain(unordered.into_iter().flatten())
+    }
+
+    /// Iterate mutably over every entity and its component
+    ///
+    /// See the type documentation for the iteration order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> + '_ {
+        let pairs = self.components.iter_mut().map(|(&entity, component)| (entity, component));
+        let (ordered, unordered) = match &self.sequence {
+            Some(sequence) => (Some(sort_by_sequence(sequence, pairs.collect())), None),
+            None => (None, Some(pairs)),
+        };
+        ordered.into_iter().flatten().chain(unordered.into_iter().flatten())
+    }
+
+    /// Keep only the components for which `keep` returns true
+    ///
+    /// `keep` may modify the components it keeps. See the type documentation
+    /// for the order components are visited in.
+    pub fn retain(&mut self, mut keep: impl FnMut(Entity, &mut T) -> bool) {
+        if self.sequence.is_none() {
+            self.components.retain(|&entity, component| keep(entity, component));
+            return;
+        }
+        let removed: Vec<Entity> = self
+            .iter_mut()
+            .filter_map(|(entity, component)| (!keep(entity, component)).then_some(entity))
+            .collect();
+        for entity in removed {
+            self.remove(entity);
+        }
+    }
+
+    /// Remove every component, yielding each with its entity
+    ///
+    /// The storage is empty afterwards even if the iterator is dropped
+    /// early; capacity is kept. See the type documentation for the
+    /// iteration order.
+    pub fn drain(&mut self) -> impl Iterator<Item = (Entity, T)> + '_ {
+        let (ordered, unordered) = match &mut self.sequence {
+            Some(sequence) => {
+                let drained = sort_by_sequence(sequence, self.components.drain().collect());
+                sequence.clear();
+                (Some(drained), None)
+            }
+            None => (None, Some(self.components.drain())),
+        };
+        ordered.into_iter().flatten().chain(unordered.into_iter().flatten())
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// In insertion-ordered mode, validates that exactly the stored entities
+    /// have a sequence number and that the numbers are distinct and no
+    /// larger than the last one handed out. A storage without insertion order has
+    /// nothing to check. Available in tests and with the `debug-invariants`
+    /// feature.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let Some(sequence) = &self.sequence else {
+            return Ok(());
+        };
+        if sequence.len() != self.components.len() {
+            return Err(format!(
+                "Length mismatch: sequence={}, components={}",
+                sequence.len(),
+                self.components.len()
+            ));
+        }
+        let mut seen = std::collections::HashSet::with_capacity(sequence.len());
+        for (entity, &number) in sequence {
+            if !self.components.contains_key(entity) {
+                return Err(format!("Entity {:?} has a sequence number but no component", entity));
+            }
+            if number == 0 || number > self.next_sequence {
+                return Err(format!(
+                    "Entity {:?} has sequence number {} outside 1..={}",
+                    entity, number, self.next_sequence
+                ));
+            }
+            if !seen.insert(number) {
+                return Err(format!("Sequence number {} is shared by two entities", number));
+            }
         }
+        Ok(())
+    }
+
+    /// Iterate over the entities that have a component here
+    #[cfg(feature = "parallel")]
+    pub(crate) fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.components.keys().copied()
+    }
+
+    /// Iterate mutably over every component on Rayon's thread pool
+    #[cfg(feature = "parallel")]
+    pub(crate) fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = (Entity, &mut T)> + '_ {
+        self.components.par_iter_mut().map(|(&entity, component)| (entity, component))
     }
 }
 
@@ -332,10 +839,20 @@ impl<T: Component> ComponentStorage for HashMapStorage<T> {
     type Component = T;
 
     fn insert(&mut self, entity: Entity, component: Self::Component) {
+        if let Some(sequence) = &mut self.sequence {
+            let next = &mut self.next_sequence;
+            sequence.entry(entity).or_insert_with(|| {
+                *next += 1;
+                *next
+            });
+        }
         self.components.insert(entity, component);
     }
 
     fn remove(&mut self, entity: Entity) -> Option<Self::Component> {
+        if let Some(sequence) = &mut self.sequence {
+            sequence.remove(&entity);
+        }
         self.components.remove(&entity)
     }
 
@@ -352,8 +869,31 @@ impl<T: Component> ComponentStorage for HashMapStorage<T> {
     }
 
     fn clear(&mut self) {
+        if let Some(sequence) = &mut self.sequence {
+            sequence.clear();
+        }
         self.components.clear();
     }
+
+    fn memory_report(&self) -> MemoryReport {
+        let capacity = self.components.capacity();
+        let sequence_bytes = self
+            .sequence
+            .as_ref()
+            .map_or(0, |sequence| hash_map_bytes::<Entity, u64>(sequence.capacity()));
+        MemoryReport {
+            entity_capacity: capacity,
+            component_bytes: capacity * std::mem::size_of::<T>(),
+            map_overhead_estimate: hash_map_bytes::<Entity, ()>(capacity) + sequence_bytes,
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        if let Some(sequence) = &mut self.sequence {
+            sequence.shrink_to_fit();
+        }
+        self.components.shrink_to_fit();
+    }
 }
 
 /// Dense array component storage with cache-friendly layout
@@ -406,11 +946,12 @@ impl<T: Component> ComponentStorage for HashMapStorage<T> {
 /// # Example
 ///
 /// ```
-/// use physics_engine::ecs::{Entity, ComponentStorage, SoAStorage};
+/// use physics_engine::ecs::{ComponentStorage, SoAStorage, World};
 /// use physics_engine::ecs::components::Position;
 ///
+/// let mut world = World::new();
+/// let entity = world.create_entity();
 /// let mut storage = SoAStorage::<Position>::new();
-/// let entity = Entity::new(1, 0);
 ///
 /// storage.insert(entity, Position::new(1.0, 2.0, 3.0));
 /// assert!(storage.contains(entity));
@@ -424,9 +965,17 @@ pub struct SoAStorage<T: Component + Copy> {
     /// The actual component data stored densely
     /// Components are Copy so we can efficiently return values
     components: Vec<T>,
+    /// Occupancy fraction below which removals trigger `compact()` (None = disabled)
+    auto_shrink_threshold: Option<Real>,
 }
 
 impl<T: Component + Copy> SoAStorage<T> {
+    /// Capacity below which auto-shrink never triggers
+    ///
+    /// Small storages are cheap to keep around, and shrinking them on every
+    /// removal would just cause reallocation churn.
+    pub const AUTO_SHRINK_MIN_CAPACITY: usize = 64;
+
     /// Create a new empty SoA storage
     pub fn new() -> Self {
         Self::with_capacity(0)
@@ -438,9 +987,49 @@ impl<T: Component + Copy> SoAStorage<T> {
             entity_to_index: HashMap::with_capacity(capacity),
             index_to_entity: Vec::with_capacity(capacity),
             components: Vec::with_capacity(capacity),
+            auto_shrink_threshold: None,
         }
     }
 
+    /// Get the number of components the dense arrays can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.components.capacity()
+    }
+
+    /// Release unused capacity after a wave of removals
+    ///
+    /// Removals swap-remove from the dense arrays, so the data stays packed
+    /// but the allocation never shrinks on its own. This shrinks the dense
+    /// arrays and the entity index map to fit the current number of components.
+    /// Entity-to-index mappings are unchanged.
+    pub fn compact(&mut self) {
+        self.components.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.entity_to_index.shrink_to_fit();
+    }
+
+    /// Enable or disable automatic compaction on removal
+    ///
+    /// When enabled, `remove()` calls `compact()` once occupancy
+    /// (`len() / capacity()`) drops below `threshold`. Storages with capacity
+    /// under `AUTO_SHRINK_MIN_CAPACITY` are never auto-compacted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the threshold is not in the range (0, 1). A threshold of 1
+    /// would compact, and so reallocate, on every removal.
+    pub fn set_auto_shrink_threshold(&mut self, threshold: Option<Real>) {
+        if let Some(t) = threshold {
+            assert!(t > 0.0 && t < 1.0, "Auto-shrink threshold must be in (0, 1)");
+        }
+        self.auto_shrink_threshold = threshold;
+    }
+
+    /// Get the auto-shrink occupancy threshold
+    pub fn auto_shrink_threshold(&self) -> Option<Real> {
+        self.auto_shrink_threshold
+    }
+
     /// Get the number of components stored
     pub fn len(&self) -> usize {
         self.components.len()
@@ -458,6 +1047,13 @@ impl<T: Component + Copy> SoAStorage<T> {
         self.components.reserve(additional);
     }
 
+    /// Fraction of the entity index map's capacity in use, between 0.0 and 1.0
+    ///
+    /// An unallocated map reports 0.0.
+    pub fn load_factor(&self) -> Real {
+        load_factor(self.entity_to_index.len(), self.entity_to_index.capacity())
+    }
+
     /// Get all entities that have components in this storage
     pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
         self.index_to_entity.iter().copied()
@@ -485,6 +1081,23 @@ impl<T: Component + Copy> SoAStorage<T> {
         self.entity_to_index.get(&entity).copied()
     }
 
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Pairs are zipped from the dense entity and component arrays, so each
+    /// component is always yielded with the entity that owns it. The order
+    /// matches `entities()` and changes when a removal swaps the last
+    /// element into the freed slot.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> + '_ {
+        self.index_to_entity.iter().copied().zip(self.components.iter())
+    }
+
+    /// Iterate over `(entity, component)` pairs with mutable component access
+    ///
+    /// Same ordering as `iter()`.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> + '_ {
+        self.index_to_entity.iter().copied().zip(self.components.iter_mut())
+    }
+
     /// Check internal invariants for testing and debugging
     ///
     /// This method validates that the storage's internal state is consistent:
@@ -493,8 +1106,9 @@ impl<T: Component + Copy> SoAStorage<T> {
     /// - No entity appears twice
     ///
     /// Returns `Ok(())` if all invariants hold, or `Err(String)` with a
-    /// description of the violated invariant.
-    #[cfg(test)]
+    /// description of the violated invariant. Available in tests and with
+    /// the `debug-invariants` feature.
+    #[cfg(any(test, feature = "debug-invariants"))]
     pub fn check_invariants(&self) -> Result<(), String> {
         // Check lengths match
         if self.entity_to_index.len() != self.index_to_entity.len() {
@@ -591,6 +1205,15 @@ impl<T: Component + Copy> ComponentStorage for SoAStorage<T> {
             debug_assert_eq!(self.entity_to_index.len(), self.index_to_entity.len());
             debug_assert_eq!(self.entity_to_index.len(), self.components.len());
 
+            if let Some(threshold) = self.auto_shrink_threshold {
+                let capacity = self.components.capacity();
+                if capacity >= Self::AUTO_SHRINK_MIN_CAPACITY
+                    && (self.components.len() as Real) < threshold * capacity as Real
+                {
+                    self.compact();
+                }
+            }
+
             Some(component)
         } else {
             None
@@ -616,6 +1239,20 @@ impl<T: Component + Copy> ComponentStorage for SoAStorage<T> {
         self.index_to_entity.clear();
         self.components.clear();
     }
+
+    fn memory_report(&self) -> MemoryReport {
+        let capacity = self.components.capacity();
+        MemoryReport {
+            entity_capacity: capacity,
+            component_bytes: capacity * std::mem::size_of::<T>(),
+            map_overhead_estimate: hash_map_bytes::<Entity, usize>(self.entity_to_index.capacity())
+                + self.index_to_entity.capacity() * std::mem::size_of::<Entity>(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.compact();
+    }
 }
 
 /// True Structure-of-Arrays storage for Position components
@@ -634,11 +1271,11 @@ impl<T: Component + Copy> ComponentStorage for SoAStorage<T> {
 /// # Example
 ///
 /// ```rust
-/// use physics_engine::ecs::{Entity, ComponentStorage, PositionSoAStorage};
+/// use physics_engine::ecs::{ComponentStorage, PositionSoAStorage, World};
 /// use physics_engine::ecs::components::Position;
 ///
+/// let entity = World::new().create_entity();
 /// let mut storage = PositionSoAStorage::new();
-/// let entity = Entity::new(1, 0);
 ///
 /// storage.insert(entity, Position::new(1.0, 2.0, 3.0));
 ///
@@ -652,12 +1289,26 @@ impl<T: Component + Copy> ComponentStorage for SoAStorage<T> {
 ///     // Process x, y, z with SIMD
 /// }
 /// ```
+///
+/// # Index Alignment
+///
+/// The true SoA storages (`PositionSoAStorage`, `VelocitySoAStorage`,
+/// `AccelerationSoAStorage`, `MassSoAStorage`) each maintain their own
+/// entity-to-index mapping. Indices are assigned in insertion order and
+/// removal uses swap-remove, so two storages place the same entity at the
+/// same dense index only if they received the same sequence of inserts and
+/// removes. Nothing enforces this across storages.
+///
+/// Kernels that zip field arrays from several storages must either verify
+/// alignment by comparing `dense_entities()` (`dense_order()` in generic
+/// code) or map between storages with `get_index()`.
 pub struct PositionSoAStorage {
-    entity_to_index: HashMap<Entity, usize>,
+    entity_to_index: EntityIndexMap,
     index_to_entity: Vec<Entity>,
-    x_values: Vec<f64>,
-    y_values: Vec<f64>,
-    z_values: Vec<f64>,
+    ticks: ChangeTicks,
+    x_values: Vec<Real>,
+    y_values: Vec<Real>,
+    z_values: Vec<Real>,
 }
 
 impl PositionSoAStorage {
@@ -669,8 +1320,9 @@ impl PositionSoAStorage {
     /// Create a new Position SoA storage with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
         PositionSoAStorage {
-            entity_to_index: HashMap::with_capacity(capacity),
+            entity_to_index: EntityIndexMap::with_capacity(capacity),
             index_to_entity: Vec::with_capacity(capacity),
+            ticks: ChangeTicks::with_capacity(capacity),
             x_values: Vec::with_capacity(capacity),
             y_values: Vec::with_capacity(capacity),
             z_values: Vec::with_capacity(capacity),
@@ -686,6 +1338,73 @@ impl PositionSoAStorage {
     pub fn is_empty(&self) -> bool {
         self.x_values.is_empty()
     }
+
+    /// Get the entities in dense-index order
+    ///
+    /// The entity at position `i` owns the field values at index `i`.
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get the dense index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Get the tick that modifications are currently stamped with
+    pub fn current_tick(&self) -> u64 {
+        self.ticks.current()
+    }
+
+    /// Move to the next tick and return it
+    pub fn advance_tick(&mut self) -> u64 {
+        self.ticks.advance()
+    }
+
+    /// Move to `tick`, for example a driver's `Simulation::current_tick()`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick` is earlier than `current_tick()`.
+    pub fn set_tick(&mut self, tick: u64) {
+        self.ticks.set(tick);
+    }
+
+    /// Get the tick at which an entity's component was last modified
+    pub fn last_changed(&self, entity: Entity) -> Option<u64> {
+        self.get_index(entity).map(|index| self.ticks.get(index))
+    }
+
+    /// Iterate over the entities modified after `tick`, in dense-index order
+    ///
+    /// `insert()` stamps the entity's index with `current_tick()`,
+    /// `field_arrays_mut()` stamps every index and `field_arrays_mut_masked()`
+    /// the masked ones. Reads stamp nothing. Removed entities are not
+    /// reported.
+    pub fn changed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks.changed_since(tick).map(|index| self.index_to_entity[index])
+    }
+
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Components are reconstructed from the field arrays on the fly, so
+    /// items are yielded by value. The order matches `dense_entities()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, crate::ecs::components::Position)> + '_ {
+        self.index_to_entity.iter().enumerate().map(move |(i, &entity)| {
+            (entity, crate::ecs::components::Position::new(self.x_values[i], self.y_values[i], self.z_values[i]))
+        })
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// Validates that the index map, entity list, change ticks and the
+    /// three field arrays have the same length and that the mappings are
+    /// bidirectional. Available in tests and with the `debug-invariants`
+    /// feature.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_dense_invariants(&self.entity_to_index, &self.index_to_entity, &self.ticks, &[&self.x_values, &self.y_values, &self.z_values])
+    }
 }
 
 impl Default for PositionSoAStorage {
@@ -703,7 +1422,14 @@ impl ComponentStorage for PositionSoAStorage {
             self.x_values[index] = component.x();
             self.y_values[index] = component.y();
             self.z_values[index] = component.z();
+            self.ticks.mark(index);
         } else {
+            // A stale generation of this ID still holds the sparse slot
+            #[cfg(feature = "sparse-set")]
+            if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+                self.remove(stale);
+            }
+
             // New entity, append to end
             let new_index = self.x_values.len();
             self.x_values.push(component.x());
@@ -711,6 +1437,7 @@ impl ComponentStorage for PositionSoAStorage {
             self.z_values.push(component.z());
             self.entity_to_index.insert(entity, new_index);
             self.index_to_entity.push(entity);
+            self.ticks.push();
         }
     }
 
@@ -738,6 +1465,7 @@ impl ComponentStorage for PositionSoAStorage {
             self.y_values.pop();
             self.z_values.pop();
             self.index_to_entity.pop();
+            self.ticks.swap_remove(index);
 
             Some(Self::Component::new(x, y, z))
         } else {
@@ -765,13 +1493,33 @@ impl ComponentStorage for PositionSoAStorage {
     fn clear(&mut self) {
         self.entity_to_index.clear();
         self.index_to_entity.clear();
+        self.ticks.clear();
         self.x_values.clear();
         self.y_values.clear();
         self.z_values.clear();
     }
 
+    fn memory_report(&self) -> MemoryReport {
+        let capacities = [self.x_values.capacity(), self.y_values.capacity(), self.z_values.capacity()];
+        MemoryReport {
+            entity_capacity: capacities.into_iter().min().unwrap_or(0),
+            component_bytes: capacities.iter().sum::<usize>() * std::mem::size_of::<Real>(),
+            map_overhead_estimate: dense_index_bytes(&self.entity_to_index, self.index_to_entity.capacity())
+                + self.ticks.heap_bytes(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entity_to_index.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.ticks.shrink_to_fit();
+        self.x_values.shrink_to_fit();
+        self.y_values.shrink_to_fit();
+        self.z_values.shrink_to_fit();
+    }
+
     fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
-        Some(FieldArrays::Position(
+        Some(FieldArrays::position(
             &self.x_values,
             &self.y_values,
             &self.z_values,
@@ -779,47 +1527,124 @@ impl ComponentStorage for PositionSoAStorage {
     }
 
     fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
-        Some(FieldArraysMut::Position(
+        self.ticks.mark_all();
+        Some(FieldArraysMut::position(
+            &mut self.x_values,
+            &mut self.y_values,
+            &mut self.z_values,
+        ))
+    }
+
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        self.ticks.mark_masked(written);
+        Some(FieldArraysMut::position(
             &mut self.x_values,
             &mut self.y_values,
             &mut self.z_values,
         ))
     }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        Some(&self.index_to_entity)
+    }
 }
 
 /// True Structure-of-Arrays storage for Velocity components
 ///
 /// Similar to `PositionSoAStorage` but for velocity components (dx, dy, dz).
 pub struct VelocitySoAStorage {
-    entity_to_index: HashMap<Entity, usize>,
+    entity_to_index: EntityIndexMap,
     index_to_entity: Vec<Entity>,
-    dx_values: Vec<f64>,
-    dy_values: Vec<f64>,
-    dz_values: Vec<f64>,
+    ticks: ChangeTicks,
+    dx_values: Vec<Real>,
+    dy_values: Vec<Real>,
+    dz_values: Vec<Real>,
 }
 
 impl VelocitySoAStorage {
+    /// Create a new empty Velocity SoA storage
     pub fn new() -> Self {
         Self::with_capacity(0)
     }
 
+    /// Create a new Velocity SoA storage with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
         VelocitySoAStorage {
-            entity_to_index: HashMap::with_capacity(capacity),
+            entity_to_index: EntityIndexMap::with_capacity(capacity),
             index_to_entity: Vec::with_capacity(capacity),
+            ticks: ChangeTicks::with_capacity(capacity),
             dx_values: Vec::with_capacity(capacity),
             dy_values: Vec::with_capacity(capacity),
             dz_values: Vec::with_capacity(capacity),
         }
     }
 
+    /// Get the number of components stored
     pub fn len(&self) -> usize {
         self.dx_values.len()
     }
 
+    /// Check if the storage is empty
     pub fn is_empty(&self) -> bool {
         self.dx_values.is_empty()
     }
+
+    /// Get the entities in dense-index order
+    ///
+    /// The entity at position `i` owns the field values at index `i`.
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get the dense index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Get the tick that modifications are currently stamped with
+    pub fn current_tick(&self) -> u64 {
+        self.ticks.current()
+    }
+
+    /// Move to the next tick and return it
+    pub fn advance_tick(&mut self) -> u64 {
+        self.ticks.advance()
+    }
+
+    /// Move to `tick`; panics if it is earlier than `current_tick()`
+    pub fn set_tick(&mut self, tick: u64) {
+        self.ticks.set(tick);
+    }
+
+    /// Get the tick at which an entity's component was last modified
+    pub fn last_changed(&self, entity: Entity) -> Option<u64> {
+        self.get_index(entity).map(|index| self.ticks.get(index))
+    }
+
+    /// Iterate over the entities modified after `tick`, in dense-index order
+    ///
+    /// Same rules as `PositionSoAStorage::changed_since()`.
+    pub fn changed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks.changed_since(tick).map(|index| self.index_to_entity[index])
+    }
+
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Components are reconstructed from the field arrays on the fly, so
+    /// items are yielded by value. The order matches `dense_entities()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, crate::ecs::components::Velocity)> + '_ {
+        self.index_to_entity.iter().enumerate().map(move |(i, &entity)| {
+            (entity, crate::ecs::components::Velocity::new(self.dx_values[i], self.dy_values[i], self.dz_values[i]))
+        })
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// See `PositionSoAStorage::check_invariants()`.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_dense_invariants(&self.entity_to_index, &self.index_to_entity, &self.ticks, &[&self.dx_values, &self.dy_values, &self.dz_values])
+    }
 }
 
 impl Default for VelocitySoAStorage {
@@ -836,13 +1661,21 @@ impl ComponentStorage for VelocitySoAStorage {
             self.dx_values[index] = component.dx();
             self.dy_values[index] = component.dy();
             self.dz_values[index] = component.dz();
+            self.ticks.mark(index);
         } else {
+            // A stale generation of this ID still holds the sparse slot
+            #[cfg(feature = "sparse-set")]
+            if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+                self.remove(stale);
+            }
+
             let new_index = self.dx_values.len();
             self.dx_values.push(component.dx());
             self.dy_values.push(component.dy());
             self.dz_values.push(component.dz());
             self.entity_to_index.insert(entity, new_index);
             self.index_to_entity.push(entity);
+            self.ticks.push();
         }
     }
 
@@ -868,6 +1701,7 @@ impl ComponentStorage for VelocitySoAStorage {
             self.dy_values.pop();
             self.dz_values.pop();
             self.index_to_entity.pop();
+            self.ticks.swap_remove(index);
 
             Some(Self::Component::new(dx, dy, dz))
         } else {
@@ -890,13 +1724,33 @@ impl ComponentStorage for VelocitySoAStorage {
     fn clear(&mut self) {
         self.entity_to_index.clear();
         self.index_to_entity.clear();
+        self.ticks.clear();
         self.dx_values.clear();
         self.dy_values.clear();
         self.dz_values.clear();
     }
 
+    fn memory_report(&self) -> MemoryReport {
+        let capacities = [self.dx_values.capacity(), self.dy_values.capacity(), self.dz_values.capacity()];
+        MemoryReport {
+            entity_capacity: capacities.into_iter().min().unwrap_or(0),
+            component_bytes: capacities.iter().sum::<usize>() * std::mem::size_of::<Real>(),
+            map_overhead_estimate: dense_index_bytes(&self.entity_to_index, self.index_to_entity.capacity())
+                + self.ticks.heap_bytes(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entity_to_index.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.ticks.shrink_to_fit();
+        self.dx_values.shrink_to_fit();
+        self.dy_values.shrink_to_fit();
+        self.dz_values.shrink_to_fit();
+    }
+
     fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
-        Some(FieldArrays::Velocity(
+        Some(FieldArrays::velocity(
             &self.dx_values,
             &self.dy_values,
             &self.dz_values,
@@ -904,44 +1758,121 @@ impl ComponentStorage for VelocitySoAStorage {
     }
 
     fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
-        Some(FieldArraysMut::Velocity(
+        self.ticks.mark_all();
+        Some(FieldArraysMut::velocity(
             &mut self.dx_values,
             &mut self.dy_values,
             &mut self.dz_values,
         ))
     }
+
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        self.ticks.mark_masked(written);
+        Some(FieldArraysMut::velocity(
+            &mut self.dx_values,
+            &mut self.dy_values,
+            &mut self.dz_values,
+        ))
+    }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        Some(&self.index_to_entity)
+    }
 }
 
 /// True Structure-of-Arrays storage for Acceleration components
 pub struct AccelerationSoAStorage {
-    entity_to_index: HashMap<Entity, usize>,
+    entity_to_index: EntityIndexMap,
     index_to_entity: Vec<Entity>,
-    ax_values: Vec<f64>,
-    ay_values: Vec<f64>,
-    az_values: Vec<f64>,
+    ticks: ChangeTicks,
+    ax_values: Vec<Real>,
+    ay_values: Vec<Real>,
+    az_values: Vec<Real>,
 }
 
 impl AccelerationSoAStorage {
+    /// Create a new empty Acceleration SoA storage
     pub fn new() -> Self {
         Self::with_capacity(0)
     }
 
+    /// Create a new Acceleration SoA storage with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
         AccelerationSoAStorage {
-            entity_to_index: HashMap::with_capacity(capacity),
+            entity_to_index: EntityIndexMap::with_capacity(capacity),
             index_to_entity: Vec::with_capacity(capacity),
+            ticks: ChangeTicks::with_capacity(capacity),
             ax_values: Vec::with_capacity(capacity),
             ay_values: Vec::with_capacity(capacity),
             az_values: Vec::with_capacity(capacity),
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.ax_values.len()
+    /// Get the number of components stored
+    pub fn len(&self) -> usize {
+        self.ax_values.len()
+    }
+
+    /// Check if the storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.ax_values.is_empty()
+    }
+
+    /// Get the entities in dense-index order
+    ///
+    /// The entity at position `i` owns the field values at index `i`.
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get the dense index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Get the tick that modifications are currently stamped with
+    pub fn current_tick(&self) -> u64 {
+        self.ticks.current()
+    }
+
+    /// Move to the next tick and return it
+    pub fn advance_tick(&mut self) -> u64 {
+        self.ticks.advance()
+    }
+
+    /// Move to `tick`; panics if it is earlier than `current_tick()`
+    pub fn set_tick(&mut self, tick: u64) {
+        self.ticks.set(tick);
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.ax_values.is_empty()
+    /// Get the tick at which an entity's component was last modified
+    pub fn last_changed(&self, entity: Entity) -> Option<u64> {
+        self.get_index(entity).map(|index| self.ticks.get(index))
+    }
+
+    /// Iterate over the entities modified after `tick`, in dense-index order
+    ///
+    /// Same rules as `PositionSoAStorage::changed_since()`.
+    pub fn changed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks.changed_since(tick).map(|index| self.index_to_entity[index])
+    }
+
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Components are reconstructed from the field arrays on the fly, so
+    /// items are yielded by value. The order matches `dense_entities()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, crate::ecs::components::Acceleration)> + '_ {
+        self.index_to_entity.iter().enumerate().map(move |(i, &entity)| {
+            (entity, crate::ecs::components::Acceleration::new(self.ax_values[i], self.ay_values[i], self.az_values[i]))
+        })
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// See `PositionSoAStorage::check_invariants()`.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_dense_invariants(&self.entity_to_index, &self.index_to_entity, &self.ticks, &[&self.ax_values, &self.ay_values, &self.az_values])
     }
 }
 
@@ -959,13 +1890,21 @@ impl ComponentStorage for AccelerationSoAStorage {
             self.ax_values[index] = component.ax();
             self.ay_values[index] = component.ay();
             self.az_values[index] = component.az();
+            self.ticks.mark(index);
         } else {
+            // A stale generation of this ID still holds the sparse slot
+            #[cfg(feature = "sparse-set")]
+            if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+                self.remove(stale);
+            }
+
             let new_index = self.ax_values.len();
             self.ax_values.push(component.ax());
             self.ay_values.push(component.ay());
             self.az_values.push(component.az());
             self.entity_to_index.insert(entity, new_index);
             self.index_to_entity.push(entity);
+            self.ticks.push();
         }
     }
 
@@ -991,6 +1930,7 @@ impl ComponentStorage for AccelerationSoAStorage {
             self.ay_values.pop();
             self.az_values.pop();
             self.index_to_entity.pop();
+            self.ticks.swap_remove(index);
 
             Some(Self::Component::new(ax, ay, az))
         } else {
@@ -1013,13 +1953,33 @@ impl ComponentStorage for AccelerationSoAStorage {
     fn clear(&mut self) {
         self.entity_to_index.clear();
         self.index_to_entity.clear();
+        self.ticks.clear();
         self.ax_values.clear();
         self.ay_values.clear();
         self.az_values.clear();
     }
 
+    fn memory_report(&self) -> MemoryReport {
+        let capacities = [self.ax_values.capacity(), self.ay_values.capacity(), self.az_values.capacity()];
+        MemoryReport {
+            entity_capacity: capacities.into_iter().min().unwrap_or(0),
+            component_bytes: capacities.iter().sum::<usize>() * std::mem::size_of::<Real>(),
+            map_overhead_estimate: dense_index_bytes(&self.entity_to_index, self.index_to_entity.capacity())
+                + self.ticks.heap_bytes(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entity_to_index.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.ticks.shrink_to_fit();
+        self.ax_values.shrink_to_fit();
+        self.ay_values.shrink_to_fit();
+        self.az_values.shrink_to_fit();
+    }
+
     fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
-        Some(FieldArrays::Acceleration(
+        Some(FieldArrays::acceleration(
             &self.ax_values,
             &self.ay_values,
             &self.az_values,
@@ -1027,41 +1987,145 @@ impl ComponentStorage for AccelerationSoAStorage {
     }
 
     fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
-        Some(FieldArraysMut::Acceleration(
+        self.ticks.mark_all();
+        Some(FieldArraysMut::acceleration(
+            &mut self.ax_values,
+            &mut self.ay_values,
+            &mut self.az_values,
+        ))
+    }
+
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        self.ticks.mark_masked(written);
+        Some(FieldArraysMut::acceleration(
             &mut self.ax_values,
             &mut self.ay_values,
             &mut self.az_values,
         ))
     }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        Some(&self.index_to_entity)
+    }
 }
 
 /// True Structure-of-Arrays storage for Mass components
 pub struct MassSoAStorage {
-    entity_to_index: HashMap<Entity, usize>,
+    entity_to_index: EntityIndexMap,
     index_to_entity: Vec<Entity>,
-    values: Vec<f64>,
+    ticks: ChangeTicks,
+    values: Vec<Real>,
+    /// `Mass::inverse()` of each value, so force conversion multiplies
+    inverses: Vec<Real>,
+    /// Set when `values` were handed out mutably and `inverses` may be out of date
+    inverses_stale: bool,
 }
 
 impl MassSoAStorage {
+    /// Create a new empty Mass SoA storage
     pub fn new() -> Self {
         Self::with_capacity(0)
     }
 
+    /// Create a new Mass SoA storage with the given capacity
     pub fn with_capacity(capacity: usize) -> Self {
         MassSoAStorage {
-            entity_to_index: HashMap::with_capacity(capacity),
+            entity_to_index: EntityIndexMap::with_capacity(capacity),
             index_to_entity: Vec::with_capacity(capacity),
+            ticks: ChangeTicks::with_capacity(capacity),
             values: Vec::with_capacity(capacity),
+            inverses: Vec::with_capacity(capacity),
+            inverses_stale: false,
         }
     }
 
+    /// Get the number of components stored
     pub fn len(&self) -> usize {
         self.values.len()
     }
 
+    /// Check if the storage is empty
     pub fn is_empty(&self) -> bool {
         self.values.is_empty()
     }
+
+    /// Get the entities in dense-index order
+    ///
+    /// The entity at position `i` owns the field values at index `i`.
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get the dense index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Get the tick that modifications are currently stamped with
+    pub fn current_tick(&self) -> u64 {
+        self.ticks.current()
+    }
+
+    /// Move to the next tick and return it
+    pub fn advance_tick(&mut self) -> u64 {
+        self.ticks.advance()
+    }
+
+    /// Move to `tick`; panics if it is earlier than `current_tick()`
+    pub fn set_tick(&mut self, tick: u64) {
+        self.ticks.set(tick);
+    }
+
+    /// Get the tick at which an entity's component was last modified
+    pub fn last_changed(&self, entity: Entity) -> Option<u64> {
+        self.get_index(entity).map(|index| self.ticks.get(index))
+    }
+
+    /// Iterate over the entities modified after `tick`, in dense-index order
+    ///
+    /// Same rules as `PositionSoAStorage::changed_since()`.
+    pub fn changed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.ticks.changed_since(tick).map(|index| self.index_to_entity[index])
+    }
+
+    /// Get the cached inverse masses in dense-index order
+    ///
+    /// Returns None after `field_arrays_mut()`, which can change the values
+    /// behind the cache, until `refresh_inverse_masses()` is called.
+    pub fn inverse_masses(&self) -> Option<&[Real]> {
+        if self.inverses_stale {
+            None
+        } else {
+            Some(&self.inverses)
+        }
+    }
+
+    /// Recompute the cached inverse masses from the current values
+    pub fn refresh_inverse_masses(&mut self) {
+        for (inverse, &value) in self.inverses.iter_mut().zip(&self.values) {
+            *inverse = crate::ecs::components::Mass::new(value).inverse();
+        }
+        self.inverses_stale = false;
+    }
+
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Components are reconstructed from the field arrays on the fly, so
+    /// items are yielded by value. The order matches `dense_entities()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, crate::ecs::components::Mass)> + '_ {
+        self.index_to_entity
+            .iter()
+            .copied()
+            .zip(self.values.iter().map(|&value| crate::ecs::components::Mass::new(value)))
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// See `PositionSoAStorage::check_invariants()`.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_dense_invariants(&self.entity_to_index, &self.index_to_entity, &self.ticks, &[&self.values, &self.inverses])
+    }
 }
 
 impl Default for MassSoAStorage {
@@ -1076,11 +2140,21 @@ impl ComponentStorage for MassSoAStorage {
     fn insert(&mut self, entity: Entity, component: Self::Component) {
         if let Some(&index) = self.entity_to_index.get(&entity) {
             self.values[index] = component.value();
+            self.inverses[index] = component.inverse();
+            self.ticks.mark(index);
         } else {
+            // A stale generation of this ID still holds the sparse slot
+            #[cfg(feature = "sparse-set")]
+            if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+                self.remove(stale);
+            }
+
             let new_index = self.values.len();
             self.values.push(component.value());
+            self.inverses.push(component.inverse());
             self.entity_to_index.insert(entity, new_index);
             self.index_to_entity.push(entity);
+            self.ticks.push();
         }
     }
 
@@ -1091,6 +2165,7 @@ impl ComponentStorage for MassSoAStorage {
             let last_index = self.values.len() - 1;
             if index != last_index {
                 self.values.swap(index, last_index);
+                self.inverses.swap(index, last_index);
                 
                 let swapped_entity = self.index_to_entity[last_index];
                 *self.entity_to_index.get_mut(&swapped_entity)
@@ -1099,7 +2174,9 @@ impl ComponentStorage for MassSoAStorage {
             }
             
             self.values.pop();
+            self.inverses.pop();
             self.index_to_entity.pop();
+            self.ticks.swap_remove(index);
 
             Some(Self::Component::new(value))
         } else {
@@ -1122,15 +2199,47 @@ impl ComponentStorage for MassSoAStorage {
     fn clear(&mut self) {
         self.entity_to_index.clear();
         self.index_to_entity.clear();
+        self.ticks.clear();
         self.values.clear();
+        self.inverses.clear();
+        self.inverses_stale = false;
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        MemoryReport {
+            entity_capacity: self.values.capacity(),
+            component_bytes: (self.values.capacity() + self.inverses.capacity()) * std::mem::size_of::<Real>(),
+            map_overhead_estimate: dense_index_bytes(&self.entity_to_index, self.index_to_entity.capacity())
+                + self.ticks.heap_bytes(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entity_to_index.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.ticks.shrink_to_fit();
+        self.values.shrink_to_fit();
+        self.inverses.shrink_to_fit();
     }
 
     fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
-        Some(FieldArrays::Mass(&self.values))
+        Some(FieldArrays::mass(&self.values))
     }
 
     fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
-        Some(FieldArraysMut::Mass(&mut self.values))
+        self.ticks.mark_all();
+        self.inverses_stale = true;
+        Some(FieldArraysMut::mass(&mut self.values))
+    }
+
+    fn field_arrays_mut_masked(&mut self, written: &[bool]) -> Option<FieldArraysMut<'_, Self::Component>> {
+        self.ticks.mark_masked(written);
+        self.inverses_stale = true;
+        Some(FieldArraysMut::mass(&mut self.values))
+    }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        Some(&self.index_to_entity)
     }
 }
 
@@ -1316,6 +2425,326 @@ mod tests {
         assert_eq!(storage.len(), 0);
     }
 
+    #[test]
+    fn test_soa_storage_compact_after_removals() {
+        let mut storage = SoAStorage::<TestComponent>::new();
+
+        for i in 0..10000 {
+            storage.insert(Entity::new(i as u64, 0), TestComponent { x: i as f32, y: 0.0 });
+        }
+        let full_capacity = storage.capacity();
+        assert!(full_capacity >= 10000);
+
+        // Remove 9k entities; capacity is retained until compaction
+        for i in 0..9000 {
+            storage.remove(Entity::new(i as u64, 0));
+        }
+        assert_eq!(storage.len(), 1000);
+        assert_eq!(storage.capacity(), full_capacity);
+
+        storage.compact();
+        assert!(storage.capacity() < full_capacity / 5);
+        storage.check_invariants().unwrap();
+
+        for i in 9000..10000 {
+            let entity = Entity::new(i as u64, 0);
+            assert_eq!(storage.get(entity).unwrap().x, i as f32);
+        }
+        assert!(!storage.contains(Entity::new(0, 0)));
+    }
+
+    #[test]
+    fn test_soa_storage_auto_shrink() {
+        let mut storage = SoAStorage::<TestComponent>::new();
+        storage.set_auto_shrink_threshold(Some(0.25));
+
+        for i in 0..1024 {
+            storage.insert(Entity::new(i as u64, 0), TestComponent { x: i as f32, y: 0.0 });
+        }
+        let full_capacity = storage.capacity();
+
+        for i in 0..1000 {
+            storage.remove(Entity::new(i as u64, 0));
+            storage.check_invariants().unwrap();
+        }
+
+        // Shrinks happened automatically, without an explicit compact()
+        assert!(storage.capacity() < full_capacity / 4);
+        for i in 1000..1024 {
+            assert_eq!(storage.get(Entity::new(i as u64, 0)).unwrap().x, i as f32);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Auto-shrink threshold must be in (0, 1)")]
+    fn test_soa_storage_invalid_auto_shrink_threshold() {
+        let mut storage = SoAStorage::<TestComponent>::new();
+        storage.set_auto_shrink_threshold(Some(1.5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Auto-shrink threshold must be in (0, 1)")]
+    fn test_soa_storage_full_auto_shrink_threshold_rejected() {
+        let mut storage = SoAStorage::<TestComponent>::new();
+        storage.set_auto_shrink_threshold(Some(1.0));
+    }
+
+    /// Fill a storage, remove most entries, and check the reports track it
+    fn check_memory_report_lifecycle<S: ComponentStorage>(storage: &mut S, make: impl Fn(usize) -> S::Component) {
+        let empty = storage.memory_report();
+        for i in 0..5000 {
+            storage.insert(Entity::new(i as u64, 0), make(i));
+        }
+        let grown = storage.memory_report();
+        assert!(grown.entity_capacity >= 5000);
+        assert!(grown.component_bytes > empty.component_bytes);
+        assert!(grown.map_overhead_estimate > empty.map_overhead_estimate);
+
+        for i in 0..4900 {
+            storage.remove(Entity::new(i as u64, 0));
+        }
+
+        storage.shrink_to_fit();
+        let shrunk = storage.memory_report();
+        assert!(shrunk.entity_capacity >= 100);
+        assert!(shrunk.entity_capacity < grown.entity_capacity / 5);
+        assert!(shrunk.component_bytes < grown.component_bytes / 5);
+        assert!(shrunk.total_bytes() < grown.total_bytes());
+        assert!(storage.contains(Entity::new(4999, 0)));
+    }
+
+    #[test]
+    fn test_memory_report_and_shrink_to_fit() {
+        use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
+
+        check_memory_report_lifecycle(&mut HashMapStorage::<TestComponent>::new(), |i| TestComponent {
+            x: i as f32,
+            y: 0.0,
+        });
+        check_memory_report_lifecycle(&mut SoAStorage::<TestComponent>::new(), |i| TestComponent {
+            x: i as f32,
+            y: 0.0,
+        });
+        check_memory_report_lifecycle(&mut PositionSoAStorage::new(), |i| Position::new(i as Real, 0.0, 0.0));
+        check_memory_report_lifecycle(&mut VelocitySoAStorage::new(), |i| Velocity::new(i as Real, 0.0, 0.0));
+        check_memory_report_lifecycle(&mut AccelerationSoAStorage::new(), |i| {
+            Acceleration::new(i as Real, 0.0, 0.0)
+        });
+        check_memory_report_lifecycle(&mut MassSoAStorage::new(), |i| Mass::new(i as Real + 1.0));
+    }
+
+    #[test]
+    fn test_memory_report_aggregate_is_sum_of_parts() {
+        use crate::ecs::components::{Position, Mass};
+
+        let mut positions = PositionSoAStorage::with_capacity(100);
+        let mut masses = SoAStorage::<Mass>::with_capacity(50);
+        let mut tags = HashMapStorage::<TestComponent>::new();
+        for i in 0..40 {
+            let entity = Entity::new(i, 0);
+            positions.insert(entity, Position::zero());
+            masses.insert(entity, Mass::new(1.0));
+            tags.insert(entity, TestComponent { x: 0.0, y: 0.0 });
+        }
+
+        let parts = [positions.memory_report(), masses.memory_report(), tags.memory_report()];
+        let total: MemoryReport = parts.iter().copied().sum();
+        assert_eq!(total.entity_capacity, parts.iter().map(|p| p.entity_capacity).sum::<usize>());
+        assert_eq!(total.component_bytes, parts.iter().map(|p| p.component_bytes).sum::<usize>());
+        assert_eq!(
+            total.map_overhead_estimate,
+            parts.iter().map(|p| p.map_overhead_estimate).sum::<usize>()
+        );
+        assert_eq!(total.total_bytes(), parts.iter().map(|p| p.total_bytes()).sum::<usize>());
+
+        // Three Real fields per position, one Mass struct per mass
+        assert_eq!(parts[0].component_bytes, 3 * std::mem::size_of::<Real>() * parts[0].entity_capacity);
+        assert_eq!(parts[1].component_bytes, std::mem::size_of::<Mass>() * parts[1].entity_capacity);
+    }
+
+    #[test]
+    fn test_position_soa_memory_bytes_grows_linearly() {
+        use crate::ecs::components::Position;
+
+        let bytes_per_entity = |count: u64| {
+            let mut storage = PositionSoAStorage::new();
+            for i in 0..count {
+                storage.insert(Entity::new(i, 0), Position::zero());
+            }
+            storage.shrink_to_fit();
+            storage.memory_bytes() as f64 / count as f64
+        };
+
+        // Field arrays alone cost 3 Reals per entity; index maps add a
+        // bounded per-entity overhead whatever the count
+        let base = bytes_per_entity(1000);
+        assert!(base >= 3.0 * std::mem::size_of::<Real>() as f64);
+        for count in [2000, 4000, 8000] {
+            let ratio = bytes_per_entity(count) / base;
+            assert!(ratio > 0.5 && ratio < 2.0, "{} entities: {}x the bytes per entity of 1000", count, ratio);
+        }
+    }
+
+    fn check_capacity_covers_len<S: ComponentStorage>(
+        storage: &mut S,
+        make: impl Fn(usize) -> S::Component,
+        len: impl Fn(&S) -> usize,
+    ) {
+        assert!(storage.capacity() >= len(storage));
+        for i in 0..300 {
+            storage.insert(Entity::new(i as u64, 0), make(i));
+            assert!(storage.capacity() >= len(storage));
+        }
+        assert_eq!(len(storage), 300);
+        for i in (0..300).step_by(2) {
+            storage.remove(Entity::new(i as u64, 0));
+            assert!(storage.capacity() >= len(storage));
+        }
+        storage.shrink_to_fit();
+        assert!(storage.capacity() >= len(storage));
+        assert_eq!(len(storage), 150);
+        assert!(storage.memory_bytes() > 0);
+    }
+
+    #[test]
+    fn test_capacity_at_least_len() {
+        use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
+
+        let make = |i: usize| TestComponent { x: i as f32, y: 0.0 };
+        check_capacity_covers_len(&mut HashMapStorage::<TestComponent>::new(), make, |s| s.len());
+        check_capacity_covers_len(&mut SoAStorage::<TestComponent>::new(), make, |s| s.len());
+        check_capacity_covers_len(&mut PositionSoAStorage::new(), |i| Position::new(i as Real, 0.0, 0.0), |s| s.len());
+        check_capacity_covers_len(&mut VelocitySoAStorage::new(), |i| Velocity::new(i as Real, 0.0, 0.0), |s| s.len());
+        check_capacity_covers_len(
+            &mut AccelerationSoAStorage::new(),
+            |i| Acceleration::new(i as Real, 0.0, 0.0),
+            |s| s.len(),
+        );
+        check_capacity_covers_len(&mut MassSoAStorage::new(), |i| Mass::new(i as Real + 1.0), |s| s.len());
+    }
+
+    #[test]
+    fn test_load_factor_tracks_occupancy() {
+        let mut storage = HashMapStorage::<TestComponent>::new();
+        assert_eq!(storage.load_factor(), 0.0);
+
+        for i in 0..1000 {
+            storage.insert(Entity::new(i, 0), TestComponent { x: 0.0, y: 0.0 });
+        }
+        let full = storage.load_factor();
+        assert!(full > 0.0 && full <= 1.0);
+
+        for i in 0..900 {
+            storage.remove(Entity::new(i, 0));
+        }
+        assert!(storage.load_factor() < full / 5.0);
+        storage.shrink_to_fit();
+        assert!(storage.load_factor() > 0.4);
+
+        let mut dense = SoAStorage::<TestComponent>::with_capacity(100);
+        dense.insert(Entity::new(1, 0), TestComponent { x: 0.0, y: 0.0 });
+        assert!(dense.load_factor() > 0.0 && dense.load_factor() < 0.05);
+    }
+
+    fn tag(x: f32) -> TestComponent {
+        TestComponent { x, y: 0.0 }
+    }
+
+    #[test]
+    fn test_hash_map_storage_retain() {
+        for mut storage in [HashMapStorage::new(), HashMapStorage::with_insertion_order()] {
+            for i in 0..10 {
+                storage.insert(Entity::new(i, 0), tag(i as f32));
+            }
+
+            storage.retain(|entity, component| {
+                component.y = 1.0;
+                entity.id().raw() % 3 == 0
+            });
+
+            assert_eq!(storage.len(), 4);
+            for i in 0..10 {
+                let entity = Entity::new(i, 0);
+                assert_eq!(storage.contains(entity), i % 3 == 0);
+                if let Some(component) = storage.get(entity) {
+                    assert_eq!(component.y, 1.0, "Kept components keep their modifications");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_map_storage_drain_leaves_storage_empty() {
+        for mut storage in [HashMapStorage::new(), HashMapStorage::with_insertion_order()] {
+            for i in 0..10 {
+                storage.insert(Entity::new(i, 0), tag(i as f32));
+            }
+            let mut drained: Vec<(Entity, TestComponent)> = storage.drain().collect();
+            drained.sort_by_key(|(entity, _)| entity.id().raw());
+            assert_eq!(drained.len(), 10);
+            assert!(drained.iter().all(|(entity, component)| component.x == entity.id().raw() as f32));
+            assert!(storage.is_empty());
+            assert!(storage.get(Entity::new(3, 0)).is_none());
+
+            // Dropping the iterator early still empties the storage
+            storage.insert(Entity::new(1, 0), tag(1.0));
+            storage.insert(Entity::new(2, 0), tag(2.0));
+            assert!(storage.drain().next().is_some());
+            assert!(storage.is_empty());
+            assert_eq!(storage.iter().count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_hash_map_storage_iter_consistent_with_contains() {
+        let mut storage = HashMapStorage::new();
+        for i in 0..50 {
+            storage.insert(Entity::new(i, 0), tag(i as f32));
+        }
+        for i in (0..50).step_by(2) {
+            storage.remove(Entity::new(i, 0));
+        }
+
+        assert_eq!(storage.iter().count(), storage.len());
+        for (entity, component) in storage.iter() {
+            assert!(storage.contains(entity));
+            assert_eq!(storage.get(entity), Some(component));
+        }
+
+        for (_, component) in storage.iter_mut() {
+            component.y = component.x * 2.0;
+        }
+        assert_eq!(storage.get(Entity::new(7, 0)).unwrap().y, 14.0);
+    }
+
+    #[test]
+    fn test_insertion_ordered_storage_iterates_in_insertion_order() {
+        let mut storage = HashMapStorage::with_insertion_order();
+        assert!(storage.is_insertion_ordered());
+        assert!(!HashMapStorage::<TestComponent>::new().is_insertion_ordered());
+
+        let ids = [42, 7, 19, 3, 88, 1, 56];
+        for &id in &ids {
+            storage.insert(Entity::new(id, 0), tag(id as f32));
+        }
+        // Re-inserting keeps the original place; removing and re-inserting
+        // moves the entity to the end
+        storage.insert(Entity::new(19, 0), tag(-1.0));
+        storage.remove(Entity::new(7, 0));
+        storage.insert(Entity::new(7, 0), tag(7.0));
+
+        let order = |storage: &HashMapStorage<TestComponent>| -> Vec<u64> {
+            storage.iter().map(|(entity, _)| entity.id().raw()).collect()
+        };
+        assert_eq!(order(&storage), vec![42, 19, 3, 88, 1, 56, 7]);
+
+        storage.retain(|entity, _| entity.id().raw() != 88);
+        let visited: Vec<u64> = storage.iter_mut().map(|(entity, _)| entity.id().raw()).collect();
+        assert_eq!(visited, vec![42, 19, 3, 1, 56, 7]);
+        let drained: Vec<f32> = storage.drain().map(|(_, component)| component.x).collect();
+        assert_eq!(drained, vec![42.0, -1.0, 3.0, 1.0, 56.0, 7.0]);
+    }
+
     #[test]
     fn test_soa_storage_components_slice() {
         let mut storage = SoAStorage::<TestComponent>::new();
@@ -1356,6 +2785,56 @@ mod tests {
     // Test with actual physics components
     use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
 
+    #[test]
+    fn test_soa_storage_iter_matches_entities_after_swap_remove() {
+        let mut storage = SoAStorage::<TestComponent>::new();
+        for i in 0..5 {
+            storage.insert(Entity::new(i, 0), TestComponent { x: i as f32, y: 0.0 });
+        }
+
+        // Removing from the front swaps the last element into slot 0
+        storage.remove(Entity::new(0, 0));
+
+        let entities: Vec<Entity> = storage.entities().collect();
+        let pairs: Vec<Entity> = storage.iter().map(|(entity, _)| entity).collect();
+        assert_eq!(pairs, entities);
+        assert_eq!(entities[0], Entity::new(4, 0));
+        for (entity, component) in storage.iter() {
+            assert_eq!(component.x, entity.id().raw() as f32);
+        }
+
+        for (entity, component) in storage.iter_mut() {
+            component.y = entity.id().raw() as f32 * 2.0;
+        }
+        for entity in entities {
+            assert_eq!(storage.get(entity).unwrap().y, entity.id().raw() as f32 * 2.0);
+        }
+    }
+
+    #[test]
+    fn test_true_soa_iter_reconstructs_components() {
+        let mut positions = PositionSoAStorage::new();
+        let mut masses = MassSoAStorage::new();
+        for i in 0..4 {
+            let entity = Entity::new(i, 0);
+            positions.insert(entity, Position::new(i as Real, -(i as Real), 0.5));
+            masses.insert(entity, Mass::new(i as Real + 1.0));
+        }
+        positions.remove(Entity::new(1, 0));
+        masses.remove(Entity::new(1, 0));
+
+        let order: Vec<Entity> = positions.iter().map(|(entity, _)| entity).collect();
+        assert_eq!(order, positions.dense_entities());
+        for (entity, pos) in positions.iter() {
+            assert_eq!(pos.x(), entity.id().raw() as Real);
+            assert_eq!(pos.y(), -(entity.id().raw() as Real));
+        }
+        for (entity, mass) in masses.iter() {
+            assert_eq!(mass.value(), entity.id().raw() as Real + 1.0);
+        }
+        assert_eq!(masses.iter().count(), 3);
+    }
+
     #[test]
     fn test_soa_storage_with_position() {
         let mut storage = SoAStorage::<Position>::new();
@@ -1405,7 +2884,7 @@ mod tests {
         // Create 1000 entities
         for i in 0..1000 {
             let entity = Entity::new(i, 0);
-            positions.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+            positions.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
             velocities.insert(entity, Velocity::new(1.0, 2.0, 3.0));
         }
         
@@ -1523,7 +3002,7 @@ mod tests {
         // Insert many entities
         for i in 0..100 {
             let entity = Entity::new(i, 0);
-            storage.insert(entity, Position::new(i as f64, i as f64 * 2.0, i as f64 * 3.0));
+            storage.insert(entity, Position::new(i as Real, i as Real * 2.0, i as Real * 3.0));
         }
         
         assert_eq!(storage.len(), 100);
@@ -1612,6 +3091,69 @@ mod tests {
         assert_eq!(values[0], 10.0);
     }
 
+    #[test]
+    fn test_mass_soa_storage_inverse_masses() {
+        let mut storage = MassSoAStorage::new();
+        let (e1, e2, e3) = (Entity::new(1, 0), Entity::new(2, 0), Entity::new(3, 0));
+        storage.insert(e1, Mass::new(4.0));
+        storage.insert(e2, Mass::immovable());
+        storage.insert(e3, Mass::new(0.5));
+        assert_eq!(storage.inverse_masses(), Some(&[0.25, 0.0, 2.0][..]));
+
+        // Overwrites and swap-removes keep the cache aligned with the values
+        storage.insert(e2, Mass::new(8.0));
+        storage.remove(e1);
+        assert_eq!(storage.inverse_masses(), Some(&[2.0, 0.125][..]));
+
+        // Writes through the field arrays invalidate it until refreshed
+        storage.field_arrays_mut().unwrap().as_mass_array_mut()[0] = 2.0;
+        assert_eq!(storage.inverse_masses(), None);
+        storage.refresh_inverse_masses();
+        assert_eq!(storage.inverse_masses(), Some(&[0.5, 0.125][..]));
+    }
+
+    #[test]
+    fn test_soa_storages_index_alignment() {
+        let mut positions = PositionSoAStorage::new();
+        let mut velocities = VelocitySoAStorage::new();
+        let mut accelerations = AccelerationSoAStorage::new();
+        let mut masses = MassSoAStorage::new();
+
+        // Identical insert sequences produce identical dense orderings
+        for i in 0..5 {
+            let entity = Entity::new(i, 0);
+            positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
+            velocities.insert(entity, Velocity::zero());
+            accelerations.insert(entity, Acceleration::zero());
+            masses.insert(entity, Mass::new(1.0));
+        }
+        assert_eq!(positions.dense_entities(), velocities.dense_entities());
+        assert_eq!(positions.dense_entities(), accelerations.dense_entities());
+        assert_eq!(positions.dense_entities(), masses.dense_entities());
+
+        // Identical removals keep storages aligned
+        let removed = Entity::new(1, 0);
+        positions.remove(removed);
+        velocities.remove(removed);
+        accelerations.remove(removed);
+        masses.remove(removed);
+        assert_eq!(positions.dense_entities(), masses.dense_entities());
+        assert_eq!(positions.get_index(Entity::new(4, 0)), Some(1));
+        assert_eq!(masses.get_index(Entity::new(4, 0)), Some(1));
+
+        // Diverging removal breaks alignment, but get_index still resolves
+        masses.remove(Entity::new(0, 0));
+        masses.insert(Entity::new(0, 0), Mass::new(1.0));
+        assert_ne!(positions.dense_entities(), masses.dense_entities());
+        for &entity in positions.dense_entities() {
+            let pos_index = positions.get_index(entity).unwrap();
+            let arrays = positions.field_arrays().unwrap();
+            let (x, _, _) = arrays.as_position_arrays();
+            assert_eq!(x[pos_index], entity.id().raw() as Real);
+            assert!(masses.get_index(entity).is_some());
+        }
+    }
+
     #[test]
     fn test_soa_storage_swap_remove() {
         let mut storage = PositionSoAStorage::new();
@@ -1661,7 +3203,7 @@ mod tests {
         let mut storage = VelocitySoAStorage::new();
         
         for i in 0..10 {
-            storage.insert(Entity::new(i, 0), Velocity::new(i as f64, 0.0, 0.0));
+            storage.insert(Entity::new(i, 0), Velocity::new(i as Real, 0.0, 0.0));
         }
         
         assert_eq!(storage.len(), 10);
@@ -1688,13 +3230,33 @@ mod tests {
         assert!(storage.contains(e1_gen1));
     }
 
+    #[cfg(feature = "sparse-set")]
+    #[test]
+    fn test_true_soa_sparse_index_evicts_stale_generation() {
+        let mut storage = PositionSoAStorage::new();
+        let other = Entity::new(0, 0);
+        let e1_gen0 = Entity::new(1, 0);
+        let e1_gen1 = Entity::new(1, 1);
+
+        storage.insert(e1_gen0, Position::new(1.0, 0.0, 0.0));
+        storage.insert(other, Position::new(2.0, 0.0, 0.0));
+        storage.insert(e1_gen1, Position::new(3.0, 0.0, 0.0));
+
+        assert_eq!(storage.len(), 2);
+        assert!(!storage.contains(e1_gen0));
+        let arrays = storage.field_arrays().unwrap();
+        let (x, _, _) = arrays.as_position_arrays();
+        assert_eq!(x[storage.get_index(other).unwrap()], 2.0);
+        assert_eq!(x[storage.get_index(e1_gen1).unwrap()], 3.0);
+    }
+
     #[test]
     fn test_soa_storage_large_scale() {
         let mut storage = PositionSoAStorage::with_capacity(1000);
         
         // Insert 1000 entities
         for i in 0..1000 {
-            storage.insert(Entity::new(i, 0), Position::new(i as f64, 0.0, 0.0));
+            storage.insert(Entity::new(i, 0), Position::new(i as Real, 0.0, 0.0));
         }
         
         assert_eq!(storage.len(), 1000);
@@ -1742,5 +3304,78 @@ mod tests {
         assert_eq!(x1.len(), 2);
         assert_eq!(x2.len(), 2);
     }
-}
+    #[test]
+    fn test_field_arrays_mut_writes_survive_structural_changes() {
+        // The compile_fail examples on field_arrays_mut() cover overlapping
+        // use; once the borrow ends, inserts and swap-removes must keep each
+        // entity paired with the values written through the arrays
+        let mut storage = PositionSoAStorage::new();
+        for i in 1..=4 {
+            storage.insert(Entity::new(i, 0), Position::zero());
+        }
+
+        {
+            let mut arrays = storage.field_arrays_mut().unwrap();
+            let (x, y, _) = arrays.as_position_arrays_mut();
+            for i in 0..x.len() {
+                x[i] = i as Real;
+                y[i] = -(i as Real);
+            }
+        }
+        let written: Vec<(Entity, Real)> =
+            storage.dense_entities().iter().enumerate().map(|(i, &entity)| (entity, i as Real)).collect();
+
+        storage.remove(written[0].0);
+        storage.insert(Entity::new(5, 0), Position::new(9.0, 9.0, 9.0));
+        let arrays = storage.field_arrays().unwrap();
+        let (x, y, _) = arrays.as_position_arrays();
+        for &(entity, value) in &written[1..] {
+            let index = storage.get_index(entity).unwrap();
+            assert_eq!((x[index], y[index]), (value, -value));
+        }
+        assert_eq!(x[storage.get_index(Entity::new(5, 0)).unwrap()], 9.0);
+        assert_eq!(storage.get_index(written[0].0), None);
+    }
+
+    #[test]
+    fn test_soa_change_ticks() {
+        let mut storage = PositionSoAStorage::new();
+        let entities: Vec<Entity> = (1..=4).map(|i| Entity::new(i, 0)).collect();
+        for &entity in &entities {
+            storage.insert(entity, Position::zero());
+        }
+        assert_eq!(storage.current_tick(), 0);
+        assert_eq!(storage.last_changed(entities[0]), Some(0));
+
+        // Reading marks nothing, in-place inserts mark one entry
+        let seen = storage.current_tick();
+        assert_eq!(storage.advance_tick(), 1);
+        let _ = storage.field_arrays().unwrap();
+        assert_eq!(storage.changed_since(seen).count(), 0);
+        storage.insert(entities[2], Position::new(1.0, 0.0, 0.0));
+        assert_eq!(storage.changed_since(seen).collect::<Vec<_>>(), vec![entities[2]]);
 
+        // Masked mutable access marks only the declared indices
+        storage.set_tick(5);
+        let written: Vec<bool> = (0..storage.len()).map(|i| i % 2 == 1).collect();
+        storage.field_arrays_mut_masked(&written).unwrap();
+        assert_eq!(storage.changed_since(1).collect::<Vec<_>>(), vec![entities[1], entities[3]]);
+
+        // Removal carries the swapped entity's tick to its new index
+        storage.remove(entities[1]);
+        assert_eq!(storage.last_changed(entities[3]), Some(5));
+        assert_eq!(storage.changed_since(1).collect::<Vec<_>>(), vec![entities[3]]);
+
+        storage.advance_tick();
+        storage.field_arrays_mut().unwrap();
+        assert_eq!(storage.changed_since(5).count(), storage.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "Change tick must not decrease")]
+    fn test_soa_change_tick_is_monotonic() {
+        let mut storage = MassSoAStorage::new();
+        storage.set_tick(3);
+        storage.set_tick(2);
+    }
+}
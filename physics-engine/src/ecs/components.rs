@@ -17,8 +17,66 @@
 //! Newtonian mechanics: position, velocity, acceleration, and mass.
 //! Components use SIMD-friendly representations with double-precision
 //! floats for accuracy in physics simulations.
+//!
+//! # Validation
+//!
+//! `try_new` constructors reject non-finite values (and negative mass) with a
+//! `ComponentError`. The plain vector constructors and setters accept any value
+//! for speed; with the `strict-validation` feature enabled they additionally
+//! `debug_assert!` that values are finite, catching NaN at the point it is
+//! introduced in debug builds. Release builds behave the same either way.
 
+use crate::real::Real;
 use crate::ecs::Component;
+use crate::math::Tolerances;
+use std::fmt;
+
+/// Error returned by validated component constructors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentError {
+    /// A value was NaN or infinite
+    NonFinite {
+        /// Name of the component being constructed
+        component: &'static str,
+    },
+    /// A mass value was negative
+    NegativeMass(Real),
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentError::NonFinite { component } => {
+                write!(f, "{} values must be finite", component)
+            }
+            ComponentError::NegativeMass(value) => {
+                write!(f, "Mass must be non-negative, got {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ComponentError {}
+
+/// Check that all values are finite, for `try_new` constructors
+fn check_finite(component: &'static str, values: &[Real]) -> Result<(), ComponentError> {
+    if values.iter().all(|v| v.is_finite()) {
+        Ok(())
+    } else {
+        Err(ComponentError::NonFinite { component })
+    }
+}
+
+/// Debug-only finiteness check applied by constructors and setters in strict mode
+#[inline(always)]
+fn strict_check(_component: &'static str, _values: &[Real]) {
+    #[cfg(feature = "strict-validation")]
+    debug_assert!(
+        _values.iter().all(|v| v.is_finite()),
+        "{} values must be finite (strict validation)",
+        _component
+    );
+}
 
 /// 3D position component with double-precision coordinates
 ///
@@ -37,49 +95,59 @@ use crate::ecs::Component;
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
-    x: f64,
-    y: f64,
-    z: f64,
+    x: Real,
+    y: Real,
+    z: Real,
 }
 
 impl Position {
     /// Create a new position with the given coordinates
-    pub fn new(x: f64, y: f64, z: f64) -> Self {
+    pub fn new(x: Real, y: Real, z: Real) -> Self {
+        strict_check("Position", &[x, y, z]);
         Position { x, y, z }
     }
 
+    /// Create a new position, rejecting NaN or infinite coordinates
+    pub fn try_new(x: Real, y: Real, z: Real) -> Result<Self, ComponentError> {
+        check_finite("Position", &[x, y, z])?;
+        Ok(Position { x, y, z })
+    }
+
     /// Create a position at the origin (0, 0, 0)
     pub fn zero() -> Self {
         Position::new(0.0, 0.0, 0.0)
     }
 
     /// Get the x coordinate
-    pub fn x(&self) -> f64 {
+    pub fn x(&self) -> Real {
         self.x
     }
 
     /// Get the y coordinate
-    pub fn y(&self) -> f64 {
+    pub fn y(&self) -> Real {
         self.y
     }
 
     /// Get the z coordinate
-    pub fn z(&self) -> f64 {
+    pub fn z(&self) -> Real {
         self.z
     }
 
     /// Set the x coordinate
-    pub fn set_x(&mut self, x: f64) {
+    pub fn set_x(&mut self, x: Real) {
+        strict_check("Position", &[x]);
         self.x = x;
     }
 
     /// Set the y coordinate
-    pub fn set_y(&mut self, y: f64) {
+    pub fn set_y(&mut self, y: Real) {
+        strict_check("Position", &[y]);
         self.y = y;
     }
 
     /// Set the z coordinate
-    pub fn set_z(&mut self, z: f64) {
+    pub fn set_z(&mut self, z: Real) {
+        strict_check("Position", &[z]);
         self.z = z;
     }
 
@@ -88,13 +156,20 @@ impl Position {
         self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
     }
 
+    /// Check the position against explicit tolerances
+    ///
+    /// With `Tolerances::default()` this is the same as `is_valid()`.
+    pub fn is_valid_with(&self, tolerances: &Tolerances) -> bool {
+        tolerances.position_is_valid(self)
+    }
+
     /// Get the position as an array
-    pub fn as_array(&self) -> [f64; 3] {
+    pub fn as_array(&self) -> [Real; 3] {
         [self.x, self.y, self.z]
     }
 
     /// Create a position from an array
-    pub fn from_array(arr: [f64; 3]) -> Self {
+    pub fn from_array(arr: [Real; 3]) -> Self {
         Position::new(arr[0], arr[1], arr[2])
     }
 }
@@ -122,49 +197,59 @@ impl Default for Position {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Velocity {
-    dx: f64,
-    dy: f64,
-    dz: f64,
+    dx: Real,
+    dy: Real,
+    dz: Real,
 }
 
 impl Velocity {
     /// Create a new velocity with the given components
-    pub fn new(dx: f64, dy: f64, dz: f64) -> Self {
+    pub fn new(dx: Real, dy: Real, dz: Real) -> Self {
+        strict_check("Velocity", &[dx, dy, dz]);
         Velocity { dx, dy, dz }
     }
 
+    /// Create a new velocity, rejecting NaN or infinite components
+    pub fn try_new(dx: Real, dy: Real, dz: Real) -> Result<Self, ComponentError> {
+        check_finite("Velocity", &[dx, dy, dz])?;
+        Ok(Velocity { dx, dy, dz })
+    }
+
     /// Create a zero velocity (at rest)
     pub fn zero() -> Self {
         Velocity::new(0.0, 0.0, 0.0)
     }
 
     /// Get the x component
-    pub fn dx(&self) -> f64 {
+    pub fn dx(&self) -> Real {
         self.dx
     }
 
     /// Get the y component
-    pub fn dy(&self) -> f64 {
+    pub fn dy(&self) -> Real {
         self.dy
     }
 
     /// Get the z component
-    pub fn dz(&self) -> f64 {
+    pub fn dz(&self) -> Real {
         self.dz
     }
 
     /// Set the x component
-    pub fn set_dx(&mut self, dx: f64) {
+    pub fn set_dx(&mut self, dx: Real) {
+        strict_check("Velocity", &[dx]);
         self.dx = dx;
     }
 
     /// Set the y component
-    pub fn set_dy(&mut self, dy: f64) {
+    pub fn set_dy(&mut self, dy: Real) {
+        strict_check("Velocity", &[dy]);
         self.dy = dy;
     }
 
     /// Set the z component
-    pub fn set_dz(&mut self, dz: f64) {
+    pub fn set_dz(&mut self, dz: Real) {
+        strict_check("Velocity", &[dz]);
         self.dz = dz;
     }
 
@@ -174,17 +259,17 @@ impl Velocity {
     }
 
     /// Get the velocity as an array
-    pub fn as_array(&self) -> [f64; 3] {
+    pub fn as_array(&self) -> [Real; 3] {
         [self.dx, self.dy, self.dz]
     }
 
     /// Create a velocity from an array
-    pub fn from_array(arr: [f64; 3]) -> Self {
+    pub fn from_array(arr: [Real; 3]) -> Self {
         Velocity::new(arr[0], arr[1], arr[2])
     }
 
     /// Calculate the magnitude (speed) of the velocity vector
-    pub fn magnitude(&self) -> f64 {
+    pub fn magnitude(&self) -> Real {
         (self.dx * self.dx + self.dy * self.dy + self.dz * self.dz).sqrt()
     }
 }
@@ -212,49 +297,59 @@ impl Default for Velocity {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Acceleration {
-    ax: f64,
-    ay: f64,
-    az: f64,
+    ax: Real,
+    ay: Real,
+    az: Real,
 }
 
 impl Acceleration {
     /// Create a new acceleration with the given components
-    pub fn new(ax: f64, ay: f64, az: f64) -> Self {
+    pub fn new(ax: Real, ay: Real, az: Real) -> Self {
+        strict_check("Acceleration", &[ax, ay, az]);
         Acceleration { ax, ay, az }
     }
 
+    /// Create a new acceleration, rejecting NaN or infinite components
+    pub fn try_new(ax: Real, ay: Real, az: Real) -> Result<Self, ComponentError> {
+        check_finite("Acceleration", &[ax, ay, az])?;
+        Ok(Acceleration { ax, ay, az })
+    }
+
     /// Create a zero acceleration
     pub fn zero() -> Self {
         Acceleration::new(0.0, 0.0, 0.0)
     }
 
     /// Get the x component
-    pub fn ax(&self) -> f64 {
+    pub fn ax(&self) -> Real {
         self.ax
     }
 
     /// Get the y component
-    pub fn ay(&self) -> f64 {
+    pub fn ay(&self) -> Real {
         self.ay
     }
 
     /// Get the z component
-    pub fn az(&self) -> f64 {
+    pub fn az(&self) -> Real {
         self.az
     }
 
     /// Set the x component
-    pub fn set_ax(&mut self, ax: f64) {
+    pub fn set_ax(&mut self, ax: Real) {
+        strict_check("Acceleration", &[ax]);
         self.ax = ax;
     }
 
     /// Set the y component
-    pub fn set_ay(&mut self, ay: f64) {
+    pub fn set_ay(&mut self, ay: Real) {
+        strict_check("Acceleration", &[ay]);
         self.ay = ay;
     }
 
     /// Set the z component
-    pub fn set_az(&mut self, az: f64) {
+    pub fn set_az(&mut self, az: Real) {
+        strict_check("Acceleration", &[az]);
         self.az = az;
     }
 
@@ -264,12 +359,12 @@ impl Acceleration {
     }
 
     /// Get the acceleration as an array
-    pub fn as_array(&self) -> [f64; 3] {
+    pub fn as_array(&self) -> [Real; 3] {
         [self.ax, self.ay, self.az]
     }
 
     /// Create an acceleration from an array
-    pub fn from_array(arr: [f64; 3]) -> Self {
+    pub fn from_array(arr: [Real; 3]) -> Self {
         Acceleration::new(arr[0], arr[1], arr[2])
     }
 }
@@ -288,6 +383,9 @@ impl Default for Acceleration {
 /// for zero or near-zero mass values to prevent division-by-zero errors, treating
 /// such entities as immovable bodies.
 ///
+/// The inverse mass is computed once whenever the value is set, so hot loops
+/// multiply by `inverse()` instead of dividing per entity per stage.
+///
 /// # Examples
 ///
 /// ```
@@ -302,12 +400,20 @@ impl Default for Acceleration {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Mass {
-    value: f64,
+    value: Real,
+    /// Cached 1/value, or 0.0 for immovable bodies
+    inverse: Real,
 }
 
 impl Mass {
     /// Threshold below which mass is considered effectively zero (immovable)
-    pub const IMMOVABLE_THRESHOLD: f64 = 1e-10;
+    pub const IMMOVABLE_THRESHOLD: Real = 1e-10;
+
+    /// Build a mass from an already validated value, caching its inverse
+    fn from_valid(value: Real) -> Self {
+        let inverse = if value < Self::IMMOVABLE_THRESHOLD { 0.0 } else { 1.0 / value };
+        Mass { value, inverse }
+    }
 
     /// Create a new mass with the given value in kilograms
     ///
@@ -316,29 +422,29 @@ impl Mass {
     /// Panics if the mass is negative or NaN. This is appropriate for programming
     /// errors where invalid data should not be constructed. For fallible construction,
     /// use `try_new`.
-    pub fn new(value: f64) -> Self {
+    pub fn new(value: Real) -> Self {
         assert!(value >= 0.0 && value.is_finite(), "Mass must be non-negative and finite");
-        Mass { value }
+        Mass::from_valid(value)
     }
 
     /// Try to create a new mass with the given value in kilograms
     ///
-    /// Returns `None` if the value is negative or NaN.
-    pub fn try_new(value: f64) -> Option<Self> {
-        if value >= 0.0 && value.is_finite() {
-            Some(Mass { value })
-        } else {
-            None
+    /// Returns an error if the value is NaN, infinite, or negative.
+    pub fn try_new(value: Real) -> Result<Self, ComponentError> {
+        check_finite("Mass", &[value])?;
+        if value < 0.0 {
+            return Err(ComponentError::NegativeMass(value));
         }
+        Ok(Mass::from_valid(value))
     }
 
     /// Create an immovable mass (treated as infinite mass)
     pub fn immovable() -> Self {
-        Mass { value: 0.0 }
+        Mass::from_valid(0.0)
     }
 
     /// Get the mass value
-    pub fn value(&self) -> f64 {
+    pub fn value(&self) -> Real {
         self.value
     }
 
@@ -347,21 +453,19 @@ impl Mass {
     /// # Panics
     ///
     /// Panics if the mass is negative or NaN. For fallible setting, use `try_set_value`.
-    pub fn set_value(&mut self, value: f64) {
+    pub fn set_value(&mut self, value: Real) {
         assert!(value >= 0.0 && value.is_finite(), "Mass must be non-negative and finite");
-        self.value = value;
+        *self = Mass::from_valid(value);
     }
 
     /// Try to set the mass value
     ///
-    /// Returns `Ok(())` on success, or `Err(())` if the value is negative or NaN.
-    pub fn try_set_value(&mut self, value: f64) -> Result<(), ()> {
-        if value >= 0.0 && value.is_finite() {
-            self.value = value;
-            Ok(())
-        } else {
-            Err(())
-        }
+    /// Returns an error if the value is NaN, infinite, or negative, leaving
+    /// the mass unchanged. On success the cached inverse is refreshed, so a
+    /// value dropping below `IMMOVABLE_THRESHOLD` makes the body immovable.
+    pub fn try_set_value(&mut self, value: Real) -> Result<(), ComponentError> {
+        *self = Mass::try_new(value)?;
+        Ok(())
     }
 
     /// Check if the mass is valid (non-negative and finite)
@@ -376,13 +480,10 @@ impl Mass {
 
     /// Get the inverse mass (1/m) for use in calculations
     ///
-    /// Returns 0.0 for immovable bodies to prevent division by zero.
-    pub fn inverse(&self) -> f64 {
-        if self.is_immovable() {
-            0.0
-        } else {
-            1.0 / self.value
-        }
+    /// Returns 0.0 for immovable bodies to prevent division by zero. The value
+    /// is cached at construction, so this is a plain field read.
+    pub fn inverse(&self) -> Real {
+        self.inverse
     }
 }
 
@@ -394,10 +495,217 @@ impl Default for Mass {
     }
 }
 
+/// Radius component describing the physical size of a body
+///
+/// Represents the radius of an entity in meters. Used by systems that need a
+/// length scale per body, such as per-body gravitational softening.
+///
+/// # Examples
+///
+/// ```
+/// use physics_engine::ecs::components::Radius;
+///
+/// let radius = Radius::new(6.371e6); // Earth
+/// assert_eq!(radius.value(), 6.371e6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radius {
+    value: Real,
+}
+
+impl Radius {
+    /// Create a new radius with the given value in meters
+    ///
+    /// # Panics
+    ///
+    /// Panics if the radius is negative or not finite.
+    pub fn new(value: Real) -> Self {
+        assert!(value >= 0.0 && value.is_finite(), "Radius must be non-negative and finite");
+        Radius { value }
+    }
+
+    /// Get the radius value
+    pub fn value(&self) -> Real {
+        self.value
+    }
+}
+
+impl Component for Radius {}
+
+/// Angular velocity component in radians per second
+///
+/// The vector points along the spin axis with magnitude equal to the spin
+/// rate, following the right-hand rule.
+///
+/// # Examples
+///
+/// ```
+/// use physics_engine::ecs::components::AngularVelocity;
+///
+/// let spin = AngularVelocity::new(0.0, 0.0, 2.0); // Counter-clockwise about z
+/// assert_eq!(spin.as_array(), [0.0, 0.0, 2.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AngularVelocity {
+    wx: Real,
+    wy: Real,
+    wz: Real,
+}
+
+impl AngularVelocity {
+    /// Create a new angular velocity with the given components
+    pub fn new(wx: Real, wy: Real, wz: Real) -> Self {
+        strict_check("AngularVelocity", &[wx, wy, wz]);
+        AngularVelocity { wx, wy, wz }
+    }
+
+    /// Create a new angular velocity, rejecting NaN or infinite components
+    pub fn try_new(wx: Real, wy: Real, wz: Real) -> Result<Self, ComponentError> {
+        check_finite("AngularVelocity", &[wx, wy, wz])?;
+        Ok(AngularVelocity { wx, wy, wz })
+    }
+
+    /// Create a zero angular velocity (not spinning)
+    pub fn zero() -> Self {
+        AngularVelocity::new(0.0, 0.0, 0.0)
+    }
+
+    /// Get the x component
+    pub fn wx(&self) -> Real {
+        self.wx
+    }
+
+    /// Get the y component
+    pub fn wy(&self) -> Real {
+        self.wy
+    }
+
+    /// Get the z component
+    pub fn wz(&self) -> Real {
+        self.wz
+    }
+
+    /// Set the x component
+    pub fn set_wx(&mut self, wx: Real) {
+        strict_check("AngularVelocity", &[wx]);
+        self.wx = wx;
+    }
+
+    /// Set the y component
+    pub fn set_wy(&mut self, wy: Real) {
+        strict_check("AngularVelocity", &[wy]);
+        self.wy = wy;
+    }
+
+    /// Set the z component
+    pub fn set_wz(&mut self, wz: Real) {
+        strict_check("AngularVelocity", &[wz]);
+        self.wz = wz;
+    }
+
+    /// Check if all components are finite (not NaN or infinite)
+    pub fn is_valid(&self) -> bool {
+        self.wx.is_finite() && self.wy.is_finite() && self.wz.is_finite()
+    }
+
+    /// Get the angular velocity as an array
+    pub fn as_array(&self) -> [Real; 3] {
+        [self.wx, self.wy, self.wz]
+    }
+
+    /// Create an angular velocity from an array
+    pub fn from_array(arr: [Real; 3]) -> Self {
+        AngularVelocity::new(arr[0], arr[1], arr[2])
+    }
+}
+
+impl Component for AngularVelocity {}
+
+impl Default for AngularVelocity {
+    fn default() -> Self {
+        AngularVelocity::zero()
+    }
+}
+
+/// Moment of inertia component for bodies with isotropic rotation
+///
+/// Stores a single scalar moment in kg⋅m², which is exact for spheres and
+/// a reasonable approximation for compact bodies. As with `Mass`, a zero
+/// moment marks a body whose spin cannot be changed, and the inverse is
+/// cached for use in impulse calculations.
+///
+/// # Examples
+///
+/// ```
+/// use physics_engine::ecs::components::Inertia;
+///
+/// let inertia = Inertia::solid_sphere(5.0, 2.0);
+/// assert!((inertia.value() - 8.0).abs() < 1e-12); // 2/5 · m · r²
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inertia {
+    value: Real,
+    /// Cached 1/value, or 0.0 when the spin is fixed
+    inverse: Real,
+}
+
+impl Inertia {
+    /// Create a new moment of inertia in kg⋅m²
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is negative or not finite.
+    pub fn new(value: Real) -> Self {
+        assert!(value >= 0.0 && value.is_finite(), "Inertia must be non-negative and finite");
+        let inverse = if value < Mass::IMMOVABLE_THRESHOLD { 0.0 } else { 1.0 / value };
+        Inertia { value, inverse }
+    }
+
+    /// Moment of inertia of a uniform solid sphere, 2/5 · m · r²
+    pub fn solid_sphere(mass: Real, radius: Real) -> Self {
+        Inertia::new(0.4 * mass * radius * radius)
+    }
+
+    /// Get the moment of inertia
+    pub fn value(&self) -> Real {
+        self.value
+    }
+
+    /// Get the inverse moment (1/I), or 0.0 when the spin is fixed
+    pub fn inverse(&self) -> Real {
+        self.inverse
+    }
+}
+
+impl Component for Inertia {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_radius_creation() {
+        assert_eq!(Radius::new(2.5).value(), 2.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Radius must be non-negative and finite")]
+    fn test_negative_radius_panics() {
+        Radius::new(-1.0);
+    }
+
+    #[test]
+    fn test_inertia_inverse_cached() {
+        assert_eq!(Inertia::new(4.0).inverse(), 0.25);
+        assert_eq!(Inertia::new(0.0).inverse(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Inertia must be non-negative and finite")]
+    fn test_negative_inertia_panics() {
+        Inertia::new(-1.0);
+    }
+
     #[test]
     fn test_position_creation() {
         let pos = Position::new(1.0, 2.0, 3.0);
@@ -415,14 +723,15 @@ mod tests {
     }
 
     #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
     fn test_position_validation() {
         let valid = Position::new(1.0, 2.0, 3.0);
         assert!(valid.is_valid());
 
-        let invalid = Position::new(f64::NAN, 2.0, 3.0);
+        let invalid = Position::new(Real::NAN, 2.0, 3.0);
         assert!(!invalid.is_valid());
 
-        let infinite = Position::new(f64::INFINITY, 2.0, 3.0);
+        let infinite = Position::new(Real::INFINITY, 2.0, 3.0);
         assert!(!infinite.is_valid());
     }
 
@@ -453,11 +762,12 @@ mod tests {
     }
 
     #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
     fn test_velocity_validation() {
         let valid = Velocity::new(1.0, 2.0, 3.0);
         assert!(valid.is_valid());
 
-        let invalid = Velocity::new(f64::NAN, 2.0, 3.0);
+        let invalid = Velocity::new(Real::NAN, 2.0, 3.0);
         assert!(!invalid.is_valid());
     }
 
@@ -470,11 +780,12 @@ mod tests {
     }
 
     #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
     fn test_acceleration_validation() {
         let valid = Acceleration::new(1.0, 2.0, 3.0);
         assert!(valid.is_valid());
 
-        let invalid = Acceleration::new(f64::INFINITY, 2.0, 3.0);
+        let invalid = Acceleration::new(Real::INFINITY, 2.0, 3.0);
         assert!(!invalid.is_valid());
     }
 
@@ -488,17 +799,48 @@ mod tests {
     #[test]
     fn test_mass_try_new() {
         let valid = Mass::try_new(10.5);
-        assert!(valid.is_some());
+        assert!(valid.is_ok());
         assert_eq!(valid.unwrap().value(), 10.5);
 
         let negative = Mass::try_new(-1.0);
-        assert!(negative.is_none());
+        assert_eq!(negative, Err(ComponentError::NegativeMass(-1.0)));
+
+        let nan = Mass::try_new(Real::NAN);
+        assert_eq!(nan, Err(ComponentError::NonFinite { component: "Mass" }));
+
+        let inf = Mass::try_new(Real::INFINITY);
+        assert!(inf.is_err());
+    }
+
+    #[test]
+    fn test_vector_try_new_rejects_non_finite() {
+        assert_eq!(Position::try_new(1.0, 2.0, 3.0), Ok(Position::new(1.0, 2.0, 3.0)));
+        assert_eq!(
+            Position::try_new(Real::NAN, 0.0, 0.0),
+            Err(ComponentError::NonFinite { component: "Position" })
+        );
+        assert!(Velocity::try_new(0.0, Real::INFINITY, 0.0).is_err());
+        assert!(Velocity::try_new(0.0, -5.0, 0.0).is_ok());
+        assert!(Acceleration::try_new(0.0, 0.0, Real::NEG_INFINITY).is_err());
+        assert!(Acceleration::try_new(-9.81, 0.0, 0.0).is_ok());
+
+        let err = Position::try_new(Real::NAN, 0.0, 0.0).unwrap_err();
+        assert_eq!(err.to_string(), "Position values must be finite");
+    }
 
-        let nan = Mass::try_new(f64::NAN);
-        assert!(nan.is_none());
+    #[test]
+    #[cfg(all(feature = "strict-validation", debug_assertions))]
+    #[should_panic(expected = "Position values must be finite (strict validation)")]
+    fn test_strict_validation_panics_on_nan_in_debug() {
+        Position::new(Real::NAN, 0.0, 0.0);
+    }
 
-        let inf = Mass::try_new(f64::INFINITY);
-        assert!(inf.is_none());
+    #[test]
+    #[cfg(all(feature = "strict-validation", debug_assertions))]
+    #[should_panic(expected = "Velocity values must be finite (strict validation)")]
+    fn test_strict_validation_checks_setters() {
+        let mut vel = Velocity::zero();
+        vel.set_dy(Real::INFINITY);
     }
 
     #[test]
@@ -510,7 +852,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "Mass must be non-negative and finite")]
     fn test_mass_nan_panics() {
-        Mass::new(f64::NAN);
+        Mass::new(Real::NAN);
     }
 
     #[test]
@@ -533,6 +875,27 @@ mod tests {
         assert_eq!(large_mass.inverse(), 0.01);
     }
 
+    #[test]
+    fn test_mass_cached_inverse() {
+        for value in [1e-9, 0.3, 2.0, 7.0, 5.972e24] {
+            assert_eq!(Mass::new(value).inverse(), 1.0 / value);
+            assert_eq!(Mass::try_new(value).unwrap().inverse(), 1.0 / value);
+        }
+        assert_eq!(Mass::immovable().inverse(), 0.0);
+        assert_eq!(Mass::new(1e-12).inverse(), 0.0);
+
+        // Setters keep the cache in sync
+        let mut mass = Mass::new(4.0);
+        mass.set_value(8.0);
+        assert_eq!(mass.inverse(), 0.125);
+        mass.try_set_value(0.0).unwrap();
+        assert_eq!(mass.inverse(), 0.0);
+        mass.try_set_value(0.5).unwrap();
+        assert_eq!(mass.inverse(), 2.0);
+        assert!(mass.try_set_value(-1.0).is_err());
+        assert_eq!(mass.inverse(), 2.0);
+    }
+
     #[test]
     fn test_mass_zero_handling() {
         let zero_mass = Mass::new(0.0);
@@ -549,13 +912,13 @@ mod tests {
         assert_eq!(mass.value(), 20.0);
         
         // Invalid updates
-        assert!(mass.try_set_value(-1.0).is_err());
+        assert_eq!(mass.try_set_value(-1.0), Err(ComponentError::NegativeMass(-1.0)));
         assert_eq!(mass.value(), 20.0); // Value unchanged
         
-        assert!(mass.try_set_value(f64::NAN).is_err());
+        assert!(mass.try_set_value(Real::NAN).is_err());
         assert_eq!(mass.value(), 20.0); // Value unchanged
         
-        assert!(mass.try_set_value(f64::INFINITY).is_err());
+        assert!(mass.try_set_value(Real::INFINITY).is_err());
         assert_eq!(mass.value(), 20.0); // Value unchanged
     }
 
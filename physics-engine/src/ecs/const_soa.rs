@@ -0,0 +1,380 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Generic true Structure-of-Arrays storage
+//!
+//! `PositionSoAStorage`, `VelocitySoAStorage`, `AccelerationSoAStorage` and
+//! `MassSoAStorage` share the same index bookkeeping and differ only in how
+//! many fields they split a component into. `ConstSoAStorage<T, N>` captures
+//! that once: it stores `N` parallel field arrays for any component that
+//! converts to and from `[Real; N]` via `SoAFields`.
+//!
+//! ```
+//! use physics_engine::ecs::{ComponentStorage, ConstSoAStorage, World};
+//! use physics_engine::ecs::components::Position;
+//!
+//! let mut world = World::new();
+//! let entity = world.create_entity();
+//!
+//! let mut storage = ConstSoAStorage::<Position, 3>::new();
+//! storage.insert(entity, Position::new(1.0, 2.0, 3.0));
+//!
+//! // Field 1 is the y column
+//! assert_eq!(storage.field_array(1), &[2.0]);
+//! storage.field_array_mut(1)[0] = 5.0;
+//! assert_eq!(storage.remove(entity).unwrap().y(), 5.0);
+//! ```
+//!
+//! Components with at most three fields also expose their columns through
+//! `field_arrays()`, so the storage can stand in for the per-type storages
+//! in generic code. Index alignment across storages follows the same rules
+//! as `PositionSoAStorage`.
+
+use crate::real::Real;
+use crate::ecs::Entity;
+use crate::ecs::component::{
+    dense_index_bytes, Component, ComponentStorage, EntityIndexMap, FieldArrays, FieldArraysMut,
+    MemoryReport,
+};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+use std::marker::PhantomData;
+
+/// Component that can be split into `N` scalar fields
+///
+/// Fields are listed in declaration order, so `to_fields()` of a `Position`
+/// is `[x, y, z]`. `from_fields(c.to_fields())` must reproduce `c`.
+pub trait SoAFields<const N: usize>: Component + Sized {
+    /// Split the component into its fields
+    fn to_fields(&self) -> [Real; N];
+
+    /// Rebuild a component from its fields
+    fn from_fields(fields: [Real; N]) -> Self;
+}
+
+impl SoAFields<3> for Position {
+    fn to_fields(&self) -> [Real; 3] {
+        [self.x(), self.y(), self.z()]
+    }
+
+    fn from_fields([x, y, z]: [Real; 3]) -> Self {
+        Position::new(x, y, z)
+    }
+}
+
+impl SoAFields<3> for Velocity {
+    fn to_fields(&self) -> [Real; 3] {
+        [self.dx(), self.dy(), self.dz()]
+    }
+
+    fn from_fields([dx, dy, dz]: [Real; 3]) -> Self {
+        Velocity::new(dx, dy, dz)
+    }
+}
+
+impl SoAFields<3> for Acceleration {
+    fn to_fields(&self) -> [Real; 3] {
+        [self.ax(), self.ay(), self.az()]
+    }
+
+    fn from_fields([ax, ay, az]: [Real; 3]) -> Self {
+        Acceleration::new(ax, ay, az)
+    }
+}
+
+impl SoAFields<1> for Mass {
+    fn to_fields(&self) -> [Real; 1] {
+        [self.value()]
+    }
+
+    fn from_fields([value]: [Real; 1]) -> Self {
+        Mass::new(value)
+    }
+}
+
+/// True Structure-of-Arrays storage with `N` field arrays
+///
+/// Behaves like the per-type SoA storages: indices are assigned in insertion
+/// order, removal uses swap-remove across every field, and `get()` /
+/// `get_mut()` return `None` because no component is stored whole.
+pub struct ConstSoAStorage<T: SoAFields<N>, const N: usize> {
+    entity_to_index: EntityIndexMap,
+    index_to_entity: Vec<Entity>,
+    fields: [Vec<Real>; N],
+    _component: PhantomData<T>,
+}
+
+impl<T: SoAFields<N>, const N: usize> ConstSoAStorage<T, N> {
+    /// Create a new empty storage
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new storage with the given capacity in every field
+    pub fn with_capacity(capacity: usize) -> Self {
+        ConstSoAStorage {
+            entity_to_index: EntityIndexMap::with_capacity(capacity),
+            index_to_entity: Vec::with_capacity(capacity),
+            fields: std::array::from_fn(|_| Vec::with_capacity(capacity)),
+            _component: PhantomData,
+        }
+    }
+
+    /// Get the number of components stored
+    pub fn len(&self) -> usize {
+        self.index_to_entity.len()
+    }
+
+    /// Check if the storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.index_to_entity.is_empty()
+    }
+
+    /// Get the entities in dense-index order
+    ///
+    /// The entity at position `i` owns the field values at index `i`.
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get the dense index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Get the values of field `field` in dense-index order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field >= N`.
+    pub fn field_array(&self, field: usize) -> &[Real] {
+        assert!(field < N, "Field index {} out of range for {} fields", field, N);
+        &self.fields[field]
+    }
+
+    /// Get mutable values of field `field` in dense-index order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field >= N`.
+    pub fn field_array_mut(&mut self, field: usize) -> &mut [Real] {
+        assert!(field < N, "Field index {} out of range for {} fields", field, N);
+        &mut self.fields[field]
+    }
+
+    /// Iterate over `(entity, component)` pairs in dense-index order
+    ///
+    /// Components are reconstructed from the field arrays on the fly, so
+    /// items are yielded by value. The order matches `dense_entities()`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, T)> + '_ {
+        self.index_to_entity
+            .iter()
+            .enumerate()
+            .map(move |(i, &entity)| (entity, self.component_at(i)))
+    }
+
+    fn component_at(&self, index: usize) -> T {
+        T::from_fields(std::array::from_fn(|field| self.fields[field][index]))
+    }
+}
+
+impl<T: SoAFields<N>, const N: usize> Default for ConstSoAStorage<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: SoAFields<N>, const N: usize> ComponentStorage for ConstSoAStorage<T, N> {
+    type Component = T;
+
+    fn insert(&mut self, entity: Entity, component: Self::Component) {
+        let values = component.to_fields();
+        if let Some(&index) = self.entity_to_index.get(&entity) {
+            // Entity already exists, update in place
+            for (field, value) in self.fields.iter_mut().zip(values) {
+                field[index] = value;
+            }
+        } else {
+            // A stale generation of this ID still holds the sparse slot
+            #[cfg(feature = "sparse-set")]
+            if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+                self.remove(stale);
+            }
+
+            // New entity, append to end
+            let new_index = self.index_to_entity.len();
+            for (field, value) in self.fields.iter_mut().zip(values) {
+                field.push(value);
+            }
+            self.entity_to_index.insert(entity, new_index);
+            self.index_to_entity.push(entity);
+        }
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<Self::Component> {
+        let index = self.entity_to_index.remove(&entity)?;
+        let component = self.component_at(index);
+
+        // Swap with last element to avoid shifting
+        let last_index = self.index_to_entity.len() - 1;
+        if index != last_index {
+            let swapped_entity = self.index_to_entity[last_index];
+            *self.entity_to_index.get_mut(&swapped_entity)
+                .expect("ConstSoAStorage: entity_to_index missing entry for swapped entity during remove") = index;
+        }
+        for field in &mut self.fields {
+            field.swap_remove(index);
+        }
+        self.index_to_entity.swap_remove(index);
+
+        Some(component)
+    }
+
+    fn get(&self, _entity: Entity) -> Option<&Self::Component> {
+        None // Use field_array() or field_arrays() for SoA storage
+    }
+
+    fn get_mut(&mut self, _entity: Entity) -> Option<&mut Self::Component> {
+        None // Use field_array_mut() or field_arrays_mut() for SoA storage
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.entity_to_index.contains_key(&entity)
+    }
+
+    fn clear(&mut self) {
+        self.entity_to_index.clear();
+        self.index_to_entity.clear();
+        for field in &mut self.fields {
+            field.clear();
+        }
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        let capacities = self.fields.iter().map(Vec::capacity);
+        MemoryReport {
+            entity_capacity: capacities.clone().min().unwrap_or(self.index_to_entity.capacity()),
+            component_bytes: capacities.sum::<usize>() * std::mem::size_of::<Real>(),
+            map_overhead_estimate: dense_index_bytes(&self.entity_to_index, self.index_to_entity.capacity()),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.entity_to_index.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        for field in &mut self.fields {
+            field.shrink_to_fit();
+        }
+    }
+
+    /// Field columns for components with at most three fields, else `None`
+    fn field_arrays(&self) -> Option<FieldArrays<'_, Self::Component>> {
+        if N > 3 {
+            return None;
+        }
+        let mut columns: [&[Real]; 3] = [&[]; 3];
+        for (column, field) in columns.iter_mut().zip(&self.fields) {
+            *column = field;
+        }
+        Some(FieldArrays::from_columns(columns))
+    }
+
+    /// Mutable field columns for components with at most three fields, else `None`
+    fn field_arrays_mut(&mut self) -> Option<FieldArraysMut<'_, Self::Component>> {
+        if N > 3 {
+            return None;
+        }
+        let mut columns: [&mut [Real]; 3] = [&mut [], &mut [], &mut []];
+        for (column, field) in columns.iter_mut().zip(self.fields.iter_mut()) {
+            *column = field;
+        }
+        Some(FieldArraysMut::from_columns(columns))
+    }
+
+    fn dense_order(&self) -> Option<&[Entity]> {
+        Some(&self.index_to_entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{MassSoAStorage, PositionSoAStorage};
+
+    /// Apply the same inserts and removes to two storages
+    fn apply_ops<S: ComponentStorage>(storage: &mut S, make: impl Fn(u64) -> S::Component) {
+        for id in 0..6 {
+            storage.insert(Entity::new(id, 0), make(id));
+        }
+        // Middle, first, last and a missing entity
+        storage.remove(Entity::new(2, 0));
+        storage.remove(Entity::new(0, 0));
+        storage.remove(Entity::new(5, 0));
+        assert!(storage.remove(Entity::new(9, 0)).is_none());
+        // Update in place and append after removals
+        storage.insert(Entity::new(3, 0), make(30));
+        storage.insert(Entity::new(7, 0), make(7));
+    }
+
+    #[test]
+    fn test_three_fields_match_position_soa_storage() {
+        let position = |id: u64| {
+            let v = id as Real;
+            Position::new(v, v * 10.0, -v)
+        };
+        let mut generic = ConstSoAStorage::<Position, 3>::new();
+        let mut reference = PositionSoAStorage::new();
+        apply_ops(&mut generic, position);
+        apply_ops(&mut reference, position);
+
+        assert_eq!(generic.dense_entities(), reference.dense_entities());
+        let (x, y, z) = reference.field_arrays().unwrap().as_position_arrays();
+        assert_eq!(generic.field_array(0), x);
+        assert_eq!(generic.field_array(1), y);
+        assert_eq!(generic.field_array(2), z);
+        assert_eq!(generic.field_arrays().unwrap().as_position_arrays(), (x, y, z));
+
+        // Every entity's fields stayed together through the swap-removes
+        assert_eq!(generic.iter().collect::<Vec<_>>(), reference.iter().collect::<Vec<_>>());
+        assert_eq!(generic.get_index(Entity::new(7, 0)), reference.get_index(Entity::new(7, 0)));
+        assert_eq!(generic.len(), 4);
+        assert_eq!(generic.remove(Entity::new(3, 0)), Some(position(30)));
+    }
+
+    #[test]
+    fn test_one_field_matches_mass_soa_storage() {
+        let mass = |id: u64| Mass::new(id as Real + 1.0);
+        let mut generic = ConstSoAStorage::<Mass, 1>::new();
+        let mut reference = MassSoAStorage::new();
+        apply_ops(&mut generic, mass);
+        apply_ops(&mut reference, mass);
+
+        assert_eq!(generic.dense_entities(), reference.dense_entities());
+        assert_eq!(generic.field_array(0), reference.field_arrays().unwrap().as_mass_array());
+        assert_eq!(generic.iter().collect::<Vec<_>>(), reference.iter().collect::<Vec<_>>());
+
+        generic.field_array_mut(0)[0] = 42.0;
+        let first = generic.dense_entities()[0];
+        assert_eq!(generic.remove(first).unwrap().value(), 42.0);
+
+        generic.clear();
+        assert!(generic.is_empty());
+        generic.shrink_to_fit();
+        assert_eq!(generic.memory_report().component_bytes, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Field index 1 out of range for 1 fields")]
+    fn test_field_array_out_of_range_panics() {
+        ConstSoAStorage::<Mass, 1>::new().field_array(1);
+    }
+}
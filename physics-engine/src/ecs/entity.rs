@@ -41,6 +41,16 @@ impl fmt::Display for EntityId {
 }
 
 /// Entity handle with generational index support for safe references
+///
+/// Entities are only created by a `World`, so every handle refers to an
+/// entity the World has seen. To correlate entities with IDs from outside
+/// the engine, use `World::create_entity_with_external_id()`.
+///
+/// ```compile_fail
+/// use physics_engine::ecs::Entity;
+///
+/// let forged = Entity::new(1, 0);
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Entity {
     id: EntityId,
@@ -49,7 +59,7 @@ pub struct Entity {
 
 impl Entity {
     /// Create a new entity with the given ID and generation
-    pub fn new(id: u64, generation: u32) -> Self {
+    pub(crate) fn new(id: u64, generation: u32) -> Self {
         Entity {
             id: EntityId::new(id),
             generation,
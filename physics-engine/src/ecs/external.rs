@@ -0,0 +1,149 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Mapping between entities and external IDs
+//!
+//! Games and datasets identify objects with their own IDs, such as a
+//! catalogue number or a network object ID. Entities can only be created by
+//! a `World`, so rather than building entities from those IDs, create them
+//! with `World::create_entity_with_external_id()` and look them up later:
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//!
+//! let mut world = World::new();
+//! let comet = world.create_entity_with_external_id(1_066).unwrap();
+//! assert_eq!(world.lookup_external(1_066), Some(comet));
+//! assert_eq!(world.external_id(comet), Some(1_066));
+//!
+//! // Destroying the entity releases its external ID
+//! world.destroy_entity(comet);
+//! assert_eq!(world.lookup_external(1_066), None);
+//! ```
+
+use crate::ecs::Entity;
+use crate::ecs::component::hash_map_bytes;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error returned when mapping an external ID fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalIdError {
+    /// The external ID is already mapped to a live entity
+    Duplicate {
+        /// The external ID that was requested
+        external_id: u64,
+        /// The entity it is already mapped to
+        entity: Entity,
+    },
+}
+
+impl fmt::Display for ExternalIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalIdError::Duplicate { external_id, entity } => {
+                write!(f, "External ID {} is already mapped to {}", external_id, entity)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExternalIdError {}
+
+/// Bidirectional map between external IDs and live entities
+///
+/// Owned by a `World`, which adds entries in
+/// `create_entity_with_external_id()` and removes them when the entity is
+/// destroyed, so both directions always agree.
+#[derive(Debug, Clone, Default)]
+pub struct ExternalIdMap {
+    to_entity: HashMap<u64, Entity>,
+    to_external: HashMap<Entity, u64>,
+}
+
+impl ExternalIdMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        ExternalIdMap {
+            to_entity: HashMap::new(),
+            to_external: HashMap::new(),
+        }
+    }
+
+    /// Get the entity mapped to an external ID
+    pub fn entity(&self, external_id: u64) -> Option<Entity> {
+        self.to_entity.get(&external_id).copied()
+    }
+
+    /// Get the external ID mapped to an entity
+    pub fn external_id(&self, entity: Entity) -> Option<u64> {
+        self.to_external.get(&entity).copied()
+    }
+
+    /// Get the number of mapped entities
+    pub fn len(&self) -> usize {
+        self.to_entity.len()
+    }
+
+    /// Check if no entities are mapped
+    pub fn is_empty(&self) -> bool {
+        self.to_entity.is_empty()
+    }
+
+    /// Iterate over `(external_id, entity)` pairs in unspecified order
+    pub fn iter(&self) -> impl Iterator<Item = (u64, Entity)> + '_ {
+        self.to_entity.iter().map(|(&external_id, &entity)| (external_id, entity))
+    }
+
+    /// Check that an external ID is free to map
+    pub(crate) fn check_free(&self, external_id: u64) -> Result<(), ExternalIdError> {
+        match self.entity(external_id) {
+            Some(entity) => Err(ExternalIdError::Duplicate { external_id, entity }),
+            None => Ok(()),
+        }
+    }
+
+    /// Map an external ID to an entity that has none
+    pub(crate) fn insert(&mut self, external_id: u64, entity: Entity) -> Result<(), ExternalIdError> {
+        self.check_free(external_id)?;
+        debug_assert!(!self.to_external.contains_key(&entity), "{} already has an external ID", entity);
+        self.to_entity.insert(external_id, entity);
+        self.to_external.insert(entity, external_id);
+        Ok(())
+    }
+
+    /// Remove an entity's mapping, returning its external ID
+    pub(crate) fn remove_entity(&mut self, entity: Entity) -> Option<u64> {
+        let external_id = self.to_external.remove(&entity)?;
+        self.to_entity.remove(&external_id);
+        Some(external_id)
+    }
+
+    /// Remove every mapping
+    pub(crate) fn clear(&mut self) {
+        self.to_entity.clear();
+        self.to_external.clear();
+    }
+
+    /// Release unused capacity
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.to_entity.shrink_to_fit();
+        self.to_external.shrink_to_fit();
+    }
+
+    /// Estimate the heap bytes held by both directions of the map
+    pub(crate) fn memory_bytes(&self) -> usize {
+        hash_map_bytes::<u64, Entity>(self.to_entity.capacity())
+            + hash_map_bytes::<Entity, u64>(self.to_external.capacity())
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! JSON snapshots of a World's bodies
+//!
+//! `World::to_json_string()` dumps the live entities and their World-owned
+//! `Position`, `Velocity` and `Mass` components as one JSON object, for
+//! quick inspection and for analysis in Python or JavaScript:
+//!
+//! ```json
+//! {
+//!   "entities": ["0:0", "1:0"],
+//!   "positions": { "0:0": [1.0, 0.0, 0.0], "1:0": [0.0, 2.0, 0.0] },
+//!   "velocities": { "0:0": [0.0, 1.0, 0.0] },
+//!   "masses": { "0:0": 5.0, "1:0": 0.0 }
+//! }
+//! ```
+//!
+//! Entities are keyed as `id:generation` and listed in creation order; an
+//! entity appears in a component map only if it has that component.
+//! Numbers are written with enough digits to read back exactly, so
+//! `World::from_json_str()` restores the same entities and components bit
+//! for bit. Non-finite values have no JSON representation and are written
+//! as `null`, which `from_json_str()` rejects.
+//!
+//! Only the four maps above are read or written; other World-owned
+//! components and external IDs are not part of the snapshot.
+
+use crate::real::Real;
+use crate::ecs::{Entity, World};
+use crate::ecs::components::{Mass, Position, Velocity};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error reading a World from JSON
+#[derive(Debug)]
+pub enum WorldJsonError {
+    /// The text is not valid JSON
+    Syntax(serde_json::Error),
+    /// The JSON is valid but does not describe a world
+    Format(String),
+}
+
+impl fmt::Display for WorldJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldJsonError::Syntax(err) => write!(f, "Invalid JSON: {}", err),
+            WorldJsonError::Format(reason) => write!(f, "Invalid world JSON: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for WorldJsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorldJsonError::Syntax(err) => Some(err),
+            WorldJsonError::Format(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for WorldJsonError {
+    fn from(err: serde_json::Error) -> Self {
+        WorldJsonError::Syntax(err)
+    }
+}
+
+impl World {
+    /// Serialize the live entities and their bodies to a JSON string
+    ///
+    /// See the `json` module documentation for the layout.
+    pub fn to_json_string(&self) -> String {
+        let entities = self.live_entities_snapshot();
+        let component_map = |value: &dyn Fn(Entity) -> Option<Value>| -> Map<String, Value> {
+            entities
+                .iter()
+                .filter_map(|&entity| value(entity).map(|value| (entity_key(entity), value)))
+                .collect()
+        };
+        let vector = |[x, y, z]: [Real; 3]| Value::from(vec![number(x), number(y), number(z)]);
+
+        let mut root = Map::new();
+        root.insert(
+            "entities".to_string(),
+            entities.iter().map(|&entity| Value::from(entity_key(entity))).collect(),
+        );
+        root.insert(
+            "positions".to_string(),
+            Value::Object(component_map(&|entity| {
+                self.get_component::<Position>(entity).map(|p| vector([p.x(), p.y(), p.z()]))
+            })),
+        );
+        root.insert(
+            "velocities".to_string(),
+            Value::Object(component_map(&|entity| {
+                self.get_component::<Velocity>(entity).map(|v| vector([v.dx(), v.dy(), v.dz()]))
+            })),
+        );
+        root.insert(
+            "masses".to_string(),
+            Value::Object(component_map(&|entity| {
+                self.get_component::<Mass>(entity).map(|m| number(m.value()))
+            })),
+        );
+        Value::Object(root).to_string()
+    }
+
+    /// Build a World from a string written by `to_json_string()`
+    ///
+    /// The entities keep their IDs and generations; IDs between them are
+    /// free for reuse. Component maps that are absent count as empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WorldJsonError::Syntax` for malformed JSON, and
+    /// `WorldJsonError::Format` for a malformed entity key, a duplicate
+    /// entity ID, a component keyed by an entity that isn't listed, or a
+    /// component value that is not finite or, for masses, is negative.
+    pub fn from_json_str(json: &str) -> Result<World, WorldJsonError> {
+        let root: Value = serde_json::from_str(json)?;
+        let root = root.as_object().ok_or_else(|| format_error("expected an object"))?;
+
+        let entities = root
+            .get("entities")
+            .and_then(Value::as_array)
+            .ok_or_else(|| format_error("expected an \"entities\" array"))?
+            .iter()
+            .map(|key| key.as_str().ok_or_else(|| format_error("entity keys must be strings")).and_then(parse_entity_key))
+            .collect::<Result<Vec<Entity>, _>>()?;
+        let mut world = World::with_entities(&entities).ok_or_else(|| format_error("duplicate entity ID"))?;
+        let listed: HashMap<String, Entity> = entities.iter().map(|&entity| (entity_key(entity), entity)).collect();
+
+        for (key, value) in component_entries(root, "positions", &listed)? {
+            let [x, y, z] = parse_vector(value, key)?;
+            let position = Position::try_new(x, y, z).map_err(|err| format_error(format!("{}: {}", key, err)))?;
+            world.insert_component(listed[key], position);
+        }
+        for (key, value) in component_entries(root, "velocities", &listed)? {
+            let [dx, dy, dz] = parse_vector(value, key)?;
+            let velocity = Velocity::try_new(dx, dy, dz).map_err(|err| format_error(format!("{}: {}", key, err)))?;
+            world.insert_component(listed[key], velocity);
+        }
+        for (key, value) in component_entries(root, "masses", &listed)? {
+            let mass = Mass::try_new(parse_number(value, key)?).map_err(|err| format_error(format!("{}: {}", key, err)))?;
+            world.insert_component(listed[key], mass);
+        }
+        Ok(world)
+    }
+}
+
+fn format_error(reason: impl Into<String>) -> WorldJsonError {
+    WorldJsonError::Format(reason.into())
+}
+
+/// Key of an entity in the JSON maps: `id:generation`
+fn entity_key(entity: Entity) -> String {
+    format!("{}:{}", entity.id().raw(), entity.generation())
+}
+
+fn parse_entity_key(key: &str) -> Result<Entity, WorldJsonError> {
+    let (id, generation) = key
+        .split_once(':')
+        .ok_or_else(|| format_error(format!("entity key {:?} is not id:generation", key)))?;
+    match (id.parse(), generation.parse()) {
+        (Ok(id), Ok(generation)) => Ok(Entity::new(id, generation)),
+        _ => Err(format_error(format!("entity key {:?} is not id:generation", key))),
+    }
+}
+
+/// Entries of a component map, each checked to name a listed entity
+fn component_entries<'a>(
+    root: &'a Map<String, Value>,
+    name: &str,
+    listed: &HashMap<String, Entity>,
+) -> Result<Vec<(&'a String, &'a Value)>, WorldJsonError> {
+    let map = match root.get(name) {
+        Some(value) => value
+            .as_object()
+            .ok_or_else(|| format_error(format!("\"{}\" must be an object", name)))?,
+        None => return Ok(Vec::new()),
+    };
+    map.iter()
+        .map(|(key, value)| {
+            if listed.contains_key(key) {
+                Ok((key, value))
+            } else {
+                Err(format_error(format!("\"{}\" names unlisted entity {}", name, key)))
+            }
+        })
+        .collect()
+}
+
+// JSON numbers are f64, which holds any `Real` exactly; NaN and infinities
+// become null
+#[allow(clippy::unnecessary_cast)]
+fn number(value: Real) -> Value {
+    Value::from(value as f64)
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn parse_number(value: &Value, key: &str) -> Result<Real, WorldJsonError> {
+    value
+        .as_f64()
+        .map(|number| number as Real)
+        .ok_or_else(|| format_error(format!("{}: expected a number, found {}", key, value)))
+}
+
+fn parse_vector(value: &Value, key: &str) -> Result<[Real; 3], WorldJsonError> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y, z]) => Ok([parse_number(x, key)?, parse_number(y, key)?, parse_number(z, key)?]),
+        _ => Err(format_error(format!("{}: expected an array of three numbers, found {}", key, value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_round_trips_through_json() {
+        let mut world = World::new();
+        let sun = world
+            .spawn()
+            .with(Position::new(0.1, -0.2, 0.3))
+            .with(Velocity::new(1.0 / 3.0, 0.0, -2e-7))
+            .with(Mass::new(1.989e30))
+            .build();
+        let dust = world.spawn().with(Position::new(1.5e11, 2.0 / 7.0, 0.0)).with(Mass::immovable()).build();
+        // A live entity past generation 0, and a free ID below a live one
+        let gone = world.create_entity();
+        world.destroy_entity(gone);
+        let reused = world.spawn().with(Velocity::new(-5.0, 5.0, 0.5)).build();
+        assert_eq!(reused.generation(), 1);
+        let freed = world.create_entity();
+        world.create_entity();
+        world.destroy_entity(freed);
+
+        let json = world.to_json_string();
+        let restored = World::from_json_str(&json).unwrap();
+
+        assert_eq!(restored.live_entities_snapshot(), world.live_entities_snapshot());
+        for entity in world.iter_entities() {
+            assert_eq!(restored.get_component::<Position>(entity), world.get_component::<Position>(entity));
+            assert_eq!(restored.get_component::<Velocity>(entity), world.get_component::<Velocity>(entity));
+            assert_eq!(
+                restored.get_component::<Mass>(entity).map(Mass::value),
+                world.get_component::<Mass>(entity).map(Mass::value)
+            );
+        }
+        assert!(restored.get_component::<Velocity>(dust).is_none());
+        assert_eq!(restored.get_component::<Mass>(sun).unwrap().value(), 1.989e30);
+        assert_eq!(restored.to_json_string(), json);
+
+        // New entities take the freed ID rather than one already in use
+        let mut restored = restored;
+        let fresh = restored.create_entity();
+        assert_eq!(fresh.id(), freed.id());
+        assert_eq!(restored.entity_count(), world.entity_count() + 1);
+    }
+
+    #[test]
+    fn test_from_json_rejects_malformed_worlds() {
+        let cases = [
+            ("[]", "expected an object"),
+            (r#"{"entities": ["0"]}"#, "is not id:generation"),
+            (r#"{"entities": ["0:0", "0:1"]}"#, "duplicate entity ID"),
+            (r#"{"entities": ["0:0"], "positions": {"1:0": [0, 0, 0]}}"#, "unlisted entity"),
+            (r#"{"entities": ["0:0"], "velocities": {"0:0": [0, null, 0]}}"#, "expected a number"),
+            (r#"{"entities": ["0:0"], "masses": {"0:0": -1.0}}"#, "0:0"),
+        ];
+        for (json, expected) in cases {
+            let err = World::from_json_str(json).err().unwrap();
+            assert!(err.to_string().contains(expected), "{:?} gave {}", json, err);
+        }
+        assert!(matches!(World::from_json_str("{"), Err(WorldJsonError::Syntax(_))));
+    }
+}
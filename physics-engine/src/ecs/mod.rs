@@ -18,13 +18,27 @@
 //! - Component storage with cache-friendly data layouts
 //! - System execution framework
 //! - Newtonian physics components and systems
+//! - A bundle of the four Newtonian storages
 //! - System scheduler with parallel execution support
+//! - Sleeping for bodies that have come to rest
+//! - Change tracking for incremental systems
+//! - JSON snapshots of a world (`serde` feature)
 //! - Optional parallel execution support via Rayon
 
 mod entity;
 mod component;
 mod system;
 mod world;
+mod sparse;
+mod const_soa;
+mod state;
+mod sleep;
+mod external;
+mod change;
+#[cfg(feature = "parallel")]
+mod query;
+#[cfg(feature = "serde")]
+mod json;
 
 /// Newtonian physics components
 pub mod components;
@@ -36,11 +50,21 @@ pub mod scheduler;
 pub use entity::{Entity, EntityId};
 pub use component::{
     Component, ComponentStorage, HashMapStorage, SoAStorage,
-    FieldArrays, FieldArraysMut,
+    FieldArrays, FieldArraysMut, MemoryReport,
     PositionSoAStorage, VelocitySoAStorage, AccelerationSoAStorage, MassSoAStorage,
 };
+pub use sparse::{SparseEntityIndex, SparseSoAStorage, DENSE_ID_LIMIT};
+pub use const_soa::{ConstSoAStorage, SoAFields};
+pub use state::{NewtonianState, SoANewtonianState};
+pub use sleep::{SleepState, SleepSystem};
+pub use external::{ExternalIdError, ExternalIdMap};
+pub use change::ChangeTracking;
 pub use system::{System, SystemExecutor};
-pub use world::World;
+pub use world::{World, EntityBuilder, ComponentMask, MAX_COMPONENT_TYPES};
+#[cfg(feature = "parallel")]
+pub use query::Query;
+#[cfg(feature = "serde")]
+pub use json::WorldJsonError;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,169 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Typed parallel queries over World-owned components
+//!
+//! `World::par_query::<(A, B)>()` visits every entity that has all of the
+//! listed components in its World-owned storages, yielding
+//! `(Entity, &A, &B)` on Rayon's thread pool. Queries only borrow the World
+//! immutably, so any number may run at once; mutable access goes through
+//! `World::par_query_mut()`, which borrows the World exclusively.
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//! use physics_engine::ecs::components::{Position, Velocity};
+//! use rayon::prelude::*;
+//!
+//! let mut world = World::new();
+//! world.spawn().with(Position::zero()).with(Velocity::new(1.0, 0.0, 0.0)).build();
+//! world.spawn().with(Position::zero()).build();
+//!
+//! let moving = world.par_query::<(Position, Velocity)>().count();
+//! assert_eq!(moving, 1);
+//! ```
+
+use crate::ecs::{Component, ComponentStorage, Entity, HashMapStorage, World};
+
+/// Tuple of component types queried together with `World::par_query()`
+///
+/// Implemented for tuples of one to four component types. Candidates are
+/// drawn from the first component's storage, so listing the rarest
+/// component first does the least work.
+pub trait Query: 'static {
+    /// Item yielded per matching entity, e.g. `(Entity, &'w A, &'w B)`
+    type Item<'w>: Send;
+
+    /// Storages borrowed for the duration of a query
+    #[doc(hidden)]
+    type Storages<'w>: Copy + Send + Sync;
+
+    /// Borrow the storages, or None if any component type has none
+    #[doc(hidden)]
+    fn storages(world: &World) -> Option<Self::Storages<'_>>;
+
+    /// Entities that may match
+    #[doc(hidden)]
+    fn candidates(storages: Self::Storages<'_>) -> Vec<Entity>;
+
+    /// Look up every queried component of an entity
+    #[doc(hidden)]
+    fn fetch(storages: Self::Storages<'_>, entity: Entity) -> Option<Self::Item<'_>>;
+}
+
+macro_rules! impl_query {
+    ($first:ident $(, $rest:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<$first: Component $(, $rest: Component)*> Query for ($first, $($rest,)*) {
+            type Item<'w> = (Entity, &'w $first $(, &'w $rest)*);
+            type Storages<'w> = (&'w HashMapStorage<$first>, $(&'w HashMapStorage<$rest>,)*);
+
+            fn storages(world: &World) -> Option<Self::Storages<'_>> {
+                Some((world.storage::<$first>()?, $(world.storage::<$rest>()?,)*))
+            }
+
+            fn candidates(storages: Self::Storages<'_>) -> Vec<Entity> {
+                storages.0.entities().collect()
+            }
+
+            fn fetch(storages: Self::Storages<'_>, entity: Entity) -> Option<Self::Item<'_>> {
+                let ($first, $($rest,)*) = storages;
+                Some((entity, $first.get(entity)? $(, $rest.get(entity)?)*))
+            }
+        }
+    };
+}
+
+impl_query!(A);
+impl_query!(A, B);
+impl_query!(A, B, C);
+impl_query!(A, B, C, D);
+
+#[cfg(test)]
+mod tests {
+    use crate::ecs::World;
+    use crate::ecs::components::{Mass, Position, Velocity};
+    use crate::real::{Real, RealExt};
+    use rayon::prelude::*;
+
+    /// World with `count` moving bodies, every third one also massive,
+    /// plus a few bodies without velocity
+    fn populated_world(count: usize) -> World {
+        let mut world = World::new();
+        for i in 0..count {
+            let v = i as Real;
+            let mut builder = world
+                .spawn()
+                .with(Position::new(v, 0.0, 0.0))
+                .with(Velocity::new(v * 0.5, -v, 1.0));
+            if i % 3 == 0 {
+                builder = builder.with(Mass::new(1.0 + v));
+            }
+            builder.build();
+        }
+        for _ in 0..5 {
+            world.spawn().with(Position::zero()).build();
+        }
+        world
+    }
+
+    #[test]
+    fn test_par_query_sum_matches_sequential() {
+        let world = populated_world(1000);
+
+        let (sx, sy, sz) = world
+            .par_query::<(Position, Velocity)>()
+            .map(|(_, _, vel)| (vel.dx(), vel.dy(), vel.dz()))
+            .reduce(|| (0.0, 0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+
+        let mut expected = (0.0, 0.0, 0.0);
+        let mut count = 0;
+        for &entity in world.entities() {
+            let pos = world.get_component::<Position>(entity);
+            if let (Some(_), Some(vel)) = (pos, world.get_component::<Velocity>(entity)) {
+                expected.0 += vel.dx();
+                expected.1 += vel.dy();
+                expected.2 += vel.dz();
+                count += 1;
+            }
+        }
+
+        assert_eq!(world.par_query::<(Position, Velocity)>().count(), count);
+        assert!((sx - expected.0).abs() < Real::TOLERANCE * expected.0.abs());
+        assert!((sy - expected.1).abs() < Real::TOLERANCE * expected.1.abs());
+        assert_eq!(sz, expected.2);
+    }
+
+    #[test]
+    fn test_par_query_filters_to_entities_with_all_components() {
+        let world = populated_world(30);
+        assert_eq!(world.par_query::<(Position,)>().count(), 35);
+        assert_eq!(world.par_query::<(Velocity, Mass)>().count(), 10);
+        assert!(world
+            .par_query::<(Mass, Position, Velocity)>()
+            .all(|(entity, mass, pos, _)| mass.value() == 1.0 + pos.x() && world.is_entity_alive(entity)));
+
+        // A component type the World has never stored matches nothing
+        assert_eq!(World::new().par_query::<(Position, Velocity)>().count(), 0);
+    }
+
+    #[test]
+    fn test_par_query_mut_updates_every_component() {
+        let mut world = populated_world(100);
+        world.par_query_mut::<Position>().for_each(|(_, pos)| {
+            *pos = Position::new(pos.x() + 1.0, pos.y(), pos.z());
+        });
+
+        assert!(world.par_query::<(Position,)>().all(|(_, pos)| pos.x() >= 1.0));
+        assert_eq!(World::new().par_query_mut::<Velocity>().count(), 0);
+    }
+}
@@ -105,7 +105,7 @@ impl Scheduler {
     pub fn add_system<S: System + 'static>(&mut self, system: S, stage: StageId) {
         self.stages
             .entry(stage)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(Box::new(system));
     }
 
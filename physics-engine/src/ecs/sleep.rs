@@ -0,0 +1,390 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Per-entity sleeping for bodies that have come to rest
+//!
+//! In settling simulations most bodies end up at rest but still cost a full
+//! force evaluation and integration every step. `SleepSystem` puts an entity
+//! to sleep once its speed and acceleration have stayed below thresholds for
+//! a number of consecutive steps. Sleeping entities are recorded in the
+//! `ForceRegistry`, which is how the rest of the engine sees them:
+//!
+//! - `apply_forces_to_acceleration()` and the integrators skip them
+//! - `GravitySystem` computations with `GravityOptions::with_sleeping()`
+//!   only compute the force awake bodies exert on them, while they keep
+//!   attracting awake bodies
+//! - accumulating a force above `ForceRegistry::wake_force_threshold()`
+//!   wakes them
+//!
+//! Only entities with a `SleepState` component are ever put to sleep.
+//!
+//! # Step Order
+//!
+//! Run `SleepSystem::update()` once per step, after integration, so it sees
+//! the velocities and accelerations the step produced:
+//!
+//! 1. Compute forces (e.g. `compute_forces_into_with()` with
+//!    `GravityOptions::with_sleeping()`) and register them,
+//!    which wakes disturbed sleepers
+//! 2. `apply_forces_to_acceleration()` and integrate
+//! 3. `SleepSystem::update()`
+
+use crate::real::Real;
+use crate::ecs::{Component, ComponentStorage, Entity};
+use crate::ecs::components::{Acceleration, Velocity};
+use crate::ecs::systems::ForceRegistry;
+
+/// Quiet-step counter for an entity that may sleep
+///
+/// Counts the consecutive steps the entity's speed and acceleration have
+/// stayed below the `SleepSystem` thresholds. Entities without this
+/// component never sleep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SleepState {
+    quiet_steps: u32,
+}
+
+impl SleepState {
+    /// Create a sleep state with no quiet steps recorded
+    pub fn new() -> Self {
+        SleepState { quiet_steps: 0 }
+    }
+
+    /// Get the number of consecutive quiet steps
+    ///
+    /// Reset to zero when the entity falls asleep, so a woken entity has to
+    /// settle again before it sleeps.
+    pub fn quiet_steps(&self) -> u32 {
+        self.quiet_steps
+    }
+}
+
+impl Component for SleepState {}
+
+/// System putting entities to sleep once they have been quiet long enough
+#[derive(Debug, Clone)]
+pub struct SleepSystem {
+    speed_threshold: Real,
+    acceleration_threshold: Real,
+    quiet_steps_to_sleep: u32,
+}
+
+impl SleepSystem {
+    /// Create a sleep system
+    ///
+    /// An entity counts as quiet for a step when its speed is at most
+    /// `speed_threshold` (m/s) and its acceleration magnitude at most
+    /// `acceleration_threshold` (m/s²). It falls asleep after
+    /// `quiet_steps_to_sleep` consecutive quiet steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a threshold is negative or not finite, or if
+    /// `quiet_steps_to_sleep` is zero.
+    pub fn new(speed_threshold: Real, acceleration_threshold: Real, quiet_steps_to_sleep: u32) -> Self {
+        assert!(
+            speed_threshold >= 0.0 && speed_threshold.is_finite()
+                && acceleration_threshold >= 0.0 && acceleration_threshold.is_finite(),
+            "Sleep thresholds must be non-negative and finite"
+        );
+        assert!(quiet_steps_to_sleep > 0, "Quiet steps to sleep must be positive");
+        SleepSystem {
+            speed_threshold,
+            acceleration_threshold,
+            quiet_steps_to_sleep,
+        }
+    }
+
+    /// Get the speed (m/s) at or below which an entity is quiet
+    pub fn speed_threshold(&self) -> Real {
+        self.speed_threshold
+    }
+
+    /// Get the acceleration magnitude (m/s²) at or below which an entity is quiet
+    pub fn acceleration_threshold(&self) -> Real {
+        self.acceleration_threshold
+    }
+
+    /// Get the number of consecutive quiet steps before an entity sleeps
+    pub fn quiet_steps_to_sleep(&self) -> u32 {
+        self.quiet_steps_to_sleep
+    }
+
+    /// Count quiet steps and put entities that have settled to sleep
+    ///
+    /// Entities without a `SleepState` or `Velocity`, and entities already
+    /// asleep, are left alone; a missing `Acceleration` counts as zero. An
+    /// entity falling asleep has its velocity and acceleration zeroed, so it
+    /// is exactly at rest while it sleeps.
+    ///
+    /// # Returns
+    ///
+    /// Number of entities put to sleep by this call
+    pub fn update<'a, I>(
+        &self,
+        entities: I,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        states: &mut impl ComponentStorage<Component = SleepState>,
+        force_registry: &mut ForceRegistry,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let mut fell_asleep = 0;
+
+        for entity in entities {
+            if force_registry.is_sleeping(*entity) {
+                continue;
+            }
+            let state = match states.get_mut(*entity) {
+                Some(s) => s,
+                None => continue,
+            };
+            let speed = match velocities.get(*entity) {
+                Some(v) => v.magnitude(),
+                None => continue,
+            };
+            let acceleration = accelerations
+                .get(*entity)
+                .map_or(0.0, |a| a.as_array().iter().map(|c| c * c).sum::<Real>().sqrt());
+
+            if speed > self.speed_threshold || acceleration > self.acceleration_threshold {
+                state.quiet_steps = 0;
+                continue;
+            }
+            state.quiet_steps = state.quiet_steps.saturating_add(1);
+            if state.quiet_steps < self.quiet_steps_to_sleep {
+                continue;
+            }
+
+            state.quiet_steps = 0;
+            force_registry.put_to_sleep(*entity);
+            if let Some(v) = velocities.get_mut(*entity) {
+                *v = Velocity::zero();
+            }
+            if let Some(a) = accelerations.get_mut(*entity) {
+                *a = Acceleration::zero();
+            }
+            fell_asleep += 1;
+        }
+
+        fell_asleep
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::HashMapStorage;
+    use crate::ecs::components::{Mass, Position};
+    use crate::ecs::systems::{apply_forces_to_acceleration, Force};
+    use crate::integration::{Integrator, VelocityVerletIntegrator};
+    use crate::plugins::gravity::{GravityOptions, GravityPlugin, GravitySystem};
+    use std::collections::HashMap;
+
+    /// Bodies stepped with gravity, Verlet and (optionally) sleeping
+    struct Scene {
+        entities: Vec<Entity>,
+        positions: HashMapStorage<Position>,
+        velocities: HashMapStorage<Velocity>,
+        accelerations: HashMapStorage<Acceleration>,
+        masses: HashMapStorage<Mass>,
+        states: HashMapStorage<SleepState>,
+        registry: ForceRegistry,
+        gravity: GravitySystem,
+        integrator: VelocityVerletIntegrator,
+        sleep: Option<SleepSystem>,
+    }
+
+    impl Scene {
+        fn new(gravity: GravityPlugin, sleep: Option<SleepSystem>) -> Self {
+            let mut registry = ForceRegistry::new();
+            registry.set_warn_on_missing_components(false);
+            Scene {
+                entities: Vec::new(),
+                positions: HashMapStorage::new(),
+                velocities: HashMapStorage::new(),
+                accelerations: HashMapStorage::new(),
+                masses: HashMapStorage::new(),
+                states: HashMapStorage::new(),
+                registry,
+                gravity: GravitySystem::new(gravity),
+                integrator: VelocityVerletIntegrator::new(0.01),
+                sleep,
+            }
+        }
+
+        fn add(&mut self, position: Position, velocity: Velocity, mass: Mass, can_sleep: bool) -> Entity {
+            let entity = Entity::new(self.entities.len() as u64 + 1, 0);
+            self.entities.push(entity);
+            self.positions.insert(entity, position);
+            self.velocities.insert(entity, velocity);
+            self.masses.insert(entity, mass);
+            if can_sleep {
+                self.states.insert(entity, SleepState::new());
+            }
+            entity
+        }
+
+        fn step(&mut self) {
+            let mut forces = HashMap::new();
+            let options = GravityOptions::new().with_sleeping(&self.registry);
+            self.gravity.compute_forces_into_with(
+                &self.entities, &self.positions, &self.masses, options, &mut forces,
+            );
+            self.registry.clear();
+            self.registry.register_forces(&forces);
+            apply_forces_to_acceleration(
+                self.entities.iter(), &self.registry, &self.masses, &mut self.accelerations, false,
+            );
+            self.integrator.integrate(
+                self.entities.iter(),
+                &mut self.positions,
+                &mut self.velocities,
+                &self.accelerations,
+                &self.masses,
+                &mut self.registry,
+                false,
+            );
+            if let Some(sleep) = &self.sleep {
+                sleep.update(
+                    self.entities.iter(),
+                    &mut self.velocities,
+                    &mut self.accelerations,
+                    &mut self.states,
+                    &mut self.registry,
+                );
+            }
+        }
+
+        fn position(&self, entity: Entity) -> [Real; 3] {
+            self.positions.get(entity).unwrap().as_array()
+        }
+    }
+
+    /// A particle at rest midway between two fixed attractors
+    fn balanced_scene() -> (Scene, Entity) {
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.1);
+        let mut scene = Scene::new(plugin, Some(SleepSystem::new(1e-6, 1e-6, 5)));
+        scene.add(Position::new(-10.0, 0.0, 0.0), Velocity::zero(), Mass::immovable(), false);
+        scene.add(Position::new(10.0, 0.0, 0.0), Velocity::zero(), Mass::immovable(), false);
+        let particle = scene.add(Position::zero(), Velocity::zero(), Mass::new(1.0), true);
+        (scene, particle)
+    }
+
+    #[test]
+    fn test_resting_particle_falls_asleep_and_stops_updating() {
+        let (mut scene, particle) = balanced_scene();
+
+        for step in 1..5 {
+            scene.step();
+            assert_eq!(scene.states.get(particle).unwrap().quiet_steps(), step);
+        }
+        assert!(!scene.registry.is_sleeping(particle));
+        scene.step();
+        assert!(scene.registry.is_sleeping(particle));
+        assert_eq!(scene.registry.sleeping_count(), 1);
+
+        // Asleep, the particle is not integrated even if something moves it
+        scene.velocities.insert(particle, Velocity::new(1.0, 0.0, 0.0));
+        for _ in 0..10 {
+            scene.step();
+        }
+        assert_eq!(scene.position(particle), [0.0, 0.0, 0.0]);
+        assert!(scene.registry.is_sleeping(particle));
+    }
+
+    #[test]
+    fn test_nearby_massive_body_wakes_sleeper() {
+        let (mut scene, particle) = balanced_scene();
+        for _ in 0..5 {
+            scene.step();
+        }
+        assert!(scene.registry.is_sleeping(particle));
+
+        scene.registry.set_wake_force_threshold(0.5);
+        scene.add(Position::new(0.0, 2.0, 0.0), Velocity::zero(), Mass::new(10.0), false);
+
+        // G·m·M/r² ≈ 2.5 N exceeds the wake threshold
+        scene.step();
+        assert!(!scene.registry.is_sleeping(particle));
+        assert_eq!(scene.registry.sleeping_count(), 0);
+        scene.step();
+        assert!(scene.position(particle)[1] > 0.0, "Woken particle did not fall toward the newcomer");
+    }
+
+    #[test]
+    fn test_weak_force_does_not_wake_sleeper() {
+        let mut registry = ForceRegistry::new();
+        registry.set_wake_force_threshold(1.0);
+        let entity = Entity::new(1, 0);
+        registry.put_to_sleep(entity);
+
+        let mut forces = HashMap::new();
+        forces.insert(entity, Force::new(0.6, 0.8, 0.0));
+        registry.register_forces(&forces);
+        assert!(registry.is_sleeping(entity));
+
+        forces.insert(entity, Force::new(0.0, 2.0, 0.0));
+        registry.clear();
+        registry.register_forces(&forces);
+        assert!(!registry.is_sleeping(entity));
+        assert!(!registry.wake(entity));
+    }
+
+    /// Two awake probes falling symmetrically past a sleeper, which feels
+    /// exactly zero net force from them
+    fn probe_trajectories(sleep: bool, sleeping_bodies_attract: bool) -> (Vec<[Real; 3]>, bool) {
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.1);
+        plugin.set_sleeping_bodies_attract(sleeping_bodies_attract);
+        let sleep_system = if sleep { Some(SleepSystem::new(1e-6, 1e-6, 3)) } else { None };
+        let mut scene = Scene::new(plugin, sleep_system);
+
+        let sleeper = scene.add(Position::zero(), Velocity::zero(), Mass::new(100.0), true);
+        let left = scene.add(Position::new(-5.0, 0.0, 0.0), Velocity::new(0.0, -3.0, 0.0), Mass::new(1.0), false);
+        let right = scene.add(Position::new(5.0, 0.0, 0.0), Velocity::new(0.0, 3.0, 0.0), Mass::new(1.0), false);
+
+        let mut trajectory = Vec::new();
+        for _ in 0..200 {
+            scene.step();
+            trajectory.push(scene.position(left));
+            trajectory.push(scene.position(right));
+        }
+        assert_eq!(scene.position(sleeper), [0.0, 0.0, 0.0]);
+        (trajectory, scene.registry.is_sleeping(sleeper))
+    }
+
+    #[test]
+    fn test_awake_trajectories_unaffected_by_sleeping_attractor() {
+        let (reference, slept) = probe_trajectories(false, true);
+        assert!(!slept);
+
+        let (with_sleep, slept) = probe_trajectories(true, true);
+        assert!(slept);
+        assert_eq!(with_sleep, reference);
+
+        // Excluding sleeping attractors is opt-in and changes the orbits
+        let (without_attraction, slept) = probe_trajectories(true, false);
+        assert!(slept);
+        assert_ne!(without_attraction, reference);
+    }
+
+    #[test]
+    #[should_panic(expected = "Quiet steps to sleep must be positive")]
+    fn test_zero_quiet_steps_panics() {
+        SleepSystem::new(0.1, 0.1, 0);
+    }
+}
@@ -0,0 +1,776 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Sparse-set entity index and storage
+//!
+//! Entity IDs handed out by `World` are small, densely reused integers, so the
+//! entity → dense index mapping can be a plain `Vec` indexed by ID instead of a
+//! `HashMap`. Lookups become a bounds check and a generation comparison, with
+//! no hashing and better memory locality.
+//!
+//! # Generations
+//!
+//! Each slot stores the generation it was inserted with. Looking up an entity
+//! whose generation differs from the stored one misses, so stale handles never
+//! resolve to a reused ID's data. Only one generation per ID can be stored at a
+//! time: inserting a different generation of an occupied ID evicts the stale
+//! component first (see `SparseSoAStorage`).
+//!
+//! # Memory
+//!
+//! The sparse array grows to the largest inserted ID (amortized), so memory is
+//! O(max ID) rather than O(len). This is ideal for IDs allocated by `World`,
+//! which recycles freed IDs. IDs at or above `DENSE_ID_LIMIT` are kept in an
+//! ordered map instead, so a single large ID cannot allocate a huge array.
+
+use super::component::{Component, ComponentStorage, MemoryReport};
+use super::entity::Entity;
+use std::collections::BTreeMap;
+
+/// Entity IDs below this are indexed by a sparse array slot; larger IDs fall
+/// back to an ordered map
+///
+/// Caps the sparse array at 2²⁰ slots (16 MiB on 64-bit targets).
+pub const DENSE_ID_LIMIT: u64 = 1 << 20;
+
+/// Entity → dense index mapping backed by a sparse array indexed by entity ID
+///
+/// The API mirrors the subset of `HashMap<Entity, usize>` used by the SoA
+/// storages, so it can be swapped in without changing their logic.
+#[derive(Debug, Clone, Default)]
+pub struct SparseEntityIndex {
+    /// Slot per entity ID below `DENSE_ID_LIMIT`: (generation, dense index)
+    slots: Vec<Option<(u32, usize)>>,
+    /// (generation, dense index) per entity ID at or above `DENSE_ID_LIMIT`
+    overflow: BTreeMap<u64, (u32, usize)>,
+    /// Number of mapped entities
+    len: usize,
+}
+
+impl SparseEntityIndex {
+    /// Create a new empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new index with room for entity IDs below `capacity`
+    ///
+    /// The capacity is clamped to `DENSE_ID_LIMIT`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SparseEntityIndex {
+            slots: Vec::with_capacity(capacity.min(DENSE_ID_LIMIT as usize)),
+            overflow: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Convert an entity ID to a sparse array slot, if below `DENSE_ID_LIMIT`
+    #[inline]
+    fn slot_of(entity: &Entity) -> Option<usize> {
+        let id = entity.id().raw();
+        (id < DENSE_ID_LIMIT).then_some(id as usize)
+    }
+
+    /// Get the (generation, dense index) stored for an entity's ID
+    #[inline]
+    fn entry(&self, entity: &Entity) -> Option<&(u32, usize)> {
+        match Self::slot_of(entity) {
+            Some(slot) => self.slots.get(slot)?.as_ref(),
+            None => self.overflow.get(&entity.id().raw()),
+        }
+    }
+
+    /// Get a mutable reference to the (generation, dense index) stored for an entity's ID
+    #[inline]
+    fn entry_mut(&mut self, entity: &Entity) -> Option<&mut (u32, usize)> {
+        match Self::slot_of(entity) {
+            Some(slot) => self.slots.get_mut(slot)?.as_mut(),
+            None => self.overflow.get_mut(&entity.id().raw()),
+        }
+    }
+
+    /// Get the dense index for an entity, if present with a matching generation
+    #[inline]
+    pub fn get(&self, entity: &Entity) -> Option<&usize> {
+        match self.entry(entity) {
+            Some((generation, index)) if *generation == entity.generation() => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the dense index for an entity
+    #[inline]
+    pub fn get_mut(&mut self, entity: &Entity) -> Option<&mut usize> {
+        match self.entry_mut(entity) {
+            Some((generation, index)) if *generation == entity.generation() => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Check if the entity is present with a matching generation
+    #[inline]
+    pub fn contains_key(&self, entity: &Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    /// Get the entity of a different generation occupying this entity's ID slot
+    ///
+    /// Returns `None` if the slot is empty or holds the same generation.
+    pub fn stale_entity(&self, entity: &Entity) -> Option<Entity> {
+        match self.entry(entity) {
+            Some((generation, _)) if *generation != entity.generation() => {
+                Some(Entity::new(entity.id().raw(), *generation))
+            }
+            _ => None,
+        }
+    }
+
+    /// Map an entity to a dense index
+    ///
+    /// Grows the sparse array (amortized) if the ID is beyond its current
+    /// length and below `DENSE_ID_LIMIT`. Returns the previous index if the
+    /// same entity was present. A different generation in the same slot is
+    /// overwritten; callers that own dense data for it must evict it first.
+    pub fn insert(&mut self, entity: Entity, index: usize) -> Option<usize> {
+        let value = (entity.generation(), index);
+        let previous = match Self::slot_of(&entity) {
+            Some(slot) => {
+                if slot >= self.slots.len() {
+                    self.slots.resize(slot + 1, None);
+                }
+                self.slots[slot].replace(value)
+            }
+            None => self.overflow.insert(entity.id().raw(), value),
+        };
+
+        match previous {
+            Some((generation, old_index)) if generation == entity.generation() => Some(old_index),
+            Some(_) => None,
+            None => {
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Remove an entity, returning its dense index if it was present
+    pub fn remove(&mut self, entity: &Entity) -> Option<usize> {
+        let index = self.get(entity).copied()?;
+        match Self::slot_of(entity) {
+            Some(slot) => self.slots[slot] = None,
+            None => {
+                self.overflow.remove(&entity.id().raw());
+            }
+        }
+        self.len -= 1;
+        Some(index)
+    }
+
+    /// Get the number of mapped entities
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if no entities are mapped
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all mappings
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.overflow.clear();
+        self.len = 0;
+    }
+
+    /// Release unused capacity, including empty slots past the highest ID
+    pub fn shrink_to_fit(&mut self) {
+        while matches!(self.slots.last(), Some(None)) {
+            self.slots.pop();
+        }
+        self.slots.shrink_to_fit();
+    }
+
+    /// Heap bytes held by the sparse slot array, plus an estimate for the
+    /// overflow map's entries
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.slots.capacity() * std::mem::size_of::<Option<(u32, usize)>>()
+            + self.overflow.len() * std::mem::size_of::<(u64, (u32, usize))>()
+    }
+
+    /// Iterate over (entity, dense index) pairs in ID order
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, usize)> + '_ {
+        let dense = self.slots.iter().enumerate().filter_map(|(id, slot)| {
+            slot.map(|(generation, index)| (Entity::new(id as u64, generation), index))
+        });
+        let overflow = self
+            .overflow
+            .iter()
+            .map(|(&id, &(generation, index))| (Entity::new(id, generation), index));
+        dense.chain(overflow)
+    }
+}
+
+/// Dense component storage using a sparse-set entity index
+///
+/// Same layout and API as `SoAStorage` (dense component array, swap-remove,
+/// index-aligned entity list), but entity lookups go through a
+/// `SparseEntityIndex` instead of a `HashMap`. Prefer this for lookup-heavy
+/// workloads such as the gravity inner loop, where every pair does a
+/// `positions.get()` and `masses.get()`.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::ecs::{ComponentStorage, SparseSoAStorage, World};
+/// use physics_engine::ecs::components::Position;
+///
+/// let mut world = World::new();
+/// let stale = world.create_entity();
+/// world.destroy_entity(stale);
+/// let entity = world.create_entity(); // same ID, next generation
+///
+/// let mut storage = SparseSoAStorage::<Position>::new();
+/// storage.insert(entity, Position::new(1.0, 2.0, 3.0));
+/// assert!(storage.contains(entity));
+/// assert!(!storage.contains(stale)); // stale generation misses
+/// ```
+pub struct SparseSoAStorage<T: Component + Copy> {
+    /// Mapping from Entity to dense array index
+    entity_to_index: SparseEntityIndex,
+    /// Mapping from dense array index back to Entity (for swap_remove)
+    index_to_entity: Vec<Entity>,
+    /// The actual component data stored densely
+    components: Vec<T>,
+}
+
+impl<T: Component + Copy> SparseSoAStorage<T> {
+    /// Create a new empty sparse-set storage
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new sparse-set storage with the given capacity
+    pub fn with_capacity(capacity: usize) -> Self {
+        SparseSoAStorage {
+            entity_to_index: SparseEntityIndex::with_capacity(capacity),
+            index_to_entity: Vec::with_capacity(capacity),
+            components: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Get the number of components stored
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Check if the storage is empty
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Reserve space for at least `additional` more components
+    pub fn reserve(&mut self, additional: usize) {
+        self.index_to_entity.reserve(additional);
+        self.components.reserve(additional);
+    }
+
+    /// Get all entities that have components in this storage
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.index_to_entity.iter().copied()
+    }
+
+    /// Get the dense entity array, aligned index-for-index with `components()`
+    pub fn dense_entities(&self) -> &[Entity] {
+        &self.index_to_entity
+    }
+
+    /// Get a reference to the dense component array
+    pub fn components(&self) -> &[T] {
+        &self.components
+    }
+
+    /// Get a mutable reference to the dense component array
+    pub fn components_mut(&mut self) -> &mut [T] {
+        &mut self.components
+    }
+
+    /// Get the index for an entity, if it exists
+    pub fn get_index(&self, entity: Entity) -> Option<usize> {
+        self.entity_to_index.get(&entity).copied()
+    }
+
+    /// Release unused capacity after a wave of removals
+    pub fn compact(&mut self) {
+        self.components.shrink_to_fit();
+        self.index_to_entity.shrink_to_fit();
+        self.entity_to_index.shrink_to_fit();
+    }
+
+    /// Check internal invariants for testing and debugging
+    ///
+    /// Validates that the sparse index, entity list, and component array
+    /// have the same length and that mappings are bidirectional. Available
+    /// in tests and with the `debug-invariants` feature.
+    #[cfg(any(test, feature = "debug-invariants"))]
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if self.entity_to_index.len() != self.index_to_entity.len() {
+            return Err(format!(
+                "Length mismatch: entity_to_index={}, index_to_entity={}",
+                self.entity_to_index.len(),
+                self.index_to_entity.len()
+            ));
+        }
+        if self.entity_to_index.len() != self.components.len() {
+            return Err(format!(
+                "Length mismatch: entity_to_index={}, components={}",
+                self.entity_to_index.len(),
+                self.components.len()
+            ));
+        }
+
+        for (entity, index) in self.entity_to_index.iter() {
+            if index >= self.index_to_entity.len() {
+                return Err(format!(
+                    "Entity {:?} maps to out-of-bounds index {}",
+                    entity, index
+                ));
+            }
+            if self.index_to_entity[index] != entity {
+                return Err(format!(
+                    "Mapping inconsistency: entity {:?} -> index {}, but index {} -> entity {:?}",
+                    entity, index, index, self.index_to_entity[index]
+                ));
+            }
+        }
+
+        // Every dense entity must resolve back to its own index; with a
+        // bidirectional mapping this also rules out duplicates
+        for (i, entity) in self.index_to_entity.iter().enumerate() {
+            if self.entity_to_index.get(entity) != Some(&i) {
+                return Err(format!(
+                    "Duplicate or unmapped entity {:?} at index {}",
+                    entity, i
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Component + Copy> Default for SparseSoAStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Component + Copy> ComponentStorage for SparseSoAStorage<T> {
+    type Component = T;
+
+    fn insert(&mut self, entity: Entity, component: Self::Component) {
+        if let Some(&index) = self.entity_to_index.get(&entity) {
+            // Entity already exists, update in place
+            self.components[index] = component;
+            return;
+        }
+
+        // A different generation of this ID still holds a slot; it is stale
+        if let Some(stale) = self.entity_to_index.stale_entity(&entity) {
+            self.remove(stale);
+        }
+
+        let new_index = self.components.len();
+        self.components.push(component);
+        self.entity_to_index.insert(entity, new_index);
+        self.index_to_entity.push(entity);
+
+        debug_assert_eq!(self.entity_to_index.len(), self.index_to_entity.len());
+        debug_assert_eq!(self.entity_to_index.len(), self.components.len());
+    }
+
+    fn remove(&mut self, entity: Entity) -> Option<Self::Component> {
+        let index = self.entity_to_index.remove(&entity)?;
+        let component = self.components.swap_remove(index);
+        self.index_to_entity.swap_remove(index);
+
+        // Update the entity that was moved into the vacated slot
+        if index < self.index_to_entity.len() {
+            let swapped_entity = self.index_to_entity[index];
+            *self.entity_to_index.get_mut(&swapped_entity)
+                .expect("Internal invariant violated: entity in index_to_entity but not in entity_to_index") = index;
+        }
+
+        debug_assert_eq!(self.entity_to_index.len(), self.index_to_entity.len());
+        debug_assert_eq!(self.entity_to_index.len(), self.components.len());
+
+        Some(component)
+    }
+
+    fn get(&self, entity: Entity) -> Option<&Self::Component> {
+        let index = self.entity_to_index.get(&entity)?;
+        Some(&self.components[*index])
+    }
+
+    fn get_mut(&mut self, entity: Entity) -> Option<&mut Self::Component> {
+        let index = self.entity_to_index.get(&entity)?;
+        Some(&mut self.components[*index])
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.entity_to_index.contains_key(&entity)
+    }
+
+    fn clear(&mut self) {
+        self.entity_to_index.clear();
+        self.index_to_entity.clear();
+        self.components.clear();
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        let capacity = self.components.capacity();
+        MemoryReport {
+            entity_capacity: capacity,
+            component_bytes: capacity * std::mem::size_of::<T>(),
+            map_overhead_estimate: self.entity_to_index.heap_bytes()
+                + self.index_to_entity.capacity() * std::mem::size_of::<Entity>(),
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.compact();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{Position, Velocity, Mass};
+    use crate::real::Real;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct TestComponent {
+        x: f32,
+        y: f32,
+    }
+
+    impl Component for TestComponent {}
+
+    #[test]
+    fn test_sparse_index_generation_mismatch() {
+        let mut index = SparseEntityIndex::new();
+        index.insert(Entity::new(3, 2), 7);
+
+        assert_eq!(index.get(&Entity::new(3, 2)), Some(&7));
+        assert_eq!(index.get(&Entity::new(3, 1)), None);
+        assert_eq!(index.get(&Entity::new(3, 3)), None);
+        assert_eq!(index.remove(&Entity::new(3, 1)), None);
+        assert_eq!(index.stale_entity(&Entity::new(3, 3)), Some(Entity::new(3, 2)));
+        assert_eq!(index.stale_entity(&Entity::new(3, 2)), None);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_sparse_index_grows_for_large_ids() {
+        let mut index = SparseEntityIndex::new();
+        index.insert(Entity::new(100_000, 0), 0);
+        index.insert(Entity::new(5, 0), 1);
+
+        assert_eq!(index.get(&Entity::new(100_000, 0)), Some(&0));
+        assert_eq!(index.get(&Entity::new(5, 0)), Some(&1));
+        assert_eq!(index.get(&Entity::new(200_000, 0)), None);
+        assert_eq!(index.len(), 2);
+
+        index.remove(&Entity::new(100_000, 0));
+        index.shrink_to_fit();
+        assert_eq!(index.get(&Entity::new(5, 0)), Some(&1));
+        assert_eq!(index.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_sparse_index_huge_id_does_not_grow_slots() {
+        let mut index = SparseEntityIndex::new();
+        let huge = Entity::new(u64::MAX >> 1, 4);
+        let edge = Entity::new(DENSE_ID_LIMIT, 0);
+        index.insert(Entity::new(2, 0), 0);
+        assert_eq!(index.insert(huge, 1), None);
+        index.insert(edge, 2);
+
+        // Only the small ID occupies the sparse array
+        assert_eq!(index.slots.len(), 3);
+        assert_eq!(index.get(&huge), Some(&1));
+        assert_eq!(index.get(&Entity::new(u64::MAX >> 1, 3)), None);
+        assert_eq!(index.stale_entity(&Entity::new(u64::MAX >> 1, 5)), Some(huge));
+        assert_eq!(index.insert(huge, 5), Some(1));
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            index.iter().collect::<Vec<_>>(),
+            vec![(Entity::new(2, 0), 0), (edge, 2), (huge, 5)]
+        );
+
+        assert_eq!(index.remove(&huge), Some(5));
+        assert_eq!(index.get(&huge), None);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_sparse_storage_large_id() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let entity = Entity::new(u64::MAX >> 1, 0);
+        storage.insert(entity, TestComponent { x: 1.0, y: 2.0 });
+        storage.insert(Entity::new(0, 0), TestComponent { x: 3.0, y: 4.0 });
+
+        assert_eq!(storage.get(entity).unwrap().x, 1.0);
+        assert!(storage.memory_report().map_overhead_estimate < 1024);
+        storage.remove(Entity::new(0, 0));
+        assert!(storage.check_invariants().is_ok());
+        assert_eq!(storage.remove(entity).unwrap().y, 2.0);
+        assert!(storage.is_empty());
+    }
+
+    #[test]
+    fn test_sparse_storage_basic() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let entity = Entity::new(1, 0);
+
+        let comp = TestComponent { x: 10.0, y: 20.0 };
+        storage.insert(entity, comp);
+
+        assert!(storage.contains(entity));
+        assert_eq!(storage.get(entity).unwrap().x, 10.0);
+        assert_eq!(storage.get(entity).unwrap().y, 20.0);
+
+        let removed = storage.remove(entity);
+        assert_eq!(removed, Some(comp));
+        assert!(!storage.contains(entity));
+    }
+
+    #[test]
+    fn test_sparse_storage_multiple_entities() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let e1 = Entity::new(1, 0);
+        let e2 = Entity::new(2, 0);
+        let e3 = Entity::new(3, 0);
+
+        storage.insert(e1, TestComponent { x: 1.0, y: 2.0 });
+        storage.insert(e2, TestComponent { x: 3.0, y: 4.0 });
+        storage.insert(e3, TestComponent { x: 5.0, y: 6.0 });
+
+        assert_eq!(storage.len(), 3);
+        assert_eq!(storage.get(e2).unwrap().x, 3.0);
+
+        // Remove middle element (swap_remove behavior)
+        storage.remove(e2);
+        assert_eq!(storage.len(), 2);
+        assert!(!storage.contains(e2));
+        assert_eq!(storage.get(e1).unwrap().x, 1.0);
+        assert_eq!(storage.get(e3).unwrap().x, 5.0);
+    }
+
+    #[test]
+    fn test_sparse_storage_update() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let entity = Entity::new(1, 0);
+
+        storage.insert(entity, TestComponent { x: 1.0, y: 2.0 });
+        storage.insert(entity, TestComponent { x: 10.0, y: 20.0 });
+        assert_eq!(storage.len(), 1);
+        assert_eq!(storage.get(entity).unwrap().x, 10.0);
+    }
+
+    #[test]
+    fn test_sparse_storage_get_mut() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let entity = Entity::new(1, 0);
+
+        storage.insert(entity, TestComponent { x: 1.0, y: 2.0 });
+        if let Some(comp) = storage.get_mut(entity) {
+            comp.x = 100.0;
+        }
+        assert_eq!(storage.get(entity).unwrap().x, 100.0);
+    }
+
+    #[test]
+    fn test_sparse_storage_clear() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+
+        storage.insert(Entity::new(1, 0), TestComponent { x: 1.0, y: 2.0 });
+        storage.insert(Entity::new(2, 0), TestComponent { x: 3.0, y: 4.0 });
+        storage.clear();
+        assert!(storage.is_empty());
+        assert!(storage.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn test_sparse_storage_entity_generations() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let e1_gen0 = Entity::new(1, 0);
+        let e1_gen1 = Entity::new(1, 1);
+
+        storage.insert(e1_gen0, TestComponent { x: 1.0, y: 2.0 });
+        assert!(storage.contains(e1_gen0));
+        assert!(!storage.contains(e1_gen1)); // Different generation
+
+        storage.remove(e1_gen0);
+        assert!(!storage.contains(e1_gen0));
+
+        storage.insert(e1_gen1, TestComponent { x: 10.0, y: 20.0 });
+        assert!(!storage.contains(e1_gen0));
+        assert!(storage.contains(e1_gen1));
+    }
+
+    #[test]
+    fn test_sparse_storage_new_generation_evicts_stale() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let other = Entity::new(0, 0);
+        let e1_gen0 = Entity::new(1, 0);
+        let e1_gen1 = Entity::new(1, 1);
+
+        storage.insert(e1_gen0, TestComponent { x: 1.0, y: 0.0 });
+        storage.insert(other, TestComponent { x: 2.0, y: 0.0 });
+
+        // The ID was recycled without removing the old component
+        storage.insert(e1_gen1, TestComponent { x: 3.0, y: 0.0 });
+
+        assert_eq!(storage.len(), 2);
+        assert!(!storage.contains(e1_gen0));
+        assert_eq!(storage.get(e1_gen1).unwrap().x, 3.0);
+        assert_eq!(storage.get(other).unwrap().x, 2.0);
+        assert!(storage.check_invariants().is_ok());
+    }
+
+    #[test]
+    fn test_sparse_storage_rapid_creation_destruction() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+
+        for i in 0..100 {
+            storage.insert(Entity::new(i, 0), TestComponent { x: i as f32, y: i as f32 * 2.0 });
+        }
+        for i in (1..100).step_by(2) {
+            storage.remove(Entity::new(i, 0));
+        }
+        assert_eq!(storage.len(), 50);
+
+        for i in (0..100).step_by(2) {
+            let entity = Entity::new(i, 0);
+            assert_eq!(storage.get(entity).unwrap().x, i as f32);
+        }
+    }
+
+    #[test]
+    fn test_sparse_storage_large_entity_count() {
+        let mut storage = SparseSoAStorage::<TestComponent>::with_capacity(10000);
+
+        for i in 0..10000 {
+            storage.insert(Entity::new(i as u64, 0), TestComponent { x: i as f32, y: 0.0 });
+        }
+        assert_eq!(storage.len(), 10000);
+        assert_eq!(storage.get(Entity::new(5555, 0)).unwrap().x, 5555.0);
+
+        storage.clear();
+        assert_eq!(storage.len(), 0);
+    }
+
+    #[test]
+    fn test_sparse_storage_compact_after_removals() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+
+        for i in 0..10000 {
+            storage.insert(Entity::new(i as u64, 0), TestComponent { x: i as f32, y: 0.0 });
+        }
+        for i in 0..9000 {
+            storage.remove(Entity::new(i as u64, 0));
+        }
+        storage.compact();
+        assert!(storage.check_invariants().is_ok());
+
+        for i in 9000..10000 {
+            assert_eq!(storage.get(Entity::new(i as u64, 0)).unwrap().x, i as f32);
+        }
+    }
+
+    #[test]
+    fn test_sparse_storage_entities_iter() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        let e1 = Entity::new(1, 0);
+        let e2 = Entity::new(2, 0);
+
+        storage.insert(e1, TestComponent { x: 1.0, y: 2.0 });
+        storage.insert(e2, TestComponent { x: 3.0, y: 4.0 });
+
+        let entities: Vec<Entity> = storage.entities().collect();
+        assert_eq!(entities, vec![e1, e2]);
+        assert_eq!(storage.dense_entities(), &[e1, e2]);
+        assert_eq!(storage.get_index(e2), Some(1));
+    }
+
+    #[test]
+    fn test_sparse_storage_with_physics_components() {
+        let mut positions = SparseSoAStorage::<Position>::new();
+        let mut velocities = SparseSoAStorage::<Velocity>::new();
+        let mut masses = SparseSoAStorage::<Mass>::new();
+
+        for i in 0..100 {
+            let entity = Entity::new(i, 0);
+            positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
+            velocities.insert(entity, Velocity::new(1.0, 0.0, 0.0));
+            masses.insert(entity, Mass::new(1.0));
+        }
+
+        for (pos, vel) in positions.components_mut().iter_mut().zip(velocities.components()) {
+            pos.set_x(pos.x() + vel.dx());
+        }
+        assert_eq!(positions.get(Entity::new(10, 0)).unwrap().x(), 11.0);
+        assert_eq!(masses.len(), 100);
+    }
+
+    #[test]
+    fn test_sparse_storage_invariants() {
+        let mut storage = SparseSoAStorage::<TestComponent>::new();
+        assert!(storage.check_invariants().is_ok());
+
+        for i in 0..10 {
+            storage.insert(Entity::new(i, 0), TestComponent { x: i as f32, y: i as f32 * 2.0 });
+            assert!(storage.check_invariants().is_ok(),
+                "Invariants violated after inserting entity {}", i);
+        }
+
+        for i in (0..10).step_by(2) {
+            storage.remove(Entity::new(i, 0));
+            assert!(storage.check_invariants().is_ok(),
+                "Invariants violated after removing entity {}", i);
+        }
+
+        for i in (1..10).step_by(2) {
+            storage.insert(Entity::new(i, 0), TestComponent { x: 100.0, y: 200.0 });
+            assert!(storage.check_invariants().is_ok(),
+                "Invariants violated after updating entity {}", i);
+        }
+
+        storage.clear();
+        assert!(storage.check_invariants().is_ok());
+        assert_eq!(storage.len(), 0);
+    }
+
+    #[test]
+    fn test_sparse_storage_implements_component_storage() {
+        fn test_storage<S: ComponentStorage<Component = Position>>(mut storage: S) {
+            let entity = Entity::new(1, 0);
+            storage.insert(entity, Position::new(1.0, 2.0, 3.0));
+            assert!(storage.contains(entity));
+            storage.clear();
+        }
+
+        test_storage(SparseSoAStorage::<Position>::new());
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Bundle of the four Newtonian component storages
+//!
+//! Most systems take positions, velocities, accelerations, and masses as
+//! four separate arguments in a fixed order. `NewtonianState` keeps the four
+//! storages together, each bound to its component type, so call sites pass
+//! one argument and the storages cannot be handed over in the wrong order:
+//!
+//! ```
+//! use physics_engine::ecs::{NewtonianState, World};
+//! use physics_engine::ecs::components::{Mass, Position, Velocity};
+//! use physics_engine::ecs::systems::{apply_forces_to_state, ForceRegistry};
+//! use physics_engine::integration::{Integrator, VelocityVerletIntegrator};
+//! use physics_engine::plugins::gravity::{GravityPlugin, GravitySystem};
+//! use std::collections::HashMap;
+//!
+//! let mut world = World::new();
+//! let mut state = NewtonianState::hashmap();
+//! let bodies = [world.create_entity(), world.create_entity()];
+//! state.insert_body(bodies[0], Position::new(-1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+//! state.insert_body(bodies[1], Position::new(1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+//!
+//! let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+//! let mut registry = ForceRegistry::new();
+//! let mut forces = HashMap::new();
+//! let mut integrator = VelocityVerletIntegrator::new(0.01);
+//!
+//! gravity.compute_forces_for_state(&bodies, &state, &mut forces);
+//! registry.register_forces(&forces);
+//! apply_forces_to_state(bodies.iter(), &registry, &mut state, false);
+//! integrator.integrate_state(bodies.iter(), &mut state, &mut registry, false);
+//! ```
+//!
+//! Any storage layout works; `hashmap()` and `soa()` build the two common
+//! ones. Storages of the wrong component type, such as positions and
+//! velocities passed in swapped order, are rejected at compile time; see
+//! `tests/ui/newtonian_state_swapped.rs`.
+
+use crate::ecs::{
+    AccelerationSoAStorage, ComponentStorage, Entity, HashMapStorage, MassSoAStorage, PositionSoAStorage,
+    VelocitySoAStorage,
+};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+
+/// Position, velocity, acceleration, and mass storages kept together
+///
+/// Generic over the storage types, which default to `HashMapStorage`. See
+/// `SoANewtonianState` for the Structure-of-Arrays layout.
+pub struct NewtonianState<
+    P = HashMapStorage<Position>,
+    V = HashMapStorage<Velocity>,
+    A = HashMapStorage<Acceleration>,
+    M = HashMapStorage<Mass>,
+> {
+    pub(crate) positions: P,
+    pub(crate) velocities: V,
+    pub(crate) accelerations: A,
+    pub(crate) masses: M,
+}
+
+/// `NewtonianState` over the aligned Structure-of-Arrays storages
+pub type SoANewtonianState = NewtonianState<PositionSoAStorage, VelocitySoAStorage, AccelerationSoAStorage, MassSoAStorage>;
+
+impl<P, V, A, M> NewtonianState<P, V, A, M>
+where
+    P: ComponentStorage<Component = Position>,
+    V: ComponentStorage<Component = Velocity>,
+    A: ComponentStorage<Component = Acceleration>,
+    M: ComponentStorage<Component = Mass>,
+{
+    /// Bundle existing storages
+    pub fn new(positions: P, velocities: V, accelerations: A, masses: M) -> Self {
+        NewtonianState {
+            positions,
+            velocities,
+            accelerations,
+            masses,
+        }
+    }
+
+    /// Split the bundle back into its storages
+    pub fn into_parts(self) -> (P, V, A, M) {
+        (self.positions, self.velocities, self.accelerations, self.masses)
+    }
+
+    /// Get the position storage
+    pub fn positions(&self) -> &P {
+        &self.positions
+    }
+
+    /// Get the position storage mutably
+    pub fn positions_mut(&mut self) -> &mut P {
+        &mut self.positions
+    }
+
+    /// Get the velocity storage
+    pub fn velocities(&self) -> &V {
+        &self.velocities
+    }
+
+    /// Get the velocity storage mutably
+    pub fn velocities_mut(&mut self) -> &mut V {
+        &mut self.velocities
+    }
+
+    /// Get the acceleration storage
+    pub fn accelerations(&self) -> &A {
+        &self.accelerations
+    }
+
+    /// Get the acceleration storage mutably
+    pub fn accelerations_mut(&mut self) -> &mut A {
+        &mut self.accelerations
+    }
+
+    /// Get the mass storage
+    pub fn masses(&self) -> &M {
+        &self.masses
+    }
+
+    /// Get the mass storage mutably
+    pub fn masses_mut(&mut self) -> &mut M {
+        &mut self.masses
+    }
+
+    /// Borrow the storages the way integrators need them
+    ///
+    /// Positions and velocities are mutable; accelerations and masses are
+    /// read-only.
+    pub fn split_mut(&mut self) -> (&mut P, &mut V, &A, &M) {
+        (&mut self.positions, &mut self.velocities, &self.accelerations, &self.masses)
+    }
+
+    /// Insert a body with a zero acceleration
+    pub fn insert_body(&mut self, entity: Entity, position: Position, velocity: Velocity, mass: Mass) {
+        self.positions.insert(entity, position);
+        self.velocities.insert(entity, velocity);
+        self.accelerations.insert(entity, Acceleration::zero());
+        self.masses.insert(entity, mass);
+    }
+
+    /// Remove an entity's components from all four storages
+    ///
+    /// Returns true if any storage held a component for the entity.
+    pub fn remove_body(&mut self, entity: Entity) -> bool {
+        let removed = [
+            self.positions.remove(entity).is_some(),
+            self.velocities.remove(entity).is_some(),
+            self.accelerations.remove(entity).is_some(),
+            self.masses.remove(entity).is_some(),
+        ];
+        removed.contains(&true)
+    }
+
+    /// Check if all four storages hold a component for the entity
+    pub fn contains_body(&self, entity: Entity) -> bool {
+        self.positions.contains(entity)
+            && self.velocities.contains(entity)
+            && self.accelerations.contains(entity)
+            && self.masses.contains(entity)
+    }
+}
+
+impl NewtonianState {
+    /// Create an empty state backed by `HashMapStorage`s
+    pub fn hashmap() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create an empty `HashMapStorage`-backed state with room for `capacity` bodies
+    pub fn with_capacity(capacity: usize) -> Self {
+        NewtonianState::new(
+            HashMapStorage::with_capacity(capacity),
+            HashMapStorage::with_capacity(capacity),
+            HashMapStorage::with_capacity(capacity),
+            HashMapStorage::with_capacity(capacity),
+        )
+    }
+}
+
+impl SoANewtonianState {
+    /// Create an empty state backed by the SoA storages
+    ///
+    /// Bodies inserted with `insert_body()` land at the same dense index in
+    /// every storage, which keeps the integrators on their dense paths.
+    pub fn soa() -> Self {
+        Self::soa_with_capacity(0)
+    }
+
+    /// Create an empty SoA-backed state with room for `capacity` bodies
+    pub fn soa_with_capacity(capacity: usize) -> Self {
+        NewtonianState::new(
+            PositionSoAStorage::with_capacity(capacity),
+            VelocitySoAStorage::with_capacity(capacity),
+            AccelerationSoAStorage::with_capacity(capacity),
+            MassSoAStorage::with_capacity(capacity),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::systems::{apply_forces_to_acceleration, apply_forces_to_state, ForceRegistry};
+    use crate::integration::{Integrator, RK4Integrator, VelocityVerletIntegrator};
+    use crate::plugins::gravity::{GravityPlugin, GravitySystem};
+    use crate::real::Real;
+    use std::collections::HashMap;
+
+    fn populate<P, V, A, M>(state: &mut NewtonianState<P, V, A, M>) -> Vec<Entity>
+    where
+        P: ComponentStorage<Component = Position>,
+        V: ComponentStorage<Component = Velocity>,
+        A: ComponentStorage<Component = Acceleration>,
+        M: ComponentStorage<Component = Mass>,
+    {
+        (0..6)
+            .map(|i| {
+                let entity = Entity::new(i, 0);
+                let t = i as Real;
+                state.insert_body(
+                    entity,
+                    Position::new(t.cos() * (1.0 + t), t.sin(), 0.1 * t),
+                    Velocity::new(-0.1 * t, 0.2, 0.0),
+                    Mass::new(1.0 + 0.5 * t),
+                );
+                entity
+            })
+            .collect()
+    }
+
+    /// Run gravity, force conversion, and `steps` Verlet or RK4 steps
+    /// through the bundle and through the explicit-argument API
+    fn assert_bundle_matches_explicit<P, V, A, M>(mut make: impl FnMut() -> NewtonianState<P, V, A, M>, rk4: bool)
+    where
+        P: ComponentStorage<Component = Position>,
+        V: ComponentStorage<Component = Velocity>,
+        A: ComponentStorage<Component = Acceleration>,
+        M: ComponentStorage<Component = Mass>,
+    {
+        let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+        let mut bundled = make();
+        let entities = populate(&mut bundled);
+        let (mut positions, mut velocities, mut accelerations, masses) = {
+            let mut explicit = make();
+            populate(&mut explicit);
+            explicit.into_parts()
+        };
+
+        let mut verlet = [VelocityVerletIntegrator::new(0.01), VelocityVerletIntegrator::new(0.01)];
+        let mut runge = [RK4Integrator::new(0.01), RK4Integrator::new(0.01)];
+        let mut registries = [ForceRegistry::new(), ForceRegistry::new()];
+        let mut forces = HashMap::new();
+        for _ in 0..20 {
+            let [bundled_registry, explicit_registry] = &mut registries;
+
+            bundled_registry.clear();
+            gravity.compute_forces_for_state(&entities, &bundled, &mut forces);
+            bundled_registry.register_forces(&forces);
+            apply_forces_to_state(entities.iter(), bundled_registry, &mut bundled, false);
+
+            explicit_registry.clear();
+            gravity.compute_forces_into(&entities, &positions, &masses, &mut forces);
+            explicit_registry.register_forces(&forces);
+            apply_forces_to_acceleration(entities.iter(), explicit_registry, &masses, &mut accelerations, false);
+
+            if rk4 {
+                runge[0].integrate_state(entities.iter(), &mut bundled, bundled_registry, false);
+                runge[1].integrate(entities.iter(), &mut positions, &mut velocities, &accelerations, &masses, explicit_registry, false);
+            } else {
+                verlet[0].integrate_state(entities.iter(), &mut bundled, bundled_registry, false);
+                verlet[1].integrate(entities.iter(), &mut positions, &mut velocities, &accelerations, &masses, explicit_registry, false);
+            }
+        }
+
+        for entity in &entities {
+            assert_eq!(bundled.positions().get(*entity), positions.get(*entity));
+            assert_eq!(bundled.velocities().get(*entity), velocities.get(*entity));
+            assert_eq!(bundled.accelerations().get(*entity), accelerations.get(*entity));
+        }
+    }
+
+    #[test]
+    fn test_bundle_calls_match_explicit_arguments() {
+        for rk4 in [false, true] {
+            assert_bundle_matches_explicit(NewtonianState::hashmap, rk4);
+            assert_bundle_matches_explicit(NewtonianState::soa, rk4);
+        }
+    }
+
+    #[test]
+    fn test_soa_bodies_stay_aligned() {
+        let mut state = NewtonianState::soa_with_capacity(8);
+        let entities = populate(&mut state);
+        assert!(state.remove_body(entities[1]));
+        assert!(!state.remove_body(entities[1]));
+        assert!(!state.contains_body(entities[1]));
+        assert!(state.contains_body(entities[2]));
+
+        let (positions, velocities, accelerations, masses) = state.split_mut();
+        let order = positions.dense_entities().to_vec();
+        assert_eq!(velocities.dense_entities(), order.as_slice());
+        assert_eq!(accelerations.dense_entities(), order.as_slice());
+        assert_eq!(masses.dense_entities(), order.as_slice());
+    }
+}
@@ -17,24 +17,40 @@
 //! Systems are designed to be generic and configurable via plugins rather than
 //! hardcoding specific simulation constants.
 
-use crate::ecs::{Entity, ComponentStorage};
-use crate::ecs::components::{Acceleration, Mass, Velocity};
-use std::collections::HashMap;
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage, MassSoAStorage, AccelerationSoAStorage, NewtonianState};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+use crate::integration::{dense, simd_axpy};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 /// Represents a 3D force vector
+///
+/// Forces combine with `+`, `-`, `+=`, unary `-` and multiplication by a
+/// scalar:
+///
+/// ```
+/// use physics_engine::ecs::systems::Force;
+///
+/// let thrust = Force::new(10.0, 0.0, 0.0);
+/// let drag = Force::new(-2.0, 1.0, 0.0);
+/// let mut net = thrust + drag * 0.5;
+/// net += -drag;
+/// assert_eq!(net, Force::new(11.0, -0.5, 0.0));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Force {
     /// X component of the force in Newtons
-    pub fx: f64,
+    pub fx: Real,
     /// Y component of the force in Newtons
-    pub fy: f64,
+    pub fy: Real,
     /// Z component of the force in Newtons
-    pub fz: f64,
+    pub fz: Real,
 }
 
 impl Force {
     /// Create a new force vector
-    pub fn new(fx: f64, fy: f64, fz: f64) -> Self {
+    pub fn new(fx: Real, fy: Real, fz: Real) -> Self {
         Force { fx, fy, fz }
     }
 
@@ -49,22 +65,72 @@ impl Force {
     }
 
     /// Add another force to this one
+    ///
+    /// Equivalent to `*self += *other`.
     pub fn add(&mut self, other: &Force) {
         self.fx += other.fx;
         self.fy += other.fy;
         self.fz += other.fz;
     }
 
+    /// Multiply every component by `factor`
+    ///
+    /// Equivalent to `self * factor`.
+    pub fn scale(&self, factor: Real) -> Force {
+        Force::new(self.fx * factor, self.fy * factor, self.fz * factor)
+    }
+
     /// Get the magnitude of the force
-    pub fn magnitude(&self) -> f64 {
+    pub fn magnitude(&self) -> Real {
         (self.fx * self.fx + self.fy * self.fy + self.fz * self.fz).sqrt()
     }
 }
 
+impl std::ops::Add for Force {
+    type Output = Force;
+
+    fn add(self, other: Force) -> Force {
+        Force::new(self.fx + other.fx, self.fy + other.fy, self.fz + other.fz)
+    }
+}
+
+impl std::ops::AddAssign for Force {
+    fn add_assign(&mut self, other: Force) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::Sub for Force {
+    type Output = Force;
+
+    fn sub(self, other: Force) -> Force {
+        Force::new(self.fx - other.fx, self.fy - other.fy, self.fz - other.fz)
+    }
+}
+
+impl std::ops::Mul<Real> for Force {
+    type Output = Force;
+
+    fn mul(self, factor: Real) -> Force {
+        self.scale(factor)
+    }
+}
+
+impl std::ops::Neg for Force {
+    type Output = Force;
+
+    fn neg(self) -> Force {
+        Force::new(-self.fx, -self.fy, -self.fz)
+    }
+}
+
 /// Trait for force providers that can be registered with the force registry
 ///
 /// Force providers compute forces based on entity state and can represent
 /// gravity, springs, drag, user input, or any other force-generating mechanism.
+/// Time-dependent providers read the simulation time of the evaluation from
+/// `ForceRegistry::time()`, and velocity-dependent ones such as drag read the
+/// velocity each entity is evaluated at from `ForceRegistry::velocity()`.
 pub trait ForceProvider: Send + Sync {
     /// Compute the force to apply to a specific entity
     ///
@@ -76,23 +142,118 @@ pub trait ForceProvider: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Stable handle to a provider registered with a `ForceRegistry`
+///
+/// Ids are handed out in increasing order and never reused by the registry
+/// that issued them, so ordering ids orders providers by registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProviderId(u64);
+
+/// How a provider's force combines with the others'
+///
+/// See "Override Providers" on `ForceRegistry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ProviderTier {
+    /// The force is summed with every other normal provider's (the default)
+    #[default]
+    Normal,
+    /// A force returned for an entity replaces all normal forces on it
+    Override,
+}
+
+/// A registered provider, its tier and its enabled flag
+struct ProviderEntry {
+    id: ProviderId,
+    tier: ProviderTier,
+    enabled: bool,
+    provider: Box<dyn ForceProvider>,
+}
+
 /// Registry for managing force providers and accumulating forces per entity
 ///
 /// The force registry allows plugins to register arbitrary force providers
 /// that will be applied to entities during physics updates. Forces are
 /// accumulated per entity and can be used to compute accelerations.
 ///
+/// # Provider Order
+///
+/// Providers are evaluated in registration order, and their forces summed in
+/// that order. Removing a provider does not reorder the rest, and providers
+/// registered later always come after every existing one. Since floating
+/// point addition is not associative, this keeps accumulated forces (and the
+/// magnitude clamp applied to them) reproducible. Disabled providers are
+/// skipped but keep their place.
+///
+/// # Override Providers
+///
+/// A provider registered with `ProviderTier::Override` can take full control
+/// of an entity, for teleport fields or script-driven bodies. Override
+/// providers are evaluated first, in registration order; the first one that
+/// returns a force for an entity decides it alone. Later overrides and every
+/// normal provider are skipped for that entity, and
+/// `overriding_provider()` reports which provider fired. Entities no
+/// override returns a force for accumulate exactly as if no override were
+/// registered. The magnitude clamp applies to overridden forces as well.
+///
+/// # Simulation Time
+///
+/// The registry carries the simulation time at which forces are being
+/// evaluated, readable by providers through `time()`. Integrators take it as
+/// the start of the step, set it to each stage's time before evaluating
+/// forces there, and leave it at the end of the step, so repeated steps
+/// advance it automatically. Call `set_time()` to start elsewhere or to
+/// resynchronize with an external clock.
+///
+/// # Evaluation Velocities
+///
+/// Likewise, the registry carries the velocity of each entity at the state
+/// forces are evaluated at, for providers whose forces depend on it. Call
+/// `set_velocities()` before accumulating forces from a storage. RK4 sets
+/// the velocities of each of its stages, so drag-like forces keep its fourth
+/// order, and leaves the end-of-step velocities behind. The other
+/// integrators leave the velocities untouched, so their evaluations see the
+/// velocities of the start of the step.
+///
 /// # Logging
 ///
-/// Currently uses `eprintln!` for warnings. Future versions will integrate with
-/// the `log` crate to allow configurable logging handlers.
+/// Warnings go to stderr by default. Install a handler with
+/// `set_warning_handler()` to route them into your own logging instead.
+/// Either way, warnings are only emitted while
+/// `set_warn_on_missing_components()` is enabled (the default).
+///
+/// Configuration is only reachable through methods, so new settings can be
+/// added without breaking callers:
+///
+/// ```compile_fail
+/// use physics_engine::ecs::systems::ForceRegistry;
+///
+/// let mut registry = ForceRegistry::new();
+/// registry.max_force_magnitude = 1e20; // use set_max_force_magnitude()
+/// ```
 pub struct ForceRegistry {
-    providers: Vec<Box<dyn ForceProvider>>,
+    /// Providers in registration order, so ids are strictly increasing
+    providers: Vec<ProviderEntry>,
+    next_provider_id: u64,
+    time: Real,
+    /// Velocities of the state forces are evaluated at
+    velocities: HashMap<Entity, Velocity>,
     accumulated_forces: HashMap<Entity, Force>,
+    /// Override provider that decided each entity's accumulated force
+    overridden: HashMap<Entity, ProviderId>,
+    warning_handler: Option<WarningHandler>,
+    min_mass: Real,
     /// Configuration for overflow/NaN detection
-    pub max_force_magnitude: f64,
+    max_force_magnitude: Real,
     /// Whether to log warnings for skipped entities
-    pub warn_on_missing_components: bool,
+    warn_on_missing_components: bool,
+    /// Largest acceleration magnitude produced from a force (None = unlimited)
+    max_acceleration: Option<Real>,
+    /// Entities skipped by force application and integration until woken
+    sleeping: HashSet<Entity>,
+    /// Accumulated force magnitude above which a sleeping entity wakes
+    wake_force_threshold: Real,
+    /// Maps behind `PrecomputedForces` providers, refilled once unshared
+    precomputed_maps: Vec<Arc<HashMap<Entity, Force>>>,
 }
 
 impl ForceRegistry {
@@ -100,29 +261,425 @@ impl ForceRegistry {
     pub fn new() -> Self {
         ForceRegistry {
             providers: Vec::new(),
+            next_provider_id: 0,
+            time: 0.0,
+            velocities: HashMap::new(),
             accumulated_forces: HashMap::new(),
+            overridden: HashMap::new(),
+            warning_handler: None,
+            min_mass: Mass::IMMOVABLE_THRESHOLD,
             max_force_magnitude: 1e10, // 10 billion Newtons default limit
             warn_on_missing_components: true,
+            max_acceleration: None,
+            sleeping: HashSet::new(),
+            wake_force_threshold: 0.0,
+            precomputed_maps: Vec::new(),
+        }
+    }
+
+    /// Set the mass below which bodies are treated as immovable
+    ///
+    /// `apply_forces_to_acceleration()` skips bodies lighter than this
+    /// instead of producing huge accelerations from a finite force. Masses
+    /// below `Mass::IMMOVABLE_THRESHOLD` are always immovable, so lower
+    /// values have no further effect.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_mass` is negative or not finite.
+    pub fn set_min_mass(&mut self, min_mass: Real) {
+        assert!(
+            min_mass >= 0.0 && min_mass.is_finite(),
+            "Minimum mass must be non-negative and finite"
+        );
+        self.min_mass = min_mass;
+    }
+
+    /// Get the effective mass below which bodies are treated as immovable
+    pub fn min_mass(&self) -> Real {
+        self.min_mass.max(Mass::IMMOVABLE_THRESHOLD)
+    }
+
+    /// Get the inverse mass (1/m) under this registry's minimum mass
+    ///
+    /// Unlike `Mass::inverse()`, which only treats masses below
+    /// `Mass::IMMOVABLE_THRESHOLD` as immovable, this returns 0.0 for every
+    /// body lighter than `min_mass()`.
+    pub fn inverse_mass(&self, mass: &Mass) -> Real {
+        if mass.value() < self.min_mass() {
+            0.0
+        } else {
+            mass.inverse()
+        }
+    }
+
+    /// Warn that a body is lighter than `min_mass()` and is treated as immovable
+    fn warn_below_min_mass(&self, entity: Entity, mass: &Mass) {
+        if mass.value() > 0.0 {
+            self.warn(&format!(
+                "Warning: Entity {:?} mass {:.2e} kg is below the minimum {:.2e} kg, treating as immovable",
+                entity,
+                mass.value(),
+                self.min_mass()
+            ));
+        }
+    }
+
+    /// Set the largest total force magnitude (N) accumulated for an entity
+    ///
+    /// Larger totals are scaled down to this magnitude, with a warning.
+    /// Defaults to 1e10 N.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_force` is not positive.
+    pub fn set_max_force_magnitude(&mut self, max_force: Real) {
+        assert!(max_force > 0.0, "Maximum force magnitude must be positive");
+        self.max_force_magnitude = max_force;
+    }
+
+    /// Get the largest total force magnitude (N) accumulated for an entity
+    pub fn max_force_magnitude(&self) -> Real {
+        self.max_force_magnitude
+    }
+
+    /// Set the largest acceleration magnitude (m/s²) produced from a force
+    ///
+    /// Accelerations computed by `acceleration_from_force()` above the limit
+    /// are scaled down to it, with a warning. This is separate from the force
+    /// clamp: a force within `max_force_magnitude()` can still give a huge
+    /// acceleration on a light body. `None` (the default) disables the limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the limit is not positive.
+    pub fn set_max_acceleration(&mut self, max_acceleration: Option<Real>) {
+        if let Some(limit) = max_acceleration {
+            assert!(limit > 0.0, "Maximum acceleration must be positive");
+        }
+        self.max_acceleration = max_acceleration;
+    }
+
+    /// Get the acceleration magnitude limit, if any
+    pub fn max_acceleration(&self) -> Option<Real> {
+        self.max_acceleration
+    }
+
+    /// Convert a force on a body to its acceleration (a = F/m)
+    ///
+    /// This is the single force-to-acceleration policy shared by
+    /// `apply_forces_to_acceleration()` and every integrator stage:
+    ///
+    /// - Immovable bodies and bodies lighter than `min_mass()` get None.
+    /// - Non-finite accelerations (e.g. from overflow) are rejected with None.
+    /// - Accelerations above `max_acceleration()` are scaled down to it.
+    pub fn acceleration_from_force(&self, force: &Force, mass: &Mass) -> Option<Acceleration> {
+        let inv_mass = self.inverse_mass(mass);
+        if inv_mass == 0.0 {
+            return None;
+        }
+        let [ax, ay, az] = self.limit_acceleration([
+            force.fx * inv_mass,
+            force.fy * inv_mass,
+            force.fz * inv_mass,
+        ])?;
+        Some(Acceleration::new(ax, ay, az))
+    }
+
+    /// Reject non-finite accelerations and apply the magnitude limit
+    fn limit_acceleration(&self, acceleration: [Real; 3]) -> Option<[Real; 3]> {
+        let (limited, clamped_from) = clamp_acceleration(acceleration, self.max_acceleration)?;
+        if let (Some(magnitude), Some(limit)) = (clamped_from, self.max_acceleration) {
+            self.warn(&format!(
+                "Warning: Acceleration magnitude {:.2e} exceeds limit {:.2e}, clamping",
+                magnitude, limit
+            ));
+        }
+        Some(limited)
+    }
+
+    /// Enable or disable warnings for skipped entities and clamped forces
+    pub fn set_warn_on_missing_components(&mut self, warn: bool) {
+        self.warn_on_missing_components = warn;
+    }
+
+    /// Check if warnings are enabled
+    pub fn warn_on_missing_components(&self) -> bool {
+        self.warn_on_missing_components
+    }
+
+    /// Set the simulation time (s) seen by force providers
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is not finite.
+    pub fn set_time(&mut self, time: Real) {
+        assert!(time.is_finite(), "Simulation time must be finite");
+        self.time = time;
+    }
+
+    /// Get the simulation time (s) of the current force evaluation
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
+    /// Set the velocity an entity's forces are evaluated at
+    pub fn set_velocity(&mut self, entity: Entity, velocity: Velocity) {
+        self.velocities.insert(entity, velocity);
+    }
+
+    /// Replace the evaluation velocities with those of `entities` in `velocities`
+    ///
+    /// Entities without a velocity in the storage are left without one.
+    pub fn set_velocities<'a, I>(&mut self, entities: I, velocities: &impl ComponentStorage<Component = Velocity>)
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        self.velocities.clear();
+        for entity in entities {
+            if let Some(velocity) = velocities.get(*entity) {
+                self.velocities.insert(*entity, *velocity);
+            }
+        }
+    }
+
+    /// Get the velocity of an entity for the current force evaluation
+    ///
+    /// Returns `None` if no velocity was set for the entity.
+    pub fn velocity(&self, entity: Entity) -> Option<Velocity> {
+        self.velocities.get(&entity).copied()
+    }
+
+    /// Put an entity to sleep
+    ///
+    /// Sleeping entities are skipped by `apply_forces_to_acceleration()` and
+    /// by the integrators, so they hold still at no integration cost. Forces
+    /// are still accumulated for them, and one larger than
+    /// `wake_force_threshold()` wakes the entity. `SleepSystem` calls this
+    /// for entities that have been quiet long enough.
+    pub fn put_to_sleep(&mut self, entity: Entity) {
+        self.sleeping.insert(entity);
+    }
+
+    /// Wake a sleeping entity, returning whether it was asleep
+    pub fn wake(&mut self, entity: Entity) -> bool {
+        self.sleeping.remove(&entity)
+    }
+
+    /// Check if an entity is asleep
+    pub fn is_sleeping(&self, entity: Entity) -> bool {
+        self.sleeping.contains(&entity)
+    }
+
+    /// Get the number of sleeping entities
+    pub fn sleeping_count(&self) -> usize {
+        self.sleeping.len()
+    }
+
+    /// Set the force magnitude (N) above which a sleeping entity wakes
+    ///
+    /// Checked whenever a force is accumulated for a sleeping entity. The
+    /// default of 0.0 wakes an entity on any non-zero force.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is negative or not finite.
+    pub fn set_wake_force_threshold(&mut self, threshold: Real) {
+        assert!(
+            threshold >= 0.0 && threshold.is_finite(),
+            "Wake force threshold must be non-negative and finite"
+        );
+        self.wake_force_threshold = threshold;
+    }
+
+    /// Get the force magnitude (N) above which a sleeping entity wakes
+    pub fn wake_force_threshold(&self) -> Real {
+        self.wake_force_threshold
+    }
+
+    /// Route warnings to `handler` instead of stderr
+    ///
+    /// The handler receives each warning message without a trailing newline.
+    pub fn set_warning_handler(&mut self, handler: WarningHandler) {
+        self.warning_handler = Some(handler);
+    }
+
+    /// Restore the default of printing warnings to stderr
+    pub fn clear_warning_handler(&mut self) {
+        self.warning_handler = None;
+    }
+
+    /// Emit a warning through the installed handler, or stderr by default
+    ///
+    /// Respects `warn_on_missing_components()`.
+    pub(crate) fn warn(&self, message: &str) {
+        if self.warn_on_missing_components {
+            self.emit_warning(message);
+        }
+    }
+
+    /// Emit a warning through the installed handler, or stderr by default,
+    /// even while `warn_on_missing_components()` is disabled
+    pub(crate) fn emit_warning(&self, message: &str) {
+        match &self.warning_handler {
+            Some(handler) => handler(message),
+            None => eprintln!("{}", message),
         }
     }
 
     /// Register a force provider
-    pub fn register_provider(&mut self, provider: Box<dyn ForceProvider>) {
-        self.providers.push(provider);
+    ///
+    /// The provider is enabled and evaluated after all existing providers.
+    /// Returns an id for toggling or removing it later.
+    pub fn register_provider(&mut self, provider: Box<dyn ForceProvider>) -> ProviderId {
+        self.register_provider_with_tier(provider, ProviderTier::Normal)
+    }
+
+    /// Register a force provider in the given tier
+    ///
+    /// Like `register_provider()`, which registers in `ProviderTier::Normal`.
+    /// See "Override Providers" above for how `ProviderTier::Override`
+    /// providers are evaluated.
+    pub fn register_provider_with_tier(&mut self, provider: Box<dyn ForceProvider>, tier: ProviderTier) -> ProviderId {
+        let id = ProviderId(self.next_provider_id);
+        self.next_provider_id += 1;
+        self.providers.push(ProviderEntry {
+            id,
+            tier,
+            enabled: true,
+            provider,
+        });
+        id
+    }
+
+    /// Get a provider's tier, or None if no provider has this id
+    pub fn provider_tier(&self, id: ProviderId) -> Option<ProviderTier> {
+        self.entry_index(id).map(|index| self.providers[index].tier)
+    }
+
+    /// Enable or disable a provider without unregistering it
+    ///
+    /// Disabled providers contribute nothing to accumulated forces. Forces
+    /// already accumulated are unchanged until the next accumulation.
+    /// Returns false if no provider has this id.
+    pub fn set_enabled(&mut self, id: ProviderId, enabled: bool) -> bool {
+        match self.entry_index(id) {
+            Some(index) => {
+                self.providers[index].enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether a provider is enabled, or None if no provider has this id
+    pub fn is_enabled(&self, id: ProviderId) -> Option<bool> {
+        self.entry_index(id).map(|index| self.providers[index].enabled)
+    }
+
+    /// Unregister a provider and return it
+    ///
+    /// The ids and relative order of the remaining providers are unchanged.
+    /// Returns None if no provider has this id.
+    pub fn remove_provider(&mut self, id: ProviderId) -> Option<Box<dyn ForceProvider>> {
+        let index = self.entry_index(id)?;
+        Some(self.providers.remove(index).provider)
+    }
+
+    /// Ids of all registered providers, in evaluation order
+    pub fn provider_ids(&self) -> impl Iterator<Item = ProviderId> + '_ {
+        self.providers.iter().map(|entry| entry.id)
+    }
+
+    /// Get a registered provider by id
+    pub fn provider(&self, id: ProviderId) -> Option<&dyn ForceProvider> {
+        self.entry_index(id).map(|index| self.providers[index].provider.as_ref())
+    }
+
+    /// Position of a provider in `providers`, found by binary search on its id
+    fn entry_index(&self, id: ProviderId) -> Option<usize> {
+        self.providers.binary_search_by_key(&id, |entry| entry.id).ok()
+    }
+
+    /// Register precomputed forces and accumulate them
+    ///
+    /// This is the bridge for systems that compute forces in bulk, such as
+    /// `GravitySystem::compute_forces_into()`. The forces are registered as a
+    /// single provider, so integrators that re-evaluate forces still see them,
+    /// and are accumulated immediately so `get_force()` and
+    /// `apply_forces_to_acceleration()` work without a separate
+    /// `accumulate_for_entity()` pass.
+    ///
+    /// The forces are copied into a map the registry reuses once its
+    /// provider has been dropped, for instance by `clear()`, so calling this
+    /// every step does not allocate once the map has grown.
+    ///
+    /// Returns the number of entities that had forces accumulated.
+    pub fn register_forces(&mut self, forces: &HashMap<Entity, Force>) -> usize {
+        let forces_map = self.precomputed_map(forces.iter().map(|(entity, force)| (*entity, *force)));
+        self.register_provider(Box::new(PrecomputedForces { forces: forces_map }));
+
+        let mut count = 0;
+        for entity in forces.keys() {
+            if self.accumulate_for_entity(*entity) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Register precomputed forces and accumulate them in slice order
+    ///
+    /// Same as `register_forces()`, but entities are accumulated in the order
+    /// given rather than hash map order, so bulk systems that care about
+    /// reproducibility can hand over their results as computed. Each entity
+    /// should appear at most once.
+    ///
+    /// Returns the number of entities that had forces accumulated.
+    pub fn register_ordered_forces(&mut self, forces: &[(Entity, Force)]) -> usize {
+        let forces_map = self.precomputed_map(forces.iter().copied());
+        self.register_provider(Box::new(PrecomputedForces { forces: forces_map }));
+
+        let mut count = 0;
+        for (entity, _) in forces {
+            if self.accumulate_for_entity(*entity) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Fill a map no provider holds any more with `forces`, or a new one
+    fn precomputed_map(&mut self, forces: impl Iterator<Item = (Entity, Force)>) -> Arc<HashMap<Entity, Force>> {
+        let index = match self.precomputed_maps.iter_mut().position(|map| Arc::get_mut(map).is_some()) {
+            Some(index) => index,
+            None => {
+                self.precomputed_maps.push(Arc::default());
+                self.precomputed_maps.len() - 1
+            }
+        };
+        let map = Arc::get_mut(&mut self.precomputed_maps[index]).expect("precomputed map is unshared");
+        map.clear();
+        map.extend(forces);
+        Arc::clone(&self.precomputed_maps[index])
     }
 
     /// Clear all accumulated forces
     pub fn clear_forces(&mut self) {
         self.accumulated_forces.clear();
+        self.overridden.clear();
     }
 
     /// Clear all providers and accumulated forces
     ///
     /// This is useful for resetting the registry between simulation steps
     /// when force providers need to be re-registered with updated force values.
+    /// Ids of the cleared providers are not reused, and the time, evaluation
+    /// velocities and sleeping entities are kept.
     pub fn clear(&mut self) {
         self.providers.clear();
         self.accumulated_forces.clear();
+        self.overridden.clear();
     }
 
     /// Accumulate forces for a specific entity from all providers
@@ -131,29 +688,46 @@ impl ForceRegistry {
     pub fn accumulate_for_entity(&mut self, entity: Entity) -> bool {
         let mut total_force = Force::zero();
         let mut has_forces = false;
+        let mut override_id = None;
 
-        for provider in &self.providers {
-            if let Some(force) = provider.compute_force(entity, self) {
-                if !force.is_valid() {
-                    if self.warn_on_missing_components {
-                        // Use Debug formatting to prevent injection attacks
-                        eprintln!("Warning: Force provider produced invalid force (NaN/Inf) for {:?}", entity);
-                    }
+        for tier in [ProviderTier::Override, ProviderTier::Normal] {
+            for entry in &self.providers {
+                if !entry.enabled || entry.tier != tier {
                     continue;
                 }
+                if let Some(force) = entry.provider.compute_force(entity, self) {
+                    if !force.is_valid() {
+                        // Use Debug formatting to prevent injection attacks
+                        self.warn(&format!("Warning: Force provider produced invalid force (NaN/Inf) for {:?}", entity));
+                        continue;
+                    }
 
-                total_force.add(&force);
-                has_forces = true;
+                    total_force += force;
+                    has_forces = true;
+                    if tier == ProviderTier::Override {
+                        override_id = Some(entry.id);
+                        break;
+                    }
+                }
+            }
+            if override_id.is_some() {
+                break;
             }
         }
+        match override_id {
+            Some(id) => self.overridden.insert(entity, id),
+            None => self.overridden.remove(&entity),
+        };
 
         // Check for overflow
         if has_forces && total_force.magnitude() > self.max_force_magnitude {
             if self.warn_on_missing_components {
                 let mag = total_force.magnitude();
                 // Sanitize numeric output
-                eprintln!("Warning: Total force magnitude {:.2e} exceeds limit {:.2e} for {:?}", 
-                          mag, self.max_force_magnitude, entity);
+                self.warn(&format!(
+                    "Warning: Total force magnitude {:.2e} exceeds limit {:.2e} for {:?}",
+                    mag, self.max_force_magnitude, entity
+                ));
             }
             // Clamp to max magnitude
             let mag = total_force.magnitude();
@@ -164,6 +738,9 @@ impl ForceRegistry {
         }
 
         if has_forces {
+            if !self.sleeping.is_empty() && total_force.magnitude() > self.wake_force_threshold {
+                self.sleeping.remove(&entity);
+            }
             self.accumulated_forces.insert(entity, total_force);
         }
 
@@ -175,10 +752,27 @@ impl ForceRegistry {
         self.accumulated_forces.get(&entity).copied()
     }
 
-    /// Get the number of registered providers
+    /// Get the override provider that decided an entity's accumulated force
+    ///
+    /// Returns None when the entity's last accumulation summed the normal
+    /// providers, or it has not been accumulated since the forces were
+    /// cleared.
+    pub fn overriding_provider(&self, entity: Entity) -> Option<ProviderId> {
+        self.overridden.get(&entity).copied()
+    }
+
+    /// Get the number of registered providers, including disabled ones
     pub fn provider_count(&self) -> usize {
         self.providers.len()
     }
+
+    /// Create a sink for accumulating forces from parallel tasks
+    ///
+    /// See `ParallelForceSink` for usage. The sink does not borrow the
+    /// registry; its forces land here when it is committed.
+    pub fn parallel_sink(&self) -> ParallelForceSink {
+        ParallelForceSink::new()
+    }
 }
 
 impl Default for ForceRegistry {
@@ -187,77 +781,425 @@ impl Default for ForceRegistry {
     }
 }
 
-/// Apply accumulated forces to compute accelerations (F = ma)
+/// Callback receiving `ForceRegistry` warning messages
+pub type WarningHandler = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Force provider returning a fixed set of per-entity forces
+struct PrecomputedForces {
+    forces: Arc<HashMap<Entity, Force>>,
+}
+
+impl ForceProvider for PrecomputedForces {
+    fn compute_force(&self, entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+        self.forces.get(&entity).copied()
+    }
+
+    fn name(&self) -> &str {
+        "precomputed_forces"
+    }
+}
+
+/// Force accumulator that can be shared across parallel tasks
 ///
-/// This function takes accumulated forces and mass components to compute
-/// accelerations according to Newton's second law. Entities without mass
-/// or with immovable mass are skipped with optional warnings.
+/// Each clone buffers its contributions locally, with no locking on the hot
+/// path. When a clone is dropped (or `flush()` is called) its buffer is
+/// handed to the shared state. `commit()` then merges every buffer and
+/// registers the result with a `ForceRegistry`.
 ///
-/// # Arguments
+/// Contributions to the same entity are summed in a canonical order, so the
+/// merged forces are bit-for-bit identical no matter how the work was split
+/// across tasks or threads.
 ///
-/// * `entities` - Iterator over entities to process
-/// * `force_registry` - Registry containing accumulated forces
-/// * `masses` - Storage for mass components
-/// * `accelerations` - Storage for acceleration components (output)
-/// * `warn_on_missing` - Whether to log warnings for entities without required components
+/// # Example
 ///
-/// # Returns
+/// ```
+/// use physics_engine::ecs::{Entity, World};
+/// use physics_engine::ecs::systems::{Force, ForceRegistry};
 ///
-/// Number of entities that had their acceleration updated
-pub fn apply_forces_to_acceleration<'a, I>(
-    entities: I,
-    force_registry: &ForceRegistry,
-    masses: &impl ComponentStorage<Component = Mass>,
-    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
-    warn_on_missing: bool,
-) -> usize
-where
-    I: Iterator<Item = &'a Entity>,
-{
-    let mut updated_count = 0;
-
-    for entity in entities {
-        // Skip if no force accumulated
-        let force = match force_registry.get_force(*entity) {
-            Some(f) => f,
-            None => continue,
-        };
+/// let mut world = World::new();
+/// let entities: Vec<Entity> = (0..100).map(|_| world.create_entity()).collect();
+/// let mut registry = ForceRegistry::new();
+/// let sink = registry.parallel_sink();
+///
+/// std::thread::scope(|scope| {
+///     for chunk in entities.chunks(25) {
+///         let mut sink = sink.clone();
+///         scope.spawn(move || {
+///             for &entity in chunk {
+///                 sink.add(entity, Force::new(0.0, -9.81, 0.0));
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(sink.commit(&mut registry), 100);
+/// ```
+pub struct ParallelForceSink {
+    local: Vec<(Entity, Force)>,
+    shared: Arc<Mutex<Vec<ForceBuffer>>>,
+}
 
-        // Skip if no mass component
-        let mass = match masses.get(*entity) {
-            Some(m) => m,
-            None => {
-                if warn_on_missing {
-                    eprintln!("Warning: Entity {:?} has force but no Mass component, skipping", entity);
-                }
-                continue;
-            }
-        };
+/// Buffered force contributions from one sink clone
+type ForceBuffer = Vec<(Entity, Force)>;
 
-        // Skip immovable bodies
-        if mass.is_immovable() {
-            continue;
+impl ParallelForceSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        ParallelForceSink {
+            local: Vec::new(),
+            shared: Arc::new(Mutex::new(Vec::new())),
         }
+    }
 
-        // Compute acceleration: a = F/m
-        let inv_mass = mass.inverse();
-        let acceleration = Acceleration::new(
-            force.fx * inv_mass,
-            force.fy * inv_mass,
-            force.fz * inv_mass,
-        );
+    /// Add a force contribution for an entity to this clone's buffer
+    pub fn add(&mut self, entity: Entity, force: Force) {
+        self.local.push((entity, force));
+    }
 
-        // Validate acceleration
-        if !acceleration.is_valid() {
-            if warn_on_missing {
-                eprintln!("Warning: Computed invalid acceleration for entity {:?}, skipping", entity);
-            }
-            continue;
+    /// Hand this clone's buffered contributions to the shared state
+    ///
+    /// Called automatically on drop.
+    pub fn flush(&mut self) {
+        if self.local.is_empty() {
+            return;
         }
+        let local = std::mem::take(&mut self.local);
+        self.shared
+            .lock()
+            .expect("ParallelForceSink: shared buffer lock poisoned")
+            .push(local);
+    }
 
-        // Update or insert acceleration component
-        if accelerations.contains(*entity) {
-            if let Some(acc) = accelerations.get_mut(*entity) {
+    /// Merge all buffers into per-entity net forces
+    ///
+    /// Returns one `(entity, force)` pair per entity, sorted by entity ID and
+    /// generation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any clone of this sink is still alive, since its buffered
+    /// contributions would be lost.
+    pub fn into_forces(mut self) -> Vec<(Entity, Force)> {
+        self.flush();
+        assert_eq!(
+            Arc::strong_count(&self.shared),
+            1,
+            "ParallelForceSink merged while clones are still alive"
+        );
+
+        let buffers: Vec<ForceBuffer> = std::mem::take(
+            &mut *self
+                .shared
+                .lock()
+                .expect("ParallelForceSink: shared buffer lock poisoned"),
+        );
+        let mut contributions: Vec<(Entity, Force)> = buffers.into_iter().flatten().collect();
+
+        // Sort on entity and then on the force bits, so the summation order
+        // for each entity does not depend on how contributions were split
+        contributions.sort_unstable_by(|(ea, fa), (eb, fb)| {
+            (ea.id().raw(), ea.generation())
+                .cmp(&(eb.id().raw(), eb.generation()))
+                .then(fa.fx.total_cmp(&fb.fx))
+                .then(fa.fy.total_cmp(&fb.fy))
+                .then(fa.fz.total_cmp(&fb.fz))
+        });
+
+        let mut merged: Vec<(Entity, Force)> = Vec::new();
+        for (entity, force) in contributions {
+            match merged.last_mut() {
+                Some((last, total)) if *last == entity => total.add(&force),
+                _ => merged.push((entity, force)),
+            }
+        }
+        merged
+    }
+
+    /// Merge all buffers and register the net forces with a registry
+    ///
+    /// Equivalent to `registry.register_forces()` on the merged forces.
+    /// Returns the number of entities that had forces accumulated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any clone of this sink is still alive.
+    pub fn commit(self, registry: &mut ForceRegistry) -> usize {
+        let forces: HashMap<Entity, Force> = self.into_forces().into_iter().collect();
+        registry.register_forces(&forces)
+    }
+}
+
+impl Default for ParallelForceSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for ParallelForceSink {
+    /// Create a new handle with an empty local buffer sharing the same state
+    fn clone(&self) -> Self {
+        ParallelForceSink {
+            local: Vec::new(),
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Drop for ParallelForceSink {
+    fn drop(&mut self) {
+        // A poisoned lock means a task panicked; the sink is unusable anyway
+        if !self.local.is_empty() {
+            if let Ok(mut shared) = self.shared.lock() {
+                shared.push(std::mem::take(&mut self.local));
+            }
+        }
+    }
+}
+
+/// Reject non-finite accelerations and scale ones above `limit` down to it
+///
+/// The arithmetic behind `ForceRegistry::acceleration_from_force()`, free of
+/// the registry so dense kernels can run it on worker threads. Returns the
+/// limited acceleration and, when it was scaled, its original magnitude.
+pub(crate) fn clamp_acceleration(acceleration: [Real; 3], limit: Option<Real>) -> Option<([Real; 3], Option<Real>)> {
+    if !acceleration.iter().all(|a| a.is_finite()) {
+        return None;
+    }
+    let limit = match limit {
+        Some(limit) => limit,
+        None => return Some((acceleration, None)),
+    };
+
+    // Scale by the largest component so the magnitude cannot overflow
+    let largest = acceleration.iter().fold(0.0 as Real, |m, a| m.max(a.abs()));
+    if largest == 0.0 {
+        return Some((acceleration, None));
+    }
+    let norm = acceleration.iter().map(|a| (a / largest).powi(2)).sum::<Real>().sqrt();
+    let magnitude = largest * norm;
+    if magnitude <= limit {
+        return Some((acceleration, None));
+    }
+    let scale = limit / largest / norm;
+    Some((acceleration.map(|a| a * scale), Some(magnitude)))
+}
+
+/// Apply accumulated forces to compute accelerations (F = ma)
+///
+/// This function takes accumulated forces and mass components to compute
+/// accelerations according to Newton's second law. Entities without mass
+/// or with immovable mass are skipped with optional warnings, and sleeping
+/// entities are skipped silently. Each force is
+/// converted with `ForceRegistry::acceleration_from_force()`, the same policy
+/// integrators use for their internal force evaluations.
+///
+/// # Arguments
+///
+/// * `entities` - Iterator over entities to process
+/// * `force_registry` - Registry containing accumulated forces
+/// * `masses` - Storage for mass components
+/// * `accelerations` - Storage for acceleration components (output)
+/// * `warn_on_missing` - Whether to log warnings for entities without required components
+///
+/// # Dense Storages
+///
+/// When the mass and acceleration storages expose field arrays in the same
+/// dense order as the entity list, the accelerations are written by index
+/// in fixed-size chunks, spread over the Rayon pool with the `parallel`
+/// feature. Each entry depends only on its own force and mass, so the
+/// result is bitwise identical to the per-entity path.
+///
+/// # Returns
+///
+/// Number of entities that had their acceleration updated
+pub fn apply_forces_to_acceleration<'a, I>(
+    entities: I,
+    force_registry: &ForceRegistry,
+    masses: &impl ComponentStorage<Component = Mass>,
+    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+    warn_on_missing: bool,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    if masses.dense_order().is_none() || accelerations.dense_order().is_none() {
+        return apply_forces_per_entity(entities, force_registry, masses, accelerations, warn_on_missing);
+    }
+
+    let entities_vec: Vec<Entity> = entities.copied().collect();
+    if !dense::is_aligned(&entities_vec, &[masses.dense_order(), accelerations.dense_order()]) {
+        return apply_forces_per_entity(entities_vec.iter(), force_registry, masses, accelerations, warn_on_missing);
+    }
+
+    let mass_arrays = masses.field_arrays().expect("Dense storage must provide field arrays");
+    let mut acceleration_arrays = accelerations.field_arrays_mut().expect("Dense storage must provide field arrays");
+    let (ax, ay, az) = acceleration_arrays.as_acceleration_arrays_mut();
+    dense_accelerations_from_forces(
+        &entities_vec,
+        force_registry,
+        mass_arrays.as_mass_array(),
+        [ax, ay, az],
+        cfg!(feature = "parallel"),
+        warn_on_missing,
+        &mut DenseForceBuffers::default(),
+    )
+}
+
+/// Scratch arrays for `dense_accelerations_from_forces()`
+///
+/// Callers that convert forces every substep keep one of these around so
+/// the arrays are allocated once rather than on every call.
+#[derive(Default)]
+pub(crate) struct DenseForceBuffers {
+    forces: [Vec<Real>; 3],
+    inverse_masses: Vec<Real>,
+    active: Vec<bool>,
+}
+
+impl DenseForceBuffers {
+    /// Zero the buffers and size them for `count` entities
+    fn reset(&mut self, count: usize) {
+        for column in self.forces.iter_mut().chain(std::iter::once(&mut self.inverse_masses)) {
+            column.clear();
+            column.resize(count, 0.0);
+        }
+        self.active.clear();
+        self.active.resize(count, false);
+    }
+}
+
+/// Convert forces to accelerations in dense order
+///
+/// `mass_values` and `accelerations` are field arrays aligned with
+/// `entities`. Registry lookups and warnings run in entity order, then the
+/// conversion runs in chunks, on the Rayon pool when `parallel` is set.
+/// Entries the per-entity path would skip are left untouched.
+pub(crate) fn dense_accelerations_from_forces(
+    entities: &[Entity],
+    force_registry: &ForceRegistry,
+    mass_values: &[Real],
+    accelerations: [&mut [Real]; 3],
+    parallel: bool,
+    warn_on_missing: bool,
+    buffers: &mut DenseForceBuffers,
+) -> usize {
+    let count = entities.len();
+    buffers.reset(count);
+    let DenseForceBuffers { forces, inverse_masses, active } = buffers;
+    for (i, entity) in entities.iter().enumerate() {
+        if force_registry.is_sleeping(*entity) {
+            continue;
+        }
+        let Some(force) = force_registry.get_force(*entity) else {
+            continue;
+        };
+        let mass = Mass::new(mass_values[i]);
+        let inverse_mass = force_registry.inverse_mass(&mass);
+        if inverse_mass == 0.0 {
+            if warn_on_missing {
+                force_registry.warn_below_min_mass(*entity, &mass);
+            }
+            continue;
+        }
+        forces[0][i] = force.fx;
+        forces[1][i] = force.fy;
+        forces[2][i] = force.fz;
+        inverse_masses[i] = inverse_mass;
+        active[i] = true;
+    }
+
+    // Clamping warnings go through the registry's handler, which can't be
+    // called from worker threads, so report them before the parallel pass
+    let limit = force_registry.max_acceleration();
+    if let (Some(max), true) = (limit, force_registry.warn_on_missing_components()) {
+        for i in (0..count).filter(|&i| active[i]) {
+            let acceleration = [forces[0][i], forces[1][i], forces[2][i]].map(|f| f * inverse_masses[i]);
+            if let Some((_, Some(magnitude))) = clamp_acceleration(acceleration, limit) {
+                force_registry.warn(&format!(
+                    "Warning: Acceleration magnitude {:.2e} exceeds limit {:.2e}, clamping",
+                    magnitude, max
+                ));
+            }
+        }
+    }
+
+    let updated_count = dense::accelerations_from_forces(
+        accelerations,
+        [&forces[0], &forces[1], &forces[2]],
+        inverse_masses,
+        active,
+        limit,
+        parallel,
+    );
+    if warn_on_missing && updated_count < active.iter().filter(|&&a| a).count() {
+        for (i, entity) in entities.iter().enumerate() {
+            let acceleration = [forces[0][i], forces[1][i], forces[2][i]].map(|f| f * inverse_masses[i]);
+            if active[i] && !acceleration.iter().all(|a| a.is_finite()) {
+                force_registry.warn(&format!("Warning: Computed invalid acceleration for entity {:?}, skipping", entity));
+            }
+        }
+    }
+    updated_count
+}
+
+/// Per-entity body of `apply_forces_to_acceleration()`
+fn apply_forces_per_entity<'a, I>(
+    entities: I,
+    force_registry: &ForceRegistry,
+    masses: &impl ComponentStorage<Component = Mass>,
+    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+    warn_on_missing: bool,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let mut updated_count = 0;
+
+    for entity in entities {
+        if force_registry.is_sleeping(*entity) {
+            continue;
+        }
+
+        // Skip if no force accumulated
+        let force = match force_registry.get_force(*entity) {
+            Some(f) => f,
+            None => continue,
+        };
+
+        // Skip if no mass component
+        let mass = match masses.get(*entity) {
+            Some(m) => m,
+            None => {
+                if warn_on_missing {
+                    force_registry.warn(&format!("Warning: Entity {:?} has force but no Mass component, skipping", entity));
+                }
+                continue;
+            }
+        };
+
+        // Skip immovable bodies, and treat bodies below the registry's
+        // minimum mass as immovable rather than dividing by a tiny mass
+        if force_registry.inverse_mass(mass) == 0.0 {
+            if warn_on_missing {
+                force_registry.warn_below_min_mass(*entity, mass);
+            }
+            continue;
+        }
+
+        // Compute acceleration: a = F/m, validated and limited
+        let acceleration = match force_registry.acceleration_from_force(&force, mass) {
+            Some(acceleration) => acceleration,
+            None => {
+                if warn_on_missing {
+                    force_registry.warn(&format!("Warning: Computed invalid acceleration for entity {:?}, skipping", entity));
+                }
+                continue;
+            }
+        };
+
+        // Update or insert acceleration component
+        if accelerations.contains(*entity) {
+            if let Some(acc) = accelerations.get_mut(*entity) {
                 *acc = acceleration;
             }
         } else {
@@ -270,6 +1212,240 @@ where
     updated_count
 }
 
+/// Apply accumulated forces to the accelerations of a `NewtonianState`
+///
+/// Bundle form of `apply_forces_to_acceleration()`, with the same rules and
+/// return value.
+pub fn apply_forces_to_state<'a, I, P, V, A, M>(
+    entities: I,
+    force_registry: &ForceRegistry,
+    state: &mut NewtonianState<P, V, A, M>,
+    warn_on_missing: bool,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+    P: ComponentStorage<Component = Position>,
+    V: ComponentStorage<Component = Velocity>,
+    A: ComponentStorage<Component = Acceleration>,
+    M: ComponentStorage<Component = Mass>,
+{
+    apply_forces_to_acceleration(entities, force_registry, &state.masses, &mut state.accelerations, warn_on_missing)
+}
+
+/// Zero the accelerations of entities that received no force this step
+///
+/// `apply_forces_to_acceleration` only writes entities with an accumulated
+/// force, so an entity whose force stopped keeps its last acceleration and
+/// the integrator keeps accelerating it. Call this alongside it when the
+/// acceleration storage persists across steps. Entities with an immovable
+/// mass are left untouched.
+///
+/// # Arguments
+///
+/// * `entities` - Iterator over entities to process
+/// * `force_registry` - Registry containing this step's accumulated forces
+/// * `masses` - Storage for mass components (to check for immovable bodies)
+/// * `accelerations` - Storage for acceleration components (output)
+///
+/// # Returns
+///
+/// Number of accelerations reset to zero
+pub fn reset_unforced_accelerations<'a, I>(
+    entities: I,
+    force_registry: &ForceRegistry,
+    masses: &impl ComponentStorage<Component = Mass>,
+    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let mut reset_count = 0;
+
+    for entity in entities {
+        if force_registry.get_force(*entity).is_some() {
+            continue;
+        }
+        if masses.get(*entity).is_some_and(|m| m.is_immovable()) {
+            continue;
+        }
+        if let Some(acc) = accelerations.get_mut(*entity) {
+            *acc = Acceleration::zero();
+            reset_count += 1;
+        }
+    }
+
+    reset_count
+}
+
+/// Fill accelerations from the registered forces before the first step
+///
+/// Velocity Verlet's first half-kick uses the accelerations already in
+/// storage, so a loop that starts from zeroed accelerations gets that kick
+/// wrong. This evaluates every provider once for `entities`, converts the
+/// forces with `apply_forces_to_acceleration()`, and gives every other
+/// entity a zero acceleration (inserting one if missing), so the storage is
+/// complete and matches the current positions.
+///
+/// Immovable and sleeping entities are left untouched. Register the forces
+/// for the current positions (for example with `register_forces()`) before
+/// calling this.
+///
+/// # Returns
+///
+/// Number of entities given a non-zero acceleration
+pub fn initialize_accelerations<'a, I>(
+    entities: I,
+    force_registry: &mut ForceRegistry,
+    masses: &impl ComponentStorage<Component = Mass>,
+    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+    warn_on_missing: bool,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let entities_vec: Vec<Entity> = entities.copied().collect();
+
+    force_registry.clear_forces();
+    for entity in &entities_vec {
+        force_registry.accumulate_for_entity(*entity);
+    }
+    let updated_count =
+        apply_forces_to_acceleration(entities_vec.iter(), force_registry, masses, accelerations, warn_on_missing);
+    reset_unforced_accelerations(entities_vec.iter(), force_registry, masses, accelerations);
+
+    for entity in &entities_vec {
+        let skipped = force_registry.is_sleeping(*entity) || masses.get(*entity).is_some_and(|m| m.is_immovable());
+        if !skipped && !accelerations.contains(*entity) {
+            accelerations.insert(*entity, Acceleration::zero());
+        }
+    }
+
+    updated_count
+}
+
+/// Apply accumulated forces to Structure-of-Arrays acceleration storage (F = ma)
+///
+/// This is the SoA counterpart of `apply_forces_to_acceleration`. Instead of
+/// writing one `Acceleration` component at a time, it walks the mass storage in
+/// dense-index order, builds an immovable mask from the mass array, converts
+/// the whole force array at once with the `simd_axpy` kernel, and writes the
+/// `ax`/`ay`/`az` field arrays directly so the results can be fed to the SIMD
+/// integration kernels.
+///
+/// # Index Alignment
+///
+/// The output is written in the mass storage's dense order. Entities that
+/// have a force but no acceleration slot are appended to `accelerations`
+/// first. If the acceleration storage's leading entities match the mass
+/// storage's dense order, indices are used directly; otherwise a permutation
+/// is built via `AccelerationSoAStorage::get_index()`. See
+/// `PositionSoAStorage` for the alignment contract between SoA storages.
+///
+/// Entities without an accumulated force, immovable entities and sleeping
+/// entities are left untouched, matching `apply_forces_to_acceleration`.
+///
+/// # Returns
+///
+/// Number of entities that had their acceleration updated
+pub fn apply_forces_to_acceleration_soa(
+    force_registry: &ForceRegistry,
+    masses: &MassSoAStorage,
+    accelerations: &mut AccelerationSoAStorage,
+) -> usize {
+    let entities = masses.dense_entities();
+    let mass_values = match masses.field_arrays() {
+        Some(arrays) => arrays.as_mass_array(),
+        None => return 0,
+    };
+    let inverse_masses = masses.inverse_masses();
+
+    // Immovable (or sleeping) mask and per-entity forces, both in mass dense order
+    let immovable: Vec<bool> = mass_values
+        .iter()
+        .zip(entities)
+        .map(|(&m, entity)| m < force_registry.min_mass() || force_registry.is_sleeping(*entity))
+        .collect();
+    let forces: Vec<Option<Force>> = entities
+        .iter()
+        .map(|entity| force_registry.get_force(*entity))
+        .collect();
+
+    // Allocate acceleration slots for entities that will be written
+    for (i, entity) in entities.iter().enumerate() {
+        if forces[i].is_some() && !immovable[i] && !accelerations.contains(*entity) {
+            accelerations.insert(*entity, Acceleration::zero());
+        }
+    }
+
+    // Map mass indices to acceleration indices (identity when aligned)
+    let aligned = accelerations.dense_entities().starts_with(entities);
+    let permutation: Option<Vec<Option<usize>>> = if aligned {
+        None
+    } else {
+        Some(entities.iter().map(|entity| accelerations.get_index(*entity)).collect())
+    };
+
+    let mut arrays = match accelerations.field_arrays_mut() {
+        Some(arrays) => arrays,
+        None => return 0,
+    };
+    let (ax, ay, az) = arrays.as_acceleration_arrays_mut();
+
+    // a = inv_mass * f with the SIMD multiply-add kernel, converted through
+    // fixed-size stack arrays so no scratch is allocated per call
+    const CHUNK: usize = 64;
+    let count = entities.len();
+    let mut updated_count = 0;
+    for start in (0..count).step_by(CHUNK) {
+        let len = CHUNK.min(count - start);
+
+        // Skipped entities get zero force and inverse mass
+        let mut f = [[0.0; CHUNK]; 3];
+        let mut inv_mass = [0.0; CHUNK];
+        for (j, i) in (start..start + len).enumerate() {
+            if let (Some(force), false) = (forces[i], immovable[i]) {
+                [f[0][j], f[1][j], f[2][j]] = [force.fx, force.fy, force.fz];
+                // Bodies below min_mass are in the immovable mask, so the
+                // cached inverse is the registry's inverse mass
+                inv_mass[j] = match inverse_masses {
+                    Some(inverses) => inverses[i],
+                    None => force_registry.inverse_mass(&Mass::new(mass_values[i])),
+                };
+            }
+        }
+        let mut a = [[0.0; CHUNK]; 3];
+        for (a_axis, f_axis) in a.iter_mut().zip(&f) {
+            simd_axpy(&mut a_axis[..len], &inv_mass[..len], &f_axis[..len]);
+        }
+
+        for (j, i) in (start..start + len).enumerate() {
+            if immovable[i] || forces[i].is_none() {
+                continue;
+            }
+            let index = match &permutation {
+                None => i,
+                Some(map) => match map[i] {
+                    Some(index) => index,
+                    None => continue,
+                },
+            };
+
+            // Same validation and limit as acceleration_from_force()
+            let acceleration = match force_registry.limit_acceleration([a[0][j], a[1][j], a[2][j]]) {
+                Some(acceleration) => acceleration,
+                None => continue,
+            };
+
+            ax[index] = acceleration[0];
+            ay[index] = acceleration[1];
+            az[index] = acceleration[2];
+            updated_count += 1;
+        }
+    }
+
+    updated_count
+}
+
 /// Integration system that updates velocity and position based on acceleration
 ///
 /// Performs semi-implicit (symplectic) Euler integration:
@@ -292,12 +1468,85 @@ where
 /// * `masses` - Storage for mass components (to check for immovable bodies)
 /// * `warn_on_missing` - Whether to log warnings for entities without required components
 ///
+/// # Dense Storages
+///
+/// When all four storages expose field arrays in the same dense order as the
+/// entity list (see `ComponentStorage::dense_order()`), the update runs by
+/// index in fixed-size chunks instead of per-entity lookups, spread over the
+/// Rayon pool with the `parallel` feature. The arithmetic is the same, so the
+/// result is bitwise identical to the per-entity path. Any other combination,
+/// including HashMap storages, takes the per-entity path.
+///
 /// # Returns
 ///
 /// Number of entities that were updated
 pub fn integrate_motion<'a, I>(
     entities: I,
-    dt: f64,
+    dt: Real,
+    positions: &mut impl ComponentStorage<Component = crate::ecs::components::Position>,
+    velocities: &mut impl ComponentStorage<Component = Velocity>,
+    accelerations: &impl ComponentStorage<Component = Acceleration>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    warn_on_missing: bool,
+) -> usize
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let all_dense = positions.dense_order().is_some()
+        && velocities.dense_order().is_some()
+        && accelerations.dense_order().is_some()
+        && masses.dense_order().is_some();
+    if !all_dense {
+        return integrate_motion_per_entity(entities, dt, positions, velocities, accelerations, masses, warn_on_missing);
+    }
+
+    let entities_vec: Vec<Entity> = entities.copied().collect();
+    let orders = [
+        positions.dense_order(),
+        velocities.dense_order(),
+        accelerations.dense_order(),
+        masses.dense_order(),
+    ];
+    if !dense::is_aligned(&entities_vec, &orders) {
+        return integrate_motion_per_entity(
+            entities_vec.iter(),
+            dt,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            warn_on_missing,
+        );
+    }
+
+    let mass_arrays = masses.field_arrays().expect("Dense storage must provide field arrays");
+    let movable: Vec<bool> = mass_arrays.as_mass_array().iter().map(|&m| !Mass::new(m).is_immovable()).collect();
+    let acceleration_arrays = accelerations.field_arrays().expect("Dense storage must provide field arrays");
+    let (ax, ay, az) = acceleration_arrays.as_acceleration_arrays();
+    let parallel = cfg!(feature = "parallel");
+
+    let mut velocity_arrays = velocities.field_arrays_mut_masked(&movable).expect("Dense storage must provide field arrays");
+    let (vx, vy, vz) = velocity_arrays.as_velocity_arrays_mut();
+    dense::euler_kick([vx, vy, vz], [ax, ay, az], &movable, dt, parallel);
+
+    let mut position_arrays = positions.field_arrays_mut_masked(&movable).expect("Dense storage must provide field arrays");
+    let (px, py, pz) = position_arrays.as_position_arrays_mut();
+    let updated_count = dense::euler_drift([px, py, pz], [vx, vy, vz], &movable, dt, parallel);
+    if warn_on_missing && updated_count < movable.iter().filter(|&&m| m).count() {
+        for (i, entity) in entities_vec.iter().enumerate() {
+            let finite = [px[i], py[i], pz[i], vx[i], vy[i], vz[i]].iter().all(|value| value.is_finite());
+            if movable[i] && !finite {
+                eprintln!("Warning: Integration produced invalid state for entity {:?}", entity);
+            }
+        }
+    }
+    updated_count
+}
+
+/// Per-entity body of `integrate_motion()`
+fn integrate_motion_per_entity<'a, I>(
+    entities: I,
+    dt: Real,
     positions: &mut impl ComponentStorage<Component = crate::ecs::components::Position>,
     velocities: &mut impl ComponentStorage<Component = Velocity>,
     accelerations: &impl ComponentStorage<Component = Acceleration>,
@@ -357,117 +1606,506 @@ where
             if warn_on_missing {
                 eprintln!("Warning: Integration produced invalid state for entity {:?}", entity);
             }
-            continue;
-        }
+            continue;
+        }
+
+        updated_count += 1;
+    }
+
+    updated_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, Entity};
+    use crate::ecs::components::Position;
+    use crate::real::RealExt;
+
+    #[test]
+    fn test_force_creation() {
+        let force = Force::new(10.0, 20.0, 30.0);
+        assert_eq!(force.fx, 10.0);
+        assert_eq!(force.fy, 20.0);
+        assert_eq!(force.fz, 30.0);
+    }
+
+    #[test]
+    fn test_force_validation() {
+        let valid = Force::new(1.0, 2.0, 3.0);
+        assert!(valid.is_valid());
+
+        let invalid = Force::new(Real::NAN, 2.0, 3.0);
+        assert!(!invalid.is_valid());
+    }
+
+    #[test]
+    fn test_force_add() {
+        let mut f1 = Force::new(1.0, 2.0, 3.0);
+        let f2 = Force::new(4.0, 5.0, 6.0);
+        f1.add(&f2);
+        assert_eq!(f1.fx, 5.0);
+        assert_eq!(f1.fy, 7.0);
+        assert_eq!(f1.fz, 9.0);
+    }
+
+    #[test]
+    fn test_force_operators_match_component_arithmetic() {
+        let f1 = Force::new(1.0, -2.0, 3.5);
+        let f2 = Force::new(4.0, 0.5, -6.0);
+
+        assert_eq!(f1 + f2 * 0.5, Force::new(1.0 + 4.0 * 0.5, -2.0 + 0.5 * 0.5, 3.5 - 6.0 * 0.5));
+        assert_eq!(f1 - f2, Force::new(1.0 - 4.0, -2.0 - 0.5, 3.5 + 6.0));
+        assert_eq!(f2.scale(-2.0), f2 * -2.0);
+        assert_eq!(-f1, Force::new(-1.0, 2.0, -3.5));
+        assert_eq!(-Force::zero() + f1, f1);
+
+        let mut sum = f1;
+        sum += f2;
+        let mut legacy = f1;
+        legacy.add(&f2);
+        assert_eq!(sum, legacy);
+    }
+
+    #[test]
+    fn test_force_magnitude() {
+        let force = Force::new(3.0, 4.0, 0.0);
+        assert_eq!(force.magnitude(), 5.0);
+    }
+
+    struct TestForceProvider {
+        force: Force,
+    }
+
+    impl ForceProvider for TestForceProvider {
+        fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+            Some(self.force)
+        }
+
+        fn name(&self) -> &str {
+            "TestForceProvider"
+        }
+    }
+
+    #[test]
+    fn test_force_registry() {
+        let mut registry = ForceRegistry::new();
+        assert_eq!(registry.provider_count(), 0);
+
+        let provider = Box::new(TestForceProvider {
+            force: Force::new(10.0, 0.0, 0.0),
+        });
+        registry.register_provider(provider);
+        assert_eq!(registry.provider_count(), 1);
+    }
+
+    #[test]
+    fn test_force_accumulation() {
+        let mut registry = ForceRegistry::new();
+        
+        // Register two force providers
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(10.0, 0.0, 0.0),
+        }));
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(0.0, 20.0, 0.0),
+        }));
+
+        let entity = Entity::new(1, 0);
+        assert!(registry.accumulate_for_entity(entity));
+
+        let force = registry.get_force(entity).unwrap();
+        assert_eq!(force.fx, 10.0);
+        assert_eq!(force.fy, 20.0);
+        assert_eq!(force.fz, 0.0);
+    }
+
+    #[test]
+    fn test_disabled_provider_contributes_nothing() {
+        let mut registry = ForceRegistry::new();
+        let gravity = registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(0.0, -9.8, 0.0),
+        }));
+        let wind = registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(3.0, 0.0, 0.0),
+        }));
+        let entity = Entity::new(1, 0);
+
+        assert!(registry.set_enabled(wind, false));
+        assert_eq!(registry.is_enabled(wind), Some(false));
+        assert!(registry.accumulate_for_entity(entity));
+        assert_eq!(registry.get_force(entity), Some(Force::new(0.0, -9.8, 0.0)));
+
+        // With every provider disabled nothing accumulates
+        registry.set_enabled(gravity, false);
+        registry.clear_forces();
+        assert!(!registry.accumulate_for_entity(entity));
+        assert_eq!(registry.provider_count(), 2);
+
+        registry.set_enabled(gravity, true);
+        registry.set_enabled(wind, true);
+        registry.accumulate_for_entity(entity);
+        assert_eq!(registry.get_force(entity), Some(Force::new(3.0, -9.8, 0.0)));
+    }
+
+    #[test]
+    fn test_remove_provider_keeps_other_ids() {
+        let mut registry = ForceRegistry::new();
+        let ids: Vec<ProviderId> = (1..=3)
+            .map(|i| {
+                registry.register_provider(Box::new(TestForceProvider {
+                    force: Force::new(i as Real, 0.0, 0.0),
+                }))
+            })
+            .collect();
+
+        assert!(registry.remove_provider(ids[1]).is_some());
+        assert!(registry.remove_provider(ids[1]).is_none());
+        assert!(!registry.set_enabled(ids[1], false));
+        assert_eq!(registry.provider_ids().collect::<Vec<_>>(), vec![ids[0], ids[2]]);
+
+        // The remaining ids still address the same providers
+        registry.set_enabled(ids[0], false);
+        let entity = Entity::new(1, 0);
+        registry.accumulate_for_entity(entity);
+        assert_eq!(registry.get_force(entity), Some(Force::new(3.0, 0.0, 0.0)));
+
+        // Ids are not reused, even after clear()
+        registry.clear();
+        let fresh = registry.register_provider(Box::new(TestForceProvider { force: Force::zero() }));
+        assert!(!ids.contains(&fresh));
+    }
+
+    /// Provider that records its tag each time it is evaluated
+    struct OrderProbe {
+        tag: u32,
+        log: Arc<Mutex<Vec<u32>>>,
+    }
+
+    impl ForceProvider for OrderProbe {
+        fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+            self.log.lock().unwrap().push(self.tag);
+            None
+        }
+
+        fn name(&self) -> &str {
+            "OrderProbe"
+        }
+    }
+
+    #[test]
+    fn test_providers_evaluated_in_registration_order_after_removal() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = ForceRegistry::new();
+        let register = |registry: &mut ForceRegistry, tag| {
+            registry.register_provider(Box::new(OrderProbe { tag, log: log.clone() }))
+        };
+        register(&mut registry, 1);
+        let second = register(&mut registry, 2);
+        register(&mut registry, 3);
+        registry.remove_provider(second);
+        let fourth = register(&mut registry, 4);
+
+        registry.accumulate_for_entity(Entity::new(1, 0));
+        assert_eq!(*log.lock().unwrap(), vec![1, 3, 4]);
+
+        let ids: Vec<ProviderId> = registry.provider_ids().collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ids.last(), Some(&fourth));
+        assert_eq!(registry.provider(fourth).map(|p| p.name()), Some("OrderProbe"));
+    }
+
+    /// Provider returning a fixed force for one entity only
+    struct TargetedForce {
+        target: Entity,
+        force: Force,
+    }
+
+    impl ForceProvider for TargetedForce {
+        fn compute_force(&self, entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+            (entity == self.target).then_some(self.force)
+        }
+
+        fn name(&self) -> &str {
+            "TargetedForce"
+        }
+    }
+
+    #[test]
+    fn test_override_masks_gravity_for_target_only() {
+        use crate::plugins::gravity::{GravityPlugin, GravitySystem};
+
+        let mut world = crate::ecs::World::new();
+        let (held, free) = (world.create_entity(), world.create_entity());
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(held, Position::new(0.0, 0.0, 0.0));
+        positions.insert(free, Position::new(2.0, 0.0, 0.0));
+        masses.insert(held, Mass::new(1.0));
+        masses.insert(free, Mass::new(1.0));
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.0);
+        let mut forces = HashMap::new();
+        GravitySystem::new(plugin).compute_forces_into(&[held, free], &positions, &masses, &mut forces);
+
+        let mut registry = ForceRegistry::new();
+        registry.register_forces(&forces);
+        let gravity_on_free = registry.get_force(free).unwrap();
+        let hold = Force::new(0.0, 5.0, 0.0);
+        let id = registry.register_provider_with_tier(Box::new(TargetedForce { target: held, force: hold }), ProviderTier::Override);
+        assert_eq!(registry.provider_tier(id), Some(ProviderTier::Override));
+
+        registry.clear_forces();
+        for entity in [held, free] {
+            registry.accumulate_for_entity(entity);
+        }
+        assert_eq!(registry.get_force(held), Some(hold));
+        assert_eq!(registry.overriding_provider(held), Some(id));
+        assert_eq!(registry.get_force(free), Some(gravity_on_free));
+        assert_eq!(registry.overriding_provider(free), None);
+
+        // A disabled override no longer masks anything
+        registry.set_enabled(id, false);
+        registry.accumulate_for_entity(held);
+        assert_eq!(registry.get_force(held), Some(-gravity_on_free));
+        assert_eq!(registry.overriding_provider(held), None);
+    }
+
+    #[test]
+    fn test_first_registered_override_wins() {
+        let entity = Entity::new(1, 0);
+        let other = Entity::new(2, 0);
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider { force: Force::new(1.0, 0.0, 0.0) }));
+        // Returns nothing for `entity`, so the next override gets its turn
+        registry.register_provider_with_tier(
+            Box::new(TargetedForce { target: other, force: Force::new(0.0, 0.0, 7.0) }),
+            ProviderTier::Override,
+        );
+        let first = registry.register_provider_with_tier(
+            Box::new(TargetedForce { target: entity, force: Force::new(0.0, 2.0, 0.0) }),
+            ProviderTier::Override,
+        );
+        registry.register_provider_with_tier(
+            Box::new(TargetedForce { target: entity, force: Force::new(0.0, 3.0, 0.0) }),
+            ProviderTier::Override,
+        );
+
+        registry.accumulate_for_entity(entity);
+        assert_eq!(registry.get_force(entity), Some(Force::new(0.0, 2.0, 0.0)));
+        assert_eq!(registry.overriding_provider(entity), Some(first));
+
+        registry.clear_forces();
+        assert_eq!(registry.overriding_provider(entity), None);
+    }
+
+    #[test]
+    fn test_overrides_leave_uncovered_entities_unchanged() {
+        let covered = Entity::new(1, 0);
+        let uncovered = Entity::new(2, 0);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let build = |with_override: bool| {
+            let mut registry = ForceRegistry::new();
+            registry.register_provider(Box::new(TestForceProvider { force: Force::new(0.1, 0.2, 0.3) }));
+            if with_override {
+                registry.register_provider_with_tier(
+                    Box::new(TargetedForce { target: covered, force: Force::zero() }),
+                    ProviderTier::Override,
+                );
+            }
+            registry.register_provider(Box::new(OrderProbe { tag: 1, log: log.clone() }));
+            registry.register_provider(Box::new(TestForceProvider { force: Force::new(0.7, -0.4, 1e-3) }));
+            registry
+        };
 
-        updated_count += 1;
+        let mut plain = build(false);
+        let mut overridden = build(true);
+        plain.accumulate_for_entity(uncovered);
+        overridden.accumulate_for_entity(uncovered);
+        assert_eq!(overridden.get_force(uncovered), plain.get_force(uncovered));
+        assert_eq!(log.lock().unwrap().len(), 2);
+
+        // Normal providers are not even evaluated for an overridden entity
+        overridden.accumulate_for_entity(covered);
+        assert_eq!(overridden.get_force(covered), Some(Force::zero()));
+        assert_eq!(log.lock().unwrap().len(), 2);
     }
 
-    updated_count
-}
+    #[test]
+    fn test_register_forces_accumulates_immediately() {
+        let mut registry = ForceRegistry::new();
+        let e1 = Entity::new(1, 0);
+        let e2 = Entity::new(2, 0);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ecs::{HashMapStorage, Entity};
-    use crate::ecs::components::Position;
+        let mut forces = HashMap::new();
+        forces.insert(e1, Force::new(1.0, 2.0, 3.0));
+        forces.insert(e2, Force::new(-4.0, 0.0, 0.0));
+
+        assert_eq!(registry.register_forces(&forces), 2);
+        assert_eq!(registry.provider_count(), 1);
+        assert_eq!(registry.get_force(e1), Some(Force::new(1.0, 2.0, 3.0)));
+        assert_eq!(registry.get_force(e2), Some(Force::new(-4.0, 0.0, 0.0)));
+
+        // Forces remain available to integrators that re-accumulate
+        registry.clear_forces();
+        assert!(registry.accumulate_for_entity(e1));
+        assert_eq!(registry.get_force(e1), Some(Force::new(1.0, 2.0, 3.0)));
+    }
 
     #[test]
-    fn test_force_creation() {
-        let force = Force::new(10.0, 20.0, 30.0);
-        assert_eq!(force.fx, 10.0);
-        assert_eq!(force.fy, 20.0);
-        assert_eq!(force.fz, 30.0);
+    fn test_register_forces_reuses_maps_after_clear() {
+        let mut registry = ForceRegistry::new();
+        let e1 = Entity::new(1, 0);
+        let forces = HashMap::from([(e1, Force::new(1.0, 0.0, 0.0))]);
+
+        registry.register_forces(&forces);
+        registry.register_ordered_forces(&[(e1, Force::new(0.0, 2.0, 0.0))]);
+        assert_eq!(registry.precomputed_maps.len(), 2);
+        assert_eq!(registry.get_force(e1), Some(Force::new(1.0, 2.0, 0.0)));
+
+        // Both maps are free again once their providers are cleared
+        for step in 0..3 {
+            registry.clear();
+            let forces = HashMap::from([(e1, Force::new(step as Real, 0.0, 0.0))]);
+            registry.register_forces(&forces);
+            registry.register_forces(&forces);
+            assert_eq!(registry.precomputed_maps.len(), 2);
+            assert_eq!(registry.get_force(e1), Some(Force::new(2.0 * step as Real, 0.0, 0.0)));
+        }
     }
 
     #[test]
-    fn test_force_validation() {
-        let valid = Force::new(1.0, 2.0, 3.0);
-        assert!(valid.is_valid());
+    fn test_register_ordered_forces_accumulates_immediately() {
+        let mut registry = ForceRegistry::new();
+        let e1 = Entity::new(1, 0);
+        let e2 = Entity::new(2, 0);
 
-        let invalid = Force::new(f64::NAN, 2.0, 3.0);
-        assert!(!invalid.is_valid());
+        let forces = [(e2, Force::new(-4.0, 0.0, 0.0)), (e1, Force::new(1.0, 2.0, 3.0))];
+        assert_eq!(registry.register_ordered_forces(&forces), 2);
+        assert_eq!(registry.provider_count(), 1);
+        assert_eq!(registry.get_force(e1), Some(Force::new(1.0, 2.0, 3.0)));
+        assert_eq!(registry.get_force(e2), Some(Force::new(-4.0, 0.0, 0.0)));
     }
 
     #[test]
-    fn test_force_add() {
-        let mut f1 = Force::new(1.0, 2.0, 3.0);
-        let f2 = Force::new(4.0, 5.0, 6.0);
-        f1.add(&f2);
-        assert_eq!(f1.fx, 5.0);
-        assert_eq!(f1.fy, 7.0);
-        assert_eq!(f1.fz, 9.0);
+    fn test_evaluation_velocities_replace_and_survive_clear() {
+        let (a, b, c) = (Entity::new(1, 0), Entity::new(2, 0), Entity::new(3, 0));
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        velocities.insert(a, Velocity::new(1.0, 0.0, 0.0));
+        velocities.insert(b, Velocity::new(0.0, 2.0, 0.0));
+
+        let mut registry = ForceRegistry::new();
+        registry.set_velocity(c, Velocity::new(9.0, 9.0, 9.0));
+        registry.set_velocities([a, b, c].iter(), &velocities);
+        assert_eq!(registry.velocity(a), Some(Velocity::new(1.0, 0.0, 0.0)));
+        assert_eq!(registry.velocity(b), Some(Velocity::new(0.0, 2.0, 0.0)));
+        // Replaced, not merged: c has no velocity in the storage
+        assert_eq!(registry.velocity(c), None);
+
+        registry.clear();
+        assert_eq!(registry.velocity(a), Some(Velocity::new(1.0, 0.0, 0.0)));
     }
 
     #[test]
-    fn test_force_magnitude() {
-        let force = Force::new(3.0, 4.0, 0.0);
-        assert_eq!(force.magnitude(), 5.0);
-    }
+    fn test_force_overflow_detection() {
+        let mut registry = ForceRegistry::new();
+        registry.set_max_force_magnitude(100.0);
+        registry.set_warn_on_missing_components(false);
 
-    struct TestForceProvider {
-        force: Force,
-    }
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(1000.0, 0.0, 0.0), // Exceeds limit
+        }));
 
-    impl ForceProvider for TestForceProvider {
-        fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
-            Some(self.force)
-        }
+        let entity = Entity::new(1, 0);
+        registry.accumulate_for_entity(entity);
 
-        fn name(&self) -> &str {
-            "TestForceProvider"
-        }
+        let force = registry.get_force(entity).unwrap();
+        // Should be clamped to max magnitude
+        assert!(force.magnitude() <= 100.0 + 1e-6);
     }
 
     #[test]
-    fn test_force_registry() {
+    fn test_acceleration_from_force_policy() {
         let mut registry = ForceRegistry::new();
-        assert_eq!(registry.provider_count(), 0);
+        registry.set_warn_on_missing_components(false);
+        let force = Force::new(30.0, 40.0, 0.0);
+
+        let acc = registry.acceleration_from_force(&force, &Mass::new(2.0)).unwrap();
+        assert_eq!((acc.ax(), acc.ay(), acc.az()), (15.0, 20.0, 0.0));
+
+        // Immovable, below the minimum mass, and non-finite inputs are rejected
+        assert!(registry.acceleration_from_force(&force, &Mass::immovable()).is_none());
+        registry.set_min_mass(5.0);
+        assert!(registry.acceleration_from_force(&force, &Mass::new(2.0)).is_none());
+        registry.set_min_mass(0.0);
+        let nan = Force::new(Real::NAN, 0.0, 0.0);
+        assert!(registry.acceleration_from_force(&nan, &Mass::new(2.0)).is_none());
+        let overflowing = Force::new(Real::MAX, 0.0, 0.0);
+        assert!(registry.acceleration_from_force(&overflowing, &Mass::new(0.5)).is_none());
+
+        // The acceleration limit keeps the direction
+        registry.set_max_acceleration(Some(10.0));
+        let acc = registry.acceleration_from_force(&force, &Mass::new(1.0)).unwrap();
+        assert!((acc.ax() - 6.0).abs() < Real::TOLERANCE);
+        assert!((acc.ay() - 8.0).abs() < Real::TOLERANCE);
+        let acc = registry.acceleration_from_force(&Force::new(Real::MAX, Real::MAX, 0.0), &Mass::new(1.0)).unwrap();
+        assert!((acc.ax() - 10.0 / (2.0 as Real).sqrt()).abs() < Real::TOLERANCE);
+    }
 
-        let provider = Box::new(TestForceProvider {
-            force: Force::new(10.0, 0.0, 0.0),
-        });
-        registry.register_provider(provider);
-        assert_eq!(registry.provider_count(), 1);
+    #[test]
+    #[should_panic(expected = "Maximum acceleration must be positive")]
+    fn test_non_positive_max_acceleration_panics() {
+        ForceRegistry::new().set_max_acceleration(Some(0.0));
     }
 
     #[test]
-    fn test_force_accumulation() {
+    fn test_warning_handler_captures_invalid_force() {
+        let captured = Arc::new(Mutex::new(Vec::<String>::new()));
         let mut registry = ForceRegistry::new();
-        
-        // Register two force providers
-        registry.register_provider(Box::new(TestForceProvider {
-            force: Force::new(10.0, 0.0, 0.0),
+        let sink = Arc::clone(&captured);
+        registry.set_warning_handler(Box::new(move |message| {
+            sink.lock().unwrap().push(message.to_string());
         }));
         registry.register_provider(Box::new(TestForceProvider {
-            force: Force::new(0.0, 20.0, 0.0),
+            force: Force::new(Real::NAN, 0.0, 0.0),
         }));
 
-        let entity = Entity::new(1, 0);
-        assert!(registry.accumulate_for_entity(entity));
+        let entity = Entity::new(7, 0);
+        assert!(!registry.accumulate_for_entity(entity));
 
-        let force = registry.get_force(entity).unwrap();
-        assert_eq!(force.fx, 10.0);
-        assert_eq!(force.fy, 20.0);
-        assert_eq!(force.fz, 0.0);
+        let messages = captured.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("invalid force"));
+        assert!(messages[0].contains(&format!("{:?}", entity)));
+        drop(messages);
+
+        // The warn flag still gates the handler
+        registry.set_warn_on_missing_components(false);
+        registry.accumulate_for_entity(entity);
+        assert_eq!(captured.lock().unwrap().len(), 1);
     }
 
     #[test]
-    fn test_force_overflow_detection() {
+    fn test_warning_handler_captures_missing_mass() {
+        let captured = Arc::new(Mutex::new(Vec::<String>::new()));
         let mut registry = ForceRegistry::new();
-        registry.max_force_magnitude = 100.0;
-        registry.warn_on_missing_components = false;
-
+        let sink = Arc::clone(&captured);
+        registry.set_warning_handler(Box::new(move |message| {
+            sink.lock().unwrap().push(message.to_string());
+        }));
         registry.register_provider(Box::new(TestForceProvider {
-            force: Force::new(1000.0, 0.0, 0.0), // Exceeds limit
+            force: Force::new(1.0, 0.0, 0.0),
         }));
 
-        let entity = Entity::new(1, 0);
+        let entity = Entity::new(3, 0);
         registry.accumulate_for_entity(entity);
+        let masses = HashMapStorage::<Mass>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        apply_forces_to_acceleration([entity].iter(), &registry, &masses, &mut accelerations, true);
 
-        let force = registry.get_force(entity).unwrap();
-        // Should be clamped to max magnitude
-        assert!(force.magnitude() <= 100.0 + 1e-6);
+        let messages = captured.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("no Mass component"), "{}", messages[0]);
     }
 
     #[test]
@@ -485,7 +2123,7 @@ mod tests {
 
         let mut accelerations = HashMapStorage::<Acceleration>::new();
 
-        let entities = vec![entity];
+        let entities = [entity];
         let count = apply_forces_to_acceleration(
             entities.iter(),
             &registry,
@@ -501,6 +2139,34 @@ mod tests {
         assert_eq!(acc.az(), 0.0);
     }
 
+    #[test]
+    fn test_initialize_accelerations_covers_every_movable_entity() {
+        let pushed = Entity::new(1, 0);
+        let idle = Entity::new(2, 0);
+        let anchored = Entity::new(3, 0);
+
+        let mut registry = ForceRegistry::new();
+        registry.register_forces(&HashMap::from([(pushed, Force::new(0.0, 30.0, 0.0))]));
+        registry.clear_forces();
+
+        let mut masses = HashMapStorage::<Mass>::new();
+        masses.insert(pushed, Mass::new(10.0));
+        masses.insert(idle, Mass::new(1.0));
+        masses.insert(anchored, Mass::immovable());
+
+        // A stale acceleration from an earlier run must not survive
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        accelerations.insert(idle, Acceleration::new(5.0, 0.0, 0.0));
+
+        let entities = [pushed, idle, anchored];
+        let count = initialize_accelerations(entities.iter(), &mut registry, &masses, &mut accelerations, false);
+
+        assert_eq!(count, 1);
+        assert_eq!(accelerations.get(pushed).unwrap().ay(), 3.0);
+        assert_eq!(*accelerations.get(idle).unwrap(), Acceleration::zero());
+        assert!(accelerations.get(anchored).is_none());
+    }
+
     #[test]
     fn test_apply_forces_skips_immovable() {
         let mut registry = ForceRegistry::new();
@@ -516,7 +2182,7 @@ mod tests {
 
         let mut accelerations = HashMapStorage::<Acceleration>::new();
 
-        let entities = vec![entity];
+        let entities = [entity];
         let count = apply_forces_to_acceleration(
             entities.iter(),
             &registry,
@@ -530,6 +2196,304 @@ mod tests {
         assert!(!accelerations.contains(entity));
     }
 
+    #[test]
+    fn test_tiny_mass_does_not_blow_up() {
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(1e9, 0.0, 0.0),
+        }));
+
+        let entity = Entity::new(1, 0);
+        registry.accumulate_for_entity(entity);
+
+        let mut masses = HashMapStorage::<Mass>::new();
+        masses.insert(entity, Mass::new(1e-300));
+        assert_eq!(masses.get(entity).unwrap().inverse(), 0.0);
+
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        accelerations.insert(entity, Acceleration::zero());
+        let entities = [entity];
+        let count = apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+
+        assert_eq!(count, 0);
+        assert!(accelerations.get(entity).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_min_mass_clamps_inverse_mass() {
+        let captured = Arc::new(Mutex::new(Vec::<String>::new()));
+        let mut registry = ForceRegistry::new();
+        let sink = Arc::clone(&captured);
+        registry.set_warning_handler(Box::new(move |message| {
+            sink.lock().unwrap().push(message.to_string());
+        }));
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(1e9, 0.0, 0.0),
+        }));
+        registry.set_min_mass(1e-3);
+
+        // Movable by Mass's own threshold, but lighter than the registry's minimum
+        let light = Mass::new(1e-6);
+        assert_eq!(light.inverse(), 1e6);
+        assert_eq!(registry.inverse_mass(&light), 0.0);
+        assert_eq!(registry.inverse_mass(&Mass::new(2.0)), 0.5);
+        assert!(registry.acceleration_from_force(&Force::new(1.0, 0.0, 0.0), &light).is_none());
+
+        let entity = Entity::new(1, 0);
+        registry.accumulate_for_entity(entity);
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut dense_masses = MassSoAStorage::new();
+        masses.insert(entity, light);
+        dense_masses.insert(entity, light);
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut dense_accelerations = AccelerationSoAStorage::new();
+        accelerations.insert(entity, Acceleration::zero());
+        dense_accelerations.insert(entity, Acceleration::zero());
+        let entities = [entity];
+        assert_eq!(apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, true), 0);
+        assert_eq!(
+            apply_forces_to_acceleration(entities.iter(), &registry, &dense_masses, &mut dense_accelerations, true),
+            0
+        );
+        assert_eq!(accelerations.get(entity), Some(&Acceleration::zero()));
+        assert_eq!(dense_accelerations.field_arrays().unwrap().as_acceleration_arrays().0, &[0.0]);
+
+        // Both paths warn through the registry's handler
+        let messages = captured.lock().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.contains("below the minimum")));
+    }
+
+    #[test]
+    fn test_configurable_min_mass() {
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(1.0, 0.0, 0.0),
+        }));
+        registry.set_min_mass(1.0);
+        assert_eq!(registry.min_mass(), 1.0);
+
+        let (light, heavy) = (Entity::new(1, 0), Entity::new(2, 0));
+        let mut masses = HashMapStorage::<Mass>::new();
+        masses.insert(light, Mass::new(0.5));
+        masses.insert(heavy, Mass::new(2.0));
+        for entity in [light, heavy] {
+            registry.accumulate_for_entity(entity);
+        }
+
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let entities = [light, heavy];
+        let count = apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+
+        assert_eq!(count, 1);
+        assert!(!accelerations.contains(light));
+        assert_eq!(accelerations.get(heavy).unwrap().ax(), 0.5);
+
+        // Lowering below the immovable threshold cannot make tiny masses movable
+        registry.set_min_mass(0.0);
+        assert_eq!(registry.min_mass(), Mass::IMMOVABLE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_apply_forces_to_acceleration_soa_aligned() {
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(20.0, -10.0, 4.0),
+        }));
+
+        let e1 = Entity::new(1, 0);
+        let e2 = Entity::new(2, 0);
+        let e3 = Entity::new(3, 0);
+
+        let mut masses = MassSoAStorage::new();
+        let mut accelerations = AccelerationSoAStorage::new();
+        for (entity, mass) in [(e1, Mass::new(10.0)), (e2, Mass::immovable()), (e3, Mass::new(2.0))] {
+            masses.insert(entity, mass);
+            accelerations.insert(entity, Acceleration::zero());
+            registry.accumulate_for_entity(entity);
+        }
+
+        let count = apply_forces_to_acceleration_soa(&registry, &masses, &mut accelerations);
+        assert_eq!(count, 2);
+
+        let arrays = accelerations.field_arrays().unwrap();
+        let (ax, ay, az) = arrays.as_acceleration_arrays();
+        assert_eq!((ax[0], ay[0], az[0]), (2.0, -1.0, 0.4));
+        // Immovable body is masked out
+        assert_eq!((ax[1], ay[1], az[1]), (0.0, 0.0, 0.0));
+        assert_eq!((ax[2], ay[2], az[2]), (10.0, -5.0, 2.0));
+    }
+
+    #[test]
+    fn test_apply_forces_dense_matches_per_entity_path() {
+        let count = 2 * crate::integration::dense::CHUNK_SIZE + 31;
+        let entities: Vec<Entity> = (0..count).map(|i| Entity::new(i as u64, 0)).collect();
+        let mut registry = ForceRegistry::new();
+        registry.set_min_mass(1e-3);
+        registry.set_max_acceleration(Some(50.0));
+        let forces: Vec<(Entity, Force)> = entities
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % 11 != 0)
+            .map(|(i, entity)| {
+                let t = i as Real;
+                (*entity, Force::new(30.0 * t.sin(), 7.0 * t.cos(), if i % 13 == 0 { 1e4 } else { 0.5 }))
+            })
+            .collect();
+        registry.register_ordered_forces(&forces);
+        registry.put_to_sleep(entities[5]);
+
+        let mut dense_masses = MassSoAStorage::new();
+        let mut dense_accelerations = AccelerationSoAStorage::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        for (i, entity) in entities.iter().enumerate() {
+            let mass = match i % 7 {
+                0 => Mass::immovable(),
+                1 => Mass::new(1e-6),
+                _ => Mass::new(0.5 + (i % 5) as Real),
+            };
+            let stale = Acceleration::new(-1.0, -2.0, -3.0);
+            dense_masses.insert(*entity, mass);
+            dense_accelerations.insert(*entity, stale);
+            masses.insert(*entity, mass);
+            accelerations.insert(*entity, stale);
+        }
+
+        let dense_count = apply_forces_to_acceleration(entities.iter(), &registry, &dense_masses, &mut dense_accelerations, false);
+        let count_per_entity = apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+        assert_eq!(dense_count, count_per_entity);
+
+        for (entity, acceleration) in dense_accelerations.iter() {
+            let expected = accelerations.get(entity).unwrap();
+            assert_eq!(
+                [acceleration.ax(), acceleration.ay(), acceleration.az()].map(Real::to_bits),
+                [expected.ax(), expected.ay(), expected.az()].map(Real::to_bits),
+                "Entity {:?} differs between dense and per-entity paths",
+                entity
+            );
+        }
+    }
+
+    #[test]
+    fn test_dense_accelerations_parallel_matches_serial() {
+        let count = 16 * crate::integration::dense::CHUNK_SIZE;
+        let entities: Vec<Entity> = (0..count).map(|i| Entity::new(i as u64, 0)).collect();
+        let mut registry = ForceRegistry::new();
+        let forces: Vec<(Entity, Force)> = entities
+            .iter()
+            .enumerate()
+            .map(|(i, entity)| (*entity, Force::new((i as Real).sin(), 1.0, -(i as Real).cos())))
+            .collect();
+        registry.register_ordered_forces(&forces);
+        let mass_values: Vec<Real> = (0..count).map(|i| 1.0 + (i % 9) as Real).collect();
+
+        let run = |parallel: bool| {
+            let mut out = [vec![0.0; count], vec![0.0; count], vec![0.0; count]];
+            let [x, y, z] = &mut out;
+            let updated = dense_accelerations_from_forces(
+                &entities,
+                &registry,
+                &mass_values,
+                [x, y, z],
+                parallel,
+                false,
+                &mut DenseForceBuffers::default(),
+            );
+            (updated, out)
+        };
+        let (serial_updated, serial) = run(false);
+        let (parallel_updated, parallel) = run(true);
+
+        assert_eq!(serial_updated, count);
+        assert_eq!(parallel_updated, serial_updated);
+        for axis in 0..3 {
+            assert!(
+                serial[axis].iter().zip(&parallel[axis]).all(|(a, b)| a.to_bits() == b.to_bits()),
+                "Parallel accelerations diverged from the serial run"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cached_inverse_mass_matches_soa_path() {
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(7.0, -11.0, 13.0),
+        }));
+
+        let entities: Vec<Entity> = (0..3).map(|i| Entity::new(i, 0)).collect();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut soa_masses = MassSoAStorage::new();
+        let mut soa_accelerations = AccelerationSoAStorage::new();
+        for (entity, value) in entities.iter().zip([3.0, 0.7, 1e5]) {
+            masses.insert(*entity, Mass::new(value));
+            soa_masses.insert(*entity, Mass::new(value));
+            soa_accelerations.insert(*entity, Acceleration::zero());
+            registry.accumulate_for_entity(*entity);
+        }
+
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+        assert!(soa_masses.inverse_masses().is_some());
+        apply_forces_to_acceleration_soa(&registry, &soa_masses, &mut soa_accelerations);
+
+        // Without the cache the inverses are recomputed, to the same result
+        let mut stale_masses = MassSoAStorage::new();
+        let mut stale_accelerations = AccelerationSoAStorage::new();
+        for (entity, value) in entities.iter().zip([3.0, 0.7, 1e5]) {
+            stale_masses.insert(*entity, Mass::new(value));
+            stale_accelerations.insert(*entity, Acceleration::zero());
+        }
+        stale_masses.field_arrays_mut();
+        assert!(stale_masses.inverse_masses().is_none());
+        apply_forces_to_acceleration_soa(&registry, &stale_masses, &mut stale_accelerations);
+
+        let arrays = soa_accelerations.field_arrays().unwrap();
+        let (ax, ay, az) = arrays.as_acceleration_arrays();
+        let stale_arrays = stale_accelerations.field_arrays().unwrap();
+        assert_eq!(stale_arrays.as_acceleration_arrays(), (ax, ay, az));
+        for (i, entity) in entities.iter().enumerate() {
+            let acc = accelerations.get(*entity).unwrap();
+            let inv = 1.0 / masses.get(*entity).unwrap().value();
+            assert_eq!((acc.ax(), acc.ay(), acc.az()), (7.0 * inv, -11.0 * inv, 13.0 * inv));
+            assert_eq!((acc.ax(), acc.ay(), acc.az()), (ax[i], ay[i], az[i]));
+        }
+    }
+
+    #[test]
+    fn test_apply_forces_to_acceleration_soa_permuted() {
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(TestForceProvider {
+            force: Force::new(12.0, 0.0, 0.0),
+        }));
+
+        let entities: Vec<Entity> = (0..4).map(|i| Entity::new(i, 0)).collect();
+        let mut masses = MassSoAStorage::new();
+        for (i, entity) in entities.iter().enumerate() {
+            masses.insert(*entity, Mass::new((i + 1) as Real));
+            registry.accumulate_for_entity(*entity);
+        }
+
+        // Acceleration storage populated in reverse order, missing one entity
+        let mut accelerations = AccelerationSoAStorage::new();
+        for entity in entities.iter().skip(1).rev() {
+            accelerations.insert(*entity, Acceleration::zero());
+        }
+        assert_ne!(accelerations.dense_entities(), masses.dense_entities());
+
+        let count = apply_forces_to_acceleration_soa(&registry, &masses, &mut accelerations);
+        assert_eq!(count, 4);
+        assert_eq!(accelerations.len(), 4);
+
+        let arrays = accelerations.field_arrays().unwrap();
+        let (ax, _, _) = arrays.as_acceleration_arrays();
+        for (i, entity) in entities.iter().enumerate() {
+            let index = accelerations.get_index(*entity).unwrap();
+            assert_eq!(ax[index], 12.0 / (i + 1) as Real);
+        }
+    }
+
     #[test]
     fn test_integrate_motion() {
         let entity = Entity::new(1, 0);
@@ -547,7 +2511,7 @@ mod tests {
         masses.insert(entity, Mass::new(1.0));
 
         let dt = 0.1; // 0.1 seconds
-        let entities = vec![entity];
+        let entities = [entity];
         let count = integrate_motion(
             entities.iter(),
             dt,
@@ -585,7 +2549,7 @@ mod tests {
         masses.insert(entity, Mass::new(1.0));
 
         let dt = 0.1;
-        let entities = vec![entity];
+        let entities = [entity];
         let count = integrate_motion(
             entities.iter(),
             dt,
@@ -607,6 +2571,43 @@ mod tests {
         assert!((pos.x() - 0.5).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_velocity_constant_after_force_stops() {
+        let entity = Entity::new(1, 0);
+        let wall = Entity::new(2, 0);
+        let entities = [entity, wall];
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        for &e in &entities {
+            positions.insert(e, Position::zero());
+            velocities.insert(e, Velocity::zero());
+        }
+        masses.insert(entity, Mass::new(2.0));
+        masses.insert(wall, Mass::immovable());
+        accelerations.insert(wall, Acceleration::new(1.0, 0.0, 0.0));
+
+        let mut registry = ForceRegistry::new();
+        let push = HashMap::from([(entity, Force::new(4.0, 0.0, 0.0))]);
+        for step in 0..20 {
+            registry.clear();
+            if step < 10 {
+                registry.register_forces(&push);
+            }
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+            reset_unforced_accelerations(entities.iter(), &registry, &masses, &mut accelerations);
+            integrate_motion(entities.iter(), 0.1, &mut positions, &mut velocities, &accelerations, &masses, false);
+        }
+
+        // 10 steps of a = 2 m/s² at dt = 0.1, then coasting
+        assert!((velocities.get(entity).unwrap().dx() - 2.0).abs() < 1e-10 * Real::EPSILON_RATIO);
+        assert_eq!(accelerations.get(entity), Some(&Acceleration::zero()));
+        // Immovable bodies are not touched
+        assert_eq!(accelerations.get(wall).unwrap().ax(), 1.0);
+    }
+
     #[test]
     fn test_integrate_motion_skips_immovable() {
         let entity = Entity::new(1, 0);
@@ -624,7 +2625,7 @@ mod tests {
         masses.insert(entity, Mass::immovable());
 
         let dt = 0.1;
-        let entities = vec![entity];
+        let entities = [entity];
         let count = integrate_motion(
             entities.iter(),
             dt,
@@ -645,10 +2646,63 @@ mod tests {
         assert_eq!(pos.x(), 0.0);
     }
 
+    #[test]
+    fn test_integrate_motion_dense_matches_per_entity_path() {
+        use crate::ecs::{PositionSoAStorage, VelocitySoAStorage};
+
+        // Several dense chunks with immovable bodies scattered through them
+        let count = 2 * crate::integration::dense::CHUNK_SIZE + 57;
+        let entities: Vec<Entity> = (0..count).map(|i| Entity::new(i as u64, 0)).collect();
+        let mut dense = (
+            PositionSoAStorage::new(),
+            VelocitySoAStorage::new(),
+            AccelerationSoAStorage::new(),
+            MassSoAStorage::new(),
+        );
+        let mut sparse = (
+            HashMapStorage::<Position>::new(),
+            HashMapStorage::<Velocity>::new(),
+            HashMapStorage::<Acceleration>::new(),
+            HashMapStorage::<Mass>::new(),
+        );
+        for (i, entity) in entities.iter().enumerate() {
+            let t = i as Real;
+            let position = Position::new(t.sin(), t.cos(), 0.1 * t);
+            let velocity = Velocity::new(0.7 * t.cos(), 0.3, -0.2 * t.sin());
+            let acceleration = Acceleration::new(-t.sin(), 0.5, 0.01 * t);
+            let mass = if i % 5 == 0 { Mass::immovable() } else { Mass::new(2.0) };
+            dense.0.insert(*entity, position);
+            dense.1.insert(*entity, velocity);
+            dense.2.insert(*entity, acceleration);
+            dense.3.insert(*entity, mass);
+            sparse.0.insert(*entity, position);
+            sparse.1.insert(*entity, velocity);
+            sparse.2.insert(*entity, acceleration);
+            sparse.3.insert(*entity, mass);
+        }
+
+        let dt = 0.01;
+        let dense_count = integrate_motion(entities.iter(), dt, &mut dense.0, &mut dense.1, &dense.2, &dense.3, false);
+        let sparse_count = integrate_motion(entities.iter(), dt, &mut sparse.0, &mut sparse.1, &sparse.2, &sparse.3, false);
+        assert_eq!(dense_count, sparse_count);
+        assert_eq!(dense_count, (0..count).filter(|i| i % 5 != 0).count());
+
+        for ((entity, position), (_, velocity)) in dense.0.iter().zip(dense.1.iter()) {
+            let expected_position = sparse.0.get(entity).unwrap();
+            let expected_velocity = sparse.1.get(entity).unwrap();
+            assert_eq!(position.x().to_bits(), expected_position.x().to_bits());
+            assert_eq!(position.y().to_bits(), expected_position.y().to_bits());
+            assert_eq!(position.z().to_bits(), expected_position.z().to_bits());
+            assert_eq!(velocity.dx().to_bits(), expected_velocity.dx().to_bits());
+            assert_eq!(velocity.dy().to_bits(), expected_velocity.dy().to_bits());
+            assert_eq!(velocity.dz().to_bits(), expected_velocity.dz().to_bits());
+        }
+    }
+
     #[test]
     fn test_missing_components_handling() {
         let mut registry = ForceRegistry::new();
-        registry.warn_on_missing_components = false;
+        registry.set_warn_on_missing_components(false);
         registry.register_provider(Box::new(TestForceProvider {
             force: Force::new(10.0, 0.0, 0.0),
         }));
@@ -659,7 +2713,7 @@ mod tests {
         let masses = HashMapStorage::<Mass>::new(); // No mass
         let mut accelerations = HashMapStorage::<Acceleration>::new();
 
-        let entities = vec![entity];
+        let entities = [entity];
         let count = apply_forces_to_acceleration(
             entities.iter(),
             &registry,
@@ -671,4 +2725,85 @@ mod tests {
         // Should skip entity without mass
         assert_eq!(count, 0);
     }
+
+    /// Contributions with widely varying magnitudes, several per entity, so
+    /// the floating-point sum depends on the order they are added in
+    fn sink_contributions() -> Vec<(Entity, Force)> {
+        (0..2000u64)
+            .map(|i| {
+                let entity = Entity::new(i % 37, 0);
+                let scale = (10.0 as Real).powi((i % 17) as i32 - 8);
+                let force = Force::new(scale * (i as Real).sin(), scale / (i as Real + 1.0), -scale);
+                (entity, force)
+            })
+            .collect()
+    }
+
+    fn accumulate_with_threads(contributions: &[(Entity, Force)], threads: usize) -> Vec<(Entity, Force)> {
+        let sink = ParallelForceSink::new();
+        let chunk_size = contributions.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            for chunk in contributions.chunks(chunk_size) {
+                let mut sink = sink.clone();
+                scope.spawn(move || {
+                    for &(entity, force) in chunk {
+                        sink.add(entity, force);
+                    }
+                });
+            }
+        });
+        sink.into_forces()
+    }
+
+    #[test]
+    fn test_parallel_sink_matches_sequential_exactly() {
+        let contributions = sink_contributions();
+
+        let mut sequential = ParallelForceSink::new();
+        for &(entity, force) in &contributions {
+            sequential.add(entity, force);
+        }
+        let sequential = sequential.into_forces();
+        assert_eq!(sequential.len(), 37);
+
+        let concurrent = accumulate_with_threads(&contributions, 8);
+        assert_eq!(concurrent, sequential);
+
+        // Committed forces land in the registry unchanged
+        let mut registry = ForceRegistry::new();
+        let sink = registry.parallel_sink();
+        let mut handle = sink.clone();
+        for &(entity, force) in &contributions {
+            handle.add(entity, force);
+        }
+        drop(handle);
+        assert_eq!(sink.commit(&mut registry), 37);
+        for (entity, force) in &sequential {
+            assert_eq!(registry.get_force(*entity), Some(*force));
+        }
+    }
+
+    #[test]
+    fn test_parallel_sink_deterministic_across_thread_counts() {
+        let contributions = sink_contributions();
+        let reference = accumulate_with_threads(&contributions, 1);
+
+        for threads in [2, 3, 5, 8, 16] {
+            let merged = accumulate_with_threads(&contributions, threads);
+            assert_eq!(merged, reference, "Merged forces differ with {} threads", threads);
+        }
+
+        let ids: Vec<u64> = reference.iter().map(|(entity, _)| entity.id().raw()).collect();
+        let mut sorted = ids.clone();
+        sorted.sort_unstable();
+        assert_eq!(ids, sorted);
+    }
+
+    #[test]
+    #[should_panic(expected = "clones are still alive")]
+    fn test_parallel_sink_rejects_live_clones() {
+        let sink = ParallelForceSink::new();
+        let _clone = sink.clone();
+        sink.into_forces();
+    }
 }
@@ -15,9 +15,137 @@
 //!
 //! The World is the central container for all ECS data,
 //! managing entities, components, and providing query interfaces.
+//!
+//! # Component Masks
+//!
+//! Storages passed to systems usually live outside the World, so the World
+//! cannot see which components an entity has. Code that inserts or removes
+//! components in such storages can report it with `note_component_added()`
+//! / `note_component_removed()`; the World then keeps a per-entity bitmask
+//! that `query_mask()` filters without touching any storage:
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//! use physics_engine::ecs::components::{Position, Mass, Radius};
+//!
+//! let mut world = World::new();
+//! let e = world.create_entity();
+//! world.note_component_added::<Position>(e);
+//! world.note_component_added::<Mass>(e);
+//!
+//! let include = world.mask_of::<Position>() | world.mask_of::<Mass>();
+//! let exclude = world.mask_of::<Radius>();
+//! assert_eq!(world.query_mask(include, exclude).collect::<Vec<_>>(), vec![e]);
+//! ```
+//!
+//! # Owned Storages
+//!
+//! For scene setup and small simulations the World can also own one
+//! `HashMapStorage` per component type. `spawn()` builds an entity with its
+//! components in one expression, keeping masks up to date:
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//! use physics_engine::ecs::components::{Position, Velocity, Mass};
+//!
+//! let mut world = World::new();
+//! let planet = world
+//!     .spawn()
+//!     .with(Position::new(1.5e11, 0.0, 0.0))
+//!     .with(Velocity::new(0.0, 29_780.0, 0.0))
+//!     .with(Mass::new(5.972e24))
+//!     .build();
+//!
+//! assert_eq!(world.get_component::<Mass>(planet).unwrap().value(), 5.972e24);
+//! ```
+//!
+//! Systems take storages by reference, so owned storages can be passed to
+//! them through `storage()` / `storage_mut()`. With the `parallel` feature,
+//! `par_query()` and `par_query_mut()` iterate owned components on Rayon's
+//! thread pool.
+//!
+//! # Live Entities
+//!
+//! `iter_entities()` walks the live entities in creation order, so callers
+//! do not need to keep their own entity lists in sync with destruction.
+//! Iteration borrows the World; take a `live_entities_snapshot()` to create
+//! or destroy entities while walking them:
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//!
+//! let mut world = World::new();
+//! let a = world.create_entity();
+//! let b = world.create_entity();
+//! world.destroy_entity(a);
+//!
+//! for entity in world.live_entities_snapshot() {
+//!     world.destroy_entity(entity);
+//!     world.create_entity();
+//! }
+//! assert!(!world.is_entity_alive(b));
+//! assert_eq!(world.iter_entities().count(), 1);
+//! ```
+//!
+//! Destroyed IDs are reused before new ones are allocated. After heavy
+//! churn, `compact()` releases the bookkeeping held for IDs above the
+//! highest live one.
+
+use crate::ecs::{Component, ComponentStorage, Entity, ExternalIdError, ExternalIdMap, HashMapStorage, MemoryReport};
+use crate::ecs::component::hash_map_bytes;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[cfg(feature = "parallel")]
+use crate::ecs::Query;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Bitmask of component types, one bit per type registered with a World
+pub type ComponentMask = u64;
+
+/// Maximum number of component types a World can track in masks
+pub const MAX_COMPONENT_TYPES: usize = ComponentMask::BITS as usize;
+
+/// Destroyed entries the creation-order list may hold beyond the live count
+/// before `destroy_entity()` drops them
+const CREATION_ORDER_SLACK: usize = 64;
+
+/// Type-erased view of a World-owned storage
+trait OwnedStorage: Send + Sync {
+    fn remove_entity(&mut self, entity: Entity);
+    fn clear(&mut self);
+    fn memory_report(&self) -> MemoryReport;
+    fn shrink_to_fit(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Component> OwnedStorage for HashMapStorage<T> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.remove(entity);
+    }
+
+    fn clear(&mut self) {
+        ComponentStorage::clear(self);
+    }
+
+    fn memory_report(&self) -> MemoryReport {
+        ComponentStorage::memory_report(self)
+    }
 
-use crate::ecs::Entity;
-use std::collections::{HashSet, VecDeque};
+    fn shrink_to_fit(&mut self) {
+        ComponentStorage::shrink_to_fit(self);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
 
 /// The main ECS world container
 ///
@@ -28,6 +156,17 @@ pub struct World {
     free_ids: VecDeque<u64>,
     entity_generations: Vec<u32>,
     alive_entities: HashSet<Entity>,
+    /// Entities in creation order; destroyed ones linger until pruned
+    creation_order: Vec<Entity>,
+    /// Generation given to ID slots allocated after `compact()` dropped them
+    generation_floor: u32,
+    component_bits: HashMap<TypeId, u32>,
+    /// Component mask per entity ID (zero for dead IDs)
+    entity_masks: Vec<ComponentMask>,
+    /// Storages owned by the World, keyed by component type
+    storages: HashMap<TypeId, Box<dyn OwnedStorage>>,
+    /// External IDs of entities created with one
+    external_ids: ExternalIdMap,
 }
 
 impl World {
@@ -38,6 +177,12 @@ impl World {
             free_ids: VecDeque::new(),
             entity_generations: Vec::new(),
             alive_entities: HashSet::new(),
+            creation_order: Vec::new(),
+            generation_floor: 0,
+            component_bits: HashMap::new(),
+            entity_masks: Vec::new(),
+            storages: HashMap::new(),
+            external_ids: ExternalIdMap::new(),
         }
     }
 
@@ -64,6 +209,12 @@ impl World {
             free_ids: VecDeque::with_capacity(capacity / 4), // Estimate 25% turnover
             entity_generations: Vec::with_capacity(capacity),
             alive_entities: HashSet::with_capacity(capacity),
+            creation_order: Vec::with_capacity(capacity),
+            generation_floor: 0,
+            component_bits: HashMap::new(),
+            entity_masks: Vec::with_capacity(capacity),
+            storages: HashMap::new(),
+            external_ids: ExternalIdMap::new(),
         }
     }
 
@@ -74,6 +225,49 @@ impl World {
     pub fn reserve(&mut self, additional: usize) {
         self.entity_generations.reserve(additional);
         self.alive_entities.reserve(additional);
+        self.creation_order.reserve(additional);
+        self.entity_masks.reserve(additional);
+    }
+
+    /// Release unused capacity in the entity bookkeeping and owned storages
+    ///
+    /// Entity IDs and generations are preserved; only spare allocation is
+    /// returned. Storages kept outside the World are shrunk separately with
+    /// `ComponentStorage::shrink_to_fit()`.
+    pub fn shrink_to_fit(&mut self) {
+        self.free_ids.shrink_to_fit();
+        self.entity_generations.shrink_to_fit();
+        self.alive_entities.shrink_to_fit();
+        self.creation_order.shrink_to_fit();
+        self.entity_masks.shrink_to_fit();
+        self.external_ids.shrink_to_fit();
+        for storage in self.storages.values_mut() {
+            storage.shrink_to_fit();
+        }
+    }
+
+    /// Report approximate memory held by the entity bookkeeping and owned storages
+    ///
+    /// Storages kept outside the World are not included; combine this with
+    /// each storage's `memory_report()` for a full picture.
+    pub fn memory_report(&self) -> MemoryReport {
+        let bookkeeping = MemoryReport {
+            entity_capacity: self.entity_generations.capacity(),
+            component_bytes: 0,
+            map_overhead_estimate: self.entity_generations.capacity() * std::mem::size_of::<u32>()
+                + self.free_ids.capacity() * std::mem::size_of::<u64>()
+                + hash_map_bytes::<Entity, ()>(self.alive_entities.capacity())
+                + self.creation_order.capacity() * std::mem::size_of::<Entity>()
+                + self.entity_masks.capacity() * std::mem::size_of::<ComponentMask>()
+                + self.external_ids.memory_bytes(),
+        };
+        // Owned storages add bytes but not entity slots
+        let owned: MemoryReport = self.storages.values().map(|storage| storage.memory_report()).sum();
+        MemoryReport {
+            entity_capacity: bookkeeping.entity_capacity,
+            component_bytes: owned.component_bytes,
+            map_overhead_estimate: bookkeeping.map_overhead_estimate + owned.map_overhead_estimate,
+        }
     }
 
     /// Create a new entity
@@ -86,29 +280,67 @@ impl World {
 
         // Extend generations vector if needed
         if id as usize >= self.entity_generations.len() {
-            self.entity_generations.resize(id as usize + 1, 0);
+            self.entity_generations.resize(id as usize + 1, self.generation_floor);
+            self.entity_masks.resize(id as usize + 1, 0);
         }
 
         let generation = self.entity_generations[id as usize];
         let entity = Entity::new(id, generation);
         self.alive_entities.insert(entity);
+        self.creation_order.push(entity);
         
         entity
     }
 
+    /// Create a new entity mapped to an ID from outside the engine
+    ///
+    /// The mapping lasts until the entity is destroyed. Fails, creating no
+    /// entity, if the external ID is already mapped to a live entity.
+    pub fn create_entity_with_external_id(&mut self, external_id: u64) -> Result<Entity, ExternalIdError> {
+        self.external_ids.check_free(external_id)?;
+        let entity = self.create_entity();
+        self.external_ids.insert(external_id, entity)?;
+        Ok(entity)
+    }
+
+    /// Get the live entity mapped to an external ID
+    pub fn lookup_external(&self, external_id: u64) -> Option<Entity> {
+        self.external_ids.entity(external_id)
+    }
+
+    /// Get the external ID of a live entity, if it was created with one
+    pub fn external_id(&self, entity: Entity) -> Option<u64> {
+        self.external_ids.external_id(entity)
+    }
+
+    /// Get the map of external IDs to live entities
+    pub fn external_ids(&self) -> &ExternalIdMap {
+        &self.external_ids
+    }
+
     /// Destroy an entity
     ///
     /// This increments the generation counter to invalidate old references
-    /// and adds the ID to a free list for reuse.
+    /// and adds the ID to a free list for reuse. Any external ID mapped to
+    /// the entity is released.
     pub fn destroy_entity(&mut self, entity: Entity) -> bool {
         if self.alive_entities.remove(&entity) {
+            self.external_ids.remove_entity(entity);
             // Increment generation for this entity ID
             let id = entity.id().raw();
             let id_usize = id as usize;
             if id_usize < self.entity_generations.len() {
                 self.entity_generations[id_usize] = self.entity_generations[id_usize].wrapping_add(1);
+                self.entity_masks[id_usize] = 0;
                 self.free_ids.push_back(id);
             }
+            for storage in self.storages.values_mut() {
+                storage.remove_entity(entity);
+            }
+            // Amortized O(1): pruning frees at least as many entries as stay
+            if self.creation_order.len() > 2 * self.alive_entities.len() + CREATION_ORDER_SLACK {
+                self.prune_creation_order();
+            }
             true
         } else {
             false
@@ -125,17 +357,301 @@ impl World {
         self.alive_entities.len()
     }
 
-    /// Clear all entities
+    /// Clear all entities and their owned components
     pub fn clear(&mut self) {
+        for storage in self.storages.values_mut() {
+            storage.clear();
+        }
         self.alive_entities.clear();
+        self.creation_order.clear();
         self.entity_generations.clear();
         self.free_ids.clear();
+        self.entity_masks.clear();
+        self.external_ids.clear();
         self.next_entity_id = 0;
     }
 
-    /// Get an iterator over all alive entities
+    /// Get an iterator over references to all alive entities, in creation order
     pub fn entities(&self) -> impl Iterator<Item = &Entity> {
-        self.alive_entities.iter()
+        self.creation_order.iter().filter(|entity| self.alive_entities.contains(entity))
+    }
+
+    /// Iterate over all alive entities in creation order
+    ///
+    /// An entity that reuses a destroyed ID comes after every entity created
+    /// before it. The iterator borrows the World; use
+    /// `live_entities_snapshot()` to create or destroy entities while
+    /// iterating.
+    pub fn iter_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities().copied()
+    }
+
+    /// Collect the alive entities, in creation order, into a new Vec
+    ///
+    /// The snapshot is unaffected by later creation and destruction.
+    pub fn live_entities_snapshot(&self) -> Vec<Entity> {
+        self.iter_entities().collect()
+    }
+
+    /// Release bookkeeping left behind by destroyed entities
+    ///
+    /// Drops destroyed entities from the creation order, releases the ID
+    /// slots above the highest live ID, and then shrinks spare capacity as
+    /// `shrink_to_fit()` does. Live entities keep their IDs, so slots below
+    /// the highest live ID are kept for reuse.
+    ///
+    /// Handles to destroyed entities stay dead: IDs allocated again later
+    /// start at a generation above any released slot's.
+    pub fn compact(&mut self) {
+        self.prune_creation_order();
+
+        let live_len = self
+            .alive_entities
+            .iter()
+            .map(|entity| entity.id().raw() as usize + 1)
+            .max()
+            .unwrap_or(0);
+        if live_len < self.entity_generations.len() {
+            let released = self.entity_generations[live_len..].iter().copied();
+            self.generation_floor = released.fold(self.generation_floor, u32::max);
+            self.entity_generations.truncate(live_len);
+            self.entity_masks.truncate(live_len);
+            self.free_ids.retain(|&id| (id as usize) < live_len);
+            self.next_entity_id = live_len as u64;
+        }
+
+        self.shrink_to_fit();
+    }
+
+    /// Build a world whose live entities are exactly `entities`, in that order
+    ///
+    /// IDs between the live ones are free for reuse. Returns None if two
+    /// entities share an ID.
+    #[cfg(feature = "serde")]
+    pub(crate) fn with_entities(entities: &[Entity]) -> Option<World> {
+        let mut world = World::new();
+        let mut ids = HashSet::with_capacity(entities.len());
+        for &entity in entities {
+            let id = entity.id().raw();
+            if !ids.insert(id) {
+                return None;
+            }
+            if id as usize >= world.entity_generations.len() {
+                world.entity_generations.resize(id as usize + 1, 0);
+                world.entity_masks.resize(id as usize + 1, 0);
+            }
+            world.entity_generations[id as usize] = entity.generation();
+            world.alive_entities.insert(entity);
+            world.creation_order.push(entity);
+        }
+        world.next_entity_id = world.entity_generations.len() as u64;
+        world.free_ids = (0..world.next_entity_id).filter(|id| !ids.contains(id)).collect();
+        Some(world)
+    }
+
+    /// Drop destroyed entities from the creation order
+    fn prune_creation_order(&mut self) {
+        let alive = &self.alive_entities;
+        self.creation_order.retain(|entity| alive.contains(entity));
+    }
+
+    /// Get the mask bit for a component type, assigning one if needed
+    ///
+    /// Bits are assigned in registration order and stay fixed for the
+    /// lifetime of the World, including across `clear()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `MAX_COMPONENT_TYPES` component types are registered.
+    pub fn mask_of<T: Component>(&mut self) -> ComponentMask {
+        let next_bit = self.component_bits.len();
+        let bit = *self.component_bits.entry(TypeId::of::<T>()).or_insert_with(|| {
+            assert!(
+                next_bit < MAX_COMPONENT_TYPES,
+                "World supports at most {} component types in masks",
+                MAX_COMPONENT_TYPES
+            );
+            next_bit as u32
+        });
+        1 << bit
+    }
+
+    /// Get the mask bit for a component type without registering it
+    pub fn registered_mask_of<T: Component>(&self) -> Option<ComponentMask> {
+        self.component_bits.get(&TypeId::of::<T>()).map(|&bit| 1 << bit)
+    }
+
+    /// Record that a component of type `T` was added to an entity
+    ///
+    /// Returns false (and records nothing) if the entity is not alive.
+    pub fn note_component_added<T: Component>(&mut self, entity: Entity) -> bool {
+        if !self.is_entity_alive(entity) {
+            return false;
+        }
+        let bit = self.mask_of::<T>();
+        self.entity_masks[entity.id().raw() as usize] |= bit;
+        true
+    }
+
+    /// Record that a component of type `T` was removed from an entity
+    ///
+    /// Returns false if the entity is not alive.
+    pub fn note_component_removed<T: Component>(&mut self, entity: Entity) -> bool {
+        if !self.is_entity_alive(entity) {
+            return false;
+        }
+        if let Some(bit) = self.registered_mask_of::<T>() {
+            self.entity_masks[entity.id().raw() as usize] &= !bit;
+        }
+        true
+    }
+
+    /// Get the component mask of an entity (zero if not alive)
+    pub fn entity_mask(&self, entity: Entity) -> ComponentMask {
+        if self.is_entity_alive(entity) {
+            self.entity_masks[entity.id().raw() as usize]
+        } else {
+            0
+        }
+    }
+
+    /// Iterate over alive entities that have every component in `include`
+    /// and none of the components in `exclude`
+    ///
+    /// Masks are built with `mask_of()`. Iteration order is unspecified.
+    pub fn query_mask(
+        &self,
+        include: ComponentMask,
+        exclude: ComponentMask,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.alive_entities.iter().copied().filter(move |entity| {
+            let mask = self.entity_masks[entity.id().raw() as usize];
+            mask & include == include && mask & exclude == 0
+        })
+    }
+
+    /// Start building a new entity with World-owned components
+    ///
+    /// The entity is created immediately; `build()` returns it once all
+    /// components have been added.
+    pub fn spawn(&mut self) -> EntityBuilder<'_> {
+        let entity = self.create_entity();
+        EntityBuilder { world: self, entity }
+    }
+
+    /// Insert a component into the World-owned storage for its type
+    ///
+    /// Replaces any existing component of the same type and records it in
+    /// the entity's mask. Returns false (and inserts nothing) if the entity
+    /// is not alive.
+    pub fn insert_component<T: Component>(&mut self, entity: Entity, component: T) -> bool {
+        if !self.note_component_added::<T>(entity) {
+            return false;
+        }
+        self.owned_storage_mut::<T>().insert(entity, component);
+        true
+    }
+
+    /// Remove a component from the World-owned storage for its type
+    pub fn remove_component<T: Component>(&mut self, entity: Entity) -> Option<T> {
+        self.note_component_removed::<T>(entity);
+        self.storage_mut::<T>()?.remove(entity)
+    }
+
+    /// Get a component from the World-owned storage for its type
+    pub fn get_component<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.storage::<T>()?.get(entity)
+    }
+
+    /// Get a mutable component from the World-owned storage for its type
+    pub fn get_component_mut<T: Component>(&mut self, entity: Entity) -> Option<&mut T> {
+        self.storage_mut::<T>()?.get_mut(entity)
+    }
+
+    /// Get the World-owned storage for a component type, if any were inserted
+    pub fn storage<T: Component>(&self) -> Option<&HashMapStorage<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any().downcast_ref())
+    }
+
+    /// Get the World-owned storage for a component type mutably
+    ///
+    /// Components inserted directly through this storage are not recorded in
+    /// entity masks; use `insert_component()` for that.
+    pub fn storage_mut<T: Component>(&mut self) -> Option<&mut HashMapStorage<T>> {
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any_mut().downcast_mut())
+    }
+
+    /// Iterate in parallel over entities with every component in `Q`
+    ///
+    /// `Q` is a tuple of component types, e.g. `(Position, Velocity)`,
+    /// yielding `(Entity, &Position, &Velocity)`. Only World-owned storages
+    /// are visited; components inserted through `storage_mut()` count even
+    /// though they are not recorded in masks. Iteration order is unspecified.
+    #[cfg(feature = "parallel")]
+    pub fn par_query<Q: Query>(&self) -> impl ParallelIterator<Item = Q::Item<'_>> + '_ {
+        let storages = Q::storages(self);
+        let candidates = storages.map(Q::candidates).unwrap_or_default();
+        candidates
+            .into_par_iter()
+            .filter_map(move |entity| storages.and_then(|storages| Q::fetch(storages, entity)))
+    }
+
+    /// Iterate in parallel over one component type, with mutable access
+    ///
+    /// Borrows the World exclusively, so it cannot overlap a `par_query()`
+    /// or another mutable query. Iteration order is unspecified.
+    ///
+    /// ```compile_fail
+    /// use physics_engine::ecs::World;
+    /// use physics_engine::ecs::components::{Position, Velocity};
+    ///
+    /// let mut world = World::new();
+    /// let reading = world.par_query::<(Position, Velocity)>();
+    /// let writing = world.par_query_mut::<Velocity>(); // World already borrowed
+    /// drop(reading);
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn par_query_mut<T: Component>(&mut self) -> impl ParallelIterator<Item = (Entity, &mut T)> + '_ {
+        self.storage_mut::<T>()
+            .into_par_iter()
+            .flat_map(|storage| storage.par_iter_mut())
+    }
+
+    fn owned_storage_mut<T: Component>(&mut self) -> &mut HashMapStorage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(HashMapStorage::<T>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .expect("World: owned storage registered under the wrong type")
+    }
+}
+
+/// Builder for an entity and its World-owned components
+///
+/// Created by `World::spawn()`. The entity already exists in the World, so
+/// dropping the builder without calling `build()` leaves it alive with the
+/// components added so far.
+#[must_use = "call build() to get the spawned entity"]
+pub struct EntityBuilder<'w> {
+    world: &'w mut World,
+    entity: Entity,
+}
+
+impl EntityBuilder<'_> {
+    /// Add a component to the entity
+    pub fn with<T: Component>(self, component: T) -> Self {
+        self.world.insert_component(self.entity, component);
+        self
+    }
+
+    /// Finish building and return the entity
+    pub fn build(self) -> Entity {
+        self.entity
     }
 }
 
@@ -252,4 +768,290 @@ mod tests {
         
         assert_eq!(world.entity_count(), 100);
     }
+
+    #[derive(Debug, Clone, Copy)]
+    struct Tag;
+    impl Component for Tag {}
+
+    #[test]
+    fn test_query_mask_matches_brute_force() {
+        use crate::ecs::components::{Position, Mass, Radius};
+        use crate::ecs::{ComponentStorage, HashMapStorage};
+
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut radii = HashMapStorage::<Radius>::new();
+
+        let mut entities = Vec::new();
+        for i in 0..30 {
+            let e = world.create_entity();
+            if i % 2 == 0 {
+                positions.insert(e, Position::zero());
+                world.note_component_added::<Position>(e);
+            }
+            if i % 3 == 0 {
+                masses.insert(e, Mass::new(1.0));
+                world.note_component_added::<Mass>(e);
+            }
+            if i % 5 == 0 {
+                radii.insert(e, Radius::new(1.0));
+                world.note_component_added::<Radius>(e);
+            }
+            entities.push(e);
+        }
+
+        // Remove some components and destroy some entities, then reuse IDs
+        for e in entities.iter().step_by(4) {
+            if masses.remove(*e).is_some() {
+                world.note_component_removed::<Mass>(*e);
+            }
+        }
+        for e in entities.iter().skip(1).step_by(7) {
+            positions.remove(*e);
+            masses.remove(*e);
+            radii.remove(*e);
+            world.destroy_entity(*e);
+        }
+        for _ in 0..3 {
+            let e = world.create_entity();
+            masses.insert(e, Mass::new(2.0));
+            world.note_component_added::<Mass>(e);
+        }
+
+        let include = world.mask_of::<Position>() | world.mask_of::<Mass>();
+        let exclude = world.mask_of::<Radius>();
+        let mut queried: Vec<Entity> = world.query_mask(include, exclude).collect();
+        let mut expected: Vec<Entity> = world
+            .entities()
+            .copied()
+            .filter(|e| positions.contains(*e) && masses.contains(*e) && !radii.contains(*e))
+            .collect();
+        queried.sort_by_key(|e| e.id().raw());
+        expected.sort_by_key(|e| e.id().raw());
+        assert!(!expected.is_empty());
+        assert_eq!(queried, expected);
+
+        let mass = world.mask_of::<Mass>();
+        let position = world.mask_of::<Position>();
+        let only_mass: Vec<Entity> = world.query_mask(mass, position | exclude).collect();
+        assert_eq!(
+            only_mass.len(),
+            world
+                .entities()
+                .filter(|e| masses.contains(**e) && !positions.contains(**e) && !radii.contains(**e))
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_mask_cleared_on_destroy_and_reuse() {
+        let mut world = World::new();
+        let e1 = world.create_entity();
+        assert!(world.note_component_added::<Tag>(e1));
+        let tag = world.registered_mask_of::<Tag>().unwrap();
+        assert_eq!(world.entity_mask(e1), tag);
+
+        world.destroy_entity(e1);
+        assert_eq!(world.entity_mask(e1), 0);
+        assert!(!world.note_component_added::<Tag>(e1));
+
+        // Same ID, new generation, starts with no components
+        let e2 = world.create_entity();
+        assert_eq!(e2.id(), e1.id());
+        assert_eq!(world.entity_mask(e2), 0);
+        assert_eq!(world.query_mask(tag, 0).count(), 0);
+        assert_eq!(world.query_mask(0, 0).count(), 1);
+    }
+
+    #[test]
+    fn test_mask_bits_are_stable() {
+        use crate::ecs::components::{Position, Velocity};
+
+        let mut world = World::new();
+        let p = world.mask_of::<Position>();
+        let v = world.mask_of::<Velocity>();
+        assert_ne!(p, v);
+        assert_eq!(world.mask_of::<Position>(), p);
+
+        world.clear();
+        assert_eq!(world.registered_mask_of::<Velocity>(), Some(v));
+        assert_eq!(world.registered_mask_of::<Tag>(), None);
+    }
+
+    #[test]
+    fn test_world_shrink_to_fit_after_mass_destroy() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..1000).map(|_| world.create_entity()).collect();
+        let grown = world.memory_report();
+        assert!(grown.entity_capacity >= 1000);
+
+        for e in &entities[..990] {
+            world.destroy_entity(*e);
+        }
+        world.shrink_to_fit();
+        let shrunk = world.memory_report();
+        assert!(shrunk.total_bytes() < grown.total_bytes());
+
+        // Survivors are untouched
+        for e in &entities[990..] {
+            assert!(world.is_entity_alive(*e));
+        }
+    }
+
+    #[test]
+    fn test_iter_entities_matches_churn_in_creation_order() {
+        let mut world = World::new();
+        let mut expected: Vec<Entity> = Vec::new();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+        for round in 0..2000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if expected.is_empty() || state % 5 < 3 {
+                expected.push(world.create_entity());
+            } else {
+                let victim = expected.remove((state >> 8) as usize % expected.len());
+                assert!(world.destroy_entity(victim));
+            }
+
+            if round % 50 == 0 {
+                assert_eq!(world.live_entities_snapshot(), expected);
+            }
+            assert!(world.creation_order.len() <= 2 * expected.len() + CREATION_ORDER_SLACK + 1);
+        }
+        assert_eq!(world.iter_entities().collect::<Vec<_>>(), expected);
+        assert_eq!(world.entities().count(), world.entity_count());
+    }
+
+    #[test]
+    fn test_snapshot_is_isolated_from_creation_during_iteration() {
+        let mut world = World::new();
+        let originals: Vec<Entity> = (0..5).map(|_| world.create_entity()).collect();
+
+        let snapshot = world.live_entities_snapshot();
+        let mut created = Vec::new();
+        for &entity in &snapshot {
+            world.destroy_entity(entity);
+            created.push(world.create_entity());
+        }
+
+        assert_eq!(snapshot, originals);
+        // Destroyed IDs were reused, and the new entities follow creation order
+        assert_eq!(world.live_entities_snapshot(), created);
+        assert!(originals.iter().all(|e| !world.is_entity_alive(*e)));
+    }
+
+    #[test]
+    fn test_compact_releases_bookkeeping_after_churn() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..10_000).map(|_| world.create_entity()).collect();
+        for e in &entities[100..] {
+            world.destroy_entity(*e);
+        }
+        world.shrink_to_fit();
+        let shrunk_only = world.memory_report();
+
+        world.compact();
+        let compacted = world.memory_report();
+        assert!(compacted.entity_capacity <= 100 + CREATION_ORDER_SLACK);
+        assert!(compacted.total_bytes() * 10 < shrunk_only.total_bytes());
+        assert_eq!(world.live_entities_snapshot(), &entities[..100]);
+
+        // IDs restart above the survivors, and stale handles to the released
+        // slots stay dead once those IDs are handed out again
+        let fresh: Vec<Entity> = (0..200).map(|_| world.create_entity()).collect();
+        assert_eq!(fresh[0].id().raw(), 100);
+        for (stale, new) in entities[100..300].iter().zip(&fresh) {
+            assert_eq!(stale.id(), new.id());
+            assert_ne!(stale, new);
+            assert!(!world.is_entity_alive(*stale));
+        }
+    }
+
+    #[test]
+    fn test_spawn_builder_inserts_components() {
+        use crate::ecs::components::{Mass, Position, Velocity};
+
+        let mut world = World::new();
+        world.create_entity();
+        let before = world.entity_count();
+
+        let entity = world
+            .spawn()
+            .with(Position::new(1.0, 2.0, 3.0))
+            .with(Velocity::new(4.0, 5.0, 6.0))
+            .with(Mass::new(7.0))
+            .build();
+
+        assert_eq!(world.entity_count(), before + 1);
+        assert!(world.is_entity_alive(entity));
+        assert_eq!(world.get_component::<Position>(entity).unwrap().y(), 2.0);
+        assert_eq!(world.get_component::<Velocity>(entity).unwrap().dz(), 6.0);
+        assert_eq!(world.get_component::<Mass>(entity).unwrap().value(), 7.0);
+
+        let include = world.mask_of::<Position>() | world.mask_of::<Velocity>() | world.mask_of::<Mass>();
+        assert_eq!(world.query_mask(include, 0).collect::<Vec<_>>(), vec![entity]);
+    }
+
+    #[test]
+    fn test_owned_components_follow_entity_lifecycle() {
+        use crate::ecs::components::Mass;
+
+        let mut world = World::new();
+        let entity = world.spawn().with(Mass::new(1.0)).build();
+        assert!(world.storage::<Mass>().unwrap().contains(entity));
+
+        world.get_component_mut::<Mass>(entity).unwrap().set_value(2.0);
+        assert_eq!(world.remove_component::<Mass>(entity).unwrap().value(), 2.0);
+        assert_eq!(world.entity_mask(entity), 0);
+
+        world.insert_component(entity, Mass::new(3.0));
+        world.destroy_entity(entity);
+        assert!(!world.storage::<Mass>().unwrap().contains(entity));
+        assert!(!world.insert_component(entity, Mass::new(4.0)));
+        assert!(world.get_component::<Mass>(entity).is_none());
+    }
+
+    #[test]
+    fn test_duplicate_external_id_is_rejected() {
+        let mut world = World::new();
+        let first = world.create_entity_with_external_id(42).unwrap();
+
+        let err = world.create_entity_with_external_id(42).unwrap_err();
+        assert_eq!(err, ExternalIdError::Duplicate { external_id: 42, entity: first });
+        assert_eq!(world.entity_count(), 1, "A rejected external ID created an entity");
+        assert_eq!(world.lookup_external(42), Some(first));
+
+        // Entities created without an external ID have none
+        let plain = world.create_entity();
+        assert_eq!(world.external_id(plain), None);
+        assert_eq!(world.external_ids().len(), 1);
+    }
+
+    #[test]
+    fn test_external_id_released_on_destroy() {
+        let mut world = World::new();
+        let first = world.create_entity_with_external_id(7).unwrap();
+        let other = world.create_entity_with_external_id(8).unwrap();
+
+        assert!(world.destroy_entity(first));
+        assert_eq!(world.lookup_external(7), None);
+        assert_eq!(world.external_id(first), None);
+        assert_eq!(world.lookup_external(8), Some(other));
+
+        // The external ID can be reused; the new entity takes over the freed
+        // slot, and the stale handle stays unmapped
+        let second = world.create_entity_with_external_id(7).unwrap();
+        assert_eq!(second.id(), first.id());
+        assert_ne!(second, first);
+        assert_eq!(world.lookup_external(7), Some(second));
+        assert_eq!(world.external_id(second), Some(7));
+        assert_eq!(world.external_id(first), None);
+
+        world.clear();
+        assert!(world.external_ids().is_empty());
+        assert_eq!(world.lookup_external(8), None);
+    }
 }
@@ -0,0 +1,69 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Errors reported while running a simulation
+//!
+//! `PhysicsError` is returned by the calls that advance a simulation or its
+//! plugins, so a host can match on what went wrong instead of parsing a
+//! message.
+
+use crate::ecs::Entity;
+use std::fmt;
+
+/// Error returned while advancing a simulation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhysicsError {
+    /// A body's state went NaN or infinite
+    NonFinite {
+        /// The first offending body, in creation order
+        entity: Entity,
+        /// The offending component, `"position"` or `"velocity"`
+        field: &'static str,
+    },
+    /// A plugin panicked in `initialize`, `update` or `shutdown`
+    PluginPanicked {
+        /// Name of the plugin, which the registry has poisoned
+        plugin: String,
+        /// The panic message, or a placeholder for a non-string payload
+        payload_message: String,
+    },
+    /// A plugin returned an error from `initialize`, `update` or `shutdown`
+    PluginFailed {
+        /// Name of the plugin
+        plugin: String,
+        /// The error the plugin returned
+        message: String,
+    },
+    /// The plugin registry could not run its plugins, for example because of
+    /// a missing or circular dependency
+    Registry(String),
+}
+
+impl fmt::Display for PhysicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhysicsError::NonFinite { entity, field } => {
+                write!(f, "Body {:?} has a non-finite {}", entity, field)
+            }
+            PhysicsError::PluginPanicked { plugin, payload_message } => {
+                write!(f, "Plugin '{}' panicked: {}", plugin, payload_message)
+            }
+            PhysicsError::PluginFailed { plugin, message } => {
+                write!(f, "Plugin '{}' failed: {}", plugin, message)
+            }
+            PhysicsError::Registry(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PhysicsError {}
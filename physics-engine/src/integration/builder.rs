@@ -0,0 +1,420 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Runtime integrator selection and configuration
+//!
+//! `Integrator::integrate()` is generic, so the trait cannot be used as
+//! `Box<dyn Integrator>`. Instead, `IntegratorBuilder` produces an
+//! `AnyIntegrator`, an enum over the concrete integrators that implements
+//! `Integrator` by forwarding to the selected one. It can be passed anywhere
+//! an integrator is expected, including `Simulation`.
+//!
+//! # Example
+//!
+//! ```
+//! use physics_engine::integration::{Integrator, IntegratorBuilder, IntegratorKind};
+//!
+//! let integrator = IntegratorBuilder::new()
+//!     .method(IntegratorKind::Yoshida)
+//!     .timestep(0.005)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(integrator.name(), "Yoshida 4");
+//! assert_eq!(integrator.timestep(), 0.005);
+//! ```
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
+use crate::ecs::systems::ForceRegistry;
+use crate::pool::PoolConfig;
+use super::{Integrator, RK4Integrator, TimestepSuggestion, VelocityVerletIntegrator, YoshidaIntegrator};
+
+/// Integration method selected at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegratorKind {
+    /// Velocity Verlet (2nd-order symplectic)
+    VelocityVerlet,
+    /// Classic Runge-Kutta 4th order
+    RK4,
+    /// Yoshida 4th-order symplectic composition
+    Yoshida,
+}
+
+/// Any of the built-in integrators, chosen at runtime
+pub enum AnyIntegrator {
+    /// Velocity Verlet integrator
+    VelocityVerlet(VelocityVerletIntegrator),
+    /// RK4 integrator
+    RK4(RK4Integrator),
+    /// Yoshida integrator
+    Yoshida(YoshidaIntegrator),
+}
+
+impl AnyIntegrator {
+    /// Get the method of the wrapped integrator
+    pub fn kind(&self) -> IntegratorKind {
+        match self {
+            AnyIntegrator::VelocityVerlet(_) => IntegratorKind::VelocityVerlet,
+            AnyIntegrator::RK4(_) => IntegratorKind::RK4,
+            AnyIntegrator::Yoshida(_) => IntegratorKind::Yoshida,
+        }
+    }
+}
+
+impl Integrator for AnyIntegrator {
+    fn name(&self) -> &str {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.name(),
+            AnyIntegrator::RK4(i) => i.name(),
+            AnyIntegrator::Yoshida(i) => i.name(),
+        }
+    }
+
+    fn timestep(&self) -> Real {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.timestep(),
+            AnyIntegrator::RK4(i) => i.timestep(),
+            AnyIntegrator::Yoshida(i) => i.timestep(),
+        }
+    }
+
+    fn set_timestep(&mut self, dt: Real) {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.set_timestep(dt),
+            AnyIntegrator::RK4(i) => i.set_timestep(dt),
+            AnyIntegrator::Yoshida(i) => i.set_timestep(dt),
+        }
+    }
+
+    fn supports_negative_dt(&self) -> bool {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.supports_negative_dt(),
+            AnyIntegrator::RK4(i) => i.supports_negative_dt(),
+            AnyIntegrator::Yoshida(i) => i.supports_negative_dt(),
+        }
+    }
+
+    fn validate_timestep(&self) -> Result<(), String> {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.validate_timestep(),
+            AnyIntegrator::RK4(i) => i.validate_timestep(),
+            AnyIntegrator::Yoshida(i) => i.validate_timestep(),
+        }
+    }
+
+    fn suggest_timestep<'a, I>(
+        &self,
+        entities: I,
+        positions: &impl ComponentStorage<Component = Position>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+    ) -> Option<TimestepSuggestion>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+            AnyIntegrator::RK4(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+            AnyIntegrator::Yoshida(i) => i.suggest_timestep(entities, positions, accelerations, masses),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.reset(),
+            AnyIntegrator::RK4(i) => i.reset(),
+            AnyIntegrator::Yoshida(i) => i.reset(),
+        }
+    }
+
+    fn prime<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &P,
+        accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        force_eval: F,
+    ) where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.prime(entities, positions, accelerations, masses, force_registry, force_eval),
+            AnyIntegrator::RK4(i) => i.prime(entities, positions, accelerations, masses, force_registry, force_eval),
+            AnyIntegrator::Yoshida(i) => i.prime(entities, positions, accelerations, masses, force_registry, force_eval),
+        }
+    }
+
+    fn integrate<'a, I>(
+        &mut self,
+        entities: I,
+        positions: &mut impl ComponentStorage<Component = Position>,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.integrate(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing,
+            ),
+            AnyIntegrator::RK4(i) => i.integrate(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing,
+            ),
+            AnyIntegrator::Yoshida(i) => i.integrate(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing,
+            ),
+        }
+    }
+
+    fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        match self {
+            AnyIntegrator::VelocityVerlet(i) => i.integrate_with(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing, refresh_forces,
+            ),
+            AnyIntegrator::RK4(i) => i.integrate_with(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing, refresh_forces,
+            ),
+            AnyIntegrator::Yoshida(i) => i.integrate_with(
+                entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing, refresh_forces,
+            ),
+        }
+    }
+}
+
+/// Builder for a runtime-selected integrator
+///
+/// Defaults to Velocity Verlet at `DEFAULT_TIMESTEP`. Options that only
+/// apply to one method (`pool_config()` for RK4, `max_substep()` for
+/// Velocity Verlet) are rejected by `build()` for the others, rather than
+/// silently ignored.
+#[derive(Debug, Clone)]
+pub struct IntegratorBuilder {
+    kind: IntegratorKind,
+    timestep: Real,
+    pool_config: Option<PoolConfig>,
+    max_substep: Option<Real>,
+}
+
+impl IntegratorBuilder {
+    /// Default timestep (s): one frame at 60 FPS
+    pub const DEFAULT_TIMESTEP: Real = 1.0 / 60.0;
+
+    /// Create a builder for Velocity Verlet at the default timestep
+    pub fn new() -> Self {
+        IntegratorBuilder {
+            kind: IntegratorKind::VelocityVerlet,
+            timestep: Self::DEFAULT_TIMESTEP,
+            pool_config: None,
+            max_substep: None,
+        }
+    }
+
+    /// Interactive preset: 60 FPS frames split into substeps of at most 1/240 s
+    ///
+    /// Velocity Verlet keeps energy bounded over long sessions, and the
+    /// substeps keep stiff contacts stable at a frame-sized timestep.
+    pub fn realtime_60fps() -> Self {
+        Self::new()
+            .method(IntegratorKind::VelocityVerlet)
+            .timestep(1.0 / 60.0)
+            .max_substep(1.0 / 240.0)
+    }
+
+    /// Offline preset: RK4 with a 1 ms timestep and larger scratch buffers
+    ///
+    /// Intended for smooth force fields where per-step accuracy matters more
+    /// than speed, with pools sized for a few thousand bodies.
+    pub fn high_accuracy() -> Self {
+        Self::new()
+            .method(IntegratorKind::RK4)
+            .timestep(1e-3)
+            .pool_config(PoolConfig::new(4096, 8))
+    }
+
+    /// Set the integration method
+    pub fn method(mut self, kind: IntegratorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the timestep in seconds
+    pub fn timestep(mut self, dt: Real) -> Self {
+        self.timestep = dt;
+        self
+    }
+
+    /// Set the buffer pool configuration (RK4 only)
+    pub fn pool_config(mut self, config: PoolConfig) -> Self {
+        self.pool_config = Some(config);
+        self
+    }
+
+    /// Cap the size of a single internal step (Velocity Verlet only)
+    ///
+    /// See `VelocityVerletIntegrator::set_max_substep()`.
+    pub fn max_substep(mut self, dt_max: Real) -> Self {
+        self.max_substep = Some(dt_max);
+        self
+    }
+
+    /// Get the selected integration method
+    pub fn kind(&self) -> IntegratorKind {
+        self.kind
+    }
+
+    /// Build the integrator
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timestep or maximum substep is not positive
+    /// and finite, or if an option does not apply to the selected method.
+    pub fn build(self) -> Result<AnyIntegrator, String> {
+        if !(self.timestep > 0.0 && self.timestep.is_finite()) {
+            return Err(format!("Timestep must be positive and finite, got {}", self.timestep));
+        }
+        if let Some(dt_max) = self.max_substep {
+            if !(dt_max > 0.0 && dt_max.is_finite()) {
+                return Err(format!("Maximum substep must be positive and finite, got {}", dt_max));
+            }
+            if self.kind != IntegratorKind::VelocityVerlet {
+                return Err(format!("Maximum substep is not supported by {:?}", self.kind));
+            }
+        }
+        if self.pool_config.is_some() && self.kind != IntegratorKind::RK4 {
+            return Err(format!("Pool configuration is not supported by {:?}", self.kind));
+        }
+
+        Ok(match self.kind {
+            IntegratorKind::VelocityVerlet => {
+                let mut integrator = VelocityVerletIntegrator::new(self.timestep);
+                integrator.set_max_substep(self.max_substep);
+                AnyIntegrator::VelocityVerlet(integrator)
+            }
+            IntegratorKind::RK4 => AnyIntegrator::RK4(RK4Integrator::with_pool_config(
+                self.timestep,
+                self.pool_config.unwrap_or_default(),
+            )),
+            IntegratorKind::Yoshida => AnyIntegrator::Yoshida(YoshidaIntegrator::new(self.timestep)),
+        })
+    }
+}
+
+impl Default for IntegratorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::HashMapStorage;
+
+    #[test]
+    fn test_builder_sets_method_and_timestep() {
+        let cases = [
+            (IntegratorKind::VelocityVerlet, "Velocity Verlet"),
+            (IntegratorKind::RK4, "Runge-Kutta 4"),
+            (IntegratorKind::Yoshida, "Yoshida 4"),
+        ];
+        for (kind, name) in cases {
+            let integrator = IntegratorBuilder::new().method(kind).timestep(0.002).build().unwrap();
+            assert_eq!(integrator.kind(), kind);
+            assert_eq!(integrator.name(), name);
+            assert_eq!(integrator.timestep(), 0.002);
+        }
+
+        let default = IntegratorBuilder::default().build().unwrap();
+        assert_eq!(default.kind(), IntegratorKind::VelocityVerlet);
+        assert_eq!(default.timestep(), IntegratorBuilder::DEFAULT_TIMESTEP);
+    }
+
+    #[test]
+    fn test_presets() {
+        let realtime = IntegratorBuilder::realtime_60fps().build().unwrap();
+        assert_eq!(realtime.timestep(), 1.0 / 60.0);
+        assert!(realtime.validate_timestep().is_ok());
+        match &realtime {
+            AnyIntegrator::VelocityVerlet(verlet) => assert_eq!(verlet.substep_count(), 4),
+            _ => panic!("realtime_60fps() should use Velocity Verlet"),
+        }
+
+        let accurate = IntegratorBuilder::high_accuracy().build().unwrap();
+        assert_eq!(accurate.kind(), IntegratorKind::RK4);
+        assert!(accurate.timestep() < realtime.timestep());
+        assert!(accurate.validate_timestep().is_ok());
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_configuration() {
+        assert!(IntegratorBuilder::new().timestep(0.0).build().is_err());
+        assert!(IntegratorBuilder::new().timestep(Real::NAN).build().is_err());
+        assert!(IntegratorBuilder::new().max_substep(-1.0).build().is_err());
+        assert!(IntegratorBuilder::realtime_60fps().method(IntegratorKind::RK4).build().is_err());
+        assert!(IntegratorBuilder::high_accuracy().method(IntegratorKind::Yoshida).build().is_err());
+    }
+
+    /// Take one step of a body under constant acceleration
+    fn step_once(integrator: &mut impl Integrator) -> Position {
+        let entity = Entity::new(0, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::zero());
+        velocities.insert(entity, Velocity::new(1.0, 0.0, 0.0));
+        accelerations.insert(entity, Acceleration::new(0.0, -9.8, 0.0));
+        masses.insert(entity, Mass::new(1.0));
+
+        integrator.integrate(
+            [entity].iter(),
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &masses,
+            &mut ForceRegistry::new(),
+            false,
+        );
+        *positions.get(entity).unwrap()
+    }
+
+    #[test]
+    fn test_any_integrator_matches_wrapped_integrator() {
+        let mut built = IntegratorBuilder::new().timestep(0.1).build().unwrap();
+        let mut direct = VelocityVerletIntegrator::new(0.1);
+        assert_eq!(step_once(&mut built), step_once(&mut direct));
+    }
+}
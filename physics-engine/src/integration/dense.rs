@@ -0,0 +1,216 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Chunked integration kernels for dense storages
+//!
+//! When every storage an integrator touches exposes field arrays in the same
+//! dense order (see `ComponentStorage::dense_order()`), positions and
+//! velocities can be updated by index instead of by entity lookup. The index
+//! range is split into fixed-size chunks; with the `parallel` feature the
+//! chunks run on the Rayon pool, otherwise in index order.
+//!
+//! Each element is updated independently, with the same arithmetic as the
+//! per-entity integrators, so results are bitwise identical however the
+//! chunks are scheduled. Immovable entries are skipped through a mask built
+//! once per step; the kernels themselves do not allocate.
+
+use crate::real::Real;
+use crate::ecs::Entity;
+use crate::ecs::systems::clamp_acceleration;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Number of dense indices per work chunk
+pub(crate) const CHUNK_SIZE: usize = 4096;
+
+/// Read-only x, y and z field arrays
+pub(crate) type Columns<'a> = [&'a [Real]; 3];
+
+/// Check that every storage's dense order is exactly `entities`
+pub(crate) fn is_aligned(entities: &[Entity], orders: &[Option<&[Entity]>]) -> bool {
+    !entities.is_empty() && orders.iter().all(|order| *order == Some(entities))
+}
+
+/// Run `kernel` over matching chunks of three field arrays and sum the results
+///
+/// `kernel` receives the dense index of the chunk's first element. Chunks run
+/// on the Rayon pool when `parallel` is set and the feature is enabled.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn for_each_chunk<K>(
+    [x, y, z]: [&mut [Real]; 3],
+    parallel: bool,
+    kernel: K,
+) -> usize
+where
+    K: Fn(usize, &mut [Real], &mut [Real], &mut [Real]) -> usize + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        if parallel {
+            return x
+                .par_chunks_mut(CHUNK_SIZE)
+                .zip(y.par_chunks_mut(CHUNK_SIZE))
+                .zip(z.par_chunks_mut(CHUNK_SIZE))
+                .enumerate()
+                .map(|(chunk, ((x, y), z))| kernel(chunk * CHUNK_SIZE, x, y, z))
+                .sum();
+        }
+    }
+    x.chunks_mut(CHUNK_SIZE)
+        .zip(y.chunks_mut(CHUNK_SIZE))
+        .zip(z.chunks_mut(CHUNK_SIZE))
+        .enumerate()
+        .map(|(chunk, ((x, y), z))| kernel(chunk * CHUNK_SIZE, x, y, z))
+        .sum()
+}
+
+/// Verlet drift x' = x + v·dt + ½·a·dt² for movable indices
+///
+/// Returns the number of movable entries whose new position is finite.
+pub(crate) fn verlet_drift(
+    positions: [&mut [Real]; 3],
+    [vx, vy, vz]: Columns<'_>,
+    [ax, ay, az]: Columns<'_>,
+    movable: &[bool],
+    dt: Real,
+    parallel: bool,
+) -> usize {
+    let dt_sq = dt * dt;
+    for_each_chunk(positions, parallel, |start, x, y, z| {
+        let mut valid = 0;
+        for j in 0..x.len() {
+            let i = start + j;
+            if !movable[i] {
+                continue;
+            }
+            x[j] = x[j] + vx[i] * dt + 0.5 * ax[i] * dt_sq;
+            y[j] = y[j] + vy[i] * dt + 0.5 * ay[i] * dt_sq;
+            z[j] = z[j] + vz[i] * dt + 0.5 * az[i] * dt_sq;
+            valid += usize::from(x[j].is_finite() && y[j].is_finite() && z[j].is_finite());
+        }
+        valid
+    })
+}
+
+/// Verlet kick v' = v + ½·(a_old + a_new)·dt
+///
+/// Only movable entries whose position (already drifted) is finite are
+/// updated. Returns the number of those whose new velocity is finite.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn verlet_kick(
+    velocities: [&mut [Real]; 3],
+    [ax_old, ay_old, az_old]: Columns<'_>,
+    [ax_new, ay_new, az_new]: Columns<'_>,
+    [px, py, pz]: Columns<'_>,
+    movable: &[bool],
+    dt: Real,
+    parallel: bool,
+) -> usize {
+    for_each_chunk(velocities, parallel, |start, vx, vy, vz| {
+        let mut valid = 0;
+        for j in 0..vx.len() {
+            let i = start + j;
+            let drifted = movable[i] && px[i].is_finite() && py[i].is_finite() && pz[i].is_finite();
+            if !drifted {
+                continue;
+            }
+            vx[j] += 0.5 * (ax_old[i] + ax_new[i]) * dt;
+            vy[j] += 0.5 * (ay_old[i] + ay_new[i]) * dt;
+            vz[j] += 0.5 * (az_old[i] + az_new[i]) * dt;
+            valid += usize::from(vx[j].is_finite() && vy[j].is_finite() && vz[j].is_finite());
+        }
+        valid
+    })
+}
+
+/// Semi-implicit Euler kick v' = v + a·dt for movable indices
+pub(crate) fn euler_kick(
+    velocities: [&mut [Real]; 3],
+    [ax, ay, az]: Columns<'_>,
+    movable: &[bool],
+    dt: Real,
+    parallel: bool,
+) {
+    for_each_chunk(velocities, parallel, |start, vx, vy, vz| {
+        for j in 0..vx.len() {
+            let i = start + j;
+            if movable[i] {
+                vx[j] += ax[i] * dt;
+                vy[j] += ay[i] * dt;
+                vz[j] += az[i] * dt;
+            }
+        }
+        0
+    });
+}
+
+/// Semi-implicit Euler drift x' = x + v'·dt for movable indices
+///
+/// Returns the number of movable entries whose position and velocity are
+/// both finite afterwards.
+pub(crate) fn euler_drift(
+    positions: [&mut [Real]; 3],
+    [vx, vy, vz]: Columns<'_>,
+    movable: &[bool],
+    dt: Real,
+    parallel: bool,
+) -> usize {
+    for_each_chunk(positions, parallel, |start, x, y, z| {
+        let mut valid = 0;
+        for j in 0..x.len() {
+            let i = start + j;
+            if !movable[i] {
+                continue;
+            }
+            x[j] += vx[i] * dt;
+            y[j] += vy[i] * dt;
+            z[j] += vz[i] * dt;
+            let finite = [x[j], y[j], z[j], vx[i], vy[i], vz[i]].iter().all(|value| value.is_finite());
+            valid += usize::from(finite);
+        }
+        valid
+    })
+}
+
+/// Acceleration a = F·m⁻¹ for active indices, checked and limited
+///
+/// Uses the same arithmetic as `ForceRegistry::acceleration_from_force()`.
+/// Inactive entries, and entries whose acceleration is not finite, are left
+/// untouched. Returns the number of entries written.
+pub(crate) fn accelerations_from_forces(
+    accelerations: [&mut [Real]; 3],
+    [fx, fy, fz]: Columns<'_>,
+    inverse_masses: &[Real],
+    active: &[bool],
+    limit: Option<Real>,
+    parallel: bool,
+) -> usize {
+    for_each_chunk(accelerations, parallel, |start, ax, ay, az| {
+        let mut written = 0;
+        for j in 0..ax.len() {
+            let i = start + j;
+            if !active[i] {
+                continue;
+            }
+            let inv_mass = inverse_masses[i];
+            let acceleration = [fx[i] * inv_mass, fy[i] * inv_mass, fz[i] * inv_mass];
+            if let Some(([x, y, z], _)) = clamp_acceleration(acceleration, limit) {
+                ax[j] = x;
+                ay[j] = y;
+                az[j] = z;
+                written += 1;
+            }
+        }
+        written
+    })
+}
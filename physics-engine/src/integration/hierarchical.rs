@@ -0,0 +1,478 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Hierarchical (block) timesteps
+//!
+//! A generalization of the multi-rate integrator to any number of rates and
+//! any integrator. Each entity carries a `TimestepClass` k and is stepped
+//! with dt / 2ᵏ, where dt is the base step; entities without a class are in
+//! class 0. Within a base step, `HierarchicalStepper` advances the classes
+//! recursively, finest first: to advance class k over an interval of
+//! dt / 2ᵏ it first advances every finer class over the two halves of the
+//! interval, then steps class k once. Every class therefore ends the base
+//! step at the same time, and the next one starts from a synchronized state.
+//!
+//! Solar-system-plus-asteroids runs are the typical use: the inner bodies
+//! take many small steps while the outer ones take one, so forces are
+//! evaluated for far fewer bodies than a global step of the inner bodies'
+//! size would need.
+//!
+//! # Approximation
+//!
+//! Forces are evaluated with the most recent positions available: when
+//! class k is stepped, the finer classes have already reached the end of
+//! its interval and the coarser classes are still at the start of theirs.
+//! A body therefore sees coarser bodies frozen for the whole of its step,
+//! and the coupling between classes is only first-order accurate, as for
+//! `MultiRateIntegrator`. Within a class the wrapped integrator keeps its
+//! own order. This is a good trade when bodies of different classes are
+//! weakly coupled, as a distant planet is to a tight binary.
+//!
+//! # Force Evaluation
+//!
+//! The callback of `integrate_with()` is passed the class being stepped as
+//! its targets and every entity of the step as its sources. The registry is
+//! only read for the targets, so a provider that computes forces on the
+//! targets alone, such as `GravitySystem::compute_forces_on()`, pays for
+//! the class instead of the whole system. `HierarchicalStats::force_evaluations`
+//! counts the targets, summed over evaluations.
+
+use crate::real::Real;
+use crate::ecs::{Component, ComponentStorage, Entity, HashMapStorage};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+use crate::ecs::systems::{apply_forces_to_acceleration, ForceRegistry};
+use super::Integrator;
+
+/// Power-of-two subdivision of the base timestep for one entity
+///
+/// Class k steps with dt / 2ᵏ, so class 0 takes the base step itself.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::integration::TimestepClass;
+///
+/// let class = TimestepClass::new(3);
+/// assert_eq!(class.subdivisions(), 8);
+/// assert_eq!(class.timestep(0.8), 0.1);
+///
+/// // Each factor of four in acceleration halves the step
+/// assert_eq!(TimestepClass::for_acceleration(64.0, 1.0), TimestepClass::new(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TimestepClass(u8);
+
+impl Component for TimestepClass {}
+
+impl TimestepClass {
+    /// Finest class, 2²⁰ steps per base step
+    pub const MAX_LEVEL: u8 = 20;
+
+    /// Create the class with 2^`level` steps per base step
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` exceeds `MAX_LEVEL`
+    pub fn new(level: u8) -> Self {
+        assert!(
+            level <= Self::MAX_LEVEL,
+            "Timestep class must be at most {}",
+            Self::MAX_LEVEL
+        );
+        TimestepClass(level)
+    }
+
+    /// Class assigned by the acceleration heuristic
+    ///
+    /// A body whose acceleration varies over a length scale L needs a step
+    /// of order √(L / |a|), so each factor of four in acceleration above
+    /// `base_acceleration`, the acceleration the base step resolves, adds
+    /// one level. Magnitudes at or below it, or NaN, give class 0; the
+    /// result is capped at `MAX_LEVEL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base_acceleration` is not positive and finite
+    pub fn for_acceleration(magnitude: Real, base_acceleration: Real) -> Self {
+        assert!(
+            base_acceleration > 0.0 && base_acceleration.is_finite(),
+            "Base acceleration must be positive and finite"
+        );
+        let ratio = magnitude / base_acceleration;
+        if ratio.is_nan() || ratio <= 1.0 {
+            return TimestepClass(0);
+        }
+        let level = (0.5 * ratio.log2()).ceil();
+        TimestepClass(level.min(Self::MAX_LEVEL as Real) as u8)
+    }
+
+    /// Get the level k
+    pub fn level(&self) -> u8 {
+        self.0
+    }
+
+    /// Number of steps this class takes per base step, 2ᵏ
+    pub fn subdivisions(&self) -> u32 {
+        1 << self.0
+    }
+
+    /// Step size of this class for a base step of `base_timestep`
+    pub fn timestep(&self, base_timestep: Real) -> Real {
+        base_timestep / self.subdivisions() as Real
+    }
+}
+
+/// Work done by the last `HierarchicalStepper` base step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HierarchicalStats {
+    /// Steps taken by the wrapped integrator, over all classes
+    pub class_steps: usize,
+    /// Bodies whose force was evaluated, summed over evaluations
+    pub force_evaluations: usize,
+}
+
+/// Integrator wrapper that steps each timestep class at its own rate
+///
+/// The wrapped integrator takes every step; its timestep is overwritten
+/// with each class's step size, so configure the base step on the stepper.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::integration::{HierarchicalStepper, VelocityVerletIntegrator};
+///
+/// let stepper = HierarchicalStepper::new(VelocityVerletIntegrator::new(0.01));
+/// assert_eq!(stepper.timestep(), 0.01);
+/// assert_eq!(stepper.stats().class_steps, 0);
+/// ```
+pub struct HierarchicalStepper<T: Integrator> {
+    inner: T,
+    timestep: Real,
+    stats: HierarchicalStats,
+}
+
+impl<T: Integrator> HierarchicalStepper<T> {
+    /// Wrap an integrator, taking its timestep as the base step
+    pub fn new(inner: T) -> Self {
+        let timestep = inner.timestep();
+        HierarchicalStepper {
+            inner,
+            timestep,
+            stats: HierarchicalStats::default(),
+        }
+    }
+
+    /// Get the base timestep, taken by class 0
+    pub fn timestep(&self) -> Real {
+        self.timestep
+    }
+
+    /// Set the base timestep
+    ///
+    /// # Panics
+    ///
+    /// Panics if timestep is non-positive, NaN, or infinite
+    pub fn set_timestep(&mut self, dt: Real) {
+        assert!(
+            dt > 0.0 && dt.is_finite(),
+            "Timestep must be positive and finite"
+        );
+        self.timestep = dt;
+    }
+
+    /// Get the wrapped integrator
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get the wrapped integrator mutably
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwrap the integrator
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Work done by the last `integrate_with()` call
+    pub fn stats(&self) -> HierarchicalStats {
+        self.stats
+    }
+
+    /// Assign every entity a class from its acceleration magnitude
+    ///
+    /// Uses `TimestepClass::for_acceleration()`, replacing any class the
+    /// entity already had. Entities without an acceleration go in class 0.
+    /// Returns the finest level assigned, or 0 for no entities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base_acceleration` is not positive and finite
+    pub fn assign_classes<'a, I>(
+        &self,
+        entities: I,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        base_acceleration: Real,
+        classes: &mut impl ComponentStorage<Component = TimestepClass>,
+    ) -> u8
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let mut finest = 0;
+        for entity in entities {
+            let magnitude = accelerations
+                .get(*entity)
+                .map_or(0.0, |a| (a.ax() * a.ax() + a.ay() * a.ay() + a.az() * a.az()).sqrt());
+            let class = TimestepClass::for_acceleration(magnitude, base_acceleration);
+            finest = finest.max(class.level());
+            classes.insert(*entity, class);
+        }
+        finest
+    }
+
+    /// Group entities by class, preserving their order
+    ///
+    /// Element k holds class k; the result ends at the finest class present.
+    pub fn split<'a, I>(
+        &self,
+        entities: I,
+        classes: &impl ComponentStorage<Component = TimestepClass>,
+    ) -> Vec<Vec<Entity>>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let mut split: Vec<Vec<Entity>> = Vec::new();
+        for entity in entities {
+            let level = classes.get(*entity).map_or(0, |class| class.level() as usize);
+            if split.len() <= level {
+                split.resize_with(level + 1, Vec::new);
+            }
+            split[level].push(*entity);
+        }
+        split
+    }
+
+    /// Integrate one base step, refreshing forces before every evaluation
+    ///
+    /// `refresh_forces` is called with the targets (the class being
+    /// stepped), the sources (every entity of the step) and the current
+    /// positions before each force evaluation. It should bring the registry
+    /// up to date for the targets, with the forces from all the sources;
+    /// forces on other entities are never read. `accelerations` must hold the
+    /// accelerations at the entry positions. The registry's time is set to
+    /// the start of each class step, and left at the end of the base step.
+    ///
+    /// Returns the number of entities updated in each class's final step,
+    /// summed over the classes.
+    #[allow(clippy::too_many_arguments)]
+    pub fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        classes: &impl ComponentStorage<Component = TimestepClass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        mut refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &[Entity], &P, &mut ForceRegistry),
+    {
+        self.stats = HierarchicalStats::default();
+        let entities_vec: Vec<Entity> = entities.copied().collect();
+        if entities_vec.is_empty() {
+            return 0;
+        }
+        let split = self.split(entities_vec.iter(), classes);
+        // Each class carries its own a(t) between its steps
+        let mut class_accelerations: Vec<HashMapStorage<Acceleration>> = split
+            .iter()
+            .map(|class| {
+                let mut storage = HashMapStorage::with_capacity(class.len());
+                for entity in class {
+                    if let Some(a) = accelerations.get(*entity) {
+                        storage.insert(*entity, *a);
+                    }
+                }
+                storage
+            })
+            .collect();
+        let mut updated = vec![0; split.len()];
+        let t0 = force_registry.time();
+
+        let mut step = ClassStep {
+            all: &entities_vec,
+            split: &split,
+            positions,
+            velocities,
+            masses,
+            force_registry,
+            warn_on_missing,
+            refresh_forces: &mut refresh_forces,
+        };
+        self.advance(&mut step, 0, t0, self.timestep, &mut class_accelerations, &mut updated);
+
+        force_registry.set_time(t0 + self.timestep);
+        updated.iter().sum()
+    }
+
+    /// Advance class `level` and every finer class over one step of `dt`
+    fn advance<P, V, M, F>(
+        &mut self,
+        step: &mut ClassStep<'_, P, V, M, F>,
+        level: usize,
+        start: Real,
+        dt: Real,
+        class_accelerations: &mut [HashMapStorage<Acceleration>],
+        updated: &mut [usize],
+    ) where
+        P: ComponentStorage<Component = Position>,
+        V: ComponentStorage<Component = Velocity>,
+        M: ComponentStorage<Component = Mass>,
+        F: FnMut(&[Entity], &[Entity], &P, &mut ForceRegistry),
+    {
+        if level + 1 < step.split.len() {
+            self.advance(step, level + 1, start, 0.5 * dt, class_accelerations, updated);
+            self.advance(step, level + 1, start + 0.5 * dt, 0.5 * dt, class_accelerations, updated);
+        }
+        let class = &step.split[level];
+        if class.is_empty() {
+            return;
+        }
+
+        step.force_registry.set_time(start);
+        self.inner.set_timestep(dt);
+        self.stats.class_steps += 1;
+        let (all, refresh_forces, evaluations) = (step.all, &mut *step.refresh_forces, &mut self.stats.force_evaluations);
+        updated[level] = self.inner.integrate_with(
+            class.iter(),
+            step.positions,
+            step.velocities,
+            &class_accelerations[level],
+            step.masses,
+            step.force_registry,
+            step.warn_on_missing,
+            |_, positions, registry| {
+                *evaluations += class.len();
+                refresh_forces(class, all, positions, registry)
+            },
+        );
+        // The registry holds the forces at the class's new positions
+        apply_forces_to_acceleration(class.iter(), step.force_registry, step.masses, &mut class_accelerations[level], false);
+    }
+}
+
+/// State shared by the class steps of one base step
+struct ClassStep<'s, P, V, M, F> {
+    all: &'s [Entity],
+    split: &'s [Vec<Entity>],
+    positions: &'s mut P,
+    velocities: &'s mut V,
+    masses: &'s M,
+    force_registry: &'s mut ForceRegistry,
+    warn_on_missing: bool,
+    refresh_forces: &'s mut F,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::World;
+    use crate::integration::VelocityVerletIntegrator;
+
+    #[test]
+    fn test_classes_from_acceleration() {
+        assert_eq!(TimestepClass::for_acceleration(0.5, 1.0).level(), 0);
+        assert_eq!(TimestepClass::for_acceleration(1.0, 1.0).level(), 0);
+        assert_eq!(TimestepClass::for_acceleration(1.5, 1.0).level(), 1);
+        assert_eq!(TimestepClass::for_acceleration(4.0, 1.0).level(), 1);
+        assert_eq!(TimestepClass::for_acceleration(4.5, 1.0).level(), 2);
+        assert_eq!(TimestepClass::for_acceleration(Real::NAN, 1.0).level(), 0);
+        assert_eq!(TimestepClass::for_acceleration(Real::INFINITY, 1.0).level(), TimestepClass::MAX_LEVEL);
+
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..3).map(|_| world.create_entity()).collect();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        accelerations.insert(entities[0], Acceleration::new(0.0, 30.0, 40.0));
+        accelerations.insert(entities[1], Acceleration::new(1.0, 0.0, 0.0));
+        let mut classes = HashMapStorage::<TimestepClass>::new();
+
+        let stepper = HierarchicalStepper::new(VelocityVerletIntegrator::new(0.1));
+        assert_eq!(stepper.assign_classes(entities.iter(), &accelerations, 1.0, &mut classes), 3);
+        assert_eq!(classes.get(entities[0]), Some(&TimestepClass::new(3)));
+        assert_eq!(classes.get(entities[2]), Some(&TimestepClass::new(0)));
+        assert_eq!(
+            stepper.split(entities.iter(), &classes),
+            vec![vec![entities[1], entities[2]], vec![], vec![], vec![entities[0]]]
+        );
+    }
+
+    #[test]
+    fn test_every_class_reaches_the_end_of_the_base_step() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..3).map(|_| world.create_entity()).collect();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut classes = HashMapStorage::<TimestepClass>::new();
+        for (level, entity) in entities.iter().enumerate() {
+            positions.insert(*entity, Position::zero());
+            velocities.insert(*entity, Velocity::new(2.0, 0.0, 0.0));
+            masses.insert(*entity, Mass::new(1.0));
+            classes.insert(*entity, TimestepClass::new(2 * level as u8));
+        }
+        let mut registry = ForceRegistry::new();
+        registry.set_time(1.0);
+
+        let mut stepper = HierarchicalStepper::new(VelocityVerletIntegrator::new(0.5));
+        let mut refreshes = 0;
+        let updated = stepper.integrate_with(
+            entities.iter(),
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &masses,
+            &classes,
+            &mut registry,
+            false,
+            |targets, sources, _, _| {
+                assert_eq!(targets.len(), 1);
+                assert_eq!(sources.len(), 3);
+                refreshes += 1;
+            },
+        );
+
+        assert_eq!(updated, 3);
+        for entity in &entities {
+            assert!((positions.get(*entity).unwrap().x() - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(registry.time(), 1.5);
+        // Classes 0, 2 and 4 take 1, 4 and 16 steps
+        assert_eq!(refreshes, 21);
+        assert_eq!(stepper.stats(), HierarchicalStats { class_steps: 21, force_evaluations: 21 });
+        // Class 0 steps last, leaving the wrapped integrator at the base step
+        assert_eq!(stepper.inner().timestep(), 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Timestep class must be at most")]
+    fn test_class_above_max_level_panics() {
+        TimestepClass::new(TimestepClass::MAX_LEVEL + 1);
+    }
+}
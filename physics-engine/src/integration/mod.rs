@@ -21,6 +21,10 @@
 //!
 //! - **Velocity Verlet**: Symplectic integrator with good energy conservation
 //! - **RK4 (Runge-Kutta 4th order)**: Higher accuracy for smooth dynamics
+//! - **Yoshida (4th-order symplectic)**: Verlet's energy behavior with RK4's order
+//! - **Multi-rate Verlet**: Substeps fast bodies within a coarser base step
+//! - **Hierarchical timesteps**: Steps each body at a power-of-two fraction of
+//!   the base step, with any of the above
 //!
 //! # Choosing an Integrator
 //!
@@ -39,16 +43,28 @@
 //! - Recommended: Start with dt = 1/60 (60 FPS) and adjust based on simulation needs
 //! - For stiff systems: Smaller timesteps or implicit integrators may be needed
 
-use crate::ecs::{Entity, ComponentStorage};
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage, NewtonianState};
 use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
-use crate::ecs::systems::ForceRegistry;
+use crate::ecs::systems::{initialize_accelerations, ForceRegistry};
 
 mod verlet;
 mod rk4;
+mod yoshida;
+mod multirate;
+mod hierarchical;
+mod builder;
 mod simd_helpers;
+mod sampler;
+pub(crate) mod dense;
 
 pub use verlet::VelocityVerletIntegrator;
-pub use rk4::RK4Integrator;
+pub use rk4::{RK4Integrator, RK4Stats};
+pub use yoshida::YoshidaIntegrator;
+pub use multirate::{FastPredicate, MultiRateIntegrator, RatePartition};
+pub use hierarchical::{HierarchicalStats, HierarchicalStepper, TimestepClass};
+pub use builder::{AnyIntegrator, IntegratorBuilder, IntegratorKind};
+pub use sampler::StateSampler;
 pub use simd_helpers::*;
 
 /// Calculate kinetic energy for a single entity
@@ -57,7 +73,7 @@ pub use simd_helpers::*;
 pub fn calculate_kinetic_energy(
     velocity: &Velocity,
     mass: &Mass,
-) -> f64 {
+) -> Real {
     if mass.is_immovable() {
         return 0.0;
     }
@@ -72,7 +88,7 @@ pub fn calculate_total_kinetic_energy<'a, I>(
     entities: I,
     velocities: &impl ComponentStorage<Component = Velocity>,
     masses: &impl ComponentStorage<Component = Mass>,
-) -> f64
+) -> Real
 where
     I: Iterator<Item = &'a Entity>,
 {
@@ -85,6 +101,244 @@ where
     total
 }
 
+/// Calculate the total power delivered by forces: P = Σ F·v
+///
+/// Uses the forces currently accumulated in the registry, so call it after
+/// `accumulate_for_entity()` (or `register_forces()`) for the state whose
+/// velocities are passed in. Entities without a force or velocity contribute
+/// nothing.
+pub fn calculate_power<'a, I>(
+    entities: I,
+    force_registry: &ForceRegistry,
+    velocities: &impl ComponentStorage<Component = Velocity>,
+) -> Real
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let mut total = 0.0;
+    for entity in entities {
+        if let (Some(force), Some(vel)) = (force_registry.get_force(*entity), velocities.get(*entity)) {
+            total += force.fx * vel.dx() + force.fy * vel.dy() + force.fz * vel.dz();
+        }
+    }
+    total
+}
+
+/// Accumulates work done by forces across steps
+///
+/// Integrates power over time with the trapezoidal rule. Comparing the
+/// cumulative work against the change in kinetic energy exposes energy the
+/// integrator injects or removes on its own. `Simulation::set_work_tracking()`
+/// keeps one for the driver's integrate phase.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::integration::WorkAccumulator;
+///
+/// let mut work = WorkAccumulator::new();
+/// work.record_step(10.0, 20.0, 0.5); // power at start and end of step, dt
+/// assert_eq!(work.cumulative_work(), 7.5);
+/// assert_eq!(work.energy_leak(7.0), -0.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkAccumulator {
+    cumulative_work: Real,
+    last_power: Real,
+    steps: usize,
+}
+
+impl WorkAccumulator {
+    /// Create an accumulator with zero work
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one step given the power at its start and end
+    ///
+    /// Returns the work done during the step.
+    pub fn record_step(&mut self, power_start: Real, power_end: Real, dt: Real) -> Real {
+        let work = 0.5 * (power_start + power_end) * dt;
+        self.cumulative_work += work;
+        self.last_power = power_end;
+        self.steps += 1;
+        work
+    }
+
+    /// Total work done by forces since creation or the last reset
+    pub fn cumulative_work(&self) -> Real {
+        self.cumulative_work
+    }
+
+    /// Power at the end of the most recent step
+    pub fn last_power(&self) -> Real {
+        self.last_power
+    }
+
+    /// Number of steps recorded
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Kinetic energy change not accounted for by work: ΔKE - W
+    ///
+    /// Positive values mean the integrator added energy; negative values mean
+    /// it removed energy.
+    pub fn energy_leak(&self, kinetic_energy_change: Real) -> Real {
+        kinetic_energy_change - self.cumulative_work
+    }
+
+    /// Reset accumulated work to zero
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Fraction of the stability limit recommended for accurate integration
+///
+/// Staying well inside the stability region keeps phase and amplitude errors
+/// small; a tenth of the limit gives roughly 30 steps per oscillation period
+/// for Verlet.
+pub const SUGGESTED_TIMESTEP_FRACTION: Real = 0.1;
+
+/// Timestep recommendation derived from the current state of the system
+///
+/// Produced by `Integrator::suggest_timestep()`. The stability limit is
+/// `stability_factor / ω_max`, where ω_max is the highest angular frequency
+/// estimated from current accelerations or a caller-provided stiffness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimestepSuggestion {
+    /// Estimated highest angular frequency in the system (rad/s)
+    pub max_angular_frequency: Real,
+    /// Largest timestep expected to remain stable for this integrator
+    pub max_stable_timestep: Real,
+    /// Recommended timestep for accurate results
+    pub suggested_timestep: Real,
+    /// Warning when the configured timestep exceeds the suggestion
+    pub warning: Option<String>,
+}
+
+impl TimestepSuggestion {
+    /// Build a suggestion from an angular frequency and stability factor
+    ///
+    /// # Arguments
+    ///
+    /// * `max_angular_frequency` - Highest angular frequency ω in rad/s
+    /// * `stability_factor` - Integrator stability bound on ω·dt
+    /// * `configured_timestep` - Timestep the integrator is currently using
+    pub fn from_frequency(
+        max_angular_frequency: Real,
+        stability_factor: Real,
+        configured_timestep: Real,
+    ) -> Self {
+        let max_stable_timestep = stability_factor / max_angular_frequency;
+        let suggested_timestep = max_stable_timestep * SUGGESTED_TIMESTEP_FRACTION;
+
+        let warning = if configured_timestep > max_stable_timestep {
+            Some(format!(
+                "Timestep {:.3e} s exceeds the estimated stability limit {:.3e} s \
+                (ω_max = {:.3e} rad/s). Expect the simulation to diverge; use dt <= {:.3e} s.",
+                configured_timestep, max_stable_timestep, max_angular_frequency, suggested_timestep
+            ))
+        } else if configured_timestep > suggested_timestep {
+            Some(format!(
+                "Timestep {:.3e} s is stable but coarse for ω_max = {:.3e} rad/s. \
+                Consider dt <= {:.3e} s for better accuracy.",
+                configured_timestep, max_angular_frequency, suggested_timestep
+            ))
+        } else {
+            None
+        };
+
+        TimestepSuggestion {
+            max_angular_frequency,
+            max_stable_timestep,
+            suggested_timestep,
+            warning,
+        }
+    }
+
+    /// Check if the configured timestep is within the stability limit
+    pub fn is_stable(&self, timestep: Real) -> bool {
+        timestep <= self.max_stable_timestep
+    }
+}
+
+/// Shared `Integrator::prime()` for integrators that read a(t) from storage
+fn prime_accelerations<'a, I, P, F>(
+    entities: I,
+    positions: &P,
+    accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    force_registry: &mut ForceRegistry,
+    mut force_eval: F,
+) where
+    I: Iterator<Item = &'a Entity>,
+    P: ComponentStorage<Component = Position>,
+    F: FnMut(&[Entity], &P, &mut ForceRegistry),
+{
+    let entities_vec: Vec<Entity> = entities.copied().collect();
+    force_eval(&entities_vec, positions, force_registry);
+    initialize_accelerations(entities_vec.iter(), force_registry, masses, accelerations, false);
+}
+
+/// Estimate the highest angular frequency in the system
+///
+/// Uses two sources and returns the larger:
+///
+/// - **Acceleration proxy**: ω ≈ √(|a| / |x|) per entity, which is exact for a
+///   harmonic restoring force centered on the origin and a reasonable order
+///   of magnitude for central forces such as gravity around a body at the origin.
+/// - **Stiffness bound**: ω = √(k / m_min) when `max_stiffness` is provided,
+///   using the lightest movable mass.
+///
+/// Immovable entities and entities at the origin are ignored by the proxy.
+/// Returns `None` if no finite positive frequency could be estimated.
+pub fn estimate_max_angular_frequency<'a, I>(
+    entities: I,
+    positions: &impl ComponentStorage<Component = Position>,
+    accelerations: &impl ComponentStorage<Component = Acceleration>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    max_stiffness: Option<Real>,
+) -> Option<Real>
+where
+    I: Iterator<Item = &'a Entity>,
+{
+    let mut omega_max: Real = 0.0;
+    let mut min_mass = Real::INFINITY;
+
+    for entity in entities {
+        let mass = match masses.get(*entity) {
+            Some(m) if !m.is_immovable() => m,
+            _ => continue,
+        };
+        min_mass = min_mass.min(mass.value());
+
+        let (pos, acc) = match (positions.get(*entity), accelerations.get(*entity)) {
+            (Some(p), Some(a)) => (p, a),
+            _ => continue,
+        };
+
+        let x = (pos.x() * pos.x() + pos.y() * pos.y() + pos.z() * pos.z()).sqrt();
+        let a = (acc.ax() * acc.ax() + acc.ay() * acc.ay() + acc.az() * acc.az()).sqrt();
+        if x > 0.0 && a.is_finite() && x.is_finite() {
+            omega_max = omega_max.max((a / x).sqrt());
+        }
+    }
+
+    if let Some(k) = max_stiffness {
+        if k > 0.0 && k.is_finite() && min_mass.is_finite() {
+            omega_max = omega_max.max((k / min_mass).sqrt());
+        }
+    }
+
+    if omega_max > 0.0 && omega_max.is_finite() {
+        Some(omega_max)
+    } else {
+        None
+    }
+}
+
 /// Trait for numerical integration methods
 ///
 /// Integrators update position and velocity components based on forces
@@ -95,31 +349,58 @@ pub trait Integrator: Send + Sync {
     fn name(&self) -> &str;
 
     /// Get the timestep used by this integrator
-    fn timestep(&self) -> f64;
+    fn timestep(&self) -> Real;
 
     /// Set the timestep for this integrator
     ///
     /// # Panics
     ///
-    /// Panics if timestep is non-positive, NaN, or infinite
-    fn set_timestep(&mut self, dt: f64);
+    /// Panics if timestep is zero, NaN, or infinite, or negative when
+    /// `supports_negative_dt()` is false
+    fn set_timestep(&mut self, dt: Real);
+
+    /// Check whether this integrator can integrate backwards in time
+    ///
+    /// Integrators returning `true` accept a negative timestep in
+    /// `set_timestep()`, so a run can be reversed with `reverse_time()`.
+    /// Time-reversible methods such as velocity Verlet then retrace their
+    /// trajectory up to rounding error. The default is `false`.
+    fn supports_negative_dt(&self) -> bool {
+        false
+    }
+
+    /// Reverse the direction of time by negating the timestep
+    ///
+    /// # Panics
+    ///
+    /// Panics if `supports_negative_dt()` is false
+    fn reverse_time(&mut self) {
+        assert!(
+            self.supports_negative_dt(),
+            "{} does not support negative timesteps",
+            self.name()
+        );
+        let dt = self.timestep();
+        self.set_timestep(-dt);
+    }
 
     /// Validate the timestep for stability
     ///
     /// Returns warnings if the timestep might cause numerical issues.
     /// Extremely small timesteps may lead to precision loss, while large
-    /// timesteps may cause instability.
+    /// timesteps may cause instability. Integrators that support negative
+    /// timesteps are checked on the magnitude of theirs.
     fn validate_timestep(&self) -> Result<(), String> {
-        let dt = self.timestep();
+        let dt = if self.supports_negative_dt() { self.timestep().abs() } else { self.timestep() };
         
         if dt <= 0.0 || !dt.is_finite() {
-            return Err(format!("Invalid timestep: {}. Must be positive and finite.", dt));
+            return Err(format!("Invalid timestep: {}. Must be positive and finite.", self.timestep()));
         }
         
         // Warn about very small timesteps (potential precision issues)
         if dt < 1e-9 {
             return Err(format!(
-                "Warning: Timestep {} is extremely small and may cause precision loss with f64. \
+                "Warning: Timestep {} is extremely small and may cause precision loss with Real. \
                 Consider using larger timestep or higher precision types.",
                 dt
             ));
@@ -137,10 +418,80 @@ pub trait Integrator: Send + Sync {
         Ok(())
     }
 
+    /// Suggest a timestep from the current state of the system
+    ///
+    /// Unlike `validate_timestep()`, which only checks static bounds, this
+    /// estimates the stability limit for the actual forces acting on the
+    /// entities. The returned suggestion carries a warning when the configured
+    /// timestep exceeds it.
+    ///
+    /// The default implementation returns `None` (no estimate available).
+    fn suggest_timestep<'a, I>(
+        &self,
+        _entities: I,
+        _positions: &impl ComponentStorage<Component = Position>,
+        _accelerations: &impl ComponentStorage<Component = Acceleration>,
+        _masses: &impl ComponentStorage<Component = Mass>,
+    ) -> Option<TimestepSuggestion>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        None
+    }
+
+    /// Clear internal state so the integrator can be reused
+    ///
+    /// Call this between independent simulations. Integrators that keep
+    /// buffers or previous-step state (pools, cached accelerations) release
+    /// it here; configuration such as the timestep is kept. The default
+    /// implementation does nothing, which is correct for stateless integrators.
+    fn reset(&mut self) {}
+
+    /// Evaluate the accelerations the first step starts from
+    ///
+    /// Velocity Verlet, Yoshida and the multi-rate integrator read a(t) from
+    /// `accelerations` rather than evaluating it, and each step leaves the
+    /// storage's contents for the next one to start from. The very first step
+    /// has no predecessor: if the caller never filled the storage, it runs
+    /// from zero accelerations, a one-step error that stays in the trajectory
+    /// as a small, permanent energy offset.
+    ///
+    /// `prime()` calls `force_eval` with the current positions to bring the
+    /// registry up to date (the same contract as the `integrate_with()`
+    /// callbacks), then fills the storage as `initialize_accelerations()`
+    /// does. Call it once before the first `integrate()`. Skipping it keeps the old behavior of
+    /// starting from whatever the storage holds.
+    ///
+    /// The default implementation does nothing, which is correct for
+    /// integrators that evaluate every force they use, such as RK4.
+    fn prime<'a, I, P, F>(
+        &mut self,
+        _entities: I,
+        _positions: &P,
+        _accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        _masses: &impl ComponentStorage<Component = Mass>,
+        _force_registry: &mut ForceRegistry,
+        _force_eval: F,
+    ) where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+    }
+
     /// Integrate motion for a collection of entities
     ///
     /// Updates position and velocity components based on forces and the
     /// integrator's numerical method. Returns the number of entities updated.
+    /// The registry's `time()` is taken as the start of the step. Before
+    /// each force evaluation it is set to that stage's time, and after the
+    /// step it is left at the start time plus the timestep.
+    ///
+    /// An empty `entities` iterator returns 0 immediately, without touching
+    /// the force registry or acquiring scratch buffers.
+    ///
+    /// Immovable entities and entities asleep in the force registry (see
+    /// `SleepSystem`) are not moved.
     ///
     /// # Arguments
     ///
@@ -155,6 +506,7 @@ pub trait Integrator: Send + Sync {
     /// # Returns
     ///
     /// Number of entities successfully updated
+    #[allow(clippy::too_many_arguments)]
     fn integrate<'a, I>(
         &mut self,
         entities: I,
@@ -167,21 +519,91 @@ pub trait Integrator: Send + Sync {
     ) -> usize
     where
         I: Iterator<Item = &'a Entity>;
+
+    /// Integrate one timestep, refreshing forces before every evaluation
+    ///
+    /// `refresh_forces` is called with the entities and their current
+    /// positions and should bring the registry up to date for them, as for
+    /// `VelocityVerletIntegrator::integrate_with()`. `accelerations` must
+    /// hold the accelerations at the entry positions.
+    ///
+    /// The default implementation refreshes once, at the entry positions,
+    /// and then calls `integrate()`, so every evaluation within the step
+    /// sees those forces. Velocity Verlet, Yoshida, RK4 and the multi-rate
+    /// integrator override it to refresh before each of their evaluations.
+    #[allow(clippy::too_many_arguments)]
+    fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        mut refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        let entities_vec: Vec<Entity> = entities.copied().collect();
+        if entities_vec.is_empty() {
+            return 0;
+        }
+        refresh_forces(&entities_vec, positions, force_registry);
+        self.integrate(
+            entities_vec.iter(),
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+        )
+    }
+
+    /// Integrate motion for the bodies of a `NewtonianState`
+    ///
+    /// Bundle form of `integrate()`: the state's positions and velocities are
+    /// updated from its accelerations and masses.
+    fn integrate_state<'a, I, P, V, A, M>(
+        &mut self,
+        entities: I,
+        state: &mut NewtonianState<P, V, A, M>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        V: ComponentStorage<Component = Velocity>,
+        A: ComponentStorage<Component = Acceleration>,
+        M: ComponentStorage<Component = Mass>,
+    {
+        let (positions, velocities, accelerations, masses) = state.split_mut();
+        self.integrate(entities, positions, velocities, accelerations, masses, force_registry, warn_on_missing)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::real::RealExt;
+    use crate::ecs::HashMapStorage;
+
     // Simple harmonic oscillator test fixture
     // Mass-spring system: F = -kx, analytical solution: x(t) = A*cos(ωt + φ)
     struct HarmonicOscillator {
-        spring_constant: f64,
-        mass: f64,
-        initial_position: f64,
-        initial_velocity: f64,
+        spring_constant: Real,
+        mass: Real,
+        initial_position: Real,
+        initial_velocity: Real,
     }
 
     impl HarmonicOscillator {
-        fn new(k: f64, m: f64, x0: f64, v0: f64) -> Self {
+        fn new(k: Real, m: Real, x0: Real, v0: Real) -> Self {
             HarmonicOscillator {
                 spring_constant: k,
                 mass: m,
@@ -190,28 +612,28 @@ mod tests {
             }
         }
 
-        fn omega(&self) -> f64 {
+        fn omega(&self) -> Real {
             (self.spring_constant / self.mass).sqrt()
         }
 
-        fn amplitude(&self) -> f64 {
+        fn amplitude(&self) -> Real {
             let omega = self.omega();
             (self.initial_position.powi(2) + (self.initial_velocity / omega).powi(2)).sqrt()
         }
 
-        fn phase(&self) -> f64 {
+        fn phase(&self) -> Real {
             let omega = self.omega();
             (-self.initial_velocity / (omega * self.initial_position)).atan()
         }
 
-        fn position_at(&self, t: f64) -> f64 {
+        fn position_at(&self, t: Real) -> Real {
             let omega = self.omega();
             let amplitude = self.amplitude();
             let phase = self.phase();
             amplitude * (omega * t + phase).cos()
         }
 
-        fn energy(&self, x: f64, v: f64) -> f64 {
+        fn energy(&self, x: Real, v: Real) -> Real {
             0.5 * self.mass * v * v + 0.5 * self.spring_constant * x * x
         }
     }
@@ -221,7 +643,7 @@ mod tests {
         let sho = HarmonicOscillator::new(100.0, 1.0, 1.0, 0.0);
         
         // Verify omega calculation
-        let omega = (100.0_f64).sqrt();
+        let omega = (100.0 as Real).sqrt();
         assert!((sho.omega() - omega).abs() < 1e-10);
         
         // At t=0, position should be initial position
@@ -232,4 +654,157 @@ mod tests {
         let e1 = sho.energy(0.0, 10.0);
         assert!((e0 - e1).abs() < 1e-6); // Energy approximately conserved
     }
+
+    /// Build storages for the oscillator at its turning point (x = x0, a = -ω²x0)
+    fn sho_storages(sho: &HarmonicOscillator) -> (
+        Entity,
+        HashMapStorage<Position>,
+        HashMapStorage<Acceleration>,
+        HashMapStorage<Mass>,
+    ) {
+        let entity = Entity::new(1, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+
+        let x0 = sho.position_at(0.0);
+        positions.insert(entity, Position::new(x0, 0.0, 0.0));
+        accelerations.insert(entity, Acceleration::new(-sho.spring_constant * x0 / sho.mass, 0.0, 0.0));
+        masses.insert(entity, Mass::new(sho.mass));
+        (entity, positions, accelerations, masses)
+    }
+
+    /// Force provider applying the same force to every entity
+    struct ConstantForce(crate::ecs::systems::Force);
+
+    impl crate::ecs::systems::ForceProvider for ConstantForce {
+        fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<crate::ecs::systems::Force> {
+            Some(self.0)
+        }
+
+        fn name(&self) -> &str {
+            "constant"
+        }
+    }
+
+    #[test]
+    fn test_work_matches_kinetic_energy_change_under_constant_force() {
+        use crate::ecs::systems::{apply_forces_to_acceleration, Force};
+
+        let entity = Entity::new(1, 0);
+        let entities = [entity];
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::zero());
+        velocities.insert(entity, Velocity::new(1.0, -2.0, 0.5));
+        accelerations.insert(entity, Acceleration::zero());
+        masses.insert(entity, Mass::new(2.0));
+
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(ConstantForce(Force::new(3.0, 4.0, -1.0))));
+
+        let dt = 0.01;
+        let mut integrator = VelocityVerletIntegrator::new(dt);
+        let mut work = WorkAccumulator::new();
+        let initial_ke = calculate_total_kinetic_energy(entities.iter(), &velocities, &masses);
+
+        for _ in 0..200 {
+            registry.clear_forces();
+            registry.accumulate_for_entity(entity);
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+            let power_start = calculate_power(entities.iter(), &registry, &velocities);
+
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut registry,
+                false,
+            );
+
+            let power_end = calculate_power(entities.iter(), &registry, &velocities);
+            work.record_step(power_start, power_end, dt);
+        }
+
+        let delta_ke = calculate_total_kinetic_energy(entities.iter(), &velocities, &masses) - initial_ke;
+        assert_eq!(work.steps(), 200);
+        assert!(work.cumulative_work() > 0.0);
+        assert!(
+            work.energy_leak(delta_ke).abs() < 1e-9 * Real::EPSILON_RATIO * delta_ke.abs(),
+            "ΔKE {} vs work {}",
+            delta_ke,
+            work.cumulative_work()
+        );
+    }
+
+    #[test]
+    fn test_verlet_timestep_suggestion_matches_stability_limit() {
+        let sho = HarmonicOscillator::new(100.0, 1.0, 1.0, 0.0);
+        let (entity, positions, accelerations, masses) = sho_storages(&sho);
+        let theoretical_limit = 2.0 / sho.omega();
+
+        let integrator = VelocityVerletIntegrator::new(0.001);
+        let suggestion = integrator
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .unwrap();
+
+        let ratio = suggestion.max_stable_timestep / theoretical_limit;
+        assert!((0.5..=2.0).contains(&ratio), "Stability estimate off by {}", ratio);
+        assert!(suggestion.suggested_timestep < suggestion.max_stable_timestep);
+        assert!(suggestion.warning.is_none());
+
+        // Intentionally too large timestep is flagged
+        let too_large = VelocityVerletIntegrator::new(0.5);
+        let suggestion = too_large
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .unwrap();
+        assert!(!suggestion.is_stable(0.5));
+        assert!(suggestion.warning.unwrap().contains("stability limit"));
+    }
+
+    #[test]
+    fn test_rk4_timestep_suggestion_uses_own_stability_factor() {
+        let sho = HarmonicOscillator::new(100.0, 1.0, 1.0, 0.0);
+        let (entity, positions, accelerations, masses) = sho_storages(&sho);
+
+        let verlet = VelocityVerletIntegrator::new(0.001)
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .unwrap();
+        let rk4 = RK4Integrator::new(0.001)
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .unwrap();
+
+        // RK4's stability interval on the imaginary axis (2√2) is wider than Verlet's (2)
+        assert!(rk4.max_stable_timestep > verlet.max_stable_timestep);
+        let ratio = rk4.max_stable_timestep / (2.0 * crate::real::consts::SQRT_2 / sho.omega());
+        assert!((0.5..=2.0).contains(&ratio));
+    }
+
+    #[test]
+    fn test_timestep_suggestion_from_max_stiffness() {
+        let entity = Entity::new(1, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        let accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::new(1.0, 0.0, 0.0));
+        masses.insert(entity, Mass::new(1.0));
+
+        // No accelerations and no stiffness: nothing to estimate from
+        let mut integrator = VelocityVerletIntegrator::new(0.01);
+        assert!(integrator
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .is_none());
+
+        // Stiff spring k = 1e6 gives ω = 1000 rad/s
+        integrator.set_max_stiffness(Some(1e6));
+        let suggestion = integrator
+            .suggest_timestep([entity].iter(), &positions, &accelerations, &masses)
+            .unwrap();
+        assert!((suggestion.max_angular_frequency - 1000.0).abs() < 1e-9);
+        assert!(suggestion.warning.is_some());
+    }
 }
@@ -0,0 +1,403 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Multi-rate velocity Verlet integration
+//!
+//! Systems that mix fast and slow dynamics, such as a tight binary orbited
+//! by distant bodies, waste effort under a single global timestep: it must
+//! resolve the fastest orbit, so the slow bodies are stepped far more often
+//! than they need. The multi-rate integrator splits the entities into a
+//! fast and a slow set at the start of every base step and
+//!
+//! 1. advances the fast set by `fast_substeps` Verlet substeps of
+//!    dt / `fast_substeps`, with the slow bodies held at their positions at
+//!    the start of the step, then
+//! 2. advances the slow set by one Verlet step of dt, seeing the fast
+//!    bodies at their new positions.
+//!
+//! Both sets end the step at the same time, so the next step starts from a
+//! synchronized state.
+//!
+//! # Accuracy
+//!
+//! Each set on its own is integrated by velocity Verlet, but the coupling
+//! between the sets is only first-order accurate: the fast bodies see the
+//! slow ones frozen for a whole base step. This is a good trade when the
+//! sets are weakly coupled — the fast bodies' mutual forces dominate theirs,
+//! and the slow bodies feel the fast set mainly through its slowly moving
+//! centre of mass.
+//!
+//! # Force Evaluation
+//!
+//! As with `VelocityVerletIntegrator::integrate_with()`, providers that
+//! compute forces from positions outside the registry must be refreshed at
+//! every evaluation. The callback of `integrate_with()` is always passed
+//! every entity of the step, not only the set being advanced, so forces
+//! between the sets are included.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage, HashMapStorage};
+use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
+use crate::ecs::systems::{ForceRegistry, apply_forces_to_acceleration};
+use super::{prime_accelerations, Integrator, VelocityVerletIntegrator};
+
+/// Predicate selecting the fast entities of a `MultiRateIntegrator`
+pub type FastPredicate = Box<dyn Fn(Entity) -> bool + Send + Sync>;
+
+/// Rule splitting entities into fast and slow sets
+pub enum RatePartition {
+    /// Entities for which the predicate returns `true` are fast
+    Predicate(FastPredicate),
+    /// Entities whose acceleration magnitude at the start of the step is at
+    /// least this value are fast
+    ///
+    /// Entities without an acceleration component are slow.
+    AccelerationAbove(Real),
+}
+
+/// Velocity Verlet integrator that substeps fast entities
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::integration::{Integrator, MultiRateIntegrator, RatePartition};
+///
+/// // Bodies accelerating at 100 m/s² or more take 16 substeps per step
+/// let integrator = MultiRateIntegrator::new(0.01, 16, RatePartition::AccelerationAbove(100.0));
+/// assert_eq!(integrator.fast_substeps(), 16);
+/// assert_eq!(integrator.name(), "Multi-rate Verlet");
+/// ```
+pub struct MultiRateIntegrator {
+    timestep: Real,
+    fast_substeps: usize,
+    partition: RatePartition,
+}
+
+impl MultiRateIntegrator {
+    /// Create a multi-rate integrator
+    ///
+    /// `timestep` is the base step taken by the slow set; the fast set takes
+    /// `fast_substeps` substeps per base step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if timestep is non-positive, NaN, or infinite, if
+    /// `fast_substeps` is zero, or if an `AccelerationAbove` threshold is
+    /// negative or NaN
+    pub fn new(timestep: Real, fast_substeps: usize, partition: RatePartition) -> Self {
+        assert!(
+            timestep > 0.0 && timestep.is_finite(),
+            "Timestep must be positive and finite"
+        );
+        let mut integrator = MultiRateIntegrator {
+            timestep,
+            fast_substeps: 1,
+            partition: RatePartition::AccelerationAbove(Real::INFINITY),
+        };
+        integrator.set_fast_substeps(fast_substeps);
+        integrator.set_partition(partition);
+        integrator
+    }
+
+    /// Get the number of substeps the fast set takes per base step
+    pub fn fast_substeps(&self) -> usize {
+        self.fast_substeps
+    }
+
+    /// Set the number of substeps the fast set takes per base step
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fast_substeps` is zero
+    pub fn set_fast_substeps(&mut self, fast_substeps: usize) {
+        assert!(fast_substeps > 0, "Fast substep count must be at least 1");
+        self.fast_substeps = fast_substeps;
+    }
+
+    /// Get the rule splitting entities into fast and slow sets
+    pub fn partition(&self) -> &RatePartition {
+        &self.partition
+    }
+
+    /// Set the rule splitting entities into fast and slow sets
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `AccelerationAbove` threshold is negative or NaN
+    pub fn set_partition(&mut self, partition: RatePartition) {
+        if let RatePartition::AccelerationAbove(threshold) = partition {
+            assert!(threshold >= 0.0, "Acceleration threshold must be non-negative");
+        }
+        self.partition = partition;
+    }
+
+    /// Split entities into the fast and slow sets, preserving their order
+    pub fn split<'a, I>(
+        &self,
+        entities: I,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+    ) -> (Vec<Entity>, Vec<Entity>)
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        entities.copied().partition(|&entity| match &self.partition {
+            RatePartition::Predicate(is_fast) => is_fast(entity),
+            RatePartition::AccelerationAbove(threshold) => accelerations.get(entity).is_some_and(|a| {
+                (a.ax() * a.ax() + a.ay() * a.ay() + a.az() * a.az()).sqrt() >= *threshold
+            }),
+        })
+    }
+
+    /// Integrate one base step, refreshing forces before every evaluation
+    ///
+    /// `refresh_forces` is called with every entity of the step and the
+    /// current positions before each force evaluation, and should bring the
+    /// registry up to date for them. `accelerations` must hold the
+    /// accelerations at the entry positions.
+    ///
+    /// Returns the number of entities updated across both sets.
+    #[allow(clippy::too_many_arguments)]
+    pub fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        mut refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        let entities_vec: Vec<Entity> = entities.copied().collect();
+        if entities_vec.is_empty() {
+            return 0;
+        }
+        let (fast, slow) = self.split(entities_vec.iter(), accelerations);
+        let t0 = force_registry.time();
+        let mut refresh_all = |_: &[Entity], positions: &P, registry: &mut ForceRegistry| {
+            refresh_forces(&entities_vec, positions, registry)
+        };
+        let mut updated_count = 0;
+
+        // Fast set: substeps of dt / n, slow bodies frozen
+        if !fast.is_empty() {
+            let mut substep = VelocityVerletIntegrator::new(self.timestep / self.fast_substeps as Real);
+            let mut fast_accelerations = HashMapStorage::<Acceleration>::with_capacity(fast.len());
+            for entity in &fast {
+                if let Some(a) = accelerations.get(*entity) {
+                    fast_accelerations.insert(*entity, *a);
+                }
+            }
+            for _ in 0..self.fast_substeps {
+                updated_count = substep.integrate_with(
+                    fast.iter(),
+                    positions,
+                    velocities,
+                    &fast_accelerations,
+                    masses,
+                    force_registry,
+                    warn_on_missing,
+                    &mut refresh_all,
+                );
+                // The registry holds the forces at the new fast positions
+                apply_forces_to_acceleration(fast.iter(), force_registry, masses, &mut fast_accelerations, false);
+            }
+        }
+
+        // Slow set: one step of dt, fast bodies already at the end of the step
+        if !slow.is_empty() {
+            force_registry.set_time(t0);
+            updated_count += VelocityVerletIntegrator::new(self.timestep).integrate_with(
+                slow.iter(),
+                positions,
+                velocities,
+                accelerations,
+                masses,
+                force_registry,
+                warn_on_missing,
+                &mut refresh_all,
+            );
+        }
+
+        force_registry.set_time(t0 + self.timestep);
+        updated_count
+    }
+}
+
+impl Integrator for MultiRateIntegrator {
+    fn name(&self) -> &str {
+        "Multi-rate Verlet"
+    }
+
+    fn timestep(&self) -> Real {
+        self.timestep
+    }
+
+    fn set_timestep(&mut self, dt: Real) {
+        assert!(
+            dt > 0.0 && dt.is_finite(),
+            "Timestep must be positive and finite"
+        );
+        self.timestep = dt;
+    }
+
+    fn prime<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &P,
+        accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        force_eval: F,
+    ) where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        prime_accelerations(entities, positions, accelerations, masses, force_registry, force_eval);
+    }
+
+    /// Integrate one base step with the forces already in the registry
+    ///
+    /// The registry is re-accumulated at every evaluation but not refreshed
+    /// for the new positions; use `integrate_with()` for providers such as
+    /// `GravitySystem` that compute forces outside the registry.
+    fn integrate<'a, I>(
+        &mut self,
+        entities: I,
+        positions: &mut impl ComponentStorage<Component = Position>,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        self.integrate_with(
+            entities,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+            |_, _, _| {},
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        MultiRateIntegrator::integrate_with(
+            self,
+            entities,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+            refresh_forces,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::World;
+
+    #[test]
+    fn test_split_by_acceleration_threshold_and_predicate() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..4).map(|_| world.create_entity()).collect();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        accelerations.insert(entities[0], Acceleration::new(0.0, 300.0, 400.0));
+        accelerations.insert(entities[1], Acceleration::new(1.0, 0.0, 0.0));
+        accelerations.insert(entities[2], Acceleration::new(500.0, 0.0, 0.0));
+
+        let by_acceleration = MultiRateIntegrator::new(0.1, 4, RatePartition::AccelerationAbove(500.0));
+        let (fast, slow) = by_acceleration.split(entities.iter(), &accelerations);
+        assert_eq!(fast, vec![entities[0], entities[2]]);
+        assert_eq!(slow, vec![entities[1], entities[3]]);
+
+        let chosen = entities[3];
+        let by_predicate = MultiRateIntegrator::new(0.1, 4, RatePartition::Predicate(Box::new(move |e| e == chosen)));
+        let (fast, slow) = by_predicate.split(entities.iter(), &accelerations);
+        assert_eq!(fast, vec![chosen]);
+        assert_eq!(slow.len(), 3);
+    }
+
+    #[test]
+    fn test_both_sets_reach_the_end_of_the_step() {
+        let mut world = World::new();
+        let fast = world.create_entity();
+        let slow = world.create_entity();
+        let entities = [fast, slow];
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        for entity in entities {
+            positions.insert(entity, Position::zero());
+            velocities.insert(entity, Velocity::new(2.0, 0.0, 0.0));
+            masses.insert(entity, Mass::new(1.0));
+        }
+        let mut registry = ForceRegistry::new();
+        registry.set_time(1.0);
+
+        let mut integrator = MultiRateIntegrator::new(0.5, 8, RatePartition::Predicate(Box::new(move |e| e == fast)));
+        let updated = integrator.integrate(
+            entities.iter(),
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &masses,
+            &mut registry,
+            false,
+        );
+
+        assert_eq!(updated, 2);
+        for entity in entities {
+            assert!((positions.get(entity).unwrap().x() - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(registry.time(), 1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fast substep count must be at least 1")]
+    fn test_zero_fast_substeps_panics() {
+        MultiRateIntegrator::new(0.1, 0, RatePartition::AccelerationAbove(1.0));
+    }
+}
@@ -38,6 +38,11 @@
 //! ... and so on
 //! ```
 //!
+//! Every stage evaluates forces at its own state: positions are staged in
+//! the position storage, and velocities in the force registry, where
+//! velocity-dependent providers read them with `ForceRegistry::velocity()`.
+//! Drag and other forces of the velocity therefore keep the fourth order.
+//!
 //! # Properties
 //!
 //! - **Fourth-order accurate**: Local error O(dt⁵), global error O(dt⁴)
@@ -55,13 +60,22 @@
 //! - Kutta, W. (1901). Beitrag zur näherungsweisen Integration totaler
 //!   Differentialgleichungen. Zeitschrift für Mathematik und Physik, 46, 435-453.
 
+use crate::real::Real;
 use crate::ecs::{Entity, ComponentStorage};
 use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
 use crate::ecs::systems::ForceRegistry;
 use crate::pool::{HashMapPool, PoolConfig};
-use super::Integrator;
+use super::{Integrator, TimestepSuggestion, estimate_max_angular_frequency};
 use std::collections::HashMap;
 
+/// Per-step statistics reported by `RK4Integrator::stats()`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RK4Stats {
+    /// Force registry evaluations (clear + accumulate passes) in the last
+    /// `integrate()` call: 4 normally, 3 when initial forces are reused
+    pub force_evaluations: usize,
+}
+
 /// Runge-Kutta 4th order integrator for physics simulation
 ///
 /// This integrator provides high accuracy for smooth dynamics at the cost
@@ -75,6 +89,16 @@ use std::collections::HashMap;
 /// allocation churn. Pools are configured at creation time and reused
 /// across integration steps.
 ///
+/// # Reusing Initial Forces
+///
+/// The k1 stage evaluates forces at the entry state, which callers often
+/// already have in the registry (e.g. after `apply_forces_to_acceleration()`).
+/// `set_reuse_initial_forces(true)` skips that evaluation, reducing force
+/// evaluations from 4 to 3 per step. The registry **must** then hold forces for
+/// the current positions and velocities when `integrate()` is called; the
+/// forces left over from the previous step are the k4 stage forces, not the
+/// entry-state forces, so they must be recomputed first.
+///
 /// # Example
 ///
 /// ```
@@ -84,11 +108,14 @@ use std::collections::HashMap;
 /// assert_eq!(integrator.timestep(), 1.0 / 60.0);
 /// ```
 pub struct RK4Integrator {
-    timestep: f64,
+    timestep: Real,
+    max_stiffness: Option<Real>,
     // Memory pools for reusable buffers to reduce allocation churn
     position_pool: HashMapPool<Entity, Position>,
     velocity_pool: HashMapPool<Entity, Velocity>,
     acceleration_pool: HashMapPool<Entity, Acceleration>,
+    reuse_initial_forces: bool,
+    stats: RK4Stats,
 }
 
 impl RK4Integrator {
@@ -99,7 +126,7 @@ impl RK4Integrator {
     /// # Panics
     ///
     /// Panics if timestep is non-positive, NaN, or infinite
-    pub fn new(timestep: f64) -> Self {
+    pub fn new(timestep: Real) -> Self {
         Self::with_pool_config(timestep, PoolConfig::default())
     }
 
@@ -113,16 +140,19 @@ impl RK4Integrator {
     /// # Panics
     ///
     /// Panics if timestep is non-positive, NaN, or infinite
-    pub fn with_pool_config(timestep: f64, pool_config: PoolConfig) -> Self {
+    pub fn with_pool_config(timestep: Real, pool_config: PoolConfig) -> Self {
         assert!(
             timestep > 0.0 && timestep.is_finite(),
             "Timestep must be positive and finite"
         );
         RK4Integrator {
             timestep,
+            max_stiffness: None,
             position_pool: HashMapPool::with_config(pool_config.clone()),
             velocity_pool: HashMapPool::with_config(pool_config.clone()),
             acceleration_pool: HashMapPool::with_config(pool_config),
+            reuse_initial_forces: false,
+            stats: RK4Stats::default(),
         }
     }
 
@@ -142,6 +172,40 @@ impl RK4Integrator {
         self.acceleration_pool.clear();
     }
 
+    /// Use the forces already in the registry for the k1 stage
+    ///
+    /// See the type-level documentation for the correctness requirement.
+    pub fn set_reuse_initial_forces(&mut self, reuse: bool) {
+        self.reuse_initial_forces = reuse;
+    }
+
+    /// Check if the k1 stage reuses forces already in the registry
+    pub fn reuse_initial_forces(&self) -> bool {
+        self.reuse_initial_forces
+    }
+
+    /// Get statistics for the last `integrate()` call
+    pub fn stats(&self) -> RK4Stats {
+        self.stats
+    }
+
+    /// Stability bound on ω·dt for classical RK4
+    ///
+    /// RK4's stability region reaches 2√2 along the imaginary axis, which
+    /// governs undamped oscillatory motion.
+    pub const STABILITY_FACTOR: Real = 2.0 * crate::real::consts::SQRT_2;
+
+    /// Set the maximum stiffness (N/m) used for timestep suggestions
+    ///
+    /// See `VelocityVerletIntegrator::set_max_stiffness()`.
+    pub fn set_max_stiffness(&mut self, stiffness: Option<Real>) {
+        self.max_stiffness = stiffness;
+    }
+
+    /// Get the maximum stiffness used for timestep suggestions
+    pub fn max_stiffness(&self) -> Option<Real> {
+        self.max_stiffness
+    }
 }
 
 impl Integrator for RK4Integrator {
@@ -149,37 +213,106 @@ impl Integrator for RK4Integrator {
         "Runge-Kutta 4"
     }
 
-    fn timestep(&self) -> f64 {
+    fn timestep(&self) -> Real {
         self.timestep
     }
 
-    fn set_timestep(&mut self, dt: f64) {
+    fn set_timestep(&mut self, dt: Real) {
         assert!(
-            dt > 0.0 && dt.is_finite(),
-            "Timestep must be positive and finite"
+            dt != 0.0 && dt.is_finite(),
+            "Timestep must be non-zero and finite"
         );
         self.timestep = dt;
     }
 
+    fn supports_negative_dt(&self) -> bool {
+        true
+    }
+
+    fn suggest_timestep<'a, I>(
+        &self,
+        entities: I,
+        positions: &impl ComponentStorage<Component = Position>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+    ) -> Option<TimestepSuggestion>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let omega = estimate_max_angular_frequency(
+            entities,
+            positions,
+            accelerations,
+            masses,
+            self.max_stiffness,
+        )?;
+        Some(TimestepSuggestion::from_frequency(omega, Self::STABILITY_FACTOR, self.timestep.abs()))
+    }
+
+    fn reset(&mut self) {
+        self.clear_pools();
+        self.stats = RK4Stats::default();
+    }
+
     fn integrate<'a, I>(
         &mut self,
         entities: I,
         positions: &mut impl ComponentStorage<Component = Position>,
         velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        self.integrate_with(
+            entities,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+            |_, _, _| {},
+        )
+    }
+
+    /// Integrate one timestep, refreshing forces before every stage
+    ///
+    /// `refresh_forces` is called with the stage positions before each of
+    /// the four force evaluations (three with `reuse_initial_forces()`), so
+    /// forces computed outside the registry, such as gravity from
+    /// `GravitySystem`, keep the fourth order.
+    #[allow(clippy::too_many_arguments)]
+    fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
         _accelerations: &impl ComponentStorage<Component = Acceleration>,
         masses: &impl ComponentStorage<Component = Mass>,
         force_registry: &mut ForceRegistry,
         warn_on_missing: bool,
+        mut refresh_forces: F,
     ) -> usize
     where
         I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
     {
         let dt = self.timestep;
         let dt_2 = dt * 0.5;
         let dt_6 = dt / 6.0;
 
         let entities_vec: Vec<Entity> = entities.copied().collect();
+        if entities_vec.is_empty() {
+            return 0;
+        }
         let mut updated_count = 0;
+        let mut force_evaluations = 0;
+        let t0 = force_registry.time();
 
         // Acquire buffers from pools (automatically returned on scope exit)
         let mut k1_positions = self.position_pool.acquire();
@@ -197,8 +330,8 @@ impl Integrator for RK4Integrator {
 
         for entity in &entities_vec {
             if let (Some(pos), Some(vel)) = (positions.get(*entity), velocities.get(*entity)) {
-                // Skip immovable bodies
-                if masses.get(*entity).map_or(true, |m| m.is_immovable()) {
+                // Skip immovable bodies and sleeping entities
+                if masses.get(*entity).map_or(true, |m| m.is_immovable()) || force_registry.is_sleeping(*entity) {
                     continue;
                 }
                 initial_positions.insert(*entity, *pos);
@@ -206,20 +339,30 @@ impl Integrator for RK4Integrator {
             }
         }
 
+        // Stage accelerations go through ForceRegistry::acceleration_from_force(),
+        // the same policy as apply_forces_to_acceleration(): bodies below the
+        // minimum mass and non-finite results get zero acceleration, and the
+        // acceleration limit applies at every stage.
+
         // ==================== STAGE 1: Compute k1 ====================
         // Compute k1 at initial state (t, y0)
         // All entities remain at their initial positions during this stage
         
-        force_registry.clear_forces();
-        for entity in &entities_vec {
-            force_registry.accumulate_for_entity(*entity);
+        force_registry.set_time(t0);
+        force_registry.set_velocities(entities_vec.iter(), velocities);
+        if !self.reuse_initial_forces {
+            refresh_forces(&entities_vec, positions, force_registry);
+            force_registry.clear_forces();
+            for entity in &entities_vec {
+                force_registry.accumulate_for_entity(*entity);
+            }
+            force_evaluations += 1;
         }
 
         for entity in &entities_vec {
-            let pos = match initial_positions.get(entity) {
-                Some(p) => p,
-                None => continue,
-            };
+            if !initial_positions.contains_key(entity) {
+                continue;
+            }
             let vel = match initial_velocities.get(entity) {
                 Some(v) => v,
                 None => continue,
@@ -236,22 +379,14 @@ impl Integrator for RK4Integrator {
             ));
 
             // k1 for velocity derivative is acceleration at current state (dv/dt = a)
-            let acceleration = if let Some(force) = force_registry.get_force(*entity) {
-                let inv_mass = mass.inverse();
-                Acceleration::new(
-                    force.fx * inv_mass,
-                    force.fy * inv_mass,
-                    force.fz * inv_mass,
-                )
-            } else {
-                Acceleration::zero()
-            };
+            let acceleration = force_registry
+                .get_force(*entity)
+                .and_then(|force| force_registry.acceleration_from_force(&force, mass))
+                .unwrap_or_else(Acceleration::zero);
 
-            if acceleration.is_valid() {
-                k1_velocities.insert(*entity, Velocity::new(
-                    acceleration.ax(), acceleration.ay(), acceleration.az()
-                ));
-            }
+            k1_velocities.insert(*entity, Velocity::new(
+                acceleration.ax(), acceleration.ay(), acceleration.az()
+            ));
         }
 
         // ==================== STAGE 2: Compute k2 ====================
@@ -263,9 +398,9 @@ impl Integrator for RK4Integrator {
                 Some(p) => p,
                 None => continue,
             };
-            let k1_pos = match k1_positions.get(entity) {
-                Some(k) => k,
-                None => continue,
+            let (k1_pos, k1_vel) = match (k1_positions.get(entity), k1_velocities.get(entity)) {
+                (Some(kp), Some(kv)) => (kp, kv),
+                _ => continue,
             };
 
             // Move entity to intermediate position: pos + k1*dt/2
@@ -278,13 +413,25 @@ impl Integrator for RK4Integrator {
             if let Some(p) = positions.get_mut(*entity) {
                 *p = intermediate_pos;
             }
+
+            // Velocity-dependent providers see the stage velocity
+            if let Some(vel) = initial_velocities.get(entity) {
+                force_registry.set_velocity(*entity, Velocity::new(
+                    vel.dx() + k1_vel.dx() * dt_2,
+                    vel.dy() + k1_vel.dy() * dt_2,
+                    vel.dz() + k1_vel.dz() * dt_2,
+                ));
+            }
         }
 
         // Now compute forces with ALL entities at their intermediate positions
+        force_registry.set_time(t0 + dt_2);
+        refresh_forces(&entities_vec, positions, force_registry);
         force_registry.clear_forces();
         for entity in &entities_vec {
             force_registry.accumulate_for_entity(*entity);
         }
+        force_evaluations += 1;
 
         for entity in &entities_vec {
             let vel = match initial_velocities.get(entity) {
@@ -312,22 +459,14 @@ impl Integrator for RK4Integrator {
             ));
 
             // k2 for velocity derivative is acceleration at intermediate state
-            let acceleration = if let Some(force) = force_registry.get_force(*entity) {
-                let inv_mass = mass.inverse();
-                Acceleration::new(
-                    force.fx * inv_mass,
-                    force.fy * inv_mass,
-                    force.fz * inv_mass,
-                )
-            } else {
-                Acceleration::zero()
-            };
+            let acceleration = force_registry
+                .get_force(*entity)
+                .and_then(|force| force_registry.acceleration_from_force(&force, mass))
+                .unwrap_or_else(Acceleration::zero);
 
-            if acceleration.is_valid() {
-                k2_velocities.insert(*entity, Velocity::new(
-                    acceleration.ax(), acceleration.ay(), acceleration.az()
-                ));
-            }
+            k2_velocities.insert(*entity, Velocity::new(
+                acceleration.ax(), acceleration.ay(), acceleration.az()
+            ));
         }
 
         // ==================== STAGE 3: Compute k3 ====================
@@ -339,9 +478,9 @@ impl Integrator for RK4Integrator {
                 Some(p) => p,
                 None => continue,
             };
-            let k2_pos = match k2_positions.get(entity) {
-                Some(k) => k,
-                None => continue,
+            let (k2_pos, k2_vel) = match (k2_positions.get(entity), k2_velocities.get(entity)) {
+                (Some(kp), Some(kv)) => (kp, kv),
+                _ => continue,
             };
 
             // Move entity to intermediate position: pos + k2*dt/2
@@ -354,13 +493,25 @@ impl Integrator for RK4Integrator {
             if let Some(p) = positions.get_mut(*entity) {
                 *p = intermediate_pos;
             }
+
+            // Velocity-dependent providers see the stage velocity
+            if let Some(vel) = initial_velocities.get(entity) {
+                force_registry.set_velocity(*entity, Velocity::new(
+                    vel.dx() + k2_vel.dx() * dt_2,
+                    vel.dy() + k2_vel.dy() * dt_2,
+                    vel.dz() + k2_vel.dz() * dt_2,
+                ));
+            }
         }
 
         // Compute forces with ALL entities at their k3 intermediate positions
+        force_registry.set_time(t0 + dt_2);
+        refresh_forces(&entities_vec, positions, force_registry);
         force_registry.clear_forces();
         for entity in &entities_vec {
             force_registry.accumulate_for_entity(*entity);
         }
+        force_evaluations += 1;
 
         for entity in &entities_vec {
             let vel = match initial_velocities.get(entity) {
@@ -388,22 +539,14 @@ impl Integrator for RK4Integrator {
             ));
 
             // k3 for velocity derivative is acceleration at intermediate state
-            let acceleration = if let Some(force) = force_registry.get_force(*entity) {
-                let inv_mass = mass.inverse();
-                Acceleration::new(
-                    force.fx * inv_mass,
-                    force.fy * inv_mass,
-                    force.fz * inv_mass,
-                )
-            } else {
-                Acceleration::zero()
-            };
+            let acceleration = force_registry
+                .get_force(*entity)
+                .and_then(|force| force_registry.acceleration_from_force(&force, mass))
+                .unwrap_or_else(Acceleration::zero);
 
-            if acceleration.is_valid() {
-                k3_velocities.insert(*entity, Velocity::new(
-                    acceleration.ax(), acceleration.ay(), acceleration.az()
-                ));
-            }
+            k3_velocities.insert(*entity, Velocity::new(
+                acceleration.ax(), acceleration.ay(), acceleration.az()
+            ));
         }
 
         // ==================== STAGE 4: Compute k4 ====================
@@ -415,9 +558,9 @@ impl Integrator for RK4Integrator {
                 Some(p) => p,
                 None => continue,
             };
-            let k3_pos = match k3_positions.get(entity) {
-                Some(k) => k,
-                None => continue,
+            let (k3_pos, k3_vel) = match (k3_positions.get(entity), k3_velocities.get(entity)) {
+                (Some(kp), Some(kv)) => (kp, kv),
+                _ => continue,
             };
 
             // Move entity to end position: pos + k3*dt
@@ -430,13 +573,25 @@ impl Integrator for RK4Integrator {
             if let Some(p) = positions.get_mut(*entity) {
                 *p = end_pos;
             }
+
+            // Velocity-dependent providers see the stage velocity
+            if let Some(vel) = initial_velocities.get(entity) {
+                force_registry.set_velocity(*entity, Velocity::new(
+                    vel.dx() + k3_vel.dx() * dt,
+                    vel.dy() + k3_vel.dy() * dt,
+                    vel.dz() + k3_vel.dz() * dt,
+                ));
+            }
         }
 
         // Compute forces with ALL entities at their k4 end positions
+        force_registry.set_time(t0 + dt);
+        refresh_forces(&entities_vec, positions, force_registry);
         force_registry.clear_forces();
         for entity in &entities_vec {
             force_registry.accumulate_for_entity(*entity);
         }
+        force_evaluations += 1;
 
         for entity in &entities_vec {
             let vel = match initial_velocities.get(entity) {
@@ -464,22 +619,14 @@ impl Integrator for RK4Integrator {
             ));
 
             // k4 for velocity derivative is acceleration at end state
-            let acceleration = if let Some(force) = force_registry.get_force(*entity) {
-                let inv_mass = mass.inverse();
-                Acceleration::new(
-                    force.fx * inv_mass,
-                    force.fy * inv_mass,
-                    force.fz * inv_mass,
-                )
-            } else {
-                Acceleration::zero()
-            };
+            let acceleration = force_registry
+                .get_force(*entity)
+                .and_then(|force| force_registry.acceleration_from_force(&force, mass))
+                .unwrap_or_else(Acceleration::zero);
 
-            if acceleration.is_valid() {
-                k4_velocities.insert(*entity, Velocity::new(
-                    acceleration.ax(), acceleration.ay(), acceleration.az()
-                ));
-            }
+            k4_velocities.insert(*entity, Velocity::new(
+                acceleration.ax(), acceleration.ay(), acceleration.az()
+            ));
         }
 
         // ==================== FINAL UPDATE ====================
@@ -491,6 +638,9 @@ impl Integrator for RK4Integrator {
                     *p = *initial_pos;
                 }
             }
+            if let Some(initial_vel) = initial_velocities.get(entity) {
+                force_registry.set_velocity(*entity, *initial_vel);
+            }
         }
         
         // Apply the RK4 weighted average: y(t+dt) = y(t) + (k1 + 2*k2 + 2*k3 + k4)*dt/6
@@ -557,10 +707,13 @@ impl Integrator for RK4Integrator {
             if let Some(v) = velocities.get_mut(*entity) {
                 *v = new_vel;
             }
+            force_registry.set_velocity(*entity, new_vel);
 
             updated_count += 1;
         }
 
+        force_registry.set_time(t0 + dt);
+        self.stats = RK4Stats { force_evaluations };
         updated_count
     }
 }
@@ -568,6 +721,7 @@ impl Integrator for RK4Integrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::real::RealExt;
     use crate::ecs::{HashMapStorage, Entity};
 
     #[test]
@@ -608,7 +762,7 @@ mod tests {
 
         let mut force_registry = ForceRegistry::new();
 
-        let entities = vec![entity];
+        let entities = [entity];
         let count = integrator.integrate(
             entities.iter(),
             &mut positions,
@@ -623,9 +777,9 @@ mod tests {
 
         let pos = positions.get(entity).unwrap();
         // With no forces, position should update by velocity * dt
-        assert!((pos.x() - 0.1).abs() < 1e-10);
-        assert!((pos.y() - 0.2).abs() < 1e-10);
-        assert!((pos.z() - 0.3).abs() < 1e-10);
+        assert!((pos.x() - 0.1).abs() < Real::TOLERANCE);
+        assert!((pos.y() - 0.2).abs() < Real::TOLERANCE);
+        assert!((pos.z() - 0.3).abs() < Real::TOLERANCE);
     }
 
     #[test]
@@ -648,7 +802,7 @@ mod tests {
 
         // Run multiple integrations
         for _ in 0..5 {
-            let entities = vec![entity];
+            let entities = [entity];
             integrator.integrate(
                 entities.iter(),
                 &mut positions,
@@ -664,4 +818,98 @@ mod tests {
         let pos = positions.get(entity).unwrap();
         assert!(pos.is_valid());
     }
+
+    /// Run `steps` RK4 steps on bodies under a constant force and return final positions
+    fn run_constant_force_system(
+        integrator: &mut RK4Integrator,
+        initial: &[(Position, Velocity, Real)],
+        force: crate::ecs::systems::Force,
+        steps: usize,
+    ) -> Vec<Position> {
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut entities = Vec::new();
+        for (i, (pos, vel, mass)) in initial.iter().enumerate() {
+            let entity = Entity::new(i as u64, 0);
+            positions.insert(entity, *pos);
+            velocities.insert(entity, *vel);
+            masses.insert(entity, Mass::new(*mass));
+            entities.push(entity);
+        }
+
+        let forces: std::collections::HashMap<_, _> = entities.iter().map(|e| (*e, force)).collect();
+        let mut force_registry = ForceRegistry::new();
+        for _ in 0..steps {
+            force_registry.clear();
+            force_registry.register_forces(&forces);
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut force_registry,
+                false,
+            );
+        }
+
+        entities.iter().map(|e| *positions.get(*e).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_rk4_reset_matches_fresh_integrator() {
+        use crate::ecs::systems::Force;
+
+        let system_a = [
+            (Position::zero(), Velocity::new(1.0, 0.0, 0.0), 1.0),
+            (Position::new(5.0, 0.0, 0.0), Velocity::new(0.0, 2.0, 0.0), 3.0),
+            (Position::new(0.0, 5.0, 0.0), Velocity::zero(), 0.5),
+        ];
+        let system_b = [(Position::new(-1.0, 2.0, 3.0), Velocity::new(0.5, 0.5, -0.5), 2.0)];
+
+        let mut reused = RK4Integrator::new(0.01);
+        run_constant_force_system(&mut reused, &system_a, Force::new(1.0, -1.0, 0.0), 50);
+        assert!(reused.pool_stats().0.pool_size > 0);
+
+        reused.reset();
+        assert_eq!(reused.pool_stats().0.pool_size, 0);
+        assert_eq!(reused.timestep(), 0.01);
+        let after_reset = run_constant_force_system(&mut reused, &system_b, Force::new(0.0, 2.0, 1.0), 50);
+
+        let mut fresh = RK4Integrator::new(0.01);
+        let from_fresh = run_constant_force_system(&mut fresh, &system_b, Force::new(0.0, 2.0, 1.0), 50);
+
+        assert_eq!(after_reset, from_fresh);
+    }
+
+    #[test]
+    fn test_rk4_reuse_initial_forces_saves_one_evaluation() {
+        use crate::ecs::systems::Force;
+
+        let system = [
+            (Position::zero(), Velocity::new(1.0, 0.0, 0.0), 1.0),
+            (Position::new(3.0, 1.0, 0.0), Velocity::new(0.0, -1.0, 2.0), 4.0),
+        ];
+        let force = Force::new(2.0, -3.0, 0.5);
+
+        let mut standard = RK4Integrator::new(0.01);
+        assert!(!standard.reuse_initial_forces());
+        assert_eq!(standard.stats().force_evaluations, 0);
+        let expected = run_constant_force_system(&mut standard, &system, force, 25);
+        assert_eq!(standard.stats(), RK4Stats { force_evaluations: 4 });
+
+        // register_forces() leaves entry-state forces in the registry each step
+        let mut reusing = RK4Integrator::new(0.01);
+        reusing.set_reuse_initial_forces(true);
+        let actual = run_constant_force_system(&mut reusing, &system, force, 25);
+        assert_eq!(reusing.stats(), RK4Stats { force_evaluations: 3 });
+
+        assert_eq!(actual, expected);
+
+        reusing.reset();
+        assert_eq!(reusing.stats().force_evaluations, 0);
+        assert!(reusing.reuse_initial_forces());
+    }
 }
@@ -0,0 +1,377 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! State interpolation between integration steps
+//!
+//! The simulation only stores states at multiples of dt. `StateSampler` keeps
+//! the previous and current snapshots and reconstructs position and velocity
+//! at any time within the last step. Steps may run backwards in time, as they
+//! do with a negative timestep.
+//!
+//! # Method
+//!
+//! With both positions and velocities available, the sampler uses cubic
+//! Hermite interpolation, treating velocities as the derivatives at the
+//! endpoints. For s = (t − t₀)/h:
+//!
+//! **p(s) = h₀₀(s)·p₀ + h₁₀(s)·h·v₀ + h₀₁(s)·p₁ + h₁₁(s)·h·v₁**
+//!
+//! This is exact for motion under constant acceleration. If either snapshot
+//! lacks a velocity, positions are interpolated linearly and the velocity is
+//! the average over the step.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Position, Velocity};
+use std::collections::HashMap;
+
+/// Position and optional velocity of an entity at a snapshot time
+type SnapshotState = (Position, Option<Velocity>);
+
+/// Captured state of a set of entities at one time
+#[derive(Debug, Clone)]
+struct Snapshot {
+    time: Real,
+    states: HashMap<Entity, SnapshotState>,
+}
+
+/// Interpolates entity state at arbitrary times within the last step
+///
+/// Call `record()` once per step (including once before the first step) to
+/// capture the state; `sample()` then answers queries for any time between
+/// the two most recent snapshots.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+/// use physics_engine::ecs::components::{Position, Velocity};
+/// use physics_engine::integration::StateSampler;
+///
+/// let entity = World::new().create_entity();
+/// let mut positions = HashMapStorage::<Position>::new();
+/// let mut velocities = HashMapStorage::<Velocity>::new();
+/// positions.insert(entity, Position::new(0.0, 0.0, 0.0));
+/// velocities.insert(entity, Velocity::new(1.0, 0.0, 0.0));
+///
+/// let mut sampler = StateSampler::new();
+/// sampler.record(0.0, [entity].iter(), &positions, &velocities).unwrap();
+/// positions.insert(entity, Position::new(0.1, 0.0, 0.0));
+/// sampler.record(0.1, [entity].iter(), &positions, &velocities).unwrap();
+///
+/// let (pos, _vel) = sampler.sample(entity, 0.05).unwrap();
+/// assert!((pos.x() - 0.05).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StateSampler {
+    previous: Option<Snapshot>,
+    current: Option<Snapshot>,
+}
+
+impl StateSampler {
+    /// Create a sampler with no recorded state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capture the state of `entities` at `time`
+    ///
+    /// The previously current snapshot becomes the previous one. Entities
+    /// without a position are not captured; entities without a velocity are
+    /// captured for linear interpolation only.
+    ///
+    /// `time` may be before the last recorded time, so a run stepped with a
+    /// negative timestep samples the same way as a forward one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and records nothing, if `time` is not finite or
+    /// equals the last recorded time, since a zero-length step cannot be
+    /// interpolated.
+    pub fn record<'a, I>(
+        &mut self,
+        time: Real,
+        entities: I,
+        positions: &impl ComponentStorage<Component = Position>,
+        velocities: &impl ComponentStorage<Component = Velocity>,
+    ) -> Result<(), String>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        if !time.is_finite() {
+            return Err(format!("Snapshot time {} is not finite", time));
+        }
+        if let Some(current) = &self.current {
+            if time == current.time {
+                return Err(format!("Snapshot time {} repeats the last recorded time", time));
+            }
+        }
+
+        let mut states = HashMap::new();
+        for entity in entities {
+            if let Some(pos) = positions.get(*entity) {
+                states.insert(*entity, (*pos, velocities.get(*entity).copied()));
+            }
+        }
+
+        self.previous = self.current.take();
+        self.current = Some(Snapshot { time, states });
+        Ok(())
+    }
+
+    /// Get the times of the previous and current snapshots, if two exist
+    ///
+    /// The first time is after the second when the last step ran backwards.
+    pub fn time_range(&self) -> Option<(Real, Real)> {
+        match (&self.previous, &self.current) {
+            (Some(prev), Some(curr)) => Some((prev.time, curr.time)),
+            _ => None,
+        }
+    }
+
+    /// Sample position and velocity of an entity at time `t`
+    ///
+    /// `t` must lie between the two times of `time_range()`. At the endpoints
+    /// the stored state is returned exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fewer than two snapshots have been recorded, `t` is
+    /// outside the last step, or the entity is missing from either snapshot.
+    pub fn sample(&self, entity: Entity, t: Real) -> Result<(Position, Velocity), String> {
+        let (prev, curr) = match (&self.previous, &self.current) {
+            (Some(prev), Some(curr)) => (prev, curr),
+            _ => return Err("StateSampler needs two recorded snapshots before sampling".to_string()),
+        };
+
+        let (earliest, latest) = (prev.time.min(curr.time), prev.time.max(curr.time));
+        if !(earliest..=latest).contains(&t) {
+            return Err(format!(
+                "Sample time {} is outside the last step [{}, {}]",
+                t, earliest, latest
+            ));
+        }
+
+        let (p0, v0) = prev.states.get(&entity).copied().ok_or_else(|| {
+            format!("Entity {:?} is missing from the snapshot at t = {}", entity, prev.time)
+        })?;
+        let (p1, v1) = curr.states.get(&entity).copied().ok_or_else(|| {
+            format!("Entity {:?} is missing from the snapshot at t = {}", entity, curr.time)
+        })?;
+
+        let h = curr.time - prev.time;
+        let s = (t - prev.time) / h;
+        let average_velocity = || {
+            Velocity::new((p1.x() - p0.x()) / h, (p1.y() - p0.y()) / h, (p1.z() - p0.z()) / h)
+        };
+
+        if t == prev.time {
+            return Ok((p0, v0.unwrap_or_else(average_velocity)));
+        }
+        if t == curr.time {
+            return Ok((p1, v1.unwrap_or_else(average_velocity)));
+        }
+
+        match (v0, v1) {
+            (Some(v0), Some(v1)) => Ok(hermite(p0, v0, p1, v1, h, s)),
+            _ => {
+                let p = p0.as_array();
+                let q = p1.as_array();
+                let lerp = |i: usize| p[i] + (q[i] - p[i]) * s;
+                Ok((Position::new(lerp(0), lerp(1), lerp(2)), average_velocity()))
+            }
+        }
+    }
+
+    /// Sample at a fraction `alpha` ∈ [0, 1] of the last step
+    ///
+    /// `alpha = 0` is the previous snapshot and `alpha = 1` the current one,
+    /// whichever direction the step ran.
+    /// Convenient for rendering with a fixed-timestep accumulator, where
+    /// `alpha` is the leftover time divided by dt.
+    pub fn sample_alpha(&self, entity: Entity, alpha: Real) -> Result<(Position, Velocity), String> {
+        let (t0, t1) = self
+            .time_range()
+            .ok_or_else(|| "StateSampler needs two recorded snapshots before sampling".to_string())?;
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(format!("Interpolation alpha {} is outside [0, 1]", alpha));
+        }
+        // Pin the endpoints so rounding cannot push t outside the range
+        let t = if alpha == 1.0 { t1 } else { t0 + alpha * (t1 - t0) };
+        self.sample(entity, t)
+    }
+
+    /// Discard all recorded snapshots
+    pub fn clear(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+}
+
+/// Cubic Hermite interpolation of position and its derivative at fraction `s`
+fn hermite(p0: Position, v0: Velocity, p1: Position, v1: Velocity, h: Real, s: Real) -> (Position, Velocity) {
+    let s2 = s * s;
+    let s3 = s2 * s;
+
+    // Basis functions and their derivatives with respect to s
+    let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+    let h10 = s3 - 2.0 * s2 + s;
+    let h01 = -2.0 * s3 + 3.0 * s2;
+    let h11 = s3 - s2;
+    let d00 = 6.0 * s2 - 6.0 * s;
+    let d10 = 3.0 * s2 - 4.0 * s + 1.0;
+    let d01 = -6.0 * s2 + 6.0 * s;
+    let d11 = 3.0 * s2 - 2.0 * s;
+
+    let p0 = p0.as_array();
+    let p1 = p1.as_array();
+    let v0 = v0.as_array();
+    let v1 = v1.as_array();
+
+    let pos = |i: usize| h00 * p0[i] + h10 * h * v0[i] + h01 * p1[i] + h11 * h * v1[i];
+    // dp/dt = (dp/ds) / h
+    let vel = |i: usize| (d00 * p0[i] + d01 * p1[i]) / h + d10 * v0[i] + d11 * v1[i];
+
+    (
+        Position::new(pos(0), pos(1), pos(2)),
+        Velocity::new(vel(0), vel(1), vel(2)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real::RealExt;
+    use crate::ecs::HashMapStorage;
+
+    /// Record a body under constant acceleration at t0 and t1
+    fn constant_acceleration_sampler(
+        entity: Entity,
+        p0: [Real; 3],
+        v0: [Real; 3],
+        a: [Real; 3],
+        t0: Real,
+        t1: Real,
+    ) -> StateSampler {
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut sampler = StateSampler::new();
+
+        for t in [t0, t1] {
+            let state = exact_state(p0, v0, a, t);
+            positions.insert(entity, state.0);
+            velocities.insert(entity, state.1);
+            sampler.record(t, [entity].iter(), &positions, &velocities).unwrap();
+        }
+        sampler
+    }
+
+    fn exact_state(p0: [Real; 3], v0: [Real; 3], a: [Real; 3], t: Real) -> (Position, Velocity) {
+        let p = |i: usize| p0[i] + v0[i] * t + 0.5 * a[i] * t * t;
+        let v = |i: usize| v0[i] + a[i] * t;
+        (Position::new(p(0), p(1), p(2)), Velocity::new(v(0), v(1), v(2)))
+    }
+
+    #[test]
+    fn test_hermite_exact_for_constant_acceleration() {
+        let entity = Entity::new(1, 0);
+        let (p0, v0, a) = ([1.0, -2.0, 0.5], [3.0, 0.0, -1.0], [0.0, -9.81, 2.0]);
+        let sampler = constant_acceleration_sampler(entity, p0, v0, a, 3.1, 3.2);
+
+        for t in [3.11, crate::real::consts::PI, 3.15, 3.199] {
+            let (pos, vel) = sampler.sample(entity, t).unwrap();
+            let (exact_pos, exact_vel) = exact_state(p0, v0, a, t);
+            for i in 0..3 {
+                assert!((pos.as_array()[i] - exact_pos.as_array()[i]).abs() < 1e-10 * Real::EPSILON_RATIO);
+                assert!((vel.as_array()[i] - exact_vel.as_array()[i]).abs() < 1e-9 * Real::EPSILON_RATIO);
+            }
+        }
+    }
+
+    #[test]
+    fn test_endpoints_reproduce_stored_states() {
+        let entity = Entity::new(1, 0);
+        let (p0, v0, a) = ([0.3, 0.0, 0.0], [1.0, 2.0, 3.0], [0.7, 0.0, -0.1]);
+        let sampler = constant_acceleration_sampler(entity, p0, v0, a, 0.0, 0.25);
+
+        assert_eq!(sampler.sample(entity, 0.0).unwrap(), exact_state(p0, v0, a, 0.0));
+        assert_eq!(sampler.sample(entity, 0.25).unwrap(), exact_state(p0, v0, a, 0.25));
+        assert_eq!(sampler.sample_alpha(entity, 1.0).unwrap(), exact_state(p0, v0, a, 0.25));
+    }
+
+    #[test]
+    fn test_linear_fallback_without_velocities() {
+        let entity = Entity::new(1, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        let velocities = HashMapStorage::<Velocity>::new();
+        let mut sampler = StateSampler::new();
+
+        positions.insert(entity, Position::new(0.0, 0.0, 0.0));
+        sampler.record(0.0, [entity].iter(), &positions, &velocities).unwrap();
+        positions.insert(entity, Position::new(2.0, 4.0, 0.0));
+        sampler.record(2.0, [entity].iter(), &positions, &velocities).unwrap();
+
+        let (pos, vel) = sampler.sample_alpha(entity, 0.25).unwrap();
+        assert_eq!(pos, Position::new(0.5, 1.0, 0.0));
+        assert_eq!(vel, Velocity::new(1.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn test_out_of_range_and_missing_errors() {
+        let entity = Entity::new(1, 0);
+        let mut sampler = StateSampler::new();
+        assert!(sampler.sample(entity, 0.0).unwrap_err().contains("two recorded snapshots"));
+
+        sampler = constant_acceleration_sampler(entity, [0.0; 3], [1.0, 0.0, 0.0], [0.0; 3], 1.0, 2.0);
+        let err = sampler.sample(entity, 2.5).unwrap_err();
+        assert!(err.contains("outside the last step [1, 2]"), "{}", err);
+        assert!(sampler.sample(entity, 0.999).is_err());
+        assert!(sampler.sample_alpha(entity, -0.1).unwrap_err().contains("alpha"));
+
+        let err = sampler.sample(Entity::new(9, 0), 1.5).unwrap_err();
+        assert!(err.contains("missing"));
+    }
+
+    #[test]
+    fn test_repeated_or_non_finite_time_is_rejected() {
+        let positions = HashMapStorage::<Position>::new();
+        let velocities = HashMapStorage::<Velocity>::new();
+        let mut sampler = StateSampler::new();
+        sampler.record(1.0, std::iter::empty(), &positions, &velocities).unwrap();
+
+        let err = sampler.record(1.0, std::iter::empty(), &positions, &velocities).unwrap_err();
+        assert!(err.contains("repeats"), "{}", err);
+        assert!(sampler.record(Real::NAN, std::iter::empty(), &positions, &velocities).is_err());
+        // Neither rejected snapshot was recorded
+        assert!(sampler.time_range().is_none());
+    }
+
+    #[test]
+    fn test_backward_step_interpolates() {
+        let entity = Entity::new(1, 0);
+        let (p0, v0, a) = ([0.0, 1.0, 0.0], [2.0, 0.0, -1.0], [0.0, -9.81, 0.5]);
+        let sampler = constant_acceleration_sampler(entity, p0, v0, a, 2.0, 1.5);
+        assert_eq!(sampler.time_range(), Some((2.0, 1.5)));
+
+        let (pos, vel) = sampler.sample(entity, 1.6).unwrap();
+        let (exact_pos, exact_vel) = exact_state(p0, v0, a, 1.6);
+        for i in 0..3 {
+            assert!((pos.as_array()[i] - exact_pos.as_array()[i]).abs() < 1e-10 * Real::EPSILON_RATIO);
+            assert!((vel.as_array()[i] - exact_vel.as_array()[i]).abs() < 1e-9 * Real::EPSILON_RATIO);
+        }
+        assert_eq!(sampler.sample_alpha(entity, 1.0).unwrap(), exact_state(p0, v0, a, 1.5));
+
+        let err = sampler.sample(entity, 2.1).unwrap_err();
+        assert!(err.contains("outside the last step [1.5, 2]"), "{}", err);
+    }
+}
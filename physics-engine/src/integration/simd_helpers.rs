@@ -16,8 +16,9 @@
 //! This module provides SIMD-accelerated versions of integration operations
 //! that work with contiguous component data.
 
+use crate::real::Real;
 #[cfg(feature = "simd")]
-use crate::simd::select_backend;
+use crate::simd::{select_backend, SimdBackend};
 
 /// Process velocity updates with SIMD acceleration
 ///
@@ -27,13 +28,13 @@ use crate::simd::select_backend;
 /// scalar processing for remainder elements or when SIMD is not available.
 #[cfg_attr(not(feature = "simd"), allow(unused_variables))]
 pub fn simd_update_velocities(
-    vx: &mut [f64],
-    vy: &mut [f64],
-    vz: &mut [f64],
-    ax: &[f64],
-    ay: &[f64],
-    az: &[f64],
-    dt: f64,
+    vx: &mut [Real],
+    vy: &mut [Real],
+    vz: &mut [Real],
+    ax: &[Real],
+    ay: &[Real],
+    az: &[Real],
+    dt: Real,
 ) {
     #[cfg(feature = "simd")]
     {
@@ -78,17 +79,18 @@ pub fn simd_update_velocities(
 /// Uses SIMD when available and entity count is sufficient. Falls back to
 /// scalar processing for remainder elements or when SIMD is not available.
 #[cfg_attr(not(feature = "simd"), allow(unused_variables))]
+#[allow(clippy::too_many_arguments)]
 pub fn simd_update_positions(
-    px: &mut [f64],
-    py: &mut [f64],
-    pz: &mut [f64],
-    vx: &[f64],
-    vy: &[f64],
-    vz: &[f64],
-    ax: &[f64],
-    ay: &[f64],
-    az: &[f64],
-    dt: f64,
+    px: &mut [Real],
+    py: &mut [Real],
+    pz: &mut [Real],
+    vx: &[Real],
+    vy: &[Real],
+    vz: &[Real],
+    ax: &[Real],
+    ay: &[Real],
+    az: &[Real],
+    dt: Real,
 ) {
     let dt_sq_half = 0.5 * dt * dt;
     
@@ -154,12 +156,12 @@ pub fn simd_update_positions(
 /// scalar processing for remainder elements or when SIMD is not available.
 #[cfg_attr(not(feature = "simd"), allow(unused_variables))]
 pub fn simd_accumulate_forces(
-    total_fx: &mut [f64],
-    total_fy: &mut [f64],
-    total_fz: &mut [f64],
-    fx: &[f64],
-    fy: &[f64],
-    fz: &[f64],
+    total_fx: &mut [Real],
+    total_fy: &mut [Real],
+    total_fz: &mut [Real],
+    fx: &[Real],
+    fy: &[Real],
+    fz: &[Real],
 ) {
     #[cfg(feature = "simd")]
     {
@@ -197,6 +199,183 @@ pub fn simd_accumulate_forces(
     }
 }
 
+/// Multiply-add with SIMD acceleration
+///
+/// Computes y += a * x element-wise. With zeroed `y` and `a` holding inverse
+/// masses, this converts a force array into an acceleration array.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+#[cfg_attr(not(feature = "simd"), allow(unused_variables))]
+pub fn simd_axpy(y: &mut [Real], a: &[Real], x: &[Real]) {
+    assert!(
+        y.len() == a.len() && y.len() == x.len(),
+        "simd_axpy: slice lengths differ"
+    );
+
+    #[cfg(feature = "simd")]
+    {
+        axpy_with_backend(select_backend().as_ref(), y, a, x);
+    }
+
+    #[cfg(not(feature = "simd"))]
+    {
+        for i in 0..y.len() {
+            y[i] += a[i] * x[i];
+        }
+    }
+}
+
+/// Scale with SIMD acceleration
+///
+/// Computes y *= a, e.g. to shrink a force array uniformly when clamping.
+#[cfg_attr(not(feature = "simd"), allow(unused_variables))]
+pub fn simd_scale(y: &mut [Real], a: Real) {
+    #[cfg(feature = "simd")]
+    {
+        scale_with_backend(select_backend().as_ref(), y, a);
+    }
+
+    #[cfg(not(feature = "simd"))]
+    {
+        for value in y.iter_mut() {
+            *value *= a;
+        }
+    }
+}
+
+/// Dense force pipeline: sum per-provider force arrays and convert to accelerations
+///
+/// Each entry of `contributions` holds one provider's `[fx, fy, fz]` arrays
+/// in dense entity order. They are accumulated into `totals` (which are not
+/// cleared first), then `accelerations` are overwritten with
+/// `inv_mass * totals`. Immovable entities should carry an inverse mass of
+/// zero, which leaves their acceleration at zero.
+///
+/// # Panics
+///
+/// Panics if any array's length differs from `inv_mass.len()`.
+pub fn simd_dense_force_pipeline(
+    contributions: &[[&[Real]; 3]],
+    inv_mass: &[Real],
+    totals: [&mut [Real]; 3],
+    accelerations: [&mut [Real]; 3],
+) {
+    #[cfg(feature = "simd")]
+    {
+        dense_force_pipeline_with_backend(
+            select_backend().as_ref(),
+            contributions,
+            inv_mass,
+            totals,
+            accelerations,
+        );
+    }
+
+    #[cfg(not(feature = "simd"))]
+    {
+        check_pipeline_lengths(contributions, inv_mass, &totals, &accelerations);
+        let [total_fx, total_fy, total_fz] = totals;
+        for [fx, fy, fz] in contributions {
+            simd_accumulate_forces(total_fx, total_fy, total_fz, fx, fy, fz);
+        }
+        for (acceleration, total) in accelerations.into_iter().zip([&*total_fx, &*total_fy, &*total_fz]) {
+            acceleration.fill(0.0);
+            simd_axpy(acceleration, inv_mass, total);
+        }
+    }
+}
+
+/// Dense force pipeline on an explicit backend
+///
+/// Same as `simd_dense_force_pipeline()`, but runs the kernels of `backend`
+/// instead of the automatically selected one, so backends can be compared
+/// against each other. Tails shorter than the backend width use scalar code.
+///
+/// # Panics
+///
+/// Panics if any array's length differs from `inv_mass.len()`, or if
+/// `backend` is not supported on this CPU.
+#[cfg(feature = "simd")]
+pub fn dense_force_pipeline_with_backend(
+    backend: &dyn SimdBackend,
+    contributions: &[[&[Real]; 3]],
+    inv_mass: &[Real],
+    totals: [&mut [Real]; 3],
+    accelerations: [&mut [Real]; 3],
+) {
+    assert!(backend.is_supported(), "SIMD backend {} is not supported on this CPU", backend.name());
+    check_pipeline_lengths(contributions, inv_mass, &totals, &accelerations);
+
+    let count = inv_mass.len();
+    let simd_count = (count / backend.width()) * backend.width();
+    let [total_fx, total_fy, total_fz] = totals;
+
+    for [fx, fy, fz] in contributions {
+        for (total, force) in [(&mut *total_fx, fx), (&mut *total_fy, fy), (&mut *total_fz, fz)] {
+            unsafe {
+                backend.accumulate_forces_vectorized(&mut total[..simd_count], &force[..simd_count]);
+            }
+            for i in simd_count..count {
+                total[i] += force[i];
+            }
+        }
+    }
+
+    for (acceleration, total) in accelerations.into_iter().zip([&*total_fx, &*total_fy, &*total_fz]) {
+        acceleration.fill(0.0);
+        axpy_with_backend(backend, acceleration, inv_mass, total);
+    }
+}
+
+/// Check that every pipeline array has one entry per entity
+fn check_pipeline_lengths(
+    contributions: &[[&[Real]; 3]],
+    inv_mass: &[Real],
+    totals: &[&mut [Real]; 3],
+    accelerations: &[&mut [Real]; 3],
+) {
+    let count = inv_mass.len();
+    assert!(
+        contributions.iter().flatten().all(|f| f.len() == count)
+            && totals.iter().all(|f| f.len() == count)
+            && accelerations.iter().all(|a| a.len() == count),
+        "Dense force pipeline: every array must have {} entries",
+        count
+    );
+}
+
+/// y += a * x on `backend`, with a scalar tail
+#[cfg(feature = "simd")]
+fn axpy_with_backend(backend: &dyn SimdBackend, y: &mut [Real], a: &[Real], x: &[Real]) {
+    let count = y.len();
+    let simd_count = (count / backend.width()) * backend.width();
+    if simd_count > 0 {
+        unsafe {
+            backend.axpy(&mut y[..simd_count], &a[..simd_count], &x[..simd_count]);
+        }
+    }
+    for i in simd_count..count {
+        y[i] += a[i] * x[i];
+    }
+}
+
+/// y *= a on `backend`, with a scalar tail
+#[cfg(feature = "simd")]
+fn scale_with_backend(backend: &dyn SimdBackend, y: &mut [Real], a: Real) {
+    let count = y.len();
+    let simd_count = (count / backend.width()) * backend.width();
+    if simd_count > 0 {
+        unsafe {
+            backend.scale(&mut y[..simd_count], a);
+        }
+    }
+    for value in &mut y[simd_count..] {
+        *value *= a;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,4 +464,94 @@ mod tests {
         assert!((vx[1] - 2.1).abs() < 1e-10);
         assert!((vx[2] - 3.15).abs() < 1e-10);
     }
+    
+    #[test]
+    fn test_axpy_and_scale_tails() {
+        // 11 covers a full AVX-512 vector, a full AVX2 vector, and a tail
+        let x: Vec<Real> = (0..11).map(|i| i as Real).collect();
+        let a = vec![2.0; 11];
+        let mut y = vec![1.0; 11];
+        
+        simd_axpy(&mut y, &a, &x);
+        simd_scale(&mut y, 0.5);
+        
+        for (i, &value) in y.iter().enumerate() {
+            assert_eq!(value, (1.0 + 2.0 * i as Real) * 0.5);
+        }
+    }
+    
+    #[test]
+    fn test_dense_force_pipeline() {
+        let count = 13;
+        let gravity: Vec<Real> = vec![-9.8; count];
+        let drag: Vec<Real> = (0..count).map(|i| -0.1 * i as Real).collect();
+        let zeros = vec![0.0; count];
+        // Entity 0 is immovable
+        let inv_mass: Vec<Real> = (0..count).map(|i| if i == 0 { 0.0 } else { 1.0 / i as Real }).collect();
+        
+        let (mut fx, mut fy, mut fz) = (vec![0.0; count], vec![0.0; count], vec![0.0; count]);
+        let (mut ax, mut ay, mut az) = (vec![1.0; count], vec![1.0; count], vec![1.0; count]);
+        simd_dense_force_pipeline(
+            &[[&zeros, &gravity, &zeros], [&drag, &zeros, &zeros]],
+            &inv_mass,
+            [&mut fx, &mut fy, &mut fz],
+            [&mut ax, &mut ay, &mut az],
+        );
+        
+        for i in 0..count {
+            assert_eq!(fx[i], drag[i]);
+            assert_eq!(fy[i], -9.8);
+            assert_eq!(ax[i], inv_mass[i] * drag[i]);
+            assert_eq!(ay[i], inv_mass[i] * -9.8);
+            assert_eq!(az[i], 0.0);
+        }
+        assert_eq!(ay[0], 0.0, "Immovable entity must not accelerate");
+    }
+    
+    #[test]
+    #[cfg(feature = "simd")]
+    fn test_dense_force_pipeline_matches_scalar_backend() {
+        use crate::simd::ScalarBackend;
+        
+        let count = 1003;
+        let providers: Vec<Vec<Real>> = (0..3)
+            .map(|p| (0..count).map(|i| ((i * 7 + p) as Real * 0.13).sin()).collect())
+            .collect();
+        let inv_mass: Vec<Real> = (0..count).map(|i| 1.0 / (1.0 + i as Real)).collect();
+        let contributions = [
+            [&providers[0][..], &providers[1][..], &providers[2][..]],
+            [&providers[2][..], &providers[0][..], &providers[1][..]],
+        ];
+        
+        let run = |backend: &dyn SimdBackend| {
+            let (mut fx, mut fy, mut fz) = (vec![0.0; count], vec![0.0; count], vec![0.0; count]);
+            let (mut ax, mut ay, mut az) = (vec![0.0; count], vec![0.0; count], vec![0.0; count]);
+            dense_force_pipeline_with_backend(
+                backend,
+                &contributions,
+                &inv_mass,
+                [&mut fx, &mut fy, &mut fz],
+                [&mut ax, &mut ay, &mut az],
+            );
+            (fx, fy, fz, ax, ay, az)
+        };
+        
+        // Same operations in the same order, so results are bit-identical
+        assert_eq!(run(select_backend().as_ref()), run(&ScalarBackend));
+    }
+    
+    #[test]
+    #[should_panic(expected = "every array must have 4 entries")]
+    fn test_dense_force_pipeline_rejects_mismatched_lengths() {
+        let short = vec![0.0; 3];
+        let inv_mass = vec![1.0; 4];
+        let (mut fx, mut fy, mut fz) = (vec![0.0; 4], vec![0.0; 4], vec![0.0; 4]);
+        let (mut ax, mut ay, mut az) = (vec![0.0; 4], vec![0.0; 4], vec![0.0; 4]);
+        simd_dense_force_pipeline(
+            &[[&short, &short, &short]],
+            &inv_mass,
+            [&mut fx, &mut fy, &mut fz],
+            [&mut ax, &mut ay, &mut az],
+        );
+    }
 }
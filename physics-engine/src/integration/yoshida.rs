@@ -0,0 +1,576 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Yoshida 4th-order symplectic integrator implementation
+//!
+//! The Yoshida method composes three velocity Verlet sub-steps with weights
+//! chosen so that the second- and third-order error terms cancel:
+//!
+//! ```text
+//! w1 = 1 / (2 - 2^(1/3))
+//! w0 = -2^(1/3) / (2 - 2^(1/3))
+//!
+//! step(dt) = verlet(w1*dt) ∘ verlet(w0*dt) ∘ verlet(w1*dt)
+//! ```
+//!
+//! The middle sub-step runs backwards in time (w0 < 0). Each sub-step is a
+//! kick-drift-kick Verlet step, and the force at the end of one sub-step is
+//! the force at the start of the next.
+//!
+//! # Properties
+//!
+//! - **Symplectic**: Composition of symplectic maps, so energy error stays
+//!   bounded with no secular drift
+//! - **Time-reversible**: The composition is symmetric
+//! - **Fourth-order accurate**: Global error O(dt⁴)
+//! - **Three force evaluations per step**: One per sub-step, versus four for
+//!   RK4 (which is not symplectic) and one for Verlet
+//!
+//! # Force Evaluation
+//!
+//! Like the other integrators, forces are re-evaluated at each sub-stage by
+//! clearing and re-accumulating the force registry. Providers that compute
+//! forces from positions outside the registry (e.g. `GravitySystem`) must be
+//! refreshed at every sub-stage, which `integrate_with()` supports through a
+//! callback; without it the sub-stages see the forces registered before the
+//! step and the method loses its fourth-order accuracy.
+//!
+//! # References
+//!
+//! - Yoshida, H. (1990). Construction of higher order symplectic integrators.
+//!   Physics Letters A, 150(5-7), 262-268.
+//! - Hairer, E., Lubich, C., & Wanner, G. (2006). Geometric Numerical Integration
+//!   (2nd ed.). Springer. Section II.4.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage, HashMapStorage};
+use crate::ecs::components::{Position, Velocity, Acceleration, Mass};
+use crate::ecs::systems::{ForceRegistry, apply_forces_to_acceleration};
+use super::{prime_accelerations, Integrator, TimestepSuggestion, estimate_max_angular_frequency};
+
+/// Yoshida 4th-order symplectic integrator
+///
+/// Best suited for long-running orbital and oscillatory simulations that
+/// need better accuracy than Verlet without RK4's energy drift.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::integration::{YoshidaIntegrator, Integrator};
+///
+/// let integrator = YoshidaIntegrator::new(0.01);
+/// assert_eq!(integrator.name(), "Yoshida 4");
+/// ```
+pub struct YoshidaIntegrator {
+    timestep: Real,
+    max_stiffness: Option<Real>,
+}
+
+impl YoshidaIntegrator {
+    /// Stability bound on ω·dt for the Yoshida composition
+    ///
+    /// Each sub-step is a Verlet step (stable for ω·h < 2) and the largest
+    /// sub-step weight is |w0| ≈ 1.70, giving ω·dt < 2 / 1.70.
+    pub const STABILITY_FACTOR: Real = 1.17;
+
+    /// Sub-step weights (w1, w0, w1)
+    pub fn coefficients() -> [Real; 3] {
+        let cbrt2 = (2.0 as Real).cbrt();
+        let w1 = 1.0 / (2.0 - cbrt2);
+        let w0 = -cbrt2 / (2.0 - cbrt2);
+        [w1, w0, w1]
+    }
+
+    /// Create a new Yoshida integrator with the given timestep
+    ///
+    /// # Panics
+    ///
+    /// Panics if timestep is non-positive, NaN, or infinite
+    pub fn new(timestep: Real) -> Self {
+        assert!(
+            timestep > 0.0 && timestep.is_finite(),
+            "Timestep must be positive and finite"
+        );
+        YoshidaIntegrator {
+            timestep,
+            max_stiffness: None,
+        }
+    }
+
+    /// Set the maximum stiffness (N/m) used for timestep suggestions
+    pub fn set_max_stiffness(&mut self, stiffness: Option<Real>) {
+        self.max_stiffness = stiffness;
+    }
+
+    /// Get the maximum stiffness used for timestep suggestions
+    pub fn max_stiffness(&self) -> Option<Real> {
+        self.max_stiffness
+    }
+
+    /// Integrate one step, refreshing forces before every evaluation
+    ///
+    /// Identical to `integrate()`, except `refresh_forces` is called with the
+    /// updated positions before each of the three force evaluations. It
+    /// should bring the registry up to date for those positions, typically by
+    /// clearing it and registering freshly computed forces:
+    ///
+    /// ```rust,ignore
+    /// integrator.integrate_with(
+    ///     entities.iter(), &mut positions, &mut velocities, &accelerations,
+    ///     &masses, &mut registry, false,
+    ///     |entities, positions, registry| {
+    ///         registry.clear();
+    ///         gravity.compute_forces_into(entities, positions, &masses, &mut forces);
+    ///         registry.register_forces(&forces);
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// `accelerations` must hold the accelerations at the entry positions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        mut refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        let entities_vec: Vec<Entity> = entities.copied().collect();
+        if entities_vec.is_empty() {
+            return 0;
+        }
+
+        // Movable entities with the components needed to integrate
+        let mut movable = Vec::with_capacity(entities_vec.len());
+        for entity in &entities_vec {
+            if masses.get(*entity).map_or(true, |m| m.is_immovable()) || force_registry.is_sleeping(*entity) {
+                continue;
+            }
+            if !positions.contains(*entity) {
+                if warn_on_missing {
+                    eprintln!("Warning: Entity {:?} missing Position component", entity);
+                }
+                continue;
+            }
+            if velocities.get(*entity).is_none() {
+                if warn_on_missing {
+                    eprintln!("Warning: Entity {:?} missing Velocity component", entity);
+                }
+                continue;
+            }
+            movable.push(*entity);
+        }
+
+        // Aligned with `movable`
+        let mut current_accelerations: Vec<Acceleration> = movable
+            .iter()
+            .map(|e| accelerations.get(*e).copied().unwrap_or_else(Acceleration::zero))
+            .collect();
+        let mut new_accelerations = HashMapStorage::<Acceleration>::new();
+        let t0 = force_registry.time();
+        let mut elapsed_weight = 0.0;
+
+        for weight in Self::coefficients() {
+            let h = weight * self.timestep;
+            elapsed_weight += weight;
+
+            // Kick (half) and drift
+            for (entity, acc) in movable.iter().zip(&current_accelerations) {
+                let (Some(vel), Some(pos)) = (velocities.get_mut(*entity), positions.get_mut(*entity)) else {
+                    continue;
+                };
+                vel.set_dx(vel.dx() + 0.5 * h * acc.ax());
+                vel.set_dy(vel.dy() + 0.5 * h * acc.ay());
+                vel.set_dz(vel.dz() + 0.5 * h * acc.az());
+                pos.set_x(pos.x() + h * vel.dx());
+                pos.set_y(pos.y() + h * vel.dy());
+                pos.set_z(pos.z() + h * vel.dz());
+            }
+
+            // Forces at the drifted positions, at the end of this sub-step
+            force_registry.set_time(t0 + elapsed_weight * self.timestep);
+            refresh_forces(&entities_vec, positions, force_registry);
+            force_registry.clear_forces();
+            for entity in &entities_vec {
+                force_registry.accumulate_for_entity(*entity);
+            }
+            new_accelerations.clear();
+            apply_forces_to_acceleration(
+                movable.iter(),
+                force_registry,
+                masses,
+                &mut new_accelerations,
+                warn_on_missing,
+            );
+
+            // Kick (half)
+            for (entity, current) in movable.iter().zip(current_accelerations.iter_mut()) {
+                let acc = new_accelerations.get(*entity).copied().unwrap_or_else(Acceleration::zero);
+                if let Some(vel) = velocities.get_mut(*entity) {
+                    vel.set_dx(vel.dx() + 0.5 * h * acc.ax());
+                    vel.set_dy(vel.dy() + 0.5 * h * acc.ay());
+                    vel.set_dz(vel.dz() + 0.5 * h * acc.az());
+                }
+                *current = acc;
+            }
+        }
+
+        force_registry.set_time(t0 + self.timestep);
+
+        let mut updated_count = 0;
+        for entity in &movable {
+            let valid = positions.get(*entity).is_some_and(|p| p.is_valid())
+                && velocities.get(*entity).is_some_and(|v| v.is_valid());
+            if valid {
+                updated_count += 1;
+            } else if warn_on_missing {
+                eprintln!("Warning: Invalid state after Yoshida update for {:?}", entity);
+            }
+        }
+
+        updated_count
+    }
+}
+
+impl Integrator for YoshidaIntegrator {
+    fn name(&self) -> &str {
+        "Yoshida 4"
+    }
+
+    fn timestep(&self) -> Real {
+        self.timestep
+    }
+
+    fn set_timestep(&mut self, dt: Real) {
+        assert!(
+            dt != 0.0 && dt.is_finite(),
+            "Timestep must be non-zero and finite"
+        );
+        self.timestep = dt;
+    }
+
+    fn supports_negative_dt(&self) -> bool {
+        true
+    }
+
+    fn suggest_timestep<'a, I>(
+        &self,
+        entities: I,
+        positions: &impl ComponentStorage<Component = Position>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+    ) -> Option<TimestepSuggestion>
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let omega = estimate_max_angular_frequency(
+            entities,
+            positions,
+            accelerations,
+            masses,
+            self.max_stiffness,
+        )?;
+        Some(TimestepSuggestion::from_frequency(omega, Self::STABILITY_FACTOR, self.timestep.abs()))
+    }
+
+    fn prime<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &P,
+        accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        force_eval: F,
+    ) where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        prime_accelerations(entities, positions, accelerations, masses, force_registry, force_eval);
+    }
+
+    fn integrate<'a, I>(
+        &mut self,
+        entities: I,
+        positions: &mut impl ComponentStorage<Component = Position>,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        self.integrate_with(
+            entities,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+            |_, _, _| {},
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn integrate_with<'a, I, P, F>(
+        &mut self,
+        entities: I,
+        positions: &mut P,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        accelerations: &impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+        warn_on_missing: bool,
+        refresh_forces: F,
+    ) -> usize
+    where
+        I: Iterator<Item = &'a Entity>,
+        P: ComponentStorage<Component = Position>,
+        F: FnMut(&[Entity], &P, &mut ForceRegistry),
+    {
+        YoshidaIntegrator::integrate_with(
+            self,
+            entities,
+            positions,
+            velocities,
+            accelerations,
+            masses,
+            force_registry,
+            warn_on_missing,
+            refresh_forces,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real::RealExt;
+    use crate::ecs::systems::{Force, ForceProvider};
+    use std::sync::atomic::Ordering;
+
+    // Atomic holding the bits of a `Real`
+    #[cfg(not(feature = "single-precision"))]
+    use std::sync::atomic::AtomicU64 as AtomicBits;
+    #[cfg(feature = "single-precision")]
+    use std::sync::atomic::AtomicU32 as AtomicBits;
+    use std::sync::Arc;
+
+    const STIFFNESS: Real = 1.0;
+    const MASS: Real = 1.0;
+
+    /// Spring force F = -kx on a single body, reading x from a shared cell
+    /// that the refresh callback updates at every sub-stage
+    struct SpringForce {
+        x_bits: Arc<AtomicBits>,
+    }
+
+    impl ForceProvider for SpringForce {
+        fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+            let x = Real::from_bits(self.x_bits.load(Ordering::Relaxed));
+            Some(Force::new(-STIFFNESS * x, 0.0, 0.0))
+        }
+
+        fn name(&self) -> &str {
+            "SpringForce"
+        }
+    }
+
+    fn oscillator_energy(positions: &HashMapStorage<Position>, velocities: &HashMapStorage<Velocity>, entity: Entity) -> Real {
+        let x = positions.get(entity).unwrap().x();
+        let v = velocities.get(entity).unwrap().dx();
+        0.5 * MASS * v * v + 0.5 * STIFFNESS * x * x
+    }
+
+    /// Run the harmonic oscillator from x = 1, v = 0, calling `observe` with
+    /// the energy after each step
+    ///
+    /// With `verlet` set, each step is a single velocity Verlet step (the
+    /// one-sub-step case of the composition) as a baseline.
+    fn run_oscillator(dt: Real, steps: usize, verlet: bool, mut observe: impl FnMut(Real)) -> (Real, Real) {
+        let entity = Entity::new(0, 0);
+        let entities = [entity];
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::new(1.0, 0.0, 0.0));
+        velocities.insert(entity, Velocity::new(0.0, 0.0, 0.0));
+        masses.insert(entity, Mass::new(MASS));
+
+        let x_bits = Arc::new(AtomicBits::new((1.0 as Real).to_bits()));
+        let mut registry = ForceRegistry::new();
+        registry.register_provider(Box::new(SpringForce { x_bits: x_bits.clone() }));
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut yoshida = YoshidaIntegrator::new(dt);
+
+        for _ in 0..steps {
+            if verlet {
+                // Velocity Verlet is the single sub-step case of the composition
+                let x = positions.get(entity).unwrap().x();
+                let v = velocities.get(entity).unwrap().dx();
+                let a0 = -STIFFNESS * x / MASS;
+                let half = v + 0.5 * dt * a0;
+                let x1 = x + dt * half;
+                let a1 = -STIFFNESS * x1 / MASS;
+                positions.insert(entity, Position::new(x1, 0.0, 0.0));
+                velocities.insert(entity, Velocity::new(half + 0.5 * dt * a1, 0.0, 0.0));
+            } else {
+                let x = positions.get(entity).unwrap().x();
+                accelerations.insert(entity, Acceleration::new(-STIFFNESS * x / MASS, 0.0, 0.0));
+                yoshida.integrate_with(
+                    entities.iter(),
+                    &mut positions,
+                    &mut velocities,
+                    &accelerations,
+                    &masses,
+                    &mut registry,
+                    false,
+                    |_, positions, _| {
+                        x_bits.store(positions.get(entity).unwrap().x().to_bits(), Ordering::Relaxed);
+                    },
+                );
+            }
+            observe(oscillator_energy(&positions, &velocities, entity));
+        }
+
+        (positions.get(entity).unwrap().x(), velocities.get(entity).unwrap().dx())
+    }
+
+    #[test]
+    fn test_yoshida_creation_and_coefficients() {
+        let integrator = YoshidaIntegrator::new(0.01);
+        assert_eq!(integrator.timestep(), 0.01);
+
+        let [w1, w0, w1b] = YoshidaIntegrator::coefficients();
+        assert_eq!(w1, w1b);
+        // Weights sum to one, and cubes sum to zero (third-order cancellation)
+        assert!((2.0 * w1 + w0 - 1.0).abs() < 1e-14);
+        assert!((2.0 * w1.powi(3) + w0.powi(3)).abs() < 1e-14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Timestep must be positive and finite")]
+    fn test_yoshida_invalid_timestep() {
+        YoshidaIntegrator::new(0.0);
+    }
+
+    /// Run the oscillator for `steps` and check the energy error neither
+    /// grows nor comes close to Verlet's
+    fn assert_energy_bounded(steps: usize) {
+        let dt = 0.05;
+        let initial_energy = 0.5 * STIFFNESS;
+
+        let mut yoshida_max_error: Real = 0.0;
+        let mut first_half_max: Real = 0.0;
+        let mut step = 0;
+        run_oscillator(dt, steps, false, |energy| {
+            let error = (energy - initial_energy).abs() / initial_energy;
+            yoshida_max_error = yoshida_max_error.max(error);
+            if step < steps / 2 {
+                first_half_max = first_half_max.max(error);
+            }
+            step += 1;
+        });
+
+        // Verlet's error is bounded too, so a shorter run finds its maximum
+        let mut verlet_max_error: Real = 0.0;
+        run_oscillator(dt, steps / 100, true, |energy| {
+            verlet_max_error = verlet_max_error.max((energy - initial_energy).abs() / initial_energy);
+        });
+
+        // No secular drift: the second half is no worse than the first
+        assert!(
+            yoshida_max_error <= first_half_max * 1.01 + 1e-12,
+            "Energy error grew: first half {:.3e}, overall {:.3e}",
+            first_half_max,
+            yoshida_max_error
+        );
+        assert!(
+            yoshida_max_error * 100.0 < verlet_max_error,
+            "Yoshida {:.3e} should beat Verlet {:.3e} by 100x",
+            yoshida_max_error,
+            verlet_max_error
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "energy error falls below f32 rounding")]
+    fn test_yoshida_energy_bounded() {
+        assert_energy_bounded(20_000);
+    }
+
+    #[test]
+    #[ignore = "slow (10⁶ steps); run with --ignored"]
+    fn test_yoshida_energy_bounded_over_million_steps() {
+        assert_energy_bounded(1_000_000);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "truncation error falls below f32 rounding")]
+    fn test_yoshida_fourth_order_convergence() {
+        // Error at t = 2 against the exact solution x = cos(t), v = -sin(t)
+        let t_final = 2.0;
+        let error_at = |dt: Real| {
+            let steps = (t_final / dt).round() as usize;
+            let (x, v) = run_oscillator(dt, steps, false, |_| {});
+            ((x - t_final.cos()).powi(2) + (v + t_final.sin()).powi(2)).sqrt()
+        };
+
+        let errors: Vec<Real> = [0.1, 0.05, 0.025].iter().map(|&dt| error_at(dt)).collect();
+        for pair in errors.windows(2) {
+            let order = (pair[0] / pair[1]).log2();
+            assert!((order - 4.0).abs() < 0.3, "Observed order {:.2}, errors {:?}", order, errors);
+        }
+    }
+
+    #[test]
+    fn test_yoshida_integrate_without_refresh_moves_bodies() {
+        let entity = Entity::new(1, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::zero());
+        velocities.insert(entity, Velocity::new(1.0, 2.0, 3.0));
+        masses.insert(entity, Mass::new(1.0));
+
+        let mut integrator = YoshidaIntegrator::new(0.1);
+        let count = integrator.integrate(
+            [entity].iter(),
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &masses,
+            &mut ForceRegistry::new(),
+            false,
+        );
+
+        // Free motion: sub-step weights sum to one
+        assert_eq!(count, 1);
+        let pos = positions.get(entity).unwrap();
+        assert!((pos.x() - 0.1).abs() < 1e-12 * Real::EPSILON_RATIO);
+        assert!((pos.z() - 0.3).abs() < 1e-12 * Real::EPSILON_RATIO);
+    }
+}
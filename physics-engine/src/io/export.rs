@@ -0,0 +1,131 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Saving initial conditions to files
+//!
+//! Writes the formats read by `import`, converting bodies from simulation
+//! units into file units first. ASCII values are written in the shortest
+//! exponent form that parses back to the same value, so with the default
+//! `UnitScaling` a save followed by a load is lossless:
+//!
+//! ```
+//! use physics_engine::ecs::components::{Mass, Position, Velocity};
+//! use physics_engine::io::{export, import, UnitScaling};
+//!
+//! let bodies = vec![(Mass::new(0.1), Position::new(1.0 / 3.0, 0.0, -2.0), Velocity::zero())];
+//! let mut table = Vec::new();
+//! export::write_ascii(&mut table, &bodies, &UnitScaling::default()).unwrap();
+//!
+//! let loaded = import::read_ascii(table.as_slice(), &UnitScaling::default()).unwrap();
+//! assert_eq!(loaded[0].1, bodies[0].1);
+//! ```
+
+use crate::io::{body_values, Body, UnitScaling, ASCII_COLUMNS, BINARY_HEADER_BYTES};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Save initial conditions to a file, choosing the format by extension
+///
+/// Files ending in `.bin` or `.tipsy` are written with `write_binary()`,
+/// all others with `write_ascii()`.
+pub fn save(path: impl AsRef<Path>, bodies: &[Body], scaling: &UnitScaling) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut file = BufWriter::new(File::create(path)?);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("bin") | Some("tipsy") => write_binary(&mut file, bodies, scaling)?,
+        _ => write_ascii(&mut file, bodies, scaling)?,
+    }
+    file.flush()
+}
+
+/// Write bodies as an ASCII table with a header row
+pub fn write_ascii<W: Write>(mut writer: W, bodies: &[Body], scaling: &UnitScaling) -> io::Result<()> {
+    writeln!(writer, "{}", ASCII_COLUMNS.join(" "))?;
+    for body in bodies {
+        let values = scaling.scale_to_file(body_values(body));
+        let row: Vec<String> = values.iter().map(|value| format!("{:e}", value)).collect();
+        writeln!(writer, "{}", row.join(" "))?;
+    }
+    Ok(())
+}
+
+/// Write bodies in the binary layout
+///
+/// The header time, and each record's softening and potential, are zero.
+///
+/// # Errors
+///
+/// Fails with `InvalidInput` if there are more than `u32::MAX` bodies.
+// The conversion is a no-op for f64 but widens f32 values
+#[allow(clippy::useless_conversion)]
+pub fn write_binary<W: Write>(mut writer: W, bodies: &[Body], scaling: &UnitScaling) -> io::Result<()> {
+    let count = u32::try_from(bodies.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many bodies for the binary layout"))?;
+
+    let mut header = [0u8; BINARY_HEADER_BYTES];
+    header[8..12].copy_from_slice(&count.to_le_bytes());
+    header[12..16].copy_from_slice(&3u32.to_le_bytes());
+    header[20..24].copy_from_slice(&count.to_le_bytes());
+    writer.write_all(&header)?;
+
+    for body in bodies {
+        let values = scaling.scale_to_file(body_values(body));
+        for value in values.into_iter().chain([0.0, 0.0]) {
+            writer.write_all(&f64::from(value).to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{Mass, Position, Velocity};
+    use crate::io::{import, BINARY_RECORD_BYTES};
+    use crate::real::Real;
+
+    fn awkward_bodies() -> Vec<Body> {
+        vec![
+            (Mass::new(1.0 / 3.0), Position::new(Real::MAX, -Real::MIN_POSITIVE, 0.1), Velocity::new(-0.0, 7.0, 1e-300 as Real)),
+            (Mass::immovable(), Position::new(2.0_f64.sqrt() as Real, 1e10, -5.5), Velocity::new(3e8, 0.0, -1.0 / 7.0)),
+        ]
+    }
+
+    fn assert_bitwise_equal(a: &[Body], b: &[Body]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            let bits = |body: &Body| body_values(body).map(Real::to_bits);
+            assert_eq!(bits(x), bits(y));
+        }
+    }
+
+    #[test]
+    fn test_ascii_round_trip_is_lossless() {
+        let bodies = awkward_bodies();
+        let mut table = Vec::new();
+        write_ascii(&mut table, &bodies, &UnitScaling::default()).unwrap();
+        let loaded = import::read_ascii(table.as_slice(), &UnitScaling::default()).unwrap();
+        assert_bitwise_equal(&bodies, &loaded);
+    }
+
+    #[test]
+    fn test_binary_round_trip_is_lossless() {
+        let bodies = awkward_bodies();
+        let mut file = Vec::new();
+        write_binary(&mut file, &bodies, &UnitScaling::default()).unwrap();
+        assert_eq!(file.len(), BINARY_HEADER_BYTES + bodies.len() * BINARY_RECORD_BYTES);
+        let loaded = import::read_binary(file.as_slice(), &UnitScaling::default()).unwrap();
+        assert_bitwise_equal(&bodies, &loaded);
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Loading initial conditions from files
+//!
+//! # ASCII Table
+//!
+//! One body per line, with seven columns in this order:
+//!
+//! ```text
+//! # Comments start with '#' and run to the end of the line
+//! mass  x     y    z    vx   vy    vz
+//! 5.0   1.0   0.0  0.0  0.0  2.5   0.0
+//! 1e-3, -2.0, 0.5, 0.0, 0.1, -1.0, 0.0
+//! ```
+//!
+//! - Columns are separated by whitespace, or by commas if the line has any
+//! - Blank lines and comments are skipped
+//! - The first non-comment line may be a header naming the columns
+//!   `mass x y z vx vy vz` (case-insensitive)
+//! - Masses must be non-negative, and every value finite
+//!
+//! Errors name the 1-based line and column of the first bad value.
+//!
+//! # Binary
+//!
+//! A Tipsy-like layout: a header followed by one dark-matter style record
+//! per body. All values are little-endian, and unlike classic Tipsy every
+//! record field is an `f64`, so no precision is lost.
+//!
+//! | Offset | Type  | Field                          |
+//! |--------|-------|--------------------------------|
+//! | 0      | `f64` | time (ignored on import)       |
+//! | 8      | `u32` | number of bodies               |
+//! | 12     | `u32` | dimensions, always 3           |
+//! | 16     | `u32` | gas particles, always 0        |
+//! | 20     | `u32` | dark particles, equal to count |
+//! | 24     | `u32` | star particles, always 0       |
+//! | 28     | `u32` | padding                        |
+//!
+//! Each 72-byte record holds `mass x y z vx vy vz eps phi` as `f64`. The
+//! softening `eps` and potential `phi` are ignored on import.
+
+use crate::real::Real;
+use crate::ecs::components::{Mass, Position, Velocity};
+use crate::io::{Body, UnitScaling, ASCII_COLUMNS, BINARY_HEADER_BYTES, BINARY_RECORD_BYTES};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Error returned when initial conditions cannot be loaded
+#[derive(Debug)]
+pub enum ImportError {
+    /// The source could not be read
+    Io(io::Error),
+    /// A line of an ASCII table is malformed
+    Ascii {
+        /// 1-based line number
+        line: usize,
+        /// 1-based column of the bad value, or `None` for the whole line
+        column: Option<usize>,
+        /// Why the line was rejected
+        reason: String,
+    },
+    /// A binary file is malformed
+    Binary {
+        /// 0-based index of the bad record, or `None` for the header
+        record: Option<usize>,
+        /// Why the file was rejected
+        reason: String,
+    },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "Failed to read initial conditions: {}", err),
+            ImportError::Ascii { line, column: Some(column), reason } => write!(
+                f,
+                "Line {}, column {} ({}): {}",
+                line,
+                column,
+                ASCII_COLUMNS[column - 1],
+                reason
+            ),
+            ImportError::Ascii { line, column: None, reason } => write!(f, "Line {}: {}", line, reason),
+            ImportError::Binary { record: Some(record), reason } => {
+                write!(f, "Record {}: {}", record, reason)
+            }
+            ImportError::Binary { record: None, reason } => write!(f, "Header: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        ImportError::Io(err)
+    }
+}
+
+/// Load initial conditions from a file, choosing the format by extension
+///
+/// Files ending in `.bin` or `.tipsy` are read with `read_binary()`, all
+/// others with `read_ascii()`.
+pub fn load(path: impl AsRef<Path>, scaling: &UnitScaling) -> Result<Vec<Body>, ImportError> {
+    let path = path.as_ref();
+    let file = BufReader::new(File::open(path)?);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("bin") | Some("tipsy") => read_binary(file, scaling),
+        _ => read_ascii(file, scaling),
+    }
+}
+
+/// Read an ASCII table of bodies, converting them into simulation units
+///
+/// Stops at the first malformed line. See the module documentation for the
+/// format.
+pub fn read_ascii<R: BufRead>(reader: R, scaling: &UnitScaling) -> Result<Vec<Body>, ImportError> {
+    let mut bodies = Vec::new();
+    let mut first_row = true;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+        let content = line.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = if content.contains(',') {
+            content.split(',').map(str::trim).collect()
+        } else {
+            content.split_whitespace().collect()
+        };
+        if std::mem::take(&mut first_row) && is_header(&fields) {
+            continue;
+        }
+
+        let ascii_error = |column: Option<usize>, reason: String| ImportError::Ascii {
+            line: line_number,
+            column,
+            reason,
+        };
+        if fields.len() != ASCII_COLUMNS.len() {
+            return Err(ascii_error(
+                None,
+                format!("Expected {} columns, found {}", ASCII_COLUMNS.len(), fields.len()),
+            ));
+        }
+
+        let mut values = [0.0; 7];
+        for (column, (value, field)) in values.iter_mut().zip(&fields).enumerate() {
+            *value = field
+                .parse::<Real>()
+                .map_err(|err| ascii_error(Some(column + 1), format!("Invalid number '{}': {}", field, err)))?;
+        }
+        let body = build_body(values, scaling)
+            .map_err(|(column, reason)| ascii_error(Some(column + 1), reason))?;
+        bodies.push(body);
+    }
+
+    Ok(bodies)
+}
+
+/// Read bodies in the binary layout, converting them into simulation units
+///
+/// See the module documentation for the layout.
+pub fn read_binary<R: Read>(mut reader: R, scaling: &UnitScaling) -> Result<Vec<Body>, ImportError> {
+    let header_error = |reason: &str| ImportError::Binary {
+        record: None,
+        reason: reason.to_string(),
+    };
+
+    let mut header = [0u8; BINARY_HEADER_BYTES];
+    read_full(&mut reader, &mut header).map_err(|err| match err {
+        Some(err) => ImportError::Io(err),
+        None => header_error("Truncated header"),
+    })?;
+    let word = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+    let (count, dimensions, gas, dark, stars) = (word(8), word(12), word(16), word(20), word(24));
+    if dimensions != 3 {
+        return Err(header_error(&format!("Expected 3 dimensions, found {}", dimensions)));
+    }
+    if gas != 0 || stars != 0 || dark != count {
+        return Err(header_error("Only dark-matter records are supported"));
+    }
+
+    let count = count as usize;
+    // The count is untrusted, so cap the up-front allocation
+    let mut bodies = Vec::with_capacity(count.min(1 << 16));
+    let mut record = [0u8; BINARY_RECORD_BYTES];
+    for index in 0..count {
+        let record_error = |reason: String| ImportError::Binary {
+            record: Some(index),
+            reason,
+        };
+        read_full(&mut reader, &mut record).map_err(|err| match err {
+            Some(err) => ImportError::Io(err),
+            None => record_error("Truncated record".to_string()),
+        })?;
+
+        let values: [Real; 7] = std::array::from_fn(|i| {
+            f64::from_le_bytes(record[i * 8..i * 8 + 8].try_into().unwrap()) as Real
+        });
+        let body = build_body(values, scaling)
+            .map_err(|(column, reason)| record_error(format!("{}: {}", ASCII_COLUMNS[column], reason)))?;
+        bodies.push(body);
+    }
+
+    Ok(bodies)
+}
+
+/// Check whether a row names the ASCII columns
+fn is_header(fields: &[&str]) -> bool {
+    fields.len() == ASCII_COLUMNS.len()
+        && fields.iter().zip(ASCII_COLUMNS).all(|(field, name)| field.eq_ignore_ascii_case(name))
+}
+
+/// Fill `buf` completely, returning `Err(None)` on a premature end of input
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), Option<io::Error>> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => None,
+        _ => Some(err),
+    })
+}
+
+/// Validate `mass x y z vx vy vz` values and convert them into a body
+///
+/// On failure, returns the 0-based column of the bad value and the reason.
+fn build_body(values: [Real; 7], scaling: &UnitScaling) -> Result<Body, (usize, String)> {
+    let check_finite = |values: &[Real; 7], reason: &str| match values.iter().position(|v| !v.is_finite()) {
+        Some(column) => Err((column, reason.to_string())),
+        None => Ok(()),
+    };
+    check_finite(&values, "Value is not finite")?;
+    if values[0] < 0.0 {
+        return Err((0, "Mass must be non-negative".to_string()));
+    }
+
+    let [m, x, y, z, vx, vy, vz] = scaling.scale_to_simulation(values);
+    check_finite(&[m, x, y, z, vx, vy, vz], "Value is not finite in simulation units")?;
+    Ok((Mass::new(m), Position::new(x, y, z), Velocity::new(vx, vy, vz)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real::RealExt;
+    use crate::units::{Kilograms, Meters, Seconds, UnitSystem, ASTRONOMICAL_UNIT};
+
+    #[test]
+    fn test_ascii_header_comments_and_separators() {
+        let table = "\
+# Two bodies
+  MASS x y z vx vy vz
+
+5.0   1.0 0.0  0.0 0.0 2.5  0.0   # whitespace
+1e-3, -2, 0.5, 0,  0.1, -1, 0.0   # commas
+";
+        let bodies = read_ascii(table.as_bytes(), &UnitScaling::default()).unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0].0.value(), 5.0);
+        assert_eq!(bodies[0].2.as_array(), [0.0, 2.5, 0.0]);
+        assert_eq!(bodies[1].0.value(), 1e-3);
+        assert_eq!(bodies[1].1.as_array(), [-2.0, 0.5, 0.0]);
+        assert_eq!(bodies[1].2.as_array(), [0.1, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_ascii_errors_name_line_and_column() {
+        let error = |table: &str| read_ascii(table.as_bytes(), &UnitScaling::default()).unwrap_err();
+
+        match error("1 0 0 0 0 0 0\n# comment\n1 0 zero 0 0 0 0\n") {
+            ImportError::Ascii { line, column, reason } => {
+                assert_eq!((line, column), (3, Some(3)));
+                assert!(reason.contains("'zero'"), "{}", reason);
+            }
+            other => panic!("Unexpected error {:?}", other),
+        }
+        match error("1 0 0 0 0 0\n") {
+            ImportError::Ascii { line: 1, column: None, reason } => {
+                assert_eq!(reason, "Expected 7 columns, found 6");
+            }
+            other => panic!("Unexpected error {:?}", other),
+        }
+        // A header is only accepted on the first row
+        assert!(matches!(
+            error("1 0 0 0 0 0 0\nmass x y z vx vy vz\n"),
+            ImportError::Ascii { line: 2, column: Some(1), .. }
+        ));
+
+        let negative = error("-1 0 0 0 0 0 0\n");
+        assert_eq!(negative.to_string(), "Line 1, column 1 (mass): Mass must be non-negative");
+        assert!(matches!(error("1 0 0 0 inf 0 0\n"), ImportError::Ascii { column: Some(5), .. }));
+    }
+
+    #[test]
+    fn test_unit_scaling_converts_into_simulation_units() {
+        let au = UnitSystem::new(Meters::from_au(1.0), Kilograms(1.0), Seconds(1.0));
+        let scaling = UnitScaling::new(UnitSystem::si(), au);
+        let table = format!("2 {} 0 0 {} 0 0\n", ASTRONOMICAL_UNIT, 3.0 * ASTRONOMICAL_UNIT);
+        let (mass, position, velocity) = read_ascii(table.as_bytes(), &scaling).unwrap()[0];
+        assert_eq!(mass.value(), 2.0);
+        assert!((position.x() - 1.0).abs() < Real::TOLERANCE);
+        assert!((velocity.dx() - 3.0).abs() < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_binary_rejects_bad_headers_and_truncation() {
+        let scaling = UnitScaling::default();
+        let mut header = vec![0u8; BINARY_HEADER_BYTES];
+        header[8..12].copy_from_slice(&2u32.to_le_bytes());
+        header[12..16].copy_from_slice(&3u32.to_le_bytes());
+        header[20..24].copy_from_slice(&2u32.to_le_bytes());
+
+        // One complete record of two
+        let mut file = header.clone();
+        file.extend((0..9).flat_map(|i| (i as f64).to_le_bytes()));
+        match read_binary(file.as_slice(), &scaling).unwrap_err() {
+            ImportError::Binary { record: Some(1), reason } => assert_eq!(reason, "Truncated record"),
+            other => panic!("Unexpected error {:?}", other),
+        }
+
+        let mut two_dimensional = header.clone();
+        two_dimensional[12..16].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            read_binary(two_dimensional.as_slice(), &scaling),
+            Err(ImportError::Binary { record: None, .. })
+        ));
+        assert!(matches!(
+            read_binary(&header[..10], &scaling),
+            Err(ImportError::Binary { record: None, .. })
+        ));
+    }
+}
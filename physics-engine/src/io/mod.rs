@@ -0,0 +1,133 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Reading and writing N-body initial conditions
+//!
+//! Initial conditions are exchanged as lists of `Body` tuples, one per
+//! particle, so they can be spawned into any storage layout. Two formats are
+//! supported in both directions:
+//!
+//! - **ASCII table**: one body per line with the columns
+//!   `mass x y z vx vy vz`, separated by whitespace or commas. Blank lines
+//!   and `#` comments are ignored, and the first line may be a header naming
+//!   the columns. See `import::read_ascii()`.
+//! - **Binary**: a Tipsy-like layout of a fixed header followed by one
+//!   record per body, in little-endian double precision. See
+//!   `import::read_binary()`.
+//!
+//! Both exporters write values with enough digits to reproduce them exactly,
+//! so a round trip through a file preserves every bit.
+//!
+//! # Units
+//!
+//! Files often use different units from the simulation. A `UnitScaling`
+//! names the unit system of the file and of the simulation, and every body
+//! is converted on the way in and out:
+//!
+//! ```
+//! use physics_engine::io::{import, UnitScaling};
+//! use physics_engine::units::UnitSystem;
+//! use physics_engine::{Real, RealExt};
+//!
+//! // File in SI, simulation in AU / solar masses / years
+//! let scaling = UnitScaling::new(UnitSystem::si(), UnitSystem::astronomical());
+//! let table = "mass x y z vx vy vz\n1.989e30 1.495978707e11 0 0 0 0 0\n";
+//!
+//! let bodies = import::read_ascii(table.as_bytes(), &scaling).unwrap();
+//! let (mass, position, _) = bodies[0];
+//! assert!((mass.value() - 1.0).abs() < Real::TOLERANCE);
+//! assert!((position.x() - 1.0).abs() < Real::TOLERANCE);
+//! ```
+
+pub mod import;
+pub mod export;
+
+use crate::real::Real;
+use crate::ecs::components::{Mass, Position, Velocity};
+use crate::units::UnitSystem;
+
+/// Initial state of one body: its mass, position, and velocity
+pub type Body = (Mass, Position, Velocity);
+
+/// Column names of the ASCII table, in order
+pub const ASCII_COLUMNS: [&str; 7] = ["mass", "x", "y", "z", "vx", "vy", "vz"];
+
+/// Size of the binary header in bytes
+pub(crate) const BINARY_HEADER_BYTES: usize = 32;
+
+/// Size of one binary body record in bytes: nine `f64` fields
+pub(crate) const BINARY_RECORD_BYTES: usize = 72;
+
+/// Conversion between the units of a file and of the simulation
+///
+/// The default uses SI for both, which leaves values untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UnitScaling {
+    file_units: UnitSystem,
+    simulation_units: UnitSystem,
+}
+
+impl UnitScaling {
+    /// Create a scaling from the units values are stored in to the units
+    /// the simulation runs in
+    pub fn new(file_units: UnitSystem, simulation_units: UnitSystem) -> Self {
+        UnitScaling {
+            file_units,
+            simulation_units,
+        }
+    }
+
+    /// Get the unit system of the file
+    pub fn file_units(&self) -> UnitSystem {
+        self.file_units
+    }
+
+    /// Get the unit system of the simulation
+    pub fn simulation_units(&self) -> UnitSystem {
+        self.simulation_units
+    }
+
+    /// Simulation units per file unit of each ASCII column
+    fn factors(&self) -> [Real; 7] {
+        let (file, sim) = (&self.file_units, &self.simulation_units);
+        let m = file.mass_scale() / sim.mass_scale();
+        let l = file.length_scale() / sim.length_scale();
+        let v = file.velocity_scale() / sim.velocity_scale();
+        [m, l, l, l, v, v, v]
+    }
+
+    /// Convert `mass x y z vx vy vz` values from file to simulation units
+    pub(crate) fn scale_to_simulation(&self, values: [Real; 7]) -> [Real; 7] {
+        let factors = self.factors();
+        std::array::from_fn(|i| values[i] * factors[i])
+    }
+
+    /// Convert `mass x y z vx vy vz` values from simulation to file units
+    pub(crate) fn scale_to_file(&self, values: [Real; 7]) -> [Real; 7] {
+        let factors = self.factors();
+        std::array::from_fn(|i| values[i] / factors[i])
+    }
+}
+
+/// Flatten a body into `mass x y z vx vy vz` values
+pub(crate) fn body_values((mass, position, velocity): &Body) -> [Real; 7] {
+    [
+        mass.value(),
+        position.x(),
+        position.y(),
+        position.z(),
+        velocity.dx(),
+        velocity.dy(),
+        velocity.dz(),
+    ]
+}
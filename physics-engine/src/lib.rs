@@ -39,6 +39,14 @@
 //! ```
 
 #![warn(missing_docs)]
+// Literals are written for f64 and round in single-precision builds
+#![cfg_attr(feature = "single-precision", allow(clippy::excessive_precision))]
+
+/// Crate-wide scalar type and precision-dependent constants
+pub mod real;
+
+/// Numerical tolerances for validity and conservation checks
+pub mod math;
 
 /// Entity Component System implementation
 pub mod ecs;
@@ -56,4 +64,41 @@ pub mod simd;
 /// Memory pooling for reducing allocation churn
 pub mod pool;
 
+/// Physical units and simulation unit systems
+pub mod units;
+
+/// Analytic two-body orbital mechanics
+pub mod orbits;
+
+/// Energy and equilibrium diagnostics for gravitating systems
+pub mod diagnostics;
+
+/// Sharing simulation state across threads
+pub mod sync;
+
+/// Errors reported while running a simulation
+pub mod error;
+
+/// Simulation driver and input recording
+pub mod simulation;
+
+/// Preset initial conditions for tests, benchmarks and examples
+pub mod scenarios;
+
+/// Neighborhood queries over entity positions
+pub mod spatial;
+
+/// Loading and saving initial conditions
+#[cfg(feature = "io")]
+pub mod io;
+
+/// General-purpose helpers such as streaming statistics
+pub mod util;
+
+/// Headless simulation runs for benchmarking
+#[cfg(feature = "bench")]
+pub mod bench;
+
 pub use ecs::{World, Entity};
+pub use real::{Real, RealExt};
+pub use error::PhysicsError;
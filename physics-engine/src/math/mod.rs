@@ -0,0 +1,192 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Shared numerical tolerances
+//!
+//! Validity and conservation checks compare floating-point results against
+//! a tolerance. `Tolerances` collects those thresholds in one place, so the
+//! precision a check expects is explicit and can be tuned per call instead
+//! of being a literal buried in the check:
+//!
+//! ```
+//! use physics_engine::math::Tolerances;
+//! use physics_engine::ecs::components::Position;
+//!
+//! // The default accepts any finite position
+//! let far = Position::new(1e12, 0.0, 0.0);
+//! assert!(far.is_valid_with(&Tolerances::default()));
+//!
+//! // A bounded scene can reject runaway bodies as well as NaN
+//! let scene = Tolerances::default().with_max_coordinate(1e6);
+//! assert!(!far.is_valid_with(&scene));
+//!
+//! // Conservation checks compare relative energy drift
+//! assert!(scene.energy_conserved(100.0, 100.0 + 1e-9));
+//! ```
+//!
+//! The defaults are scaled from `Real::TOLERANCE`, so they hold in
+//! `single-precision` builds as well.
+
+use crate::real::{Real, RealExt};
+use crate::ecs::components::Position;
+
+/// Thresholds used by validation and conservation checks
+///
+/// Build one from `Tolerances::default()` and adjust the fields that matter
+/// with the `with_*` methods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct Tolerances {
+    /// Largest coordinate magnitude a valid position may have
+    ///
+    /// Defaults to `Real::MAX`, so only non-finite positions are invalid.
+    pub max_coordinate: Real,
+    /// Allowed deviation of a unit quantity's norm from 1
+    ///
+    /// Applies to quaternions, directions, and other values that must be
+    /// normalized. Defaults to `Real::TOLERANCE`.
+    pub unit_norm: Real,
+    /// Allowed relative energy drift, |E − E₀| / |E₀|
+    ///
+    /// Defaults to `Real::TOLERANCE`, which suits runs that conserve energy
+    /// exactly up to rounding. Loosen it for integrators with bounded error.
+    pub energy_drift: Real,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Tolerances {
+            max_coordinate: Real::MAX,
+            unit_norm: Real::TOLERANCE,
+            energy_drift: Real::TOLERANCE,
+        }
+    }
+}
+
+impl Tolerances {
+    /// Set the largest valid coordinate magnitude
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_coordinate` is negative or NaN.
+    pub fn with_max_coordinate(mut self, max_coordinate: Real) -> Self {
+        assert!(max_coordinate >= 0.0, "Maximum coordinate must be non-negative");
+        self.max_coordinate = max_coordinate;
+        self
+    }
+
+    /// Set the allowed deviation of a unit norm from 1
+    ///
+    /// # Panics
+    ///
+    /// Panics if `unit_norm` is negative or not finite.
+    pub fn with_unit_norm(mut self, unit_norm: Real) -> Self {
+        assert!(unit_norm >= 0.0 && unit_norm.is_finite(), "Unit norm tolerance must be non-negative and finite");
+        self.unit_norm = unit_norm;
+        self
+    }
+
+    /// Set the allowed relative energy drift
+    ///
+    /// # Panics
+    ///
+    /// Panics if `energy_drift` is negative or not finite.
+    pub fn with_energy_drift(mut self, energy_drift: Real) -> Self {
+        assert!(
+            energy_drift >= 0.0 && energy_drift.is_finite(),
+            "Energy drift tolerance must be non-negative and finite"
+        );
+        self.energy_drift = energy_drift;
+        self
+    }
+
+    /// Check that a position is finite and within `max_coordinate`
+    pub fn position_is_valid(&self, position: &Position) -> bool {
+        position
+            .as_array()
+            .iter()
+            .all(|c| c.is_finite() && c.abs() <= self.max_coordinate)
+    }
+
+    /// Check that a vector, such as a quaternion's `[w, x, y, z]`, has unit norm
+    ///
+    /// Non-finite components always fail.
+    pub fn is_unit_norm(&self, components: &[Real]) -> bool {
+        let norm = components.iter().map(|c| c * c).sum::<Real>().sqrt();
+        (norm - 1.0).abs() <= self.unit_norm
+    }
+
+    /// Check that energy stayed within `energy_drift` of its initial value
+    ///
+    /// The drift is relative to `|initial|`, or absolute when the initial
+    /// energy is zero. Non-finite energies always fail.
+    pub fn energy_conserved(&self, initial: Real, current: Real) -> bool {
+        let change = (current - initial).abs();
+        let drift = if initial == 0.0 { change } else { change / initial.abs() };
+        drift <= self.energy_drift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quaternion_near_unit_norm_depends_on_tolerance() {
+        // A unit quaternion that has drifted to norm 1 + 5e-4
+        let quaternion = [0.0, 0.6, 0.0, 0.8].map(|c: Real| c * 1.0005);
+        let loose = Tolerances::default().with_unit_norm(1e-3);
+        let strict = Tolerances::default().with_unit_norm(1e-4);
+        assert!(loose.is_unit_norm(&quaternion));
+        assert!(!strict.is_unit_norm(&quaternion));
+
+        assert!(Tolerances::default().is_unit_norm(&[0.0, 0.6, 0.0, 0.8]));
+        assert!(!loose.is_unit_norm(&[Real::NAN, 0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn test_default_position_validity_matches_is_valid() {
+        let tolerances = Tolerances::default();
+        let extreme = Position::new(Real::MAX, -Real::MAX, 0.0);
+        assert_eq!(tolerances.position_is_valid(&extreme), extreme.is_valid());
+
+        let bounded = tolerances.with_max_coordinate(10.0);
+        assert!(bounded.position_is_valid(&Position::new(-10.0, 10.0, 0.0)));
+        assert!(!bounded.position_is_valid(&Position::new(0.0, 0.0, 10.5)));
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
+    fn test_non_finite_position_validity_matches_is_valid() {
+        let tolerances = Tolerances::default();
+        for position in [Position::new(Real::INFINITY, 0.0, 0.0), Position::new(0.0, Real::NAN, 0.0)] {
+            assert_eq!(tolerances.position_is_valid(&position), position.is_valid());
+        }
+    }
+
+    #[test]
+    fn test_energy_drift_is_relative_unless_initial_is_zero() {
+        let tolerances = Tolerances::default().with_energy_drift(1e-3);
+        assert!(tolerances.energy_conserved(-1000.0, -1000.9));
+        assert!(!tolerances.energy_conserved(-1000.0, -1001.1));
+        assert!(tolerances.energy_conserved(0.0, 5e-4));
+        assert!(!tolerances.energy_conserved(0.0, 2e-3));
+        assert!(!tolerances.energy_conserved(1.0, Real::NAN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unit norm tolerance must be non-negative and finite")]
+    fn test_negative_unit_norm_tolerance_panics() {
+        Tolerances::default().with_unit_norm(-1.0);
+    }
+}
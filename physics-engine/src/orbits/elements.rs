@@ -0,0 +1,371 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Osculating Keplerian elements
+//!
+//! Converts a relative state vector into the conic the body would follow
+//! if every other force vanished at that instant, and back. Tracking the
+//! elements over a run shows secular drift (a slowly growing eccentricity,
+//! a precessing periapsis) that is hard to see in raw positions.
+//!
+//! # Degenerate Orbits
+//!
+//! The argument of periapsis is undefined for circular orbits and the
+//! ascending node for equatorial ones. Below `CIRCULAR_THRESHOLD` and
+//! `EQUATORIAL_THRESHOLD` the usual conventions apply:
+//! - circular: periapsis is placed at the ascending node (ω = 0), so the
+//!   true anomaly is the argument of latitude
+//! - equatorial: the node is placed on the x axis (Ω = 0), so ω is the
+//!   longitude of periapsis
+//! - both: the true anomaly is the true longitude measured from the x axis
+
+use crate::real::Real;
+use crate::real::consts::TAU;
+use crate::ecs::{ComponentStorage, Entity};
+use crate::ecs::components::{Mass, Position, Velocity};
+use super::{cross, dot};
+
+/// Eccentricity below which an orbit is treated as circular
+pub const CIRCULAR_THRESHOLD: Real = 64.0 * Real::EPSILON;
+
+/// Sine of the inclination below which an orbit is treated as equatorial
+pub const EQUATORIAL_THRESHOLD: Real = 64.0 * Real::EPSILON;
+
+/// Keplerian elements of a two-body orbit
+///
+/// Angles are in radians, with `i` in [0, π] and the others in [0, 2π).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    /// Semi-major axis in meters: negative for hyperbolic orbits and
+    /// infinite for parabolic ones
+    pub a: Real,
+    /// Eccentricity
+    pub e: Real,
+    /// Inclination to the xy plane
+    pub i: Real,
+    /// Right ascension (longitude) of the ascending node, measured from the x axis
+    pub raan: Real,
+    /// Argument of periapsis, measured from the ascending node
+    pub argp: Real,
+    /// True anomaly, measured from periapsis
+    pub true_anomaly: Real,
+}
+
+impl OrbitalElements {
+    /// Position and velocity relative to the central body
+    ///
+    /// The inverse of `osculating_elements()` for elliptic and hyperbolic
+    /// orbits. Parabolic elements (infinite `a`) have no finite semi-latus
+    /// rectum in this form and give NaN.
+    pub fn to_state(&self, central_mu: Real) -> (Position, Velocity) {
+        let p = self.a * (1.0 - self.e * self.e);
+        let (sin_nu, cos_nu) = self.true_anomaly.sin_cos();
+        let r = p / (1.0 + self.e * cos_nu);
+        let speed_scale = (central_mu / p).sqrt();
+
+        // Perifocal basis: P toward periapsis, Q 90° ahead in the orbit
+        let (sin_raan, cos_raan) = self.raan.sin_cos();
+        let (sin_argp, cos_argp) = self.argp.sin_cos();
+        let (sin_i, cos_i) = self.i.sin_cos();
+        let p_hat = [
+            cos_raan * cos_argp - sin_raan * sin_argp * cos_i,
+            sin_raan * cos_argp + cos_raan * sin_argp * cos_i,
+            sin_argp * sin_i,
+        ];
+        let q_hat = [
+            -cos_raan * sin_argp - sin_raan * cos_argp * cos_i,
+            -sin_raan * sin_argp + cos_raan * cos_argp * cos_i,
+            cos_argp * sin_i,
+        ];
+
+        let (rp, rq) = (r * cos_nu, r * sin_nu);
+        let (vp, vq) = (-speed_scale * sin_nu, speed_scale * (self.e + cos_nu));
+        let position: [Real; 3] = std::array::from_fn(|k| rp * p_hat[k] + rq * q_hat[k]);
+        let velocity: [Real; 3] = std::array::from_fn(|k| vp * p_hat[k] + vq * q_hat[k]);
+        (Position::from_array(position), Velocity::from_array(velocity))
+    }
+}
+
+/// Osculating elements of a body relative to the central body
+///
+/// `position` and `velocity` are relative to the central body, and
+/// `central_mu` is the standard gravitational parameter μ = G·(M + m).
+/// Circular and equatorial orbits follow the conventions in the module
+/// documentation. Radial trajectories have no orbital plane and give NaN
+/// angles.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::{Real, RealExt};
+/// use physics_engine::ecs::components::{Position, Velocity};
+/// use physics_engine::orbits::osculating_elements;
+///
+/// // Launched at 1.1 × circular speed, the body is at periapsis
+/// let elements = osculating_elements(&Position::new(1.0, 0.0, 0.0), &Velocity::new(0.0, 1.1, 0.0), 1.0);
+/// assert!((elements.e - 0.21).abs() < Real::TOLERANCE);
+/// assert!((elements.a - 1.0 / 0.79).abs() < Real::TOLERANCE);
+/// assert_eq!(elements.true_anomaly, 0.0);
+/// ```
+pub fn osculating_elements(position: &Position, velocity: &Velocity, central_mu: Real) -> OrbitalElements {
+    let r_vec = position.as_array();
+    let v_vec = velocity.as_array();
+    let r = norm(&r_vec);
+    let v_squared = dot(&v_vec, &v_vec);
+    let radial_speed = dot(&r_vec, &v_vec);
+
+    let h_vec = cross(&r_vec, &v_vec);
+    let h = norm(&h_vec);
+    let h_hat = h_vec.map(|c| c / h);
+
+    // e = ((v² − μ/r)·r − (r·v)·v) / μ
+    let e_vec: [Real; 3] =
+        std::array::from_fn(|k| ((v_squared - central_mu / r) * r_vec[k] - radial_speed * v_vec[k]) / central_mu);
+    let e = norm(&e_vec);
+
+    let energy = 0.5 * v_squared - central_mu / r;
+    let a = if energy == 0.0 { Real::INFINITY } else { -central_mu / (2.0 * energy) };
+    let i = (h_vec[0] * h_vec[0] + h_vec[1] * h_vec[1]).sqrt().atan2(h_vec[2]);
+
+    // Ascending node direction ẑ × h, replaced by the x axis for equatorial orbits
+    let node_vec = [-h_vec[1], h_vec[0], 0.0];
+    let node_length = norm(&node_vec);
+    let (node, raan) = if node_length > EQUATORIAL_THRESHOLD * h {
+        (node_vec.map(|c| c / node_length), wrap_angle(node_vec[1].atan2(node_vec[0])))
+    } else {
+        ([1.0, 0.0, 0.0], 0.0)
+    };
+    let periapsis = if e > CIRCULAR_THRESHOLD { e_vec.map(|c| c / e) } else { node };
+
+    OrbitalElements {
+        a,
+        e,
+        i,
+        raan,
+        argp: angle_in_plane(&node, &periapsis, &h_hat),
+        true_anomaly: angle_in_plane(&periapsis, &r_vec, &h_hat),
+    }
+}
+
+/// Osculating elements of every body relative to the dominant mass
+///
+/// The most massive body is taken as the central body, and every other
+/// body's elements use its state relative to that body with
+/// μ = G·(M + m). Suited to planetary systems, where each orbit is close
+/// to a Kepler orbit about the star.
+#[derive(Debug, Clone)]
+pub struct SystemOrbitReport {
+    central: Entity,
+    orbits: Vec<(Entity, OrbitalElements)>,
+}
+
+impl SystemOrbitReport {
+    /// Compute the elements of `entities` relative to the most massive of them
+    ///
+    /// Entities missing a position or velocity, or without a positive mass
+    /// (including immovable bodies), are skipped. Of equally massive
+    /// candidates the first is central.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no entity has all three components and a positive mass
+    pub fn compute(
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        velocities: &impl ComponentStorage<Component = Velocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        g: Real,
+    ) -> Option<Self> {
+        let state = |entity: Entity| match (positions.get(entity), velocities.get(entity), masses.get(entity)) {
+            (Some(p), Some(v), Some(m)) if m.value() > 0.0 => Some((p.as_array(), v.as_array(), m.value())),
+            _ => None,
+        };
+
+        let mut central: Option<(Entity, Real)> = None;
+        for &entity in entities {
+            if let Some((_, _, mass)) = state(entity) {
+                if central.map_or(true, |(_, best)| mass > best) {
+                    central = Some((entity, mass));
+                }
+            }
+        }
+        let (central, central_mass) = central?;
+        let (central_position, central_velocity, _) = state(central)?;
+
+        let orbits = entities
+            .iter()
+            .filter(|&&entity| entity != central)
+            .filter_map(|&entity| {
+                let (p, v, m) = state(entity)?;
+                let relative_position = Position::from_array(std::array::from_fn(|k| p[k] - central_position[k]));
+                let relative_velocity = Velocity::from_array(std::array::from_fn(|k| v[k] - central_velocity[k]));
+                let mu = g * (central_mass + m);
+                Some((entity, osculating_elements(&relative_position, &relative_velocity, mu)))
+            })
+            .collect();
+
+        Some(SystemOrbitReport { central, orbits })
+    }
+
+    /// Get the central body
+    pub fn central(&self) -> Entity {
+        self.central
+    }
+
+    /// Get each orbiting body's elements, in the order the entities were given
+    pub fn orbits(&self) -> &[(Entity, OrbitalElements)] {
+        &self.orbits
+    }
+
+    /// Get one body's elements
+    pub fn elements(&self, entity: Entity) -> Option<&OrbitalElements> {
+        self.orbits.iter().find(|(e, _)| *e == entity).map(|(_, elements)| elements)
+    }
+}
+
+fn norm(a: &[Real; 3]) -> Real {
+    dot(a, a).sqrt()
+}
+
+/// Angle from `from` to `to` about `normal`, in [0, 2π)
+fn angle_in_plane(from: &[Real; 3], to: &[Real; 3], normal: &[Real; 3]) -> Real {
+    wrap_angle(dot(normal, &cross(from, to)).atan2(dot(from, to)))
+}
+
+/// Map an angle in (−π, π] onto [0, 2π)
+fn wrap_angle(angle: Real) -> Real {
+    let wrapped = if angle < 0.0 { angle + TAU } else { angle };
+    // A tiny negative angle rounds up to exactly 2π
+    if wrapped >= TAU {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::HashMapStorage;
+    use crate::real::RealExt;
+
+    /// Difference between two angles, accounting for wrap-around
+    fn angle_error(a: Real, b: Real) -> Real {
+        let d = (a - b).abs() % TAU;
+        d.min(TAU - d)
+    }
+
+    #[test]
+    fn test_circular_orbit_elements() {
+        let mu: Real = 3.0;
+        let r = 2.0;
+        let v = (mu / r).sqrt();
+
+        let equatorial = osculating_elements(&Position::new(0.0, r, 0.0), &Velocity::new(-v, 0.0, 0.0), mu);
+        assert!(equatorial.e < 1e-10 * Real::EPSILON_RATIO, "Eccentricity {}", equatorial.e);
+        assert!((equatorial.a - r).abs() < Real::TOLERANCE);
+        assert_eq!((equatorial.i, equatorial.raan, equatorial.argp), (0.0, 0.0, 0.0));
+        // True longitude of a body on the y axis
+        assert!(angle_error(equatorial.true_anomaly, TAU / 4.0) < Real::TOLERANCE);
+
+        // Tilted 60° about the x axis, a quarter orbit past the ascending node
+        let (sin_i, cos_i) = (TAU / 6.0).sin_cos();
+        let inclined = osculating_elements(
+            &Position::new(0.0, r * cos_i, r * sin_i),
+            &Velocity::new(-v, 0.0, 0.0),
+            mu,
+        );
+        assert!(inclined.e < 1e-10 * Real::EPSILON_RATIO, "Eccentricity {}", inclined.e);
+        assert!((inclined.a - r).abs() < Real::TOLERANCE);
+        assert!(angle_error(inclined.i, TAU / 6.0) < Real::TOLERANCE);
+        assert!(angle_error(inclined.raan, 0.0) < Real::TOLERANCE);
+        assert_eq!(inclined.argp, 0.0);
+        assert!(angle_error(inclined.true_anomaly, TAU / 4.0) < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_elliptic_elements_round_trip_through_state() {
+        let tolerance = 1e-9 * Real::EPSILON_RATIO;
+        let mu = 1.5;
+        for elements in [
+            OrbitalElements { a: 2.0, e: 0.3, i: 0.5, raan: 1.2, argp: 2.5, true_anomaly: 4.0 },
+            OrbitalElements { a: 7.5, e: 0.9, i: 2.8, raan: 5.9, argp: 0.1, true_anomaly: 0.7 },
+            OrbitalElements { a: -3.0, e: 1.4, i: 1.0, raan: 3.0, argp: 4.4, true_anomaly: 5.8 },
+        ] {
+            let (position, velocity) = elements.to_state(mu);
+            let back = osculating_elements(&position, &velocity, mu);
+
+            assert!((back.a - elements.a).abs() < tolerance * elements.a.abs(), "{:?} != {:?}", back, elements);
+            assert!((back.e - elements.e).abs() < tolerance, "{:?} != {:?}", back, elements);
+            for (got, want) in [
+                (back.i, elements.i),
+                (back.raan, elements.raan),
+                (back.argp, elements.argp),
+                (back.true_anomaly, elements.true_anomaly),
+            ] {
+                assert!(angle_error(got, want) < tolerance, "{:?} != {:?}", back, elements);
+            }
+        }
+    }
+
+    #[test]
+    fn test_equatorial_ellipse_uses_longitude_of_periapsis() {
+        // Periapsis at 45°, body 90° past it; prograde, then retrograde
+        let elements = OrbitalElements { a: 1.0, e: 0.5, i: 0.0, raan: 0.0, argp: TAU / 8.0, true_anomaly: TAU / 4.0 };
+        let (position, velocity) = elements.to_state(1.0);
+        let prograde = osculating_elements(&position, &velocity, 1.0);
+        assert_eq!((prograde.i, prograde.raan), (0.0, 0.0));
+        assert!(angle_error(prograde.argp, TAU / 8.0) < Real::TOLERANCE);
+        assert!(angle_error(prograde.true_anomaly, TAU / 4.0) < Real::TOLERANCE);
+
+        let retrograde = OrbitalElements { i: TAU / 2.0, ..elements };
+        let (position, velocity) = retrograde.to_state(1.0);
+        let back = osculating_elements(&position, &velocity, 1.0);
+        assert!(angle_error(back.i, TAU / 2.0) < Real::TOLERANCE);
+        assert_eq!(back.raan, 0.0);
+        assert!((back.e - 0.5).abs() < Real::TOLERANCE);
+        let (periapsis, _) = OrbitalElements { true_anomaly: 0.0, ..back }.to_state(1.0);
+        let (expected, _) = OrbitalElements { true_anomaly: 0.0, ..retrograde }.to_state(1.0);
+        assert!((periapsis.x() - expected.x()).abs() < Real::TOLERANCE);
+        assert!((periapsis.y() - expected.y()).abs() < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_report_is_relative_to_dominant_mass() {
+        let mut positions = HashMapStorage::new();
+        let mut velocities = HashMapStorage::new();
+        let mut masses = HashMapStorage::new();
+        let planet = Entity::new(0, 0);
+        let star = Entity::new(1, 0);
+        let untracked = Entity::new(2, 0);
+
+        // The star drifts; the planet circles it at r = 1 with μ = G·(M + m) = 1
+        positions.insert(star, Position::new(10.0, 0.0, 0.0));
+        velocities.insert(star, Velocity::new(5.0, 5.0, 0.0));
+        masses.insert(star, Mass::new(3.0));
+        positions.insert(planet, Position::new(11.0, 0.0, 0.0));
+        velocities.insert(planet, Velocity::new(5.0, 6.0, 0.0));
+        masses.insert(planet, Mass::new(1.0));
+        positions.insert(untracked, Position::zero());
+
+        let report = SystemOrbitReport::compute(&[planet, star, untracked], &positions, &velocities, &masses, 0.25)
+            .unwrap();
+        assert_eq!(report.central(), star);
+        assert_eq!(report.orbits().len(), 1);
+        let elements = report.elements(planet).unwrap();
+        assert!(elements.e < 1e-10 * Real::EPSILON_RATIO, "Eccentricity {}", elements.e);
+        assert!((elements.a - 1.0).abs() < Real::TOLERANCE);
+
+        assert!(SystemOrbitReport::compute(&[untracked], &positions, &velocities, &masses, 0.25).is_none());
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Analytic two-body orbital mechanics
+//!
+//! Exact solutions of the Kepler problem, used as reference solutions when
+//! validating integrators and for advancing a body around a dominant
+//! central mass without numerical integration.
+//!
+//! All functions work with the state of a body *relative to* the central
+//! body and the standard gravitational parameter μ = G·(M + m).
+
+mod elements;
+mod propagate;
+
+use crate::real::Real;
+pub use elements::{
+    osculating_elements, OrbitalElements, SystemOrbitReport, CIRCULAR_THRESHOLD, EQUATORIAL_THRESHOLD,
+};
+pub use propagate::{
+    propagate_kepler, try_propagate_kepler, KEPLER_MAX_ITERATIONS, KEPLER_TOLERANCE,
+};
+
+use crate::ecs::components::{Position, Velocity};
+
+/// Specific orbital energy ε = v²/2 − μ/r
+///
+/// Negative for bound (elliptic) orbits, zero for parabolic and positive
+/// for hyperbolic trajectories.
+pub fn specific_energy(position: &Position, velocity: &Velocity, central_mu: Real) -> Real {
+    let r = dot(&position.as_array(), &position.as_array()).sqrt();
+    let v_squared = dot(&velocity.as_array(), &velocity.as_array());
+    0.5 * v_squared - central_mu / r
+}
+
+/// Specific angular momentum vector h = r × v
+pub fn specific_angular_momentum(position: &Position, velocity: &Velocity) -> [Real; 3] {
+    cross(&position.as_array(), &velocity.as_array())
+}
+
+pub(crate) fn dot(a: &[Real; 3], b: &[Real; 3]) -> Real {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: &[Real; 3], b: &[Real; 3]) -> [Real; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
@@ -0,0 +1,354 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Universal-variable Kepler propagation
+//!
+//! Advances a two-body state by solving the universal Kepler equation for
+//! the universal anomaly χ:
+//!
+//! **√μ·Δt = (r₀·v₀/√μ)·χ²·C(αχ²) + (1 − αr₀)·χ³·S(αχ²) + r₀·χ**
+//!
+//! Where α = 2/r₀ − v₀²/μ is the reciprocal semi-major axis and C, S are
+//! the Stumpff functions. The same equation covers elliptic (α > 0),
+//! parabolic (α = 0) and hyperbolic (α < 0) orbits, so no case analysis is
+//! needed beyond the initial guess.
+//!
+//! # Convergence
+//!
+//! The equation is solved with the Laguerre-Conway iteration, which
+//! converges from poor initial guesses where plain Newton iteration can
+//! overshoot (notably for hyperbolic flybys and near-parabolic orbits).
+//! Iteration stops when the update satisfies
+//! |Δχ| ≤ `KEPLER_TOLERANCE`·max(|χ|, 1), or when the residual of the
+//! equation is down at floating-point rounding level, and fails after
+//! `KEPLER_MAX_ITERATIONS` iterations.
+//!
+//! Strongly hyperbolic states far from periapsis involve large, mostly
+//! cancelling terms, so their attainable accuracy is lower (around 1e-9
+//! relative) than for bound orbits (around 1e-12).
+//!
+//! For elliptic orbits Δt is first reduced modulo the orbital period, so
+//! propagating across many revolutions stays as accurate as propagating
+//! across one.
+
+use crate::real::{Real, RealExt};
+use crate::ecs::components::{Position, Velocity};
+use super::dot;
+use crate::real::consts::PI;
+
+/// Relative convergence tolerance on the universal anomaly
+pub const KEPLER_TOLERANCE: Real = Real::SOLVER_TOLERANCE;
+
+/// Maximum Laguerre iterations before propagation fails
+pub const KEPLER_MAX_ITERATIONS: usize = 50;
+
+/// Residual, relative to the magnitude of the equation's terms, treated as rounding noise
+const RESIDUAL_FLOOR: Real = 16.0 * Real::EPSILON;
+
+/// Order parameter of the Laguerre-Conway iteration
+const LAGUERRE_ORDER: Real = 5.0;
+
+/// |α|·r₀ below which an orbit is treated as parabolic for the initial guess
+const PARABOLIC_THRESHOLD: Real = 1e-12 * Real::EPSILON_RATIO;
+
+/// Propagate a two-body state by `dt` seconds
+///
+/// `position` and `velocity` are relative to the central body, and
+/// `central_mu` is the standard gravitational parameter μ = G·(M + m).
+/// Negative `dt` propagates backwards in time.
+///
+/// # Panics
+///
+/// Panics if the inputs are invalid or the iteration fails to converge.
+/// Use `try_propagate_kepler()` to handle these cases as errors.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::{Real, RealExt};
+/// use physics_engine::ecs::components::{Position, Velocity};
+/// use physics_engine::orbits::propagate_kepler;
+/// use physics_engine::real::consts::FRAC_PI_2;
+///
+/// // Circular orbit with μ = 1, r = 1: period is 2π
+/// let r0 = Position::new(1.0, 0.0, 0.0);
+/// let v0 = Velocity::new(0.0, 1.0, 0.0);
+/// let (r, v) = propagate_kepler(r0, v0, 1.0, FRAC_PI_2);
+///
+/// let tolerance = 1e-12 * Real::EPSILON_RATIO;
+/// assert!(r.x().abs() < tolerance && (r.y() - 1.0).abs() < tolerance);
+/// assert!((v.dx() + 1.0).abs() < tolerance);
+/// ```
+pub fn propagate_kepler(
+    position: Position,
+    velocity: Velocity,
+    central_mu: Real,
+    dt: Real,
+) -> (Position, Velocity) {
+    try_propagate_kepler(position, velocity, central_mu, dt)
+        .unwrap_or_else(|e| panic!("Kepler propagation failed: {}", e))
+}
+
+/// Propagate a two-body state by `dt` seconds, reporting failures
+///
+/// See `propagate_kepler()` for details.
+///
+/// # Errors
+///
+/// Returns an error if μ is not positive, the state or `dt` is not finite,
+/// the body is at the central body's position, or the iteration does not
+/// converge within `KEPLER_MAX_ITERATIONS`.
+pub fn try_propagate_kepler(
+    position: Position,
+    velocity: Velocity,
+    central_mu: Real,
+    dt: Real,
+) -> Result<(Position, Velocity), String> {
+    if !(central_mu > 0.0 && central_mu.is_finite()) {
+        return Err(format!("Gravitational parameter must be positive and finite, got {}", central_mu));
+    }
+    if !position.is_valid() || !velocity.is_valid() || !dt.is_finite() {
+        return Err("State and timestep must be finite".to_string());
+    }
+
+    let r0_vec = position.as_array();
+    let v0_vec = velocity.as_array();
+    let r0 = dot(&r0_vec, &r0_vec).sqrt();
+    if r0 == 0.0 {
+        return Err("Position coincides with the central body".to_string());
+    }
+    if dt == 0.0 {
+        return Ok((position, velocity));
+    }
+
+    let sqrt_mu = central_mu.sqrt();
+    let v0_squared = dot(&v0_vec, &v0_vec);
+    let rv0 = dot(&r0_vec, &v0_vec);
+    let sigma0 = rv0 / sqrt_mu;
+    let alpha = 2.0 / r0 - v0_squared / central_mu;
+
+    // Whole revolutions of a bound orbit do not change the state
+    let dt = if alpha * r0 > PARABOLIC_THRESHOLD {
+        let period = 2.0 * PI / (central_mu * alpha.powi(3)).sqrt();
+        dt % period
+    } else {
+        dt
+    };
+
+    let chi = solve_universal_anomaly(r0, sigma0, alpha, sqrt_mu, dt)?;
+
+    let z = alpha * chi * chi;
+    let (c, s) = stumpff(z);
+    let chi_squared = chi * chi;
+
+    // Lagrange coefficients
+    let f = 1.0 - chi_squared / r0 * c;
+    let g = dt - chi_squared * chi / sqrt_mu * s;
+    let r_vec = [0, 1, 2].map(|i| f * r0_vec[i] + g * v0_vec[i]);
+    let r = dot(&r_vec, &r_vec).sqrt();
+    let f_dot = sqrt_mu / (r * r0) * chi * (z * s - 1.0);
+    let g_dot = 1.0 - chi_squared / r * c;
+    let v_vec = [0, 1, 2].map(|i| f_dot * r0_vec[i] + g_dot * v0_vec[i]);
+
+    Ok((
+        Position::new(r_vec[0], r_vec[1], r_vec[2]),
+        Velocity::new(v_vec[0], v_vec[1], v_vec[2]),
+    ))
+}
+
+/// Solve the universal Kepler equation for χ with Laguerre-Conway iteration
+fn solve_universal_anomaly(r0: Real, sigma0: Real, alpha: Real, sqrt_mu: Real, dt: Real) -> Result<Real, String> {
+    let mut chi = initial_guess(r0, sigma0, alpha, sqrt_mu, dt);
+    let n = LAGUERRE_ORDER;
+
+    for _ in 0..KEPLER_MAX_ITERATIONS {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let chi_squared = chi * chi;
+
+        // F(χ), F'(χ) = r and F''(χ)
+        let terms = [
+            sigma0 * chi_squared * c,
+            (1.0 - alpha * r0) * chi_squared * chi * s,
+            r0 * chi,
+            -sqrt_mu * dt,
+        ];
+        let value: Real = terms.iter().sum();
+        // Rounding noise in F grows with the largest terms that cancel
+        let noise_floor = RESIDUAL_FLOOR * terms.iter().map(|t| t.abs()).sum::<Real>();
+        let first = sigma0 * chi * (1.0 - z * s) + (1.0 - alpha * r0) * chi_squared * c + r0;
+        let second = sigma0 * (1.0 - z * c) + (1.0 - alpha * r0) * chi * (1.0 - z * s);
+
+        let discriminant = ((n - 1.0).powi(2) * first * first - n * (n - 1.0) * value * second).abs().sqrt();
+        let denominator = first + first.signum() * discriminant;
+        let delta = if denominator != 0.0 { n * value / denominator } else { value / first };
+        chi -= delta;
+
+        if !chi.is_finite() {
+            return Err("Universal anomaly iteration diverged".to_string());
+        }
+        if delta.abs() <= KEPLER_TOLERANCE * chi.abs().max(1.0) || value.abs() <= noise_floor {
+            return Ok(chi);
+        }
+    }
+
+    Err(format!("Universal anomaly did not converge in {} iterations", KEPLER_MAX_ITERATIONS))
+}
+
+/// Starting value of χ for the iteration
+fn initial_guess(r0: Real, sigma0: Real, alpha: Real, sqrt_mu: Real, dt: Real) -> Real {
+    if alpha * r0 > PARABOLIC_THRESHOLD {
+        // Elliptic: mean motion scaled into χ units
+        return sqrt_mu * dt * alpha;
+    }
+
+    if alpha * r0 < -PARABOLIC_THRESHOLD {
+        // Hyperbolic (Vallado's estimate from the hyperbolic anomaly)
+        let a = 1.0 / alpha;
+        let sign = dt.signum();
+        let numerator = -2.0 * sqrt_mu * sqrt_mu * alpha * dt;
+        let denominator = sigma0 * sqrt_mu + sign * (-sqrt_mu * sqrt_mu * a).sqrt() * (1.0 - r0 * alpha);
+        let guess = sign * (-a).sqrt() * (numerator / denominator).ln();
+        if guess.is_finite() {
+            return guess;
+        }
+    }
+
+    // Parabolic, or hyperbolic estimate unavailable
+    sqrt_mu * dt / r0
+}
+
+/// Stumpff functions C(z) and S(z)
+///
+/// Uses series expansions near z = 0 to avoid cancellation.
+fn stumpff(z: Real) -> (Real, Real) {
+    if z > 1e-3 {
+        let sqrt_z = z.sqrt();
+        ((1.0 - sqrt_z.cos()) / z, (sqrt_z - sqrt_z.sin()) / (z * sqrt_z))
+    } else if z < -1e-3 {
+        let sqrt_z = (-z).sqrt();
+        ((sqrt_z.cosh() - 1.0) / -z, (sqrt_z.sinh() - sqrt_z) / (-z * sqrt_z))
+    } else {
+        let c = 1.0 / 2.0 - z / 24.0 + z * z / 720.0 - z * z * z / 40320.0;
+        let s = 1.0 / 6.0 - z / 120.0 + z * z / 5040.0 - z * z * z / 362880.0;
+        (c, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orbits::{specific_angular_momentum, specific_energy};
+
+    fn assert_states_close(a: (Position, Velocity), b: (Position, Velocity), tolerance: Real) {
+        let scale_r = dot(&a.0.as_array(), &a.0.as_array()).sqrt();
+        let scale_v = dot(&a.1.as_array(), &a.1.as_array()).sqrt();
+        for i in 0..3 {
+            let dr = (a.0.as_array()[i] - b.0.as_array()[i]).abs() / scale_r;
+            let dv = (a.1.as_array()[i] - b.1.as_array()[i]).abs() / scale_v;
+            assert!(dr < tolerance, "position mismatch: {:?} vs {:?}", a.0, b.0);
+            assert!(dv < tolerance, "velocity mismatch: {:?} vs {:?}", a.1, b.1);
+        }
+    }
+
+    fn check_invariants(r0: Position, v0: Velocity, mu: Real, dt: Real) {
+        let (r, v) = propagate_kepler(r0, v0, mu, dt);
+
+        let e0 = specific_energy(&r0, &v0, mu);
+        let e1 = specific_energy(&r, &v, mu);
+        assert!((e1 - e0).abs() <= 1e-10 * Real::EPSILON_RATIO * e0.abs().max(mu / 1.0e3), "energy {} vs {}", e0, e1);
+
+        let h0 = specific_angular_momentum(&r0, &v0);
+        let h1 = specific_angular_momentum(&r, &v);
+        let h_scale = dot(&h0, &h0).sqrt();
+        for i in 0..3 {
+            assert!((h1[i] - h0[i]).abs() < 1e-10 * Real::EPSILON_RATIO * h_scale, "angular momentum {:?} vs {:?}", h0, h1);
+        }
+
+        // Forward then backward recovers the initial state
+        let back = propagate_kepler(r, v, mu, -dt);
+        assert_states_close((r0, v0), back, 1e-10 * Real::EPSILON_RATIO);
+    }
+
+    #[test]
+    fn test_circular_orbit_full_period() {
+        let r0 = Position::new(1.0, 0.0, 0.0);
+        let v0 = Velocity::new(0.0, 1.0, 0.0);
+        let (r, v) = propagate_kepler(r0, v0, 1.0, 2.0 * PI);
+        assert_states_close((r0, v0), (r, v), 1e-12 * Real::EPSILON_RATIO);
+
+        let (r, _) = propagate_kepler(r0, v0, 1.0, PI);
+        assert!((r.x() + 1.0).abs() < 1e-12 * Real::EPSILON_RATIO);
+    }
+
+    #[test]
+    fn test_elliptic_round_trip_and_invariants() {
+        let r0 = Position::new(1.0, 0.2, -0.1);
+        let v0 = Velocity::new(-0.1, 1.2, 0.3);
+        for dt in [0.01, 1.0, 7.5, 123.4, -3.3] {
+            check_invariants(r0, v0, 1.0, dt);
+        }
+    }
+
+    #[test]
+    fn test_parabolic_orbit() {
+        // v = escape speed at r = 2
+        let r0 = Position::new(2.0, 0.0, 0.0);
+        let v0 = Velocity::new(0.0, 1.0, 0.0);
+        assert!(specific_energy(&r0, &v0, 1.0).abs() < 1e-15);
+        for dt in [0.5, 10.0, -4.0] {
+            check_invariants(r0, v0, 1.0, dt);
+        }
+    }
+
+    #[test]
+    fn test_hyperbolic_flyby_converges() {
+        // Incoming from far away, passes periapsis and leaves
+        let r0 = Position::new(-50.0, 1.0, 0.0);
+        let v0 = Velocity::new(2.0, 0.0, 0.0);
+        for dt in [1.0, 25.0, 50.0] {
+            check_invariants(r0, v0, 1.0, dt);
+        }
+
+        // Well after the flyby the body is receding at about v∞
+        let (r, v) = propagate_kepler(r0, v0, 1.0, 500.0);
+        let back = propagate_kepler(r, v, 1.0, -500.0);
+        assert_states_close((r0, v0), back, 1e-8 * Real::EPSILON_RATIO);
+        let v_inf = (2.0 * specific_energy(&r0, &v0, 1.0)).sqrt();
+        assert!(dot(&r.as_array(), &v.as_array()) > 0.0);
+        assert!((dot(&v.as_array(), &v.as_array()).sqrt() - v_inf).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_solar_scale_orbit() {
+        // Earth around the Sun in SI units, one year later
+        let mu = 1.327_124_4e20;
+        let au = 1.495_978_707e11;
+        let r0 = Position::new(au, 0.0, 0.0);
+        let v0 = Velocity::new(0.0, (mu / au).sqrt(), 0.0);
+        let period = 2.0 * PI * (au.powi(3) / mu).sqrt();
+        let (r, v) = propagate_kepler(r0, v0, mu, period * 10.0);
+        assert_states_close((r0, v0), (r, v), 1e-10 * Real::EPSILON_RATIO);
+        check_invariants(r0, v0, mu, 1.0e6);
+    }
+
+    #[test]
+    fn test_invalid_inputs_rejected() {
+        let r0 = Position::new(1.0, 0.0, 0.0);
+        let v0 = Velocity::new(0.0, 1.0, 0.0);
+        assert!(try_propagate_kepler(r0, v0, 0.0, 1.0).is_err());
+        assert!(try_propagate_kepler(r0, v0, 1.0, Real::NAN).is_err());
+        assert!(try_propagate_kepler(Position::zero(), v0, 1.0, 1.0).is_err());
+        assert_eq!(try_propagate_kepler(r0, v0, 1.0, 0.0).unwrap(), (r0, v0));
+    }
+}
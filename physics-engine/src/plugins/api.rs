@@ -24,6 +24,7 @@
 //! - Validate all inputs and handle errors gracefully
 //! - Not create circular dependencies with other plugins
 
+use crate::real::Real;
 use crate::ecs::{Entity, ComponentStorage, World};
 use crate::ecs::components::{Position, Velocity, Mass};
 use std::any::Any;
@@ -55,7 +56,7 @@ pub struct PluginContext<'a> {
     /// Name of the active integrator
     integrator_name: &'a str,
     /// Current simulation timestep
-    timestep: f64,
+    timestep: Real,
     #[cfg(feature = "parallel")]
     /// Handle to the Rayon thread pool (if parallel feature enabled)
     thread_pool: Option<&'a ThreadPool>,
@@ -65,10 +66,11 @@ impl<'a> PluginContext<'a> {
     /// Create a new plugin context
     ///
     /// This is only callable by the engine, not by plugins.
+    #[cfg_attr(not(test), allow(dead_code))]
     pub(crate) fn new(
         world: &'a World,
         integrator_name: &'a str,
-        timestep: f64,
+        timestep: Real,
         #[cfg(feature = "parallel")] thread_pool: Option<&'a ThreadPool>,
     ) -> Self {
         PluginContext {
@@ -91,7 +93,7 @@ impl<'a> PluginContext<'a> {
     }
 
     /// Get the current simulation timestep
-    pub fn timestep(&self) -> f64 {
+    pub fn timestep(&self) -> Real {
         self.timestep
     }
 
@@ -0,0 +1,579 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Barnes-Hut approximate N-body gravity
+//!
+//! Bodies are inserted into an octree whose nodes store their total mass and
+//! center of mass. When computing the force on a body, a distant node is
+//! treated as a single point mass if it appears small enough:
+//!
+//! **s / d < θ**
+//!
+//! Where:
+//! - s is the node's side length
+//! - d is the distance from the body to the node's center of mass
+//! - θ is the opening angle
+//!
+//! This reduces the cost from O(N²) to roughly O(N log N).
+//!
+//! # Tuning
+//!
+//! - **θ (theta)**: Larger values approximate more aggressively (faster, less
+//!   accurate). θ = 0 opens every node, which degrades to exact pairwise
+//!   summation. Values around 0.5 are a common compromise.
+//! - **Leaf capacity**: Maximum bodies per leaf before it is subdivided.
+//!   Larger leaves mean a shallower tree and more direct interactions.
+//!
+//! The gravitational constant, global softening and softening kernel come
+//! from the wrapped `GravityPlugin`. Per-body softening is not applied by the tree.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Position, Mass};
+use crate::ecs::systems::Force;
+use crate::plugins::gravity::GravityPlugin;
+use std::collections::HashMap;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Default opening angle
+pub const DEFAULT_THETA: Real = 0.5;
+
+/// Default maximum number of bodies per leaf
+pub const DEFAULT_LEAF_CAPACITY: usize = 1;
+
+/// Depth limit that stops subdivision of coincident bodies
+const MAX_DEPTH: usize = 32;
+
+/// Body data copied out of component storage for tree traversal
+#[derive(Debug, Clone, Copy)]
+struct Body {
+    entity: Entity,
+    position: [Real; 3],
+    mass: Real,
+    immovable: bool,
+}
+
+/// Octree node stored in an arena
+#[derive(Debug, Clone)]
+struct Node {
+    center: [Real; 3],
+    half_size: Real,
+    mass: Real,
+    center_of_mass: [Real; 3],
+    /// Index of the first of 8 consecutive children, if subdivided
+    children: Option<usize>,
+    /// Body indices held by a leaf
+    bodies: Vec<usize>,
+}
+
+impl Node {
+    fn new(center: [Real; 3], half_size: Real) -> Self {
+        Node {
+            center,
+            half_size,
+            mass: 0.0,
+            center_of_mass: [0.0; 3],
+            children: None,
+            bodies: Vec::new(),
+        }
+    }
+
+    fn contains(&self, p: &[Real; 3]) -> bool {
+        (0..3).all(|i| (p[i] - self.center[i]).abs() <= self.half_size)
+    }
+
+    fn octant(&self, p: &[Real; 3]) -> usize {
+        (0..3).fold(0, |acc, i| acc | (((p[i] >= self.center[i]) as usize) << i))
+    }
+}
+
+/// Octree over a set of bodies
+struct Octree {
+    nodes: Vec<Node>,
+    leaf_capacity: usize,
+}
+
+impl Octree {
+    fn build(bodies: &[Body], leaf_capacity: usize) -> Self {
+        let mut min = [Real::INFINITY; 3];
+        let mut max = [Real::NEG_INFINITY; 3];
+        for body in bodies {
+            for i in 0..3 {
+                min[i] = min[i].min(body.position[i]);
+                max[i] = max[i].max(body.position[i]);
+            }
+        }
+
+        let center = [0, 1, 2].map(|i| 0.5 * (min[i] + max[i]));
+        let half_size = (0..3)
+            .map(|i| 0.5 * (max[i] - min[i]))
+            .fold(0.0, Real::max)
+            .max(Real::MIN_POSITIVE);
+
+        let mut tree = Octree {
+            nodes: vec![Node::new(center, half_size)],
+            leaf_capacity,
+        };
+        for index in 0..bodies.len() {
+            tree.insert(0, index, bodies, 0);
+        }
+        tree.summarize(0, bodies);
+        tree
+    }
+
+    fn insert(&mut self, node: usize, body: usize, bodies: &[Body], depth: usize) {
+        if let Some(first_child) = self.nodes[node].children {
+            let octant = self.nodes[node].octant(&bodies[body].position);
+            self.insert(first_child + octant, body, bodies, depth + 1);
+            return;
+        }
+
+        self.nodes[node].bodies.push(body);
+        if self.nodes[node].bodies.len() > self.leaf_capacity && depth < MAX_DEPTH {
+            self.subdivide(node, bodies, depth);
+        }
+    }
+
+    fn subdivide(&mut self, node: usize, bodies: &[Body], depth: usize) {
+        let first_child = self.nodes.len();
+        let center = self.nodes[node].center;
+        let quarter = 0.5 * self.nodes[node].half_size;
+
+        for octant in 0..8 {
+            let child_center = [0, 1, 2].map(|i| {
+                if octant & (1 << i) != 0 { center[i] + quarter } else { center[i] - quarter }
+            });
+            self.nodes.push(Node::new(child_center, quarter));
+        }
+
+        self.nodes[node].children = Some(first_child);
+        for body in std::mem::take(&mut self.nodes[node].bodies) {
+            let octant = self.nodes[node].octant(&bodies[body].position);
+            self.insert(first_child + octant, body, bodies, depth + 1);
+        }
+    }
+
+    /// Compute mass and center of mass bottom-up
+    fn summarize(&mut self, node: usize, bodies: &[Body]) {
+        let mut mass = 0.0;
+        let mut weighted = [0.0; 3];
+
+        if let Some(first_child) = self.nodes[node].children {
+            for child in first_child..first_child + 8 {
+                self.summarize(child, bodies);
+                let child_node = &self.nodes[child];
+                mass += child_node.mass;
+                for (w, c) in weighted.iter_mut().zip(child_node.center_of_mass.iter()) {
+                    *w += child_node.mass * c;
+                }
+            }
+        } else {
+            for &body in &self.nodes[node].bodies {
+                mass += bodies[body].mass;
+                for (w, p) in weighted.iter_mut().zip(bodies[body].position.iter()) {
+                    *w += bodies[body].mass * p;
+                }
+            }
+        }
+
+        let n = &mut self.nodes[node];
+        n.mass = mass;
+        n.center_of_mass = if mass > 0.0 { weighted.map(|w| w / mass) } else { n.center };
+    }
+}
+
+/// Barnes-Hut gravity system with tunable accuracy
+///
+/// Produces the same output as `GravitySystem::compute_forces_into()` but
+/// approximates distant groups of bodies by their center of mass.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::plugins::barnes_hut::BarnesHutSystem;
+/// use physics_engine::plugins::gravity::{GravityPlugin, GRAVITATIONAL_CONSTANT};
+///
+/// let mut system = BarnesHutSystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+/// system.set_theta(0.7);
+/// system.set_leaf_capacity(4);
+/// assert_eq!(system.theta(), 0.7);
+/// ```
+pub struct BarnesHutSystem {
+    plugin: GravityPlugin,
+    theta: Real,
+    leaf_capacity: usize,
+    interaction_count: usize,
+}
+
+impl BarnesHutSystem {
+    /// Create a Barnes-Hut system using the plugin's G and softening
+    pub fn new(plugin: GravityPlugin) -> Self {
+        BarnesHutSystem {
+            plugin,
+            theta: DEFAULT_THETA,
+            leaf_capacity: DEFAULT_LEAF_CAPACITY,
+            interaction_count: 0,
+        }
+    }
+
+    /// Get the gravity plugin configuration
+    pub fn plugin(&self) -> &GravityPlugin {
+        &self.plugin
+    }
+
+    /// Set the opening angle θ
+    ///
+    /// θ = 0 opens every node and gives exact pairwise forces.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `theta` is negative or not finite.
+    pub fn set_theta(&mut self, theta: Real) {
+        assert!(theta >= 0.0 && theta.is_finite(), "Theta must be non-negative and finite");
+        self.theta = theta;
+    }
+
+    /// Get the opening angle θ
+    pub fn theta(&self) -> Real {
+        self.theta
+    }
+
+    /// Set the maximum number of bodies per leaf
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn set_leaf_capacity(&mut self, capacity: usize) {
+        assert!(capacity >= 1, "Leaf capacity must be at least 1");
+        self.leaf_capacity = capacity;
+    }
+
+    /// Get the maximum number of bodies per leaf
+    pub fn leaf_capacity(&self) -> usize {
+        self.leaf_capacity
+    }
+
+    /// Number of body-body and body-node interactions in the last computation
+    pub fn interaction_count(&self) -> usize {
+        self.interaction_count
+    }
+
+    /// Compute approximate gravitational forces into an output map
+    ///
+    /// `out` is cleared first, then holds the net force for every movable
+    /// entity that experienced gravity. Entities missing a position or mass
+    /// are ignored.
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had gravitational forces computed
+    pub fn compute_forces_into(
+        &mut self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        out: &mut HashMap<Entity, Force>,
+    ) -> usize {
+        out.clear();
+        self.interaction_count = 0;
+
+        let bodies: Vec<Body> = entities
+            .iter()
+            .filter_map(|&entity| {
+                let pos = positions.get(entity)?;
+                let mass = masses.get(entity)?;
+                Some(Body {
+                    entity,
+                    position: pos.as_array(),
+                    mass: mass.value(),
+                    immovable: mass.is_immovable(),
+                })
+            })
+            .collect();
+        if bodies.is_empty() {
+            return 0;
+        }
+
+        let tree = Octree::build(&bodies, self.leaf_capacity);
+        let evaluate = |index: usize| self.force_on_body(index, &bodies, &tree);
+
+        #[cfg(feature = "parallel")]
+        let results: Vec<(usize, Option<Force>, usize)> = (0..bodies.len())
+            .into_par_iter()
+            .map(|i| {
+                let (force, count) = evaluate(i);
+                (i, force, count)
+            })
+            .collect();
+
+        #[cfg(not(feature = "parallel"))]
+        let results: Vec<(usize, Option<Force>, usize)> = (0..bodies.len())
+            .map(|i| {
+                let (force, count) = evaluate(i);
+                (i, force, count)
+            })
+            .collect();
+
+        for (index, force, count) in results {
+            self.interaction_count += count;
+            if let Some(force) = force {
+                out.insert(bodies[index].entity, force);
+            }
+        }
+
+        out.len()
+    }
+
+    /// Traverse the tree for one body, returning its force and interaction count
+    fn force_on_body(&self, index: usize, bodies: &[Body], tree: &Octree) -> (Option<Force>, usize) {
+        let body = &bodies[index];
+        if body.immovable {
+            return (None, 0);
+        }
+
+        let mut total = Force::zero();
+        let mut has_force = false;
+        let mut interactions = 0;
+        let mut stack = vec![0usize];
+
+        while let Some(node_index) = stack.pop() {
+            let node = &tree.nodes[node_index];
+            if node.mass == 0.0 && node.children.is_none() && node.bodies.is_empty() {
+                continue;
+            }
+
+            match node.children {
+                None => {
+                    for &other in &node.bodies {
+                        if other == index {
+                            continue;
+                        }
+                        interactions += 1;
+                        if let Some(f) = self.point_force(body, bodies[other].position, bodies[other].mass) {
+                            total.add(&f);
+                            has_force = true;
+                        }
+                    }
+                }
+                Some(first_child) => {
+                    let d_sq = (0..3)
+                        .map(|i| (node.center_of_mass[i] - body.position[i]).powi(2))
+                        .sum::<Real>();
+                    let size = 2.0 * node.half_size;
+
+                    // Never approximate a node containing the body itself
+                    if self.theta > 0.0
+                        && !node.contains(&body.position)
+                        && size * size < self.theta * self.theta * d_sq
+                    {
+                        interactions += 1;
+                        if let Some(f) = self.point_force(body, node.center_of_mass, node.mass) {
+                            total.add(&f);
+                            has_force = true;
+                        }
+                    } else {
+                        stack.extend(first_child..first_child + 8);
+                    }
+                }
+            }
+        }
+
+        (if has_force { Some(total) } else { None }, interactions)
+    }
+
+    /// Softened force on `body` from a point mass
+    fn point_force(&self, body: &Body, position: [Real; 3], mass: Real) -> Option<Force> {
+        let dx = position[0] - body.position[0];
+        let dy = position[1] - body.position[1];
+        let dz = position[2] - body.position[2];
+        let r_squared = dx * dx + dy * dy + dz * dz;
+        let softening_squared = self.plugin.softening() * self.plugin.softening();
+        if r_squared + softening_squared == 0.0 {
+            return None;
+        }
+
+        let (_, force_scale) = self.plugin.softening_kernel().force_terms(
+            self.plugin.gravitational_constant() * body.mass * mass,
+            r_squared,
+            softening_squared,
+        );
+        let force = Force::new(force_scale * dx, force_scale * dy, force_scale * dz);
+        if force.is_valid() {
+            Some(self.plugin.mask_axes(force))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real::RealExt;
+    use crate::ecs::HashMapStorage;
+    use crate::plugins::gravity::{GravitySystem, GRAVITATIONAL_CONSTANT};
+
+    /// Deterministic cluster of bodies in a 100 m cube
+    fn random_cluster(count: usize, seed: u64) -> (Vec<Entity>, HashMapStorage<Position>, HashMapStorage<Mass>) {
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as Real / (1u64 << 53) as Real
+        };
+
+        let mut entities = Vec::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        for i in 0..count {
+            let entity = Entity::new(i as u64, 0);
+            positions.insert(entity, Position::new(100.0 * next(), 100.0 * next(), 100.0 * next()));
+            masses.insert(entity, Mass::new(1.0 + 9.0 * next()));
+            entities.push(entity);
+        }
+        (entities, positions, masses)
+    }
+
+    fn plugin() -> GravityPlugin {
+        let mut plugin = GravityPlugin::with_scaled_g(1e10);
+        plugin.set_softening(0.5);
+        plugin.set_warn_on_high_forces(false);
+        plugin
+    }
+
+    /// Largest force error relative to the largest exact force magnitude
+    fn max_relative_error(exact: &HashMap<Entity, Force>, approx: &HashMap<Entity, Force>) -> Real {
+        let scale = exact.values().map(|f| f.magnitude()).fold(0.0, Real::max);
+        exact
+            .iter()
+            .map(|(entity, f)| {
+                let g = approx[entity];
+                Force::new(f.fx - g.fx, f.fy - g.fy, f.fz - g.fz).magnitude() / scale
+            })
+            .fold(0.0, Real::max)
+    }
+
+    #[test]
+    fn test_theta_zero_matches_exact_pairwise() {
+        let (entities, positions, masses) = random_cluster(64, 7);
+        let mut exact = HashMap::new();
+        GravitySystem::new(plugin()).compute_forces_into(&entities, &positions, &masses, &mut exact);
+
+        for leaf_capacity in [1, 3, 16] {
+            let mut bh = BarnesHutSystem::new(plugin());
+            bh.set_theta(0.0);
+            bh.set_leaf_capacity(leaf_capacity);
+            let mut approx = HashMap::new();
+            assert_eq!(bh.compute_forces_into(&entities, &positions, &masses, &mut approx), 64);
+
+            // Every pair is visited directly, only summation order differs
+            assert_eq!(bh.interaction_count(), 64 * 63);
+            assert!(max_relative_error(&exact, &approx) < 1e-12 * Real::EPSILON_RATIO);
+        }
+    }
+
+    #[test]
+    fn test_larger_theta_means_fewer_interactions() {
+        let (entities, positions, masses) = random_cluster(300, 42);
+        let mut exact = HashMap::new();
+        GravitySystem::new(plugin()).compute_forces_into(&entities, &positions, &masses, &mut exact);
+
+        let mut previous_count = usize::MAX;
+        let mut errors = Vec::new();
+        for theta in [0.0, 0.3, 0.6, 1.0] {
+            let mut bh = BarnesHutSystem::new(plugin());
+            bh.set_theta(theta);
+            let mut approx = HashMap::new();
+            bh.compute_forces_into(&entities, &positions, &masses, &mut approx);
+
+            assert!(
+                bh.interaction_count() < previous_count,
+                "theta {} did not reduce interactions",
+                theta
+            );
+            previous_count = bh.interaction_count();
+            errors.push(max_relative_error(&exact, &approx));
+        }
+
+        assert!(errors[0] < 1e-12 * Real::EPSILON_RATIO);
+        assert!(errors[1] < errors[3]);
+        assert!(errors[3] < 0.1, "theta = 1 error too large: {}", errors[3]);
+    }
+
+    #[test]
+    fn test_fewer_interactions_than_exact_on_clustered_bodies() {
+        // Two tight clumps far apart
+        let (mut entities, mut positions, mut masses) = random_cluster(100, 3);
+        let (far_entities, far_positions, far_masses) = random_cluster(100, 11);
+        for (i, &entity) in far_entities.iter().enumerate() {
+            let moved = Entity::new(100 + i as u64, 0);
+            let p = far_positions.get(entity).unwrap();
+            positions.insert(moved, Position::new(p.x() + 1e4, p.y(), p.z()));
+            masses.insert(moved, *far_masses.get(entity).unwrap());
+            entities.push(moved);
+        }
+
+        let exact_system = GravitySystem::new(plugin());
+        let mut exact = HashMap::new();
+        exact_system.compute_forces_into(&entities, &positions, &masses, &mut exact);
+        assert_eq!(exact_system.last_interaction_count(), 200 * 199);
+
+        let mut bh = BarnesHutSystem::new(plugin());
+        bh.set_theta(0.5);
+        let mut approx = HashMap::new();
+        bh.compute_forces_into(&entities, &positions, &masses, &mut approx);
+        assert!(
+            bh.interaction_count() < exact_system.last_interaction_count() / 2,
+            "Barnes-Hut evaluated {} interactions",
+            bh.interaction_count()
+        );
+        assert!(max_relative_error(&exact, &approx) < 0.05);
+    }
+
+    #[test]
+    fn test_immovable_and_coincident_bodies() {
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let entities: Vec<Entity> = (0..4).map(|i| Entity::new(i, 0)).collect();
+        // Three bodies at the same point exercise the depth limit
+        for entity in &entities[..3] {
+            positions.insert(*entity, Position::new(1.0, 1.0, 1.0));
+            masses.insert(*entity, Mass::new(5.0));
+        }
+        positions.insert(entities[3], Position::new(50.0, 0.0, 0.0));
+        masses.insert(entities[3], Mass::immovable());
+
+        let mut bh = BarnesHutSystem::new(plugin());
+        let mut forces = HashMap::new();
+        bh.compute_forces_into(&entities, &positions, &masses, &mut forces);
+
+        // Coincident bodies cancel out under softening; immovable body gets nothing
+        assert!(!forces.contains_key(&entities[3]));
+        for entity in &entities[..3] {
+            assert!(forces[entity].magnitude() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Theta must be non-negative and finite")]
+    fn test_negative_theta_panics() {
+        BarnesHutSystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT)).set_theta(-0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Leaf capacity must be at least 1")]
+    fn test_zero_leaf_capacity_panics() {
+        BarnesHutSystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT)).set_leaf_capacity(0);
+    }
+}
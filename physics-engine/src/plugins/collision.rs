@@ -0,0 +1,1078 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Sphere collision detection and impulse resolution
+//!
+//! Bodies with a `Radius` component are treated as spheres. Each step,
+//! `CollisionSystem::detect_pairs()` finds overlapping pairs that are still
+//! approaching and records them as `CollisionEvent`s. Events are produced
+//! whether or not physical resolution is enabled, so user code can react to
+//! contacts (spawn debris, play sounds) independently of the physics.
+//!
+//! # Physics Background
+//!
+//! For a contact with unit normal n (from `a` to `b`) and relative velocity
+//! v_rel = v_b − v_a, the bodies approach when v_rel·n < 0. The impulse
+//! magnitude that resolves the contact with coefficient of restitution e is:
+//!
+//! **j = −(1 + e)(v_rel·n) / (1/m_a + 1/m_b)**
+//!
+//! Body `a` receives −j·n and body `b` receives +j·n. Immovable bodies have
+//! zero inverse mass and are not deflected.
+//!
+//! # Rotational Response
+//!
+//! `resolve_with_rotation()` additionally applies Coulomb friction at the
+//! contact point, so spinning bodies exchange angular and linear momentum.
+//! With lever arms r_a, r_b from each center to the contact point and the
+//! unit slip direction t of the contact-point relative velocity, the
+//! tangential impulse that stops slip is:
+//!
+//! **j_t = |v_t| / (1/m_a + 1/m_b + |r_a × t|²/I_a + |r_b × t|²/I_b)**
+//!
+//! clamped to the friction cone j_t ≤ μ·j. Both bodies share a single
+//! contact point, so the equal and opposite impulse conserves total linear
+//! momentum and total angular momentum about any point.
+//!
+//! # Usage
+//!
+//! ```
+//! use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+//! use physics_engine::ecs::components::{Mass, Position, Radius, Velocity};
+//! use physics_engine::plugins::collision::{CollisionEvents, CollisionSystem};
+//!
+//! let mut world = World::new();
+//! let (a, b) = (world.create_entity(), world.create_entity());
+//! let mut positions = HashMapStorage::<Position>::new();
+//! let mut velocities = HashMapStorage::<Velocity>::new();
+//! let mut masses = HashMapStorage::<Mass>::new();
+//! let mut radii = HashMapStorage::<Radius>::new();
+//! for (entity, x, vx) in [(a, 0.0, 1.0), (b, 1.5, -1.0)] {
+//!     positions.insert(entity, Position::new(x, 0.0, 0.0));
+//!     velocities.insert(entity, Velocity::new(vx, 0.0, 0.0));
+//!     masses.insert(entity, Mass::new(1.0));
+//!     radii.insert(entity, Radius::new(1.0));
+//! }
+//!
+//! let mut collisions = CollisionSystem::new(1.0);
+//! let mut events = CollisionEvents::new();
+//! collisions.detect_pairs(&[a, b], &positions, &velocities, &masses, &radii, &mut events);
+//! collisions.resolve(&events, &mut velocities, &masses);
+//!
+//! for event in events.drain() {
+//!     println!("{:?} hit {:?} at {:.1} m/s", event.a, event.b, event.relative_speed);
+//! }
+//! ```
+//!
+//! # Contact Events
+//!
+//! Detection reports every approaching pair, including bodies resting on
+//! each other. For gameplay and audio hooks, the resolution step also
+//! records a `ContactEvent` for each contact it resolves, carrying the
+//! impulse it actually applied. Set an impulse threshold to keep resting
+//! contacts out of the stream, then collect the events after each step;
+//! every resolution replaces the previous step's events:
+//!
+//! ```ignore
+//! collisions.set_contact_impulse_threshold(0.5); // N⋅s
+//! collisions.resolve(&events, &mut velocities, &masses);
+//! for contact in collisions.take_contact_events() {
+//!     play_sound(contact.entity_a, contact.entity_b, contact.impulse_magnitude);
+//! }
+//! ```
+//!
+//! Events are recorded in resolution order, which follows the order of the
+//! entity slice given to `detect_pairs()`, so identical inputs produce
+//! identical event streams.
+//!
+//! # Energy Check
+//!
+//! A correct impulse never creates kinetic energy: an elastic contact
+//! (e = 1) conserves it and any e < 1 or friction removes some. Bugs in
+//! resolution, such as an impulse computed from stale velocities, break this
+//! silently. `set_energy_check()` makes the system compare the kinetic
+//! energy (including spin, for `resolve_with_rotation()`) of the bodies in
+//! each batch before and after resolving it, and warn when it grew by more
+//! than the given relative tolerance:
+//!
+//! ```ignore
+//! collisions.set_energy_check(Some(1e-9));
+//! collisions.resolve(&events, &mut velocities, &masses);
+//! assert!(collisions.last_energy_change().unwrap() <= 1e-9 * initial_energy);
+//! ```
+//!
+//! Warnings go to stderr unless a handler is installed with
+//! `set_warning_handler()`.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Position, Velocity, Mass, Radius, AngularVelocity, Inertia};
+use crate::ecs::systems::WarningHandler;
+use crate::orbits::{cross, dot};
+use std::collections::HashSet;
+
+/// A contact between two approaching spheres
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionEvent {
+    /// First body of the pair
+    pub a: Entity,
+    /// Second body of the pair
+    pub b: Entity,
+    /// Unit contact normal pointing from `a` to `b`
+    pub normal: [Real; 3],
+    /// Contact point, midway through the overlap along the normal
+    pub contact_point: [Real; 3],
+    /// Closing speed along the normal (positive when approaching)
+    pub relative_speed: Real,
+    /// Impulse magnitude that resolves the contact (N⋅s)
+    pub impulse: Real,
+}
+
+/// A contact resolved by `CollisionSystem`, for gameplay and audio hooks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactEvent {
+    /// First body of the pair
+    pub entity_a: Entity,
+    /// Second body of the pair
+    pub entity_b: Entity,
+    /// Contact point, midway through the overlap along the normal
+    pub point: [Real; 3],
+    /// Unit contact normal pointing from `entity_a` to `entity_b`
+    pub normal: [Real; 3],
+    /// Magnitude of the impulse applied to each body, including friction (N⋅s)
+    pub impulse_magnitude: Real,
+    /// Closing speed along the normal before resolution (m/s)
+    pub relative_speed: Real,
+}
+
+/// Queue of collision events produced by `CollisionSystem::detect_pairs()`
+///
+/// Events accumulate until drained or cleared, so a queue can collect
+/// contacts over several steps before user code processes them.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionEvents {
+    events: Vec<CollisionEvent>,
+}
+
+impl CollisionEvents {
+    /// Create an empty event queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event
+    pub fn push(&mut self, event: CollisionEvent) {
+        self.events.push(event);
+    }
+
+    /// Get the queued events without removing them
+    pub fn events(&self) -> &[CollisionEvent] {
+        &self.events
+    }
+
+    /// Remove and iterate over all queued events
+    pub fn drain(&mut self) -> std::vec::Drain<'_, CollisionEvent> {
+        self.events.drain(..)
+    }
+
+    /// Discard all queued events
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Get the number of queued events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Check if the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Brute-force sphere collision detection with optional impulse resolution
+///
+/// Detection is O(N²) over the entities passed in; entities missing a
+/// position, velocity, mass, or radius are skipped.
+pub struct CollisionSystem {
+    restitution: Real,
+    friction: Real,
+    resolution_enabled: bool,
+    /// Smallest impulse recorded as a contact event
+    contact_impulse_threshold: Real,
+    /// Contacts resolved since the last `take_contact_events()`
+    contact_events: Vec<ContactEvent>,
+    /// Relative kinetic energy increase tolerated per batch (None = unchecked)
+    energy_check: Option<Real>,
+    /// Kinetic energy change of the last checked batch
+    last_energy_change: Option<Real>,
+    warning_handler: Option<WarningHandler>,
+}
+
+impl CollisionSystem {
+    /// Create a collision system with the given coefficient of restitution
+    ///
+    /// Use 1.0 for perfectly elastic and 0.0 for perfectly inelastic contacts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if restitution is outside [0, 1]
+    pub fn new(restitution: Real) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&restitution),
+            "Restitution must be in [0, 1]"
+        );
+        CollisionSystem {
+            restitution,
+            friction: 0.0,
+            resolution_enabled: true,
+            contact_impulse_threshold: 0.0,
+            contact_events: Vec::new(),
+            energy_check: None,
+            last_energy_change: None,
+            warning_handler: None,
+        }
+    }
+
+    /// Get the coefficient of restitution
+    pub fn restitution(&self) -> Real {
+        self.restitution
+    }
+
+    /// Set the Coulomb friction coefficient used by `resolve_with_rotation()`
+    ///
+    /// The default of 0.0 makes contacts frictionless, so spin is never
+    /// exchanged. `resolve()` ignores friction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coefficient is negative or not finite
+    pub fn set_friction(&mut self, friction: Real) {
+        assert!(
+            friction >= 0.0 && friction.is_finite(),
+            "Friction must be non-negative and finite"
+        );
+        self.friction = friction;
+    }
+
+    /// Get the Coulomb friction coefficient
+    pub fn friction(&self) -> Real {
+        self.friction
+    }
+
+    /// Enable or disable physical resolution
+    ///
+    /// When disabled, `resolve()` leaves velocities untouched but
+    /// `detect_pairs()` still produces events.
+    pub fn set_resolution_enabled(&mut self, enabled: bool) {
+        self.resolution_enabled = enabled;
+    }
+
+    /// Check if physical resolution is enabled
+    pub fn resolution_enabled(&self) -> bool {
+        self.resolution_enabled
+    }
+
+    /// Set the smallest impulse magnitude recorded as a contact event
+    ///
+    /// Contacts resolved with a smaller impulse, such as bodies resting on
+    /// each other, are still resolved but produce no `ContactEvent`. The
+    /// default of 0.0 records every resolved contact.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the threshold is negative or not finite
+    pub fn set_contact_impulse_threshold(&mut self, threshold: Real) {
+        assert!(
+            threshold >= 0.0 && threshold.is_finite(),
+            "Contact impulse threshold must be non-negative and finite"
+        );
+        self.contact_impulse_threshold = threshold;
+    }
+
+    /// Get the smallest impulse magnitude recorded as a contact event
+    pub fn contact_impulse_threshold(&self) -> Real {
+        self.contact_impulse_threshold
+    }
+
+    /// Get the contact events recorded by the last `resolve()` or `resolve_with_rotation()`
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    /// Remove and return the contact events recorded by the last resolution
+    ///
+    /// Each `resolve()` or `resolve_with_rotation()` call starts a fresh
+    /// buffer, like a `CollisionEvents` queue cleared every step, so events
+    /// that are never taken don't pile up. Events are in the order the
+    /// contacts were resolved.
+    pub fn take_contact_events(&mut self) -> Vec<ContactEvent> {
+        std::mem::take(&mut self.contact_events)
+    }
+
+    /// Check resolved batches for kinetic energy injection
+    ///
+    /// With `Some(tolerance)`, `resolve()` and `resolve_with_rotation()`
+    /// measure the kinetic energy of the bodies in each batch before and
+    /// after applying it, and warn if it grew by more than `tolerance` times
+    /// its initial value. `None` (the default) skips the measurement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tolerance is negative or not finite
+    pub fn set_energy_check(&mut self, tolerance: Option<Real>) {
+        if let Some(tolerance) = tolerance {
+            assert!(
+                tolerance >= 0.0 && tolerance.is_finite(),
+                "Energy check tolerance must be non-negative and finite"
+            );
+        }
+        self.energy_check = tolerance;
+    }
+
+    /// Get the relative tolerance of the energy check, if enabled
+    pub fn energy_check(&self) -> Option<Real> {
+        self.energy_check
+    }
+
+    /// Get the kinetic energy change (J) of the last checked batch
+    ///
+    /// Negative when resolution dissipated energy. None if the energy check
+    /// is disabled or no batch has been resolved since it was enabled.
+    pub fn last_energy_change(&self) -> Option<Real> {
+        self.last_energy_change
+    }
+
+    /// Route energy check warnings to a handler instead of stderr
+    pub fn set_warning_handler(&mut self, handler: WarningHandler) {
+        self.warning_handler = Some(handler);
+    }
+
+    /// Restore the default of printing warnings to stderr
+    pub fn clear_warning_handler(&mut self) {
+        self.warning_handler = None;
+    }
+
+    /// Record the energy change of a batch and warn if it exceeds the tolerance
+    fn check_energy(&mut self, before: Real, after: Real, contacts: usize) {
+        let Some(tolerance) = self.energy_check else {
+            return;
+        };
+        let change = after - before;
+        self.last_energy_change = Some(change);
+        if change > tolerance * before {
+            let message = format!(
+                "Warning: Collision resolution increased kinetic energy by {:.3e} J ({:.3e} relative) over {} contacts",
+                change,
+                change / before,
+                contacts
+            );
+            match &self.warning_handler {
+                Some(handler) => handler(&message),
+                None => eprintln!("{}", message),
+            }
+        }
+    }
+
+    /// Record a resolved contact if its impulse reaches the threshold
+    fn record_contact(&mut self, event: &CollisionEvent, impulse_magnitude: Real) {
+        if impulse_magnitude < self.contact_impulse_threshold {
+            return;
+        }
+        self.contact_events.push(ContactEvent {
+            entity_a: event.a,
+            entity_b: event.b,
+            point: event.contact_point,
+            normal: event.normal,
+            impulse_magnitude,
+            relative_speed: event.relative_speed,
+        });
+    }
+
+    /// Find overlapping, approaching pairs and append them to `events`
+    ///
+    /// Pairs that overlap but are already separating produce no event, so a
+    /// resolved contact is not reported again while the spheres move apart.
+    /// Pairs of immovable bodies are ignored.
+    ///
+    /// # Returns
+    ///
+    /// Number of events added
+    pub fn detect_pairs(
+        &self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        velocities: &impl ComponentStorage<Component = Velocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        radii: &impl ComponentStorage<Component = Radius>,
+        events: &mut CollisionEvents,
+    ) -> usize {
+        let mut count = 0;
+
+        for (i, &a) in entities.iter().enumerate() {
+            for &b in &entities[i + 1..] {
+                if let Some(event) = self.detect_pair(a, b, positions, velocities, masses, radii) {
+                    events.push(event);
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Apply impulses for the queued events
+    ///
+    /// Replaces the contact events with a `ContactEvent` for each resolved
+    /// contact whose impulse reaches the contact impulse threshold. Does
+    /// nothing else when resolution is disabled. With the energy check enabled,
+    /// also records the batch's kinetic energy change; see
+    /// `last_energy_change()`.
+    ///
+    /// # Returns
+    ///
+    /// Number of events resolved
+    pub fn resolve(
+        &mut self,
+        events: &CollisionEvents,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+    ) -> usize {
+        self.contact_events.clear();
+        if !self.resolution_enabled {
+            return 0;
+        }
+        let bodies = self.energy_check.map(|_| batch_bodies(events));
+        let before = bodies.as_ref().map(|bodies| kinetic_energy(bodies, velocities, masses));
+
+        let mut count = 0;
+        for event in events.events() {
+            let (inv_a, inv_b) = match (masses.get(event.a), masses.get(event.b)) {
+                (Some(ma), Some(mb)) => (ma.inverse(), mb.inverse()),
+                _ => continue,
+            };
+            let [nx, ny, nz] = event.normal;
+
+            if let Some(vel) = velocities.get_mut(event.a) {
+                let s = event.impulse * inv_a;
+                vel.set_dx(vel.dx() - s * nx);
+                vel.set_dy(vel.dy() - s * ny);
+                vel.set_dz(vel.dz() - s * nz);
+            }
+            if let Some(vel) = velocities.get_mut(event.b) {
+                let s = event.impulse * inv_b;
+                vel.set_dx(vel.dx() + s * nx);
+                vel.set_dy(vel.dy() + s * ny);
+                vel.set_dz(vel.dz() + s * nz);
+            }
+            self.record_contact(event, event.impulse);
+            count += 1;
+        }
+
+        if let (Some(bodies), Some(before)) = (bodies, before) {
+            self.check_energy(before, kinetic_energy(&bodies, velocities, masses), count);
+        }
+        count
+    }
+
+    /// Apply normal and friction impulses for the queued events, updating spin
+    ///
+    /// Opt-in alternative to `resolve()` for spinning bodies. The normal
+    /// impulse is the same; friction then acts on the slip velocity at the
+    /// contact point, including the contribution of each body's spin. Bodies
+    /// missing an `AngularVelocity` or `Inertia` keep their spin fixed, as if
+    /// their moment of inertia were infinite. Contact events carry the
+    /// magnitude of the combined normal and friction impulse. Does nothing
+    /// when resolution is disabled.
+    ///
+    /// # Returns
+    ///
+    /// Number of events resolved
+    pub fn resolve_with_rotation(
+        &mut self,
+        events: &CollisionEvents,
+        positions: &impl ComponentStorage<Component = Position>,
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        angular_velocities: &mut impl ComponentStorage<Component = AngularVelocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        inertias: &impl ComponentStorage<Component = Inertia>,
+    ) -> usize {
+        self.contact_events.clear();
+        if !self.resolution_enabled {
+            return 0;
+        }
+        let bodies = self.energy_check.map(|_| batch_bodies(events));
+        let before = bodies.as_ref().map(|bodies| {
+            kinetic_energy(bodies, velocities, masses) + spin_energy(bodies, angular_velocities, inertias)
+        });
+
+        let mut count = 0;
+        for event in events.events() {
+            let (inv_a, inv_b) = match (masses.get(event.a), masses.get(event.b)) {
+                (Some(ma), Some(mb)) => (ma.inverse(), mb.inverse()),
+                _ => continue,
+            };
+            let (pa, pb) = match (positions.get(event.a), positions.get(event.b)) {
+                (Some(pa), Some(pb)) => (pa.as_array(), pb.as_array()),
+                _ => continue,
+            };
+            let (va, vb) = match (velocities.get(event.a), velocities.get(event.b)) {
+                (Some(va), Some(vb)) => (va.as_array(), vb.as_array()),
+                _ => continue,
+            };
+            let (wa, inv_ia) = spin_state(event.a, angular_velocities, inertias);
+            let (wb, inv_ib) = spin_state(event.b, angular_velocities, inertias);
+
+            let p = event.contact_point;
+            let n = event.normal;
+            let ra = [p[0] - pa[0], p[1] - pa[1], p[2] - pa[2]];
+            let rb = [p[0] - pb[0], p[1] - pb[1], p[2] - pb[2]];
+
+            // Relative velocity of the contact point on b with respect to a
+            let (spin_a, spin_b) = (cross(&wa, &ra), cross(&wb, &rb));
+            let v_rel: [Real; 3] =
+                std::array::from_fn(|i| (vb[i] + spin_b[i]) - (va[i] + spin_a[i]));
+            let v_rel_n = dot(&v_rel, &n);
+            let v_t: [Real; 3] = std::array::from_fn(|i| v_rel[i] - v_rel_n * n[i]);
+            let slip = dot(&v_t, &v_t).sqrt();
+
+            // Impulse on b; a receives the opposite
+            let mut impulse: [Real; 3] = std::array::from_fn(|i| event.impulse * n[i]);
+            if self.friction > 0.0 && slip > 0.0 {
+                let t: [Real; 3] = std::array::from_fn(|i| v_t[i] / slip);
+                let (ra_t, rb_t) = (cross(&ra, &t), cross(&rb, &t));
+                let k_t = inv_a + inv_b + inv_ia * dot(&ra_t, &ra_t) + inv_ib * dot(&rb_t, &rb_t);
+                if k_t > 0.0 {
+                    let j_t = (slip / k_t).min(self.friction * event.impulse);
+                    for i in 0..3 {
+                        impulse[i] -= j_t * t[i];
+                    }
+                }
+            }
+
+            if let Some(vel) = velocities.get_mut(event.a) {
+                *vel = Velocity::from_array(std::array::from_fn(|i| va[i] - impulse[i] * inv_a));
+            }
+            if let Some(vel) = velocities.get_mut(event.b) {
+                *vel = Velocity::from_array(std::array::from_fn(|i| vb[i] + impulse[i] * inv_b));
+            }
+            if inv_ia > 0.0 {
+                let torque = cross(&ra, &impulse);
+                if let Some(w) = angular_velocities.get_mut(event.a) {
+                    *w = AngularVelocity::from_array(std::array::from_fn(|i| wa[i] - torque[i] * inv_ia));
+                }
+            }
+            if inv_ib > 0.0 {
+                let torque = cross(&rb, &impulse);
+                if let Some(w) = angular_velocities.get_mut(event.b) {
+                    *w = AngularVelocity::from_array(std::array::from_fn(|i| wb[i] + torque[i] * inv_ib));
+                }
+            }
+            self.record_contact(event, dot(&impulse, &impulse).sqrt());
+            count += 1;
+        }
+
+        if let (Some(bodies), Some(before)) = (bodies, before) {
+            let after = kinetic_energy(&bodies, velocities, masses) + spin_energy(&bodies, angular_velocities, inertias);
+            self.check_energy(before, after, count);
+        }
+        count
+    }
+
+    fn detect_pair(
+        &self,
+        a: Entity,
+        b: Entity,
+        positions: &impl ComponentStorage<Component = Position>,
+        velocities: &impl ComponentStorage<Component = Velocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        radii: &impl ComponentStorage<Component = Radius>,
+    ) -> Option<CollisionEvent> {
+        let (pa, pb) = (positions.get(a)?, positions.get(b)?);
+        let (va, vb) = (velocities.get(a)?, velocities.get(b)?);
+        let (ma, mb) = (masses.get(a)?, masses.get(b)?);
+        let (ra, rb) = (radii.get(a)?, radii.get(b)?);
+
+        let inv_mass_sum = ma.inverse() + mb.inverse();
+        if inv_mass_sum == 0.0 {
+            return None;
+        }
+
+        let dx = pb.x() - pa.x();
+        let dy = pb.y() - pa.y();
+        let dz = pb.z() - pa.z();
+        let dist_sq = dx * dx + dy * dy + dz * dz;
+        let contact = ra.value() + rb.value();
+        if dist_sq > contact * contact {
+            return None;
+        }
+
+        // Coincident centers have no defined normal; skip rather than guess
+        let dist = dist_sq.sqrt();
+        if dist == 0.0 {
+            return None;
+        }
+        let normal = [dx / dist, dy / dist, dz / dist];
+        // Split the penetration evenly so both bodies share one contact point
+        let reach = ra.value() - 0.5 * (contact - dist);
+        let contact_point = [
+            pa.x() + reach * normal[0],
+            pa.y() + reach * normal[1],
+            pa.z() + reach * normal[2],
+        ];
+
+        let v_rel_n = (vb.dx() - va.dx()) * normal[0]
+            + (vb.dy() - va.dy()) * normal[1]
+            + (vb.dz() - va.dz()) * normal[2];
+        if v_rel_n >= 0.0 {
+            return None;
+        }
+
+        Some(CollisionEvent {
+            a,
+            b,
+            normal,
+            contact_point,
+            relative_speed: -v_rel_n,
+            impulse: -(1.0 + self.restitution) * v_rel_n / inv_mass_sum,
+        })
+    }
+}
+
+/// Bodies taking part in a batch of events, each listed once in event order
+fn batch_bodies(events: &CollisionEvents) -> Vec<Entity> {
+    let mut seen = HashSet::new();
+    events
+        .events()
+        .iter()
+        .flat_map(|event| [event.a, event.b])
+        .filter(|entity| seen.insert(*entity))
+        .collect()
+}
+
+/// Translational kinetic energy of the movable bodies
+fn kinetic_energy(
+    bodies: &[Entity],
+    velocities: &impl ComponentStorage<Component = Velocity>,
+    masses: &impl ComponentStorage<Component = Mass>,
+) -> Real {
+    let mut energy = 0.0;
+    for &entity in bodies {
+        if let (Some(mass), Some(velocity)) = (masses.get(entity), velocities.get(entity)) {
+            if !mass.is_immovable() {
+                let v = velocity.as_array();
+                energy += 0.5 * mass.value() * dot(&v, &v);
+            }
+        }
+    }
+    energy
+}
+
+/// Rotational kinetic energy of the bodies with both a spin and an inertia
+fn spin_energy(
+    bodies: &[Entity],
+    angular_velocities: &impl ComponentStorage<Component = AngularVelocity>,
+    inertias: &impl ComponentStorage<Component = Inertia>,
+) -> Real {
+    let mut energy = 0.0;
+    for &entity in bodies {
+        if let (Some(w), Some(inertia)) = (angular_velocities.get(entity), inertias.get(entity)) {
+            let w = w.as_array();
+            energy += 0.5 * inertia.value() * dot(&w, &w);
+        }
+    }
+    energy
+}
+
+/// Angular velocity and inverse moment of a body, or a fixed spin if either is missing
+fn spin_state(
+    entity: Entity,
+    angular_velocities: &impl ComponentStorage<Component = AngularVelocity>,
+    inertias: &impl ComponentStorage<Component = Inertia>,
+) -> ([Real; 3], Real) {
+    match (angular_velocities.get(entity), inertias.get(entity)) {
+        (Some(w), Some(inertia)) => (w.as_array(), inertia.inverse()),
+        (Some(w), None) => (w.as_array(), 0.0),
+        _ => ([0.0; 3], 0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, World};
+    use std::sync::{Arc, Mutex};
+    use crate::real::RealExt;
+
+    struct Bodies {
+        entities: Vec<Entity>,
+        positions: HashMapStorage<Position>,
+        velocities: HashMapStorage<Velocity>,
+        masses: HashMapStorage<Mass>,
+        radii: HashMapStorage<Radius>,
+    }
+
+    /// Unit spheres of mass 2 along the x axis, given as (x, vx) pairs
+    fn row(states: &[(Real, Real)]) -> Bodies {
+        let mut world = World::new();
+        let mut bodies = Bodies {
+            entities: Vec::new(),
+            positions: HashMapStorage::new(),
+            velocities: HashMapStorage::new(),
+            masses: HashMapStorage::new(),
+            radii: HashMapStorage::new(),
+        };
+        for &(x, vx) in states {
+            let entity = world.create_entity();
+            bodies.positions.insert(entity, Position::new(x, 0.0, 0.0));
+            bodies.velocities.insert(entity, Velocity::new(vx, 0.0, 0.0));
+            bodies.masses.insert(entity, Mass::new(2.0));
+            bodies.radii.insert(entity, Radius::new(1.0));
+            bodies.entities.push(entity);
+        }
+        bodies
+    }
+
+    /// Two unit spheres overlapping along x with the given velocities
+    fn overlapping_pair(va: Real, vb: Real) -> Bodies {
+        row(&[(0.0, va), (1.8, vb)])
+    }
+
+    fn detect(system: &CollisionSystem, bodies: &Bodies, events: &mut CollisionEvents) -> usize {
+        system.detect_pairs(
+            &bodies.entities,
+            &bodies.positions,
+            &bodies.velocities,
+            &bodies.masses,
+            &bodies.radii,
+            events,
+        )
+    }
+
+    #[test]
+    fn test_approaching_pair_produces_one_event() {
+        let bodies = overlapping_pair(1.0, -1.0);
+        let system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+
+        assert_eq!(detect(&system, &bodies, &mut events), 1);
+        let event = events.events()[0];
+        assert_eq!((event.a, event.b), (bodies.entities[0], bodies.entities[1]));
+        assert_eq!(event.normal, [1.0, 0.0, 0.0]);
+        assert!((event.relative_speed - 2.0).abs() < 1e-12);
+        // j = (1 + e)·v / (1/m + 1/m) = 2·2 / 1
+        assert!((event.impulse - 4.0).abs() < 1e-12);
+
+        let drained: Vec<_> = events.drain().collect();
+        assert_eq!(drained.len(), 1);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_separating_pair_produces_no_event() {
+        let bodies = overlapping_pair(-1.0, 1.0);
+        let system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+
+        assert_eq!(detect(&system, &bodies, &mut events), 0);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_non_overlapping_pair_produces_no_event() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        bodies.positions.insert(bodies.entities[1], Position::new(5.0, 0.0, 0.0));
+        let mut events = CollisionEvents::new();
+
+        assert_eq!(detect(&CollisionSystem::new(1.0), &bodies, &mut events), 0);
+    }
+
+    #[test]
+    fn test_elastic_resolution_swaps_equal_mass_velocities() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        let mut system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+        detect(&system, &bodies, &mut events);
+
+        assert_eq!(system.resolve(&events, &mut bodies.velocities, &bodies.masses), 1);
+        let va = bodies.velocities.get(bodies.entities[0]).unwrap();
+        let vb = bodies.velocities.get(bodies.entities[1]).unwrap();
+        assert!((va.dx() + 1.0).abs() < 1e-12);
+        assert!((vb.dx() - 1.0).abs() < 1e-12);
+
+        // Now separating: no further events
+        events.clear();
+        assert_eq!(detect(&system, &bodies, &mut events), 0);
+    }
+
+    #[test]
+    fn test_events_generated_with_resolution_disabled() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        let mut system = CollisionSystem::new(0.5);
+        system.set_resolution_enabled(false);
+        let mut events = CollisionEvents::new();
+
+        assert_eq!(detect(&system, &bodies, &mut events), 1);
+        assert_eq!(system.resolve(&events, &mut bodies.velocities, &bodies.masses), 0);
+        assert_eq!(bodies.velocities.get(bodies.entities[0]).unwrap().dx(), 1.0);
+    }
+
+    /// Collision system with the energy check on and warnings captured
+    fn checked_system(restitution: Real) -> (CollisionSystem, Arc<Mutex<Vec<String>>>) {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = warnings.clone();
+        let mut system = CollisionSystem::new(restitution);
+        system.set_energy_check(Some(1e-9));
+        system.set_warning_handler(Box::new(move |message| sink.lock().unwrap().push(message.to_string())));
+        (system, warnings)
+    }
+
+    #[test]
+    fn test_energy_check_accepts_correct_resolution() {
+        for (restitution, unequal) in [(1.0, false), (1.0, true), (0.6, false), (0.0, true)] {
+            let mut bodies = row(&[(0.0, 3.0), (1.8, -1.0), (3.6, 0.5)]);
+            if unequal {
+                bodies.masses.insert(bodies.entities[0], Mass::new(7.0));
+            }
+            let (mut system, warnings) = checked_system(restitution);
+            let mut events = CollisionEvents::new();
+            // Only the first pair overlaps while approaching
+            assert_eq!(detect(&system, &bodies, &mut events), 1);
+            system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+
+            let change = system.last_energy_change().unwrap();
+            if restitution == 1.0 {
+                assert!(change.abs() < 1e-12, "Elastic contact changed energy by {}", change);
+            } else {
+                assert!(change < 0.0, "Inelastic contact did not dissipate: {}", change);
+            }
+            assert!(warnings.lock().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_energy_check_flags_overshooting_impulse() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        let (mut system, warnings) = checked_system(1.0);
+        let mut detected = CollisionEvents::new();
+        detect(&system, &bodies, &mut detected);
+
+        // Push past the elastic impulse, as a resolver bug might
+        let mut events = CollisionEvents::new();
+        for event in detected.drain() {
+            events.push(CollisionEvent { impulse: event.impulse * 1.5, ..event });
+        }
+        system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+
+        assert!(system.last_energy_change().unwrap() > 0.0);
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("increased kinetic energy"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn test_energy_check_off_by_default() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        let mut system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+        detect(&system, &bodies, &mut events);
+        system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+        assert_eq!(system.energy_check(), None);
+        assert_eq!(system.last_energy_change(), None);
+    }
+
+    /// Total linear momentum and angular momentum about `point`
+    fn momenta(
+        bodies: &Bodies,
+        spins: &HashMapStorage<AngularVelocity>,
+        inertias: &HashMapStorage<Inertia>,
+        point: [Real; 3],
+    ) -> ([Real; 3], [Real; 3]) {
+        let (mut linear, mut angular) = ([0.0; 3], [0.0; 3]);
+        for &entity in &bodies.entities {
+            let m = bodies.masses.get(entity).unwrap().value();
+            let x = bodies.positions.get(entity).unwrap().as_array();
+            let p = bodies.velocities.get(entity).unwrap().as_array().map(|v| m * v);
+            let arm = [x[0] - point[0], x[1] - point[1], x[2] - point[2]];
+            let orbital = cross(&arm, &p);
+            let spin = spins.get(entity).unwrap().as_array();
+            let inertia = inertias.get(entity).unwrap().value();
+            for i in 0..3 {
+                linear[i] += p[i];
+                angular[i] += orbital[i] + inertia * spin[i];
+            }
+        }
+        (linear, angular)
+    }
+
+    #[test]
+    fn test_spinning_body_transfers_spin_to_linear_momentum() {
+        let mut bodies = overlapping_pair(1.0, 0.0);
+        let (a, b) = (bodies.entities[0], bodies.entities[1]);
+        let mut spins = HashMapStorage::<AngularVelocity>::new();
+        let mut inertias = HashMapStorage::<Inertia>::new();
+        spins.insert(a, AngularVelocity::new(0.0, 0.0, 5.0));
+        spins.insert(b, AngularVelocity::zero());
+        for &entity in &bodies.entities {
+            inertias.insert(entity, Inertia::solid_sphere(2.0, 1.0));
+        }
+
+        let mut system = CollisionSystem::new(0.5);
+        system.set_friction(0.4);
+        let mut events = CollisionEvents::new();
+        assert_eq!(detect(&system, &bodies, &mut events), 1);
+        let contact = events.events()[0].contact_point;
+        assert!((contact[0] - 0.9).abs() < Real::TOLERANCE);
+        assert_eq!(&contact[1..], &[0.0, 0.0]);
+
+        let (linear_before, angular_before) = momenta(&bodies, &spins, &inertias, contact);
+        let resolved = system.resolve_with_rotation(
+            &events,
+            &bodies.positions,
+            &mut bodies.velocities,
+            &mut spins,
+            &bodies.masses,
+            &inertias,
+        );
+        assert_eq!(resolved, 1);
+        let (linear_after, angular_after) = momenta(&bodies, &spins, &inertias, contact);
+
+        // Friction at the contact drives b sideways and slows a's spin
+        assert!(bodies.velocities.get(b).unwrap().dy().abs() > 0.1);
+        assert!(spins.get(a).unwrap().wz() < 5.0);
+        assert!(spins.get(b).unwrap().wz().abs() > 0.0);
+
+        let tolerance = Real::TOLERANCE;
+        for i in 0..3 {
+            assert!((linear_after[i] - linear_before[i]).abs() < tolerance);
+            assert!((angular_after[i] - angular_before[i]).abs() < tolerance);
+        }
+    }
+
+    #[test]
+    fn test_frictionless_rotation_matches_plain_resolution() {
+        let mut plain = overlapping_pair(1.0, -1.0);
+        let mut spinning = overlapping_pair(1.0, -1.0);
+        let mut spins = HashMapStorage::<AngularVelocity>::new();
+        let mut inertias = HashMapStorage::<Inertia>::new();
+        for &entity in &spinning.entities {
+            spins.insert(entity, AngularVelocity::new(0.0, 3.0, 0.0));
+            inertias.insert(entity, Inertia::solid_sphere(2.0, 1.0));
+        }
+
+        let mut system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+        detect(&system, &plain, &mut events);
+        system.resolve(&events, &mut plain.velocities, &plain.masses);
+        system.resolve_with_rotation(
+            &events,
+            &spinning.positions,
+            &mut spinning.velocities,
+            &mut spins,
+            &spinning.masses,
+            &inertias,
+        );
+
+        for &entity in &spinning.entities {
+            assert_eq!(spinning.velocities.get(entity), plain.velocities.get(entity));
+            assert_eq!(spins.get(entity).unwrap().wy(), 3.0);
+        }
+    }
+
+    #[test]
+    fn test_head_on_collision_emits_one_contact_event() {
+        let mut bodies = overlapping_pair(1.0, -2.0);
+        let (a, b) = (bodies.entities[0], bodies.entities[1]);
+        bodies.masses.insert(b, Mass::new(3.0));
+        let mut system = CollisionSystem::new(0.5);
+        let mut events = CollisionEvents::new();
+        detect(&system, &bodies, &mut events);
+        system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+
+        let contacts = system.take_contact_events();
+        assert_eq!(contacts.len(), 1);
+        let contact = contacts[0];
+        assert_eq!((contact.entity_a, contact.entity_b), (a, b));
+        assert_eq!(contact.normal, [1.0, 0.0, 0.0]);
+        assert!((contact.point[0] - 0.9).abs() < Real::TOLERANCE);
+        assert!((contact.relative_speed - 3.0).abs() < Real::TOLERANCE);
+        // j = (1 + e)·v / (1/m_a + 1/m_b) = 1.5·3 / (1/2 + 1/3)
+        assert!((contact.impulse_magnitude - 5.4).abs() < Real::TOLERANCE);
+        assert!(system.take_contact_events().is_empty());
+    }
+
+    #[test]
+    fn test_contacts_below_impulse_threshold_are_not_emitted() {
+        // A slow, resting-style contact needs j = 2·0.01 / 1 = 0.02
+        let mut bodies = overlapping_pair(0.01, 0.0);
+        let mut system = CollisionSystem::new(1.0);
+        system.set_contact_impulse_threshold(0.1);
+        let mut events = CollisionEvents::new();
+        detect(&system, &bodies, &mut events);
+
+        // The contact is still resolved, just not reported
+        assert_eq!(system.resolve(&events, &mut bodies.velocities, &bodies.masses), 1);
+        assert_eq!(bodies.velocities.get(bodies.entities[1]).unwrap().dx(), 0.01);
+        assert!(system.contact_events().is_empty());
+
+        let mut fast = overlapping_pair(1.0, -1.0);
+        events.clear();
+        detect(&system, &fast, &mut events);
+        system.resolve(&events, &mut fast.velocities, &fast.masses);
+        assert_eq!(system.contact_events().len(), 1);
+    }
+
+    #[test]
+    fn test_contact_events_only_cover_the_last_resolution() {
+        let mut bodies = overlapping_pair(1.0, -1.0);
+        let mut system = CollisionSystem::new(1.0);
+        let mut events = CollisionEvents::new();
+        detect(&system, &bodies, &mut events);
+        system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+        assert_eq!(system.contact_events().len(), 1);
+
+        // Untaken events are dropped by the next resolution
+        events.clear();
+        for _ in 0..100 {
+            system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+        }
+        assert!(system.contact_events().is_empty());
+    }
+
+    #[test]
+    fn test_contact_event_order_is_stable() {
+        let run = || {
+            // The middle body is hit from both sides in one step
+            let mut bodies = row(&[(0.0, 2.0), (1.5, 0.0), (3.0, -2.0)]);
+            let mut system = CollisionSystem::new(0.8);
+            let mut events = CollisionEvents::new();
+            detect(&system, &bodies, &mut events);
+            system.resolve(&events, &mut bodies.velocities, &bodies.masses);
+            (bodies.entities, system.take_contact_events())
+        };
+
+        let (entities, first) = run();
+        let pairs: Vec<_> = first.iter().map(|c| (c.entity_a, c.entity_b)).collect();
+        assert_eq!(pairs, vec![(entities[0], entities[1]), (entities[1], entities[2])]);
+        for _ in 0..5 {
+            assert_eq!(run().1, first);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Restitution must be in [0, 1]")]
+    fn test_invalid_restitution_panics() {
+        CollisionSystem::new(1.5);
+    }
+}
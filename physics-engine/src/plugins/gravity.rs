@@ -45,16 +45,83 @@
 //!
 //! **F = G * (m₁ * m₂) / (r² + ε²)**
 //!
-//! This is a standard technique in N-body simulations. See:
+//! This is a standard technique in N-body simulations. When bodies have very
+//! different sizes, per-body softening can be enabled so that each body's
+//! `Radius` sets its own length scale, combined pairwise as
+//! ε_ij = √(ε_i² + ε_j²). See:
 //! - Dehnen, W. (2001). "Towards optimal softening in three-dimensional N-body codes"
 //! - Aarseth, S. J. (2003). "Gravitational N-Body Simulations"
 //!
+//! The form above is the Plummer kernel. It never becomes exactly Newtonian:
+//! at r = 10ε the force is still about 1.5% weaker. The cubic-spline kernel
+//! (`SofteningKernel::Spline`) has compact support instead, and is exactly
+//! Newtonian beyond 2ε, at the cost of a stronger (less smoothed) force
+//! just outside 2ε than Plummer gives for the same ε. See:
+//! - Monaghan, J. J., & Lattanzio, J. C. (1985). "A refined particle method for astrophysical problems"
+//! - Springel, V. (2005). "The cosmological simulation code GADGET-2"
+//!
 //! ## Parallel Computation
 //!
 //! For N bodies, we need to compute N*(N-1)/2 pairwise interactions. This
 //! plugin uses Rayon to parallelize force computations across entities,
 //! splitting work into chunks for efficient parallel processing.
 //!
+//! Results are collected into a buffer indexed by position in the input
+//! entity slice, and registered in that order, so the outcome never depends
+//! on how Rayon splits the work or on hash map iteration order. Each body's
+//! force is summed over the other bodies in slice order, so runs are only
+//! bit-for-bit reproducible if callers pass the entities in a stable order
+//! (for example creation order) every step.
+//!
+//! ## Summation Accuracy
+//!
+//! Each body's force is a sum of N-1 pairwise terms. In dense systems the
+//! terms largely cancel, and naive summation loses the low-order bits of
+//! small contributions next to large ones. `set_compensated_summation(true)`
+//! sums each force component with Neumaier-compensated (Kahan) summation,
+//! reducing the error to roughly one rounding regardless of N at the cost of
+//! a few extra additions per pair.
+//!
+//! ## Sleeping Bodies
+//!
+//! `GravityOptions::with_sleeping()` skips the full force sum for entities
+//! asleep in a `ForceRegistry`. A sleeping body only receives the force from
+//! awake bodies, which is all that can have changed since it fell asleep, and
+//! is woken by the registry if that force exceeds the wake threshold.
+//! Sleeping bodies still attract awake ones unless
+//! `set_sleeping_bodies_attract(false)` is used.
+//!
+//! ## Far-Field Approximation
+//!
+//! `GravityPlugin::set_far_field_approximation()` replaces the exact sum for
+//! bodies far from the system's center of mass with a single monopole term:
+//! the rest of the system's mass placed at its center of mass. Taking the
+//! monopole about the center of mass cancels the dipole term, so the error
+//! falls off as (s/d)², where s is the system size and d the distance. See
+//! `MassDistribution` for the per-step moments this uses.
+//!
+//! ## Tracer Particles
+//!
+//! Bodies marked with the `Tracer` component feel gravity but exert none.
+//! `GravityOptions::with_tracers()` leaves them out of every pairwise source
+//! loop, so a cloud of tracers follows the field of the massive bodies
+//! without attracting itself or pulling on them. Each
+//! tracer's force is an exact sum over the non-tracers, even when the
+//! far-field approximation is enabled.
+//!
+//! Tracers, sleeping bodies and per-body radii are all `GravityOptions`, so
+//! they combine with each other and apply to every `GravitySystem` entry
+//! point, from the `HashMap` output to the dense slice and SoA paths.
+//!
+//! ## Progress and Cancellation
+//!
+//! An exact step over tens of thousands of bodies takes seconds.
+//! `GravitySystem::compute_forces_with_progress()` reports each finished
+//! chunk of bodies to a callback and checks a cancel flag between chunks,
+//! so a GUI can show progress and a server can abort a step that is no
+//! longer wanted. `compute_forces_into()` stays the fast path without
+//! either.
+//!
 //! ## Numerical Stability
 //!
 //! - Zero-length vectors are detected and result in zero force
@@ -62,12 +129,17 @@
 //! - Force magnitudes are validated to be finite
 //! - Softening prevents division by extremely small numbers
 
-use crate::ecs::{Entity, ComponentStorage};
-use crate::ecs::components::{Position, Mass};
+use crate::real::Real;
+use crate::ecs::{Entity, Component, ComponentStorage, MassSoAStorage, NewtonianState, PositionSoAStorage};
+use crate::ecs::components::{Acceleration, Position, Velocity, Mass, Radius};
 use crate::ecs::systems::{Force, ForceRegistry, ForceProvider};
 use crate::plugins::{Plugin, ForceProviderPlugin, PluginContext};
 use std::any::Any;
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
@@ -76,13 +148,296 @@ use rayon::prelude::*;
 ///
 /// CODATA 2018 recommended value: 6.67430(15) × 10⁻¹¹ m³/(kg⋅s²)
 /// Source: https://physics.nist.gov/cgi-bin/cuu/Value?bg
-pub const GRAVITATIONAL_CONSTANT: f64 = 6.67430e-11;
+pub const GRAVITATIONAL_CONSTANT: Real = 6.67430e-11;
 
 /// Default softening factor to prevent singularities (meters)
 ///
 /// This value is chosen to be small enough not to affect typical planetary
 /// simulations while preventing numerical issues when particles get very close.
-pub const DEFAULT_SOFTENING: f64 = 1e3; // 1 km
+pub const DEFAULT_SOFTENING: Real = 1e3; // 1 km
+
+/// Softening kernel used to regularize close encounters
+///
+/// See the module documentation for the tradeoffs between kernels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SofteningKernel {
+    /// F ∝ r / (r² + ε²)^(3/2), softened at every distance
+    #[default]
+    Plummer,
+    /// Cubic-spline kernel, exactly Newtonian beyond 2ε
+    Spline,
+}
+
+impl SofteningKernel {
+    /// Force magnitude and the factor that scales the separation vector
+    ///
+    /// `gmm` is G·m₁·m₂. The force vector is `scale * r_vec`. Callers must
+    /// ensure `r_squared + softening_squared > 0`.
+    pub(crate) fn force_terms(self, gmm: Real, r_squared: Real, softening_squared: Real) -> (Real, Real) {
+        match self {
+            SofteningKernel::Plummer => {
+                let softened_r_squared = r_squared + softening_squared;
+                let magnitude = gmm / softened_r_squared;
+                (magnitude, magnitude / softened_r_squared.sqrt())
+            }
+            SofteningKernel::Spline => {
+                // Kernel support h = 2ε
+                let h_squared = 4.0 * softening_squared;
+                if r_squared >= h_squared {
+                    let magnitude = gmm / r_squared;
+                    return (magnitude, magnitude / r_squared.sqrt());
+                }
+
+                let h = h_squared.sqrt();
+                let r = r_squared.sqrt();
+                let u = r / h;
+                let h3_inv = 1.0 / (h * h_squared);
+                let factor = if u < 0.5 {
+                    h3_inv * (32.0 / 3.0 + u * u * (32.0 * u - 38.4))
+                } else {
+                    h3_inv
+                        * (64.0 / 3.0 - 48.0 * u + 38.4 * u * u - 32.0 / 3.0 * u * u * u
+                            - 1.0 / (15.0 * u * u * u))
+                };
+                let scale = gmm * factor;
+                (scale * r, scale)
+            }
+        }
+    }
+}
+
+/// Marker for tracer particles, which feel gravity but exert none
+///
+/// A tracer's own mass still scales the force on it, so its acceleration is
+/// the same as a source body's; it just never appears as the attractor in
+/// another body's sum. See `GravityOptions::with_tracers()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tracer;
+
+impl Component for Tracer {}
+
+/// Compute the speed of a circular orbit around a central mass
+///
+/// Uses v = √(G·M / r), treating the central body as fixed. The orbiting
+/// body's own mass is neglected, which is accurate when it is much lighter
+/// than the central body.
+///
+/// # Arguments
+///
+/// * `central_mass` - Mass of the central body in kilograms
+/// * `radius` - Orbital radius in meters
+/// * `g` - Gravitational constant (use `GRAVITATIONAL_CONSTANT` for SI units)
+///
+/// # Panics
+///
+/// Panics if `radius` is not positive and finite, or if `central_mass` or `g`
+/// is negative or not finite.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::plugins::gravity::{circular_orbit_velocity, GRAVITATIONAL_CONSTANT};
+///
+/// // Earth around the Sun: ~29.8 km/s
+/// let v = circular_orbit_velocity(1.989e30, 1.496e11, GRAVITATIONAL_CONSTANT);
+/// assert!((v - 29_780.0).abs() < 100.0);
+/// ```
+pub fn circular_orbit_velocity(central_mass: Real, radius: Real, g: Real) -> Real {
+    assert!(
+        radius > 0.0 && radius.is_finite(),
+        "Orbital radius must be positive and finite"
+    );
+    assert!(
+        central_mass >= 0.0 && central_mass.is_finite(),
+        "Central mass must be non-negative and finite"
+    );
+    assert!(
+        g >= 0.0 && g.is_finite(),
+        "Gravitational constant must be non-negative and finite"
+    );
+    (g * central_mass / radius).sqrt()
+}
+
+/// Give an entity the velocity of a circular orbit around a central entity
+///
+/// The orbiting body keeps its current position. Its velocity is set to the
+/// central body's velocity plus a vector of magnitude √(G·M / r) perpendicular
+/// to the separation. The orbit plane contains the z-axis normal where
+/// possible (counter-clockwise when viewed from +z); separations parallel to
+/// the z-axis fall back to an orbit around the x-axis.
+///
+/// # Arguments
+///
+/// * `entity` - Entity to place on the orbit
+/// * `central_entity` - Entity being orbited
+/// * `positions` - Position storage (both entities must have positions)
+/// * `velocities` - Velocity storage to update
+/// * `masses` - Mass storage (the central entity must have a mass)
+/// * `g` - Gravitational constant
+///
+/// # Returns
+///
+/// The orbital speed relative to the central body, or an error if components
+/// are missing or the two bodies coincide.
+pub fn set_circular_orbit(
+    entity: Entity,
+    central_entity: Entity,
+    positions: &impl ComponentStorage<Component = Position>,
+    velocities: &mut impl ComponentStorage<Component = Velocity>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    g: Real,
+) -> Result<Real, String> {
+    let pos = positions
+        .get(entity)
+        .ok_or_else(|| format!("Entity {:?} has no Position component", entity))?;
+    let central_pos = positions
+        .get(central_entity)
+        .ok_or_else(|| format!("Central entity {:?} has no Position component", central_entity))?;
+    let central_mass = masses
+        .get(central_entity)
+        .ok_or_else(|| format!("Central entity {:?} has no Mass component", central_entity))?;
+
+    let rx = pos.x() - central_pos.x();
+    let ry = pos.y() - central_pos.y();
+    let rz = pos.z() - central_pos.z();
+    let radius = (rx * rx + ry * ry + rz * rz).sqrt();
+    if radius == 0.0 || !radius.is_finite() {
+        return Err(format!(
+            "Entity {:?} and central entity {:?} must be at distinct finite positions",
+            entity, central_entity
+        ));
+    }
+
+    // Perpendicular direction: z × r, falling back to x × r when r is along z
+    let (mut px, mut py, mut pz) = (-ry, rx, 0.0);
+    let mut p_len = (px * px + py * py).sqrt();
+    if p_len < 1e-12 * radius {
+        px = 0.0;
+        py = -rz;
+        pz = ry;
+        p_len = (py * py + pz * pz).sqrt();
+    }
+
+    let speed = circular_orbit_velocity(central_mass.value(), radius, g);
+    let scale = speed / p_len;
+    let central_vel = velocities.get(central_entity).copied().unwrap_or_else(Velocity::zero);
+
+    velocities.insert(
+        entity,
+        Velocity::new(
+            central_vel.dx() + px * scale,
+            central_vel.dy() + py * scale,
+            central_vel.dz() + pz * scale,
+        ),
+    );
+
+    Ok(speed)
+}
+
+/// Suggest a starting timestep from the system's shortest dynamical time
+///
+/// For every pair of bodies the encounter timescale is
+/// t = √(r_ε³ / (G·(m₁ + m₂))), where r_ε = √(r² + ε²) is the softened
+/// separation. This is the free-fall time of the pair up to a constant, and
+/// for close pairs it bottoms out at √(ε³ / (G·M)). The shortest one,
+/// scaled by `safety_factor`, is returned. Tighter and heavier pairs give
+/// smaller timesteps.
+///
+/// Unlike `Integrator::suggest_timestep()`, this needs no accelerations, so
+/// it can be called before the first force evaluation. It examines all
+/// O(N²) pairs, which is meant for picking a dt once, not for every step.
+///
+/// # Arguments
+///
+/// * `entities` - Entities to consider; those without a position or a
+///   positive mass are skipped
+/// * `positions` - Position component storage
+/// * `masses` - Mass component storage
+/// * `g` - Gravitational constant
+/// * `softening` - Softening length ε, as passed to `GravityPlugin::set_softening()`
+/// * `safety_factor` - Fraction of the shortest timescale to return, e.g. 0.01
+///
+/// # Returns
+///
+/// `None` when no pair constrains the timestep: fewer than two massive
+/// bodies, `g` of zero, or only coincident bodies without softening.
+///
+/// # Panics
+///
+/// Panics if `g` or `softening` is negative or not finite, or if
+/// `safety_factor` is not positive and finite.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::ecs::components::{Mass, Position};
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+/// use physics_engine::plugins::gravity::suggest_timestep;
+///
+/// let mut world = World::new();
+/// let (a, b) = (world.create_entity(), world.create_entity());
+/// let mut positions = HashMapStorage::<Position>::new();
+/// let mut masses = HashMapStorage::<Mass>::new();
+/// positions.insert(a, Position::new(0.0, 0.0, 0.0));
+/// positions.insert(b, Position::new(1.0, 0.0, 0.0));
+/// masses.insert(a, Mass::new(1.0));
+/// masses.insert(b, Mass::new(1.0));
+///
+/// // √(1 / 2) for unit separation and masses, G = 1
+/// let dt = suggest_timestep(&[a, b], &positions, &masses, 1.0, 0.0, 0.01).unwrap();
+/// assert!((dt - 0.01 * 0.5_f64.sqrt() as physics_engine::Real).abs() < 1e-6);
+/// ```
+pub fn suggest_timestep(
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    masses: &impl ComponentStorage<Component = Mass>,
+    g: Real,
+    softening: Real,
+    safety_factor: Real,
+) -> Option<Real> {
+    assert!(
+        g >= 0.0 && g.is_finite(),
+        "Gravitational constant must be non-negative and finite"
+    );
+    assert!(
+        softening >= 0.0 && softening.is_finite(),
+        "Softening must be non-negative and finite"
+    );
+    assert!(
+        safety_factor > 0.0 && safety_factor.is_finite(),
+        "Safety factor must be positive and finite"
+    );
+
+    let bodies: Vec<(&Position, Real)> = entities
+        .iter()
+        .filter_map(|&entity| {
+            let mass = masses.get(entity)?.value();
+            (mass > 0.0).then_some((positions.get(entity)?, mass))
+        })
+        .collect();
+
+    // Minimise r_ε³ / (G·M) and take the square root once at the end
+    let softening_squared = softening * softening;
+    let mut shortest_squared: Option<Real> = None;
+    for (i, (pos_i, mass_i)) in bodies.iter().enumerate() {
+        for (pos_j, mass_j) in &bodies[i + 1..] {
+            let dx = pos_j.x() - pos_i.x();
+            let dy = pos_j.y() - pos_i.y();
+            let dz = pos_j.z() - pos_i.z();
+            let r_squared = dx * dx + dy * dy + dz * dz + softening_squared;
+            let gm = g * (mass_i + mass_j);
+            if r_squared == 0.0 || gm == 0.0 {
+                continue;
+            }
+            let t_squared = r_squared * r_squared.sqrt() / gm;
+            if t_squared.is_finite() && shortest_squared.map_or(true, |shortest| t_squared < shortest) {
+                shortest_squared = Some(t_squared);
+            }
+        }
+    }
+
+    shortest_squared.map(|t_squared| safety_factor * t_squared.sqrt())
+}
 
 /// Gravitational force plugin configuration
 ///
@@ -110,17 +465,29 @@ pub const DEFAULT_SOFTENING: f64 = 1e3; // 1 km
 #[derive(Clone)]
 pub struct GravityPlugin {
     /// Gravitational constant (default: GRAVITATIONAL_CONSTANT)
-    g_constant: f64,
+    g_constant: Real,
     /// Softening factor to prevent singularities (default: DEFAULT_SOFTENING)
-    softening: f64,
+    softening: Real,
     /// Chunk size for parallel processing (0 = auto)
     chunk_size: usize,
     /// Whether to warn about invalid calculations
     warn_on_invalid: bool,
     /// Maximum expected force magnitude (for high-force warning suppression)
-    max_expected_force: f64,
+    max_expected_force: Real,
     /// Whether to warn about high forces exceeding max_expected_force
     warn_on_high_forces: bool,
+    /// Whether to derive softening from per-body Radius components
+    use_per_body_softening: bool,
+    /// Kernel used to apply the softening length
+    softening_kernel: SofteningKernel,
+    /// Distance from the center of mass beyond which the monopole is used
+    far_field_threshold: Option<Real>,
+    /// Axes (x, y, z) whose force components are zeroed
+    axis_mask: [bool; 3],
+    /// Whether pairwise forces are summed with compensation
+    compensated_summation: bool,
+    /// Whether sleeping bodies attract awake ones
+    sleeping_bodies_attract: bool,
 }
 
 impl GravityPlugin {
@@ -134,7 +501,7 @@ impl GravityPlugin {
     /// # Panics
     ///
     /// Panics if `g_constant` is negative or not finite.
-    pub fn new(g_constant: f64) -> Self {
+    pub fn new(g_constant: Real) -> Self {
         assert!(
             g_constant >= 0.0 && g_constant.is_finite(),
             "Gravitational constant must be non-negative and finite"
@@ -147,13 +514,19 @@ impl GravityPlugin {
             warn_on_invalid: true,
             max_expected_force: 1e10, // 10 billion Newtons default
             warn_on_high_forces: true,
+            use_per_body_softening: false,
+            softening_kernel: SofteningKernel::Plummer,
+            far_field_threshold: None,
+            axis_mask: [false; 3],
+            compensated_summation: false,
+            sleeping_bodies_attract: true,
         }
     }
 
     /// Create a gravity plugin with a scaled gravitational constant
     ///
     /// Useful for demonstration simulations where realistic G is too small.
-    pub fn with_scaled_g(scale_factor: f64) -> Self {
+    pub fn with_scaled_g(scale_factor: Real) -> Self {
         Self::new(GRAVITATIONAL_CONSTANT * scale_factor)
     }
 
@@ -174,7 +547,7 @@ impl GravityPlugin {
     /// # Panics
     ///
     /// Panics if `softening` is negative or not finite.
-    pub fn set_softening(&mut self, softening: f64) {
+    pub fn set_softening(&mut self, softening: Real) {
         assert!(
             softening >= 0.0 && softening.is_finite(),
             "Softening factor must be non-negative and finite"
@@ -183,10 +556,15 @@ impl GravityPlugin {
     }
 
     /// Get the current softening factor
-    pub fn softening(&self) -> f64 {
+    pub fn softening(&self) -> Real {
         self.softening
     }
 
+    /// Get the gravitational constant used by this plugin
+    pub fn gravitational_constant(&self) -> Real {
+        self.g_constant
+    }
+
     /// Set the chunk size for parallel processing
     ///
     /// Set to 0 for automatic determination based on thread count.
@@ -213,7 +591,7 @@ impl GravityPlugin {
     /// # Panics
     ///
     /// Panics if `max_force` is negative or not finite.
-    pub fn set_max_expected_force(&mut self, max_force: f64) {
+    pub fn set_max_expected_force(&mut self, max_force: Real) {
         assert!(
             max_force >= 0.0 && max_force.is_finite(),
             "Maximum expected force must be non-negative and finite"
@@ -222,7 +600,7 @@ impl GravityPlugin {
     }
 
     /// Get the current maximum expected force magnitude
-    pub fn max_expected_force(&self) -> f64 {
+    pub fn max_expected_force(&self) -> Real {
         self.max_expected_force
     }
 
@@ -236,36 +614,300 @@ impl GravityPlugin {
         self.warn_on_high_forces
     }
 
-    /// Compute gravitational force between two entities
+    /// Enable or disable per-body softening
+    ///
+    /// When enabled and radii are supplied (see `GravityOptions::with_radii()`),
+    /// each body's softening length is its `Radius`, and a pair uses
+    /// ε_ij = √(ε_i² + ε_j²). Bodies without a `Radius` fall back to the
+    /// global softening. When disabled, the global softening is used for
+    /// every pair.
+    pub fn set_use_per_body_softening(&mut self, enabled: bool) {
+        self.use_per_body_softening = enabled;
+    }
+
+    /// Check if per-body softening is enabled
+    pub fn use_per_body_softening(&self) -> bool {
+        self.use_per_body_softening
+    }
+
+    /// Set the softening kernel
+    ///
+    /// `SofteningKernel::Plummer` (the default) softens at every distance;
+    /// `SofteningKernel::Spline` is exactly Newtonian beyond twice the
+    /// softening length.
+    pub fn set_softening_kernel(&mut self, kernel: SofteningKernel) {
+        self.softening_kernel = kernel;
+    }
+
+    /// Get the softening kernel
+    pub fn softening_kernel(&self) -> SofteningKernel {
+        self.softening_kernel
+    }
+
+    /// Zero the force along masked axes
+    ///
+    /// `mask[i] == true` removes the x, y, or z component of every force.
+    /// Masking z keeps a planar simulation exactly planar: bodies that start
+    /// in the z = 0 plane with no z velocity never pick up z motion from
+    /// rounding noise or stray out-of-plane bodies.
+    pub fn set_axis_mask(&mut self, mask: [bool; 3]) {
+        self.axis_mask = mask;
+    }
+
+    /// Get the axis mask
+    pub fn axis_mask(&self) -> [bool; 3] {
+        self.axis_mask
+    }
+
+    /// Enable or disable compensated summation of pairwise forces
+    ///
+    /// When enabled, each body's total force is summed with Neumaier
+    /// (Kahan) compensation, so small contributions are not lost next to
+    /// large, cancelling ones. Disabled by default.
+    pub fn set_compensated_summation(&mut self, enabled: bool) {
+        self.compensated_summation = enabled;
+    }
+
+    /// Check if compensated summation is enabled
+    pub fn compensated_summation(&self) -> bool {
+        self.compensated_summation
+    }
+
+    /// Set whether sleeping bodies attract awake ones
+    ///
+    /// Only consulted when `GravityOptions::with_sleeping()` is used. When
+    /// disabled, awake bodies only feel each other, which is cheaper but
+    /// changes their trajectories as neighbors fall asleep. Enabled by default.
+    pub fn set_sleeping_bodies_attract(&mut self, enabled: bool) {
+        self.sleeping_bodies_attract = enabled;
+    }
+
+    /// Check if sleeping bodies attract awake ones
+    pub fn sleeping_bodies_attract(&self) -> bool {
+        self.sleeping_bodies_attract
+    }
+
+    /// Apply the axis mask to a force vector
+    pub(crate) fn mask_axes(&self, force: Force) -> Force {
+        let [mx, my, mz] = self.axis_mask;
+        Force::new(
+            if mx { 0.0 } else { force.fx },
+            if my { 0.0 } else { force.fy },
+            if mz { 0.0 } else { force.fz },
+        )
+    }
+
+    /// Use the monopole approximation for bodies far from the center of mass
+    ///
+    /// Bodies farther than `threshold_distance` from the system's center of
+    /// mass feel the rest of the system as a single point mass at its center
+    /// of mass, costing O(1) instead of O(N) per body. Bodies inside the
+    /// threshold still use the exact pairwise sum. Only worthwhile when the
+    /// far bodies are well outside the bulk of the mass; the error grows as
+    /// (system size / distance)². Always uses the global softening.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold_distance` is not positive and finite.
+    pub fn set_far_field_approximation(&mut self, threshold_distance: Real) {
+        assert!(
+            threshold_distance > 0.0 && threshold_distance.is_finite(),
+            "Far-field threshold must be positive and finite"
+        );
+        self.far_field_threshold = Some(threshold_distance);
+    }
+
+    /// Disable the far-field approximation, using exact sums for every body
+    pub fn clear_far_field_approximation(&mut self) {
+        self.far_field_threshold = None;
+    }
+
+    /// Get the far-field threshold distance, if the approximation is enabled
+    pub fn far_field_threshold(&self) -> Option<Real> {
+        self.far_field_threshold
+    }
+
+    /// Total force on `entity` from `sources`, looking bodies up with `body`
+    ///
+    /// `body` returns an entity's position and mass, so the storage-based and
+    /// SoA paths share one pair loop. With a `distribution`, an entity beyond
+    /// the far-field threshold gets the monopole instead of the exact sum,
+    /// which counts as one interaction; otherwise every other source visited
+    /// adds one to `interactions`. `radii` is only consulted when per-body
+    /// softening is enabled.
+    fn compute_force_on<B>(
+        &self,
+        entity: Entity,
+        body: &B,
+        radii: Option<&dyn ComponentStorage<Component = Radius>>,
+        sources: &[Entity],
+        distribution: Option<&MassDistribution>,
+        interactions: &AtomicUsize,
+    ) -> Option<Force>
+    where
+        B: Fn(Entity) -> Option<([Real; 3], Real)>,
+    {
+        // Immovable bodies don't experience forces
+        let (pos, m) = body(entity)?;
+        if m < Mass::IMMOVABLE_THRESHOLD {
+            return None;
+        }
+
+        if let (Some(threshold), Some(distribution)) = (self.far_field_threshold, distribution) {
+            let com = distribution.center_of_mass();
+            let d_sq: Real = (0..3).map(|i| (pos[i] - com[i]).powi(2)).sum();
+            if d_sq > threshold * threshold {
+                interactions.fetch_add(1, Ordering::Relaxed);
+                return self.compute_monopole_force(entity, pos, m, distribution);
+            }
+        }
+
+        let mut total_force = ForceSum::new(self.compensated_summation);
+        let mut count = 0;
+        for &other_entity in sources {
+            // Skip self-interaction
+            if other_entity == entity {
+                continue;
+            }
+            count += 1;
+            let (other_pos, other_m) = match body(other_entity) {
+                Some(other) => other,
+                None => continue,
+            };
+            let softening_squared = self.pair_softening_squared(entity, other_entity, radii);
+            if let Some(force) =
+                self.compute_pair_force(entity, other_entity, pos, other_pos, m, other_m, softening_squared)
+            {
+                total_force.add(&force);
+            }
+        }
+        interactions.fetch_add(count, Ordering::Relaxed);
+
+        total_force.total()
+    }
+
+    /// Force on a far-field entity from the rest of the system as a point mass
+    fn compute_monopole_force(
+        &self,
+        entity: Entity,
+        pos: [Real; 3],
+        m: Real,
+        distribution: &MassDistribution,
+    ) -> Option<Force> {
+        // Remove the entity's own contribution from the system moments
+        let rest_mass = distribution.total_mass() - m;
+        if rest_mass <= 0.0 {
+            return None;
+        }
+        let com = distribution.center_of_mass();
+        let rest_com: [Real; 3] =
+            std::array::from_fn(|i| (distribution.total_mass() * com[i] - m * pos[i]) / rest_mass);
+
+        let dx = rest_com[0] - pos[0];
+        let dy = rest_com[1] - pos[1];
+        let dz = rest_com[2] - pos[2];
+        let r_squared = dx * dx + dy * dy + dz * dz;
+        let softening_squared = self.softening * self.softening;
+        if r_squared + softening_squared == 0.0 {
+            return None;
+        }
+
+        let (_, force_scale) = self.softening_kernel.force_terms(
+            self.g_constant * m * rest_mass,
+            r_squared,
+            softening_squared,
+        );
+        let force = Force::new(force_scale * dx, force_scale * dy, force_scale * dz);
+        if !force.is_valid() {
+            if self.warn_on_invalid {
+                eprintln!("Warning: Invalid far-field force for {:?}", entity);
+            }
+            return None;
+        }
+        Some(self.mask_axes(force))
+    }
+
+    /// Squared softening length for a pair of entities
+    fn pair_softening_squared<R>(&self, entity1: Entity, entity2: Entity, radii: Option<&R>) -> Real
+    where
+        R: ComponentStorage<Component = Radius> + ?Sized,
+    {
+        match radii {
+            Some(radii) if self.use_per_body_softening => {
+                let eps1 = radii.get(entity1).map_or(self.softening, |r| r.value());
+                let eps2 = radii.get(entity2).map_or(self.softening, |r| r.value());
+                eps1 * eps1 + eps2 * eps2
+            }
+            _ => self.softening * self.softening,
+        }
+    }
+
+    /// Compute the gravitational force on `entity1` due to `entity2`
     ///
     /// Returns None if either entity is missing required components or if
-    /// the force calculation fails validation.
-    fn compute_pairwise_force(
+    /// the force calculation fails validation. `radii` is only consulted when
+    /// per-body softening is enabled.
+    ///
+    /// The force is computed the same way for every pair, including pairs
+    /// with an immovable body, so swapping the entities negates it exactly
+    /// (Newton's third law). Immovable bodies are skipped when forces are
+    /// accumulated, not here. The tests use it to check single pairs.
+    #[cfg(test)]
+    fn compute_pairwise_force<R>(
         &self,
         entity1: Entity,
         entity2: Entity,
         positions: &impl ComponentStorage<Component = Position>,
         masses: &impl ComponentStorage<Component = Mass>,
-    ) -> Option<Force> {
+        radii: Option<&R>,
+    ) -> Option<Force>
+    where
+        R: ComponentStorage<Component = Radius>,
+    {
         // Get components for both entities
         let pos1 = positions.get(entity1)?;
         let pos2 = positions.get(entity2)?;
         let mass1 = masses.get(entity1)?;
         let mass2 = masses.get(entity2)?;
 
-        // Skip immovable bodies (they don't experience forces)
-        if mass1.is_immovable() {
-            return None;
-        }
+        let softening_squared = self.pair_softening_squared(entity1, entity2, radii);
+        self.compute_pair_force(
+            entity1,
+            entity2,
+            pos1.as_array(),
+            pos2.as_array(),
+            mass1.value(),
+            mass2.value(),
+            softening_squared,
+        )
+    }
 
+    /// Gravitational force on body 1 from body 2, given their raw state
+    ///
+    /// Used for every pair on the storage-based and SoA paths alike, so both
+    /// produce identical forces. Every term is symmetric in the two bodies except the
+    /// displacement, so swapping them negates the force exactly. The caller
+    /// handles immovable bodies. `entity1` and `entity2` are only used in
+    /// warnings.
+    #[allow(clippy::too_many_arguments)]
+    fn compute_pair_force(
+        &self,
+        entity1: Entity,
+        entity2: Entity,
+        pos1: [Real; 3],
+        pos2: [Real; 3],
+        mass1: Real,
+        mass2: Real,
+        softening_squared: Real,
+    ) -> Option<Force> {
         // Calculate displacement vector from entity1 to entity2
-        let dx = pos2.x() - pos1.x();
-        let dy = pos2.y() - pos1.y();
-        let dz = pos2.z() - pos1.z();
+        let dx = pos2[0] - pos1[0];
+        let dy = pos2[1] - pos1[1];
+        let dz = pos2[2] - pos1[2];
 
         // Calculate distance squared with softening
         let r_squared = dx * dx + dy * dy + dz * dz;
-        let softened_r_squared = r_squared + self.softening * self.softening;
+        let softened_r_squared = r_squared + softening_squared;
 
         // Avoid division by exactly zero (though softening should prevent this)
         if softened_r_squared == 0.0 {
@@ -275,8 +917,14 @@ impl GravityPlugin {
             return None;
         }
 
-        // Calculate force magnitude: F = G * m1 * m2 / (r² + ε²)
-        let force_magnitude = self.g_constant * mass1.value() * mass2.value() / softened_r_squared;
+        // Calculate force magnitude, e.g. F = G * m1 * m2 / (r² + ε²) for Plummer.
+        // The masses are multiplied first so the product does not depend on
+        // which body is entity1.
+        let (force_magnitude, force_scale) = self.softening_kernel.force_terms(
+            self.g_constant * (mass1 * mass2),
+            r_squared,
+            softening_squared,
+        );
 
         // Validate force magnitude
         if !force_magnitude.is_finite() {
@@ -299,13 +947,9 @@ impl GravityPlugin {
 
         // Calculate force direction (unit vector * magnitude / distance)
         // F_vec = F_mag * (r_vec / |r|) = F_mag * r_vec / |r|
-        // Since F_mag = G*m1*m2/(r²+ε²), we need the unit vector: r_vec/|r|
-        // Where |r| = sqrt(r²+ε²) when using softening
-        // So: F_vec = [G*m1*m2/(r²+ε²)] * r_vec / sqrt(r²+ε²)
-        //           = G*m1*m2 * r_vec / (r²+ε²)^(3/2)
-        let r = softened_r_squared.sqrt();
-        let force_scale = force_magnitude / r;
-
+        // For Plummer, F_mag = G*m1*m2/(r²+ε²) and |r| = sqrt(r²+ε²), so:
+        //   F_vec = G*m1*m2 * r_vec / (r²+ε²)^(3/2)
+        // The kernel returns force_scale = F_vec / r_vec directly.
         let fx = force_scale * dx;
         let fy = force_scale * dy;
         let fz = force_scale * dz;
@@ -321,40 +965,7 @@ impl GravityPlugin {
             return None;
         }
 
-        Some(Force::new(fx, fy, fz))
-    }
-
-    /// Compute total gravitational force on an entity from all other entities
-    ///
-    /// This is called by the force registry to accumulate forces for each entity.
-    fn compute_force_for_entity(
-        &self,
-        entity: Entity,
-        positions: &impl ComponentStorage<Component = Position>,
-        masses: &impl ComponentStorage<Component = Mass>,
-        all_entities: &[Entity],
-    ) -> Option<Force> {
-        let mut total_force = Force::zero();
-        let mut has_force = false;
-
-        // Compute pairwise forces with all other entities
-        for &other_entity in all_entities {
-            // Skip self-interaction
-            if other_entity == entity {
-                continue;
-            }
-
-            if let Some(force) = self.compute_pairwise_force(entity, other_entity, positions, masses) {
-                total_force.add(&force);
-                has_force = true;
-            }
-        }
-
-        if has_force {
-            Some(total_force)
-        } else {
-            None
-        }
+        Some(self.mask_axes(Force::new(fx, fy, fz)))
     }
 }
 
@@ -393,7 +1004,7 @@ impl ForceProvider for GravityPlugin {
         // knowledge of ALL entities in the system (N-body problem). The generic
         // ForceProvider interface only provides access to a single entity at a time.
         //
-        // Instead, use GravitySystem::compute_forces() which efficiently computes
+        // Instead, use GravitySystem::compute_forces_into() which efficiently computes
         // all pairwise gravitational interactions in a single pass.
         //
         // This trait implementation is provided for API compatibility but is not
@@ -421,7 +1032,7 @@ impl crate::plugins::api::WorldAwareForceProvider for GravityPlugin {
         _force_registry: &mut ForceRegistry,
     ) -> Result<usize, String> {
         // TODO: Complete implementation to access World's component storage
-        // For now, users should use GravitySystem::compute_forces() directly
+        // For now, users should use GravitySystem::compute_forces_into() directly
         // which provides the same functionality with explicit component storage access.
         //
         // Future implementation would:
@@ -429,16 +1040,147 @@ impl crate::plugins::api::WorldAwareForceProvider for GravityPlugin {
         // 2. Call GravitySystem with those components
         // 3. Register computed forces with force_registry
         
-        Err("WorldAwareForceProvider not yet implemented for GravityPlugin - use GravitySystem::compute_forces() instead".to_string())
+        Err("WorldAwareForceProvider not yet implemented for GravityPlugin - use GravitySystem::compute_forces_into() instead".to_string())
     }
 }
 
+/// Error returned by `GravitySystem::compute_forces_with_progress()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GravityError {
+    /// The cancel flag was set before every chunk of bodies was computed
+    Cancelled,
+}
+
+impl fmt::Display for GravityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GravityError::Cancelled => write!(f, "Gravity computation was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for GravityError {}
+
+/// Per-call options for `GravitySystem` force computations
+///
+/// The options combine freely, and every entry point that takes a
+/// `GravityOptions` applies them the same way:
+///
+/// - `with_radii()` gives each body with a `Radius` its own softening
+///   length, when per-body softening is enabled on the plugin
+/// - `with_tracers()` leaves bodies with a `Tracer` out as sources: they
+///   feel every other body but pull on nothing, and always use the exact sum
+/// - `with_sleeping()` spares bodies asleep in a `ForceRegistry` the full
+///   sum: they only feel awake bodies, exactly, and attract awake bodies
+///   only if `GravityPlugin::sleeping_bodies_attract()` is set
+///
+/// The default enables none of them. Options are the only way to ask for
+/// these behaviors: there are no per-option variants of the compute
+/// methods, so a new option reaches every entry point at once.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::Real;
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+/// use physics_engine::ecs::components::{Mass, Position, Radius};
+/// use physics_engine::ecs::systems::ForceRegistry;
+/// use physics_engine::plugins::gravity::{GravityOptions, GravityPlugin, GravitySystem, Tracer};
+/// use std::collections::HashMap;
+///
+/// let mut world = World::new();
+/// let entities = [world.create_entity(), world.create_entity(), world.create_entity()];
+/// let mut positions = HashMapStorage::<Position>::new();
+/// let mut masses = HashMapStorage::<Mass>::new();
+/// for (i, &entity) in entities.iter().enumerate() {
+///     positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
+///     masses.insert(entity, Mass::new(1.0));
+/// }
+/// let mut radii = HashMapStorage::<Radius>::new();
+/// radii.insert(entities[0], Radius::new(0.1));
+/// let mut tracers = HashMapStorage::<Tracer>::new();
+/// tracers.insert(entities[2], Tracer);
+/// let mut registry = ForceRegistry::new();
+/// registry.put_to_sleep(entities[1]);
+///
+/// let options = GravityOptions::new()
+///     .with_radii(&radii)
+///     .with_tracers(&tracers)
+///     .with_sleeping(&registry);
+/// let system = GravitySystem::new(GravityPlugin::new(1.0));
+/// let mut forces = HashMap::new();
+/// system.compute_forces_into_with(&entities, &positions, &masses, options, &mut forces);
+/// // The sleeping body only feels the awake non-tracer
+/// assert!(forces[&entities[1]].fx < 0.0);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct GravityOptions<'a> {
+    radii: Option<&'a dyn ComponentStorage<Component = Radius>>,
+    tracers: Option<&'a dyn ComponentStorage<Component = Tracer>>,
+    sleeping: Option<&'a ForceRegistry>,
+}
+
+impl<'a> GravityOptions<'a> {
+    /// Create options with nothing enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Soften each pair with the bodies' `Radius`, if per-body softening is enabled
+    ///
+    /// Bodies without a `Radius` use the global softening.
+    pub fn with_radii(mut self, radii: &'a dyn ComponentStorage<Component = Radius>) -> Self {
+        self.radii = Some(radii);
+        self
+    }
+
+    /// Treat bodies with a `Tracer` as receivers only
+    pub fn with_tracers(mut self, tracers: &'a dyn ComponentStorage<Component = Tracer>) -> Self {
+        self.tracers = Some(tracers);
+        self
+    }
+
+    /// Spare bodies asleep in `registry` the full force sum
+    ///
+    /// Registering the results wakes a sleeping body when the force from the
+    /// awake ones exceeds `ForceRegistry::wake_force_threshold()`.
+    pub fn with_sleeping(mut self, registry: &'a ForceRegistry) -> Self {
+        self.sleeping = Some(registry);
+        self
+    }
+
+    fn is_tracer(&self, entity: Entity) -> bool {
+        self.tracers.is_some_and(|tracers| tracers.contains(entity))
+    }
+
+    fn is_sleeping(&self, entity: Entity) -> bool {
+        self.sleeping.is_some_and(|registry| registry.is_sleeping(entity))
+    }
+}
+
+/// Progress callback and cancel flag of `compute_forces_with_progress()`
+type ProgressReport<'p> = (&'p (dyn Fn(usize, usize) + Sync), &'p AtomicBool);
+
+/// Source lists of one force computation, resolved from its options
+struct SourcePlan<'e> {
+    /// Sources of awake targets: no tracers, and no sleeping bodies unless
+    /// they attract
+    sources: Cow<'e, [Entity]>,
+    /// Sources of sleeping targets, the awake non-tracers, if they differ
+    /// from `sources`
+    awake_sources: Option<Vec<Entity>>,
+    /// Moments of `sources` for the far-field approximation, if enabled
+    distribution: Option<MassDistribution>,
+}
+
 /// Specialized system for computing gravitational forces efficiently
 ///
 /// This provides a more efficient implementation than the generic ForceProvider
 /// interface by computing all pairwise forces in a single pass.
 pub struct GravitySystem {
     plugin: Arc<GravityPlugin>,
+    /// Interactions evaluated by the last computation, summed across threads
+    interactions: AtomicUsize,
 }
 
 impl GravitySystem {
@@ -446,13 +1188,33 @@ impl GravitySystem {
     pub fn new(plugin: GravityPlugin) -> Self {
         GravitySystem {
             plugin: Arc::new(plugin),
+            interactions: AtomicUsize::new(0),
         }
     }
 
+    /// Get the plugin configuration this system computes forces with
+    pub fn plugin(&self) -> &GravityPlugin {
+        &self.plugin
+    }
+
+    /// Number of interactions evaluated by the last force computation
+    ///
+    /// Each other entity visited by an exact sum counts as one interaction,
+    /// as does each far-field monopole evaluation. Exact summation over `N` movable
+    /// bodies therefore reports `N·(N−1)`, and the far-field approximation
+    /// reports fewer. Immovable bodies evaluate nothing. Compare with
+    /// `BarnesHutSystem::interaction_count()` to see how much work an
+    /// approximation saves.
+    pub fn last_interaction_count(&self) -> usize {
+        self.interactions.load(Ordering::Relaxed)
+    }
+
     /// Compute gravitational forces for all entities and accumulate in registry
     ///
-    /// This efficiently computes N-body gravitational interactions using
-    /// parallel processing when available.
+    /// Registers one boxed provider per entity, so forces are only readable
+    /// after calling `accumulate_for_entity()` for each entity. With fewer
+    /// than two entities there is nothing to attract, and the registry is
+    /// left untouched.
     ///
     /// # Arguments
     ///
@@ -464,6 +1226,10 @@ impl GravitySystem {
     /// # Returns
     ///
     /// Number of entities that had gravitational forces computed
+    #[deprecated(
+        since = "0.2.0",
+        note = "use `compute_forces_into()` and `ForceRegistry::register_forces()` instead"
+    )]
     pub fn compute_forces(
         &self,
         entities: &[Entity],
@@ -471,94 +1237,648 @@ impl GravitySystem {
         masses: &impl ComponentStorage<Component = Mass>,
         force_registry: &mut ForceRegistry,
     ) -> usize {
-        #[cfg(feature = "parallel")]
-        {
-            self.compute_forces_parallel(entities, positions, masses, force_registry)
-        }
+        let forces = self.compute_all(entities, positions, masses, GravityOptions::new());
 
-        #[cfg(not(feature = "parallel"))]
-        {
-            self.compute_forces_sequential(entities, positions, masses, force_registry)
+        let mut count = 0;
+        for (&entity, force) in entities.iter().zip(forces) {
+            if let Some(force) = force {
+                force_registry.register_provider(Box::new(SimpleForceProvider::new(entity, force)));
+                count += 1;
+            }
         }
-    }
 
-    #[cfg(feature = "parallel")]
-    fn compute_forces_parallel(
-        &self,
-        entities: &[Entity],
-        positions: &impl ComponentStorage<Component = Position>,
-        masses: &impl ComponentStorage<Component = Mass>,
-        force_registry: &mut ForceRegistry,
-    ) -> usize {
-        use std::collections::HashMap;
+        count
+    }
 
-        let plugin = &self.plugin;
+    /// Compute gravitational forces for all entities into an output map
+    ///
+    /// This efficiently computes N-body gravitational interactions using
+    /// parallel processing when available. `out` is cleared first, then holds
+    /// the net force for every entity that experienced gravity. Immovable
+    /// entities and entities missing components are left out.
+    ///
+    /// The forces are final; no `accumulate_for_entity()` pass is needed.
+    /// To feed them to an integrator, use `ForceRegistry::register_forces()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - Slice of all entities to consider
+    /// * `positions` - Position component storage
+    /// * `masses` - Mass component storage
+    /// * `out` - Map to fill with per-entity forces
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had gravitational forces computed
+    pub fn compute_forces_into(
+        &self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        out: &mut HashMap<Entity, Force>,
+    ) -> usize {
+        self.compute_forces_into_with(entities, positions, masses, GravityOptions::new(), out)
+    }
 
-        // Compute forces in parallel chunks
-        let chunk_size = if plugin.chunk_size > 0 {
-            plugin.chunk_size
-        } else {
-            (entities.len() / 4).max(1)
-        };
+    /// Compute gravitational forces into an output map with the given options
+    ///
+    /// Same as `compute_forces_into()`, with radii, tracers and sleeping
+    /// bodies handled as `options` asks; see `GravityOptions`. With the
+    /// default options the result is identical to `compute_forces_into()`.
+    pub fn compute_forces_into_with(
+        &self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        options: GravityOptions<'_>,
+        out: &mut HashMap<Entity, Force>,
+    ) -> usize {
+        self.compute_forces_on(entities, entities, positions, masses, options, out)
+    }
 
-        // Use Rayon's fold-reduce pattern to improve efficiency.
-        // Each thread accumulates forces into a single HashMap,
-        // then we reduce all HashMaps into a final one.
-        let forces = entities
-            .par_chunks(chunk_size)
-            .fold(HashMap::new, |mut local_forces, chunk| {
-                for &entity in chunk {
-                    if let Some(force) = plugin.compute_force_for_entity(entity, positions, masses, entities) {
-                        local_forces.insert(entity, force);
-                    }
-                }
-                local_forces
-            })
-            .reduce(HashMap::new, |mut acc, local_forces| {
-                acc.extend(local_forces);
-                acc
-            });
+    /// Compute the gravitational forces on `targets` from `sources` into an output map
+    ///
+    /// Same as `compute_forces_into_with()`, except that only the entities in
+    /// `targets` receive a force, from every entity in `sources` other than
+    /// themselves. The cost is O(targets · sources) instead of O(N²), for
+    /// callers that only need some bodies' forces, like
+    /// `HierarchicalStepper` stepping one timestep class. The far-field
+    /// distribution, if enabled, is that of `sources`.
+    ///
+    /// With `targets` equal to `sources` the result is identical to
+    /// `compute_forces_into_with()`.
+    pub fn compute_forces_on(
+        &self,
+        targets: &[Entity],
+        sources: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        options: GravityOptions<'_>,
+        out: &mut HashMap<Entity, Force>,
+    ) -> usize {
+        let forces = self.compute(targets, sources, &storage_body(positions, masses), options);
+        collect_into(targets, forces, out)
+    }
 
-        // Accumulate forces in registry
-        let count = forces.len();
-        
-        for (entity, force) in forces {
-            force_registry.register_provider(Box::new(SimpleForceProvider::new(entity, force)));
+    /// Compute gravitational forces into an output map, reporting progress
+    ///
+    /// Same as `compute_forces_into_with()`, but the bodies are processed in
+    /// chunks of the plugin's chunk size. After each chunk, `progress` is
+    /// called with the number of bodies finished so far and the total. Calls
+    /// come from worker threads when the `parallel` feature is enabled, but
+    /// never overlap, so the reported counts increase strictly and the last
+    /// one equals `entities.len()`.
+    ///
+    /// `cancel` is checked before each chunk starts. Chunks already running
+    /// when it is set still finish, then the computation stops, `out` is
+    /// cleared and `GravityError::Cancelled` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GravityError::Cancelled` if `cancel` was set before every
+    /// chunk had started, discarding any partial results.
+    #[allow(clippy::too_many_arguments)]
+    pub fn compute_forces_with_progress(
+        &self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        options: GravityOptions<'_>,
+        out: &mut HashMap<Entity, Force>,
+        progress: &(dyn Fn(usize, usize) + Sync),
+        cancel: &AtomicBool,
+    ) -> Result<usize, GravityError> {
+        out.clear();
+        if cancel.load(Ordering::Relaxed) {
+            self.interactions.store(0, Ordering::Relaxed);
+            return Err(GravityError::Cancelled);
         }
+        let body = storage_body(positions, masses);
+        let plan = self.plan(entities, &body, options);
+        let forces = self.compute_with(entities, entities, &body, options, &plan, Some((progress, cancel)))?;
+        Ok(collect_into(entities, forces, out))
+    }
 
-        count
+    /// Compute gravitational forces for the bodies of a `NewtonianState`
+    ///
+    /// Bundle form of `compute_forces_into()`, reading the state's positions
+    /// and masses.
+    pub fn compute_forces_for_state<P, V, A, M>(
+        &self,
+        entities: &[Entity],
+        state: &NewtonianState<P, V, A, M>,
+        out: &mut HashMap<Entity, Force>,
+    ) -> usize
+    where
+        P: ComponentStorage<Component = Position>,
+        V: ComponentStorage<Component = Velocity>,
+        A: ComponentStorage<Component = Acceleration>,
+        M: ComponentStorage<Component = Mass>,
+    {
+        self.compute_forces_into(entities, state.positions(), state.masses(), out)
     }
 
-    #[cfg(not(feature = "parallel"))]
-    fn compute_forces_sequential(
+    /// Compute gravitational forces into a dense slice aligned with `entities`
+    ///
+    /// `out[i]` receives the net force on `entities[i]`, or a zero force if the
+    /// entity is immovable or missing components. This avoids hashing entirely
+    /// and pairs naturally with SoA storages indexed in the same order.
+    /// `options` applies as in `compute_forces_into_with()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out.len() != entities.len()`.
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had gravitational forces computed
+    pub fn compute_forces_into_slice(
         &self,
         entities: &[Entity],
         positions: &impl ComponentStorage<Component = Position>,
         masses: &impl ComponentStorage<Component = Mass>,
-        force_registry: &mut ForceRegistry,
+        options: GravityOptions<'_>,
+        out: &mut [Force],
     ) -> usize {
-        let plugin = &self.plugin;
+        assert_eq!(
+            out.len(),
+            entities.len(),
+            "Output slice length must match entity count"
+        );
+        let forces = self.compute_all(entities, positions, masses, options);
+
         let mut count = 0;
+        for (slot, force) in out.iter_mut().zip(forces) {
+            *slot = force.unwrap_or_else(Force::zero);
+            count += usize::from(force.is_some());
+        }
+        count
+    }
 
-        for &entity in entities {
-            if let Some(force) = plugin.compute_force_for_entity(entity, positions, masses, entities) {
-                force_registry.register_provider(Box::new(SimpleForceProvider::new(entity, force)));
-                count += 1;
+    /// Compute gravitational forces from true SoA storages and register them
+    ///
+    /// `PositionSoAStorage` and `MassSoAStorage` cannot hand out component
+    /// references, so this path reads their field arrays directly, mapping
+    /// each entity to its dense index with `get_index()`. The two storages
+    /// need not be aligned. Forces match `compute_forces_into_with()` for the
+    /// same bodies and options, and are registered in `entities` order with
+    /// `ForceRegistry::register_ordered_forces()`. Sleeping bodies are those
+    /// asleep in `force_registry`; a sleeping registry set in `options` is
+    /// ignored. With fewer than two entities the registry is left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - Slice of all entities to consider
+    /// * `positions` - Position SoA storage
+    /// * `masses` - Mass SoA storage
+    /// * `options` - Radii and tracers to honor
+    /// * `force_registry` - Registry to accumulate forces, whose sleeping
+    ///   bodies are spared the full sum
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had gravitational forces computed
+    pub fn compute_forces_soa(
+        &self,
+        entities: &[Entity],
+        positions: &PositionSoAStorage,
+        masses: &MassSoAStorage,
+        options: GravityOptions<'_>,
+        force_registry: &mut ForceRegistry,
+    ) -> usize {
+        self.interactions.store(0, Ordering::Relaxed);
+        if entities.len() < 2 {
+            return 0;
+        }
+        let (px, py, pz) = match positions.field_arrays() {
+            Some(arrays) => arrays.as_position_arrays(),
+            None => return 0,
+        };
+        let mass_values = match masses.field_arrays() {
+            Some(arrays) => arrays.as_mass_array(),
+            None => return 0,
+        };
+        let body = |entity: Entity| {
+            let p = positions.get_index(entity)?;
+            let m = masses.get_index(entity)?;
+            Some(([px[p], py[p], pz[p]], mass_values[m]))
+        };
+
+        let options = options.with_sleeping(force_registry);
+        let forces: Vec<(Entity, Force)> = entities
+            .iter()
+            .zip(self.compute(entities, entities, &body, options))
+            .filter_map(|(&entity, force)| force.map(|force| (entity, force)))
+            .collect();
+
+        force_registry.register_ordered_forces(&forces)
+    }
+
+    /// Forces on every entity from every other, from component storages
+    ///
+    /// `result[i]` holds the force on `entities[i]`.
+    fn compute_all(
+        &self,
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        options: GravityOptions<'_>,
+    ) -> Vec<Option<Force>> {
+        self.compute(entities, entities, &storage_body(positions, masses), options)
+    }
+
+    /// Forces on `targets` from `sources` without progress reports
+    ///
+    /// `result[i]` holds the force on `targets[i]`.
+    fn compute<B>(&self, targets: &[Entity], sources: &[Entity], body: &B, options: GravityOptions<'_>) -> Vec<Option<Force>>
+    where
+        B: Fn(Entity) -> Option<([Real; 3], Real)> + Sync,
+    {
+        let plan = self.plan(sources, body, options);
+        match self.compute_with(targets, sources, body, options, &plan, None) {
+            Ok(forces) => forces,
+            Err(GravityError::Cancelled) => unreachable!("Only progress reports can cancel"),
+        }
+    }
+
+    /// Resolve the source lists and far-field moments for `options`
+    ///
+    /// Borrows `sources` unchanged when no tracer or sleeping body needs
+    /// filtering out, so the default options allocate nothing here.
+    fn plan<'e, B>(&self, sources: &'e [Entity], body: &B, options: GravityOptions<'_>) -> SourcePlan<'e>
+    where
+        B: Fn(Entity) -> Option<([Real; 3], Real)> + Sync,
+    {
+        let sleepers_attract = self.plugin.sleeping_bodies_attract;
+        let excluded = |entity: Entity| {
+            options.is_tracer(entity) || (!sleepers_attract && options.is_sleeping(entity))
+        };
+        let sources: Cow<'e, [Entity]> = if sources.iter().any(|&entity| excluded(entity)) {
+            Cow::Owned(sources.iter().copied().filter(|&entity| !excluded(entity)).collect())
+        } else {
+            Cow::Borrowed(sources)
+        };
+        let awake_sources = if sleepers_attract && sources.iter().any(|&entity| options.is_sleeping(entity)) {
+            Some(sources.iter().copied().filter(|&entity| !options.is_sleeping(entity)).collect())
+        } else {
+            None
+        };
+        let distribution = self
+            .plugin
+            .far_field_threshold
+            .map(|_| MassDistribution::compute_from(&sources, body));
+
+        SourcePlan { sources, awake_sources, distribution }
+    }
+
+    /// Evaluate the force on every target, in parallel when enabled
+    ///
+    /// The one pair loop behind every entry point. Each target's sources
+    /// and far-field eligibility follow from `options` through `plan`:
+    /// sleeping targets sum over the awake sources, and sleeping and tracer
+    /// targets always use the exact sum. `result[i]` holds the force on
+    /// `targets[i]`. Each chunk writes its own slice of the buffer, so the
+    /// result is independent of scheduling.
+    ///
+    /// With `progress`, each finished chunk is reported and the cancel flag
+    /// is checked before each chunk starts.
+    fn compute_with<B>(
+        &self,
+        targets: &[Entity],
+        sources: &[Entity],
+        body: &B,
+        options: GravityOptions<'_>,
+        plan: &SourcePlan<'_>,
+        progress: Option<ProgressReport<'_>>,
+    ) -> Result<Vec<Option<Force>>, GravityError>
+    where
+        B: Fn(Entity) -> Option<([Real; 3], Real)> + Sync,
+    {
+        self.interactions.store(0, Ordering::Relaxed);
+        let total = targets.len();
+        let mut forces = vec![None; total];
+        if total == 0 || sources.len() < 2 {
+            return Ok(forces);
+        }
+
+        let force_on = |entity: Entity| {
+            let sleeping = options.is_sleeping(entity);
+            let exact_only = sleeping || options.is_tracer(entity);
+            let sources = match &plan.awake_sources {
+                Some(awake) if sleeping => awake,
+                _ => &plan.sources[..],
+            };
+            let distribution = if exact_only { None } else { plan.distribution.as_ref() };
+            self.plugin
+                .compute_force_on(entity, body, options.radii, sources, distribution, &self.interactions)
+        };
+        // Serializes the callback so reported counts never go backwards
+        let done = Mutex::new(0);
+        let fill_chunk = |entity_chunk: &[Entity], out_chunk: &mut [Option<Force>]| {
+            if let Some((_, cancel)) = progress {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+            for (&entity, slot) in entity_chunk.iter().zip(out_chunk.iter_mut()) {
+                *slot = force_on(entity);
+            }
+            let mut done = done.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            *done += entity_chunk.len();
+            if let Some((report, _)) = progress {
+                report(*done, total);
             }
+        };
+
+        let chunk_size = self.chunk_size(total);
+        #[cfg(feature = "parallel")]
+        {
+            targets
+                .par_chunks(chunk_size)
+                .zip(forces.par_chunks_mut(chunk_size))
+                .for_each(|(entity_chunk, out_chunk)| fill_chunk(entity_chunk, out_chunk));
         }
 
-        count
+        #[cfg(not(feature = "parallel"))]
+        {
+            for (entity_chunk, out_chunk) in targets.chunks(chunk_size).zip(forces.chunks_mut(chunk_size)) {
+                fill_chunk(entity_chunk, out_chunk);
+            }
+        }
+
+        if done.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()) < total {
+            return Err(GravityError::Cancelled);
+        }
+        Ok(forces)
+    }
+
+    /// Chunk size for parallel work splitting and progress reports
+    ///
+    /// Never zero, as `par_chunks()` requires, even for empty entity slices.
+    fn chunk_size(&self, entity_count: usize) -> usize {
+        if self.plugin.chunk_size > 0 {
+            self.plugin.chunk_size
+        } else {
+            (entity_count / 4).max(1)
+        }
+    }
+}
+
+/// Position and mass lookup over component storages
+fn storage_body<'s>(
+    positions: &'s impl ComponentStorage<Component = Position>,
+    masses: &'s impl ComponentStorage<Component = Mass>,
+) -> impl Fn(Entity) -> Option<([Real; 3], Real)> + Sync + 's {
+    move |entity| Some((positions.get(entity)?.as_array(), masses.get(entity)?.value()))
+}
+
+/// Fill `out` with the forces computed for `targets`, returning how many there were
+fn collect_into(targets: &[Entity], forces: Vec<Option<Force>>, out: &mut HashMap<Entity, Force>) -> usize {
+    out.clear();
+    out.extend(
+        targets
+            .iter()
+            .zip(forces)
+            .filter_map(|(&entity, force)| force.map(|force| (entity, force))),
+    );
+    out.len()
+}
+
+/// Mass moments of a set of bodies, computed once per step
+///
+/// Holds the total mass, center of mass, and second moment about the center
+/// of mass, Σ mᵢ (rᵢ − c)(rᵢ − c)ᵀ. These are what far-field approximations
+/// need: the monopole is the total mass at the center of mass, and the
+/// second moment bounds the error of treating the system as a point.
+///
+/// Sums use compensated (Neumaier) summation, so the result does not degrade
+/// with the number of bodies. With the `parallel` feature, bodies are summed
+/// in fixed-size chunks on the Rayon pool and the partial sums are combined
+/// in chunk order, so the result is identical from run to run.
+///
+/// Only mass values are used, so immovable (zero-mass) bodies contribute
+/// nothing. Entities missing a position or mass are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+/// use physics_engine::ecs::components::{Mass, Position};
+/// use physics_engine::plugins::gravity::MassDistribution;
+///
+/// let mut world = World::new();
+/// let entities = [world.create_entity(), world.create_entity()];
+/// let mut positions = HashMapStorage::<Position>::new();
+/// let mut masses = HashMapStorage::<Mass>::new();
+/// positions.insert(entities[0], Position::new(0.0, 0.0, 0.0));
+/// positions.insert(entities[1], Position::new(4.0, 0.0, 0.0));
+/// masses.insert(entities[0], Mass::new(3.0));
+/// masses.insert(entities[1], Mass::new(1.0));
+///
+/// let distribution = MassDistribution::compute(&entities, &positions, &masses);
+/// assert_eq!(distribution.total_mass(), 4.0);
+/// assert_eq!(distribution.center_of_mass(), [1.0, 0.0, 0.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassDistribution {
+    total_mass: Real,
+    center_of_mass: [Real; 3],
+    second_moment: [[Real; 3]; 3],
+}
+
+impl MassDistribution {
+    /// Compute the mass moments of `entities`
+    ///
+    /// With zero total mass, the center of mass and second moment are zero.
+    pub fn compute(
+        entities: &[Entity],
+        positions: &impl ComponentStorage<Component = Position>,
+        masses: &impl ComponentStorage<Component = Mass>,
+    ) -> Self {
+        Self::compute_from(entities, |entity| {
+            Some((positions.get(entity)?.as_array(), masses.get(entity)?.value()))
+        })
+    }
+
+    /// Compute the mass moments of `entities`, looking up each body's
+    /// position and mass with `body`
+    pub(crate) fn compute_from<F>(entities: &[Entity], body: F) -> Self
+    where
+        F: Fn(Entity) -> Option<([Real; 3], Real)> + Sync,
+    {
+        // First pass: total mass and mass-weighted position
+        let [m, mx, my, mz] = compensated_sums(entities, |entity| {
+            let (pos, m) = body(entity)?;
+            Some([m, m * pos[0], m * pos[1], m * pos[2]])
+        });
+        if m <= 0.0 {
+            return MassDistribution {
+                total_mass: 0.0,
+                center_of_mass: [0.0; 3],
+                second_moment: [[0.0; 3]; 3],
+            };
+        }
+        let com = [mx / m, my / m, mz / m];
+
+        // Second pass about the center of mass, avoiding the cancellation of
+        // Σ m r rᵀ − M c cᵀ
+        let [xx, xy, xz, yy, yz, zz] = compensated_sums(entities, |entity| {
+            let (pos, m) = body(entity)?;
+            let d = [pos[0] - com[0], pos[1] - com[1], pos[2] - com[2]];
+            Some([
+                m * d[0] * d[0],
+                m * d[0] * d[1],
+                m * d[0] * d[2],
+                m * d[1] * d[1],
+                m * d[1] * d[2],
+                m * d[2] * d[2],
+            ])
+        });
+
+        MassDistribution {
+            total_mass: m,
+            center_of_mass: com,
+            second_moment: [[xx, xy, xz], [xy, yy, yz], [xz, yz, zz]],
+        }
+    }
+
+    /// Get the total mass (kg)
+    pub fn total_mass(&self) -> Real {
+        self.total_mass
+    }
+
+    /// Get the center of mass (m)
+    pub fn center_of_mass(&self) -> [Real; 3] {
+        self.center_of_mass
+    }
+
+    /// Get the second moment about the center of mass (kg⋅m²)
+    ///
+    /// The symmetric tensor Σ mᵢ (rᵢ − c)(rᵢ − c)ᵀ. Its trace is the sum of
+    /// mᵢ|rᵢ − c|², and the moment of inertia tensor is I = tr(S)·𝟙 − S.
+    pub fn second_moment(&self) -> [[Real; 3]; 3] {
+        self.second_moment
+    }
+}
+
+/// Running sum with Neumaier compensation for lost low-order bits
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct CompensatedSum {
+    sum: Real,
+    compensation: Real,
+}
+
+impl CompensatedSum {
+    /// Add a value to the sum
+    pub(crate) fn add(&mut self, value: Real) {
+        let total = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - total) + value;
+        } else {
+            self.compensation += (value - total) + self.sum;
+        }
+        self.sum = total;
+    }
+
+    /// Fold another partial sum into this one
+    pub(crate) fn merge(&mut self, other: CompensatedSum) {
+        self.add(other.sum);
+        self.compensation += other.compensation;
+    }
+
+    /// Get the compensated total
+    pub(crate) fn value(self) -> Real {
+        self.sum + self.compensation
     }
 }
 
+/// Total of pairwise forces on one body, optionally compensated
+///
+/// The naive path is plain `Force::add`, so results are bit-identical to
+/// summing forces directly when compensation is disabled.
+struct ForceSum {
+    naive: Force,
+    compensated: Option<[CompensatedSum; 3]>,
+    has_force: bool,
+}
+
+impl ForceSum {
+    fn new(compensated: bool) -> Self {
+        ForceSum {
+            naive: Force::zero(),
+            compensated: compensated.then(|| [CompensatedSum::default(); 3]),
+            has_force: false,
+        }
+    }
+
+    fn add(&mut self, force: &Force) {
+        match &mut self.compensated {
+            Some([x, y, z]) => {
+                x.add(force.fx);
+                y.add(force.fy);
+                z.add(force.fz);
+            }
+            None => self.naive.add(force),
+        }
+        self.has_force = true;
+    }
+
+    /// Get the total, or None if no forces were added
+    fn total(self) -> Option<Force> {
+        if !self.has_force {
+            return None;
+        }
+        Some(match self.compensated {
+            Some([x, y, z]) => Force::new(x.value(), y.value(), z.value()),
+            None => self.naive,
+        })
+    }
+}
+
+/// Bodies per partial sum in `compensated_sums`
+const MOMENT_CHUNK_SIZE: usize = 1024;
+
+/// Sum `N` per-entity terms with compensation, in parallel when available
+///
+/// Partial sums are combined in chunk order so the result is deterministic.
+fn compensated_sums<const N: usize, F>(entities: &[Entity], terms: F) -> [Real; N]
+where
+    F: Fn(Entity) -> Option<[Real; N]> + Sync,
+{
+    let sum_chunk = |chunk: &[Entity]| {
+        let mut sums = [CompensatedSum::default(); N];
+        for &entity in chunk {
+            if let Some(values) = terms(entity) {
+                for (sum, value) in sums.iter_mut().zip(values) {
+                    sum.add(value);
+                }
+            }
+        }
+        sums
+    };
+
+    #[cfg(feature = "parallel")]
+    let partials: Vec<[CompensatedSum; N]> = entities.par_chunks(MOMENT_CHUNK_SIZE).map(sum_chunk).collect();
+    #[cfg(not(feature = "parallel"))]
+    let partials: Vec<[CompensatedSum; N]> = entities.chunks(MOMENT_CHUNK_SIZE).map(sum_chunk).collect();
+
+    let mut totals = [CompensatedSum::default(); N];
+    for partial in partials {
+        for (total, sum) in totals.iter_mut().zip(partial) {
+            total.merge(sum);
+        }
+    }
+    totals.map(CompensatedSum::value)
+}
+
 /// Simple force provider that returns a pre-computed force for a specific entity
-struct SimpleForceProvider {
+pub(crate) struct SimpleForceProvider {
     target_entity: Entity,
     force: Force,
 }
 
 impl SimpleForceProvider {
-    fn new(entity: Entity, force: Force) -> Self {
+    pub(crate) fn new(entity: Entity, force: Force) -> Self {
         SimpleForceProvider {
             target_entity: entity,
             force,
@@ -583,9 +1903,14 @@ impl ForceProvider for SimpleForceProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::real::RealExt;
     use crate::ecs::{World, HashMapStorage};
 
+    /// Radius storage type for pairs computed without per-body radii
+    type NoRadii = HashMapStorage<Radius>;
+
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_gravitational_constant() {
         // Verify the constant is in the right ballpark
         assert!(GRAVITATIONAL_CONSTANT > 6.6e-11);
@@ -639,7 +1964,7 @@ mod tests {
         masses.insert(entity1, Mass::new(1000.0));
         masses.insert(entity2, Mass::new(1000.0));
 
-        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses);
+        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses, None::<&NoRadii>);
         assert!(force.is_some());
 
         let f = force.unwrap();
@@ -673,7 +1998,7 @@ mod tests {
         masses.insert(entity1, Mass::new(1000.0));
         masses.insert(entity2, Mass::new(1000.0));
 
-        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses);
+        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses, None::<&NoRadii>);
         // Should return None due to zero distance
         assert!(force.is_none());
     }
@@ -696,7 +2021,7 @@ mod tests {
         masses.insert(entity1, Mass::new(1000.0));
         masses.insert(entity2, Mass::new(1000.0));
 
-        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses);
+        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses, None::<&NoRadii>);
         // With softening, should get a finite force
         assert!(force.is_some());
         
@@ -709,25 +2034,201 @@ mod tests {
         assert!(f.magnitude() < 1e-6); // Should be very small
     }
 
+    /// Assert that the pairwise forces within a pair are exactly equal and opposite
+    fn assert_reciprocal(
+        plugin: &GravityPlugin,
+        a: Entity,
+        b: Entity,
+        positions: &HashMapStorage<Position>,
+        masses: &HashMapStorage<Mass>,
+    ) {
+        let on_a = plugin.compute_pairwise_force(a, b, positions, masses, None::<&NoRadii>).unwrap();
+        let on_b = plugin.compute_pairwise_force(b, a, positions, masses, None::<&NoRadii>).unwrap();
+        assert_eq!(on_a.fx, -on_b.fx);
+        assert_eq!(on_a.fy, -on_b.fy);
+        assert_eq!(on_a.fz, -on_b.fz);
+    }
+
+    #[test]
+    fn test_pairwise_force_reciprocity_movable_pair() {
+        let mut world = World::new();
+        let a = world.create_entity();
+        let b = world.create_entity();
+
+        // Unequal masses off the axes, so rounding would expose an asymmetry
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(a, Position::new(0.3, -1.7, 2.9));
+        positions.insert(b, Position::new(-4.1, 0.7, 1.3));
+        masses.insert(a, Mass::new(3.7e5));
+        masses.insert(b, Mass::new(1.1e3));
+
+        let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        for softening in [0.0, 0.5, 10.0] {
+            plugin.set_softening(softening);
+            for kernel in [SofteningKernel::Plummer, SofteningKernel::Spline] {
+                plugin.set_softening_kernel(kernel);
+                assert_reciprocal(&plugin, a, b, &positions, &masses);
+            }
+        }
+
+        // The force on a points toward b
+        let on_a = plugin.compute_pairwise_force(a, b, &positions, &masses, None::<&NoRadii>).unwrap();
+        assert!(on_a.fx < 0.0 && on_a.fy > 0.0 && on_a.fz < 0.0);
+    }
+
     #[test]
-    fn test_immovable_bodies_ignored() {
+    fn test_pairwise_force_reciprocity_with_immovable_body() {
+        let mut world = World::new();
+        let anchor = world.create_entity();
+        let body = world.create_entity();
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(anchor, Position::new(0.0, 0.0, 0.0));
+        positions.insert(body, Position::new(1000.0, 0.0, 0.0));
+        masses.insert(body, Mass::new(1000.0));
+
         let plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        // Below the immovable threshold but nonzero, so the force is nonzero
+        for anchor_mass in [Mass::immovable(), Mass::new(Mass::IMMOVABLE_THRESHOLD / 2.0)] {
+            masses.insert(anchor, anchor_mass);
+            assert_reciprocal(&plugin, anchor, body, &positions, &masses);
+        }
+
+        // The immovable body is only skipped when forces are accumulated
+        let system = GravitySystem::new(plugin);
+        let mut forces = HashMap::new();
+        system.compute_forces_into(&[anchor, body], &positions, &masses, &mut forces);
+        assert!(!forces.contains_key(&anchor), "Immovable body received a force");
+        assert!(forces[&body].fx < 0.0);
+    }
+
+    /// Two 1000 kg bodies 1 m apart, the first with a 10 m radius
+    fn close_pair_with_radius() -> (Entity, Entity, HashMapStorage<Position>, HashMapStorage<Mass>, HashMapStorage<Radius>) {
         let mut world = World::new();
-        
-        let entity1 = world.create_entity();
-        let entity2 = world.create_entity();
+        let big = world.create_entity();
+        let point = world.create_entity();
 
         let mut positions = HashMapStorage::<Position>::new();
         let mut masses = HashMapStorage::<Mass>::new();
+        let mut radii = HashMapStorage::<Radius>::new();
+        positions.insert(big, Position::new(0.0, 0.0, 0.0));
+        positions.insert(point, Position::new(1.0, 0.0, 0.0));
+        masses.insert(big, Mass::new(1000.0));
+        masses.insert(point, Mass::new(1000.0));
+        radii.insert(big, Radius::new(10.0));
+
+        (big, point, positions, masses, radii)
+    }
 
-        positions.insert(entity1, Position::new(0.0, 0.0, 0.0));
-        positions.insert(entity2, Position::new(1000.0, 0.0, 0.0));
-        masses.insert(entity1, Mass::immovable()); // Immovable
-        masses.insert(entity2, Mass::new(1000.0));
+    #[test]
+    fn test_per_body_softening_gentler_for_large_radius() {
+        let (big, point, positions, masses, radii) = close_pair_with_radius();
 
-        let force = plugin.compute_pairwise_force(entity1, entity2, &positions, &masses);
-        // Should return None for immovable body
-        assert!(force.is_none());
+        let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        plugin.set_softening(0.01);
+        plugin.set_use_per_body_softening(true);
+
+        let point_force = plugin
+            .compute_pairwise_force(big, point, &positions, &masses, None::<&NoRadii>)
+            .unwrap();
+        let sized_force = plugin
+            .compute_pairwise_force(big, point, &positions, &masses, Some(&radii))
+            .unwrap();
+
+        assert!(sized_force.magnitude() < point_force.magnitude());
+
+        // ε_ij = √(10² + 0.01²), so F = G m² r / (r² + ε_ij²)^(3/2)
+        let eps_sq = 100.0 + 0.01 * 0.01;
+        let expected = GRAVITATIONAL_CONSTANT * 1e6 / (1.0 as Real + eps_sq).powf(1.5);
+        assert!((sized_force.fx - expected).abs() / expected < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_per_body_softening_disabled_uses_global() {
+        let (big, point, positions, masses, radii) = close_pair_with_radius();
+
+        let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        plugin.set_softening(0.01);
+        assert!(!plugin.use_per_body_softening());
+
+        let without_radii = plugin
+            .compute_pairwise_force(big, point, &positions, &masses, None::<&NoRadii>)
+            .unwrap();
+        let with_radii = plugin
+            .compute_pairwise_force(big, point, &positions, &masses, Some(&radii))
+            .unwrap();
+        assert_eq!(without_radii, with_radii);
+
+        // The system-level API follows the same switch
+        let entities = vec![big, point];
+        let mut forces = HashMap::new();
+        plugin.set_use_per_body_softening(true);
+        let system = GravitySystem::new(plugin);
+        system.compute_forces_into_with(&entities, &positions, &masses, GravityOptions::new().with_radii(&radii), &mut forces);
+        assert!(forces[&point].magnitude() < without_radii.magnitude());
+    }
+
+    /// Force on the second body of a pair `separation` meters apart
+    fn pair_force_x(plugin: &GravityPlugin, separation: Real) -> Real {
+        let mut world = World::new();
+        let e1 = world.create_entity();
+        let e2 = world.create_entity();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(e1, Position::new(0.0, 0.0, 0.0));
+        positions.insert(e2, Position::new(separation, 0.0, 0.0));
+        masses.insert(e1, Mass::new(1.0e6));
+        masses.insert(e2, Mass::new(1.0e6));
+        plugin
+            .compute_pairwise_force(e2, e1, &positions, &masses, None::<&NoRadii>)
+            .unwrap()
+            .fx
+    }
+
+    #[test]
+    fn test_spline_kernel_newtonian_beyond_two_epsilon() {
+        let mut newtonian = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        newtonian.set_softening(0.0);
+        let mut plummer = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        plummer.set_softening(1.0);
+        assert_eq!(plummer.softening_kernel(), SofteningKernel::Plummer);
+        let mut spline = plummer.clone();
+        spline.set_softening_kernel(SofteningKernel::Spline);
+
+        for separation in [2.0, 2.5, 5.0, 10.0] {
+            let exact = pair_force_x(&newtonian, separation);
+            // Spline is exactly Newtonian outside its support...
+            assert_eq!(pair_force_x(&spline, separation), exact);
+            // ...while Plummer is still noticeably weaker
+            let plummer_force = pair_force_x(&plummer, separation);
+            assert!(plummer_force.abs() < exact.abs());
+            assert!((exact - plummer_force).abs() / exact.abs() > 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_spline_kernel_softened_inside_two_epsilon() {
+        let mut newtonian = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        newtonian.set_softening(0.0);
+        let mut spline = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        spline.set_softening(1.0);
+        spline.set_softening_kernel(SofteningKernel::Spline);
+
+        // Continuous at the support boundary and at the inner branch switch
+        let outside = pair_force_x(&spline, 2.0);
+        let inside = pair_force_x(&spline, 2.0 - 1e-9);
+        assert!((outside - inside).abs() < 1e-6 * outside.abs());
+        let below = pair_force_x(&spline, 1.0 - 1e-9);
+        let above = pair_force_x(&spline, 1.0 + 1e-9);
+        assert!((below - above).abs() < 1e-6 * above.abs());
+
+        for separation in [0.01, 0.5, 1.0, 1.5, 1.99] {
+            let softened = pair_force_x(&spline, separation);
+            assert!(softened.is_finite());
+            assert!(softened.abs() < pair_force_x(&newtonian, separation).abs());
+        }
     }
 
     #[test]
@@ -754,11 +2255,447 @@ mod tests {
         plugin.set_max_expected_force(-1.0);
     }
 
+    #[test]
+    fn test_circular_orbit_velocity() {
+        let v = circular_orbit_velocity(4.0, 1.0, 1.0);
+        assert_eq!(v, 2.0);
+        assert_eq!(circular_orbit_velocity(0.0, 1.0, 1.0), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Orbital radius must be positive and finite")]
+    fn test_circular_orbit_velocity_zero_radius_panics() {
+        circular_orbit_velocity(1.0, 0.0, 1.0);
+    }
+
+    #[test]
+    fn test_set_circular_orbit_direction() {
+        let mut world = World::new();
+        let central = world.create_entity();
+        let body = world.create_entity();
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+
+        positions.insert(central, Position::new(0.0, 0.0, 0.0));
+        positions.insert(body, Position::new(0.0, 0.0, 2.0));
+        velocities.insert(central, Velocity::new(1.0, 0.0, 0.0));
+        masses.insert(central, Mass::new(8.0));
+
+        // Separation along z uses the fallback axis
+        let speed = set_circular_orbit(body, central, &positions, &mut velocities, &masses, 1.0).unwrap();
+        assert_eq!(speed, 2.0);
+
+        let vel = velocities.get(body).unwrap();
+        let relative = [vel.dx() - 1.0, vel.dy(), vel.dz()];
+        let rel_speed = (relative[0].powi(2) + relative[1].powi(2) + relative[2].powi(2)).sqrt();
+        assert!((rel_speed - 2.0).abs() < 1e-12);
+        // Perpendicular to the separation
+        assert!(relative[2].abs() < 1e-12);
+
+        // Coincident bodies are rejected
+        positions.insert(body, Position::new(0.0, 0.0, 0.0));
+        assert!(set_circular_orbit(body, central, &positions, &mut velocities, &masses, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_circular_orbit_stays_circular_under_verlet() {
+        use crate::ecs::components::Acceleration;
+        use crate::ecs::systems::apply_forces_to_acceleration;
+        use crate::integration::{Integrator, VelocityVerletIntegrator};
+
+        let central_mass = 5.97e24;
+        let radius = 7.0e6;
+
+        let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        plugin.set_softening(0.0);
+        plugin.set_warn_on_high_forces(false);
+        let gravity_system = GravitySystem::new(plugin);
+
+        let mut world = World::new();
+        let central = world.create_entity();
+        let satellite = world.create_entity();
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+
+        positions.insert(central, Position::zero());
+        positions.insert(satellite, Position::new(radius, 0.0, 0.0));
+        velocities.insert(central, Velocity::zero());
+        masses.insert(central, Mass::new(central_mass));
+        masses.insert(satellite, Mass::new(1000.0));
+
+        let speed = set_circular_orbit(
+            satellite, central, &positions, &mut velocities, &masses, GRAVITATIONAL_CONSTANT,
+        ).unwrap();
+
+        let period = 2.0 * crate::real::consts::PI * radius / speed;
+        let steps = 2000;
+        let mut integrator = VelocityVerletIntegrator::new(period / steps as Real);
+        let mut force_registry = ForceRegistry::new();
+        force_registry.set_warn_on_missing_components(false);
+
+        let all = [central, satellite];
+        // Keep the heavy central body fixed by only integrating the satellite
+        let moving = [satellite];
+
+        let mut forces = HashMap::new();
+
+        for _ in 0..steps {
+            force_registry.clear();
+            gravity_system.compute_forces_into(&all, &positions, &masses, &mut forces);
+            force_registry.register_forces(&forces);
+            apply_forces_to_acceleration(moving.iter(), &force_registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                moving.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut force_registry,
+                false,
+            );
+
+            let pos = positions.get(satellite).unwrap();
+            let r = (pos.x().powi(2) + pos.y().powi(2) + pos.z().powi(2)).sqrt();
+            assert!(
+                ((r - radius) / radius).abs() < 0.03,
+                "Orbit radius drifted to {:.4e} m (initial {:.4e} m)",
+                r, radius
+            );
+        }
+    }
+
+    /// Suggested timestep for a pair with the given separation and masses
+    fn pair_timestep(separation: Real, mass: Real, softening: Real) -> Option<Real> {
+        let mut world = World::new();
+        let (a, b) = (world.create_entity(), world.create_entity());
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(a, Position::new(0.0, 0.0, 0.0));
+        positions.insert(b, Position::new(separation, 0.0, 0.0));
+        masses.insert(a, Mass::new(mass));
+        masses.insert(b, Mass::new(mass));
+        suggest_timestep(&[a, b], &positions, &masses, 1.0, softening, 0.1)
+    }
+
+    #[test]
+    fn test_suggest_timestep_tighter_heavier_pair_is_smaller() {
+        let loose = pair_timestep(1.0, 1.0, 0.0).unwrap();
+        let tight = pair_timestep(0.1, 1.0, 0.0).unwrap();
+        let heavy = pair_timestep(1.0, 100.0, 0.0).unwrap();
+
+        // t ∝ √(r³ / M): a tenth of the separation is √1000 times shorter,
+        // a hundred times the mass is ten times shorter
+        assert!((loose - 0.1 * (0.5 as Real).sqrt()).abs() < 1e-6);
+        assert!((loose / tight - (1000.0 as Real).sqrt()).abs() < 1e-3);
+        assert!((loose / heavy - 10.0).abs() < 1e-4);
+        assert!(pair_timestep(0.1, 100.0, 0.0).unwrap() < tight.min(heavy));
+    }
+
+    #[test]
+    fn test_suggest_timestep_softening_sets_a_floor() {
+        // Coincident bodies have no timescale without softening
+        assert_eq!(pair_timestep(0.0, 1.0, 0.0), None);
+        // With it, the close-pair limit is √(ε³ / (G·M))
+        let floor = pair_timestep(0.0, 1.0, 0.1).unwrap();
+        assert!((floor - 0.1 * (0.001 as Real / 2.0).sqrt()).abs() < 1e-6);
+        assert!(pair_timestep(1e-6, 1.0, 0.1).unwrap() >= floor);
+    }
+
+    #[test]
+    fn test_suggest_timestep_needs_two_massive_bodies() {
+        let mut world = World::new();
+        let (a, b) = (world.create_entity(), world.create_entity());
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(a, Position::new(0.0, 0.0, 0.0));
+        positions.insert(b, Position::new(1.0, 0.0, 0.0));
+        masses.insert(a, Mass::new(1.0));
+        assert_eq!(suggest_timestep(&[a, b], &positions, &masses, 1.0, 0.0, 0.1), None);
+
+        masses.insert(b, Mass::new(0.0));
+        assert_eq!(suggest_timestep(&[a, b], &positions, &masses, 1.0, 0.0, 0.1), None);
+
+        masses.insert(b, Mass::new(1.0));
+        assert_eq!(suggest_timestep(&[a, b], &positions, &masses, 0.0, 0.0, 0.1), None);
+        assert!(suggest_timestep(&[a, b], &positions, &masses, 1.0, 0.0, 0.1).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Safety factor must be positive and finite")]
+    fn test_suggest_timestep_rejects_zero_safety_factor() {
+        let mut world = World::new();
+        let entity = world.create_entity();
+        suggest_timestep(&[entity], &HashMapStorage::new(), &HashMapStorage::new(), 1.0, 0.0, 0.0);
+    }
+
+    /// Run a small cluster for `steps` Verlet steps and return the final
+    /// positions in entity order
+    fn run_cluster(steps: usize) -> Vec<Position> {
+        use crate::ecs::components::Acceleration;
+        use crate::ecs::systems::apply_forces_to_acceleration;
+        use crate::integration::{Integrator, VelocityVerletIntegrator};
+
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.05);
+        // Many small chunks so parallel builds split the work across tasks
+        plugin.set_chunk_size(2);
+        let gravity_system = GravitySystem::new(plugin);
+
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut entities = Vec::new();
+        for i in 0..12 {
+            let entity = world.create_entity();
+            let angle = i as Real * 0.7;
+            let r = 1.0 + 0.15 * i as Real;
+            positions.insert(entity, Position::new(r * angle.cos(), r * angle.sin(), 0.1 * (i % 3) as Real));
+            velocities.insert(entity, Velocity::new(-0.3 * angle.sin(), 0.3 * angle.cos(), 0.0));
+            masses.insert(entity, Mass::new(0.5 + 0.1 * i as Real));
+            entities.push(entity);
+        }
+
+        let mut integrator = VelocityVerletIntegrator::new(0.001);
+        let mut force_registry = ForceRegistry::new();
+        force_registry.set_warn_on_missing_components(false);
+        let mut forces = HashMap::new();
+
+        for _ in 0..steps {
+            force_registry.clear();
+            gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
+            force_registry.register_forces(&forces);
+            apply_forces_to_acceleration(entities.iter(), &force_registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut force_registry,
+                false,
+            );
+        }
+
+        entities.iter().map(|&entity| *positions.get(entity).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_repeated_runs_are_bit_identical() {
+        let first = run_cluster(1000);
+        let second = run_cluster(1000);
+
+        for (a, b) in first.iter().zip(&second) {
+            for (x, y) in a.as_array().iter().zip(b.as_array()) {
+                assert_eq!(x.to_bits(), y.to_bits(), "Runs diverged: {:?} vs {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_forces_into_slice_order_matches_map() {
+        let (entities, positions, masses) = line_of_bodies();
+        let system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+
+        let mut map = HashMap::new();
+        system.compute_forces_into(&entities, &positions, &masses, &mut map);
+        let mut slice = vec![Force::zero(); entities.len()];
+        system.compute_forces_into_slice(&entities, &positions, &masses, GravityOptions::new(), &mut slice);
+
+        for (entity, force) in entities.iter().zip(&slice) {
+            assert_eq!(map.get(entity).copied().unwrap_or_else(Force::zero), *force);
+        }
+    }
+
+    #[test]
+    fn test_compute_forces_on_targets_matches_full_sum() {
+        let (entities, positions, masses) = line_of_bodies();
+        let system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+        let mut all = HashMap::new();
+        system.compute_forces_into(&entities, &positions, &masses, &mut all);
+        let full_interactions = system.last_interaction_count();
+
+        let targets = &entities[1..3];
+        let mut some = HashMap::new();
+        assert_eq!(system.compute_forces_on(targets, &entities, &positions, &masses, GravityOptions::new(), &mut some), 2);
+        for target in targets {
+            assert_eq!(some[target], all[target]);
+        }
+        assert_eq!(system.last_interaction_count(), 2 * (entities.len() - 1));
+        assert!(system.last_interaction_count() < full_interactions);
+
+        system.compute_forces_on(&entities, &entities, &positions, &masses, GravityOptions::new(), &mut some);
+        assert_eq!(some, all);
+    }
+
+    #[test]
+    fn test_tracers_orbit_without_self_gravity() {
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.0);
+        let system = GravitySystem::new(plugin);
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut tracers = HashMapStorage::<Tracer>::new();
+
+        let sun = world.create_entity();
+        positions.insert(sun, Position::zero());
+        velocities.insert(sun, Velocity::zero());
+        masses.insert(sun, Mass::new(1000.0));
+        // A tight cloud: as sources, neighbors 0.002 apart would outpull the sun
+        let cloud: Vec<Entity> = (0..50)
+            .map(|i| {
+                let tracer = world.create_entity();
+                let (r, angle) = (1.0 + 0.002 * i as Real, 0.001 * i as Real);
+                let speed = circular_orbit_velocity(1000.0, r, 1.0);
+                positions.insert(tracer, Position::new(r * angle.cos(), r * angle.sin(), 0.0));
+                velocities.insert(tracer, Velocity::new(-speed * angle.sin(), speed * angle.cos(), 0.0));
+                masses.insert(tracer, Mass::new(1.0));
+                tracers.insert(tracer, Tracer);
+                tracer
+            })
+            .collect();
+        let entities: Vec<Entity> = std::iter::once(sun).chain(cloud.iter().copied()).collect();
+
+        let mut forces = HashMap::new();
+        system.compute_forces_into_with(&entities, &positions, &masses, GravityOptions::new().with_tracers(&tracers), &mut forces);
+        assert!(!forces.contains_key(&sun), "The central body must feel no pull from tracers");
+        let mut pair = HashMap::new();
+        for &tracer in &cloud {
+            system.compute_forces_into(&[sun, tracer], &positions, &masses, &mut pair);
+            assert_eq!(forces[&tracer], pair[&tracer], "Tracer {:?} was perturbed by the cloud", tracer);
+        }
+        system.compute_forces_into(&entities, &positions, &masses, &mut pair);
+        assert!(pair[&sun].magnitude() > 0.0);
+
+        // Kick-drift-kick over one period of the innermost orbit
+        let period = 2.0 * crate::real::consts::PI / circular_orbit_velocity(1000.0, 1.0, 1.0);
+        let steps = 2000;
+        let dt = period / steps as Real;
+        let kick = |velocities: &mut HashMapStorage<Velocity>, forces: &HashMap<Entity, Force>| {
+            for (entity, force) in forces {
+                let v = velocities.get_mut(*entity).unwrap();
+                let inv_mass = masses.get(*entity).unwrap().inverse();
+                *v = Velocity::new(
+                    v.dx() + 0.5 * dt * force.fx * inv_mass,
+                    v.dy() + 0.5 * dt * force.fy * inv_mass,
+                    v.dz() + 0.5 * dt * force.fz * inv_mass,
+                );
+            }
+        };
+        for _ in 0..steps {
+            kick(&mut velocities, &forces);
+            for &entity in &entities {
+                let v = *velocities.get(entity).unwrap();
+                let p = positions.get_mut(entity).unwrap();
+                *p = Position::new(p.x() + v.dx() * dt, p.y() + v.dy() * dt, p.z() + v.dz() * dt);
+            }
+            system.compute_forces_into_with(&entities, &positions, &masses, GravityOptions::new().with_tracers(&tracers), &mut forces);
+            kick(&mut velocities, &forces);
+        }
+
+        assert_eq!(*positions.get(sun).unwrap(), Position::zero());
+        for (i, tracer) in cloud.iter().enumerate() {
+            let p = positions.get(*tracer).unwrap();
+            let r = (p.x() * p.x() + p.y() * p.y()).sqrt();
+            let expected = 1.0 + 0.002 * i as Real;
+            assert!((r - expected).abs() < 1e-4 * expected, "Tracer {} drifted to radius {}", i, r);
+        }
+    }
+
+    /// `count` bodies on a ring, with a gravity system splitting them into chunks of `chunk`
+    fn ring_of_bodies(count: usize, chunk: usize) -> (GravitySystem, Vec<Entity>, HashMapStorage<Position>, HashMapStorage<Mass>) {
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_chunk_size(chunk);
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let entities: Vec<Entity> = (0..count)
+            .map(|i| {
+                let entity = world.create_entity();
+                let angle = i as Real * 0.37;
+                positions.insert(entity, Position::new(angle.cos(), angle.sin(), 0.01 * i as Real));
+                masses.insert(entity, Mass::new(1.0 + (i % 5) as Real));
+                entity
+            })
+            .collect();
+        (GravitySystem::new(plugin), entities, positions, masses)
+    }
+
+    #[test]
+    fn test_progress_increases_to_entity_count() {
+        let (system, entities, positions, masses) = ring_of_bodies(100, 8);
+        let reports = Mutex::new(Vec::new());
+        let mut forces = HashMap::new();
+        let count = system
+            .compute_forces_with_progress(
+                &entities,
+                &positions,
+                &masses,
+                GravityOptions::new(),
+                &mut forces,
+                &|done, total| reports.lock().unwrap().push((done, total)),
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 13, "One report per chunk of 8");
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0), "Progress went backwards: {:?}", reports);
+        assert!(reports.iter().all(|&(_, total)| total == 100));
+        assert_eq!(reports.last(), Some(&(100, 100)));
+
+        // Same forces as the fast path
+        let mut expected = HashMap::new();
+        assert_eq!(count, system.compute_forces_into(&entities, &positions, &masses, &mut expected));
+        assert_eq!(forces, expected);
+    }
+
+    #[test]
+    fn test_cancel_after_first_chunk_discards_results() {
+        let (system, entities, positions, masses) = ring_of_bodies(400, 4);
+        let cancel = AtomicBool::new(false);
+        let calls = AtomicUsize::new(0);
+        let mut forces = HashMap::new();
+        forces.insert(entities[0], Force::new(1.0, 0.0, 0.0));
+
+        let result = system.compute_forces_with_progress(
+            &entities,
+            &positions,
+            &masses,
+            GravityOptions::new(),
+            &mut forces,
+            &|done, total| {
+                assert!(done < total, "Every chunk ran despite the cancel flag");
+                calls.fetch_add(1, Ordering::Relaxed);
+                cancel.store(true, Ordering::Relaxed);
+            },
+            &cancel,
+        );
+        assert_eq!(result, Err(GravityError::Cancelled));
+        assert!(forces.is_empty(), "Partial results were kept");
+        // Only chunks already running when the flag was set may finish
+        let calls = calls.into_inner();
+        assert!((1..100).contains(&calls), "{} of 100 chunks finished", calls);
+
+        // A flag set up front stops before any work
+        let result = system.compute_forces_with_progress(
+            &entities, &positions, &masses, GravityOptions::new(), &mut forces, &|_, _| panic!("No chunk should run"), &cancel,
+        );
+        assert_eq!(result, Err(GravityError::Cancelled));
+        assert_eq!(result.unwrap_err().to_string(), "Gravity computation was cancelled");
+    }
+
     #[cfg(feature = "parallel")]
     #[test]
     fn test_parallel_gravity_correctness() {
-        use crate::ecs::systems::ForceRegistry;
-        
         let plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
         let gravity_system = GravitySystem::new(plugin);
         let mut world = World::new();
@@ -770,8 +2707,6 @@ mod tests {
         
         let mut positions = HashMapStorage::<Position>::new();
         let mut masses = HashMapStorage::<Mass>::new();
-        let mut force_registry = ForceRegistry::new();
-        
         // Bodies at 1000 km spacing
         positions.insert(e1, Position::new(0.0, 0.0, 0.0));
         positions.insert(e2, Position::new(1e6, 0.0, 0.0));
@@ -783,25 +2718,19 @@ mod tests {
         
         let entities = vec![e1, e2, e3];
         
-        // Compute forces - this registers SimpleForceProviders
-        let count = gravity_system.compute_forces(&entities, &positions, &masses, &mut force_registry);
+        // Compute forces directly into the output map
+        let mut forces = HashMap::new();
+        let count = gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
         
         // All 3 entities should have forces computed
         assert_eq!(count, 3);
-        
-        // Need to accumulate forces from registered providers
-        for entity in &entities {
-            force_registry.accumulate_for_entity(*entity);
-        }
-        
-        // Check that forces were accumulated
-        assert!(force_registry.get_force(e1).is_some());
-        assert!(force_registry.get_force(e2).is_some());
-        assert!(force_registry.get_force(e3).is_some());
+        assert!(forces.contains_key(&e1));
+        assert!(forces.contains_key(&e2));
+        assert!(forces.contains_key(&e3));
         
         // Verify forces on outer bodies
-        let f1 = force_registry.get_force(e1).unwrap();
-        let f3 = force_registry.get_force(e3).unwrap();
+        let f1 = forces[&e1];
+        let f3 = forces[&e3];
         
         // e1 (leftmost) should experience force in positive x direction (toward e2 and e3)
         assert!(f1.fx > 0.0, "e1 should be pulled to the right");
@@ -817,7 +2746,7 @@ mod tests {
         assert!((f1.fx + f3.fx).abs() < 1e-10, "e1 and e3 forces should be symmetric");
         
         // Middle body (e2) should have forces pulling in both directions
-        let f2 = force_registry.get_force(e2).unwrap();
+        let f2 = forces[&e2];
         // Force from e1 is negative x (pulling left)
         // Force from e3 is positive x (pulling right)
         // They should roughly cancel out due to symmetry
@@ -829,8 +2758,6 @@ mod tests {
     #[cfg(feature = "parallel")]
     #[test]
     fn test_parallel_large_entity_count() {
-        use crate::ecs::systems::ForceRegistry;
-        
         let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
         plugin.set_warn_on_invalid(false); // Suppress warnings for test
         plugin.set_warn_on_high_forces(false);
@@ -848,19 +2775,437 @@ mod tests {
             entities.push(entity);
             
             // Spread entities in a grid pattern
-            let x = (i % 10) as f64 * 1e6;
-            let y = (i / 10) as f64 * 1e6;
+            let x = (i % 10) as Real * 1e6;
+            let y = (i / 10) as Real * 1e6;
             positions.insert(entity, Position::new(x, y, 0.0));
             masses.insert(entity, Mass::new(1e10));
         }
         
-        let mut force_registry = ForceRegistry::new();
-        force_registry.max_force_magnitude = 1e20; // Increase limit for test
-        
         // Compute forces - this should not exhaust memory
-        let count = gravity_system.compute_forces(&entities, &positions, &masses, &mut force_registry);
+        let mut forces = HashMap::new();
+        let count = gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
         
         // Should compute forces for all entities
         assert_eq!(count, 100);
+        assert_eq!(forces.len(), 100);
+    }
+
+    /// Three bodies in a line plus one immovable body, for API comparisons
+    fn line_of_bodies() -> (Vec<Entity>, HashMapStorage<Position>, HashMapStorage<Mass>) {
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut entities = Vec::new();
+
+        for i in 0..3 {
+            let entity = world.create_entity();
+            positions.insert(entity, Position::new(i as Real * 1e6, (i * i) as Real * 1e5, 0.0));
+            masses.insert(entity, Mass::new(1e10 * (i + 1) as Real));
+            entities.push(entity);
+        }
+        let anchor = world.create_entity();
+        positions.insert(anchor, Position::new(0.0, 2e6, 0.0));
+        masses.insert(anchor, Mass::immovable());
+        entities.push(anchor);
+
+        (entities, positions, masses)
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_compute_forces_into_matches_provider_path() {
+        let gravity_system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+        let (entities, positions, masses) = line_of_bodies();
+
+        let mut forces = HashMap::new();
+        let count = gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
+
+        let mut force_registry = ForceRegistry::new();
+        let old_count = gravity_system.compute_forces(&entities, &positions, &masses, &mut force_registry);
+        assert_eq!(count, old_count);
+
+        for entity in &entities {
+            force_registry.accumulate_for_entity(*entity);
+            assert_eq!(forces.get(entity).copied(), force_registry.get_force(*entity));
+        }
+        // Immovable body experiences no force
+        assert!(!forces.contains_key(&entities[3]));
+    }
+
+    #[test]
+    fn test_compute_forces_into_slice_matches_map() {
+        let gravity_system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+        let (entities, positions, masses) = line_of_bodies();
+
+        let mut forces = HashMap::new();
+        gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
+
+        let mut dense = vec![Force::new(1.0, 1.0, 1.0); entities.len()];
+        let count = gravity_system.compute_forces_into_slice(&entities, &positions, &masses, GravityOptions::new(), &mut dense);
+        assert_eq!(count, forces.len());
+
+        for (i, entity) in entities.iter().enumerate() {
+            assert_eq!(dense[i], forces.get(entity).copied().unwrap_or_else(Force::zero));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Output slice length must match entity count")]
+    fn test_compute_forces_into_slice_length_mismatch() {
+        let gravity_system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+        let (entities, positions, masses) = line_of_bodies();
+        let mut dense = vec![Force::zero(); 2];
+        gravity_system.compute_forces_into_slice(&entities, &positions, &masses, GravityOptions::new(), &mut dense);
+    }
+
+    #[test]
+    fn test_compute_forces_soa_matches_map() {
+        let (entities, positions, masses) = line_of_bodies();
+
+        // Insert in opposite orders so the storages are not aligned
+        let mut soa_positions = PositionSoAStorage::new();
+        let mut soa_masses = MassSoAStorage::new();
+        for &entity in &entities {
+            soa_positions.insert(entity, *positions.get(entity).unwrap());
+        }
+        for &entity in entities.iter().rev() {
+            soa_masses.insert(entity, *masses.get(entity).unwrap());
+        }
+        assert_eq!(soa_positions.get(entities[0]), None, "True SoA storage has no get()");
+
+        let mut far_field = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        // Puts the first body (~1.35e6 m from the center of mass) in the far field
+        far_field.set_far_field_approximation(1e6);
+        for plugin in [GravityPlugin::new(GRAVITATIONAL_CONSTANT), far_field] {
+            let gravity_system = GravitySystem::new(plugin);
+
+            let mut forces = HashMap::new();
+            let count = gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
+            let interactions = gravity_system.last_interaction_count();
+
+            let mut force_registry = ForceRegistry::new();
+            let soa_count =
+                gravity_system.compute_forces_soa(&entities, &soa_positions, &soa_masses, GravityOptions::new(), &mut force_registry);
+            assert_eq!(soa_count, count);
+            assert_eq!(gravity_system.last_interaction_count(), interactions);
+
+            for entity in &entities {
+                assert_eq!(force_registry.get_force(*entity), forces.get(entity).copied());
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_combine_on_every_entry_point() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..6).map(|_| world.create_entity()).collect();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut soa_positions = PositionSoAStorage::new();
+        let mut soa_masses = MassSoAStorage::new();
+        for (i, &entity) in entities.iter().enumerate() {
+            let position = Position::new(i as Real * 0.4, (i % 3) as Real * 0.3, (i % 2) as Real * 0.2);
+            let mass = Mass::new(1.0 + i as Real);
+            positions.insert(entity, position);
+            masses.insert(entity, mass);
+            soa_positions.insert(entity, position);
+            soa_masses.insert(entity, mass);
+        }
+        let mut radii = HashMapStorage::<Radius>::new();
+        radii.insert(entities[0], Radius::new(0.3));
+        radii.insert(entities[3], Radius::new(0.2));
+        let mut tracers = HashMapStorage::<Tracer>::new();
+        tracers.insert(entities[2], Tracer);
+        tracers.insert(entities[5], Tracer);
+        let mut sleep_registry = ForceRegistry::new();
+        sleep_registry.put_to_sleep(entities[4]);
+        sleep_registry.put_to_sleep(entities[5]);
+
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.05);
+        plugin.set_use_per_body_softening(true);
+        let system = GravitySystem::new(plugin.clone());
+        let options = GravityOptions::new()
+            .with_radii(&radii)
+            .with_tracers(&tracers)
+            .with_sleeping(&sleep_registry);
+
+        let mut forces = HashMap::new();
+        assert_eq!(system.compute_forces_into_with(&entities, &positions, &masses, options, &mut forces), 6);
+
+        // Awake bodies feel every non-tracer; sleeping ones, tracer or not,
+        // only the awake non-tracers. Every pair is softened by its radii.
+        let non_tracers = [entities[0], entities[1], entities[3], entities[4]];
+        let awake_non_tracers = [entities[0], entities[1], entities[3]];
+        for (i, &entity) in entities.iter().enumerate() {
+            let sources: &[Entity] = if i >= 4 { &awake_non_tracers } else { &non_tracers };
+            let expected = sources.iter().filter(|&&source| source != entity).fold(Force::zero(), |sum, &source| {
+                let force = plugin.compute_pairwise_force(entity, source, &positions, &masses, Some(&radii)).unwrap();
+                Force::new(sum.fx + force.fx, sum.fy + force.fy, sum.fz + force.fz)
+            });
+            let actual = forces[&entity];
+            let scale = expected.magnitude();
+            for (a, e) in [(actual.fx, expected.fx), (actual.fy, expected.fy), (actual.fz, expected.fz)] {
+                assert!((a - e).abs() <= 1e-12 * Real::EPSILON_RATIO * scale, "Body {}: {:?} vs {:?}", i, actual, expected);
+            }
+        }
+
+        // The other entry points honor the same options bit for bit
+        let mut on_targets = HashMap::new();
+        system.compute_forces_on(&entities, &entities, &positions, &masses, options, &mut on_targets);
+        assert_eq!(on_targets, forces);
+
+        let mut with_progress = HashMap::new();
+        system
+            .compute_forces_with_progress(
+                &entities, &positions, &masses, options, &mut with_progress, &|_, _| {}, &AtomicBool::new(false),
+            )
+            .unwrap();
+        assert_eq!(with_progress, forces);
+
+        let mut dense = vec![Force::zero(); entities.len()];
+        system.compute_forces_into_slice(&entities, &positions, &masses, options, &mut dense);
+        for (entity, force) in entities.iter().zip(&dense) {
+            assert_eq!(*force, forces[entity]);
+        }
+
+        // The SoA path takes its sleeping bodies from the registry it fills
+        let mut registry = ForceRegistry::new();
+        registry.put_to_sleep(entities[4]);
+        registry.put_to_sleep(entities[5]);
+        let soa_options = GravityOptions::new().with_radii(&radii).with_tracers(&tracers);
+        system.compute_forces_soa(&entities, &soa_positions, &soa_masses, soa_options, &mut registry);
+        for entity in &entities {
+            assert_eq!(registry.get_force(*entity), Some(forces[entity]));
+        }
+    }
+
+    #[test]
+    fn test_interaction_count_is_pair_count() {
+        let (entities, positions, masses) = line_of_bodies();
+        let gravity_system = GravitySystem::new(GravityPlugin::new(GRAVITATIONAL_CONSTANT));
+        assert_eq!(gravity_system.last_interaction_count(), 0);
+
+        // N·(N−1) over the movable bodies
+        let mut forces = HashMap::new();
+        gravity_system.compute_forces_into(&entities[..3], &positions, &masses, &mut forces);
+        assert_eq!(gravity_system.last_interaction_count(), 3 * 2);
+
+        // The immovable anchor attracts the others but evaluates nothing itself
+        gravity_system.compute_forces_into(&entities, &positions, &masses, &mut forces);
+        assert_eq!(gravity_system.last_interaction_count(), 3 * 3);
+        let mut out = vec![Force::zero(); entities.len()];
+        gravity_system.compute_forces_into_slice(&entities, &positions, &masses, GravityOptions::new(), &mut out);
+        assert_eq!(gravity_system.last_interaction_count(), 3 * 3);
+
+        // Each computation starts from zero
+        gravity_system.compute_forces_into(&entities[..1], &positions, &masses, &mut forces);
+        assert_eq!(gravity_system.last_interaction_count(), 0);
+    }
+
+    #[test]
+    fn test_mass_distribution_matches_brute_force() {
+        // More than one chunk, so partial sums are merged
+        let entities: Vec<Entity> = (0..2500).map(|i| Entity::new(i, 0)).collect();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        for (i, &entity) in entities.iter().enumerate() {
+            let t = i as Real;
+            positions.insert(entity, Position::new(10.0 + t.sin() * 3.0, (0.7 * t).cos(), 0.01 * t));
+            masses.insert(entity, Mass::new(1.0 + (i % 7) as Real));
+        }
+
+        let distribution = MassDistribution::compute(&entities, &positions, &masses);
+
+        let bodies: Vec<([Real; 3], Real)> = entities
+            .iter()
+            .map(|&e| (positions.get(e).unwrap().as_array(), masses.get(e).unwrap().value()))
+            .collect();
+        let total: Real = bodies.iter().map(|(_, m)| m).sum();
+        let com: [Real; 3] = std::array::from_fn(|k| bodies.iter().map(|(p, m)| m * p[k]).sum::<Real>() / total);
+        let moment: [[Real; 3]; 3] = std::array::from_fn(|j| {
+            std::array::from_fn(|k| bodies.iter().map(|(p, m)| m * (p[j] - com[j]) * (p[k] - com[k])).sum())
+        });
+
+        let close = |a: Real, b: Real| (a - b).abs() <= Real::TOLERANCE * b.abs().max(1.0);
+        assert!(close(distribution.total_mass(), total));
+        let second_moment = distribution.second_moment();
+        for (j, row) in moment.iter().enumerate() {
+            assert!(close(distribution.center_of_mass()[j], com[j]));
+            for (k, &expected) in row.iter().enumerate() {
+                assert!(close(second_moment[j][k], expected));
+                assert_eq!(second_moment[j][k], second_moment[k][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mass_distribution_empty() {
+        let distribution =
+            MassDistribution::compute(&[], &HashMapStorage::<Position>::new(), &HashMapStorage::<Mass>::new());
+        assert_eq!(distribution.total_mass(), 0.0);
+        assert_eq!(distribution.center_of_mass(), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_far_field_monopole_error_bound() {
+        // A compact, lopsided cluster within `size` of its center, and a probe far away
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut entities = Vec::new();
+        for (i, offset) in [[-1.0, 0.0, 0.0], [1.0, 0.2, 0.0], [0.0, 1.0, -0.5], [0.3, -0.8, 0.6]].iter().enumerate() {
+            let entity = world.create_entity();
+            positions.insert(entity, Position::from_array(*offset));
+            masses.insert(entity, Mass::new(1e9 * (i + 1) as Real));
+            entities.push(entity);
+        }
+        let size: Real = 1.5;
+        let distance: Real = 100.0;
+        let probe = world.create_entity();
+        positions.insert(probe, Position::new(distance * 0.6, distance * 0.8, 0.0));
+        masses.insert(probe, Mass::new(1.0));
+        entities.push(probe);
+
+        let mut plugin = GravityPlugin::new(GRAVITATIONAL_CONSTANT);
+        plugin.set_softening(0.0);
+        plugin.set_warn_on_invalid(false);
+        let exact_system = GravitySystem::new(plugin.clone());
+        plugin.set_far_field_approximation(distance / 2.0);
+        let far_system = GravitySystem::new(plugin);
+
+        let mut exact = HashMap::new();
+        let mut approx = HashMap::new();
+        exact_system.compute_forces_into(&entities, &positions, &masses, &mut exact);
+        far_system.compute_forces_into(&entities, &positions, &masses, &mut approx);
+        // The probe's four pair forces collapse into one monopole evaluation
+        assert_eq!(exact_system.last_interaction_count(), 5 * 4);
+        assert_eq!(far_system.last_interaction_count(), 4 * 4 + 1);
+
+        let (fe, fa) = (exact[&probe], approx[&probe]);
+        let error = Force::new(fa.fx - fe.fx, fa.fy - fe.fy, fa.fz - fe.fz).magnitude();
+        let relative_error = error / fe.magnitude();
+        assert!(relative_error > 0.0, "Far-field path was not used");
+        assert!(
+            relative_error < 3.0 * (size / distance).powi(2),
+            "Monopole error {:.2e} exceeds quadrupole bound",
+            relative_error
+        );
+
+        // Bodies inside the threshold keep the exact sum
+        for entity in &entities[..4] {
+            assert_eq!(exact[entity], approx[entity]);
+        }
+    }
+
+    #[test]
+    fn test_axis_mask_keeps_planar_run_planar() {
+        use crate::ecs::components::Acceleration;
+        use crate::ecs::systems::apply_forces_to_acceleration;
+        use crate::integration::{Integrator, VelocityVerletIntegrator};
+
+        let mut plugin = GravityPlugin::new(1.0);
+        plugin.set_softening(0.1);
+        plugin.set_axis_mask([false, false, true]);
+        assert_eq!(plugin.axis_mask(), [false, false, true]);
+        let gravity_system = GravitySystem::new(plugin);
+
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut planar = Vec::new();
+        for (x, y, vx, vy) in [(0.0, 0.0, 0.0, 0.0), (1.0, 0.0, 0.0, 1.0), (-0.5, 1.5, 0.3, -0.2)] {
+            let entity = world.create_entity();
+            positions.insert(entity, Position::new(x, y, 0.0));
+            velocities.insert(entity, Velocity::new(vx, vy, 0.0));
+            masses.insert(entity, Mass::new(1.0));
+            planar.push(entity);
+        }
+        // An out-of-plane body that would pull the others off the plane
+        let stray = world.create_entity();
+        positions.insert(stray, Position::new(0.5, 0.5, 0.7));
+        masses.insert(stray, Mass::new(0.5));
+        let mut all = planar.clone();
+        all.push(stray);
+
+        let mut integrator = VelocityVerletIntegrator::new(0.01);
+        let mut force_registry = ForceRegistry::new();
+        force_registry.set_warn_on_missing_components(false);
+        let mut forces = HashMap::new();
+
+        for _ in 0..200 {
+            force_registry.clear();
+            gravity_system.compute_forces_into(&all, &positions, &masses, &mut forces);
+            for entity in &all {
+                assert_eq!(forces[entity].fz, 0.0);
+            }
+            force_registry.register_forces(&forces);
+            apply_forces_to_acceleration(planar.iter(), &force_registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                planar.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut force_registry,
+                false,
+            );
+        }
+
+        for entity in &planar {
+            assert_eq!(positions.get(*entity).unwrap().z(), 0.0);
+            assert_eq!(velocities.get(*entity).unwrap().dz(), 0.0);
+        }
+        // The run did move the bodies within the plane
+        assert_ne!(positions.get(planar[0]).unwrap().x(), 0.0);
+    }
+
+    #[test]
+    fn test_compensated_summation_keeps_small_forces() {
+        // A probe between two huge, cancelling masses, with many unit masses
+        // on one side. Each unit pull is far below the rounding error of the
+        // huge pulls, so naive summation drops all of them.
+        let mut world = World::new();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut add_body = |x: Real, m: Real| {
+            let entity = world.create_entity();
+            positions.insert(entity, Position::new(x, 0.0, 0.0));
+            masses.insert(entity, Mass::new(m));
+            entity
+        };
+        let probe = add_body(0.0, 1.0);
+        let light_count = 1000;
+        let mut entities = vec![probe, add_body(1.0, 1e20)];
+        for _ in 0..light_count {
+            entities.push(add_body(1.0, 1.0));
+        }
+        entities.push(add_body(-1.0, 1e20));
+
+        let probe_force = |compensated: bool| {
+            let mut plugin = GravityPlugin::new(1.0);
+            plugin.set_softening(0.0);
+            plugin.set_warn_on_invalid(false);
+            plugin.set_warn_on_high_forces(false);
+            plugin.set_compensated_summation(compensated);
+            assert_eq!(plugin.compensated_summation(), compensated);
+            let mut forces = HashMap::new();
+            GravitySystem::new(plugin).compute_forces_into(&entities, &positions, &masses, &mut forces);
+            forces[&probe].fx
+        };
+
+        // G·m·Σmᵢ/r² with the huge pulls cancelling exactly
+        let expected = light_count as Real;
+        let naive_error = (probe_force(false) - expected).abs();
+        let compensated_error = (probe_force(true) - expected).abs();
+        assert!(
+            compensated_error < naive_error,
+            "compensated error {} not below naive error {}",
+            compensated_error,
+            naive_error
+        );
+        assert!(compensated_error < 1e-6 * expected);
     }
 }
@@ -217,13 +217,32 @@
 pub mod api;
 pub mod registry;
 pub mod gravity;
+pub mod anchor;
+pub mod collision;
+pub mod barnes_hut;
+pub mod uniform_gravity;
+pub mod thrust;
+pub mod sinusoidal;
+pub mod speed_limit;
+pub mod variable_mass;
 
 pub use api::{
     Plugin, PluginContext, ObjectFactory, ForceProviderPlugin,
     ConstraintSystem, PLUGIN_API_VERSION,
 };
 pub use registry::PluginRegistry;
-pub use gravity::{GravityPlugin, GravitySystem, GRAVITATIONAL_CONSTANT};
+pub use gravity::{
+    GravityError, GravityOptions, GravityPlugin, GravitySystem, MassDistribution, SofteningKernel, Tracer, GRAVITATIONAL_CONSTANT,
+    circular_orbit_velocity, set_circular_orbit, suggest_timestep,
+};
+pub use anchor::{AnchorForcePlugin, AnchorSystem, Tether};
+pub use collision::{CollisionEvent, CollisionEvents, CollisionSystem, ContactEvent};
+pub use barnes_hut::BarnesHutSystem;
+pub use uniform_gravity::{UniformGravityPlugin, UniformGravitySystem};
+pub use thrust::ThrustForcePlugin;
+pub use sinusoidal::SinusoidalForcePlugin;
+pub use speed_limit::{LimitEvent, LimitKind, MotionLimits, SpeedLimitConstraint, SpeedLimitSystem};
+pub use variable_mass::{MassFlow, VariableMassSystem};
 
 #[cfg(test)]
 mod tests {
@@ -18,6 +18,25 @@
 //! - Dependency resolution and circular dependency detection
 //! - Version compatibility checking
 //! - Optional dynamic plugin discovery via environment variables
+//! - Panic isolation for plugin lifecycle calls
+//!
+//! # Panic Isolation
+//!
+//! A plugin that panics in `initialize`, `update` or `shutdown` does not
+//! unwind through the registry. The panic is caught and reported as
+//! `PhysicsError::PluginPanicked`, naming the plugin and carrying the panic
+//! message, and the plugin is
+//! marked as poisoned. `update_all()` skips poisoned plugins, since a panic
+//! may have left them half-updated, until `clear_poison()` is called.
+//!
+//! # Shutdown
+//!
+//! `shutdown_all()` attempts every initialized plugin, in reverse load order,
+//! even when some fail, and reports all failures together. A registry that
+//! is dropped while initialized shuts its plugins down the same way, logging
+//! failures, so a host that forgets `shutdown_all()` doesn't leak plugin
+//! resources. A failed `initialize_all()` shuts down the plugins it had
+//! already initialized.
 //!
 //! # Environment Configuration
 //!
@@ -26,8 +45,11 @@
 //! export PHYSICS_ENGINE_PLUGIN_PATH=/path/to/plugins:/another/path
 //! ```
 
+use crate::error::PhysicsError;
 use crate::plugins::api::{Plugin, PLUGIN_API_VERSION};
+use std::any::Any;
 use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
 use semver::Version;
 
 /// Plugin registry for managing and executing plugins
@@ -47,6 +69,10 @@ pub struct PluginRegistry {
     load_order: Vec<String>,
     /// Whether the registry has been initialized
     initialized: bool,
+    /// Plugins that panicked, in the order they did
+    poisoned: Vec<String>,
+    /// Plugins shut down since they were last initialized
+    shut_down: Vec<String>,
 }
 
 impl PluginRegistry {
@@ -56,6 +82,8 @@ impl PluginRegistry {
             plugins: HashMap::new(),
             load_order: Vec::new(),
             initialized: false,
+            poisoned: Vec::new(),
+            shut_down: Vec::new(),
         }
     }
 
@@ -168,13 +196,16 @@ impl PluginRegistry {
     ///
     /// - Missing dependencies
     /// - Circular dependencies detected
-    /// - Plugin initialization failure
+    /// - Plugin initialization failure or panic
+    ///
+    /// If a plugin fails, the plugins initialized before it are shut down in
+    /// reverse order, leaving the registry uninitialized.
     pub fn initialize_all(
         &mut self,
         context: &crate::plugins::api::PluginContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), PhysicsError> {
         if self.initialized {
-            return Err("Registry already initialized".to_string());
+            return Err(PhysicsError::Registry("Registry already initialized".to_string()));
         }
 
         // Build dependency graph and check for missing dependencies
@@ -189,10 +220,10 @@ impl PluginRegistry {
             // Check if all dependencies are registered
             for dep in &deps {
                 if !self.plugins.contains_key(dep) {
-                    return Err(format!(
+                    return Err(PhysicsError::Registry(format!(
                         "Plugin '{}' depends on '{}' which is not registered",
                         name, dep
-                    ));
+                    )));
                 }
             }
 
@@ -200,14 +231,22 @@ impl PluginRegistry {
         }
 
         // Topological sort to determine load order and detect cycles
-        self.load_order = topological_sort(&dependencies)?;
+        self.load_order = topological_sort(&dependencies).map_err(PhysicsError::Registry)?;
 
         // Initialize plugins in dependency order
-        for name in &self.load_order {
+        self.shut_down.clear();
+        for (index, name) in self.load_order.iter().enumerate() {
             if let Some(plugin) = self.plugins.get_mut(name) {
-                plugin.initialize(context).map_err(|e| {
-                    format!("Failed to initialize plugin '{}': {}", name, e)
-                })?;
+                let result = guard_plugin_call(name, &mut self.poisoned, || {
+                    plugin.initialize(context)
+                });
+
+                if let Err(error) = result {
+                    // Unwind the plugins that did initialize. Their shutdown
+                    // errors are secondary to the initialization failure.
+                    let _ = self.shutdown_in_reverse(index);
+                    return Err(error);
+                }
             }
         }
 
@@ -217,7 +256,8 @@ impl PluginRegistry {
 
     /// Update all plugins
     ///
-    /// Calls the update method on all initialized plugins in load order.
+    /// Calls the update method on all initialized plugins in load order,
+    /// skipping poisoned plugins.
     ///
     /// # Arguments
     ///
@@ -226,44 +266,82 @@ impl PluginRegistry {
     /// # Returns
     ///
     /// Ok(()) if all plugins updated successfully, or Err with first error encountered.
+    /// An error returned by a plugin stops the update there; a panicking plugin
+    /// is poisoned and the remaining plugins are still updated before the panic
+    /// is reported.
     pub fn update_all(
         &mut self,
         context: &crate::plugins::api::PluginContext,
-    ) -> Result<(), String> {
+    ) -> Result<(), PhysicsError> {
         if !self.initialized {
-            return Err("Registry not initialized".to_string());
+            return Err(PhysicsError::Registry("Registry not initialized".to_string()));
         }
 
+        let mut first_panic = None;
         for name in &self.load_order {
+            if self.poisoned.contains(name) {
+                continue;
+            }
             if let Some(plugin) = self.plugins.get_mut(name) {
-                plugin.update(context).map_err(|e| {
-                    format!("Failed to update plugin '{}': {}", name, e)
-                })?;
+                match guard_plugin_call(name, &mut self.poisoned, || plugin.update(context)) {
+                    Err(error @ PhysicsError::PluginPanicked { .. }) => {
+                        first_panic.get_or_insert(error);
+                    }
+                    other => other?,
+                }
             }
         }
 
-        Ok(())
+        match first_panic {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 
     /// Shutdown all plugins
     ///
     /// Calls the shutdown method on all plugins in reverse load order.
+    /// Poisoned plugins are shut down too, so they can release resources; a
+    /// panic during shutdown is caught and reported like any other error.
+    ///
+    /// A failing plugin does not stop the others from being shut down. The
+    /// error lists every failure, one per line, and the registry is left
+    /// uninitialized either way.
     pub fn shutdown_all(&mut self) -> Result<(), String> {
         if !self.initialized {
             return Ok(()); // Nothing to shutdown
         }
 
-        // Shutdown in reverse order
-        for name in self.load_order.iter().rev() {
+        self.initialized = false;
+        self.shutdown_in_reverse(self.load_order.len())
+    }
+
+    /// Shut down the first `count` plugins in load order, last first
+    ///
+    /// Every plugin is attempted; failures are joined into one error.
+    fn shutdown_in_reverse(&mut self, count: usize) -> Result<(), String> {
+        let mut failures = Vec::new();
+        for name in self.load_order[..count].iter().rev() {
             if let Some(plugin) = self.plugins.get_mut(name) {
-                plugin.shutdown().map_err(|e| {
-                    format!("Failed to shutdown plugin '{}': {}", name, e)
-                })?;
+                if let Err(e) = guard_plugin_call(name, &mut self.poisoned, || plugin.shutdown()) {
+                    let reason = match e {
+                        PhysicsError::PluginFailed { message, .. } => message,
+                        PhysicsError::PluginPanicked { payload_message, .. } => {
+                            format!("plugin panicked: {}", payload_message)
+                        }
+                        other => other.to_string(),
+                    };
+                    failures.push(format!("Failed to shutdown plugin '{}': {}", name, reason));
+                }
+                self.shut_down.push(name.clone());
             }
         }
 
-        self.initialized = false;
-        Ok(())
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("\n"))
+        }
     }
 
     /// Get a plugin by name
@@ -290,12 +368,45 @@ impl PluginRegistry {
         self.initialized
     }
 
+    /// Check if a plugin has been shut down since it was last initialized
+    ///
+    /// True once `shutdown()` has been called on the plugin, whether or not
+    /// it succeeded, including during a rollback of a failed
+    /// `initialize_all()`. False for plugins that are running or were never
+    /// initialized.
+    pub fn is_shut_down(&self, name: &str) -> bool {
+        self.shut_down.iter().any(|p| p == name)
+    }
+
     /// Get the plugin load order
     ///
     /// Returns the names of plugins in the order they were initialized.
     pub fn load_order(&self) -> &[String] {
         &self.load_order
     }
+
+    /// Check if a plugin is poisoned by an earlier panic
+    pub fn is_poisoned(&self, name: &str) -> bool {
+        self.poisoned.iter().any(|p| p == name)
+    }
+
+    /// Get the names of poisoned plugins, in the order they panicked
+    pub fn poisoned_plugins(&self) -> &[String] {
+        &self.poisoned
+    }
+
+    /// Let a poisoned plugin be updated again
+    ///
+    /// The registry cannot tell whether a panic left the plugin in a usable
+    /// state; clearing the poison asserts that it did, for example after the
+    /// plugin has been reset through `get_mut()`.
+    ///
+    /// Returns whether the plugin was poisoned.
+    pub fn clear_poison(&mut self, name: &str) -> bool {
+        let before = self.poisoned.len();
+        self.poisoned.retain(|p| p != name);
+        self.poisoned.len() != before
+    }
 }
 
 impl Default for PluginRegistry {
@@ -304,6 +415,58 @@ impl Default for PluginRegistry {
     }
 }
 
+impl Drop for PluginRegistry {
+    /// Shut down plugins the host left initialized
+    fn drop(&mut self) {
+        if let Err(errors) = self.shutdown_all() {
+            for error in errors.lines() {
+                eprintln!("Warning: {} (while dropping the plugin registry)", error);
+            }
+        }
+    }
+}
+
+/// Run a plugin lifecycle call, converting a panic into an error
+///
+/// A returned error becomes `PhysicsError::PluginFailed` and a panic
+/// `PhysicsError::PluginPanicked`, both naming the plugin.
+///
+/// The call is wrapped in `AssertUnwindSafe`: a plugin that panics may be
+/// left in an inconsistent state, which is why it is poisoned rather than
+/// called again.
+fn guard_plugin_call(
+    name: &str,
+    poisoned: &mut Vec<String>,
+    call: impl FnOnce() -> Result<(), String>,
+) -> Result<(), PhysicsError> {
+    match panic::catch_unwind(AssertUnwindSafe(call)) {
+        Ok(result) => result.map_err(|message| PhysicsError::PluginFailed {
+            plugin: name.to_string(),
+            message,
+        }),
+        Err(payload) => {
+            if !poisoned.iter().any(|p| p == name) {
+                poisoned.push(name.to_string());
+            }
+            Err(PhysicsError::PluginPanicked {
+                plugin: name.to_string(),
+                payload_message: panic_message(payload.as_ref()).to_string(),
+            })
+        }
+    }
+}
+
+/// Extract the message from a panic payload
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
 /// Check if a plugin API version is compatible with the engine
 ///
 /// Uses semantic versioning rules:
@@ -417,6 +580,7 @@ mod tests {
     use crate::plugins::api::{Plugin, PluginContext};
     use crate::ecs::World;
     use std::any::Any;
+    use std::sync::{Arc, Mutex};
 
     struct TestPlugin {
         name: String,
@@ -565,7 +729,7 @@ mod tests {
 
         let result = registry.initialize_all(&context);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not registered"));
+        assert!(result.unwrap_err().to_string().contains("not registered"));
     }
 
     #[test]
@@ -592,7 +756,7 @@ mod tests {
 
         let result = registry.initialize_all(&context);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Circular dependency"));
+        assert!(result.unwrap_err().to_string().contains("Circular dependency"));
     }
 
     #[test]
@@ -708,4 +872,308 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), 0);
     }
+
+    /// Plugin that panics on initialize or on update
+    struct PanickingPlugin {
+        name: String,
+        deps: Vec<String>,
+        panic_on_initialize: bool,
+        update_calls: usize,
+    }
+
+    impl PanickingPlugin {
+        fn new(name: &str, deps: Vec<&str>, panic_on_initialize: bool) -> Self {
+            PanickingPlugin {
+                name: name.to_string(),
+                deps: deps.iter().map(|s| s.to_string()).collect(),
+                panic_on_initialize,
+                update_calls: 0,
+            }
+        }
+    }
+
+    impl Plugin for PanickingPlugin {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn dependencies(&self) -> Vec<&str> {
+            self.deps.iter().map(|s| s.as_str()).collect()
+        }
+
+        fn initialize(&mut self, _context: &PluginContext) -> Result<(), String> {
+            if self.panic_on_initialize {
+                panic!("initialize exploded");
+            }
+            Ok(())
+        }
+
+        fn update(&mut self, _context: &PluginContext) -> Result<(), String> {
+            self.update_calls += 1;
+            panic!("update {} exploded", self.update_calls);
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    fn update_count(registry: &PluginRegistry, name: &str) -> usize {
+        registry.get(name).unwrap().as_any().downcast_ref::<TestPlugin>().unwrap().update_count
+    }
+
+    #[test]
+    fn test_panicking_plugin_does_not_stop_others() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(TestPlugin::new("first", vec![]))).unwrap();
+        registry.register(Box::new(PanickingPlugin::new("faulty", vec!["first"], false))).unwrap();
+        registry.register(Box::new(TestPlugin::new("last", vec!["faulty"]))).unwrap();
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        registry.initialize_all(&context).unwrap();
+        let error = registry.update_all(&context).unwrap_err();
+        assert_eq!(
+            error,
+            PhysicsError::PluginPanicked {
+                plugin: "faulty".to_string(),
+                payload_message: "update 1 exploded".to_string(),
+            }
+        );
+
+        // Plugins after the faulty one in load order still ran
+        assert_eq!(update_count(&registry, "first"), 1);
+        assert_eq!(update_count(&registry, "last"), 1);
+        assert_eq!(registry.poisoned_plugins(), ["faulty"]);
+
+        // The poisoned plugin is skipped from now on
+        registry.update_all(&context).unwrap();
+        assert_eq!(update_count(&registry, "last"), 2);
+        let faulty = registry.get("faulty").unwrap().as_any().downcast_ref::<PanickingPlugin>().unwrap();
+        assert_eq!(faulty.update_calls, 1);
+    }
+
+    #[test]
+    fn test_clear_poison() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(PanickingPlugin::new("faulty", vec![], false))).unwrap();
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        registry.initialize_all(&context).unwrap();
+        assert!(registry.update_all(&context).is_err());
+        assert!(registry.is_poisoned("faulty"));
+        assert!(registry.update_all(&context).is_ok());
+
+        assert!(registry.clear_poison("faulty"));
+        assert!(!registry.clear_poison("faulty"));
+        assert!(!registry.is_poisoned("faulty"));
+        assert!(registry.poisoned_plugins().is_empty());
+
+        // Unpoisoned, the plugin is called (and panics) again
+        let error = registry.update_all(&context).unwrap_err();
+        assert_eq!(
+            error,
+            PhysicsError::PluginPanicked {
+                plugin: "faulty".to_string(),
+                payload_message: "update 2 exploded".to_string(),
+            }
+        );
+        assert!(registry.is_poisoned("faulty"));
+    }
+
+    /// Plugin that records its lifecycle calls in a shared log
+    struct TrackingPlugin {
+        name: String,
+        deps: Vec<String>,
+        log: Arc<Mutex<Vec<String>>>,
+        fail_initialize: bool,
+        fail_shutdown: bool,
+    }
+
+    impl TrackingPlugin {
+        fn new(name: &str, deps: Vec<&str>, log: &Arc<Mutex<Vec<String>>>) -> Self {
+            TrackingPlugin {
+                name: name.to_string(),
+                deps: deps.iter().map(|s| s.to_string()).collect(),
+                log: log.clone(),
+                fail_initialize: false,
+                fail_shutdown: false,
+            }
+        }
+    }
+
+    impl Plugin for TrackingPlugin {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn dependencies(&self) -> Vec<&str> {
+            self.deps.iter().map(|s| s.as_str()).collect()
+        }
+
+        fn initialize(&mut self, _context: &PluginContext) -> Result<(), String> {
+            self.log.lock().unwrap().push(format!("initialize {}", self.name));
+            if self.fail_initialize {
+                return Err("no resources".to_string());
+            }
+            Ok(())
+        }
+
+        fn update(&mut self, _context: &PluginContext) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn shutdown(&mut self) -> Result<(), String> {
+            self.log.lock().unwrap().push(format!("shutdown {}", self.name));
+            if self.fail_shutdown {
+                return Err(format!("{} is stuck", self.name));
+            }
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    /// Register a chain a ← b ← c of tracking plugins, configured by `setup`
+    fn tracked_chain(
+        log: &Arc<Mutex<Vec<String>>>,
+        setup: impl Fn(&mut TrackingPlugin),
+    ) -> PluginRegistry {
+        let mut registry = PluginRegistry::new();
+        for (name, deps) in [("a", vec![]), ("b", vec!["a"]), ("c", vec!["b"])] {
+            let mut plugin = TrackingPlugin::new(name, deps, log);
+            setup(&mut plugin);
+            registry.register(Box::new(plugin)).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn test_failed_initialize_rolls_back_earlier_plugins() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = tracked_chain(&log, |plugin| plugin.fail_initialize = plugin.name == "b");
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        let error = registry.initialize_all(&context).unwrap_err();
+        assert_eq!(
+            error,
+            PhysicsError::PluginFailed {
+                plugin: "b".to_string(),
+                message: "no resources".to_string(),
+            }
+        );
+        assert!(!registry.is_initialized());
+        assert_eq!(*log.lock().unwrap(), ["initialize a", "initialize b", "shutdown a"]);
+        assert!(registry.is_shut_down("a"));
+        assert!(!registry.is_shut_down("b"));
+        assert!(!registry.is_shut_down("c"));
+
+        // Nothing is left running for drop to shut down
+        drop(registry);
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_shutdown_all_reports_every_failure() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = tracked_chain(&log, |plugin| plugin.fail_shutdown = plugin.name != "b");
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        registry.initialize_all(&context).unwrap();
+        assert!(!registry.is_shut_down("a"));
+        let error = registry.shutdown_all().unwrap_err();
+
+        // Both failures are reported and the plugin between them still ran
+        assert_eq!(
+            error.lines().collect::<Vec<_>>(),
+            ["Failed to shutdown plugin 'c': c is stuck", "Failed to shutdown plugin 'a': a is stuck"]
+        );
+        assert_eq!(log.lock().unwrap()[3..], ["shutdown c", "shutdown b", "shutdown a"]);
+        assert!(["a", "b", "c"].iter().all(|name| registry.is_shut_down(name)));
+        assert!(!registry.is_initialized());
+        assert!(registry.shutdown_all().is_ok());
+    }
+
+    #[test]
+    fn test_drop_shuts_down_initialized_plugins() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = tracked_chain(&log, |plugin| plugin.fail_shutdown = plugin.name == "b");
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        registry.initialize_all(&context).unwrap();
+        drop(registry);
+
+        // Reverse load order, and b's failure doesn't stop a
+        assert_eq!(log.lock().unwrap()[3..], ["shutdown c", "shutdown b", "shutdown a"]);
+    }
+
+    #[test]
+    fn test_panic_during_initialize_shuts_down_initialized_plugins() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(TestPlugin::new("base", vec![]))).unwrap();
+        registry.register(Box::new(PanickingPlugin::new("faulty", vec!["base"], true))).unwrap();
+
+        let world = World::new();
+        #[cfg(feature = "parallel")]
+        let context = PluginContext::new(&world, "test", 0.016, None);
+        #[cfg(not(feature = "parallel"))]
+        let context = PluginContext::new(&world, "test", 0.016);
+
+        let error = registry.initialize_all(&context).unwrap_err();
+        match error {
+            PhysicsError::PluginPanicked { plugin, payload_message } => {
+                assert_eq!(plugin, "faulty");
+                assert_eq!(payload_message, "initialize exploded");
+            }
+            other => panic!("Expected a plugin panic, got {:?}", other),
+        }
+        assert!(!registry.is_initialized());
+        assert!(registry.is_poisoned("faulty"));
+
+        let base = registry.get("base").unwrap().as_any().downcast_ref::<TestPlugin>().unwrap();
+        assert_eq!(base.init_count, 1);
+        assert_eq!(base.shutdown_count, 1);
+    }
 }
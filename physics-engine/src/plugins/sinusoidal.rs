@@ -0,0 +1,258 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Sinusoidal driving force plugin
+//!
+//! A reference time-dependent force provider, for oscillating drivers,
+//! vibrating mounts and periodic forcing.
+//!
+//! # Physics Background
+//!
+//! Every target body feels the same periodic force:
+//!
+//! **F(t) = A·sin(ωt + φ)**
+//!
+//! Where:
+//! - A is the amplitude vector (N)
+//! - ω is the angular frequency (rad/s)
+//! - φ is the phase (rad)
+//!
+//! # Usage
+//!
+//! The force depends only on the simulation time, which providers read from
+//! `ForceRegistry::time()`. The integrators set that time for each stage they
+//! evaluate, so register the plugin once and it is driven correctly, including
+//! at RK4's midpoint stages.
+
+use crate::real::Real;
+use crate::ecs::Entity;
+use crate::ecs::systems::{Force, ForceRegistry, ForceProvider};
+use crate::plugins::{Plugin, ForceProviderPlugin};
+use std::any::Any;
+use std::collections::HashSet;
+
+/// Sinusoidal driving force applied to a set of target entities
+#[derive(Debug, Clone)]
+pub struct SinusoidalForcePlugin {
+    /// Amplitude vector A (N)
+    amplitude: Force,
+    /// Angular frequency ω (rad/s)
+    angular_frequency: Real,
+    /// Phase φ (rad)
+    phase: Real,
+    /// Entities the force is applied to
+    targets: HashSet<Entity>,
+}
+
+impl SinusoidalForcePlugin {
+    /// Create a driver with no targets
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amplitude, angular frequency, or phase is not finite.
+    pub fn new(amplitude: Force, angular_frequency: Real, phase: Real) -> Self {
+        assert!(amplitude.is_valid(), "Driving amplitude must be finite");
+        assert!(
+            angular_frequency.is_finite() && phase.is_finite(),
+            "Driving frequency and phase must be finite"
+        );
+        SinusoidalForcePlugin {
+            amplitude,
+            angular_frequency,
+            phase,
+            targets: HashSet::new(),
+        }
+    }
+
+    /// Apply the driving force to an entity
+    pub fn add_target(&mut self, entity: Entity) {
+        self.targets.insert(entity);
+    }
+
+    /// Stop driving an entity, returning whether it was a target
+    pub fn remove_target(&mut self, entity: Entity) -> bool {
+        self.targets.remove(&entity)
+    }
+
+    /// Check whether an entity is driven
+    pub fn is_target(&self, entity: Entity) -> bool {
+        self.targets.contains(&entity)
+    }
+
+    /// Get the amplitude vector
+    pub fn amplitude(&self) -> Force {
+        self.amplitude
+    }
+
+    /// Get the angular frequency (rad/s)
+    pub fn angular_frequency(&self) -> Real {
+        self.angular_frequency
+    }
+
+    /// Get the phase (rad)
+    pub fn phase(&self) -> Real {
+        self.phase
+    }
+
+    /// Compute the driving force F(t) = A·sin(ωt + φ) at time `t`
+    pub fn force_at(&self, t: Real) -> Force {
+        let s = (self.angular_frequency * t + self.phase).sin();
+        Force::new(self.amplitude.fx * s, self.amplitude.fy * s, self.amplitude.fz * s)
+    }
+}
+
+impl Plugin for SinusoidalForcePlugin {
+    fn name(&self) -> &str {
+        "sinusoidal_force"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl ForceProvider for SinusoidalForcePlugin {
+    fn compute_force(&self, entity: Entity, registry: &ForceRegistry) -> Option<Force> {
+        if !self.is_target(entity) {
+            return None;
+        }
+        Some(self.force_at(registry.time()))
+    }
+
+    fn name(&self) -> &str {
+        "sinusoidal_force"
+    }
+}
+
+impl ForceProviderPlugin for SinusoidalForcePlugin {
+    fn as_force_provider(&self) -> &dyn ForceProvider {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{ComponentStorage, HashMapStorage};
+    use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+    use crate::ecs::systems::apply_forces_to_acceleration;
+    use crate::integration::{Integrator, RK4Integrator, VelocityVerletIntegrator};
+    use crate::real::RealExt;
+
+    const OMEGA: Real = 2.0;
+    const DURATION: Real = 2.0;
+
+    /// Exact position at `DURATION` of a unit mass at rest at the origin,
+    /// driven by F = sin(ωt): x(t) = t/ω − sin(ωt)/ω²
+    fn exact_position() -> Real {
+        DURATION / OMEGA - (OMEGA * DURATION).sin() / (OMEGA * OMEGA)
+    }
+
+    /// Drive a unit mass for `DURATION` and return the position error
+    fn driven_error(integrator: &mut impl Integrator, steps: usize) -> Real {
+        let entity = Entity::new(1, 0);
+        let entities = [entity];
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(entity, Position::zero());
+        velocities.insert(entity, Velocity::zero());
+        masses.insert(entity, Mass::new(1.0));
+
+        let mut driver = SinusoidalForcePlugin::new(Force::new(1.0, 0.0, 0.0), OMEGA, 0.0);
+        driver.add_target(entity);
+        let mut registry = ForceRegistry::new();
+        registry.set_warn_on_missing_components(false);
+        registry.register_provider(Box::new(driver));
+
+        for _ in 0..steps {
+            // Accelerations at the start of the step; the integrator advances
+            // the registry time itself
+            registry.clear_forces();
+            registry.accumulate_for_entity(entity);
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut registry,
+                false,
+            );
+        }
+
+        assert!((registry.time() - DURATION).abs() < Real::TOLERANCE);
+        (positions.get(entity).unwrap().x() - exact_position()).abs()
+    }
+
+    #[test]
+    fn test_force_follows_time() {
+        let entity = Entity::new(1, 0);
+        let mut driver = SinusoidalForcePlugin::new(Force::new(0.0, 2.0, 0.0), 1.0, 0.5);
+        driver.add_target(entity);
+
+        let mut registry = ForceRegistry::new();
+        registry.set_time(1.0);
+        let force = driver.compute_force(entity, &registry).unwrap();
+        assert!((force.fy - 2.0 * (1.5 as Real).sin()).abs() < Real::TOLERANCE);
+        assert!(driver.compute_force(Entity::new(2, 0), &registry).is_none());
+
+        assert!(driver.remove_target(entity));
+        assert!(driver.compute_force(entity, &registry).is_none());
+    }
+
+    #[test]
+    fn test_rk4_fourth_order_with_driven_force() {
+        // Errors only fall 16x per halving if the midpoint stages see t + dt/2
+        let coarse = driven_error(&mut RK4Integrator::new(DURATION / 10.0), 10);
+        let fine = driven_error(&mut RK4Integrator::new(DURATION / 20.0), 20);
+        let ratio = coarse / fine;
+        assert!(
+            ratio > 12.0 && ratio < 20.0,
+            "RK4 error ratio {} (coarse {}, fine {}) is not 4th order",
+            ratio,
+            coarse,
+            fine
+        );
+    }
+
+    #[test]
+    fn test_verlet_second_order_with_driven_force() {
+        let coarse = driven_error(&mut VelocityVerletIntegrator::new(DURATION / 20.0), 20);
+        let fine = driven_error(&mut VelocityVerletIntegrator::new(DURATION / 40.0), 40);
+        let ratio = coarse / fine;
+        assert!(
+            ratio > 3.5 && ratio < 4.5,
+            "Verlet error ratio {} (coarse {}, fine {}) is not 2nd order",
+            ratio,
+            coarse,
+            fine
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Driving frequency and phase must be finite")]
+    fn test_non_finite_frequency_panics() {
+        SinusoidalForcePlugin::new(Force::new(1.0, 0.0, 0.0), Real::NAN, 0.0);
+    }
+}
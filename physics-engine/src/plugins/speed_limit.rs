@@ -0,0 +1,504 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Speed limiting constraint
+//!
+//! Explicit integrators can produce runaway velocities when bodies pass close
+//! to a singularity, such as two point masses in a near-collision. Capping
+//! speed is a cheap safeguard that keeps demos stable. It does not conserve
+//! energy or momentum, so use it for presentation rather than measurement.
+//!
+//! # Usage
+//!
+//! `SpeedLimitConstraint` rescales the velocity of any target body moving
+//! faster than the cap so its speed equals the cap, leaving its direction
+//! unchanged. Register it with `Simulation::add_constraint()` to apply it in
+//! the constraint phase of every step:
+//!
+//! ```
+//! use physics_engine::ecs::components::{Mass, Position, Velocity};
+//! use physics_engine::integration::VelocityVerletIntegrator;
+//! use physics_engine::plugins::speed_limit::SpeedLimitConstraint;
+//! use physics_engine::simulation::Simulation;
+//! use physics_engine::{Real, RealExt};
+//!
+//! let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+//! let body = sim.spawn_body(Position::zero(), Velocity::new(30.0, 40.0, 0.0), Mass::new(1.0));
+//!
+//! let mut limiter = SpeedLimitConstraint::new(10.0);
+//! limiter.add_target(body);
+//! sim.add_constraint(Box::new(limiter));
+//!
+//! sim.step().unwrap();
+//! assert!((sim.velocity(body).unwrap().magnitude() - 10.0).abs() < Real::TOLERANCE);
+//! ```
+//!
+//! `SpeedLimitSystem` is the diagnostic variant for every body: a global
+//! maximum speed and acceleration, overridable per body with the
+//! `MotionLimits` component. It skips immovable bodies and records a
+//! `LimitEvent` for each vector it rescales instead of clamping silently, so
+//! a step can tell how often and how hard the limits bit. Enable it with
+//! `Simulation::set_speed_limits()`; accelerations are capped after the
+//! force phase and speeds after the integrate phase, and the events are
+//! cleared at the start of each step.
+
+use crate::real::Real;
+use crate::ecs::{Component, ComponentStorage, Entity};
+use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+use crate::plugins::{Plugin, ConstraintSystem};
+use std::any::Any;
+use std::collections::HashSet;
+
+/// Constraint capping the speed of a set of target entities
+#[derive(Debug, Clone)]
+pub struct SpeedLimitConstraint {
+    /// Maximum allowed speed (m/s)
+    max_speed: Real,
+    /// Entities whose speed is capped
+    targets: HashSet<Entity>,
+}
+
+impl SpeedLimitConstraint {
+    /// Create a speed limit with no targets
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_speed` is negative or not finite.
+    pub fn new(max_speed: Real) -> Self {
+        assert!(
+            max_speed.is_finite() && max_speed >= 0.0,
+            "Maximum speed must be finite and non-negative"
+        );
+        SpeedLimitConstraint {
+            max_speed,
+            targets: HashSet::new(),
+        }
+    }
+
+    /// Cap the speed of an entity
+    pub fn add_target(&mut self, entity: Entity) {
+        self.targets.insert(entity);
+    }
+
+    /// Stop capping an entity, returning whether it was a target
+    pub fn remove_target(&mut self, entity: Entity) -> bool {
+        self.targets.remove(&entity)
+    }
+
+    /// Check whether an entity's speed is capped
+    pub fn is_target(&self, entity: Entity) -> bool {
+        self.targets.contains(&entity)
+    }
+
+    /// Get the maximum allowed speed (m/s)
+    pub fn max_speed(&self) -> Real {
+        self.max_speed
+    }
+
+    /// Rescale a velocity so its magnitude does not exceed the cap
+    ///
+    /// Returns whether the velocity was changed. Velocities at or under the
+    /// cap, and non-finite velocities, are left untouched.
+    pub fn limit(&self, velocity: &mut Velocity) -> bool {
+        match Self::clamp(velocity.as_array(), self.max_speed) {
+            Some((clamped, _)) => {
+                *velocity = Velocity::from_array(clamped);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scale `vector` down to magnitude `cap`, returning it and its original magnitude
+    ///
+    /// Every component is multiplied by the same factor, so the direction is
+    /// kept. Vectors at or under the cap, and non-finite vectors, give None.
+    fn clamp(vector: [Real; 3], cap: Real) -> Option<([Real; 3], Real)> {
+        let magnitude = (vector[0] * vector[0] + vector[1] * vector[1] + vector[2] * vector[2]).sqrt();
+        if !magnitude.is_finite() || magnitude <= cap {
+            return None;
+        }
+        let scale = cap / magnitude;
+        Some(([vector[0] * scale, vector[1] * scale, vector[2] * scale], magnitude))
+    }
+}
+
+impl Plugin for SpeedLimitConstraint {
+    fn name(&self) -> &str {
+        "speed_limit"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl ConstraintSystem for SpeedLimitConstraint {
+    /// Cap the speed of every target that has a velocity
+    ///
+    /// Targets without a velocity component are skipped.
+    fn apply_constraint(
+        &mut self,
+        _positions: &mut dyn ComponentStorage<Component = Position>,
+        velocities: &mut dyn ComponentStorage<Component = Velocity>,
+        _masses: &dyn ComponentStorage<Component = Mass>,
+    ) -> Result<(), String> {
+        for &entity in &self.targets {
+            if let Some(velocity) = velocities.get_mut(entity) {
+                self.limit(velocity);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-body speed and acceleration caps for `SpeedLimitSystem`
+///
+/// A cap left as `None` falls back to the system's global cap.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[non_exhaustive]
+pub struct MotionLimits {
+    /// Maximum speed (m/s)
+    pub max_speed: Option<Real>,
+    /// Maximum acceleration magnitude (m/s²)
+    pub max_acceleration: Option<Real>,
+}
+
+impl MotionLimits {
+    /// Create limits that cap nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap the speed
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_speed` is negative or not finite.
+    pub fn with_max_speed(mut self, max_speed: Real) -> Self {
+        assert!(
+            max_speed.is_finite() && max_speed >= 0.0,
+            "Maximum speed must be finite and non-negative"
+        );
+        self.max_speed = Some(max_speed);
+        self
+    }
+
+    /// Cap the acceleration magnitude
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_acceleration` is negative or not finite.
+    pub fn with_max_acceleration(mut self, max_acceleration: Real) -> Self {
+        assert!(
+            max_acceleration.is_finite() && max_acceleration >= 0.0,
+            "Maximum acceleration must be finite and non-negative"
+        );
+        self.max_acceleration = Some(max_acceleration);
+        self
+    }
+
+    /// Fill caps this set leaves open from `fallback`
+    fn or(self, fallback: MotionLimits) -> MotionLimits {
+        MotionLimits {
+            max_speed: self.max_speed.or(fallback.max_speed),
+            max_acceleration: self.max_acceleration.or(fallback.max_acceleration),
+        }
+    }
+}
+
+impl Component for MotionLimits {}
+
+/// Which cap a `LimitEvent` enforced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitKind {
+    /// A velocity was rescaled to the maximum speed
+    Speed,
+    /// An acceleration was rescaled to the maximum acceleration
+    Acceleration,
+}
+
+/// Record of one vector `SpeedLimitSystem` rescaled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitEvent {
+    /// The body that was limited
+    pub entity: Entity,
+    /// Which cap was enforced
+    pub kind: LimitKind,
+    /// Magnitude of the vector before it was rescaled
+    pub original_magnitude: Real,
+}
+
+/// System capping speed and acceleration, with global and per-body limits
+#[derive(Debug, Clone, Default)]
+pub struct SpeedLimitSystem {
+    global: MotionLimits,
+    /// Limits enforced since the last `clear_events()` or `take_events()`,
+    /// in enforcement order
+    events: Vec<LimitEvent>,
+}
+
+impl SpeedLimitSystem {
+    /// Create a system enforcing `global` on every body without its own limits
+    pub fn new(global: MotionLimits) -> Self {
+        SpeedLimitSystem {
+            global,
+            events: Vec::new(),
+        }
+    }
+
+    /// Get the global limits
+    pub fn global(&self) -> MotionLimits {
+        self.global
+    }
+
+    /// Replace the global limits
+    pub fn set_global(&mut self, global: MotionLimits) {
+        self.global = global;
+    }
+
+    /// Limits recorded since the last `clear_events()` or `take_events()`
+    pub fn events(&self) -> &[LimitEvent] {
+        &self.events
+    }
+
+    /// Discard the recorded limit events
+    ///
+    /// `Simulation` calls this at the start of every step, so its buffer
+    /// only ever holds one step's events.
+    pub fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// Drain the recorded limit events
+    pub fn take_events(&mut self) -> Vec<LimitEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Rescale accelerations above their body's maximum acceleration
+    ///
+    /// Each body's `MotionLimits` component overrides the global caps it
+    /// sets. Immovable bodies and non-finite accelerations are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Number of accelerations rescaled
+    pub fn limit_accelerations(
+        &mut self,
+        entities: &[Entity],
+        accelerations: &mut impl ComponentStorage<Component = Acceleration>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        limits: &impl ComponentStorage<Component = MotionLimits>,
+    ) -> usize {
+        let mut count = 0;
+        for &entity in entities {
+            let Some(cap) = self.limits_for(entity, masses, limits).max_acceleration else { continue };
+            let Some(acceleration) = accelerations.get_mut(entity) else { continue };
+
+            if let Some((clamped, original_magnitude)) = SpeedLimitConstraint::clamp(acceleration.as_array(), cap) {
+                *acceleration = Acceleration::from_array(clamped);
+                self.events.push(LimitEvent { entity, kind: LimitKind::Acceleration, original_magnitude });
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Rescale velocities above their body's maximum speed
+    ///
+    /// Each body's `MotionLimits` component overrides the global caps it
+    /// sets. Immovable bodies and non-finite velocities are skipped.
+    ///
+    /// # Returns
+    ///
+    /// Number of velocities rescaled
+    pub fn limit_velocities(
+        &mut self,
+        entities: &[Entity],
+        velocities: &mut impl ComponentStorage<Component = Velocity>,
+        masses: &impl ComponentStorage<Component = Mass>,
+        limits: &impl ComponentStorage<Component = MotionLimits>,
+    ) -> usize {
+        let mut count = 0;
+        for &entity in entities {
+            let Some(cap) = self.limits_for(entity, masses, limits).max_speed else { continue };
+            let Some(velocity) = velocities.get_mut(entity) else { continue };
+
+            if let Some((clamped, original_magnitude)) = SpeedLimitConstraint::clamp(velocity.as_array(), cap) {
+                *velocity = Velocity::from_array(clamped);
+                self.events.push(LimitEvent { entity, kind: LimitKind::Speed, original_magnitude });
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Effective limits for a body, or no limits if it is immovable
+    fn limits_for(
+        &self,
+        entity: Entity,
+        masses: &impl ComponentStorage<Component = Mass>,
+        limits: &impl ComponentStorage<Component = MotionLimits>,
+    ) -> MotionLimits {
+        if masses.get(entity).is_some_and(Mass::is_immovable) {
+            return MotionLimits::new();
+        }
+        match limits.get(entity) {
+            Some(own) => own.or(self.global),
+            None => self.global,
+        }
+    }
+}
+
+impl Plugin for SpeedLimitSystem {
+    fn name(&self) -> &str {
+        "speed_limit_system"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::HashMapStorage;
+    use crate::real::RealExt;
+
+    #[test]
+    fn test_limit_rescales_fast_bodies_and_keeps_slow_ones() {
+        let fast = Entity::new(1, 0);
+        let slow = Entity::new(2, 0);
+        let untracked = Entity::new(3, 0);
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let masses = HashMapStorage::<Mass>::new();
+        velocities.insert(fast, Velocity::new(30.0, -40.0, 120.0));
+        velocities.insert(slow, Velocity::new(3.0, 4.0, 0.0));
+        velocities.insert(untracked, Velocity::new(100.0, 0.0, 0.0));
+
+        let mut limiter = SpeedLimitConstraint::new(13.0);
+        limiter.add_target(fast);
+        limiter.add_target(slow);
+        limiter.add_target(untracked);
+        assert!(limiter.remove_target(untracked));
+        // A target without a velocity is skipped
+        limiter.add_target(Entity::new(4, 0));
+        limiter.apply_constraint(&mut positions, &mut velocities, &masses).unwrap();
+
+        // |v| = 130, so the cap scales it by 1/10 along the same direction
+        let capped = velocities.get(fast).unwrap();
+        assert!((capped.magnitude() - 13.0).abs() < Real::TOLERANCE);
+        for (got, want) in capped.as_array().iter().zip([3.0, -4.0, 12.0]) {
+            assert!((got - want).abs() < Real::TOLERANCE, "Component {} != {}", got, want);
+        }
+
+        assert_eq!(velocities.get(slow).unwrap().as_array(), [3.0, 4.0, 0.0]);
+        assert_eq!(velocities.get(untracked).unwrap().as_array(), [100.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_limit_leaves_speed_at_cap_and_zero_cap_stops_body() {
+        let mut velocity = Velocity::new(6.0, 8.0, 0.0);
+        assert!(!SpeedLimitConstraint::new(10.0).limit(&mut velocity));
+        assert_eq!(velocity.as_array(), [6.0, 8.0, 0.0]);
+
+        assert!(SpeedLimitConstraint::new(0.0).limit(&mut velocity));
+        assert_eq!(velocity.magnitude(), 0.0);
+    }
+
+    #[test]
+    fn test_system_clamps_to_cap_keeping_direction_and_records_events() {
+        let (fast, slow) = (Entity::new(1, 0), Entity::new(2, 0));
+        let entities = [fast, slow];
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let masses = HashMapStorage::<Mass>::new();
+        let limits = HashMapStorage::<MotionLimits>::new();
+        velocities.insert(fast, Velocity::new(30.0, -40.0, 120.0));
+        velocities.insert(slow, Velocity::new(3.0, 4.0, 0.0));
+        accelerations.insert(fast, Acceleration::new(0.0, 1.0, 0.0));
+        accelerations.insert(slow, Acceleration::new(0.0, -600.0, 800.0));
+
+        let mut system = SpeedLimitSystem::new(MotionLimits::new().with_max_speed(13.0).with_max_acceleration(100.0));
+        assert_eq!(system.limit_velocities(&entities, &mut velocities, &masses, &limits), 1);
+        assert_eq!(system.limit_accelerations(&entities, &mut accelerations, &masses, &limits), 1);
+
+        let capped = velocities.get(fast).unwrap();
+        assert!((capped.magnitude() - 13.0).abs() < Real::TOLERANCE);
+        for (got, want) in capped.as_array().iter().zip([3.0, -4.0, 12.0]) {
+            assert!((got - want).abs() < Real::TOLERANCE, "Component {} != {}", got, want);
+        }
+        assert_eq!(velocities.get(slow).unwrap().as_array(), [3.0, 4.0, 0.0]);
+        let [ax, ay, az] = accelerations.get(slow).unwrap().as_array();
+        assert_eq!(ax, 0.0);
+        assert!((ay + 60.0).abs() < Real::TOLERANCE && (az - 80.0).abs() < Real::TOLERANCE);
+
+        assert_eq!(
+            system.take_events(),
+            vec![
+                LimitEvent { entity: fast, kind: LimitKind::Speed, original_magnitude: 130.0 },
+                LimitEvent { entity: slow, kind: LimitKind::Acceleration, original_magnitude: 1000.0 },
+            ]
+        );
+        assert!(system.events().is_empty());
+    }
+
+    #[test]
+    fn test_system_per_body_limits_and_immovable_bodies() {
+        let (own, global, anchored) = (Entity::new(1, 0), Entity::new(2, 0), Entity::new(3, 0));
+        let entities = [own, global, anchored];
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        let mut limits = HashMapStorage::<MotionLimits>::new();
+        for &entity in &entities {
+            velocities.insert(entity, Velocity::new(50.0, 0.0, 0.0));
+            masses.insert(entity, Mass::new(1.0));
+        }
+        masses.insert(anchored, Mass::immovable());
+        limits.insert(own, MotionLimits::new().with_max_speed(20.0));
+        // An acceleration-only override still takes the global speed cap
+        limits.insert(global, MotionLimits::new().with_max_acceleration(1.0));
+
+        let mut system = SpeedLimitSystem::new(MotionLimits::new().with_max_speed(40.0));
+        assert_eq!(system.limit_velocities(&entities, &mut velocities, &masses, &limits), 2);
+        assert_eq!(velocities.get(own).unwrap().as_array(), [20.0, 0.0, 0.0]);
+        assert_eq!(velocities.get(global).unwrap().as_array(), [40.0, 0.0, 0.0]);
+        assert_eq!(velocities.get(anchored).unwrap().as_array(), [50.0, 0.0, 0.0]);
+        assert!(system.events().iter().all(|e| e.kind == LimitKind::Speed && e.original_magnitude == 50.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Maximum speed must be finite and non-negative")]
+    fn test_negative_max_speed_panics() {
+        SpeedLimitConstraint::new(-1.0);
+    }
+}
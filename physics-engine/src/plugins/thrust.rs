@@ -0,0 +1,229 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Constant applied force (thrust) plugin
+//!
+//! Rockets, actuators and scripted pushes apply a fixed force to specific
+//! bodies, independent of where they are or how fast they move. This plugin
+//! stores one force per entity and reports it until it is cleared.
+//!
+//! # Usage
+//!
+//! The force depends on nothing but the entity, so unlike `AnchorForcePlugin`
+//! this plugin is a complete `ForceProvider`. Register it once and it is
+//! evaluated every step, including the extra force evaluations integrators
+//! perform within a step:
+//!
+//! ```
+//! use physics_engine::ecs::World;
+//! use physics_engine::ecs::systems::{Force, ForceRegistry};
+//! use physics_engine::plugins::thrust::ThrustForcePlugin;
+//!
+//! let rocket = World::new().create_entity();
+//! let mut thrust = ThrustForcePlugin::new();
+//! thrust.set_thrust(rocket, Force::new(0.0, 50.0, 0.0));
+//!
+//! let mut registry = ForceRegistry::new();
+//! registry.register_provider(Box::new(thrust));
+//! registry.accumulate_for_entity(rocket);
+//! assert_eq!(registry.get_force(rocket), Some(Force::new(0.0, 50.0, 0.0)));
+//! ```
+//!
+//! The registry owns the provider once registered. To change thrust between
+//! steps, keep the returned `ProviderId` and replace the provider with
+//! `ForceRegistry::remove_provider()` and `register_provider()`.
+
+use crate::ecs::Entity;
+use crate::ecs::systems::{Force, ForceRegistry, ForceProvider};
+use crate::plugins::{Plugin, ForceProviderPlugin};
+use std::any::Any;
+use std::collections::HashMap;
+
+/// Thrust plugin holding a constant applied force per entity
+#[derive(Debug, Clone, Default)]
+pub struct ThrustForcePlugin {
+    /// Applied forces keyed by the entity they act on
+    thrusts: HashMap<Entity, Force>,
+}
+
+impl ThrustForcePlugin {
+    /// Create a new thrust plugin with no thrusting entities
+    pub fn new() -> Self {
+        ThrustForcePlugin {
+            thrusts: HashMap::new(),
+        }
+    }
+
+    /// Apply a constant force to an entity
+    ///
+    /// Replaces any thrust already set on the entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component of `force` is not finite.
+    pub fn set_thrust(&mut self, entity: Entity, force: Force) {
+        assert!(force.is_valid(), "Thrust force must be finite");
+        self.thrusts.insert(entity, force);
+    }
+
+    /// Stop thrusting an entity, returning its thrust if it had one
+    pub fn clear_thrust(&mut self, entity: Entity) -> Option<Force> {
+        self.thrusts.remove(&entity)
+    }
+
+    /// Get the thrust applied to an entity
+    pub fn thrust(&self, entity: Entity) -> Option<Force> {
+        self.thrusts.get(&entity).copied()
+    }
+
+    /// Get the number of thrusting entities
+    pub fn len(&self) -> usize {
+        self.thrusts.len()
+    }
+
+    /// Check if no entities are thrusting
+    pub fn is_empty(&self) -> bool {
+        self.thrusts.is_empty()
+    }
+}
+
+impl Plugin for ThrustForcePlugin {
+    fn name(&self) -> &str {
+        "thrust"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl ForceProvider for ThrustForcePlugin {
+    fn compute_force(&self, entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+        self.thrust(entity)
+    }
+
+    fn name(&self) -> &str {
+        "thrust"
+    }
+}
+
+impl ForceProviderPlugin for ThrustForcePlugin {
+    fn as_force_provider(&self) -> &dyn ForceProvider {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::real::Real;
+    use crate::ecs::{ComponentStorage, HashMapStorage, World};
+    use crate::ecs::components::{Acceleration, Mass, Position, Velocity};
+    use crate::ecs::systems::apply_forces_to_acceleration;
+    use crate::integration::{Integrator, VelocityVerletIntegrator};
+    use crate::plugins::PluginRegistry;
+    use crate::real::RealExt;
+
+    #[test]
+    fn test_set_and_clear_thrust() {
+        let entity = Entity::new(1, 0);
+        let mut plugin = ThrustForcePlugin::new();
+        assert!(plugin.is_empty());
+
+        plugin.set_thrust(entity, Force::new(1.0, 0.0, 0.0));
+        plugin.set_thrust(entity, Force::new(2.0, 0.0, 0.0));
+        assert_eq!(plugin.len(), 1);
+        assert_eq!(plugin.thrust(entity), Some(Force::new(2.0, 0.0, 0.0)));
+
+        assert_eq!(plugin.clear_thrust(entity), Some(Force::new(2.0, 0.0, 0.0)));
+        assert_eq!(plugin.clear_thrust(entity), None);
+        assert!(plugin.compute_force(entity, &ForceRegistry::new()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Thrust force must be finite")]
+    fn test_non_finite_thrust_panics() {
+        ThrustForcePlugin::new().set_thrust(Entity::new(1, 0), Force::new(Real::INFINITY, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_thrusted_body_accelerates_along_thrust() {
+        let mut world = World::new();
+        let rocket = world.create_entity();
+        let drifter = world.create_entity();
+        let entities = [rocket, drifter];
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        for &entity in &entities {
+            positions.insert(entity, Position::zero());
+            velocities.insert(entity, Velocity::new(0.0, 0.0, 1.0));
+            masses.insert(entity, Mass::new(2.0));
+        }
+
+        let mut thrust = ThrustForcePlugin::new();
+        thrust.set_thrust(rocket, Force::new(4.0, 0.0, 0.0));
+        let mut registry = ForceRegistry::new();
+        registry.set_warn_on_missing_components(false);
+        registry.register_provider(Box::new(thrust));
+
+        // Registered once, the thrust persists across steps
+        let mut integrator = VelocityVerletIntegrator::new(0.01);
+        let steps = 100;
+        for _ in 0..steps {
+            registry.clear_forces();
+            for &entity in &entities {
+                registry.accumulate_for_entity(entity);
+            }
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut registry,
+                false,
+            );
+        }
+
+        // a = F/m = 2 m/s² along x: v = a·t, x = ½·a·t²
+        let t = steps as Real * 0.01;
+        let vel = velocities.get(rocket).unwrap();
+        assert!((vel.dx() - 2.0 * t).abs() < Real::TOLERANCE);
+        assert!((positions.get(rocket).unwrap().x() - t * t).abs() < Real::TOLERANCE);
+        assert_eq!(vel.dy(), 0.0);
+        assert_eq!(vel.dz(), 1.0);
+
+        // The unthrusted body keeps its initial velocity
+        assert_eq!(velocities.get(drifter).unwrap().as_array(), [0.0, 0.0, 1.0]);
+        assert_eq!(positions.get(drifter).unwrap().x(), 0.0);
+    }
+
+    #[test]
+    fn test_registers_with_plugin_registry() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(ThrustForcePlugin::new())).unwrap();
+        assert_eq!(registry.get("thrust").unwrap().version(), "1.0.0");
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Uniform gravitational field plugin
+//!
+//! Many simulations only need "gravity points down" near a planet's surface,
+//! without the O(N²) cost of N-body gravity. This plugin applies a constant
+//! field to every body.
+//!
+//! # Physics Background
+//!
+//! Every body feels the same acceleration **g**, so the force on a body of
+//! mass m is:
+//!
+//! **F = m·g**
+//!
+//! Heavier bodies get proportionally larger forces but fall identically.
+//! Immovable bodies receive no force.
+//!
+//! # Usage
+//!
+//! The force depends on each body's mass, which the generic `ForceProvider`
+//! interface does not expose. Use `UniformGravitySystem::compute_forces()`
+//! each step instead, in the same way as `AnchorSystem`.
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Acceleration, Mass};
+use crate::ecs::systems::{Force, ForceRegistry, ForceProvider};
+use crate::plugins::{Plugin, ForceProviderPlugin};
+use std::any::Any;
+use std::collections::HashMap;
+
+/// Uniform gravity plugin configured with a field acceleration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformGravityPlugin {
+    /// Field acceleration g (m/s²)
+    acceleration: Acceleration,
+}
+
+impl UniformGravityPlugin {
+    /// Standard gravity at the Earth's surface (m/s²)
+    pub const EARTH_SURFACE_GRAVITY: Real = 9.80665;
+
+    /// Mean gravity at the Moon's surface (m/s²)
+    pub const MOON_SURFACE_GRAVITY: Real = 1.62;
+
+    /// Create a uniform field with the given acceleration
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component of `acceleration` is not finite.
+    pub fn new(acceleration: Acceleration) -> Self {
+        assert!(
+            acceleration.is_valid(),
+            "Uniform gravity acceleration must be finite"
+        );
+        UniformGravityPlugin { acceleration }
+    }
+
+    /// Earth surface gravity pointing down the -y axis
+    pub fn earth() -> Self {
+        Self::new(Acceleration::new(0.0, -Self::EARTH_SURFACE_GRAVITY, 0.0))
+    }
+
+    /// Moon surface gravity pointing down the -y axis
+    pub fn moon() -> Self {
+        Self::new(Acceleration::new(0.0, -Self::MOON_SURFACE_GRAVITY, 0.0))
+    }
+
+    /// Get the field acceleration
+    pub fn acceleration(&self) -> Acceleration {
+        self.acceleration
+    }
+
+    /// Set the field acceleration
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component of `acceleration` is not finite.
+    pub fn set_acceleration(&mut self, acceleration: Acceleration) {
+        *self = Self::new(acceleration);
+    }
+
+    /// Compute the force F = m·g on a body of the given mass
+    ///
+    /// Returns None for immovable bodies.
+    pub fn force_for_mass(&self, mass: &Mass) -> Option<Force> {
+        if mass.is_immovable() {
+            return None;
+        }
+        let m = mass.value();
+        Some(Force::new(
+            m * self.acceleration.ax(),
+            m * self.acceleration.ay(),
+            m * self.acceleration.az(),
+        ))
+    }
+}
+
+impl Plugin for UniformGravityPlugin {
+    fn name(&self) -> &str {
+        "uniform_gravity"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl ForceProvider for UniformGravityPlugin {
+    fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+        // F = m·g needs the entity's mass, which the generic ForceProvider
+        // interface does not expose. Use UniformGravitySystem::compute_forces().
+        None
+    }
+
+    fn name(&self) -> &str {
+        "uniform_gravity"
+    }
+}
+
+impl ForceProviderPlugin for UniformGravityPlugin {
+    fn as_force_provider(&self) -> &dyn ForceProvider {
+        self
+    }
+}
+
+/// System that evaluates uniform gravity with access to Mass
+pub struct UniformGravitySystem {
+    plugin: UniformGravityPlugin,
+}
+
+impl UniformGravitySystem {
+    /// Create a new uniform gravity system with the given plugin configuration
+    pub fn new(plugin: UniformGravityPlugin) -> Self {
+        UniformGravitySystem { plugin }
+    }
+
+    /// Get the underlying plugin
+    pub fn plugin(&self) -> &UniformGravityPlugin {
+        &self.plugin
+    }
+
+    /// Get mutable access to the underlying plugin (e.g. to change the field)
+    pub fn plugin_mut(&mut self) -> &mut UniformGravityPlugin {
+        &mut self.plugin
+    }
+
+    /// Compute gravity forces and register them with the force registry
+    ///
+    /// Entities without a Mass component and immovable entities are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `entities` - Slice of entities to consider
+    /// * `masses` - Mass component storage
+    /// * `force_registry` - Registry to accumulate forces
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had gravity forces computed
+    pub fn compute_forces(
+        &self,
+        entities: &[Entity],
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+    ) -> usize {
+        let forces: HashMap<Entity, Force> = entities
+            .iter()
+            .filter_map(|&entity| {
+                let mass = masses.get(entity)?;
+                self.plugin.force_for_mass(mass).map(|force| (entity, force))
+            })
+            .collect();
+
+        force_registry.register_forces(&forces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, World};
+    use crate::ecs::components::{Position, Velocity};
+    use crate::ecs::systems::apply_forces_to_acceleration;
+    use crate::integration::{Integrator, VelocityVerletIntegrator};
+    use crate::plugins::PluginRegistry;
+    use crate::real::RealExt;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(UniformGravityPlugin::earth().acceleration().ay(), -9.80665);
+        assert_eq!(UniformGravityPlugin::moon().acceleration().ay(), -1.62);
+        assert_eq!(UniformGravityPlugin::earth().acceleration().ax(), 0.0);
+    }
+
+    #[test]
+    // strict-validation rejects the NaN in Acceleration::new() first
+    #[should_panic(expected = "must be finite")]
+    fn test_non_finite_field_panics() {
+        let mut plugin = UniformGravityPlugin::earth();
+        plugin.set_acceleration(Acceleration::new(0.0, Real::NAN, 0.0));
+    }
+
+    #[test]
+    fn test_force_proportional_to_mass() {
+        let mut world = World::new();
+        let light = world.create_entity();
+        let heavy = world.create_entity();
+        let wall = world.create_entity();
+        let massless = world.create_entity();
+
+        let mut masses = HashMapStorage::<Mass>::new();
+        masses.insert(light, Mass::new(1.0));
+        masses.insert(heavy, Mass::new(10.0));
+        masses.insert(wall, Mass::immovable());
+
+        let system = UniformGravitySystem::new(UniformGravityPlugin::earth());
+        let mut registry = ForceRegistry::new();
+        registry.set_warn_on_missing_components(false);
+        let entities = [light, heavy, wall, massless];
+        assert_eq!(system.compute_forces(&entities, &masses, &mut registry), 2);
+
+        for &entity in &entities {
+            registry.accumulate_for_entity(entity);
+        }
+        let light_force = registry.get_force(light).unwrap();
+        let heavy_force = registry.get_force(heavy).unwrap();
+        assert_eq!(heavy_force.fy, 10.0 * light_force.fy);
+        assert!(registry.get_force(wall).is_none());
+        assert!(registry.get_force(massless).is_none());
+
+        // Identical accelerations regardless of mass
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+        let light_acc = accelerations.get(light).unwrap().ay();
+        let heavy_acc = accelerations.get(heavy).unwrap().ay();
+        assert!((light_acc - heavy_acc).abs() < Real::TOLERANCE);
+        assert!((light_acc + UniformGravityPlugin::EARTH_SURFACE_GRAVITY).abs() < Real::TOLERANCE);
+    }
+
+    #[test]
+    fn test_free_fall_distance_with_verlet() {
+        let mut world = World::new();
+        let ball = world.create_entity();
+        let entities = [ball];
+
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        positions.insert(ball, Position::new(0.0, 100.0, 0.0));
+        velocities.insert(ball, Velocity::zero());
+        accelerations.insert(ball, Acceleration::zero());
+        masses.insert(ball, Mass::new(2.5));
+
+        let system = UniformGravitySystem::new(UniformGravityPlugin::earth());
+        let mut integrator = VelocityVerletIntegrator::new(0.01);
+        let mut registry = ForceRegistry::new();
+
+        // Warm-start a(0) so the first position update sees gravity
+        system.compute_forces(&entities, &masses, &mut registry);
+        registry.accumulate_for_entity(ball);
+        apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+
+        let steps = 200;
+        for _ in 0..steps {
+            registry.clear();
+            system.compute_forces(&entities, &masses, &mut registry);
+            registry.accumulate_for_entity(ball);
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+            integrator.integrate(
+                entities.iter(),
+                &mut positions,
+                &mut velocities,
+                &accelerations,
+                &masses,
+                &mut registry,
+                false,
+            );
+        }
+
+        // Verlet is exact for constant acceleration: d = ½gt²
+        let t = steps as Real * 0.01;
+        let expected = 0.5 * UniformGravityPlugin::EARTH_SURFACE_GRAVITY * t * t;
+        let fallen = 100.0 - positions.get(ball).unwrap().y();
+        assert!(
+            (fallen - expected).abs() < 1e-9 * Real::EPSILON_RATIO,
+            "Fell {} m, expected {} m",
+            fallen,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_registers_with_plugin_registry() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(UniformGravityPlugin::moon())).unwrap();
+
+        let plugin = registry.get("uniform_gravity").unwrap();
+        assert_eq!(plugin.version(), "1.0.0");
+        let moon = plugin.as_any().downcast_ref::<UniformGravityPlugin>().unwrap();
+        assert_eq!(moon.acceleration().ay(), -UniformGravityPlugin::MOON_SURFACE_GRAVITY);
+    }
+}
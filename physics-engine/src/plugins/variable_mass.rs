@@ -0,0 +1,369 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Variable mass (mass flow) plugin
+//!
+//! Rockets burn propellant, accreting bodies gather material. This plugin
+//! changes the Mass component of chosen bodies at a constant rate dm/dt each
+//! step, never letting a shrinking body drop below a minimum mass.
+//!
+//! # Physics Background
+//!
+//! By default mass changes without any reaction: velocity is left unchanged,
+//! so momentum p = m·v changes with the mass. This matches mass that leaves
+//! or arrives at the body's own velocity, such as ablation or evaporation.
+//!
+//! Mass expelled at a relative exhaust velocity **u** pushes the body the
+//! other way. Giving a flow an exhaust velocity adds the reaction force
+//!
+//! **F = (dm/dt)·u**
+//!
+//! which points against **u** while the body loses mass (dm/dt < 0). Burning
+//! from m₀ down to m₁ then changes velocity by the Tsiolkovsky rocket
+//! equation Δv = |u|·ln(m₀/m₁).
+//!
+//! # Usage
+//!
+//! Each step, register the thrust alongside other force systems with
+//! `compute_forces()`, integrate, then change the masses with `apply()`:
+//!
+//! ```
+//! use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+//! use physics_engine::ecs::components::{Mass, Velocity};
+//! use physics_engine::ecs::systems::ForceRegistry;
+//! use physics_engine::plugins::variable_mass::{MassFlow, VariableMassSystem};
+//!
+//! let rocket = World::new().create_entity();
+//! let mut masses = HashMapStorage::new();
+//! masses.insert(rocket, Mass::new(1000.0));
+//!
+//! // Burn 2 kg/s down to 400 kg, exhausting backwards at 3 km/s
+//! let mut system = VariableMassSystem::new();
+//! system.set_flow(
+//!     rocket,
+//!     MassFlow::new(-2.0, 400.0).with_exhaust_velocity(Velocity::new(-3000.0, 0.0, 0.0)),
+//! );
+//!
+//! let mut registry = ForceRegistry::new();
+//! system.compute_forces(0.1, &[rocket], &masses, &mut registry);
+//! assert_eq!(registry.get_force(rocket).unwrap().fx, 6000.0);
+//!
+//! system.apply(0.1, &[rocket], &mut masses);
+//! assert!((masses.get(rocket).unwrap().value() - 999.8).abs() < 1e-3);
+//! ```
+
+use crate::real::Real;
+use crate::ecs::{Entity, ComponentStorage};
+use crate::ecs::components::{Mass, Velocity};
+use crate::ecs::systems::{Force, ForceRegistry};
+use crate::plugins::Plugin;
+use std::any::Any;
+use std::collections::HashMap;
+
+/// Constant mass flow applied to one body
+///
+/// Construct with `MassFlow::new()`, which validates the rate and floor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct MassFlow {
+    /// Rate of mass change dm/dt (kg/s), negative while losing mass
+    pub rate: Real,
+    /// Mass the body never drops below while losing mass (kg)
+    pub min_mass: Real,
+    /// Velocity of the expelled or gathered mass relative to the body, if
+    /// the flow produces a reaction force
+    pub exhaust_velocity: Option<Velocity>,
+}
+
+impl MassFlow {
+    /// Create a mass flow without a reaction force
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not finite, or `min_mass` is negative or not finite.
+    pub fn new(rate: Real, min_mass: Real) -> Self {
+        assert!(rate.is_finite(), "Mass flow rate must be finite");
+        assert!(
+            min_mass >= 0.0 && min_mass.is_finite(),
+            "Minimum mass must be non-negative and finite"
+        );
+        MassFlow {
+            rate,
+            min_mass,
+            exhaust_velocity: None,
+        }
+    }
+
+    /// Expel the mass at `velocity` relative to the body, producing thrust
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component of `velocity` is not finite.
+    pub fn with_exhaust_velocity(mut self, velocity: Velocity) -> Self {
+        assert!(velocity.is_valid(), "Exhaust velocity must be finite");
+        self.exhaust_velocity = Some(velocity);
+        self
+    }
+
+    /// Mass change over `dt` for a body of mass `mass`, respecting the floor
+    ///
+    /// A body already at or below the floor doesn't lose any more mass.
+    pub fn mass_change(&self, mass: Real, dt: Real) -> Real {
+        let change = self.rate * dt;
+        if change < 0.0 {
+            change.max((self.min_mass - mass).min(0.0))
+        } else {
+            change
+        }
+    }
+}
+
+/// System that changes the mass of tagged bodies at a constant rate
+#[derive(Debug, Clone, Default)]
+pub struct VariableMassSystem {
+    /// Mass flows keyed by the entity they act on
+    flows: HashMap<Entity, MassFlow>,
+}
+
+impl VariableMassSystem {
+    /// Create a new variable mass system with no flows
+    pub fn new() -> Self {
+        VariableMassSystem {
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Apply a mass flow to an entity
+    ///
+    /// Replaces any flow already set on the entity.
+    pub fn set_flow(&mut self, entity: Entity, flow: MassFlow) {
+        self.flows.insert(entity, flow);
+    }
+
+    /// Stop the mass flow on an entity, returning it if it had one
+    pub fn clear_flow(&mut self, entity: Entity) -> Option<MassFlow> {
+        self.flows.remove(&entity)
+    }
+
+    /// Get the mass flow applied to an entity
+    pub fn flow(&self, entity: Entity) -> Option<&MassFlow> {
+        self.flows.get(&entity)
+    }
+
+    /// Get the number of entities with a mass flow
+    pub fn len(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Check if no entities have a mass flow
+    pub fn is_empty(&self) -> bool {
+        self.flows.is_empty()
+    }
+
+    /// Register the reaction forces of flows with an exhaust velocity
+    ///
+    /// The thrust of a body about to reach its floor is scaled down to the
+    /// mass it can still lose within `dt`, so the momentum handed to the body
+    /// matches the mass `apply()` removes. Bodies at the floor, without an
+    /// exhaust velocity or missing Mass get no force.
+    ///
+    /// # Returns
+    ///
+    /// Number of entities that had thrust registered
+    pub fn compute_forces(
+        &self,
+        dt: Real,
+        entities: &[Entity],
+        masses: &impl ComponentStorage<Component = Mass>,
+        force_registry: &mut ForceRegistry,
+    ) -> usize {
+        if dt <= 0.0 {
+            return 0;
+        }
+
+        let mut forces = Vec::new();
+        for &entity in entities {
+            let Some(flow) = self.flows.get(&entity) else { continue };
+            let Some(exhaust) = flow.exhaust_velocity else { continue };
+            let Some(mass) = masses.get(entity) else { continue };
+
+            let rate = flow.mass_change(mass.value(), dt) / dt;
+            if rate != 0.0 {
+                forces.push((
+                    entity,
+                    Force::new(rate * exhaust.dx(), rate * exhaust.dy(), rate * exhaust.dz()),
+                ));
+            }
+        }
+
+        if forces.is_empty() {
+            return 0;
+        }
+        force_registry.register_ordered_forces(&forces)
+    }
+
+    /// Change the mass of every entity with a flow by dm/dt·`dt`
+    ///
+    /// Shrinking bodies stop at their flow's `min_mass`. Velocities are not
+    /// touched; pair with `compute_forces()` for the reaction force. A body
+    /// whose mass drops below `Mass::IMMOVABLE_THRESHOLD` becomes immovable.
+    ///
+    /// # Returns
+    ///
+    /// Number of entities whose mass changed
+    pub fn apply(
+        &self,
+        dt: Real,
+        entities: &[Entity],
+        masses: &mut impl ComponentStorage<Component = Mass>,
+    ) -> usize {
+        let mut count = 0;
+        for &entity in entities {
+            let Some(flow) = self.flows.get(&entity) else { continue };
+            let Some(mass) = masses.get_mut(entity) else { continue };
+
+            let change = flow.mass_change(mass.value(), dt);
+            if change != 0.0 && mass.try_set_value((mass.value() + change).max(0.0)).is_ok() {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl Plugin for VariableMassSystem {
+    fn name(&self) -> &str {
+        "variable_mass"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, World};
+    use crate::ecs::components::Acceleration;
+    use crate::ecs::systems::apply_forces_to_acceleration;
+
+    #[test]
+    fn test_constant_burn_matches_rocket_equation() {
+        let rocket = World::new().create_entity();
+        let entities = [rocket];
+        let (m0, m1, exhaust_speed) = (1000.0, 400.0, 3000.0);
+
+        let mut masses = HashMapStorage::new();
+        masses.insert(rocket, Mass::new(m0));
+        let mut velocities = HashMapStorage::new();
+        velocities.insert(rocket, Velocity::zero());
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+
+        let mut system = VariableMassSystem::new();
+        system.set_flow(
+            rocket,
+            MassFlow::new(-2.0, m1).with_exhaust_velocity(Velocity::new(-exhaust_speed, 0.0, 0.0)),
+        );
+
+        // 300 s of burn, then coast for a few seconds past burnout
+        let dt = 0.1;
+        let mut registry = ForceRegistry::new();
+        for _ in 0..3050 {
+            registry.clear();
+            accelerations.insert(rocket, Acceleration::zero());
+            system.compute_forces(dt, &entities, &masses, &mut registry);
+            apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, false);
+
+            let a = *accelerations.get(rocket).unwrap();
+            let v = velocities.get_mut(rocket).unwrap();
+            v.set_dx(v.dx() + a.ax() * dt);
+            system.apply(dt, &entities, &mut masses);
+        }
+
+        let expected = exhaust_speed * (m0 / m1).ln();
+        let dv = velocities.get(rocket).unwrap().dx();
+        assert!(((dv - expected) / expected).abs() < 1e-3, "Δv {} vs rocket equation {}", dv, expected);
+        assert_eq!(masses.get(rocket).unwrap().value(), m1);
+    }
+
+    #[test]
+    fn test_mass_never_drops_below_floor() {
+        let body = Entity::new(1, 0);
+        let mut masses = HashMapStorage::new();
+        masses.insert(body, Mass::new(10.0));
+
+        let mut system = VariableMassSystem::new();
+        system.set_flow(body, MassFlow::new(-4.0, 4.0).with_exhaust_velocity(Velocity::new(0.0, 0.0, 1.0)));
+
+        assert_eq!(system.apply(1.0, &[body], &mut masses), 1);
+        assert_eq!(masses.get(body).unwrap().value(), 6.0);
+
+        // The step crossing the floor gets only the remaining thrust
+        let mut registry = ForceRegistry::new();
+        assert_eq!(system.compute_forces(1.0, &[body], &masses, &mut registry), 1);
+        assert_eq!(registry.get_force(body), Some(Force::new(0.0, 0.0, -2.0)));
+        system.apply(1.0, &[body], &mut masses);
+        assert_eq!(masses.get(body).unwrap().value(), 4.0);
+
+        for _ in 0..5 {
+            assert_eq!(system.apply(1.0, &[body], &mut masses), 0);
+        }
+        assert_eq!(masses.get(body).unwrap().value(), 4.0);
+        let mut registry = ForceRegistry::new();
+        assert_eq!(system.compute_forces(1.0, &[body], &masses, &mut registry), 0);
+
+        // A zero floor lets the body burn out to immovable
+        system.set_flow(body, MassFlow::new(-10.0, 0.0));
+        system.apply(1.0, &[body], &mut masses);
+        assert!(masses.get(body).unwrap().is_immovable());
+        assert_eq!(masses.get(body).unwrap().inverse(), 0.0);
+    }
+
+    #[test]
+    fn test_plain_flow_keeps_velocity_and_adds_no_force() {
+        let mut world = World::new();
+        let (accreting, untagged) = (world.create_entity(), world.create_entity());
+        let entities = [accreting, untagged];
+        let mut masses = HashMapStorage::new();
+        masses.insert(accreting, Mass::new(2.0));
+        masses.insert(untagged, Mass::new(2.0));
+
+        let mut system = VariableMassSystem::new();
+        system.set_flow(accreting, MassFlow::new(0.5, 0.0));
+
+        let mut registry = ForceRegistry::new();
+        assert_eq!(system.compute_forces(1.0, &entities, &masses, &mut registry), 0);
+        assert_eq!(registry.provider_count(), 0);
+
+        assert_eq!(system.apply(2.0, &entities, &mut masses), 1);
+        assert_eq!(masses.get(accreting).unwrap().value(), 3.0);
+        assert_eq!(masses.get(untagged).unwrap().value(), 2.0);
+        assert!(system.clear_flow(accreting).is_some());
+        assert!(system.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Minimum mass must be non-negative and finite")]
+    fn test_negative_floor_panics() {
+        MassFlow::new(-1.0, -1.0);
+    }
+}
@@ -17,18 +17,42 @@
 //! allocations in integrators and force computation. Pools help reduce
 //! per-frame allocation overhead and improve cache locality.
 
+use crate::real::Real;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 /// Configuration for buffer pool behavior
+///
+/// Fields are readable, but the struct is `#[non_exhaustive]` so that new
+/// settings can be added without breaking callers. Build it with `new()` or
+/// `default()` and the `with_*` methods:
+///
+/// ```
+/// use physics_engine::pool::PoolConfig;
+///
+/// let config = PoolConfig::new(256, 4).with_growth_factor(1.5);
+/// assert_eq!(config.initial_capacity, 256);
+/// ```
+///
+/// ```compile_fail
+/// use physics_engine::pool::PoolConfig;
+///
+/// let config = PoolConfig {
+///     initial_capacity: 256,
+///     max_pool_size: 4,
+///     growth_factor: 1.5,
+///     log_resize_events: false,
+/// };
+/// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PoolConfig {
     /// Initial capacity for each buffer in the pool
     pub initial_capacity: usize,
     /// Maximum number of buffers to keep in the pool
     pub max_pool_size: usize,
     /// Growth factor when allocating new capacity (e.g., 2.0 for doubling)
-    pub growth_factor: f64,
+    pub growth_factor: Real,
     /// Whether to log when the pool grows or shrinks
     pub log_resize_events: bool,
 }
@@ -62,7 +86,7 @@ impl PoolConfig {
     }
 
     /// Set the growth factor for buffer capacity expansion
-    pub fn with_growth_factor(mut self, factor: f64) -> Self {
+    pub fn with_growth_factor(mut self, factor: Real) -> Self {
         assert!(factor >= 1.0, "Growth factor must be >= 1.0");
         self.growth_factor = factor;
         self
@@ -86,12 +110,12 @@ pub struct PoolStats {
 
 impl PoolStats {
     /// Calculate the hit rate as a percentage
-    pub fn hit_rate(&self) -> f64 {
+    pub fn hit_rate(&self) -> Real {
         let total = self.hits + self.misses;
         if total == 0 {
             0.0
         } else {
-            (self.hits as f64 / total as f64) * 100.0
+            (self.hits as Real / total as Real) * 100.0
         }
     }
 }
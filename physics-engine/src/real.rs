@@ -0,0 +1,74 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Crate-wide scalar type
+//!
+//! Every component, force, integrator and kernel works in `Real`. It is
+//! `f64` by default; the `single-precision` cargo feature switches it to
+//! `f32`, halving component memory for embedded and wasm targets.
+//!
+//! Tolerances that depend on the precision live on `RealExt`, so code and
+//! tests written against `Real::TOLERANCE` and friends hold in both builds.
+//!
+//! # Single Precision Caveats
+//!
+//! - `f32` overflows near 3.4e38, so SI-unit products such as `G·M·m` for
+//!   solar-system bodies overflow; use scaled units (see `units`).
+//! - AVX2/AVX-512 kernels are `f64`-only and are compiled out; the scalar
+//!   backend is always selected.
+
+/// Scalar type used throughout the engine
+#[cfg(not(feature = "single-precision"))]
+pub type Real = f64;
+
+/// Scalar type used throughout the engine
+#[cfg(feature = "single-precision")]
+pub type Real = f32;
+
+/// Mathematical constants for `Real`
+#[cfg(not(feature = "single-precision"))]
+pub use std::f64::consts;
+
+/// Mathematical constants for `Real`
+#[cfg(feature = "single-precision")]
+pub use std::f32::consts;
+
+/// Precision-dependent constants for the engine's scalar type
+pub trait RealExt: Copy {
+    /// Relative tolerance for results that are exact up to rounding
+    ///
+    /// Used for conservation checks and comparisons between algorithms that
+    /// should agree, such as SIMD and scalar kernels.
+    const TOLERANCE: Self;
+
+    /// Convergence tolerance for iterative solvers
+    const SOLVER_TOLERANCE: Self;
+
+    /// Ratio of this type's machine epsilon to that of `f64`
+    ///
+    /// Multiplying a tolerance calibrated for `f64` by this ratio gives one
+    /// allowing the same number of units in the last place.
+    const EPSILON_RATIO: Self;
+}
+
+impl RealExt for f64 {
+    const TOLERANCE: f64 = 1e-10;
+    const SOLVER_TOLERANCE: f64 = 1e-13;
+    const EPSILON_RATIO: f64 = 1.0;
+}
+
+impl RealExt for f32 {
+    const TOLERANCE: f32 = 1e-4;
+    const SOLVER_TOLERANCE: f32 = 1e-6;
+    const EPSILON_RATIO: f32 = (f32::EPSILON as f64 / f64::EPSILON) as f32;
+}
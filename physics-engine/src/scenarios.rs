@@ -0,0 +1,465 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Preset initial conditions for tests, benchmarks and examples
+//!
+//! Each constructor creates its bodies in a `World` and returns a
+//! `Scenario`: the entity list, a fully populated `NewtonianState` whose
+//! accelerations already hold the gravity at the initial positions, the
+//! gravitational constant and softening the scenario was set up for, and a
+//! suggested stable timestep.
+//!
+//! ```
+//! use physics_engine::World;
+//! use physics_engine::ecs::systems::{apply_forces_to_state, ForceRegistry};
+//! use physics_engine::integration::{Integrator, VelocityVerletIntegrator};
+//! use physics_engine::scenarios;
+//! use std::collections::HashMap;
+//!
+//! let mut world = World::new();
+//! let mut scenario = scenarios::circular_two_body(&mut world, 1.0e24, 1.0e22, 1.0e7);
+//! let gravity = scenario.gravity_system();
+//! let mut integrator = VelocityVerletIntegrator::new(scenario.suggested_timestep);
+//! let mut registry = scenario.force_registry();
+//! let mut forces = HashMap::new();
+//!
+//! for _ in 0..10 {
+//!     registry.clear();
+//!     gravity.compute_forces_into(&scenario.entities, scenario.state.positions(), scenario.state.masses(), &mut forces);
+//!     registry.register_forces(&forces);
+//!     apply_forces_to_state(scenario.entities.iter(), &registry, &mut scenario.state, false);
+//!     integrator.integrate_state(scenario.entities.iter(), &mut scenario.state, &mut registry, false);
+//! }
+//! ```
+//!
+//! The orbital scenarios use SI units with the real gravitational constant,
+//! so their forces overflow `single-precision` builds.
+
+use crate::real::{consts, Real};
+use crate::ecs::{Entity, NewtonianState, World};
+use crate::ecs::components::{Mass, Position, Velocity};
+use crate::ecs::systems::{apply_forces_to_state, ForceRegistry};
+use crate::integration::{Integrator, VelocityVerletIntegrator};
+use crate::plugins::gravity::{GravityPlugin, GravitySystem, GRAVITATIONAL_CONSTANT};
+use crate::units::{ASTRONOMICAL_UNIT, SOLAR_MASS};
+use std::collections::HashMap;
+
+/// Steps per orbital period in the suggested timestep of orbital scenarios
+pub const STEPS_PER_PERIOD: Real = 1000.0;
+
+/// Period of the figure-eight orbit in units where G = m = 1
+///
+/// From Chenciner & Montgomery (2000), as computed by Simó.
+pub const FIGURE_EIGHT_PERIOD: Real = 6.32591398;
+
+/// Factor applied to G by `random_cube()`, matching the `particle_collision` example
+const CUBE_G_SCALE: Real = 1e10;
+
+/// Softening length used by `random_cube()` (meters)
+const CUBE_SOFTENING: Real = 1.0;
+
+/// Timestep suggested for a random cube whose frequency can't be estimated (s)
+const CUBE_FALLBACK_TIMESTEP: Real = 0.01;
+
+/// Deterministic pseudo-random number generator
+///
+/// Linear Congruential Generator with Knuth's MMIX parameters. The same
+/// seed always produces the same sequence on every platform.
+#[derive(Debug, Clone)]
+pub struct DeterministicRng {
+    state: u64,
+}
+
+impl DeterministicRng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        DeterministicRng { state: seed }
+    }
+
+    /// Get the generator's internal state
+    ///
+    /// Capture it to checkpoint a sequence; `from_state()` resumes from
+    /// exactly the next value.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Resume a generator from a state captured with `state()`
+    ///
+    /// The restored generator yields the same values the original would
+    /// have produced from that point on.
+    pub fn from_state(state: u64) -> Self {
+        DeterministicRng { state }
+    }
+
+    /// Generate the next 64-bit value
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    /// Generate a float uniformly distributed in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        // Use the upper 53 bits to fill the f64 mantissa
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a float uniformly distributed in [min, max)
+    pub fn next_f64_range(&mut self, min: Real, max: Real) -> Real {
+        min + (max - min) * self.next_f64() as Real
+    }
+}
+
+/// Bodies and settings produced by a scenario constructor
+#[non_exhaustive]
+pub struct Scenario {
+    /// Bodies in creation order
+    pub entities: Vec<Entity>,
+    /// Positions, velocities, masses, and the gravitational accelerations at
+    /// the initial positions
+    pub state: NewtonianState,
+    /// Gravitational constant the scenario is set up for
+    pub g: Real,
+    /// Gravitational softening length the scenario is set up for
+    pub softening: Real,
+    /// Timestep expected to integrate the scenario stably and accurately
+    pub suggested_timestep: Real,
+    /// Orbital period, for periodic scenarios
+    pub period: Option<Real>,
+}
+
+impl Scenario {
+    /// Build a gravity system with the scenario's G and softening
+    ///
+    /// Force-magnitude warnings are disabled, since the orbital scenarios
+    /// produce forces far above the plugin's default expectation.
+    pub fn gravity_system(&self) -> GravitySystem {
+        let mut plugin = GravityPlugin::new(self.g);
+        plugin.set_softening(self.softening);
+        plugin.set_warn_on_high_forces(false);
+        GravitySystem::new(plugin)
+    }
+
+    /// Build a force registry that accepts the scenario's forces
+    ///
+    /// The force limit is lifted and missing-component warnings are off.
+    pub fn force_registry(&self) -> ForceRegistry {
+        let mut registry = ForceRegistry::new();
+        registry.set_max_force_magnitude(Real::MAX);
+        registry.set_warn_on_missing_components(false);
+        registry
+    }
+
+    /// Assemble a scenario and fill in the initial accelerations
+    fn new(entities: Vec<Entity>, state: NewtonianState, g: Real, softening: Real) -> Self {
+        let mut scenario = Scenario {
+            entities,
+            state,
+            g,
+            softening,
+            suggested_timestep: CUBE_FALLBACK_TIMESTEP,
+            period: None,
+        };
+        let mut registry = scenario.force_registry();
+        let mut forces = HashMap::new();
+        scenario
+            .gravity_system()
+            .compute_forces_for_state(&scenario.entities, &scenario.state, &mut forces);
+        registry.register_forces(&forces);
+        apply_forces_to_state(scenario.entities.iter(), &registry, &mut scenario.state, false);
+        scenario
+    }
+
+    /// Set the period and a timestep of `STEPS_PER_PERIOD` steps per period
+    fn with_period(mut self, period: Real) -> Self {
+        self.period = Some(period);
+        self.suggested_timestep = period / STEPS_PER_PERIOD;
+        self
+    }
+}
+
+/// Two bodies on circular orbits about their common barycenter
+///
+/// The bodies start on the x axis with the barycenter at rest at the origin,
+/// and orbit counter-clockwise seen from +z. Uses the real gravitational
+/// constant and no softening; the period is 2π·√(d³ / (G·(m₁ + m₂))).
+///
+/// # Panics
+///
+/// Panics if either mass or the separation is not positive and finite.
+pub fn circular_two_body(world: &mut World, m1: Real, m2: Real, separation: Real) -> Scenario {
+    assert!(
+        m1 > 0.0 && m1.is_finite() && m2 > 0.0 && m2.is_finite(),
+        "Masses must be positive and finite"
+    );
+    assert!(separation > 0.0 && separation.is_finite(), "Separation must be positive and finite");
+
+    let total = m1 + m2;
+    let relative_speed = (GRAVITATIONAL_CONSTANT * total / separation).sqrt();
+    let mut state = NewtonianState::with_capacity(2);
+    let first = world.create_entity();
+    let second = world.create_entity();
+    state.insert_body(
+        first,
+        Position::new(-separation * m2 / total, 0.0, 0.0),
+        Velocity::new(0.0, -relative_speed * m2 / total, 0.0),
+        Mass::new(m1),
+    );
+    state.insert_body(
+        second,
+        Position::new(separation * m1 / total, 0.0, 0.0),
+        Velocity::new(0.0, relative_speed * m1 / total, 0.0),
+        Mass::new(m2),
+    );
+
+    let period = 2.0 * consts::PI * separation / relative_speed;
+    Scenario::new(vec![first, second], state, GRAVITATIONAL_CONSTANT, 0.0).with_period(period)
+}
+
+/// Three equal masses chasing each other around a figure-eight
+///
+/// Uses the Chenciner–Montgomery initial conditions, scaled so each body has
+/// one solar mass and lengths are in astronomical units. The period is then
+/// `FIGURE_EIGHT_PERIOD` × √(AU³ / (G·M☉)), just over a year. Uses the real
+/// gravitational constant and no softening.
+pub fn figure_eight_three_body(world: &mut World) -> Scenario {
+    // Chenciner & Montgomery (2000) in units where G = m = 1
+    let (x1, y1) = (-0.97000436, 0.24308753);
+    let (vx3, vy3) = (-0.93240737, -0.86473146);
+    let unit_positions = [(x1, y1), (-x1, -y1), (0.0, 0.0)];
+    let unit_velocities = [(-vx3 / 2.0, -vy3 / 2.0), (-vx3 / 2.0, -vy3 / 2.0), (vx3, vy3)];
+
+    let length = ASTRONOMICAL_UNIT;
+    let time = (length * length * length / (GRAVITATIONAL_CONSTANT * SOLAR_MASS)).sqrt();
+    let speed = length / time;
+
+    let mut state = NewtonianState::with_capacity(3);
+    let mut entities = Vec::with_capacity(3);
+    for ((px, py), (vx, vy)) in unit_positions.into_iter().zip(unit_velocities) {
+        let entity = world.create_entity();
+        state.insert_body(
+            entity,
+            Position::new(px * length, py * length, 0.0),
+            Velocity::new(vx * speed, vy * speed, 0.0),
+            Mass::new(SOLAR_MASS),
+        );
+        entities.push(entity);
+    }
+
+    Scenario::new(entities, state, GRAVITATIONAL_CONSTANT, 0.0).with_period(FIGURE_EIGHT_PERIOD * time)
+}
+
+/// Ranges for the bodies placed by `random_cube()`
+///
+/// The defaults match the `particle_collision` example.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct CubeRanges {
+    /// Half-width of the cube positions are drawn from (meters)
+    pub position: Real,
+    /// Largest initial speed along each axis (m/s)
+    pub velocity: Real,
+    /// Range body masses are drawn from (kg)
+    pub mass: (Real, Real),
+}
+
+impl Default for CubeRanges {
+    fn default() -> Self {
+        CubeRanges {
+            position: 100.0,
+            velocity: 10.0,
+            mass: (1.0, 10.0),
+        }
+    }
+}
+
+impl CubeRanges {
+    /// Set the half-width of the cube
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is not positive and finite.
+    pub fn with_position(mut self, position: Real) -> Self {
+        assert!(position > 0.0 && position.is_finite(), "Position range must be positive and finite");
+        self.position = position;
+        self
+    }
+
+    /// Set the largest initial speed along each axis
+    ///
+    /// # Panics
+    ///
+    /// Panics if `velocity` is negative or not finite.
+    pub fn with_velocity(mut self, velocity: Real) -> Self {
+        assert!(velocity >= 0.0 && velocity.is_finite(), "Velocity range must be non-negative and finite");
+        self.velocity = velocity;
+        self
+    }
+
+    /// Set the range of body masses
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `0 < min <= max` and both are finite.
+    pub fn with_mass(mut self, min: Real, max: Real) -> Self {
+        assert!(
+            min > 0.0 && min <= max && max.is_finite(),
+            "Mass range must satisfy 0 < min <= max and be finite"
+        );
+        self.mass = (min, max);
+        self
+    }
+}
+
+/// `n` bodies placed uniformly at random in a cube
+///
+/// Each body draws its position, then its velocity, then its mass from a
+/// `DeterministicRng` seeded with `seed`, so a seed always yields the same
+/// bodies. Gravity uses G scaled by 1e10 and a 1 m softening, as in the
+/// `particle_collision` example, and the suggested timestep is the one
+/// `VelocityVerletIntegrator::suggest_timestep()` gives for the initial
+/// accelerations.
+pub fn random_cube(world: &mut World, n: usize, seed: u64, ranges: &CubeRanges) -> Scenario {
+    let mut rng = DeterministicRng::new(seed);
+    let mut state = NewtonianState::with_capacity(n);
+    let mut entities = Vec::with_capacity(n);
+    let (p, v) = (ranges.position, ranges.velocity);
+    for _ in 0..n {
+        let entity = world.create_entity();
+        let position = Position::new(rng.next_f64_range(-p, p), rng.next_f64_range(-p, p), rng.next_f64_range(-p, p));
+        let velocity = Velocity::new(rng.next_f64_range(-v, v), rng.next_f64_range(-v, v), rng.next_f64_range(-v, v));
+        let mass = Mass::new(rng.next_f64_range(ranges.mass.0, ranges.mass.1));
+        state.insert_body(entity, position, velocity, mass);
+        entities.push(entity);
+    }
+
+    let mut scenario = Scenario::new(entities, state, GRAVITATIONAL_CONSTANT * CUBE_G_SCALE, CUBE_SOFTENING);
+    let suggestion = VelocityVerletIntegrator::new(CUBE_FALLBACK_TIMESTEP).suggest_timestep(
+        scenario.entities.iter(),
+        scenario.state.positions(),
+        scenario.state.accelerations(),
+        scenario.state.masses(),
+    );
+    if let Some(suggestion) = suggestion {
+        scenario.suggested_timestep = suggestion.suggested_timestep;
+    }
+    scenario
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::ComponentStorage;
+
+    /// Total momentum of a scenario
+    fn momentum(scenario: &Scenario) -> [Real; 3] {
+        let mut total = [0.0; 3];
+        for &entity in &scenario.entities {
+            let m = scenario.state.masses().get(entity).unwrap().value();
+            let v = scenario.state.velocities().get(entity).unwrap().as_array();
+            for k in 0..3 {
+                total[k] += m * v[k];
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_deterministic_rng_reproducible() {
+        let mut a = DeterministicRng::new(7);
+        let mut b = DeterministicRng::new(7);
+        for _ in 0..100 {
+            let x = a.next_f64();
+            assert_eq!(x, b.next_f64());
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_deterministic_rng_resumes_from_state() {
+        let mut single = DeterministicRng::new(2024);
+        let expected: Vec<u64> = (0..40).map(|_| single.next_u64()).collect();
+
+        // First half from one generator, second half from one restored mid-stream
+        let mut first = DeterministicRng::new(2024);
+        let mut split: Vec<u64> = (0..17).map(|_| first.next_u64()).collect();
+        let mut second = DeterministicRng::from_state(first.state());
+        split.extend((17..40).map(|_| second.next_u64()));
+
+        assert_eq!(split, expected);
+        assert_eq!(second.state(), single.state());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "SI force products overflow f32")]
+    fn test_circular_two_body_orbits_barycenter() {
+        let mut world = World::new();
+        let (m1, m2, d) = (2.0e24, 5.0e23, 4.0e8);
+        let scenario = circular_two_body(&mut world, m1, m2, d);
+        assert_eq!(scenario.entities.len(), 2);
+        assert!(momentum(&scenario).iter().all(|p| p.abs() < 1e-6 * m1));
+
+        // Each body's centripetal acceleration v²/r matches its gravity
+        for &entity in &scenario.entities {
+            let r = scenario.state.positions().get(entity).unwrap().as_array()[0].abs();
+            let v = scenario.state.velocities().get(entity).unwrap().as_array()[1].abs();
+            let a = scenario.state.accelerations().get(entity).unwrap().ax().abs();
+            assert!((v * v / r - a).abs() < 1e-9 * a, "Centripetal {} vs gravity {}", v * v / r, a);
+        }
+
+        let expected_period = 2.0 * consts::PI * (d * d * d / (GRAVITATIONAL_CONSTANT * (m1 + m2))).sqrt();
+        let period = scenario.period.unwrap();
+        assert!((period - expected_period).abs() < 1e-9 * expected_period);
+        assert_eq!(scenario.suggested_timestep, period / STEPS_PER_PERIOD);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "SI force products overflow f32")]
+    fn test_figure_eight_has_zero_momentum_and_year_scale_period() {
+        let mut world = World::new();
+        let scenario = figure_eight_three_body(&mut world);
+        assert_eq!(scenario.entities.len(), 3);
+        let speed_scale = 3e4 * SOLAR_MASS;
+        assert!(momentum(&scenario).iter().all(|p| p.abs() < 1e-6 * speed_scale));
+
+        // √(AU³ / (G·M☉)) is a year over 2π
+        let years = scenario.period.unwrap() / crate::units::YEAR;
+        assert!((years - FIGURE_EIGHT_PERIOD / (2.0 * consts::PI)).abs() < 1e-3, "Period of {} years", years);
+    }
+
+    #[test]
+    fn test_random_cube_is_reproducible_and_in_range() {
+        let ranges = CubeRanges::default().with_position(5.0).with_velocity(0.5).with_mass(2.0, 3.0);
+        let mut world = World::new();
+        let a = random_cube(&mut world, 50, 99, &ranges);
+        let b = random_cube(&mut world, 50, 99, &ranges);
+
+        for (&ea, &eb) in a.entities.iter().zip(&b.entities) {
+            let position = a.state.positions().get(ea).unwrap();
+            assert_eq!(position, b.state.positions().get(eb).unwrap());
+            assert!(position.as_array().iter().all(|c| c.abs() <= 5.0));
+            assert!(a.state.velocities().get(ea).unwrap().as_array().iter().all(|c| c.abs() <= 0.5));
+            let mass = a.state.masses().get(ea).unwrap().value();
+            assert!((2.0..3.0).contains(&mass));
+        }
+        assert!(a.suggested_timestep > 0.0 && a.suggested_timestep.is_finite());
+        assert_eq!(a.suggested_timestep, b.suggested_timestep);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mass range must satisfy 0 < min <= max and be finite")]
+    fn test_inverted_mass_range_panics() {
+        CubeRanges::default().with_mass(3.0, 2.0);
+    }
+}
@@ -153,6 +153,50 @@ impl SimdBackend for Avx2Backend {
         }
     }
     
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn axpy(
+        &self,
+        y: &mut [f64],
+        a: &[f64],
+        x: &[f64],
+    ) {
+        // y += a * x
+        
+        // Process 4 elements at a time using zip for safety
+        for ((y_chunk, a_chunk), x_chunk) in y.chunks_exact_mut(4)
+            .zip(a.chunks_exact(4))
+            .zip(x.chunks_exact(4))
+        {
+            let y_vec = _mm256_loadu_pd(y_chunk.as_ptr());
+            let a_vec = _mm256_loadu_pd(a_chunk.as_ptr());
+            let x_vec = _mm256_loadu_pd(x_chunk.as_ptr());
+            
+            // Separate multiply and add (no FMA) to match the scalar backend bit for bit
+            let product = _mm256_mul_pd(a_vec, x_vec);
+            let y_new = _mm256_add_pd(y_vec, product);
+            
+            _mm256_storeu_pd(y_chunk.as_mut_ptr(), y_new);
+        }
+    }
+    
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn scale(
+        &self,
+        y: &mut [f64],
+        a: f64,
+    ) {
+        // y *= a
+        let a_vec = _mm256_set1_pd(a);
+        
+        for y_chunk in y.chunks_exact_mut(4) {
+            let y_vec = _mm256_loadu_pd(y_chunk.as_ptr());
+            let y_new = _mm256_mul_pd(y_vec, a_vec);
+            _mm256_storeu_pd(y_chunk.as_mut_ptr(), y_new);
+        }
+    }
+    
     #[cfg(not(target_arch = "x86_64"))]
     unsafe fn update_velocity_vectorized(
         &self,
@@ -183,6 +227,25 @@ impl SimdBackend for Avx2Backend {
     ) {
         panic!("AVX2 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
     }
+    
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe fn axpy(
+        &self,
+        _y: &mut [f64],
+        _a: &[f64],
+        _x: &[f64],
+    ) {
+        panic!("AVX2 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
+    }
+    
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe fn scale(
+        &self,
+        _y: &mut [f64],
+        _a: f64,
+    ) {
+        panic!("AVX2 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
+    }
 }
 
 #[cfg(test)]
@@ -270,4 +333,34 @@ mod tests {
         assert_eq!(total_forces[2], 4.5);
         assert_eq!(total_forces[3], 6.0);
     }
+    
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_avx2_axpy_and_scale() {
+        let backend = Avx2Backend;
+        if !backend.is_supported() {
+            eprintln!("Skipping AVX2 test - not supported on this CPU");
+            return;
+        }
+        // One full vector plus a tail element the kernel must leave alone
+        let mut y: Vec<f64> = (1..=5).map(|i| i as f64).collect();
+        let a = vec![0.5; 5];
+        let x: Vec<f64> = (1..=5).map(|i| 2.0 * i as f64).collect();
+        
+        unsafe {
+            backend.axpy(&mut y, &a, &x);
+        }
+        for (i, &value) in y[..4].iter().enumerate() {
+            assert_eq!(value, 2.0 * (i + 1) as f64);
+        }
+        assert_eq!(y[4], 5.0, "Tail element must be left to the caller");
+        
+        unsafe {
+            backend.scale(&mut y, 0.25);
+        }
+        for (i, &value) in y[..4].iter().enumerate() {
+            assert_eq!(value, 0.5 * (i + 1) as f64);
+        }
+        assert_eq!(y[4], 5.0);
+    }
 }
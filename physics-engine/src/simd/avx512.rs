@@ -157,6 +157,50 @@ impl SimdBackend for Avx512Backend {
         }
     }
     
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx512f")]
+    unsafe fn axpy(
+        &self,
+        y: &mut [f64],
+        a: &[f64],
+        x: &[f64],
+    ) {
+        // y += a * x
+        
+        // Process 8 elements at a time using zip for safety
+        for ((y_chunk, a_chunk), x_chunk) in y.chunks_exact_mut(8)
+            .zip(a.chunks_exact(8))
+            .zip(x.chunks_exact(8))
+        {
+            let y_vec = _mm512_loadu_pd(y_chunk.as_ptr());
+            let a_vec = _mm512_loadu_pd(a_chunk.as_ptr());
+            let x_vec = _mm512_loadu_pd(x_chunk.as_ptr());
+            
+            // Separate multiply and add (no FMA) to match the scalar backend bit for bit
+            let product = _mm512_mul_pd(a_vec, x_vec);
+            let y_new = _mm512_add_pd(y_vec, product);
+            
+            _mm512_storeu_pd(y_chunk.as_mut_ptr(), y_new);
+        }
+    }
+    
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx512f")]
+    unsafe fn scale(
+        &self,
+        y: &mut [f64],
+        a: f64,
+    ) {
+        // y *= a
+        let a_vec = _mm512_set1_pd(a);
+        
+        for y_chunk in y.chunks_exact_mut(8) {
+            let y_vec = _mm512_loadu_pd(y_chunk.as_ptr());
+            let y_new = _mm512_mul_pd(y_vec, a_vec);
+            _mm512_storeu_pd(y_chunk.as_mut_ptr(), y_new);
+        }
+    }
+    
     #[cfg(not(target_arch = "x86_64"))]
     unsafe fn update_velocity_vectorized(
         &self,
@@ -187,6 +231,25 @@ impl SimdBackend for Avx512Backend {
     ) {
         panic!("AVX-512 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
     }
+    
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe fn axpy(
+        &self,
+        _y: &mut [f64],
+        _a: &[f64],
+        _x: &[f64],
+    ) {
+        panic!("AVX-512 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
+    }
+    
+    #[cfg(not(target_arch = "x86_64"))]
+    unsafe fn scale(
+        &self,
+        _y: &mut [f64],
+        _a: f64,
+    ) {
+        panic!("AVX-512 backend is not available on non-x86_64 platforms. Use ScalarBackend instead or check is_supported() before use.");
+    }
 }
 
 #[cfg(test)]
@@ -315,4 +378,34 @@ mod tests {
                     "Mismatch at index {}: AVX-512={}, Scalar={}", i, velocities_avx512[i], velocities_scalar[i]);
         }
     }
+    
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_avx512_axpy_and_scale() {
+        let backend = Avx512Backend;
+        if !backend.is_supported() {
+            eprintln!("Skipping AVX-512 test - not supported on this CPU");
+            return;
+        }
+        // One full vector plus a tail element the kernel must leave alone
+        let mut y: Vec<f64> = (1..=9).map(|i| i as f64).collect();
+        let a = vec![0.5; 9];
+        let x: Vec<f64> = (1..=9).map(|i| 2.0 * i as f64).collect();
+        
+        unsafe {
+            backend.axpy(&mut y, &a, &x);
+        }
+        for (i, &value) in y[..8].iter().enumerate() {
+            assert_eq!(value, 2.0 * (i + 1) as f64);
+        }
+        assert_eq!(y[8], 9.0, "Tail element must be left to the caller");
+        
+        unsafe {
+            backend.scale(&mut y, 0.25);
+        }
+        for (i, &value) in y[..8].iter().enumerate() {
+            assert_eq!(value, 0.5 * (i + 1) as f64);
+        }
+        assert_eq!(y[8], 9.0);
+    }
 }
@@ -19,7 +19,7 @@
 use std::sync::OnceLock;
 
 /// CPU feature flags detected at runtime
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct CpuFeatures {
     /// CPU supports SSE (Streaming SIMD Extensions)
     pub has_sse: bool,
@@ -45,24 +45,6 @@ pub struct CpuFeatures {
     pub has_avx512dq: bool,
 }
 
-impl Default for CpuFeatures {
-    fn default() -> Self {
-        CpuFeatures {
-            has_sse: false,
-            has_sse2: false,
-            has_sse3: false,
-            has_ssse3: false,
-            has_sse4_1: false,
-            has_sse4_2: false,
-            has_avx: false,
-            has_avx2: false,
-            has_fma: false,
-            has_avx512f: false,
-            has_avx512dq: false,
-        }
-    }
-}
-
 impl CpuFeatures {
     /// Create a new CpuFeatures with all features disabled
     pub fn none() -> Self {
@@ -39,25 +39,28 @@
 mod dispatch;
 mod scalar;
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
 mod avx2;
 
-#[cfg(target_arch = "x86_64")]
+// The AVX-512 intrinsics were stabilized in Rust 1.89
+#[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
+#[clippy::msrv = "1.89"]
 mod avx512;
 
-pub use dispatch::{CpuFeatures, detect_cpu_features};
+use crate::real::Real;
+pub use dispatch::{CpuFeatures, detect_cpu_features, has_avx2, has_avx512};
 pub use scalar::ScalarBackend;
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
 pub use avx2::Avx2Backend;
 
-#[cfg(target_arch = "x86_64")]
+#[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
 pub use avx512::Avx512Backend;
 
 /// SIMD width for different instruction sets
-pub const AVX2_WIDTH: usize = 4;  // 256-bit / 64-bit per f64
+pub const AVX2_WIDTH: usize = 4;  // 256-bit / 64-bit per Real
 /// AVX-512 SIMD width: 8 f64 values per vector
-pub const AVX512_WIDTH: usize = 8; // 512-bit / 64-bit per f64
+pub const AVX512_WIDTH: usize = 8; // 512-bit / 64-bit per Real
 
 /// Backend for vectorized physics computations
 ///
@@ -91,9 +94,9 @@ pub trait SimdBackend: Send + Sync {
     /// - Implementation handles any length safely, processing full chunks only
     unsafe fn update_velocity_vectorized(
         &self,
-        velocities: &mut [f64],
-        accelerations: &[f64],
-        dt: f64,
+        velocities: &mut [Real],
+        accelerations: &[Real],
+        dt: Real,
     );
     
     /// Vectorized position update: p' = p + v * dt + 0.5 * a * dt²
@@ -108,11 +111,11 @@ pub trait SimdBackend: Send + Sync {
     /// - Implementation handles any length safely, processing full chunks only
     unsafe fn update_position_vectorized(
         &self,
-        positions: &mut [f64],
-        velocities: &[f64],
-        accelerations: &[f64],
-        dt: f64,
-        dt_sq_half: f64,
+        positions: &mut [Real],
+        velocities: &[Real],
+        accelerations: &[Real],
+        dt: Real,
+        dt_sq_half: Real,
     );
     
     /// Vectorized force accumulation: f_total += f
@@ -127,8 +130,39 @@ pub trait SimdBackend: Send + Sync {
     /// - Implementation handles any length safely, processing full chunks only
     unsafe fn accumulate_forces_vectorized(
         &self,
-        total_forces: &mut [f64],
-        forces: &[f64],
+        total_forces: &mut [Real],
+        forces: &[Real],
+    );
+
+    /// Vectorized multiply-add: y += a * x
+    ///
+    /// `a` is per-element, so converting forces to accelerations is
+    /// `axpy(accel, inv_mass, force)` with zeroed accelerations.
+    ///
+    /// # Safety
+    ///
+    /// - `y`, `a`, and `x` must have the same length
+    /// - Length should be divisible by `width()` for optimal performance
+    /// - Caller must ensure CPU supports required instructions
+    /// - Implementation handles any length safely, processing full chunks only
+    unsafe fn axpy(
+        &self,
+        y: &mut [Real],
+        a: &[Real],
+        x: &[Real],
+    );
+
+    /// Vectorized scaling: y *= a
+    ///
+    /// # Safety
+    ///
+    /// - Length should be divisible by `width()` for optimal performance
+    /// - Caller must ensure CPU supports required instructions
+    /// - Implementation handles any length safely, processing full chunks only
+    unsafe fn scale(
+        &self,
+        y: &mut [Real],
+        a: Real,
     );
 }
 
@@ -147,10 +181,11 @@ static SELECTED_BACKEND: OnceLock<&'static str> = OnceLock::new();
 /// Selection is cached globally for thread-safe access.
 pub fn select_backend() -> Box<dyn SimdBackend> {
     let backend_name = SELECTED_BACKEND.get_or_init(|| {
-        let features = detect_cpu_features();
-        
-        #[cfg(target_arch = "x86_64")]
+        // AVX kernels are f64-only, so single-precision builds use scalar
+        #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
         {
+            let features = detect_cpu_features();
+
             // Prefer AVX-512 if available
             if features.has_avx512f && features.has_avx512dq {
                 return "AVX-512";
@@ -168,9 +203,9 @@ pub fn select_backend() -> Box<dyn SimdBackend> {
     
     // Create backend based on cached selection
     match *backend_name {
-        #[cfg(target_arch = "x86_64")]
+        #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
         "AVX-512" => Box::new(Avx512Backend),
-        #[cfg(target_arch = "x86_64")]
+        #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
         "AVX2" => Box::new(Avx2Backend),
         _ => Box::new(ScalarBackend),
     }
@@ -190,10 +225,11 @@ mod tests {
     #[test]
     fn test_backend_selection_priority() {
         let backend = select_backend();
-        let features = detect_cpu_features();
         
-        #[cfg(target_arch = "x86_64")]
+        #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
         {
+            let features = detect_cpu_features();
+
             // Verify selection priority
             if features.has_avx512f && features.has_avx512dq {
                 assert_eq!(backend.name(), "AVX-512", "Should select AVX-512 when available");
@@ -207,7 +243,7 @@ mod tests {
             }
         }
         
-        #[cfg(not(target_arch = "x86_64"))]
+        #[cfg(not(all(target_arch = "x86_64", not(feature = "single-precision"))))]
         {
             assert_eq!(backend.name(), "Scalar", "Non-x86_64 should use scalar");
             assert_eq!(backend.width(), 1);
@@ -232,7 +268,7 @@ mod tests {
     }
     
     #[test]
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
     fn test_backend_correctness_across_implementations() {
         // Test that all backends produce the same results
         let mut velocities_scalar = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
@@ -270,6 +306,41 @@ mod tests {
         }
     }
     
+    #[test]
+    #[cfg(all(target_arch = "x86_64", not(feature = "single-precision")))]
+    fn test_axpy_and_scale_match_across_backends() {
+        let backends: Vec<Box<dyn SimdBackend>> = vec![Box::new(Avx2Backend), Box::new(Avx512Backend)];
+
+        for count in [0, 1, 3, 4, 7, 8, 9, 17, 33] {
+            let x: Vec<f64> = (0..count).map(|i| (i as f64 * 0.37).sin() * 1e3).collect();
+            let a: Vec<f64> = (0..count).map(|i| 1.0 / (1.0 + i as f64)).collect();
+            let y: Vec<f64> = (0..count).map(|i| (i as f64 * 1.3).cos()).collect();
+
+            let mut y_scalar = y.clone();
+            unsafe {
+                ScalarBackend.axpy(&mut y_scalar, &a, &x);
+                ScalarBackend.scale(&mut y_scalar, 0.75);
+            }
+
+            for backend in backends.iter().filter(|b| b.is_supported()) {
+                let mut y_simd = y.clone();
+                let simd_count = (count / backend.width()) * backend.width();
+                unsafe {
+                    backend.axpy(&mut y_simd[..simd_count], &a[..simd_count], &x[..simd_count]);
+                    backend.scale(&mut y_simd[..simd_count], 0.75);
+                }
+                // Tail with scalar code
+                for i in simd_count..count {
+                    y_simd[i] += a[i] * x[i];
+                    y_simd[i] *= 0.75;
+                }
+
+                // No FMA in either path, so results are bit-identical
+                assert_eq!(y_simd, y_scalar, "{} differs from scalar for count {}", backend.name(), count);
+            }
+        }
+    }
+
     #[test]
     #[cfg(target_arch = "x86_64")]
     fn test_non_aligned_entity_counts() {
@@ -332,10 +403,11 @@ mod tests {
     }
     
     #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "scalar backend has width 1")]
     fn test_single_element() {
         // Test with single element (requires tail handling since less than any SIMD width)
-        let mut velocities = vec![1.0];
-        let accelerations = vec![0.5];
+        let mut velocities = [1.0];
+        let accelerations = [0.5];
         let dt = 0.1;
         let element_count = 1;
         
@@ -359,7 +431,6 @@ mod tests {
     
     #[test]
     fn test_backend_thread_safety() {
-        use std::sync::Arc;
         use std::thread;
         
         // Test that backend selection is thread-safe and consistent
@@ -18,6 +18,7 @@
 //! - Reference implementation for testing SIMD correctness
 //! - Tail handler for entity counts not divisible by SIMD width
 
+use crate::real::Real;
 use super::SimdBackend;
 
 /// Scalar backend that processes one element at a time
@@ -41,9 +42,9 @@ impl SimdBackend for ScalarBackend {
     
     unsafe fn update_velocity_vectorized(
         &self,
-        velocities: &mut [f64],
-        accelerations: &[f64],
-        dt: f64,
+        velocities: &mut [Real],
+        accelerations: &[Real],
+        dt: Real,
     ) {
         // v' = v + a * dt
         for i in 0..velocities.len() {
@@ -53,11 +54,11 @@ impl SimdBackend for ScalarBackend {
     
     unsafe fn update_position_vectorized(
         &self,
-        positions: &mut [f64],
-        velocities: &[f64],
-        accelerations: &[f64],
-        dt: f64,
-        dt_sq_half: f64,
+        positions: &mut [Real],
+        velocities: &[Real],
+        accelerations: &[Real],
+        dt: Real,
+        dt_sq_half: Real,
     ) {
         // p' = p + v * dt + 0.5 * a * dt²
         for i in 0..positions.len() {
@@ -67,19 +68,43 @@ impl SimdBackend for ScalarBackend {
     
     unsafe fn accumulate_forces_vectorized(
         &self,
-        total_forces: &mut [f64],
-        forces: &[f64],
+        total_forces: &mut [Real],
+        forces: &[Real],
     ) {
         // f_total += f
         for i in 0..total_forces.len() {
             total_forces[i] += forces[i];
         }
     }
+    
+    unsafe fn axpy(
+        &self,
+        y: &mut [Real],
+        a: &[Real],
+        x: &[Real],
+    ) {
+        // y += a * x
+        for i in 0..y.len() {
+            y[i] += a[i] * x[i];
+        }
+    }
+    
+    unsafe fn scale(
+        &self,
+        y: &mut [Real],
+        a: Real,
+    ) {
+        // y *= a
+        for value in y.iter_mut() {
+            *value *= a;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::real::RealExt;
     
     #[test]
     fn test_scalar_backend_always_supported() {
@@ -125,10 +150,10 @@ mod tests {
         }
         
         // p' = p + v * dt + 0.5 * a * dt²
-        assert!((positions[0] - (0.0 + 10.0 * 0.1 + 1.0 * dt_sq_half)).abs() < 1e-10);
-        assert!((positions[1] - (1.0 + 20.0 * 0.1 + 2.0 * dt_sq_half)).abs() < 1e-10);
-        assert!((positions[2] - (2.0 + 30.0 * 0.1 + 3.0 * dt_sq_half)).abs() < 1e-10);
-        assert!((positions[3] - (3.0 + 40.0 * 0.1 + 4.0 * dt_sq_half)).abs() < 1e-10);
+        assert!((positions[0] - (0.0 + 10.0 * 0.1 + 1.0 * dt_sq_half)).abs() < Real::TOLERANCE);
+        assert!((positions[1] - (1.0 + 20.0 * 0.1 + 2.0 * dt_sq_half)).abs() < Real::TOLERANCE);
+        assert!((positions[2] - (2.0 + 30.0 * 0.1 + 3.0 * dt_sq_half)).abs() < Real::TOLERANCE);
+        assert!((positions[3] - (3.0 + 40.0 * 0.1 + 4.0 * dt_sq_half)).abs() < Real::TOLERANCE);
     }
     
     #[test]
@@ -146,4 +171,22 @@ mod tests {
         assert_eq!(total_forces[2], 4.5);
         assert_eq!(total_forces[3], 6.0);
     }
+    
+    #[test]
+    fn test_scalar_axpy_and_scale() {
+        let backend = ScalarBackend;
+        let mut y = vec![1.0, 2.0, 3.0, 4.0];
+        let a = vec![0.5, 0.25, 2.0, 0.0];
+        let x = vec![4.0, 8.0, 1.0, 100.0];
+        
+        unsafe {
+            backend.axpy(&mut y, &a, &x);
+        }
+        assert_eq!(y, vec![3.0, 4.0, 5.0, 4.0]);
+        
+        unsafe {
+            backend.scale(&mut y, 0.5);
+        }
+        assert_eq!(y, vec![1.5, 2.0, 2.5, 2.0]);
+    }
 }
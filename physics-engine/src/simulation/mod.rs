@@ -0,0 +1,1342 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Simulation driver owning the world, storages, and step loop
+//!
+//! `Simulation` bundles what every example wires up by hand: a `World`,
+//! the Position/Velocity/Acceleration/Mass storages, an integrator, a force
+//! registry, and optional N-body gravity, constraints, and collisions. Each
+//! `step()` runs the phases in its phase order:
+//!
+//! - `Phase::Force` recomputes forces and converts them to accelerations,
+//!   zeroing stale accelerations of bodies that no longer feel a force
+//! - `Phase::Integrate` advances the integrator, recomputing gravity at
+//!   every force evaluation within the step
+//! - `Phase::Constraint` applies the registered `ConstraintSystem`s in
+//!   ascending priority order
+//! - `Phase::Collision` detects contacts between bodies with a `Radius`
+//!   and resolves them with the `CollisionSystem`
+//!
+//! The default order is `Phase::DEFAULT_ORDER`. Moving the collision phase
+//! before integration resolves contacts from the positions the step starts
+//! at instead of the ones it ends at; see `set_phase_order()`. The first
+//! `step()` primes the integrator (`Integrator::prime()`) with the forces at
+//! the initial positions, so an order that integrates before computing forces
+//! doesn't start from zero accelerations.
+//!
+//! `set_speed_limits()` adds an optional `SpeedLimitSystem` stage that caps
+//! accelerations at the end of the force phase and speeds at the end of the
+//! integrate phase, recording a `LimitEvent` for each body it clamps.
+//!
+//! `set_work_tracking(true)` accumulates the work gravity does in each
+//! integrate phase in a `WorkAccumulator`, so a run's kinetic energy change
+//! can be checked against it with `WorkAccumulator::energy_leak()`.
+//! `sample_entity()` interpolates a body's state at any time within the last
+//! step.
+//!
+//! The integrators warn about and skip bodies that go non-finite, so a
+//! diverging run keeps stepping. `set_strict_finite(true)` instead makes
+//! `step()` and `run()` stop with `PhysicsError::NonFinite`, naming the first
+//! body whose position or velocity is NaN or infinite.
+//!
+//! External changes to a running simulation (spawning and destroying bodies,
+//! impulses, timestep and gravity changes) go through driver methods, so
+//! they can be captured in an `InputLog` and replayed; see the `replay`
+//! module.
+//!
+//! # Example
+//!
+//! ```
+//! use physics_engine::ecs::components::{Mass, Position, Velocity};
+//! use physics_engine::integration::VelocityVerletIntegrator;
+//! use physics_engine::plugins::gravity::GravityPlugin;
+//! use physics_engine::simulation::Simulation;
+//!
+//! let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+//! // Softening well below the separation, so the pull is visible in f32 too
+//! let mut gravity = GravityPlugin::new(1.0);
+//! gravity.set_softening(0.1);
+//! sim.set_gravity(Some(gravity));
+//! let a = sim.spawn_body(Position::new(-1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+//! let b = sim.spawn_body(Position::new(1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+//!
+//! sim.run(10).unwrap();
+//! assert_eq!(sim.step_count(), 10);
+//! // The bodies attract each other
+//! assert!(sim.position(a).unwrap().x() > -1.0);
+//! assert!(sim.position(b).unwrap().x() < 1.0);
+//! ```
+
+pub mod replay;
+
+pub use replay::{GravitySettings, InputEvent, InputLog, InputRecord};
+
+use crate::real::Real;
+use crate::error::PhysicsError;
+use crate::ecs::{ChangeTracking, ComponentStorage, Entity, HashMapStorage, World};
+use crate::ecs::components::{Acceleration, Mass, Position, Radius, Velocity};
+use crate::ecs::systems::{apply_forces_to_acceleration, reset_unforced_accelerations, Force, ForceRegistry, WarningHandler};
+use crate::integration::{calculate_power, Integrator, StateSampler, VelocityVerletIntegrator, WorkAccumulator};
+use crate::plugins::ConstraintSystem;
+use crate::plugins::collision::{CollisionEvents, CollisionSystem, ContactEvent};
+use crate::plugins::gravity::{GravityPlugin, GravitySystem};
+use crate::plugins::speed_limit::{LimitEvent, MotionLimits, SpeedLimitSystem};
+use std::collections::HashMap;
+
+/// One stage of a simulation step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Compute forces and convert them to accelerations
+    Force,
+    /// Advance positions and velocities by one timestep
+    Integrate,
+    /// Apply the registered constraints
+    Constraint,
+    /// Detect and resolve contacts
+    Collision,
+}
+
+impl Phase {
+    /// Phase order of a new simulation
+    ///
+    /// Constraints and collisions act on the positions integration produced,
+    /// so a step ends in a constrained, contact-resolved state.
+    pub const DEFAULT_ORDER: [Phase; 4] = [Phase::Force, Phase::Integrate, Phase::Constraint, Phase::Collision];
+}
+
+/// Fixed-timestep simulation driver
+///
+/// Generic over the integrator because `Integrator` is not object safe.
+/// Bodies are integrated in creation order, which keeps runs deterministic.
+pub struct Simulation<I: Integrator = VelocityVerletIntegrator> {
+    world: World,
+    /// Live bodies in creation order, collected from the world each step
+    step_bodies: Vec<Entity>,
+    /// Positions, marking the bodies written since the last step ended
+    positions: ChangeTracking<HashMapStorage<Position>>,
+    /// Tick at which each body's position last changed
+    position_ticks: HashMap<Entity, u64>,
+    velocities: HashMapStorage<Velocity>,
+    accelerations: HashMapStorage<Acceleration>,
+    masses: HashMapStorage<Mass>,
+    integrator: I,
+    force_registry: ForceRegistry,
+    gravity: Option<GravitySystem>,
+    gravity_forces: HashMap<Entity, Force>,
+    radii: HashMapStorage<Radius>,
+    /// Constraints in ascending priority order
+    constraints: Vec<Box<dyn ConstraintSystem>>,
+    collisions: Option<CollisionSystem>,
+    collision_events: CollisionEvents,
+    speed_limits: Option<SpeedLimitSystem>,
+    motion_limits: HashMapStorage<MotionLimits>,
+    work: Option<WorkAccumulator>,
+    /// Bodies' states at the start and end of the last step
+    sampler: StateSampler,
+    phase_order: Vec<Phase>,
+    strict_finite: bool,
+    /// Whether the integrator has been primed for the first step
+    primed: bool,
+    time: Real,
+    step_count: u64,
+    /// Change tick, bumped as each step begins
+    tick: u64,
+    recording: Option<InputLog>,
+}
+
+impl<I: Integrator> Simulation<I> {
+    /// Create an empty simulation stepped by `integrator`
+    ///
+    /// The timestep is the integrator's. Gravity and collisions are off until
+    /// `set_gravity()` and `set_collisions()` are called.
+    pub fn new(integrator: I) -> Self {
+        let mut force_registry = ForceRegistry::new();
+        force_registry.set_warn_on_missing_components(false);
+        Simulation {
+            world: World::new(),
+            step_bodies: Vec::new(),
+            positions: ChangeTracking::new(HashMapStorage::new()),
+            position_ticks: HashMap::new(),
+            velocities: HashMapStorage::new(),
+            accelerations: HashMapStorage::new(),
+            masses: HashMapStorage::new(),
+            integrator,
+            force_registry,
+            gravity: None,
+            gravity_forces: HashMap::new(),
+            radii: HashMapStorage::new(),
+            constraints: Vec::new(),
+            collisions: None,
+            collision_events: CollisionEvents::new(),
+            speed_limits: None,
+            motion_limits: HashMapStorage::new(),
+            work: None,
+            sampler: StateSampler::new(),
+            phase_order: Phase::DEFAULT_ORDER.to_vec(),
+            strict_finite: false,
+            primed: false,
+            time: 0.0,
+            step_count: 0,
+            tick: 0,
+            recording: None,
+        }
+    }
+
+    /// Enable N-body gravity with the given configuration, or disable it with `None`
+    ///
+    /// Recorded in the input log while recording.
+    pub fn set_gravity(&mut self, plugin: Option<GravityPlugin>) {
+        self.record(InputEvent::SetGravity { gravity: plugin.as_ref().map(GravitySettings::from) });
+        self.gravity = plugin.map(GravitySystem::new);
+    }
+
+    /// Enable contact resolution with the given system, or disable it with `None`
+    ///
+    /// Only bodies given a radius with `set_radius()` collide.
+    pub fn set_collisions(&mut self, collisions: Option<CollisionSystem>) {
+        self.collisions = collisions;
+    }
+
+    /// Enable speed and acceleration limits with the given system, or disable them with `None`
+    ///
+    /// Accelerations are capped after the force phase (and the first-step
+    /// priming), speeds after the integrate phase. Accelerations an
+    /// integrator evaluates within a step are not capped. Disabled, the
+    /// stage costs nothing and leaves every body untouched.
+    pub fn set_speed_limits(&mut self, limits: Option<SpeedLimitSystem>) {
+        self.speed_limits = limits;
+    }
+
+    /// Get the speed limit system, if enabled
+    pub fn speed_limits(&self) -> Option<&SpeedLimitSystem> {
+        self.speed_limits.as_ref()
+    }
+
+    /// Accumulate the work forces do during the integrate phase, or stop with `false`
+    ///
+    /// Enabling starts from zero work, even if tracking was already on. The
+    /// power Σ F·v is taken at the start and end of each integrate phase, so
+    /// tracking costs two extra force evaluations per step. Velocity changes
+    /// outside the integrate phase (constraints, collisions, speed limits,
+    /// impulses) do no work and show up in `WorkAccumulator::energy_leak()`.
+    pub fn set_work_tracking(&mut self, enabled: bool) {
+        self.work = enabled.then(WorkAccumulator::new);
+    }
+
+    /// Get the work done by forces since tracking was enabled, if it is
+    pub fn work(&self) -> Option<&WorkAccumulator> {
+        self.work.as_ref()
+    }
+
+    /// Register a constraint to apply in the constraint phase
+    ///
+    /// Constraints run in ascending `priority()` order; constraints of equal
+    /// priority run in the order they were added.
+    pub fn add_constraint(&mut self, constraint: Box<dyn ConstraintSystem>) {
+        let priority = constraint.priority();
+        let index = self.constraints.partition_point(|c| c.priority() <= priority);
+        self.constraints.insert(index, constraint);
+    }
+
+    /// Route the driver's warnings to `handler` instead of stderr
+    ///
+    /// Covers failed constraints and the warnings of the driver's force
+    /// registry. Missing-component warnings stay off, as the driver gives
+    /// every body the components it steps.
+    pub fn set_warning_handler(&mut self, handler: WarningHandler) {
+        self.force_registry.set_warning_handler(handler);
+    }
+
+    /// Set the order in which `step()` runs its phases
+    ///
+    /// Phases left out are skipped, so an order without `Phase::Collision`
+    /// disables contacts without discarding the collision system.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the order repeats a phase or leaves out `Phase::Force` or
+    /// `Phase::Integrate`.
+    pub fn set_phase_order(&mut self, order: Vec<Phase>) {
+        for (i, phase) in order.iter().enumerate() {
+            assert!(!order[..i].contains(phase), "Phase order must not repeat a phase");
+        }
+        assert!(
+            order.contains(&Phase::Force) && order.contains(&Phase::Integrate),
+            "Phase order must include the Force and Integrate phases"
+        );
+        self.phase_order = order;
+    }
+
+    /// Get the order in which `step()` runs its phases
+    pub fn phase_order(&self) -> &[Phase] {
+        &self.phase_order
+    }
+
+    /// Make `step()` fail as soon as a body's state goes non-finite
+    ///
+    /// Off by default, in which case non-finite bodies are left for the
+    /// integrators to warn about and skip. Enabling it costs a scan of every
+    /// position and velocity after each step.
+    pub fn set_strict_finite(&mut self, strict: bool) {
+        self.strict_finite = strict;
+    }
+
+    /// Check if `step()` fails on non-finite state
+    pub fn strict_finite(&self) -> bool {
+        self.strict_finite
+    }
+
+    /// Add a body and return its entity
+    ///
+    /// Recorded in the input log while recording.
+    pub fn spawn_body(&mut self, position: Position, velocity: Velocity, mass: Mass) -> Entity {
+        let entity = self.world.create_entity();
+        self.positions.insert(entity, position);
+        self.velocities.insert(entity, velocity);
+        self.accelerations.insert(entity, Acceleration::zero());
+        self.masses.insert(entity, mass);
+
+        self.record(InputEvent::Spawn {
+            position: position.as_array(),
+            velocity: velocity.as_array(),
+            mass: mass.value(),
+        });
+        entity
+    }
+
+    /// Give a body a collision radius
+    ///
+    /// Like the collision configuration, radii are not recorded in the
+    /// input log.
+    ///
+    /// # Returns
+    ///
+    /// False if the entity is not alive
+    pub fn set_radius(&mut self, entity: Entity, radius: Radius) -> bool {
+        if !self.world.is_entity_alive(entity) {
+            return false;
+        }
+        self.radii.insert(entity, radius);
+        true
+    }
+
+    /// Give a body its own speed and acceleration limits
+    ///
+    /// Caps set here override the speed limit system's global caps for this
+    /// body. Not recorded in the input log.
+    ///
+    /// # Returns
+    ///
+    /// False if the entity is not alive
+    pub fn set_motion_limits(&mut self, entity: Entity, limits: MotionLimits) -> bool {
+        if !self.world.is_entity_alive(entity) {
+            return false;
+        }
+        self.motion_limits.insert(entity, limits);
+        true
+    }
+
+    /// Remove a body and all of its components
+    ///
+    /// Returns false if the entity was not alive. Recorded in the input log
+    /// while recording.
+    pub fn destroy_body(&mut self, entity: Entity) -> bool {
+        if !self.world.destroy_entity(entity) {
+            return false;
+        }
+        self.positions.remove(entity);
+        self.position_ticks.remove(&entity);
+        self.velocities.remove(entity);
+        self.accelerations.remove(entity);
+        self.masses.remove(entity);
+        self.radii.remove(entity);
+        self.motion_limits.remove(entity);
+
+        self.record(InputEvent::Destroy { entity });
+        true
+    }
+
+    /// Change a body's velocity by `impulse / mass`
+    ///
+    /// Recorded in the input log while recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entity has no velocity or mass, is immovable,
+    /// or the impulse is not finite.
+    pub fn apply_impulse(&mut self, entity: Entity, impulse: [Real; 3]) -> Result<(), String> {
+        if !impulse.iter().all(|j| j.is_finite()) {
+            return Err(format!("Impulse for {:?} is not finite", entity));
+        }
+        let mass = self
+            .masses
+            .get(entity)
+            .ok_or_else(|| format!("Entity {:?} has no Mass component", entity))?;
+        let inv_mass = self.force_registry.inverse_mass(mass);
+        if inv_mass == 0.0 {
+            return Err(format!("Entity {:?} is immovable", entity));
+        }
+        let velocity = self
+            .velocities
+            .get_mut(entity)
+            .ok_or_else(|| format!("Entity {:?} has no Velocity component", entity))?;
+        velocity.set_dx(velocity.dx() + impulse[0] * inv_mass);
+        velocity.set_dy(velocity.dy() + impulse[1] * inv_mass);
+        velocity.set_dz(velocity.dz() + impulse[2] * inv_mass);
+
+        self.record(InputEvent::Impulse { entity, impulse });
+        Ok(())
+    }
+
+    /// Change the integration timestep
+    ///
+    /// A negative `dt` runs the simulation backwards on integrators that
+    /// support it. Recorded in the input log while recording.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is zero or not finite, as the integrators do.
+    pub fn set_timestep(&mut self, dt: Real) {
+        self.integrator.set_timestep(dt);
+        self.record(InputEvent::SetTimestep { dt });
+    }
+
+    /// Compute accelerations at the current positions without stepping
+    ///
+    /// Runs the force phase once. The first `step()` primes the integrator
+    /// anyway, so this only makes the accelerations available early, and
+    /// counts as that priming.
+    ///
+    /// Not recorded in the input log, and does not advance time.
+    pub fn initialize_accelerations(&mut self) {
+        self.collect_step_bodies();
+        self.prime();
+    }
+
+    /// Advance the simulation by one timestep
+    ///
+    /// Runs the phases in `phase_order()`. A constraint that fails is
+    /// reported as a warning (see `set_warning_handler()`) and the step
+    /// continues. The first step primes the
+    /// integrator before any phase runs, which evaluates the forces, so a
+    /// leading force phase is skipped on that step. Bodies spawned later
+    /// start from zero acceleration until the next force phase.
+    ///
+    /// # Returns
+    ///
+    /// Number of bodies the integrator updated
+    ///
+    /// # Errors
+    ///
+    /// In strict-finite mode, returns `PhysicsError::NonFinite` naming the
+    /// first body (in creation order) whose position or velocity is
+    /// non-finite after the step. The step still counts, and the offending state is kept so it
+    /// can be inspected.
+    pub fn step(&mut self) -> Result<usize, PhysicsError> {
+        self.tick += 1;
+        let dt = self.integrator.timestep();
+        self.collision_events.clear();
+        if let Some(limits) = &mut self.speed_limits {
+            limits.clear_events();
+        }
+        self.collect_step_bodies();
+        // Priming runs the force phase, so a force phase that comes before
+        // any other phase would recompute the same forces
+        let mut forces_current = !self.primed;
+        if !self.primed {
+            self.prime();
+        }
+        self.sampler.clear();
+        self.record_sample(self.time);
+
+        let mut updated = 0;
+        for i in 0..self.phase_order.len() {
+            match self.phase_order[i] {
+                Phase::Force if forces_current => {}
+                Phase::Force => self.compute_forces(),
+                Phase::Integrate => updated = self.integrate(),
+                Phase::Constraint => self.apply_constraints(),
+                Phase::Collision => self.resolve_collisions(),
+            }
+            forces_current = false;
+        }
+
+        self.stamp_position_changes();
+        self.time += dt;
+        self.step_count += 1;
+        self.record_sample(self.time);
+        if let Some(log) = &mut self.recording {
+            log.end_step = self.step_count;
+        }
+
+        if self.strict_finite {
+            if let Some((entity, field)) = self.first_non_finite() {
+                return Err(PhysicsError::NonFinite { entity, field });
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Snapshot the step bodies' positions and velocities for `sample_entity()`
+    fn record_sample(&mut self, time: Real) {
+        // Fails only for a step too short to change `time`, which leaves the
+        // sampler with a single snapshot and nothing to interpolate
+        let _ = self.sampler.record(time, self.step_bodies.iter(), &self.positions, &self.velocities);
+    }
+
+    /// Gather the live bodies, in creation order, for the phases to visit
+    fn collect_step_bodies(&mut self) {
+        self.step_bodies.clear();
+        self.step_bodies.extend(self.world.iter_entities());
+    }
+
+    /// Force phase: recompute forces and convert them to accelerations
+    fn compute_forces(&mut self) {
+        self.force_registry.clear();
+        self.force_registry.set_time(self.time);
+        if let Some(gravity) = &self.gravity {
+            gravity.compute_forces_into(&self.step_bodies, &self.positions, &self.masses, &mut self.gravity_forces);
+            self.force_registry.register_forces(&self.gravity_forces);
+        }
+
+        apply_forces_to_acceleration(
+            self.step_bodies.iter(),
+            &self.force_registry,
+            &self.masses,
+            &mut self.accelerations,
+            false,
+        );
+        reset_unforced_accelerations(
+            self.step_bodies.iter(),
+            &self.force_registry,
+            &self.masses,
+            &mut self.accelerations,
+        );
+        self.limit_accelerations();
+    }
+
+    /// Evaluate the initial accelerations before the first step
+    ///
+    /// `Integrator::prime()` lets the integrator drop state from before the
+    /// first step; the accelerations themselves come from the force phase,
+    /// so bodies without forces are zeroed and the limits apply as in every
+    /// later step.
+    fn prime(&mut self) {
+        self.integrator.prime(
+            self.step_bodies.iter(),
+            &self.positions,
+            &mut self.accelerations,
+            &self.masses,
+            &mut self.force_registry,
+            |_, _, registry| registry.clear(),
+        );
+        self.compute_forces();
+        self.primed = true;
+    }
+
+    /// Integrate phase: advance the integrator
+    ///
+    /// Gravity is recomputed at the positions of every evaluation within the
+    /// step, so Verlet's closing kick and each RK4 stage see the forces
+    /// where the bodies are rather than where the step started.
+    fn integrate(&mut self) -> usize {
+        let power_start = self.work.is_some().then(|| self.power());
+        let (gravity, masses, gravity_forces) = (&self.gravity, &self.masses, &mut self.gravity_forces);
+        let updated = self.integrator.integrate_with(
+            self.step_bodies.iter(),
+            &mut self.positions,
+            &mut self.velocities,
+            &self.accelerations,
+            &self.masses,
+            &mut self.force_registry,
+            false,
+            |entities, positions, registry| register_gravity(gravity, entities, positions, masses, gravity_forces, registry),
+        );
+        if let Some(power_start) = power_start {
+            let power_end = self.power();
+            let dt = self.integrator.timestep();
+            if let Some(work) = &mut self.work {
+                work.record_step(power_start, power_end, dt);
+            }
+        }
+        if let Some(limits) = &mut self.speed_limits {
+            limits.limit_velocities(&self.step_bodies, &mut self.velocities, &self.masses, &self.motion_limits);
+        }
+        updated
+    }
+
+    /// Power Σ F·v of the forces at the current positions and velocities
+    ///
+    /// Leaves those forces in the registry.
+    fn power(&mut self) -> Real {
+        register_gravity(
+            &self.gravity,
+            &self.step_bodies,
+            &self.positions,
+            &self.masses,
+            &mut self.gravity_forces,
+            &mut self.force_registry,
+        );
+        calculate_power(self.step_bodies.iter(), &self.force_registry, &self.velocities)
+    }
+
+    /// Cap accelerations with the speed limit system, if enabled
+    fn limit_accelerations(&mut self) {
+        if let Some(limits) = &mut self.speed_limits {
+            limits.limit_accelerations(&self.step_bodies, &mut self.accelerations, &self.masses, &self.motion_limits);
+        }
+    }
+
+    /// Constraint phase: apply every constraint in priority order
+    fn apply_constraints(&mut self) {
+        for constraint in &mut self.constraints {
+            if let Err(e) = constraint.apply_constraint(&mut self.positions, &mut self.velocities, &self.masses) {
+                self.force_registry
+                    .emit_warning(&format!("Warning: Constraint '{}' failed: {}", constraint.name(), e));
+            }
+        }
+    }
+
+    /// Collision phase: detect contacts at the current positions and resolve them
+    fn resolve_collisions(&mut self) {
+        if let Some(collisions) = &mut self.collisions {
+            collisions.detect_pairs(
+                &self.step_bodies,
+                &self.positions,
+                &self.velocities,
+                &self.masses,
+                &self.radii,
+                &mut self.collision_events,
+            );
+            collisions.resolve(&self.collision_events, &mut self.velocities, &self.masses);
+        }
+    }
+
+    /// Advance the simulation by `steps` timesteps
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first step that fails; see `step()`.
+    pub fn run(&mut self, steps: u64) -> Result<(), PhysicsError> {
+        for _ in 0..steps {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Find the first body, in creation order, with a NaN or infinite state
+    ///
+    /// # Returns
+    ///
+    /// The body and the name of the offending component, `"position"` or
+    /// `"velocity"`, or `None` if every body is finite
+    pub fn first_non_finite(&self) -> Option<(Entity, &'static str)> {
+        self.world.iter_entities().find_map(|entity| {
+            if self.positions.get(entity).is_some_and(|p| !p.is_valid()) {
+                Some((entity, "position"))
+            } else if self.velocities.get(entity).is_some_and(|v| !v.is_valid()) {
+                Some((entity, "velocity"))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Start recording external inputs into a new log
+    ///
+    /// The log remembers a fingerprint of the current state, so it can only
+    /// be replayed onto a simulation in exactly this state. Any log already
+    /// being recorded is discarded.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(InputLog::new(self.step_count, self.state_fingerprint()));
+    }
+
+    /// Stop recording and return the log, if recording
+    pub fn take_input_log(&mut self) -> Option<InputLog> {
+        self.recording.take()
+    }
+
+    /// Get the log being recorded, if any
+    pub fn input_log(&self) -> Option<&InputLog> {
+        self.recording.as_ref()
+    }
+
+    /// Hash of the complete simulation state
+    ///
+    /// Covers the step count, time, timestep, gravity settings (see
+    /// `GravitySettings`), and every body's entity, position, velocity, and
+    /// mass bit patterns, in body order. Two simulations with equal
+    /// fingerprints and the same collision and constraint setup step
+    /// identically.
+    pub fn state_fingerprint(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        hash.write(self.step_count);
+        hash.write_real(self.time);
+        hash.write_real(self.integrator.timestep());
+        match self.gravity.as_ref().map(|gravity| GravitySettings::from(gravity.plugin())) {
+            None => hash.write(0),
+            Some(settings) => {
+                hash.write(1);
+                hash.write_real(settings.g_constant);
+                hash.write_real(settings.softening);
+                hash.write(settings.softening_kernel as u64);
+                hash.write_real(settings.far_field_threshold.unwrap_or(-1.0));
+                let flags = [settings.per_body_softening, settings.compensated_summation, settings.sleeping_bodies_attract];
+                for flag in settings.axis_mask.iter().chain(&flags) {
+                    hash.write(*flag as u64);
+                }
+            }
+        }
+        hash.write(self.world.entity_count() as u64);
+        for entity in self.world.iter_entities() {
+            hash.write(entity.id().raw());
+            hash.write(entity.generation() as u64);
+            let components = [
+                self.positions.get(entity).map(|p| p.as_array()),
+                self.velocities.get(entity).map(|v| v.as_array()),
+            ];
+            for values in components.iter().flatten() {
+                values.iter().for_each(|&x| hash.write_real(x));
+            }
+            if let Some(mass) = self.masses.get(entity) {
+                hash.write_real(mass.value());
+            }
+        }
+        hash.finish()
+    }
+
+    /// Get the live bodies in creation order
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.world.iter_entities()
+    }
+
+    /// Get the world that allocates entities
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Get a body's position
+    pub fn position(&self, entity: Entity) -> Option<&Position> {
+        self.positions.get(entity)
+    }
+
+    /// Get a body's velocity
+    pub fn velocity(&self, entity: Entity) -> Option<&Velocity> {
+        self.velocities.get(entity)
+    }
+
+    /// Get the position storage
+    pub fn positions(&self) -> &HashMapStorage<Position> {
+        self.positions.inner()
+    }
+
+    /// Get the velocity storage
+    pub fn velocities(&self) -> &HashMapStorage<Velocity> {
+        &self.velocities
+    }
+
+    /// Get the acceleration storage
+    pub fn accelerations(&self) -> &HashMapStorage<Acceleration> {
+        &self.accelerations
+    }
+
+    /// Get the mass storage
+    pub fn masses(&self) -> &HashMapStorage<Mass> {
+        &self.masses
+    }
+
+    /// Get a body's collision radius
+    pub fn radius(&self, entity: Entity) -> Option<&Radius> {
+        self.radii.get(entity)
+    }
+
+    /// Get the contacts detected during the last step
+    pub fn collision_events(&self) -> &CollisionEvents {
+        &self.collision_events
+    }
+
+    /// Get the contacts resolved by the last collision phase
+    ///
+    /// Unlike `collision_events()`, these respect the collision system's
+    /// contact impulse threshold. Like them, they only cover the last step.
+    /// Empty when collisions are disabled.
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        self.collisions.as_ref().map_or(&[], CollisionSystem::contact_events)
+    }
+
+    /// Remove and return the contacts resolved by the last collision phase
+    ///
+    /// See `contact_events()`. Empty when collisions are disabled.
+    pub fn take_contact_events(&mut self) -> Vec<ContactEvent> {
+        self.collisions
+            .as_mut()
+            .map(CollisionSystem::take_contact_events)
+            .unwrap_or_default()
+    }
+
+    /// Drain the speed and acceleration limit events recorded during the last step
+    ///
+    /// Events are cleared at the start of every step, so collect them after
+    /// each `step()` to see a whole run. Empty when speed limits are disabled.
+    pub fn take_limit_events(&mut self) -> Vec<LimitEvent> {
+        self.speed_limits
+            .as_mut()
+            .map(SpeedLimitSystem::take_events)
+            .unwrap_or_default()
+    }
+
+    /// Interpolate a body's position and velocity at time `t` within the last step
+    ///
+    /// Uses a `StateSampler` holding the states the last step started and
+    /// ended at, so `t` may be anywhere between `time() - timestep()` and
+    /// `time()`, including during a backward run. Constraints and collisions
+    /// change velocities within the step, so interpolation across a contact
+    /// is only approximate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error before the first step, if `t` is outside the last
+    /// step, or if the body did not exist for the whole step.
+    pub fn sample_entity(&self, entity: Entity, t: Real) -> Result<(Position, Velocity), String> {
+        self.sampler.sample(entity, t)
+    }
+
+    /// Get the integrator
+    pub fn integrator(&self) -> &I {
+        &self.integrator
+    }
+
+    /// Get the current timestep
+    pub fn timestep(&self) -> Real {
+        self.integrator.timestep()
+    }
+
+    /// Get the simulated time in seconds
+    pub fn time(&self) -> Real {
+        self.time
+    }
+
+    /// Get the number of steps taken
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Get the change tick of the step in progress or last completed
+    ///
+    /// Starts at zero and increases by one as each step begins, before any
+    /// phase runs. The bodies whose position a step writes are stamped with
+    /// its tick; see `changed_since()`. Downstream systems that mirror
+    /// bodies into SoA storages can pass it to `PositionSoAStorage::set_tick()`
+    /// so that their ticks line up with the driver's.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Iterate over the bodies whose position changed after `tick`, in
+    /// creation order
+    ///
+    /// A body is stamped with a step's tick when that step writes its
+    /// position, so immovable and sleeping bodies are not reported. Bodies
+    /// spawned between steps are stamped by the next step. Pass the
+    /// `current_tick()` read after the last step you saw to get the bodies
+    /// that have moved since.
+    pub fn changed_since(&self, tick: u64) -> impl Iterator<Item = Entity> + '_ {
+        self.world
+            .iter_entities()
+            .filter(move |entity| self.position_ticks.get(entity).is_some_and(|&changed| changed > tick))
+    }
+
+    /// Stamp the bodies whose position was written with the current tick
+    fn stamp_position_changes(&mut self) {
+        for entity in self.positions.changed() {
+            if self.positions.contains(entity) {
+                self.position_ticks.insert(entity, self.tick);
+            }
+        }
+        self.positions.clear_changes();
+    }
+
+    /// Append an event to the log being recorded, stamped with the current step
+    fn record(&mut self, event: InputEvent) {
+        if let Some(log) = &mut self.recording {
+            log.push(InputRecord { step: self.step_count, event });
+        }
+    }
+}
+
+/// Replace the registry's forces with gravity at `positions`, if enabled
+fn register_gravity(
+    gravity: &Option<GravitySystem>,
+    entities: &[Entity],
+    positions: &impl ComponentStorage<Component = Position>,
+    masses: &HashMapStorage<Mass>,
+    gravity_forces: &mut HashMap<Entity, Force>,
+    registry: &mut ForceRegistry,
+) {
+    registry.clear();
+    if let Some(gravity) = gravity {
+        gravity.compute_forces_into(entities, positions, masses, gravity_forces);
+        registry.register_forces(gravity_forces);
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across runs and platforms
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    // `to_bits()` is u32 under single-precision
+    #[allow(clippy::unnecessary_cast)]
+    fn write_real(&mut self, value: Real) {
+        self.write(value.to_bits() as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::{Plugin, PluginContext};
+    use crate::plugins::speed_limit::LimitKind;
+    use crate::real::RealExt;
+    use std::any::Any;
+
+    /// Keeps its bodies at or above y = 0, stopping any downward motion there
+    struct Floor(Vec<Entity>);
+
+    impl Plugin for Floor {
+        fn name(&self) -> &str {
+            "floor"
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn initialize(&mut self, _context: &PluginContext) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+
+    impl ConstraintSystem for Floor {
+        fn apply_constraint(
+            &mut self,
+            positions: &mut dyn ComponentStorage<Component = Position>,
+            velocities: &mut dyn ComponentStorage<Component = Velocity>,
+            _masses: &dyn ComponentStorage<Component = Mass>,
+        ) -> Result<(), String> {
+            for &entity in &self.0 {
+                let position = positions.get_mut(entity).ok_or("Floor body has no position")?;
+                if position.y() < 0.0 {
+                    position.set_y(0.0);
+                    if let Some(velocity) = velocities.get_mut(entity) {
+                        velocity.set_dy(velocity.dy().max(0.0));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Two unit spheres already grazing at spawn, closing fast enough that
+    /// one step visibly changes the contact geometry
+    fn grazing_pair(order: Vec<Phase>) -> (Simulation, Entity, Entity) {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+        sim.set_collisions(Some(CollisionSystem::new(1.0)));
+        sim.set_phase_order(order);
+        let a = sim.spawn_body(Position::zero(), Velocity::zero(), Mass::new(1.0));
+        let b = sim.spawn_body(Position::new(-0.5, 1.9, 0.0), Velocity::new(1.0, 0.0, 0.0), Mass::new(1.0));
+        assert!(sim.set_radius(a, Radius::new(1.0)));
+        assert!(sim.set_radius(b, Radius::new(1.0)));
+        (sim, a, b)
+    }
+
+    #[test]
+    fn test_collision_phase_order_changes_grazing_contact() {
+        let before = vec![Phase::Force, Phase::Collision, Phase::Integrate, Phase::Constraint];
+        let after = Phase::DEFAULT_ORDER.to_vec();
+
+        let mut normals = Vec::new();
+        let mut deflections = Vec::new();
+        for order in [before, after] {
+            let (mut sim, a, b) = grazing_pair(order);
+            sim.step().unwrap();
+            assert_eq!(sim.collision_events().len(), 1);
+            normals.push(sim.collision_events().events()[0].normal);
+            assert_eq!(sim.contact_events().len(), 1);
+
+            let mut contacts = 1;
+            for _ in 0..30 {
+                sim.step().unwrap();
+                contacts += sim.collision_events().len();
+                contacts += sim.take_contact_events().len();
+            }
+            assert_eq!(contacts, 1, "A resolved contact was reported again");
+
+            // Elastic contact between equal masses conserves momentum and
+            // kinetic energy, pushes a away from b, and lets them separate
+            let va = sim.velocity(a).unwrap();
+            let vb = sim.velocity(b).unwrap();
+            assert!((va.dx() + vb.dx() - 1.0).abs() < Real::TOLERANCE);
+            assert!((va.dy() + vb.dy()).abs() < Real::TOLERANCE);
+            let energy = 0.5 * (va.magnitude().powi(2) + vb.magnitude().powi(2));
+            assert!((energy - 0.5).abs() < Real::TOLERANCE, "Kinetic energy {}", energy);
+            assert!(va.dy() < 0.0 && vb.dy() > 0.0);
+
+            let (pa, pb) = (sim.position(a).unwrap(), sim.position(b).unwrap());
+            let separation = ((pb.x() - pa.x()).powi(2) + (pb.y() - pa.y()).powi(2)).sqrt();
+            assert!(separation > 2.0, "Pair still overlapping at separation {}", separation);
+            deflections.push(va.dy());
+        }
+
+        // Resolving before integration uses the spawn geometry; resolving
+        // after it uses the shallower angle the step has moved b to
+        assert_ne!(normals[0], normals[1]);
+        assert!(deflections[0] < deflections[1] - 0.01, "Deflections {:?}", deflections);
+    }
+
+    #[test]
+    fn test_first_step_primes_accelerations() {
+        let integrate_first = vec![Phase::Integrate, Phase::Force];
+        let pair = || {
+            let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+            let mut gravity = GravityPlugin::new(1.0);
+            gravity.set_softening(0.0);
+            sim.set_gravity(Some(gravity));
+            sim.set_phase_order(integrate_first.clone());
+            let a = sim.spawn_body(Position::new(-1.0, 0.0, 0.0), Velocity::zero(), Mass::new(2.0));
+            let b = sim.spawn_body(Position::new(1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+            (sim, a, b)
+        };
+
+        let (mut initialized, a, b) = pair();
+        assert_eq!(*initialized.accelerations().get(a).unwrap(), Acceleration::zero());
+        initialized.initialize_accelerations();
+        assert_eq!(initialized.time(), 0.0);
+
+        // a = G m_other / r², toward the other body
+        let acceleration_a = initialized.accelerations().get(a).unwrap();
+        let acceleration_b = initialized.accelerations().get(b).unwrap();
+        assert!((acceleration_a.ax() - 0.25).abs() < Real::TOLERANCE, "a_a = {:?}", acceleration_a);
+        assert!((acceleration_b.ax() + 0.5).abs() < Real::TOLERANCE, "a_b = {:?}", acceleration_b);
+
+        // The first step primes the integrator itself, so the first drift
+        // feels gravity either way
+        let (mut primed, _, _) = pair();
+        initialized.step().unwrap();
+        primed.step().unwrap();
+        assert!(primed.position(a).unwrap().x() > -1.0);
+        assert_eq!(initialized.state_fingerprint(), primed.state_fingerprint());
+    }
+
+    /// Relative energy drift of an equal-mass circular orbit (G = 1) over two periods
+    fn circular_orbit_energy_drift<I: Integrator>(integrator: I) -> Real {
+        use crate::diagnostics::gravitational_potential_energy;
+        use crate::integration::calculate_total_kinetic_energy;
+
+        let mut sim = Simulation::new(integrator);
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.0);
+        sim.set_gravity(Some(gravity));
+        let speed = 0.5 * Real::sqrt(2.0);
+        sim.spawn_body(Position::new(-0.5, 0.0, 0.0), Velocity::new(0.0, -speed, 0.0), Mass::new(1.0));
+        sim.spawn_body(Position::new(0.5, 0.0, 0.0), Velocity::new(0.0, speed, 0.0), Mass::new(1.0));
+
+        let bodies: Vec<Entity> = sim.entities().collect();
+        let energy = |sim: &Simulation<I>| {
+            calculate_total_kinetic_energy(bodies.iter(), sim.velocities(), sim.masses())
+                + gravitational_potential_energy(&bodies, sim.positions(), sim.masses(), 1.0, 0.0)
+        };
+        let initial = energy(&sim);
+        let period = 2.0 * crate::real::consts::PI / Real::sqrt(2.0);
+        let steps = (2.0 * period / sim.timestep()).round() as u64;
+        let mut max_drift: Real = 0.0;
+        for _ in 0..steps {
+            sim.step().unwrap();
+            max_drift = max_drift.max(((energy(&sim) - initial) / initial).abs());
+        }
+        max_drift
+    }
+
+    #[test]
+    #[cfg_attr(feature = "single-precision", ignore = "f32 rounding swamps the truncation error")]
+    fn test_driver_conserves_circular_orbit_energy() {
+        use crate::integration::RK4Integrator;
+
+        // Forces refreshed within the step keep Verlet second order: its
+        // bounded energy error shrinks fourfold when dt halves
+        let coarse = circular_orbit_energy_drift(VelocityVerletIntegrator::new(0.01));
+        let fine = circular_orbit_energy_drift(VelocityVerletIntegrator::new(0.005));
+        assert!(coarse < 1e-6, "Verlet energy drift {:.3e}", coarse);
+        assert!(coarse / fine > 3.0, "Verlet drift {:.3e} -> {:.3e} is not second order", coarse, fine);
+
+        let rk4 = circular_orbit_energy_drift(RK4Integrator::new(0.01));
+        assert!(rk4 < 1e-8, "RK4 energy drift {:.3e}", rk4);
+    }
+
+    #[test]
+    fn test_work_tracking_accounts_for_kinetic_energy() {
+        use crate::integration::calculate_total_kinetic_energy;
+
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.1);
+        sim.set_gravity(Some(gravity));
+        sim.spawn_body(Position::new(-1.0, 0.0, 0.0), Velocity::new(0.0, 0.1, 0.0), Mass::new(1.0));
+        sim.spawn_body(Position::new(1.0, 0.0, 0.0), Velocity::new(0.0, -0.1, 0.0), Mass::new(2.0));
+        assert!(sim.work().is_none());
+        sim.set_work_tracking(true);
+
+        let bodies: Vec<Entity> = sim.entities().collect();
+        let kinetic = |sim: &Simulation| calculate_total_kinetic_energy(bodies.iter(), sim.velocities(), sim.masses());
+        let initial = kinetic(&sim);
+        sim.run(100).unwrap();
+
+        let work = sim.work().unwrap();
+        let delta_ke = kinetic(&sim) - initial;
+        assert_eq!(work.steps(), 100);
+        assert!(work.cumulative_work() > 0.0);
+        assert!(
+            work.energy_leak(delta_ke).abs() < 1e-4 * delta_ke,
+            "ΔKE {} vs work {}",
+            delta_ke,
+            work.cumulative_work()
+        );
+
+        sim.set_work_tracking(false);
+        sim.step().unwrap();
+        assert!(sim.work().is_none());
+    }
+
+    #[test]
+    fn test_sample_entity_within_last_step() {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+        let body = sim.spawn_body(Position::new(0.0, 0.0, 0.0), Velocity::new(1.0, 2.0, 0.0), Mass::new(1.0));
+        assert!(sim.sample_entity(body, 0.0).is_err());
+
+        sim.run(3).unwrap();
+        let t = sim.time() - 0.025;
+        let (pos, vel) = sim.sample_entity(body, t).unwrap();
+        assert!((pos.x() - t).abs() < 1e-12 * Real::EPSILON_RATIO);
+        assert!((pos.y() - 2.0 * t).abs() < 1e-12 * Real::EPSILON_RATIO);
+        assert!((vel.dx() - 1.0).abs() < 1e-12 * Real::EPSILON_RATIO);
+        assert!((vel.dy() - 2.0).abs() < 1e-12 * Real::EPSILON_RATIO);
+        assert!(sim.sample_entity(body, 0.05).unwrap_err().contains("outside the last step"));
+
+        // A backward step samples the interval it just ran over
+        sim.set_timestep(-0.1);
+        sim.step().unwrap();
+        let t = sim.time() + 0.04;
+        let (pos, _) = sim.sample_entity(body, t).unwrap();
+        assert!((pos.x() - t).abs() < 1e-12 * Real::EPSILON_RATIO);
+    }
+
+    #[test]
+    fn test_constraints_run_in_constraint_phase() {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+        let body = sim.spawn_body(Position::new(0.0, 0.05, 0.0), Velocity::new(1.0, -1.0, 0.0), Mass::new(1.0));
+        sim.add_constraint(Box::new(Floor(vec![body])));
+
+        sim.step().unwrap();
+        assert_eq!(sim.position(body).unwrap().y(), 0.0);
+        assert_eq!(sim.velocity(body).unwrap().dy(), 0.0);
+
+        // Without the constraint phase the body falls through the floor
+        sim.set_phase_order(vec![Phase::Force, Phase::Integrate]);
+        sim.apply_impulse(body, [0.0, -1.0, 0.0]).unwrap();
+        sim.step().unwrap();
+        assert!(sim.position(body).unwrap().y() < 0.0);
+    }
+
+    /// A tight gravitating pair on a close approach, with optional speed limits
+    fn close_encounter(limits: Option<SpeedLimitSystem>) -> (Simulation, Entity, Entity) {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.0);
+        sim.set_gravity(Some(gravity));
+        sim.set_speed_limits(limits);
+        let a = sim.spawn_body(Position::new(-0.5, 0.0, 0.0), Velocity::new(0.0, 0.5, 0.0), Mass::new(1.0));
+        let b = sim.spawn_body(Position::new(0.5, 0.0, 0.0), Velocity::new(0.0, -0.5, 0.0), Mass::new(1.0));
+        (sim, a, b)
+    }
+
+    #[test]
+    fn test_speed_limits_clamp_and_record_events() {
+        let limits = MotionLimits::new().with_max_speed(0.6).with_max_acceleration(2.0);
+        let (mut sim, a, b) = close_encounter(Some(SpeedLimitSystem::new(limits)));
+        assert!(sim.set_motion_limits(b, MotionLimits::new().with_max_speed(0.55)));
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            sim.step().unwrap();
+            // Each step starts from an empty buffer
+            assert!(sim.speed_limits().unwrap().events().len() <= 4);
+            events.extend(sim.take_limit_events());
+        }
+
+        assert!(sim.velocity(a).unwrap().magnitude() <= 0.6 * (1.0 + Real::EPSILON * 4.0));
+        assert!(sim.velocity(b).unwrap().magnitude() <= 0.55 * (1.0 + Real::EPSILON * 4.0));
+        assert!(events.iter().any(|e| e.entity == b && e.kind == LimitKind::Speed));
+        assert!(events.iter().any(|e| e.kind == LimitKind::Acceleration && e.original_magnitude > 2.0));
+        assert!(events.iter().all(|e| e.original_magnitude > 0.55));
+        assert!(sim.take_limit_events().is_empty());
+    }
+
+    #[test]
+    fn test_first_step_evaluates_forces_once() {
+        // Every force phase caps both accelerations and records an event
+        let limits = MotionLimits::new().with_max_acceleration(1.0e-3);
+        let (mut sim, _, _) = close_encounter(Some(SpeedLimitSystem::new(limits)));
+        sim.step().unwrap();
+        let acceleration_events =
+            |sim: &mut Simulation| sim.take_limit_events().iter().filter(|e| e.kind == LimitKind::Acceleration).count();
+        assert_eq!(acceleration_events(&mut sim), 2);
+        sim.step().unwrap();
+        assert_eq!(acceleration_events(&mut sim), 2);
+    }
+
+    #[test]
+    fn test_generous_speed_limits_leave_run_bitwise_identical() {
+        let generous = MotionLimits::new().with_max_speed(1.0e6).with_max_acceleration(1.0e6);
+        let (mut limited, _, _) = close_encounter(Some(SpeedLimitSystem::new(generous)));
+        let (mut unlimited, _, _) = close_encounter(None);
+        for _ in 0..200 {
+            limited.step().unwrap();
+            unlimited.step().unwrap();
+            assert_eq!(limited.state_fingerprint(), unlimited.state_fingerprint());
+        }
+        assert!(limited.take_limit_events().is_empty());
+    }
+
+    /// A light body beside a heavy one, stepped with a timestep so large
+    /// that the light body's first step overflows its position while the
+    /// heavy body's stays finite
+    ///
+    /// Strict validation panics on the overflow before the driver sees it.
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
+    fn overflowing_pair(strict: bool) -> (Simulation, Entity) {
+        let dt = (Real::MAX / 4.0).sqrt();
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(dt));
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.0);
+        sim.set_gravity(Some(gravity));
+        sim.set_strict_finite(strict);
+        let light = sim.spawn_body(Position::new(1.0, 0.0, 0.0), Velocity::zero(), Mass::new(1.0));
+        sim.spawn_body(Position::zero(), Velocity::zero(), Mass::new(100.0));
+        (sim, light)
+    }
+
+    #[test]
+    fn test_current_tick_advances_per_step() {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+        sim.spawn_body(Position::zero(), Velocity::new(1.0, 0.0, 0.0), Mass::new(1.0));
+        assert_eq!(sim.current_tick(), 0);
+        sim.run(3).unwrap();
+        assert_eq!(sim.current_tick(), 3);
+    }
+
+    #[test]
+    fn test_changed_since_reports_only_movers() {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.1);
+        sim.set_gravity(Some(gravity));
+        // Every other body is immovable
+        let bodies: Vec<Entity> = (0..10)
+            .map(|i| {
+                let mass = if i % 2 == 0 { Mass::new(1.0) } else { Mass::immovable() };
+                sim.spawn_body(Position::new(i as Real, 0.0, 0.0), Velocity::zero(), mass)
+            })
+            .collect();
+        sim.step().unwrap();
+
+        let seen = sim.current_tick();
+        sim.step().unwrap();
+        let movers: Vec<Entity> = bodies.iter().copied().step_by(2).collect();
+        assert_eq!(sim.changed_since(seen).collect::<Vec<_>>(), movers);
+        assert_eq!(sim.changed_since(sim.current_tick()).count(), 0);
+
+        // A destroyed body is no longer reported
+        sim.destroy_body(bodies[0]);
+        assert_eq!(sim.changed_since(seen).collect::<Vec<_>>(), movers[1..]);
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
+    fn test_strict_finite_halts_on_first_non_finite_body() {
+        let (mut sim, light) = overflowing_pair(true);
+        assert!(sim.strict_finite());
+
+        let err = sim.run(10).unwrap_err();
+        assert_eq!(sim.step_count(), 1, "Run did not halt at the failing step");
+        assert_eq!(err, PhysicsError::NonFinite { entity: light, field: "position" });
+        assert_eq!(sim.first_non_finite(), Some((light, "position")));
+        assert!(!sim.position(light).unwrap().is_valid());
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
+    fn test_lenient_mode_keeps_stepping_non_finite_bodies() {
+        let (mut sim, light) = overflowing_pair(false);
+        sim.run(10).unwrap();
+        assert_eq!(sim.step_count(), 10);
+        assert_eq!(sim.first_non_finite(), Some((light, "position")));
+    }
+
+    #[test]
+    fn test_failed_constraint_goes_to_warning_handler() {
+        use std::sync::{Arc, Mutex};
+
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+        let captured = Arc::new(Mutex::new(Vec::<String>::new()));
+        let sink = Arc::clone(&captured);
+        sim.set_warning_handler(Box::new(move |message| {
+            sink.lock().unwrap().push(message.to_string());
+        }));
+        let body = sim.spawn_body(Position::zero(), Velocity::zero(), Mass::new(1.0));
+        sim.add_constraint(Box::new(Floor(vec![body])));
+        sim.destroy_body(body);
+
+        sim.step().unwrap();
+        let messages = captured.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("Constraint 'floor' failed"), "{}", messages[0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Phase order must not repeat a phase")]
+    fn test_repeated_phase_panics() {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+        sim.set_phase_order(vec![Phase::Force, Phase::Integrate, Phase::Force]);
+    }
+}
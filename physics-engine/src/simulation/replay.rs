@@ -0,0 +1,653 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Recording and replaying external inputs
+//!
+//! A simulation step is deterministic, so a run is fully described by its
+//! initial state plus everything done to it from outside: bodies spawned
+//! and destroyed, impulses applied, and timestep and gravity changes. While
+//! recording, `Simulation` appends each of these to an `InputLog` stamped
+//! with the step it happened before. Replaying the log onto a simulation in
+//! the same initial state reproduces the run bit for bit, which turns a
+//! report like "my simulation exploded at step 8412" into a regression test.
+//!
+//! # Example
+//!
+//! ```
+//! use physics_engine::ecs::components::{Mass, Position, Velocity};
+//! use physics_engine::integration::VelocityVerletIntegrator;
+//! use physics_engine::simulation::{InputLog, Simulation};
+//!
+//! fn initial() -> Simulation {
+//!     let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+//!     sim.spawn_body(Position::zero(), Velocity::zero(), Mass::new(2.0));
+//!     sim
+//! }
+//!
+//! let mut sim = initial();
+//! let body = sim.entities().next().unwrap();
+//! sim.start_recording();
+//! sim.run(5).unwrap();
+//! sim.apply_impulse(body, [1.0, 0.0, 0.0]).unwrap();
+//! sim.run(5).unwrap();
+//! let text = sim.take_input_log().unwrap().serialize();
+//!
+//! let mut replayed = initial();
+//! InputLog::parse(&text).unwrap().replay(&mut replayed).unwrap();
+//! assert_eq!(replayed.position(body), sim.position(body));
+//! ```
+//!
+//! # Format
+//!
+//! `serialize()` writes one line per record. Reals are written as the hex
+//! digits of their bit patterns so that they round-trip exactly, and the
+//! header names the precision so a log cannot be replayed by a build with a
+//! different `Real`.
+
+use crate::real::Real;
+use crate::ecs::Entity;
+use crate::ecs::components::{Mass, Position, Velocity};
+use crate::integration::Integrator;
+use crate::plugins::gravity::{GravityPlugin, SofteningKernel};
+use super::Simulation;
+
+/// First line of a serialized log
+const HEADER: &str = "input-log v1";
+
+/// Name of the `Real` type, recorded in serialized logs
+#[cfg(not(feature = "single-precision"))]
+const PRECISION: &str = "f64";
+#[cfg(feature = "single-precision")]
+const PRECISION: &str = "f32";
+
+/// An external change made to a simulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    /// A body was added with these initial components
+    Spawn {
+        /// Initial position (m)
+        position: [Real; 3],
+        /// Initial velocity (m/s)
+        velocity: [Real; 3],
+        /// Mass (kg)
+        mass: Real,
+    },
+    /// A body was removed
+    Destroy {
+        /// The removed body
+        entity: Entity,
+    },
+    /// A body's velocity was changed by impulse / mass
+    Impulse {
+        /// The body receiving the impulse
+        entity: Entity,
+        /// Impulse vector (N⋅s)
+        impulse: [Real; 3],
+    },
+    /// The timestep was changed
+    SetTimestep {
+        /// New timestep (s)
+        dt: Real,
+    },
+    /// N-body gravity was enabled, reconfigured or disabled
+    SetGravity {
+        /// New configuration, or `None` for gravity off
+        gravity: Option<GravitySettings>,
+    },
+}
+
+/// The parts of a `GravityPlugin` configuration that change its forces
+///
+/// Recorded by `InputEvent::SetGravity` and covered by the state
+/// fingerprint. Warning settings and the parallel chunk size don't affect
+/// the forces and are not recorded; a replayed plugin gets their defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GravitySettings {
+    /// Gravitational constant (m³/(kg⋅s²))
+    pub g_constant: Real,
+    /// Global softening length (m)
+    pub softening: Real,
+    /// Kernel applying the softening length
+    pub softening_kernel: SofteningKernel,
+    /// Whether softening is derived from per-body radii
+    pub per_body_softening: bool,
+    /// Far-field monopole threshold distance (m), if enabled
+    pub far_field_threshold: Option<Real>,
+    /// Axes (x, y, z) whose force components are zeroed
+    pub axis_mask: [bool; 3],
+    /// Whether pairwise forces are summed with compensation
+    pub compensated_summation: bool,
+    /// Whether sleeping bodies attract awake ones
+    pub sleeping_bodies_attract: bool,
+}
+
+impl GravitySettings {
+    /// Build a plugin with these settings
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is one the plugin's setters reject, e.g.
+    /// a negative gravitational constant read from a corrupted log.
+    pub fn to_plugin(&self) -> Result<GravityPlugin, String> {
+        if !(self.g_constant >= 0.0 && self.g_constant.is_finite()) {
+            return Err(format!("invalid gravitational constant {}", self.g_constant));
+        }
+        if !(self.softening >= 0.0 && self.softening.is_finite()) {
+            return Err(format!("invalid softening {}", self.softening));
+        }
+        let mut plugin = GravityPlugin::new(self.g_constant);
+        plugin.set_softening(self.softening);
+        plugin.set_softening_kernel(self.softening_kernel);
+        plugin.set_use_per_body_softening(self.per_body_softening);
+        if let Some(threshold) = self.far_field_threshold {
+            if !(threshold > 0.0 && threshold.is_finite()) {
+                return Err(format!("invalid far-field threshold {}", threshold));
+            }
+            plugin.set_far_field_approximation(threshold);
+        }
+        plugin.set_axis_mask(self.axis_mask);
+        plugin.set_compensated_summation(self.compensated_summation);
+        plugin.set_sleeping_bodies_attract(self.sleeping_bodies_attract);
+        Ok(plugin)
+    }
+}
+
+impl From<&GravityPlugin> for GravitySettings {
+    fn from(plugin: &GravityPlugin) -> Self {
+        GravitySettings {
+            g_constant: plugin.gravitational_constant(),
+            softening: plugin.softening(),
+            softening_kernel: plugin.softening_kernel(),
+            per_body_softening: plugin.use_per_body_softening(),
+            far_field_threshold: plugin.far_field_threshold(),
+            axis_mask: plugin.axis_mask(),
+            compensated_summation: plugin.compensated_summation(),
+            sleeping_bodies_attract: plugin.sleeping_bodies_attract(),
+        }
+    }
+}
+
+/// An input event and the step it was applied before
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputRecord {
+    /// Number of steps completed when the event happened
+    pub step: u64,
+    /// What happened
+    pub event: InputEvent,
+}
+
+/// External inputs recorded from a simulation, in the order they happened
+///
+/// Created by `Simulation::start_recording()` and retrieved with
+/// `Simulation::take_input_log()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputLog {
+    start_step: u64,
+    fingerprint: u64,
+    pub(super) end_step: u64,
+    records: Vec<InputRecord>,
+}
+
+impl InputLog {
+    /// Start an empty log for a simulation at `start_step` with the given state fingerprint
+    pub(super) fn new(start_step: u64, fingerprint: u64) -> Self {
+        InputLog {
+            start_step,
+            fingerprint,
+            end_step: start_step,
+            records: Vec::new(),
+        }
+    }
+
+    /// Append a record
+    pub(super) fn push(&mut self, record: InputRecord) {
+        self.records.push(record);
+    }
+
+    /// Get the recorded events in order
+    pub fn records(&self) -> &[InputRecord] {
+        &self.records
+    }
+
+    /// Get the step count when recording started
+    pub fn start_step(&self) -> u64 {
+        self.start_step
+    }
+
+    /// Get the step count when recording stopped
+    pub fn end_step(&self) -> u64 {
+        self.end_step
+    }
+
+    /// Get the state fingerprint the log must be replayed onto
+    ///
+    /// See `Simulation::state_fingerprint()`.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Write the log as text
+    pub fn serialize(&self) -> String {
+        let mut out = format!(
+            "{} {}\nstart {} {:016x}\nend {}\n",
+            HEADER, PRECISION, self.start_step, self.fingerprint, self.end_step
+        );
+        for record in &self.records {
+            let line = match record.event {
+                InputEvent::Spawn { position, velocity, mass } => format!(
+                    "spawn {} {} {}",
+                    encode_all(&position),
+                    encode_all(&velocity),
+                    encode(mass)
+                ),
+                InputEvent::Destroy { entity } => {
+                    format!("destroy {} {}", entity.id().raw(), entity.generation())
+                }
+                InputEvent::Impulse { entity, impulse } => format!(
+                    "impulse {} {} {}",
+                    entity.id().raw(),
+                    entity.generation(),
+                    encode_all(&impulse)
+                ),
+                InputEvent::SetTimestep { dt } => format!("timestep {}", encode(dt)),
+                InputEvent::SetGravity { gravity: None } => "gravity off".to_string(),
+                InputEvent::SetGravity { gravity: Some(settings) } => format!(
+                    "gravity {} {} {} {} {} {} {} {}",
+                    encode(settings.g_constant),
+                    encode(settings.softening),
+                    match settings.softening_kernel {
+                        SofteningKernel::Plummer => "plummer",
+                        SofteningKernel::Spline => "spline",
+                    },
+                    settings.far_field_threshold.map_or("-".to_string(), encode),
+                    flag(settings.per_body_softening),
+                    settings.axis_mask.map(flag).concat(),
+                    flag(settings.compensated_summation),
+                    flag(settings.sleeping_bodies_attract)
+                ),
+            };
+            out.push_str(&format!("{} {}\n", record.step, line));
+        }
+        out
+    }
+
+    /// Read a log written by `serialize()`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the offending line if the text is not a
+    /// valid log, or if it was written with a different `Real` precision.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines.next().ok_or("Input log is empty")?;
+        match header.strip_prefix(HEADER).map(str::trim) {
+            Some(PRECISION) => {}
+            Some(other) => {
+                return Err(format!(
+                    "Input log was recorded with {} precision, but this build uses {}",
+                    other, PRECISION
+                ))
+            }
+            None => return Err(format!("Not an input log: expected header '{}'", HEADER)),
+        }
+
+        let (start_step, fingerprint) = match lines.next() {
+            Some((n, line)) => {
+                let fields = expect_fields(n, line, "start", 2)?;
+                (parse_u64(n, fields[0])?, parse_hex(n, fields[1])?)
+            }
+            None => return Err("Input log is missing its 'start' line".to_string()),
+        };
+        let end_step = match lines.next() {
+            Some((n, line)) => parse_u64(n, expect_fields(n, line, "end", 1)?[0])?,
+            None => return Err("Input log is missing its 'end' line".to_string()),
+        };
+
+        let mut records = Vec::new();
+        for (n, line) in lines {
+            let (step, rest) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("Line {}: expected '<step> <event> ...'", n + 1))?;
+            let step = parse_u64(n, step)?;
+            let kind = rest.split_whitespace().next().unwrap_or("");
+            let event = match kind {
+                "spawn" => {
+                    let f = expect_fields(n, rest, "spawn", 7)?;
+                    InputEvent::Spawn {
+                        position: [decode(n, f[0])?, decode(n, f[1])?, decode(n, f[2])?],
+                        velocity: [decode(n, f[3])?, decode(n, f[4])?, decode(n, f[5])?],
+                        mass: decode(n, f[6])?,
+                    }
+                }
+                "destroy" => {
+                    let f = expect_fields(n, rest, "destroy", 2)?;
+                    InputEvent::Destroy { entity: parse_entity(n, f[0], f[1])? }
+                }
+                "impulse" => {
+                    let f = expect_fields(n, rest, "impulse", 5)?;
+                    InputEvent::Impulse {
+                        entity: parse_entity(n, f[0], f[1])?,
+                        impulse: [decode(n, f[2])?, decode(n, f[3])?, decode(n, f[4])?],
+                    }
+                }
+                "timestep" => {
+                    let f = expect_fields(n, rest, "timestep", 1)?;
+                    InputEvent::SetTimestep { dt: decode(n, f[0])? }
+                }
+                "gravity" => InputEvent::SetGravity { gravity: parse_gravity(n, rest)? },
+                other => return Err(format!("Line {}: unknown event '{}'", n + 1, other)),
+            };
+            records.push(InputRecord { step, event });
+        }
+
+        Ok(InputLog { start_step, fingerprint, end_step, records })
+    }
+
+    /// Re-run the recorded inputs on `simulation`
+    ///
+    /// Steps the simulation up to each record's step, applies the event, and
+    /// finally steps up to the step recording stopped at. `simulation` must
+    /// be in exactly the state recording started from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without touching the simulation if its state does
+    /// not match the log's initial snapshot. Returns an error part-way if
+    /// the replay diverges, e.g. an impulse targets a body that is not alive,
+    /// or if a step fails because the simulation is in strict-finite mode.
+    pub fn replay<I: Integrator>(&self, simulation: &mut Simulation<I>) -> Result<(), String> {
+        let fingerprint = simulation.state_fingerprint();
+        if simulation.step_count() != self.start_step || fingerprint != self.fingerprint {
+            return Err(format!(
+                "Initial snapshot mismatch: log expects state {:016x} at step {}, \
+                 but the simulation has state {:016x} at step {}",
+                self.fingerprint,
+                self.start_step,
+                fingerprint,
+                simulation.step_count()
+            ));
+        }
+
+        for record in &self.records {
+            if record.step < simulation.step_count() {
+                return Err(format!(
+                    "Input log records are out of order at step {}",
+                    record.step
+                ));
+            }
+            simulation.run(record.step - simulation.step_count()).map_err(|e| e.to_string())?;
+
+            let diverged = |reason: String| format!("Replay diverged at step {}: {}", record.step, reason);
+            match record.event {
+                InputEvent::Spawn { position, velocity, mass } => {
+                    let mass = Mass::try_new(mass).map_err(|e| diverged(e.to_string()))?;
+                    simulation.spawn_body(Position::from_array(position), Velocity::from_array(velocity), mass);
+                }
+                InputEvent::Destroy { entity } => {
+                    if !simulation.destroy_body(entity) {
+                        return Err(diverged(format!("{:?} is not alive", entity)));
+                    }
+                }
+                InputEvent::Impulse { entity, impulse } => {
+                    simulation.apply_impulse(entity, impulse).map_err(diverged)?;
+                }
+                InputEvent::SetTimestep { dt } => {
+                    if !(dt > 0.0 && dt.is_finite()) {
+                        return Err(diverged(format!("invalid timestep {}", dt)));
+                    }
+                    simulation.set_timestep(dt);
+                }
+                InputEvent::SetGravity { gravity } => {
+                    let plugin = gravity.map(|settings| settings.to_plugin()).transpose().map_err(diverged)?;
+                    simulation.set_gravity(plugin);
+                }
+            }
+        }
+
+        if self.end_step > simulation.step_count() {
+            simulation.run(self.end_step - simulation.step_count()).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Hex digits of a real's bit pattern
+fn encode(value: Real) -> String {
+    format!("{:x}", value.to_bits())
+}
+
+fn encode_all(values: &[Real; 3]) -> String {
+    values.map(encode).join(" ")
+}
+
+/// Parse a real written by `encode()`
+// The bit conversion is a no-op for f64 but narrows to u32 for f32
+#[allow(clippy::useless_conversion)]
+fn decode(line: usize, text: &str) -> Result<Real, String> {
+    let bits = parse_hex(line, text)?
+        .try_into()
+        .map_err(|_| format!("Line {}: '{}' is too wide for {}", line + 1, text, PRECISION))?;
+    Ok(Real::from_bits(bits))
+}
+
+fn flag(value: bool) -> &'static str {
+    if value { "1" } else { "0" }
+}
+
+fn parse_flag(line: usize, text: &str) -> Result<bool, String> {
+    match text {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(format!("Line {}: '{}' is not a 0/1 flag", line + 1, text)),
+    }
+}
+
+/// Parse the fields of a `gravity` line: `off`, or the settings written by `serialize()`
+fn parse_gravity(line: usize, text: &str) -> Result<Option<GravitySettings>, String> {
+    if text.split_whitespace().nth(1) == Some("off") {
+        expect_fields(line, text, "gravity", 1)?;
+        return Ok(None);
+    }
+    let f = expect_fields(line, text, "gravity", 8)?;
+    let softening_kernel = match f[2] {
+        "plummer" => SofteningKernel::Plummer,
+        "spline" => SofteningKernel::Spline,
+        other => return Err(format!("Line {}: unknown softening kernel '{}'", line + 1, other)),
+    };
+    let far_field_threshold = match f[3] {
+        "-" => None,
+        bits => Some(decode(line, bits)?),
+    };
+    let mask: Vec<char> = f[5].chars().collect();
+    if mask.len() != 3 {
+        return Err(format!("Line {}: axis mask '{}' must have 3 flags", line + 1, f[5]));
+    }
+    let mut axis_mask = [false; 3];
+    for (axis, c) in axis_mask.iter_mut().zip(&mask) {
+        *axis = parse_flag(line, &c.to_string())?;
+    }
+    Ok(Some(GravitySettings {
+        g_constant: decode(line, f[0])?,
+        softening: decode(line, f[1])?,
+        softening_kernel,
+        far_field_threshold,
+        per_body_softening: parse_flag(line, f[4])?,
+        axis_mask,
+        compensated_summation: parse_flag(line, f[6])?,
+        sleeping_bodies_attract: parse_flag(line, f[7])?,
+    }))
+}
+
+/// Split `text` into its keyword and exactly `count` fields
+fn expect_fields<'a>(line: usize, text: &'a str, keyword: &str, count: usize) -> Result<Vec<&'a str>, String> {
+    let mut parts = text.split_whitespace();
+    let fields: Vec<&str> = match parts.next() {
+        Some(word) if word == keyword => parts.collect(),
+        _ => return Err(format!("Line {}: expected '{}'", line + 1, keyword)),
+    };
+    if fields.len() != count {
+        return Err(format!(
+            "Line {}: '{}' takes {} fields, found {}",
+            line + 1,
+            keyword,
+            count,
+            fields.len()
+        ));
+    }
+    Ok(fields)
+}
+
+fn parse_u64(line: usize, text: &str) -> Result<u64, String> {
+    text.parse()
+        .map_err(|_| format!("Line {}: '{}' is not an unsigned integer", line + 1, text))
+}
+
+fn parse_hex(line: usize, text: &str) -> Result<u64, String> {
+    u64::from_str_radix(text, 16).map_err(|_| format!("Line {}: '{}' is not a hex value", line + 1, text))
+}
+
+fn parse_entity(line: usize, id: &str, generation: &str) -> Result<Entity, String> {
+    let generation = generation
+        .parse()
+        .map_err(|_| format!("Line {}: '{}' is not a generation", line + 1, generation))?;
+    Ok(Entity::new(parse_u64(line, id)?, generation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::ComponentStorage;
+    use crate::integration::VelocityVerletIntegrator;
+    use crate::plugins::gravity::GravityPlugin;
+
+    /// Three mutually attracting bodies
+    fn initial_state() -> Simulation {
+        let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.01));
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.1);
+        sim.set_gravity(Some(gravity));
+        for (x, vy) in [(-1.0, -0.3), (0.0, 0.0), (1.2, 0.4)] {
+            sim.spawn_body(Position::new(x, 0.0, 0.0), Velocity::new(0.0, vy, 0.0), Mass::new(1.0));
+        }
+        sim
+    }
+
+    /// Run a scripted session, returning the serialized log
+    fn scripted_run(sim: &mut Simulation) -> String {
+        let bodies: Vec<Entity> = sim.entities().collect();
+        sim.start_recording();
+        sim.run(5).unwrap();
+        sim.apply_impulse(bodies[0], [0.5, 0.1, 0.0]).unwrap();
+        sim.run(7).unwrap();
+        sim.spawn_body(Position::new(0.0, 2.0, 0.0), Velocity::new(0.1, 0.0, 0.0), Mass::new(0.5));
+        sim.apply_impulse(bodies[2], [-0.2, 0.0, 0.3]).unwrap();
+        sim.run(3).unwrap();
+        sim.destroy_body(bodies[1]);
+        sim.set_timestep(0.005);
+        sim.run(10).unwrap();
+        let mut gravity = GravityPlugin::new(0.5);
+        gravity.set_softening(0.2);
+        gravity.set_softening_kernel(SofteningKernel::Spline);
+        gravity.set_axis_mask([false, false, true]);
+        sim.set_gravity(Some(gravity));
+        sim.run(10).unwrap();
+        sim.take_input_log().unwrap().serialize()
+    }
+
+    #[test]
+    fn test_replay_reproduces_run_bitwise() {
+        let mut original = initial_state();
+        let text = scripted_run(&mut original);
+
+        let log = InputLog::parse(&text).unwrap();
+        assert_eq!(log.records().len(), 6);
+        assert_eq!((log.start_step(), log.end_step()), (0, 35));
+        assert_eq!(log.serialize(), text);
+
+        let mut replayed = initial_state();
+        log.replay(&mut replayed).unwrap();
+
+        assert_eq!(replayed.step_count(), original.step_count());
+        assert!(replayed.entities().eq(original.entities()));
+        for entity in original.entities() {
+            let (a, b) = (original.position(entity).unwrap(), replayed.position(entity).unwrap());
+            assert_eq!(a.as_array().map(Real::to_bits), b.as_array().map(Real::to_bits));
+        }
+        assert_eq!(replayed.state_fingerprint(), original.state_fingerprint());
+        assert!(replayed.masses().get(Entity::new(1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_replay_rejects_mismatched_snapshot() {
+        let text = scripted_run(&mut initial_state());
+
+        let mut different = initial_state();
+        let body = different.entities().next().unwrap();
+        different.apply_impulse(body, [1e-9, 0.0, 0.0]).unwrap();
+
+        let err = InputLog::parse(&text).unwrap().replay(&mut different).unwrap_err();
+        assert!(err.contains("Initial snapshot mismatch"), "{}", err);
+        assert_eq!(different.step_count(), 0);
+    }
+
+    #[test]
+    fn test_gravity_settings_round_trip() {
+        let mut plugin = GravityPlugin::new(2.5);
+        plugin.set_softening(0.05);
+        plugin.set_far_field_approximation(40.0);
+        plugin.set_use_per_body_softening(true);
+        plugin.set_axis_mask([true, false, true]);
+        plugin.set_compensated_summation(true);
+        plugin.set_sleeping_bodies_attract(false);
+        let settings = GravitySettings::from(&plugin);
+        assert_eq!(GravitySettings::from(&settings.to_plugin().unwrap()), settings);
+
+        for gravity in [Some(settings), None] {
+            let mut log = InputLog::new(0, 0);
+            log.push(InputRecord { step: 3, event: InputEvent::SetGravity { gravity } });
+            let parsed = InputLog::parse(&log.serialize()).unwrap();
+            assert_eq!(parsed.records(), log.records());
+        }
+
+        let invalid = GravitySettings { g_constant: -1.0, ..settings };
+        assert!(invalid.to_plugin().err().unwrap().contains("gravitational constant"));
+    }
+
+    #[test]
+    fn test_replay_rejects_different_gravity() {
+        let text = scripted_run(&mut initial_state());
+
+        // Same bodies, different softening
+        let mut different = initial_state();
+        let mut gravity = GravityPlugin::new(1.0);
+        gravity.set_softening(0.2);
+        different.set_gravity(Some(gravity));
+
+        let err = InputLog::parse(&text).unwrap().replay(&mut different).unwrap_err();
+        assert!(err.contains("Initial snapshot mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        assert!(InputLog::parse("").is_err());
+        assert!(InputLog::parse("hello").unwrap_err().contains("Not an input log"));
+
+        let other = if PRECISION == "f64" { "f32" } else { "f64" };
+        let err = InputLog::parse(&format!("{} {}\nstart 0 0\nend 0\n", HEADER, other)).unwrap_err();
+        assert!(err.contains("precision"), "{}", err);
+
+        let text = format!("{} {}\nstart 0 0\nend 3\n2 explode 1 0\n", HEADER, PRECISION);
+        assert!(InputLog::parse(&text).unwrap_err().contains("Line 4: unknown event 'explode'"));
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Spatial queries over entity positions
+//!
+//! Neighborhood lookups for analysis and local force models, such as
+//! smoothed-particle kernels that only interact with bodies inside a cutoff
+//! radius. Structures are built from a Position storage snapshot and must be
+//! rebuilt after the positions change.
+
+mod neighbors;
+
+pub use neighbors::NeighborSearch;
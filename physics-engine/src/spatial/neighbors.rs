@@ -0,0 +1,520 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Radius and k-nearest-neighbor search
+//!
+//! `NeighborSearch` copies positions into a kd-tree stored implicitly in a
+//! single array: each subtree occupies a contiguous range whose median,
+//! along the axis of widest spread, sits at the middle index. Building costs
+//! O(N log N) and a query visits roughly O(log N) nodes plus the points it
+//! returns.
+//!
+//! # Storages
+//!
+//! Storages with dense field arrays, such as `PositionSoAStorage`, are read
+//! through `field_arrays()` and `dense_order()`; others through `get()`.
+//! Entities without a position, or with a non-finite one, are left out.
+//!
+//! # Rebuilding
+//!
+//! The tree is a snapshot. After positions change, call `rebuild()`, which
+//! keeps the buffers of the previous build so steady-state rebuilds don't
+//! allocate. With the `parallel` feature, large trees are built on the Rayon
+//! pool; the result is identical to a sequential build.
+
+use crate::real::Real;
+use crate::ecs::{ComponentStorage, Entity};
+use crate::ecs::components::Position;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Subtrees with at most this many points are scanned linearly
+const LEAF_SIZE: usize = 8;
+
+/// Subtrees with at least this many points build their halves in parallel
+#[cfg(feature = "parallel")]
+const PARALLEL_BUILD_THRESHOLD: usize = 16384;
+
+/// Entity and position copied out of component storage
+#[derive(Debug, Clone, Copy)]
+struct Point {
+    entity: Entity,
+    position: [Real; 3],
+}
+
+/// k-nearest candidate, ordered by squared distance then tree index
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance_sq: Real,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_sq.total_cmp(&other.distance_sq).then(self.index.cmp(&other.index))
+    }
+}
+
+/// kd-tree over entity positions for neighborhood queries
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+/// use physics_engine::ecs::components::Position;
+/// use physics_engine::spatial::NeighborSearch;
+/// use physics_engine::Real;
+///
+/// let mut world = World::new();
+/// let mut positions = HashMapStorage::<Position>::new();
+/// let entities: Vec<_> = (0..10)
+///     .map(|i| {
+///         let entity = world.create_entity();
+///         positions.insert(entity, Position::new(i as Real, 0.0, 0.0));
+///         entity
+///     })
+///     .collect();
+///
+/// let search = NeighborSearch::build(entities.iter(), &positions);
+/// let query = Position::new(4.2, 0.0, 0.0);
+/// assert_eq!(search.within_radius(&query, 1.0).len(), 2);
+///
+/// let nearest = search.k_nearest(&query, 2);
+/// assert_eq!(nearest[0].0, entities[4]);
+/// assert_eq!(nearest[1].0, entities[5]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NeighborSearch {
+    points: Vec<Point>,
+    /// Split axis of the subtree whose median sits at each index
+    axes: Vec<u8>,
+    /// Scratch lookup for dense storages whose order differs from the entity list
+    dense_index: HashMap<Entity, usize>,
+}
+
+impl NeighborSearch {
+    /// Create an empty search structure
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a search structure over the positions of `entities`
+    pub fn build<'a, I>(entities: I, positions: &impl ComponentStorage<Component = Position>) -> Self
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        let mut search = Self::new();
+        search.rebuild(entities, positions);
+        search
+    }
+
+    /// Replace the indexed points with the current positions of `entities`
+    ///
+    /// Buffers from the previous build are cleared and reused, so rebuilding
+    /// with the same number of points or fewer doesn't allocate. Use
+    /// `shrink_to_fit()` to release them after the set shrinks for good.
+    pub fn rebuild<'a, I>(&mut self, entities: I, positions: &impl ComponentStorage<Component = Position>)
+    where
+        I: Iterator<Item = &'a Entity>,
+    {
+        self.points.clear();
+        match (positions.dense_order(), positions.field_arrays()) {
+            (Some(order), Some(arrays)) => {
+                let (x, y, z) = arrays.as_position_arrays();
+                self.dense_index.clear();
+                self.dense_index.extend(order.iter().enumerate().map(|(i, &entity)| (entity, i)));
+                for &entity in entities {
+                    if let Some(&i) = self.dense_index.get(&entity) {
+                        self.points.push(Point { entity, position: [x[i], y[i], z[i]] });
+                    }
+                }
+            }
+            _ => {
+                for &entity in entities {
+                    if let Some(position) = positions.get(entity) {
+                        self.points.push(Point { entity, position: position.as_array() });
+                    }
+                }
+            }
+        }
+        self.points.retain(|point| point.position.iter().all(|value| value.is_finite()));
+
+        self.axes.clear();
+        self.axes.resize(self.points.len(), 0);
+        build_subtree(&mut self.points, &mut self.axes);
+    }
+
+    /// Release buffer capacity beyond the current number of points
+    pub fn shrink_to_fit(&mut self) {
+        self.points.shrink_to_fit();
+        self.axes.shrink_to_fit();
+        self.dense_index = HashMap::new();
+    }
+
+    /// Number of indexed points
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Check whether no points are indexed
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Number of points the buffers can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.points.capacity().min(self.axes.capacity())
+    }
+
+    /// Find every entity within `radius` of `point`, boundary included
+    ///
+    /// The order of the result is unspecified.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or NaN.
+    pub fn within_radius(&self, point: &Position, radius: Real) -> Vec<Entity> {
+        let mut found = Vec::new();
+        self.within_radius_into(point, radius, &mut found);
+        found
+    }
+
+    /// Like `within_radius()`, but appends to `out` to reuse its allocation
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` is negative or NaN.
+    pub fn within_radius_into(&self, point: &Position, radius: Real, out: &mut Vec<Entity>) {
+        assert!(radius >= 0.0, "Radius must be non-negative");
+        if !self.points.is_empty() {
+            self.collect_within(0, self.points.len(), &point.as_array(), radius * radius, out);
+        }
+    }
+
+    /// Find the `k` entities nearest to `point`, closest first
+    ///
+    /// Returns each entity with its distance from `point`. Fewer than `k`
+    /// are returned when fewer points are indexed. Entities at equal
+    /// distances are ordered consistently between calls on the same tree.
+    pub fn k_nearest(&self, point: &Position, k: usize) -> Vec<(Entity, Real)> {
+        if k == 0 || self.points.is_empty() {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(k.min(self.points.len()) + 1);
+        self.collect_nearest(0, self.points.len(), &point.as_array(), k, &mut heap);
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|candidate| (self.points[candidate.index].entity, candidate.distance_sq.sqrt()))
+            .collect()
+    }
+
+    fn collect_within(&self, lo: usize, hi: usize, query: &[Real; 3], radius_sq: Real, out: &mut Vec<Entity>) {
+        if hi - lo <= LEAF_SIZE {
+            out.extend(
+                self.points[lo..hi]
+                    .iter()
+                    .filter(|point| distance_sq(query, &point.position) <= radius_sq)
+                    .map(|point| point.entity),
+            );
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let median = &self.points[mid];
+        if distance_sq(query, &median.position) <= radius_sq {
+            out.push(median.entity);
+        }
+
+        let diff = query[self.axes[mid] as usize] - median.position[self.axes[mid] as usize];
+        let (near, far) = if diff <= 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.collect_within(near.0, near.1, query, radius_sq, out);
+        if diff * diff <= radius_sq {
+            self.collect_within(far.0, far.1, query, radius_sq, out);
+        }
+    }
+
+    fn collect_nearest(&self, lo: usize, hi: usize, query: &[Real; 3], k: usize, heap: &mut BinaryHeap<Candidate>) {
+        let offer = |index: usize, heap: &mut BinaryHeap<Candidate>| {
+            let candidate = Candidate { distance_sq: distance_sq(query, &self.points[index].position), index };
+            if heap.len() < k {
+                heap.push(candidate);
+            } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+                heap.pop();
+                heap.push(candidate);
+            }
+        };
+
+        if hi - lo <= LEAF_SIZE {
+            for index in lo..hi {
+                offer(index, heap);
+            }
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        offer(mid, heap);
+
+        let axis = self.axes[mid] as usize;
+        let diff = query[axis] - self.points[mid].position[axis];
+        let (near, far) = if diff <= 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.collect_nearest(near.0, near.1, query, k, heap);
+        if heap.len() < k || heap.peek().is_some_and(|worst| diff * diff <= worst.distance_sq) {
+            self.collect_nearest(far.0, far.1, query, k, heap);
+        }
+    }
+}
+
+fn distance_sq(a: &[Real; 3], b: &[Real; 3]) -> Real {
+    let (dx, dy, dz) = (a[0] - b[0], a[1] - b[1], a[2] - b[2]);
+    dx * dx + dy * dy + dz * dz
+}
+
+/// Order `points` into an implicit kd-tree, recording split axes in `axes`
+fn build_subtree(points: &mut [Point], axes: &mut [u8]) {
+    if points.len() <= LEAF_SIZE {
+        return;
+    }
+
+    let mut min = [Real::INFINITY; 3];
+    let mut max = [Real::NEG_INFINITY; 3];
+    for point in points.iter() {
+        for i in 0..3 {
+            min[i] = min[i].min(point.position[i]);
+            max[i] = max[i].max(point.position[i]);
+        }
+    }
+    let axis = (0..3).fold(0, |best, i| if max[i] - min[i] > max[best] - min[best] { i } else { best });
+
+    let mid = points.len() / 2;
+    points.select_nth_unstable_by(mid, |a, b| a.position[axis].total_cmp(&b.position[axis]));
+    axes[mid] = axis as u8;
+
+    let (left_points, rest) = points.split_at_mut(mid);
+    let (left_axes, rest_axes) = axes.split_at_mut(mid);
+    let (right_points, right_axes) = (&mut rest[1..], &mut rest_axes[1..]);
+
+    #[cfg(feature = "parallel")]
+    if left_points.len() >= PARALLEL_BUILD_THRESHOLD {
+        rayon::join(
+            || build_subtree(left_points, left_axes),
+            || build_subtree(right_points, right_axes),
+        );
+        return;
+    }
+
+    build_subtree(left_points, left_axes);
+    build_subtree(right_points, right_axes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{HashMapStorage, PositionSoAStorage, World};
+    use crate::scenarios::DeterministicRng;
+    use std::collections::HashSet;
+
+    /// `count` random entities in a cube of half-width 50, positions
+    /// rounded to `grid` so that coarse grids produce duplicates
+    fn random_points(count: usize, seed: u64, grid: Real) -> (Vec<Entity>, HashMapStorage<Position>) {
+        let mut world = World::new();
+        let mut rng = DeterministicRng::new(seed);
+        let mut positions = HashMapStorage::new();
+        let entities = (0..count)
+            .map(|_| {
+                let entity = world.create_entity();
+                let [x, y, z] = [0; 3].map(|_| (rng.next_f64_range(-50.0, 50.0) / grid).round() * grid);
+                positions.insert(entity, Position::new(x, y, z));
+                entity
+            })
+            .collect();
+        (entities, positions)
+    }
+
+    /// Query points inside and well outside the data bounds
+    fn query_points(seed: u64) -> Vec<Position> {
+        let mut rng = DeterministicRng::new(seed);
+        (0..40)
+            .map(|_| {
+                let [x, y, z] = [0; 3].map(|_| rng.next_f64_range(-120.0, 120.0));
+                Position::new(x, y, z)
+            })
+            .collect()
+    }
+
+    fn brute_force_distances(
+        entities: &[Entity],
+        positions: &HashMapStorage<Position>,
+        query: &Position,
+    ) -> Vec<(Entity, Real)> {
+        let mut distances: Vec<(Entity, Real)> = entities
+            .iter()
+            .map(|&entity| {
+                (entity, distance_sq(&query.as_array(), &positions.get(entity).unwrap().as_array()).sqrt())
+            })
+            .collect();
+        distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+        distances
+    }
+
+    fn assert_matches_brute_force(entities: &[Entity], positions: &HashMapStorage<Position>, search: &NeighborSearch) {
+        for query in query_points(99) {
+            let exact = brute_force_distances(entities, positions, &query);
+
+            for radius in [0.0, 5.0, 20.0, 80.0] {
+                let found: HashSet<Entity> = search.within_radius(&query, radius).into_iter().collect();
+                let expected: HashSet<Entity> =
+                    exact.iter().filter(|(_, d)| *d <= radius).map(|(e, _)| *e).collect();
+                assert_eq!(found, expected, "radius {} around {:?}", radius, query.as_array());
+            }
+
+            for k in [1, 7, 50] {
+                let nearest = search.k_nearest(&query, k);
+                let expected: Vec<Real> = exact.iter().take(k).map(|(_, d)| *d).collect();
+                assert_eq!(nearest.iter().map(|(_, d)| *d).collect::<Vec<_>>(), expected);
+                let true_distance: HashMap<Entity, Real> = exact.iter().copied().collect();
+                for (entity, distance) in &nearest {
+                    assert_eq!(true_distance[entity], *distance);
+                }
+                let unique: HashSet<Entity> = nearest.iter().map(|(e, _)| *e).collect();
+                assert_eq!(unique.len(), nearest.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_queries_match_brute_force() {
+        let (entities, positions) = random_points(2000, 7, 1e-9);
+        let search = NeighborSearch::build(entities.iter(), &positions);
+        assert_eq!(search.len(), 2000);
+        assert_matches_brute_force(&entities, &positions, &search);
+    }
+
+    #[test]
+    fn test_queries_with_duplicate_positions_match_brute_force() {
+        // A grid of 10 leaves at most 11³ distinct positions for 3000 points
+        let (entities, positions) = random_points(3000, 11, 10.0);
+        let distinct: HashSet<_> = entities.iter().map(|e| positions.get(*e).unwrap().as_array().map(Real::to_bits)).collect();
+        assert!(distinct.len() < entities.len() / 2);
+
+        let search = NeighborSearch::build(entities.iter(), &positions);
+        assert_matches_brute_force(&entities, &positions, &search);
+
+        // Every copy of a position sits at distance zero
+        let (first, position) = (entities[0], *positions.get(entities[0]).unwrap());
+        let copies = brute_force_distances(&entities, &positions, &position).iter().filter(|(_, d)| *d == 0.0).count();
+        let at_zero = search.within_radius(&position, 0.0);
+        assert_eq!(at_zero.len(), copies);
+        assert!(at_zero.contains(&first));
+    }
+
+    #[test]
+    fn test_large_tree_matches_brute_force() {
+        // Large enough for the parallel build to split work
+        let (entities, positions) = random_points(40_000, 3, 1e-9);
+        let search = NeighborSearch::build(entities.iter(), &positions);
+        for query in query_points(5).iter().take(5) {
+            let exact = brute_force_distances(&entities, &positions, query);
+            let nearest = search.k_nearest(query, 20);
+            assert_eq!(
+                nearest.iter().map(|(_, d)| *d).collect::<Vec<_>>(),
+                exact.iter().take(20).map(|(_, d)| *d).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_soa_storage_matches_hashmap_storage() {
+        let (entities, positions) = random_points(500, 21, 1e-9);
+        let mut soa = PositionSoAStorage::new();
+        // Insert in reverse so the dense order differs from the entity list
+        for &entity in entities.iter().rev() {
+            soa.insert(entity, *positions.get(entity).unwrap());
+        }
+        let subset = &entities[..400];
+        let from_soa = NeighborSearch::build(subset.iter(), &soa);
+        let from_map = NeighborSearch::build(subset.iter(), &positions);
+        assert_eq!(from_soa.len(), 400);
+
+        for query in query_points(8) {
+            let a: HashSet<Entity> = from_soa.within_radius(&query, 30.0).into_iter().collect();
+            let b: HashSet<Entity> = from_map.within_radius(&query, 30.0).into_iter().collect();
+            assert_eq!(a, b);
+            assert_eq!(from_soa.k_nearest(&query, 10), from_map.k_nearest(&query, 10));
+        }
+    }
+
+    #[test]
+    fn test_rebuild_reuses_buffers() {
+        let (entities, mut positions) = random_points(1000, 4, 1e-9);
+        let mut search = NeighborSearch::build(entities.iter(), &positions);
+        let capacity = search.capacity();
+
+        for &entity in &entities {
+            positions.get_mut(entity).unwrap().set_x(0.0);
+        }
+        search.rebuild(entities[..600].iter(), &positions);
+        assert_eq!(search.len(), 600);
+        assert_eq!(search.capacity(), capacity);
+        assert!(search.k_nearest(&Position::new(0.0, 0.0, 0.0), 600).iter().all(|(e, _)| entities[..600].contains(e)));
+
+        search.shrink_to_fit();
+        assert_eq!(search.capacity(), 600);
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        let (entities, mut positions) = random_points(20, 1, 1e-9);
+        let empty = NeighborSearch::new();
+        assert!(empty.is_empty());
+        assert!(empty.within_radius(&Position::zero(), 1.0).is_empty());
+        assert!(empty.k_nearest(&Position::zero(), 3).is_empty());
+
+        positions.remove(entities[1]);
+        let search = NeighborSearch::build(entities.iter(), &positions);
+        assert_eq!(search.len(), 19);
+        assert!(search.k_nearest(&Position::zero(), 0).is_empty());
+        assert_eq!(search.k_nearest(&Position::zero(), 100).len(), 19);
+        assert_eq!(search.within_radius(&Position::zero(), Real::INFINITY).len(), 19);
+    }
+
+    #[test]
+    #[cfg(any(not(feature = "strict-validation"), not(debug_assertions)))]
+    fn test_non_finite_positions_are_skipped() {
+        let (entities, mut positions) = random_points(20, 1, 1e-9);
+        positions.insert(entities[0], Position::new(Real::NAN, 0.0, 0.0));
+        let search = NeighborSearch::build(entities.iter(), &positions);
+        assert_eq!(search.len(), 19);
+        assert_eq!(search.k_nearest(&Position::zero(), 100).len(), 19);
+        assert_eq!(search.within_radius(&Position::zero(), Real::INFINITY).len(), 19);
+    }
+
+    #[test]
+    #[should_panic(expected = "Radius must be non-negative")]
+    fn test_negative_radius_panics() {
+        NeighborSearch::new().within_radius(&Position::zero(), -1.0);
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Sharing simulation state with other threads
+//!
+//! `SharedWorld` lets a renderer or monitor read positions while the
+//! simulation keeps stepping. The simulation owns the position storage
+//! behind an `RwLock` and calls `publish()` after each step; readers call
+//! `latest_positions()` and get an immutable snapshot of the most recent
+//! published frame.
+//!
+//! Snapshots are double-buffered: `publish()` fills a spare buffer and then
+//! swaps it in with a single pointer exchange, so a reader either sees the
+//! whole previous frame or the whole new one, never a mix. Readers only hold
+//! a lock long enough to clone an `Arc`, so they never stall the simulation.
+//!
+//! # Example
+//!
+//! ```
+//! use physics_engine::ecs::{ComponentStorage, HashMapStorage, World};
+//! use physics_engine::ecs::components::Position;
+//! use physics_engine::sync::SharedWorld;
+//! use std::sync::Arc;
+//!
+//! let entity = World::new().create_entity();
+//! let mut positions = HashMapStorage::<Position>::new();
+//! positions.insert(entity, Position::new(1.0, 2.0, 3.0));
+//!
+//! let shared = Arc::new(SharedWorld::new(positions));
+//! shared.publish(&[entity]);
+//!
+//! let reader = Arc::clone(&shared);
+//! let frame = std::thread::spawn(move || reader.latest_positions()).join().unwrap();
+//! assert_eq!(frame[0], (entity, [1.0, 2.0, 3.0]));
+//! ```
+
+use crate::ecs::{ComponentStorage, Entity, HashMapStorage};
+use crate::ecs::components::Position;
+use crate::real::Real;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// One published frame of entity positions
+pub type PositionFrame = Vec<(Entity, [Real; 3])>;
+
+/// Position storage shared between a simulation thread and readers
+///
+/// The storage must support `get()`, so use `HashMapStorage` or `SoAStorage`;
+/// true SoA storages such as `PositionSoAStorage` cannot be snapshotted.
+pub struct SharedWorld<S = HashMapStorage<Position>>
+where
+    S: ComponentStorage<Component = Position>,
+{
+    positions: RwLock<S>,
+    latest: RwLock<Arc<PositionFrame>>,
+    /// Previously published frame, reused once readers release it
+    spare: Mutex<Arc<PositionFrame>>,
+    frame_count: Mutex<u64>,
+}
+
+impl<S> SharedWorld<S>
+where
+    S: ComponentStorage<Component = Position>,
+{
+    /// Wrap a position storage for sharing
+    ///
+    /// Nothing is visible to readers until the first `publish()`.
+    pub fn new(positions: S) -> Self {
+        SharedWorld {
+            positions: RwLock::new(positions),
+            latest: RwLock::new(Arc::new(Vec::new())),
+            spare: Mutex::new(Arc::new(Vec::new())),
+            frame_count: Mutex::new(0),
+        }
+    }
+
+    /// Lock the position storage for reading
+    pub fn positions(&self) -> RwLockReadGuard<'_, S> {
+        self.positions.read().expect("SharedWorld: position lock poisoned")
+    }
+
+    /// Lock the position storage for the simulation to update
+    ///
+    /// Readers of `latest_positions()` are unaffected; they keep seeing the
+    /// last published frame until `publish()` is called.
+    pub fn positions_mut(&self) -> RwLockWriteGuard<'_, S> {
+        self.positions.write().expect("SharedWorld: position lock poisoned")
+    }
+
+    /// Snapshot the positions of `entities` and make it the latest frame
+    ///
+    /// Entities without a position are left out. The snapshot is built in
+    /// the spare buffer, which is reused when no reader still holds it, and
+    /// then swapped in atomically.
+    ///
+    /// Returns the number of frames published so far, including this one.
+    pub fn publish<'a, I>(&self, entities: I) -> u64
+    where
+        I: IntoIterator<Item = &'a Entity>,
+    {
+        let mut frame_count = self.frame_count.lock().expect("SharedWorld: frame lock poisoned");
+        let mut spare = self.spare.lock().expect("SharedWorld: spare lock poisoned");
+
+        // Readers may still hold the spare; allocate a fresh buffer if so
+        if Arc::get_mut(&mut spare).is_none() {
+            *spare = Arc::new(Vec::new());
+        }
+        let buffer = Arc::get_mut(&mut spare).expect("SharedWorld: fresh buffer is uniquely owned");
+        buffer.clear();
+        {
+            let positions = self.positions();
+            buffer.extend(
+                entities
+                    .into_iter()
+                    .filter_map(|&entity| positions.get(entity).map(|pos| (entity, pos.as_array()))),
+            );
+        }
+
+        {
+            let mut latest = self.latest.write().expect("SharedWorld: snapshot lock poisoned");
+            std::mem::swap(&mut *latest, &mut *spare);
+        }
+
+        *frame_count += 1;
+        *frame_count
+    }
+
+    /// Get the most recently published frame
+    ///
+    /// The returned snapshot never changes; call again to see newer frames.
+    pub fn latest_positions(&self) -> Arc<PositionFrame> {
+        Arc::clone(&self.latest.read().expect("SharedWorld: snapshot lock poisoned"))
+    }
+
+    /// Get the number of frames published so far
+    pub fn frame_count(&self) -> u64 {
+        *self.frame_count.lock().expect("SharedWorld: frame lock poisoned")
+    }
+
+    /// Unwrap the position storage
+    pub fn into_inner(self) -> S {
+        self.positions.into_inner().expect("SharedWorld: position lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::SoAStorage;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_nothing_visible_before_publish() {
+        let entity = Entity::new(0, 0);
+        let mut positions = HashMapStorage::<Position>::new();
+        positions.insert(entity, Position::new(1.0, 0.0, 0.0));
+
+        let shared = SharedWorld::new(positions);
+        assert!(shared.latest_positions().is_empty());
+        assert_eq!(shared.frame_count(), 0);
+
+        assert_eq!(shared.publish(&[entity, Entity::new(9, 0)]), 1);
+        assert_eq!(*shared.latest_positions(), vec![(entity, [1.0, 0.0, 0.0])]);
+    }
+
+    #[test]
+    fn test_snapshot_unaffected_by_later_steps() {
+        let entity = Entity::new(0, 0);
+        let mut positions = SoAStorage::<Position>::new();
+        positions.insert(entity, Position::new(1.0, 0.0, 0.0));
+        let shared = SharedWorld::new(positions);
+
+        shared.publish(&[entity]);
+        let held = shared.latest_positions();
+
+        // Two more frames: the second reuses the spare unless it is held
+        for x in [2.0, 3.0] {
+            shared.positions_mut().get_mut(entity).unwrap().set_x(x);
+            shared.publish(&[entity]);
+        }
+
+        assert_eq!(held[0].1[0], 1.0);
+        assert_eq!(shared.latest_positions()[0].1[0], 3.0);
+        assert_eq!(shared.frame_count(), 3);
+    }
+
+    #[test]
+    fn test_reader_never_sees_torn_frame() {
+        const ENTITIES: u64 = 200;
+        const STEPS: usize = 500;
+
+        let entities: Vec<Entity> = (0..ENTITIES).map(|i| Entity::new(i, 0)).collect();
+        let mut positions = HashMapStorage::<Position>::new();
+        for &entity in &entities {
+            positions.insert(entity, Position::new(0.0, 0.0, 0.0));
+        }
+        let shared = Arc::new(SharedWorld::new(positions));
+        shared.publish(&entities);
+
+        let done = Arc::new(AtomicBool::new(false));
+        let reader = {
+            let shared = Arc::clone(&shared);
+            let done = Arc::clone(&done);
+            std::thread::spawn(move || {
+                let mut reads = 0;
+                let mut last_step = 0.0;
+                while !done.load(Ordering::Acquire) {
+                    let frame = shared.latest_positions();
+                    assert_eq!(frame.len(), ENTITIES as usize, "Partial frame");
+
+                    // Every body is moved to x = step each step
+                    let step = frame[0].1[0];
+                    assert!(
+                        frame.iter().all(|(_, pos)| pos[0] == step && pos[1] == -step),
+                        "Torn frame at step {}",
+                        step
+                    );
+                    assert!(step >= last_step, "Frames went backwards");
+                    last_step = step;
+                    reads += 1;
+                }
+                reads
+            })
+        };
+
+        for step in 1..=STEPS {
+            {
+                let mut positions = shared.positions_mut();
+                for &entity in &entities {
+                    let pos = positions.get_mut(entity).unwrap();
+                    pos.set_x(step as Real);
+                    pos.set_y(-(step as Real));
+                }
+            }
+            shared.publish(&entities);
+        }
+        done.store(true, Ordering::Release);
+
+        let reads = reader.join().expect("Reader observed an inconsistent frame");
+        assert!(reads > 0);
+        assert_eq!(shared.latest_positions()[0].1[0], STEPS as Real);
+        assert_eq!(shared.frame_count(), STEPS as u64 + 1);
+    }
+}
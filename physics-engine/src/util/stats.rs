@@ -0,0 +1,205 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Streaming summary statistics
+//!
+//! # References
+//!
+//! - Welford, B. P. (1962). Note on a method for calculating corrected sums
+//!   of squares and products. Technometrics, 4(3), 419-420.
+
+/// Count, mean, variance and range of a stream of samples
+///
+/// Samples are folded in one at a time with Welford's online algorithm, so
+/// the memory use is constant however long the run, and the variance does
+/// not suffer the cancellation of the naive sum-of-squares formula. Samples
+/// are `f64` rather than `Real`: they are measurements such as wall-clock
+/// step times, not simulation state. NaN samples are skipped.
+///
+/// # Example
+///
+/// ```
+/// use physics_engine::util::RunningStats;
+///
+/// let mut stats = RunningStats::new();
+/// for sample in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+///     stats.push(sample);
+/// }
+/// assert_eq!(stats.count(), 8);
+/// assert_eq!(stats.mean(), Some(5.0));
+/// assert_eq!(stats.stddev(), Some(2.0));
+/// assert_eq!((stats.min(), stats.max()), (Some(2.0), Some(9.0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    /// Sum of squared deviations from the current mean
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl RunningStats {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        RunningStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Add one sample
+    pub fn push(&mut self, sample: f64) {
+        if sample.is_nan() {
+            return;
+        }
+        self.count += 1;
+        let delta = sample - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (sample - self.mean);
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+    }
+
+    /// Number of samples added
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Arithmetic mean, or None before the first sample
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Population variance (dividing by the count), or None before the
+    /// first sample
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// Population standard deviation, or None before the first sample
+    pub fn stddev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Smallest sample, or None before the first sample
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Largest sample, or None before the first sample
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+impl Default for RunningStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, samples: I) {
+        for sample in samples {
+            self.push(sample);
+        }
+    }
+}
+
+impl FromIterator<f64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(samples: I) -> Self {
+        let mut stats = RunningStats::new();
+        stats.extend(samples);
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mean and population standard deviation computed from all samples at once
+    fn batch(samples: &[f64]) -> (f64, f64) {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        (mean, variance.sqrt())
+    }
+
+    #[test]
+    fn test_empty_stats() {
+        let stats = RunningStats::default();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.variance(), None);
+        assert_eq!(stats.stddev(), None);
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.max(), None);
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let stats: RunningStats = [3.5].into_iter().collect();
+        assert_eq!(stats.mean(), Some(3.5));
+        assert_eq!(stats.stddev(), Some(0.0));
+        assert_eq!((stats.min(), stats.max()), (Some(3.5), Some(3.5)));
+    }
+
+    #[test]
+    fn test_matches_batch_computation() {
+        let samples: Vec<f64> = (0..1000)
+            .map(|i| 1e-3 * (1.0 + (i as f64 * 0.37).sin()))
+            .collect();
+        let stats: RunningStats = samples.iter().copied().collect();
+        let (mean, stddev) = batch(&samples);
+
+        assert_eq!(stats.count(), 1000);
+        assert!(
+            (stats.mean().unwrap() - mean).abs() < 1e-15,
+            "mean {} vs {}",
+            stats.mean().unwrap(),
+            mean
+        );
+        assert!(
+            (stats.stddev().unwrap() - stddev).abs() < 1e-15,
+            "stddev {} vs {}",
+            stats.stddev().unwrap(),
+            stddev
+        );
+        assert_eq!(stats.min(), samples.iter().copied().reduce(f64::min));
+        assert_eq!(stats.max(), samples.iter().copied().reduce(f64::max));
+    }
+
+    #[test]
+    fn test_large_offset_keeps_variance() {
+        // The naive E[x²] − E[x]² loses every digit here
+        let samples = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0];
+        let stats: RunningStats = samples.into_iter().collect();
+        assert_eq!(stats.mean(), Some(1e9 + 10.0));
+        assert!((stats.variance().unwrap() - 22.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nan_samples_are_skipped() {
+        let mut stats: RunningStats = [1.0, 3.0].into_iter().collect();
+        stats.push(f64::NAN);
+        assert_eq!(stats.count(), 2);
+        assert_eq!(stats.mean(), Some(2.0));
+        assert_eq!(stats.max(), Some(3.0));
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Shared fixtures for the integration tests
+//!
+//! Each test binary that declares `mod common;` compiles its own copy, so
+//! items one binary doesn't use would otherwise warn as dead code.
+#![allow(dead_code)]
+
+use physics_engine::{Real, RealExt};
+use physics_engine::real::consts::PI;
+use physics_engine::ecs::components::{Position, Velocity};
+
+/// Closed-form Kepler ellipse in units where G = 1
+///
+/// The orbit lies in the xy plane, runs counter-clockwise, and is at
+/// periapsis on the +x axis at t = 0. `state_at()` solves Kepler's equation
+/// M = E − e·sin E by Newton iteration, independently of the library's
+/// `orbits` module, so it can check that module as well as the integrators.
+///
+/// Only unsoftened gravity has this solution; Plummer softening makes the
+/// orbit precess, so runs compared against the fixture must disable it.
+#[derive(Debug, Clone, Copy)]
+pub struct KeplerOrbit {
+    /// Gravitational parameter μ = G·M
+    pub mu: Real,
+    /// Semi-major axis a
+    pub semi_major_axis: Real,
+    /// Eccentricity e, in [0, 1)
+    pub eccentricity: Real,
+}
+
+impl KeplerOrbit {
+    /// Bound orbit around `central_mass`
+    ///
+    /// For a pair of comparable masses, pass the total mass and compare the
+    /// relative state of the pair.
+    pub fn new(central_mass: Real, semi_major_axis: Real, eccentricity: Real) -> Self {
+        assert!(central_mass > 0.0 && semi_major_axis > 0.0, "Mass and semi-major axis must be positive");
+        assert!((0.0..1.0).contains(&eccentricity), "Eccentricity must be in [0, 1)");
+        KeplerOrbit { mu: central_mass, semi_major_axis, eccentricity }
+    }
+
+    /// Mean motion n = √(μ/a³)
+    pub fn mean_motion(&self) -> Real {
+        (self.mu / self.semi_major_axis.powi(3)).sqrt()
+    }
+
+    /// Orbital period T = 2π/n
+    pub fn period(&self) -> Real {
+        2.0 * PI / self.mean_motion()
+    }
+
+    /// Specific orbital energy −μ/(2a)
+    pub fn energy(&self) -> Real {
+        -self.mu / (2.0 * self.semi_major_axis)
+    }
+
+    /// Eccentric anomaly at time `t`, solving Kepler's equation
+    pub fn eccentric_anomaly(&self, t: Real) -> Real {
+        let e = self.eccentricity;
+        let mean_anomaly = (self.mean_motion() * t).rem_euclid(2.0 * PI);
+        // Starting from π converges for every e < 1
+        let mut anomaly = if e > 0.8 { PI } else { mean_anomaly };
+        for _ in 0..50 {
+            let delta = (anomaly - e * anomaly.sin() - mean_anomaly) / (1.0 - e * anomaly.cos());
+            anomaly -= delta;
+            if delta.abs() < Real::SOLVER_TOLERANCE {
+                break;
+            }
+        }
+        anomaly
+    }
+
+    /// Position and velocity relative to the central mass at time `t`
+    pub fn state_at(&self, t: Real) -> (Position, Velocity) {
+        let (a, e) = (self.semi_major_axis, self.eccentricity);
+        let b = a * (1.0 - e * e).sqrt();
+        let anomaly = self.eccentric_anomaly(t);
+        let (sin, cos) = anomaly.sin_cos();
+        // dE/dt from differentiating Kepler's equation
+        let rate = self.mean_motion() / (1.0 - e * cos);
+        (
+            Position::new(a * (cos - e), b * sin, 0.0),
+            Velocity::new(-a * sin * rate, b * cos * rate, 0.0),
+        )
+    }
+}
+
+/// Euclidean distance between two positions
+pub fn position_error(a: &Position, b: &Position) -> Real {
+    let d = [a.x() - b.x(), a.y() - b.y(), a.z() - b.z()];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
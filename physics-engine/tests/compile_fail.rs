@@ -0,0 +1,24 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Compile-fail tests for type-level guarantees
+//!
+//! Each file in `tests/ui` must fail to compile with the error pinned in
+//! its `.stderr` file. Regenerate those with `TRYBUILD=overwrite` after an
+//! intentional change to the diagnostics.
+
+#[test]
+fn test_ui_compile_failures() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -13,14 +13,23 @@
 // limitations under the License.
 //! Integration tests verifying conservation properties for integrators
 
+// Some fixtures here are not used by any test yet
+#![allow(dead_code, clippy::useless_vec)]
+
+use physics_engine::{Real, RealExt};
 use physics_engine::ecs::components::{Position, Velocity, Mass, Acceleration};
-use physics_engine::ecs::systems::{ForceRegistry, ForceProvider, Force};
-use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage};
-use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator};
+use physics_engine::diagnostics::gravitational_potential_energy;
+use physics_engine::ecs::systems::{apply_forces_to_state, ForceRegistry, ForceProvider, Force};
+use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage, World};
+use physics_engine::integration::{calculate_total_kinetic_energy, VelocityVerletIntegrator, RK4Integrator, Integrator};
+use physics_engine::math::Tolerances;
+use physics_engine::scenarios::{self, Scenario};
+use physics_engine::units::ASTRONOMICAL_UNIT;
+use std::collections::HashMap;
 
 /// Spring force provider for harmonic oscillator
 struct SpringForceProvider {
-    spring_constant: f64,
+    spring_constant: Real,
 }
 
 impl ForceProvider for SpringForceProvider {
@@ -42,8 +51,8 @@ fn compute_energy(
     velocities: &HashMapStorage<Velocity>,
     masses: &HashMapStorage<Mass>,
     entity: Entity,
-    spring_constant: f64,
-) -> f64 {
+    spring_constant: Real,
+) -> Real {
     let pos = positions.get(entity).unwrap();
     let vel = velocities.get(entity).unwrap();
     let mass = masses.get(entity).unwrap();
@@ -61,7 +70,8 @@ fn compute_energy(
 #[test]
 fn test_verlet_energy_conservation_free_particle() {
     // Free particle should conserve kinetic energy (no forces)
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -99,7 +109,7 @@ fn test_verlet_energy_conservation_free_particle() {
     // Energy should be conserved for free particle
     let energy_error = (final_energy - initial_energy).abs() / initial_energy;
     assert!(
-        energy_error < 1e-10,
+        energy_error <= Tolerances::default().energy_drift,
         "Energy not conserved for free particle: error = {}",
         energy_error
     );
@@ -108,7 +118,8 @@ fn test_verlet_energy_conservation_free_particle() {
 #[test]
 fn test_rk4_energy_conservation_free_particle() {
     // Free particle should conserve kinetic energy (no forces)
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -146,7 +157,7 @@ fn test_rk4_energy_conservation_free_particle() {
     // Energy should be conserved for free particle
     let energy_error = (final_energy - initial_energy).abs() / initial_energy;
     assert!(
-        energy_error < 1e-10,
+        energy_error <= Tolerances::default().energy_drift,
         "Energy not conserved for free particle: error = {}",
         energy_error
     );
@@ -155,7 +166,8 @@ fn test_rk4_energy_conservation_free_particle() {
 #[test]
 fn test_verlet_position_accuracy() {
     // Test position accuracy for constant velocity motion
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -172,7 +184,7 @@ fn test_verlet_position_accuracy() {
 
     let dt = 0.01;
     let steps = 100;
-    let total_time = dt * steps as f64;
+    let total_time = dt * steps as Real;
 
     let mut integrator = VelocityVerletIntegrator::new(dt);
     let entities = vec![entity];
@@ -196,7 +208,7 @@ fn test_verlet_position_accuracy() {
     // Check accuracy
     let error = (pos.x() - expected_x).abs();
     assert!(
-        error < 1e-10,
+        error < Real::TOLERANCE,
         "Position error too large: {} (expected near 0)",
         error
     );
@@ -205,7 +217,8 @@ fn test_verlet_position_accuracy() {
 #[test]
 fn test_rk4_position_accuracy() {
     // Test position accuracy for constant velocity motion
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -222,7 +235,7 @@ fn test_rk4_position_accuracy() {
 
     let dt = 0.01;
     let steps = 100;
-    let total_time = dt * steps as f64;
+    let total_time = dt * steps as Real;
 
     let mut integrator = RK4Integrator::new(dt);
     let entities = vec![entity];
@@ -246,7 +259,7 @@ fn test_rk4_position_accuracy() {
     // Check accuracy
     let error = (pos.x() - expected_x).abs();
     assert!(
-        error < 1e-10,
+        error < Real::TOLERANCE,
         "Position error too large: {} (expected near 0)",
         error
     );
@@ -255,7 +268,8 @@ fn test_rk4_position_accuracy() {
 #[test]
 fn test_verlet_constant_acceleration() {
     // Test with constant acceleration (like gravity) using a force provider
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -293,7 +307,7 @@ fn test_verlet_constant_acceleration() {
 
     let dt = 0.01;
     let steps = 100;
-    let t = dt * steps as f64;
+    let t = dt * steps as Real;
 
     let mut integrator = VelocityVerletIntegrator::new(dt);
     let entities = vec![entity];
@@ -338,7 +352,8 @@ fn test_verlet_constant_acceleration() {
 #[test]
 fn test_rk4_constant_acceleration() {
     // Test with constant acceleration (like gravity)
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -357,7 +372,7 @@ fn test_rk4_constant_acceleration() {
 
     let dt = 0.01;
     let steps = 100;
-    let _t = dt * steps as f64;
+    let _t = dt * steps as Real;
 
     let mut integrator = RK4Integrator::new(dt);
     let entities = vec![entity];
@@ -386,27 +401,18 @@ fn test_rk4_constant_acceleration() {
 #[test]
 fn test_multiple_entities() {
     // Test that multiple entities are integrated correctly
-    let entity1 = Entity::new(1, 0);
-    let entity2 = Entity::new(2, 0);
-
-    let mut positions = HashMapStorage::<Position>::new();
-    positions.insert(entity1, Position::new(0.0, 0.0, 0.0));
-    positions.insert(entity2, Position::new(1.0, 0.0, 0.0));
-
-    let mut velocities = HashMapStorage::<Velocity>::new();
-    velocities.insert(entity1, Velocity::new(1.0, 0.0, 0.0));
-    velocities.insert(entity2, Velocity::new(2.0, 0.0, 0.0));
+    let mut world = World::new();
+    let scenario = scenarios::random_cube(&mut world, 8, 3, &scenarios::CubeRanges::default());
+    let entities = scenario.entities;
+    // Free motion: drop the scenario's gravitational accelerations
+    let (mut positions, mut velocities, _, masses) = scenario.state.into_parts();
+    let initial: Vec<Position> = entities.iter().map(|entity| *positions.get(*entity).unwrap()).collect();
 
     let accelerations = HashMapStorage::<Acceleration>::new();
-    let mut masses = HashMapStorage::<Mass>::new();
-    masses.insert(entity1, Mass::new(1.0));
-    masses.insert(entity2, Mass::new(2.0));
-
     let mut force_registry = ForceRegistry::new();
 
     let dt = 0.01;
     let mut integrator = VelocityVerletIntegrator::new(dt);
-    let entities = vec![entity1, entity2];
 
     integrator.integrate(
         entities.iter(),
@@ -418,18 +424,26 @@ fn test_multiple_entities() {
         false,
     );
 
-    let pos1 = positions.get(entity1).unwrap();
-    let pos2 = positions.get(entity2).unwrap();
-
-    // Check that both entities moved
-    assert!((pos1.x() - 0.01).abs() < 1e-10, "Entity 1 should move");
-    assert!((pos2.x() - 1.02).abs() < 1e-10, "Entity 2 should move");
+    // Check that every entity moved by its own velocity
+    for (entity, start) in entities.iter().zip(&initial) {
+        let pos = positions.get(*entity).unwrap().as_array();
+        let vel = velocities.get(*entity).unwrap().as_array();
+        for k in 0..3 {
+            let expected = start.as_array()[k] + vel[k] * dt;
+            assert!(
+                (pos[k] - expected).abs() <= 64.0 * Real::EPSILON * expected.abs().max(1.0),
+                "Entity {:?} should move along its velocity",
+                entity
+            );
+        }
+    }
 }
 
 #[test]
 fn test_verlet_long_run_free_particle() {
     // Test that free particle conserves energy over long simulation
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -473,7 +487,7 @@ fn test_verlet_long_run_free_particle() {
 
     let energy_error = (final_energy - initial_energy).abs() / initial_energy;
     assert!(
-        energy_error < 1e-10,
+        energy_error <= Tolerances::default().energy_drift,
         "Energy should be conserved for free particle over 10000 steps: error = {}",
         energy_error
     );
@@ -482,7 +496,8 @@ fn test_verlet_long_run_free_particle() {
 #[test]
 fn test_rk4_long_run_free_particle() {
     // Test that free particle conserves energy over long simulation
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -526,7 +541,7 @@ fn test_rk4_long_run_free_particle() {
 
     let energy_error = (final_energy - initial_energy).abs() / initial_energy;
     assert!(
-        energy_error < 1e-10,
+        energy_error <= Tolerances::default().energy_drift,
         "Energy should be conserved for free particle over 10000 steps: error = {}",
         energy_error
     );
@@ -535,7 +550,8 @@ fn test_rk4_long_run_free_particle() {
 #[test]
 fn test_immovable_body_stays_fixed() {
     // Test that immovable bodies don't move even with forces applied
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -595,3 +611,97 @@ fn test_immovable_body_stays_fixed() {
     assert_eq!(final_vel.dy(), initial_vel.dy(), "Immovable body velocity changed in y");
     assert_eq!(final_vel.dz(), initial_vel.dz(), "Immovable body velocity changed in z");
 }
+
+/// Total kinetic plus gravitational potential energy of a scenario
+fn scenario_energy(scenario: &Scenario) -> Real {
+    let state = &scenario.state;
+    calculate_total_kinetic_energy(scenario.entities.iter(), state.velocities(), state.masses())
+        + gravitational_potential_energy(&scenario.entities, state.positions(), state.masses(), scenario.g, scenario.softening)
+}
+
+/// Euclidean distance between two positions
+fn distance(a: &Position, b: &Position) -> Real {
+    a.as_array().iter().zip(b.as_array()).map(|(x, y)| (x - y) * (x - y)).sum::<Real>().sqrt()
+}
+
+/// Take one Velocity Verlet step with gravity refreshed at the new positions
+fn verlet_gravity_step(scenario: &mut Scenario, integrator: &mut VelocityVerletIntegrator, registry: &mut ForceRegistry) {
+    let gravity = scenario.gravity_system();
+    let mut forces = HashMap::new();
+    let (positions, velocities, accelerations, masses) = scenario.state.split_mut();
+    integrator.integrate_with(
+        scenario.entities.iter(),
+        positions,
+        velocities,
+        accelerations,
+        masses,
+        registry,
+        false,
+        |entities, positions, registry| {
+            registry.clear();
+            gravity.compute_forces_into(entities, positions, masses, &mut forces);
+            registry.register_forces(&forces);
+        },
+    );
+    apply_forces_to_state(scenario.entities.iter(), registry, &mut scenario.state, false);
+}
+
+#[test]
+#[cfg_attr(feature = "single-precision", ignore = "SI force products overflow f32")]
+fn test_verlet_circular_two_body_conserves_energy() {
+    let mut world = World::new();
+    let mut scenario = scenarios::circular_two_body(&mut world, 6.0e24, 7.3e22, 3.8e8);
+    let mut integrator = VelocityVerletIntegrator::new(scenario.suggested_timestep);
+    let mut registry = scenario.force_registry();
+    let initial = scenario_energy(&scenario);
+
+    for _ in 0..scenarios::STEPS_PER_PERIOD as usize {
+        verlet_gravity_step(&mut scenario, &mut integrator, &mut registry);
+    }
+
+    let drift = ((scenario_energy(&scenario) - initial) / initial).abs();
+    println!("Circular two-body energy drift over one period: {:.3e}", drift);
+    assert!(drift < 1e-6, "Energy drifted by {:.3e}", drift);
+}
+
+/// The figure-eight must stay bounded and come back to its starting
+/// configuration after each period at the suggested timestep
+#[test]
+#[cfg_attr(feature = "single-precision", ignore = "SI force products overflow f32")]
+fn test_figure_eight_stays_bounded_and_periodic() {
+    let mut world = World::new();
+    let mut scenario = scenarios::figure_eight_three_body(&mut world);
+    let mut integrator = VelocityVerletIntegrator::new(scenario.suggested_timestep);
+    let mut registry = scenario.force_registry();
+    let initial: Vec<Position> = scenario.entities.iter().map(|&e| *scenario.state.positions().get(e).unwrap()).collect();
+    let initial_energy = scenario_energy(&scenario);
+    let steps_per_period = scenarios::STEPS_PER_PERIOD as usize;
+
+    for period in 1..=3 {
+        let mut max_radius: Real = 0.0;
+        for _ in 0..steps_per_period {
+            verlet_gravity_step(&mut scenario, &mut integrator, &mut registry);
+            for &entity in &scenario.entities {
+                max_radius = max_radius.max(distance(scenario.state.positions().get(entity).unwrap(), &Position::zero()));
+            }
+        }
+        assert!(max_radius < 1.5 * ASTRONOMICAL_UNIT, "Period {}: a body reached {:.3e} m", period, max_radius);
+
+        let return_error = scenario
+            .entities
+            .iter()
+            .zip(&initial)
+            .map(|(&e, start)| distance(scenario.state.positions().get(e).unwrap(), start))
+            .fold(0.0, Real::max);
+        println!("Figure-eight return error after period {}: {:.3e} AU", period, return_error / ASTRONOMICAL_UNIT);
+        assert!(
+            return_error < 5e-3 * ASTRONOMICAL_UNIT,
+            "Period {}: bodies ended {:.3e} AU from their start",
+            period,
+            return_error / ASTRONOMICAL_UNIT
+        );
+    }
+
+    let drift = ((scenario_energy(&scenario) - initial_energy) / initial_energy).abs();
+    assert!(drift < 1e-4, "Energy drifted by {:.3e}", drift);
+}
@@ -15,10 +15,15 @@
 //!
 //! Tests boundary conditions, extreme values, and unusual scenarios
 
+#![allow(clippy::useless_vec)]
+
+use physics_engine::{Real, RealExt};
 use physics_engine::ecs::components::{Position, Velocity, Mass, Acceleration};
-use physics_engine::ecs::systems::ForceRegistry;
-use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage};
-use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, Integrator};
+use physics_engine::ecs::systems::{apply_forces_to_acceleration, Force, ForceProvider, ForceRegistry};
+use physics_engine::ecs::{Entity, HashMapStorage, ComponentStorage, MassSoAStorage, PositionSoAStorage, World};
+use physics_engine::integration::{VelocityVerletIntegrator, RK4Integrator, YoshidaIntegrator, Integrator};
+use physics_engine::plugins::gravity::{GravityOptions, GravityPlugin, GravitySystem};
+use std::collections::HashMap;
 
 #[test]
 #[should_panic(expected = "Timestep must be positive and finite")]
@@ -47,25 +52,25 @@ fn test_rk4_negative_timestep() {
 #[test]
 #[should_panic(expected = "Timestep must be positive and finite")]
 fn test_verlet_nan_timestep() {
-    VelocityVerletIntegrator::new(f64::NAN);
+    VelocityVerletIntegrator::new(Real::NAN);
 }
 
 #[test]
 #[should_panic(expected = "Timestep must be positive and finite")]
 fn test_rk4_nan_timestep() {
-    RK4Integrator::new(f64::NAN);
+    RK4Integrator::new(Real::NAN);
 }
 
 #[test]
 #[should_panic(expected = "Timestep must be positive and finite")]
 fn test_verlet_infinite_timestep() {
-    VelocityVerletIntegrator::new(f64::INFINITY);
+    VelocityVerletIntegrator::new(Real::INFINITY);
 }
 
 #[test]
 #[should_panic(expected = "Timestep must be positive and finite")]
 fn test_rk4_infinite_timestep() {
-    RK4Integrator::new(f64::INFINITY);
+    RK4Integrator::new(Real::INFINITY);
 }
 
 #[test]
@@ -117,8 +122,9 @@ fn test_rk4_reasonable_timestep_validation() {
 #[test]
 fn test_near_immovable_mass_consistency() {
     // Test that masses just above and below the immovable threshold behave consistently
-    let entity_below = Entity::new(1, 0);
-    let entity_above = Entity::new(2, 0);
+    let mut world = World::new();
+    let entity_below = world.create_entity();
+    let entity_above = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity_below, Position::new(0.0, 0.0, 0.0));
@@ -186,7 +192,8 @@ fn test_empty_entity_list() {
 #[test]
 fn test_missing_components() {
     // Test that entities missing required components are skipped
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     let mut velocities = HashMapStorage::<Velocity>::new();
@@ -216,7 +223,8 @@ fn test_missing_components() {
 #[test]
 fn test_rk4_buffer_reuse_thread_safety() {
     // Test that RK4 buffers are properly reused without conflicts
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -254,7 +262,8 @@ fn test_rk4_buffer_reuse_thread_safety() {
 #[test]
 fn test_entity_without_mass_treated_as_immovable() {
     // Test that entities without mass components are treated as immovable
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -299,7 +308,8 @@ fn test_entity_without_mass_treated_as_immovable() {
 #[test]
 fn test_extreme_velocity() {
     // Test that integrators handle very large velocities without overflow
-    let entity = Entity::new(1, 0);
+    let mut world = World::new();
+    let entity = world.create_entity();
 
     let mut positions = HashMapStorage::<Position>::new();
     positions.insert(entity, Position::new(0.0, 0.0, 0.0));
@@ -329,3 +339,280 @@ fn test_extreme_velocity() {
     assert!(pos.is_valid(), "Position should remain valid with extreme velocity");
     assert!(pos.x().is_finite(), "Position should not overflow");
 }
+
+/// Integrate one step of `entities` with `integrator` on fresh storages
+/// holding a single free body, with a force preset for `bystander`,
+/// returning the update count and registry
+fn integrate_once<T: Integrator>(
+    integrator: &mut T,
+    entities: &[Entity],
+    body: Entity,
+    bystander: Entity,
+) -> (usize, ForceRegistry, HashMapStorage<Position>) {
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut velocities = HashMapStorage::<Velocity>::new();
+    let accelerations = HashMapStorage::<Acceleration>::new();
+    let mut masses = HashMapStorage::<Mass>::new();
+    positions.insert(body, Position::new(0.0, 0.0, 0.0));
+    velocities.insert(body, Velocity::new(1.0, 0.0, 0.0));
+    masses.insert(body, Mass::new(1.0));
+
+    // A force already accumulated for an unrelated entity
+    let mut force_registry = ForceRegistry::new();
+    force_registry.set_warn_on_missing_components(false);
+    let mut preset = HashMap::new();
+    preset.insert(bystander, Force::new(1.0, 0.0, 0.0));
+    force_registry.register_forces(&preset);
+
+    let count = integrator.integrate(
+        entities.iter(),
+        &mut positions,
+        &mut velocities,
+        &accelerations,
+        &masses,
+        &mut force_registry,
+        false,
+    );
+    (count, force_registry, positions)
+}
+
+#[test]
+fn test_integrators_skip_empty_entity_list() {
+    let mut world = World::new();
+    let body = world.create_entity();
+    let bystander = world.create_entity();
+
+    let (count, registry, _) = integrate_once(&mut VelocityVerletIntegrator::new(0.01), &[], body, bystander);
+    assert_eq!(count, 0);
+    assert!(registry.get_force(bystander).is_some(), "Verlet cleared the registry");
+
+    let (count, registry, _) = integrate_once(&mut YoshidaIntegrator::new(0.01), &[], body, bystander);
+    assert_eq!(count, 0);
+    assert!(registry.get_force(bystander).is_some(), "Yoshida cleared the registry");
+
+    let mut rk4 = RK4Integrator::new(0.01);
+    let (count, registry, _) = integrate_once(&mut rk4, &[], body, bystander);
+    assert_eq!(count, 0);
+    assert!(registry.get_force(bystander).is_some(), "RK4 cleared the registry");
+    let (position_stats, velocity_stats, _) = rk4.pool_stats();
+    assert_eq!(position_stats.hits + position_stats.misses, 0, "RK4 acquired position buffers");
+    assert_eq!(velocity_stats.hits + velocity_stats.misses, 0, "RK4 acquired velocity buffers");
+}
+
+#[test]
+fn test_integrators_single_free_body() {
+    let mut world = World::new();
+    let body = world.create_entity();
+    let bystander = world.create_entity();
+    let dt = 0.01;
+
+    let (count, _, positions) = integrate_once(&mut VelocityVerletIntegrator::new(dt), &[body], body, bystander);
+    assert_eq!(count, 1);
+    assert!((positions.get(body).unwrap().x() - dt).abs() < Real::EPSILON * 10.0);
+
+    let (count, _, positions) = integrate_once(&mut RK4Integrator::new(dt), &[body], body, bystander);
+    assert_eq!(count, 1);
+    assert!((positions.get(body).unwrap().x() - dt).abs() < Real::EPSILON * 10.0);
+
+    let (count, _, positions) = integrate_once(&mut YoshidaIntegrator::new(dt), &[body], body, bystander);
+    assert_eq!(count, 1);
+    assert!((positions.get(body).unwrap().x() - dt).abs() < Real::EPSILON * 10.0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_gravity_with_zero_and_one_entity() {
+    let mut world = World::new();
+    let body = world.create_entity();
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut masses = HashMapStorage::<Mass>::new();
+    positions.insert(body, Position::new(1.0, 2.0, 3.0));
+    masses.insert(body, Mass::new(5.0));
+    let mut soa_positions = PositionSoAStorage::new();
+    let mut soa_masses = MassSoAStorage::new();
+    soa_positions.insert(body, Position::new(1.0, 2.0, 3.0));
+    soa_masses.insert(body, Mass::new(5.0));
+
+    // Automatic chunking (len / 4 rounds to zero here), explicit chunking,
+    // and the far-field path
+    let auto = GravityPlugin::new(1.0);
+    let mut chunked = GravityPlugin::new(1.0);
+    chunked.set_chunk_size(1);
+    let mut far_field = GravityPlugin::new(1.0);
+    far_field.set_far_field_approximation(0.5);
+
+    for plugin in [auto, chunked, far_field] {
+        let system = GravitySystem::new(plugin);
+        for entities in [&[][..], &[body][..]] {
+            let mut out = HashMap::new();
+            out.insert(body, Force::new(1.0, 0.0, 0.0));
+            assert_eq!(system.compute_forces_into(entities, &positions, &masses, &mut out), 0);
+            assert!(out.is_empty(), "Stale forces were left in the output map");
+
+            let mut slice = vec![Force::new(1.0, 0.0, 0.0); entities.len()];
+            assert_eq!(system.compute_forces_into_slice(entities, &positions, &masses, GravityOptions::new(), &mut slice), 0);
+            assert!(slice.iter().all(|f| *f == Force::zero()));
+
+            let mut registry = ForceRegistry::new();
+            assert_eq!(system.compute_forces(entities, &positions, &masses, &mut registry), 0);
+            assert_eq!(system.compute_forces_soa(entities, &soa_positions, &soa_masses, GravityOptions::new(), &mut registry), 0);
+            assert_eq!(registry.provider_count(), 0, "Registry was touched");
+        }
+    }
+}
+
+#[test]
+fn test_force_application_with_no_entities() {
+    let mut registry = ForceRegistry::new();
+    let mut world = World::new();
+    assert!(!registry.accumulate_for_entity(world.create_entity()));
+
+    let masses = HashMapStorage::<Mass>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let entities: Vec<Entity> = vec![];
+    assert_eq!(
+        apply_forces_to_acceleration(entities.iter(), &registry, &masses, &mut accelerations, true),
+        0
+    );
+}
+
+/// Minimal LCG so the test needs no optional features
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn unit(&mut self) -> Real {
+        self.next() as Real / (1u64 << 31) as Real
+    }
+}
+
+#[test]
+fn test_random_small_systems_step_cleanly() {
+    let mut rng = Lcg(2025);
+    let mut plugin = GravityPlugin::new(1.0);
+    plugin.set_softening(0.1);
+    plugin.set_warn_on_high_forces(false);
+    let gravity = GravitySystem::new(plugin);
+
+    for trial in 0..20 {
+        let n = (rng.next() % 4) as u32;
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..n).map(|_| world.create_entity()).collect();
+        let mut positions = HashMapStorage::<Position>::new();
+        let mut velocities = HashMapStorage::<Velocity>::new();
+        let mut accelerations = HashMapStorage::<Acceleration>::new();
+        let mut masses = HashMapStorage::<Mass>::new();
+        for &entity in &entities {
+            positions.insert(entity, Position::new(rng.unit() * 4.0 - 2.0, rng.unit() * 4.0 - 2.0, 0.0));
+            velocities.insert(entity, Velocity::new(rng.unit() - 0.5, rng.unit() - 0.5, 0.0));
+            masses.insert(entity, Mass::new(0.5 + rng.unit()));
+        }
+
+        let mut force_registry = ForceRegistry::new();
+        force_registry.set_warn_on_missing_components(false);
+        let mut forces = HashMap::new();
+        let mut verlet = VelocityVerletIntegrator::new(0.001);
+        let mut rk4 = RK4Integrator::new(0.001);
+
+        for step in 0..100 {
+            force_registry.clear();
+            let forced = gravity.compute_forces_into(&entities, &positions, &masses, &mut forces);
+            force_registry.register_forces(&forces);
+            assert_eq!(forced, if n < 2 { 0 } else { n as usize });
+            apply_forces_to_acceleration(entities.iter(), &force_registry, &masses, &mut accelerations, false);
+
+            let updated = if trial % 2 == 0 {
+                verlet.integrate(entities.iter(), &mut positions, &mut velocities, &accelerations, &masses, &mut force_registry, false)
+            } else {
+                rk4.integrate(entities.iter(), &mut positions, &mut velocities, &accelerations, &masses, &mut force_registry, false)
+            };
+            assert_eq!(updated, n as usize, "Trial {} step {}: not every body was updated", trial, step);
+        }
+
+        for &entity in &entities {
+            assert!(positions.get(entity).unwrap().is_valid());
+            assert!(velocities.get(entity).unwrap().is_valid());
+        }
+    }
+}
+
+/// Provider applying the same force to every entity
+struct ConstantForce(Force);
+
+impl ForceProvider for ConstantForce {
+    fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+        Some(self.0)
+    }
+
+    fn name(&self) -> &str {
+        "constant"
+    }
+}
+
+/// Velocity of a 2 kg body at rest after one step under a constant force
+fn velocity_after_one_step<T: Integrator>(
+    integrator: &mut T,
+    force: Force,
+    max_acceleration: Option<Real>,
+) -> Velocity {
+    let mut world = World::new();
+    let body = world.create_entity();
+    let entities = [body];
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut velocities = HashMapStorage::<Velocity>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let mut masses = HashMapStorage::<Mass>::new();
+    positions.insert(body, Position::zero());
+    velocities.insert(body, Velocity::zero());
+    masses.insert(body, Mass::new(2.0));
+
+    let mut force_registry = ForceRegistry::new();
+    force_registry.set_warn_on_missing_components(false);
+    force_registry.set_max_force_magnitude(1e12);
+    force_registry.set_max_acceleration(max_acceleration);
+    force_registry.register_provider(Box::new(ConstantForce(force)));
+
+    force_registry.accumulate_for_entity(body);
+    apply_forces_to_acceleration(entities.iter(), &force_registry, &masses, &mut accelerations, false);
+    integrator.integrate(
+        entities.iter(),
+        &mut positions,
+        &mut velocities,
+        &accelerations,
+        &masses,
+        &mut force_registry,
+        false,
+    );
+    *velocities.get(body).unwrap()
+}
+
+/// One-step velocities from every integrator, labelled by name
+fn velocities_from_every_integrator(force: Force, max_acceleration: Option<Real>) -> Vec<(&'static str, Velocity)> {
+    let dt = 0.01;
+    vec![
+        ("Verlet", velocity_after_one_step(&mut VelocityVerletIntegrator::new(dt), force, max_acceleration)),
+        ("RK4", velocity_after_one_step(&mut RK4Integrator::new(dt), force, max_acceleration)),
+        ("Yoshida", velocity_after_one_step(&mut YoshidaIntegrator::new(dt), force, max_acceleration)),
+    ]
+}
+
+#[test]
+fn test_acceleration_limit_applies_in_every_integrator() {
+    // 1e9 N on 2 kg is 5e8 m/s², clamped to 10 m/s² along the force
+    for (name, vel) in velocities_from_every_integrator(Force::new(0.0, 6e8, 8e8), Some(10.0)) {
+        assert!((vel.dy() - 0.06).abs() < Real::TOLERANCE, "{}: vy = {}", name, vel.dy());
+        assert!((vel.dz() - 0.08).abs() < Real::TOLERANCE, "{}: vz = {}", name, vel.dz());
+        assert_eq!(vel.dx(), 0.0, "{}", name);
+    }
+}
+
+#[test]
+fn test_nan_force_rejected_in_every_integrator() {
+    for (name, vel) in velocities_from_every_integrator(Force::new(Real::NAN, 1.0, 0.0), None) {
+        assert_eq!(vel.as_array(), [0.0, 0.0, 0.0], "{} applied a NaN force", name);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Hierarchical timestep tests
+//!
+//! A tight equal-mass binary is orbited by a light, distant third body
+//! (G = 1). The base timestep is an eighth of the binary period. The
+//! acceleration heuristic must put the binary in class 4 and the third body
+//! in class 0, and the hierarchical run must follow a run that steps every
+//! body at the binary's step while evaluating fewer pairwise interactions.
+
+use physics_engine::Real;
+use physics_engine::real::consts;
+use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+use physics_engine::ecs::systems::{apply_forces_to_acceleration, Force, ForceRegistry};
+use physics_engine::ecs::{ComponentStorage, Entity, HashMapStorage, World};
+use physics_engine::integration::{HierarchicalStepper, TimestepClass, VelocityVerletIntegrator};
+use physics_engine::plugins::gravity::{GravityOptions, GravityPlugin, GravitySystem};
+use std::collections::HashMap;
+
+/// Binary separation
+const SEPARATION: Real = 0.01;
+
+/// Class of the binary, 16 steps per base step
+const BINARY_CLASS: u8 = 4;
+
+/// Base steps to run, about ten binary periods
+const STEPS: usize = 80;
+
+/// Largest distance allowed between a body and its reference position
+///
+/// The binary bodies orbit with radius 0.005 and the third body moves
+/// about 0.1 over the run.
+const TOLERANCE: Real = 1e-5;
+
+struct System {
+    entities: Vec<Entity>,
+    positions: HashMapStorage<Position>,
+    velocities: HashMapStorage<Velocity>,
+    accelerations: HashMapStorage<Acceleration>,
+    masses: HashMapStorage<Mass>,
+    classes: HashMapStorage<TimestepClass>,
+    gravity: GravitySystem,
+    registry: ForceRegistry,
+    forces: HashMap<Entity, Force>,
+}
+
+impl System {
+    /// Binary of two unit masses at the origin plus a light body on a
+    /// circular orbit at radius 1
+    fn new() -> Self {
+        let mut world = World::new();
+        let binary_speed = (2.0 / SEPARATION).sqrt() / 2.0;
+        let bodies: [(Real, Position, Velocity); 3] = [
+            (1.0, Position::new(-SEPARATION / 2.0, 0.0, 0.0), Velocity::new(0.0, -binary_speed, 0.0)),
+            (1.0, Position::new(SEPARATION / 2.0, 0.0, 0.0), Velocity::new(0.0, binary_speed, 0.0)),
+            (1e-3, Position::new(0.0, 1.0, 0.0), Velocity::new(-(2.0 as Real).sqrt(), 0.0, 0.0)),
+        ];
+        let mut system = System {
+            entities: Vec::new(),
+            positions: HashMapStorage::new(),
+            velocities: HashMapStorage::new(),
+            accelerations: HashMapStorage::new(),
+            masses: HashMapStorage::new(),
+            classes: HashMapStorage::new(),
+            gravity: {
+                let mut plugin = GravityPlugin::new(1.0);
+                plugin.set_softening(0.0);
+                plugin.set_warn_on_high_forces(false);
+                GravitySystem::new(plugin)
+            },
+            registry: ForceRegistry::new(),
+            forces: HashMap::new(),
+        };
+        system.registry.set_warn_on_missing_components(false);
+        for (mass, position, velocity) in bodies {
+            let entity = world.create_entity();
+            system.entities.push(entity);
+            system.positions.insert(entity, position);
+            system.velocities.insert(entity, velocity);
+            system.masses.insert(entity, Mass::new(mass));
+        }
+        system.refresh_accelerations();
+        system
+    }
+
+    /// Binary orbital period
+    fn binary_period() -> Real {
+        2.0 * consts::PI * (SEPARATION.powi(3) / 2.0).sqrt()
+    }
+
+    fn refresh_accelerations(&mut self) {
+        self.registry.clear();
+        self.gravity.compute_forces_into(&self.entities, &self.positions, &self.masses, &mut self.forces);
+        self.registry.register_forces(&self.forces);
+        apply_forces_to_acceleration(self.entities.iter(), &self.registry, &self.masses, &mut self.accelerations, false);
+    }
+
+    /// Largest distance between this system's bodies and another's
+    fn distance(&self, other: &System) -> Real {
+        self.entities
+            .iter()
+            .zip(&other.entities)
+            .map(|(a, b)| {
+                let (pa, pb) = (self.positions.get(*a).unwrap(), other.positions.get(*b).unwrap());
+                ((pa.x() - pb.x()).powi(2) + (pa.y() - pb.y()).powi(2) + (pa.z() - pb.z()).powi(2)).sqrt()
+            })
+            .fold(0.0, Real::max)
+    }
+}
+
+#[test]
+fn test_hierarchical_binary_matches_uniform_small_step() {
+    let dt = System::binary_period() / 8.0;
+
+    // Hierarchical: classes from the accelerations at the start
+    let mut hierarchical = System::new();
+    let mut stepper = HierarchicalStepper::new(VelocityVerletIntegrator::new(dt));
+    // The third body accelerates at about 2, the binary at 1 / SEPARATION²
+    let finest = stepper.assign_classes(
+        hierarchical.entities.iter(),
+        &hierarchical.accelerations,
+        50.0,
+        &mut hierarchical.classes,
+    );
+    assert_eq!(finest, BINARY_CLASS);
+    assert_eq!(hierarchical.classes.get(hierarchical.entities[2]), Some(&TimestepClass::new(0)));
+    let (mut hierarchical_evaluations, mut hierarchical_interactions) = (0, 0);
+    for _ in 0..STEPS {
+        let system = &mut hierarchical;
+        let (gravity, masses, forces) = (&system.gravity, &system.masses, &mut system.forces);
+        stepper.integrate_with(
+            system.entities.iter(),
+            &mut system.positions,
+            &mut system.velocities,
+            &system.accelerations,
+            &system.masses,
+            &system.classes,
+            &mut system.registry,
+            false,
+            |targets, sources, positions, registry| {
+                registry.clear();
+                gravity.compute_forces_on(targets, sources, positions, masses, GravityOptions::new(), forces);
+                registry.register_forces(forces);
+                hierarchical_interactions += gravity.last_interaction_count();
+            },
+        );
+        system.refresh_accelerations();
+        hierarchical_evaluations += stepper.stats().force_evaluations;
+    }
+
+    // Reference: every body at the binary's step
+    let mut reference = System::new();
+    let mut fine = VelocityVerletIntegrator::new(TimestepClass::new(BINARY_CLASS).timestep(dt));
+    let (mut reference_evaluations, mut reference_interactions) = (0, 0);
+    for _ in 0..STEPS * TimestepClass::new(BINARY_CLASS).subdivisions() as usize {
+        let system = &mut reference;
+        let (gravity, masses, forces) = (&system.gravity, &system.masses, &mut system.forces);
+        fine.integrate_with(
+            system.entities.iter(),
+            &mut system.positions,
+            &mut system.velocities,
+            &system.accelerations,
+            &system.masses,
+            &mut system.registry,
+            false,
+            |entities, positions, registry| {
+                reference_evaluations += entities.len();
+                registry.clear();
+                gravity.compute_forces_into(entities, positions, masses, forces);
+                registry.register_forces(forces);
+                reference_interactions += gravity.last_interaction_count();
+            },
+        );
+        system.refresh_accelerations();
+    }
+
+    let error = hierarchical.distance(&reference);
+    println!(
+        "Distance from the uniform reference: {:.3e}; interactions: hierarchical {}, uniform {}",
+        error, hierarchical_interactions, reference_interactions
+    );
+    assert!(error < TOLERANCE, "Hierarchical run drifted from the reference by {:.3e}", error);
+    assert!(reference.distance(&System::new()) > 0.05, "Bodies barely moved");
+    // 16 steps of the binary's two bodies and one of the third body, against
+    // 16 steps of all three
+    assert_eq!(hierarchical_evaluations, STEPS * (16 * 2 + 1));
+    assert_eq!(reference_evaluations, STEPS * 16 * 3);
+    // Each body evaluated pulls from the other two, as counted by gravity
+    assert_eq!(hierarchical_interactions, 2 * hierarchical_evaluations);
+    assert_eq!(reference_interactions, 2 * reference_evaluations);
+}
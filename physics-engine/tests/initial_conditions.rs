@@ -0,0 +1,118 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Tests loading and saving initial conditions with the `io` feature
+//!
+//! `fixtures/bodies_1000.txt` holds 1000 bodies whose values follow simple
+//! formulas of the body index, listed in the file's comments.
+
+#![cfg(feature = "io")]
+
+use physics_engine::Real;
+use physics_engine::ecs::components::{Mass, Position, Velocity};
+use physics_engine::io::{export, import, Body, UnitScaling};
+use std::path::PathBuf;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/bodies_1000.txt");
+
+/// Lines before the first body in the fixture: three comments and a header
+const FIXTURE_PREAMBLE_LINES: usize = 4;
+
+fn expected_body(i: usize) -> [Real; 7] {
+    let i_real = i as Real;
+    [
+        1.0 + i_real / 8.0,
+        i_real / 4.0 - 125.0,
+        -i_real / 2.0,
+        1.5 * (i % 7) as Real,
+        i_real / 8.0,
+        -0.75 * (i % 13) as Real,
+        i_real / 1024.0,
+    ]
+}
+
+fn values((mass, position, velocity): &Body) -> [Real; 7] {
+    let [x, y, z] = position.as_array();
+    let [vx, vy, vz] = velocity.as_array();
+    [mass.value(), x, y, z, vx, vy, vz]
+}
+
+/// Path in the system temp directory, unique to this test process
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("physics-engine-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn test_fixture_loads_1000_bodies() {
+    let bodies = import::load(FIXTURE, &UnitScaling::default()).unwrap();
+    assert_eq!(bodies.len(), 1000);
+    for (i, body) in bodies.iter().enumerate() {
+        assert_eq!(values(body), expected_body(i), "Body {} differs", i);
+    }
+}
+
+#[test]
+fn test_malformed_fixture_line_is_reported() {
+    let fixture = std::fs::read_to_string(FIXTURE).unwrap();
+    let mut lines: Vec<String> = fixture.lines().map(str::to_string).collect();
+    let bad_line = FIXTURE_PREAMBLE_LINES + 500;
+    let mut fields: Vec<&str> = lines[bad_line].split_whitespace().collect();
+    fields[1] = "1.0.0";
+    lines[bad_line] = fields.join(" ");
+    let corrupted = lines.join("\n");
+
+    let error = import::read_ascii(corrupted.as_bytes(), &UnitScaling::default()).unwrap_err();
+    match &error {
+        import::ImportError::Ascii { line, column, .. } => {
+            // Lines are reported 1-based
+            assert_eq!(*line, bad_line + 1);
+            assert_eq!(*column, Some(2));
+        }
+        other => panic!("Unexpected error {:?}", other),
+    }
+    assert!(error.to_string().starts_with(&format!("Line {}, column 2 (x)", bad_line + 1)));
+}
+
+#[test]
+fn test_save_and_load_round_trip_to_full_precision() {
+    // Values with long binary expansions, spanning many magnitudes
+    let bodies: Vec<Body> = (0..200)
+        .map(|i| {
+            let t = i as Real + 0.1;
+            let scale = (10.0 as Real).powi(i % 40 - 20);
+            (
+                Mass::new(t.sqrt() * scale),
+                Position::new(t.sin() * scale, -t.cos() / 3.0, t.ln() * 1e6),
+                Velocity::new(1.0 / t, -t.exp().recip(), (t * 7.0).tan()),
+            )
+        })
+        .collect();
+
+    for name in ["round_trip.txt", "round_trip.bin"] {
+        let path = temp_path(name);
+        export::save(&path, &bodies, &UnitScaling::default()).unwrap();
+        let loaded = import::load(&path, &UnitScaling::default());
+        std::fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), bodies.len());
+        for (saved, loaded) in bodies.iter().zip(&loaded) {
+            assert_eq!(
+                values(saved).map(Real::to_bits),
+                values(loaded).map(Real::to_bits),
+                "{} did not round-trip",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Multi-rate integration tests
+//!
+//! A tight equal-mass binary is orbited by two light, distant bodies (G = 1).
+//! The base timestep is an eighth of the binary period: fine for the outer
+//! orbits, coarse for the binary. The multi-rate integrator substeps the
+//! binary and must conserve its energy far better than a single global step
+//! of the same size, while moving the outer bodies as accurately as a run
+//! that resolves the binary everywhere.
+
+use physics_engine::Real;
+use physics_engine::real::consts;
+use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+use physics_engine::ecs::systems::{apply_forces_to_acceleration, Force, ForceRegistry};
+use physics_engine::ecs::{ComponentStorage, Entity, HashMapStorage, World};
+use physics_engine::integration::{MultiRateIntegrator, RatePartition, VelocityVerletIntegrator};
+use physics_engine::plugins::gravity::{GravityPlugin, GravitySystem};
+use std::collections::HashMap;
+
+/// Binary separation
+const SEPARATION: Real = 0.01;
+
+/// Substeps per base step for the binary
+const FAST_SUBSTEPS: usize = 16;
+
+/// Base steps to run, about ten binary periods
+const STEPS: usize = 80;
+
+struct System {
+    entities: Vec<Entity>,
+    positions: HashMapStorage<Position>,
+    velocities: HashMapStorage<Velocity>,
+    accelerations: HashMapStorage<Acceleration>,
+    masses: HashMapStorage<Mass>,
+    gravity: GravitySystem,
+    registry: ForceRegistry,
+    forces: HashMap<Entity, Force>,
+}
+
+impl System {
+    /// Binary of two unit masses at the origin plus two outer bodies on
+    /// circular orbits at radii 1 and 1.5
+    fn new() -> Self {
+        let mut world = World::new();
+        let binary_speed = (2.0 / SEPARATION).sqrt() / 2.0;
+        let bodies: [(Real, Position, Velocity); 4] = [
+            (1.0, Position::new(-SEPARATION / 2.0, 0.0, 0.0), Velocity::new(0.0, -binary_speed, 0.0)),
+            (1.0, Position::new(SEPARATION / 2.0, 0.0, 0.0), Velocity::new(0.0, binary_speed, 0.0)),
+            (1e-3, Position::new(0.0, 1.0, 0.0), Velocity::new(-(2.0 as Real).sqrt(), 0.0, 0.0)),
+            (1e-3, Position::new(-1.5, 0.0, 0.0), Velocity::new(0.0, -(2.0 / 1.5 as Real).sqrt(), 0.0)),
+        ];
+        let mut system = System {
+            entities: Vec::new(),
+            positions: HashMapStorage::new(),
+            velocities: HashMapStorage::new(),
+            accelerations: HashMapStorage::new(),
+            masses: HashMapStorage::new(),
+            gravity: {
+                let mut plugin = GravityPlugin::new(1.0);
+                plugin.set_softening(0.0);
+                plugin.set_warn_on_high_forces(false);
+                GravitySystem::new(plugin)
+            },
+            registry: ForceRegistry::new(),
+            forces: HashMap::new(),
+        };
+        system.registry.set_warn_on_missing_components(false);
+        for (mass, position, velocity) in bodies {
+            let entity = world.create_entity();
+            system.entities.push(entity);
+            system.positions.insert(entity, position);
+            system.velocities.insert(entity, velocity);
+            system.masses.insert(entity, Mass::new(mass));
+        }
+        system.refresh_accelerations();
+        system
+    }
+
+    /// Binary orbital period
+    fn binary_period() -> Real {
+        2.0 * consts::PI * (SEPARATION.powi(3) / 2.0).sqrt()
+    }
+
+    fn refresh_accelerations(&mut self) {
+        self.registry.clear();
+        self.gravity.compute_forces_into(&self.entities, &self.positions, &self.masses, &mut self.forces);
+        self.registry.register_forces(&self.forces);
+        apply_forces_to_acceleration(self.entities.iter(), &self.registry, &self.masses, &mut self.accelerations, false);
+    }
+
+    /// Internal energy of the binary: relative kinetic plus mutual potential
+    fn binary_energy(&self) -> Real {
+        let (a, b) = (self.entities[0], self.entities[1]);
+        let (pa, pb) = (self.positions.get(a).unwrap(), self.positions.get(b).unwrap());
+        let (va, vb) = (self.velocities.get(a).unwrap(), self.velocities.get(b).unwrap());
+        let r = ((pa.x() - pb.x()).powi(2) + (pa.y() - pb.y()).powi(2) + (pa.z() - pb.z()).powi(2)).sqrt();
+        let v_sq = (va.dx() - vb.dx()).powi(2) + (va.dy() - vb.dy()).powi(2) + (va.dz() - vb.dz()).powi(2);
+        // Reduced mass of two unit masses is 1/2
+        0.25 * v_sq - 1.0 / r
+    }
+
+    /// Largest distance between this system's outer bodies and another's
+    fn outer_distance(&self, other: &System) -> Real {
+        self.entities[2..]
+            .iter()
+            .zip(&other.entities[2..])
+            .map(|(a, b)| {
+                let (pa, pb) = (self.positions.get(*a).unwrap(), other.positions.get(*b).unwrap());
+                ((pa.x() - pb.x()).powi(2) + (pa.y() - pb.y()).powi(2) + (pa.z() - pb.z()).powi(2)).sqrt()
+            })
+            .fold(0.0, Real::max)
+    }
+}
+
+/// Step a system with forces refreshed at every evaluation, returning the
+/// largest relative binary energy error seen
+macro_rules! run {
+    ($system:expr, $integrator:expr, $steps:expr) => {{
+        let system = &mut $system;
+        let initial = system.binary_energy();
+        let mut max_error: Real = 0.0;
+        for _ in 0..$steps {
+            let (gravity, masses, forces) = (&system.gravity, &system.masses, &mut system.forces);
+            $integrator.integrate_with(
+                system.entities.iter(),
+                &mut system.positions,
+                &mut system.velocities,
+                &system.accelerations,
+                &system.masses,
+                &mut system.registry,
+                false,
+                |entities, positions, registry| {
+                    registry.clear();
+                    gravity.compute_forces_into(entities, positions, masses, forces);
+                    registry.register_forces(forces);
+                },
+            );
+            system.refresh_accelerations();
+            max_error = max_error.max(((system.binary_energy() - initial) / initial).abs());
+        }
+        max_error
+    }};
+}
+
+#[test]
+fn test_substepped_binary_beats_global_step() {
+    let dt = System::binary_period() / 8.0;
+
+    let mut global = System::new();
+    let mut verlet = VelocityVerletIntegrator::new(dt);
+    let global_error = run!(global, verlet, STEPS);
+
+    let mut multirate = System::new();
+    let mut integrator = MultiRateIntegrator::new(dt, FAST_SUBSTEPS, RatePartition::AccelerationAbove(100.0));
+    let (fast, slow) = integrator.split(multirate.entities.iter(), &multirate.accelerations);
+    assert_eq!(fast, multirate.entities[..2]);
+    assert_eq!(slow, multirate.entities[2..]);
+    let multirate_error = run!(multirate, integrator, STEPS);
+
+    let mut reference = System::new();
+    let mut fine = VelocityVerletIntegrator::new(dt / FAST_SUBSTEPS as Real);
+    run!(reference, fine, STEPS * FAST_SUBSTEPS);
+
+    println!("Binary energy error: global {:.3e}, multi-rate {:.3e}", global_error, multirate_error);
+    assert!(
+        multirate_error * 100.0 < global_error,
+        "Substepping did not help the binary: {:.3e} vs {:.3e}",
+        multirate_error,
+        global_error
+    );
+
+    // The outer bodies move about 0.1 over the run
+    let outer_error = multirate.outer_distance(&reference);
+    println!("Outer body distance from the fine reference: {:.3e}", outer_error);
+    assert!(outer_error < 1e-4, "Outer bodies drifted from the reference by {:.3e}", outer_error);
+    assert!(reference.outer_distance(&System::new()) > 0.05, "Outer bodies barely moved");
+}
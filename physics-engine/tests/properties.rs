@@ -0,0 +1,443 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Property-based tests for storage bookkeeping and force accumulation
+//!
+//! The storage properties run random sequences of inserts, removals,
+//! re-inserts under a newer generation, clears and bulk writes against each
+//! storage type, and compare it with a plain map after every operation. They
+//! call `check_invariants()`, so they need the `debug-invariants` feature:
+//!
+//! ```bash
+//! cargo test --test properties --features debug-invariants
+//! ```
+//!
+//! The force registry properties always run. They register arbitrary sets
+//! of providers, including ones producing NaN or infinite forces, and check
+//! the accumulated force against the rules documented on `ForceRegistry`.
+
+use physics_engine::Real;
+use physics_engine::ecs::Entity;
+use physics_engine::ecs::systems::{Force, ForceProvider, ForceRegistry, ProviderTier};
+use proptest::prelude::*;
+
+/// Provider returning the same force for every entity
+struct FixedForce(Option<Force>);
+
+impl ForceProvider for FixedForce {
+    fn compute_force(&self, _entity: Entity, _registry: &ForceRegistry) -> Option<Force> {
+        self.0
+    }
+
+    fn name(&self) -> &str {
+        "fixed"
+    }
+}
+
+/// A generated provider: tier, enabled flag and output
+#[derive(Debug, Clone)]
+struct ProviderSpec {
+    tier: ProviderTier,
+    enabled: bool,
+    force: Option<Force>,
+}
+
+/// Force components, finite or not
+///
+/// Finite values stay well below the range where the magnitude overflows,
+/// but reach past the default 1e10 clamp limit.
+fn component() -> impl Strategy<Value = Real> {
+    prop_oneof![
+        6 => -1e12 as Real..1e12 as Real,
+        1 => Just(0.0 as Real),
+        1 => Just(Real::NAN),
+        1 => Just(Real::INFINITY),
+        1 => Just(Real::NEG_INFINITY),
+    ]
+}
+
+fn provider() -> impl Strategy<Value = ProviderSpec> {
+    (
+        prop_oneof![4 => Just(ProviderTier::Normal), 1 => Just(ProviderTier::Override)],
+        prop::bool::weighted(0.8),
+        prop::option::weighted(0.8, (component(), component(), component())),
+    )
+        .prop_map(|(tier, enabled, force)| ProviderSpec {
+            tier,
+            enabled,
+            force: force.map(|(fx, fy, fz)| Force::new(fx, fy, fz)),
+        })
+}
+
+/// The force `accumulate_for_entity()` is documented to produce
+///
+/// Invalid forces are skipped. The first enabled override with a valid
+/// force decides alone; otherwise the enabled normal providers are summed
+/// in registration order. A total above `max_force` is scaled down to it.
+fn expected_force(providers: &[ProviderSpec], max_force: Real) -> Option<Force> {
+    let valid = |tier: ProviderTier| {
+        providers
+            .iter()
+            .filter(move |spec| spec.enabled && spec.tier == tier)
+            .filter_map(|spec| spec.force.filter(Force::is_valid))
+    };
+    let mut total = match valid(ProviderTier::Override).next() {
+        Some(force) => force,
+        None => {
+            let mut forces = valid(ProviderTier::Normal).peekable();
+            forces.peek()?;
+            let mut total = Force::zero();
+            for force in forces {
+                total += force;
+            }
+            total
+        }
+    };
+    let magnitude = total.magnitude();
+    if magnitude > max_force {
+        let scale = max_force / magnitude;
+        total.fx *= scale;
+        total.fy *= scale;
+        total.fz *= scale;
+    }
+    Some(total)
+}
+
+fn registry_with(providers: &[ProviderSpec], max_force: Real) -> ForceRegistry {
+    let mut registry = ForceRegistry::new();
+    registry.set_warn_on_missing_components(false);
+    registry.set_max_force_magnitude(max_force);
+    for spec in providers {
+        let id = registry.register_provider_with_tier(Box::new(FixedForce(spec.force)), spec.tier);
+        registry.set_enabled(id, spec.enabled);
+    }
+    registry
+}
+
+fn an_entity() -> Entity {
+    physics_engine::ecs::World::new().create_entity()
+}
+
+proptest! {
+    #[test]
+    fn accumulated_force_follows_the_documented_rules(
+        providers in prop::collection::vec(provider(), 0..8),
+        max_force in prop_oneof![Just(1e10 as Real), 1.0 as Real..1e13 as Real],
+    ) {
+        let entity = an_entity();
+        let mut registry = registry_with(&providers, max_force);
+        let expected = expected_force(&providers, max_force);
+
+        prop_assert_eq!(registry.accumulate_for_entity(entity), expected.is_some());
+        let force = registry.get_force(entity);
+        prop_assert_eq!(force, expected);
+        if let Some(force) = force {
+            prop_assert!(force.is_valid(), "Accumulated an invalid force {:?}", force);
+            prop_assert!(force.magnitude() <= max_force * (1.0 + 4.0 * Real::EPSILON));
+        }
+    }
+
+    #[test]
+    fn force_exactly_at_the_limit_is_not_clamped(
+        direction in (component(), component(), component()),
+        max_force in 1.0 as Real..1e10 as Real,
+    ) {
+        let force = Force::new(direction.0, direction.1, direction.2);
+        prop_assume!(force.is_valid() && force.magnitude() > 0.0);
+        // Scale onto the limit, then take the magnitude the registry will see
+        let scale = max_force / force.magnitude();
+        let force = Force::new(force.fx * scale, force.fy * scale, force.fz * scale);
+        let limit = force.magnitude();
+
+        let spec = ProviderSpec { tier: ProviderTier::Normal, enabled: true, force: Some(force) };
+        let entity = an_entity();
+        let mut registry = registry_with(&[spec], limit);
+        registry.accumulate_for_entity(entity);
+        prop_assert_eq!(registry.get_force(entity), Some(force));
+    }
+}
+
+#[cfg(feature = "debug-invariants")]
+mod storages {
+    use super::*;
+    use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+    use physics_engine::ecs::{
+        AccelerationSoAStorage, Component, ComponentStorage, FieldArraysMut, HashMapStorage, MassSoAStorage, PositionSoAStorage,
+        SoAStorage, SparseSoAStorage, VelocitySoAStorage, World,
+    };
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    /// Distinct entity IDs in the pool
+    const IDS: usize = 6;
+
+    /// Generations of each ID in the pool
+    const GENERATIONS: usize = 3;
+
+    /// Handles to `IDS` entity IDs, each at `GENERATIONS` generations
+    fn entity_pool() -> Vec<Entity> {
+        let mut world = World::new();
+        let mut pool = Vec::with_capacity(IDS * GENERATIONS);
+        for _ in 0..GENERATIONS {
+            let batch: Vec<Entity> = (0..IDS).map(|_| world.create_entity()).collect();
+            for entity in &batch {
+                world.destroy_entity(*entity);
+            }
+            pool.extend(batch);
+        }
+        pool
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        /// Insert or overwrite the pool entity at this index
+        Insert(usize, Real),
+        Remove(usize),
+        Clear,
+        ShrinkToFit,
+        /// Write every value through `field_arrays_mut()`, if supported
+        WriteAll(Real),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let entity = 0..IDS * GENERATIONS;
+        let value = -1e3 as Real..1e3 as Real;
+        prop_oneof![
+            8 => (entity.clone(), value.clone()).prop_map(|(e, v)| Op::Insert(e, v)),
+            5 => entity.prop_map(Op::Remove),
+            1 => Just(Op::Clear),
+            1 => Just(Op::ShrinkToFit),
+            1 => value.prop_map(Op::WriteAll),
+        ]
+    }
+
+    /// Component built from one generated value
+    trait TestComponent: Component + Copy + PartialEq + Debug {
+        fn from_value(value: Real) -> Self;
+
+        /// Overwrite every field of every component
+        fn fill(arrays: &mut FieldArraysMut<'_, Self>, value: Real);
+    }
+
+    impl TestComponent for Position {
+        fn from_value(value: Real) -> Self {
+            Position::new(value, -value, 0.5 * value)
+        }
+
+        fn fill(arrays: &mut FieldArraysMut<'_, Self>, value: Real) {
+            let (a, b, c) = arrays.as_position_arrays_mut();
+            for column in [a, b, c] {
+                column.fill(value);
+            }
+        }
+    }
+
+    impl TestComponent for Velocity {
+        fn from_value(value: Real) -> Self {
+            Velocity::new(value, 2.0 * value, -value)
+        }
+
+        fn fill(arrays: &mut FieldArraysMut<'_, Self>, value: Real) {
+            let (a, b, c) = arrays.as_velocity_arrays_mut();
+            for column in [a, b, c] {
+                column.fill(value);
+            }
+        }
+    }
+
+    impl TestComponent for Acceleration {
+        fn from_value(value: Real) -> Self {
+            Acceleration::new(-value, value, value)
+        }
+
+        fn fill(arrays: &mut FieldArraysMut<'_, Self>, value: Real) {
+            let (a, b, c) = arrays.as_acceleration_arrays_mut();
+            for column in [a, b, c] {
+                column.fill(value);
+            }
+        }
+    }
+
+    impl TestComponent for Mass {
+        fn from_value(value: Real) -> Self {
+            Mass::new(value.abs())
+        }
+
+        fn fill(arrays: &mut FieldArraysMut<'_, Self>, value: Real) {
+            arrays.as_mass_array_mut().fill(value.abs());
+        }
+    }
+
+    /// Storage under test
+    trait Checked: ComponentStorage
+    where
+        Self::Component: TestComponent,
+    {
+        /// Whether inserting a newer generation evicts the stored one
+        const EVICTS_STALE: bool;
+
+        fn invariants(&self) -> Result<(), String>;
+
+        fn contents(&self) -> HashMap<Entity, Self::Component>;
+    }
+
+    macro_rules! true_soa {
+        ($($storage:ty),*) => {$(
+            impl Checked for $storage {
+                const EVICTS_STALE: bool = cfg!(feature = "sparse-set");
+
+                fn invariants(&self) -> Result<(), String> {
+                    self.check_invariants()
+                }
+
+                fn contents(&self) -> HashMap<Entity, Self::Component> {
+                    self.iter().collect()
+                }
+            }
+        )*};
+    }
+
+    true_soa!(PositionSoAStorage, VelocitySoAStorage, AccelerationSoAStorage, MassSoAStorage);
+
+    impl Checked for HashMapStorage<Position> {
+        const EVICTS_STALE: bool = false;
+
+        fn invariants(&self) -> Result<(), String> {
+            self.check_invariants()
+        }
+
+        fn contents(&self) -> HashMap<Entity, Position> {
+            self.iter().map(|(entity, position)| (entity, *position)).collect()
+        }
+    }
+
+    impl Checked for SoAStorage<Position> {
+        const EVICTS_STALE: bool = false;
+
+        fn invariants(&self) -> Result<(), String> {
+            self.check_invariants()
+        }
+
+        fn contents(&self) -> HashMap<Entity, Position> {
+            self.iter().map(|(entity, position)| (entity, *position)).collect()
+        }
+    }
+
+    impl Checked for SparseSoAStorage<Position> {
+        const EVICTS_STALE: bool = true;
+
+        fn invariants(&self) -> Result<(), String> {
+            self.check_invariants()
+        }
+
+        fn contents(&self) -> HashMap<Entity, Position> {
+            self.dense_entities().iter().copied().zip(self.components().iter().copied()).collect()
+        }
+    }
+
+    /// Apply `ops` to `storage` and to a model map, comparing after each one
+    fn run<S>(mut storage: S, ops: &[Op]) -> Result<(), TestCaseError>
+    where
+        S: Checked,
+        S::Component: TestComponent,
+    {
+        let pool = entity_pool();
+        let mut model: HashMap<Entity, S::Component> = HashMap::new();
+        for (step, op) in ops.iter().enumerate() {
+            match *op {
+                Op::Insert(index, value) => {
+                    let entity = pool[index];
+                    if S::EVICTS_STALE {
+                        model.retain(|stored, _| stored.id() != entity.id() || *stored == entity);
+                    }
+                    storage.insert(entity, S::Component::from_value(value));
+                    model.insert(entity, S::Component::from_value(value));
+                }
+                Op::Remove(index) => {
+                    let entity = pool[index];
+                    prop_assert_eq!(storage.remove(entity), model.remove(&entity), "step {}: {:?}", step, op);
+                }
+                Op::Clear => {
+                    storage.clear();
+                    model.clear();
+                }
+                Op::ShrinkToFit => storage.shrink_to_fit(),
+                Op::WriteAll(value) => {
+                    if let Some(mut arrays) = storage.field_arrays_mut() {
+                        S::Component::fill(&mut arrays, value);
+                        // Bulk writes change values, never which entities are stored
+                        let written = storage.contents();
+                        prop_assert!(written.keys().all(|entity| model.contains_key(entity)));
+                        prop_assert_eq!(written.len(), model.len());
+                        model = written;
+                    }
+                }
+            }
+
+            if let Err(violation) = storage.invariants() {
+                return Err(TestCaseError::fail(format!("step {}: {:?} broke an invariant: {}", step, op, violation)));
+            }
+            prop_assert_eq!(&storage.contents(), &model, "step {}: {:?}", step, op);
+            for entity in &pool {
+                prop_assert_eq!(storage.contains(*entity), model.contains_key(entity), "step {}: {:?}", step, op);
+            }
+        }
+        Ok(())
+    }
+
+    fn ops() -> impl Strategy<Value = Vec<Op>> {
+        prop::collection::vec(op(), 1..64)
+    }
+
+    proptest! {
+        #[test]
+        fn position_soa_storage_stays_consistent(ops in ops()) {
+            run(PositionSoAStorage::new(), &ops)?;
+        }
+
+        #[test]
+        fn velocity_soa_storage_stays_consistent(ops in ops()) {
+            run(VelocitySoAStorage::new(), &ops)?;
+        }
+
+        #[test]
+        fn acceleration_soa_storage_stays_consistent(ops in ops()) {
+            run(AccelerationSoAStorage::new(), &ops)?;
+        }
+
+        #[test]
+        fn mass_soa_storage_stays_consistent(ops in ops()) {
+            run(MassSoAStorage::new(), &ops)?;
+        }
+
+        #[test]
+        fn hash_map_storage_stays_consistent(ops in ops()) {
+            run(HashMapStorage::<Position>::new(), &ops)?;
+        }
+
+        #[test]
+        fn insertion_ordered_hash_map_storage_stays_consistent(ops in ops()) {
+            run(HashMapStorage::<Position>::with_insertion_order(), &ops)?;
+        }
+
+        #[test]
+        fn soa_storage_stays_consistent(ops in ops()) {
+            run(SoAStorage::<Position>::new(), &ops)?;
+        }
+
+        #[test]
+        fn sparse_soa_storage_stays_consistent(ops in ops()) {
+            run(SparseSoAStorage::<Position>::new(), &ops)?;
+        }
+    }
+}
@@ -0,0 +1,530 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Public API snapshot
+//!
+//! Names the crate's public surface from outside the crate, so removing or
+//! renaming an item, or changing a listed signature, fails to compile here.
+//! The inverse checks (configuration that must *not* be reachable, such as
+//! `ForceRegistry` fields or struct literals of `#[non_exhaustive]` types)
+//! are `compile_fail` doc tests on the items themselves.
+//!
+//! Intentional surface changes should update this file in the same commit.
+
+#![allow(unused_imports)]
+
+use physics_engine::{Entity, Real, RealExt, World};
+use physics_engine::diagnostics::{
+    analyze_forces, compute_aabb, compute_aabb_soa, detect_force_outliers,
+    gravitational_potential_energy, phase_space_distance, virial_ratio, ForceHistogram, HistogramBin, OutlierReport,
+    EMPTY_AABB,
+};
+use physics_engine::ecs::{
+    AccelerationSoAStorage, Component, ComponentMask, ConstSoAStorage, ComponentStorage, EntityBuilder, EntityId,
+    ExternalIdError, ExternalIdMap, FieldArrays, FieldArraysMut, HashMapStorage, MassSoAStorage, MemoryReport, PositionSoAStorage,
+    SleepState, SleepSystem, SoAFields, SoAStorage, SparseEntityIndex, SparseSoAStorage, System, SystemExecutor,
+    VelocitySoAStorage, MAX_COMPONENT_TYPES,
+};
+#[cfg(feature = "parallel")]
+use physics_engine::ecs::Query;
+use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+use physics_engine::ecs::systems::{
+    apply_forces_to_acceleration, apply_forces_to_state, initialize_accelerations, Force, ForceProvider,
+    ForceRegistry, ParallelForceSink, ProviderId, ProviderTier,
+};
+use physics_engine::integration::{
+    AnyIntegrator, FastPredicate, HierarchicalStats, HierarchicalStepper, Integrator, IntegratorBuilder, IntegratorKind, MultiRateIntegrator,
+    RK4Integrator, RK4Stats, RatePartition, StateSampler, TimestepClass, TimestepSuggestion, VelocityVerletIntegrator, WorkAccumulator, YoshidaIntegrator,
+};
+use physics_engine::plugins::{
+    AnchorForcePlugin, AnchorSystem, BarnesHutSystem, CollisionEvent, CollisionEvents,
+    CollisionSystem, ConstraintSystem, ContactEvent, ForceProviderPlugin, GravityOptions, GravityPlugin,
+    GravitySystem, MassDistribution, ObjectFactory, Plugin, PluginContext, PluginRegistry,
+    SinusoidalForcePlugin, SofteningKernel, SpeedLimitConstraint, Tether, ThrustForcePlugin,
+    UniformGravityPlugin, UniformGravitySystem, GRAVITATIONAL_CONSTANT, PLUGIN_API_VERSION,
+};
+use physics_engine::pool::{PoolConfig, PoolStats};
+use physics_engine::simulation::{GravitySettings, InputEvent, InputLog, InputRecord, Phase, Simulation};
+use physics_engine::util::RunningStats;
+
+#[test]
+fn test_force_registry_configuration_accessors() {
+    let _: fn(&mut ForceRegistry, Real) = ForceRegistry::set_max_force_magnitude;
+    let _: fn(&ForceRegistry) -> Real = ForceRegistry::max_force_magnitude;
+    let _: fn(&mut ForceRegistry, bool) = ForceRegistry::set_warn_on_missing_components;
+    let _: fn(&ForceRegistry) -> bool = ForceRegistry::warn_on_missing_components;
+    let _: fn(&mut ForceRegistry, Real) = ForceRegistry::set_min_mass;
+    let _: fn(&mut ForceRegistry, Real) = ForceRegistry::set_time;
+    let _: fn(&mut ForceRegistry, Entity, Velocity) = ForceRegistry::set_velocity;
+    let _: fn(&ForceRegistry, Entity) -> Option<Velocity> = ForceRegistry::velocity;
+    let _: fn(&mut ForceRegistry, Box<dyn ForceProvider>, ProviderTier) -> ProviderId =
+        ForceRegistry::register_provider_with_tier;
+    let _: fn(&ForceRegistry, ProviderId) -> Option<ProviderTier> = ForceRegistry::provider_tier;
+    let _: fn(&ForceRegistry, Entity) -> Option<ProviderId> = ForceRegistry::overriding_provider;
+    assert_eq!(ProviderTier::default(), ProviderTier::Normal);
+
+    let mut registry = ForceRegistry::new();
+    assert!(registry.warn_on_missing_components());
+    registry.set_warn_on_missing_components(false);
+    registry.set_max_force_magnitude(5.0);
+    assert!(!registry.warn_on_missing_components());
+    assert_eq!(registry.max_force_magnitude(), 5.0);
+    registry.set_velocities(std::iter::empty(), &HashMapStorage::<Velocity>::new());
+}
+
+#[test]
+fn test_force_arithmetic() {
+    let _: fn(&mut Force, &Force) = Force::add;
+    let _: fn(&Force, Real) -> Force = Force::scale;
+
+    let f = Force::new(1.0, 2.0, 3.0);
+    let mut total: Force = f + f * 2.0 - f;
+    total += -f;
+    assert_eq!(total, f);
+}
+
+#[test]
+#[should_panic(expected = "Maximum force magnitude must be positive")]
+fn test_non_positive_max_force_panics() {
+    ForceRegistry::new().set_max_force_magnitude(0.0);
+}
+
+#[test]
+fn test_config_structs_build_through_constructors() {
+    let config = PoolConfig::new(32, 2).with_growth_factor(1.5).with_logging();
+    assert_eq!(config.initial_capacity, 32);
+    assert_eq!(config.max_pool_size, 2);
+    assert!(config.log_resize_events);
+
+    let tether = Tether::new(Position::zero(), 2.0, 0.5);
+    assert_eq!(tether.stiffness, 2.0);
+}
+
+#[test]
+fn test_field_arrays_keyed_on_component_type() {
+    type Column = &'static [Real];
+    let _: fn(Column, Column, Column) -> FieldArrays<'static, Position> = FieldArrays::position;
+    let _: fn(Column) -> FieldArrays<'static, Mass> = FieldArrays::mass;
+    let _: fn(&'static mut [Real]) -> FieldArraysMut<'static, Mass> = FieldArraysMut::mass;
+
+    let mut world = World::new();
+    let entity = world.create_entity();
+    let mut masses = MassSoAStorage::new();
+    masses.insert(entity, Mass::new(3.0));
+    if let Some(mut arrays) = masses.field_arrays_mut() {
+        arrays.as_mass_array_mut()[0] = 4.0;
+    }
+    assert_eq!(masses.field_arrays().unwrap().as_mass_array(), &[4.0]);
+
+    let x = [1.0, 2.0];
+    let y = [3.0, 4.0];
+    let z = [5.0, 6.0];
+    let arrays = FieldArrays::velocity(&x, &y, &z);
+    assert_eq!(arrays.as_velocity_arrays().2, &z);
+}
+
+#[test]
+#[should_panic(expected = "Field arrays must all have the same length")]
+fn test_field_arrays_length_mismatch_panics() {
+    FieldArrays::acceleration(&[1.0], &[1.0, 2.0], &[1.0]);
+}
+
+#[test]
+fn test_external_id_accessors() {
+    let _: fn(&mut World, u64) -> Result<Entity, ExternalIdError> = World::create_entity_with_external_id;
+    let _: fn(&World, u64) -> Option<Entity> = World::lookup_external;
+    let _: fn(&World, Entity) -> Option<u64> = World::external_id;
+    let _: fn(&World) -> &ExternalIdMap = World::external_ids;
+}
+
+#[test]
+fn test_const_soa_storage_accessors() {
+    let _: fn(&ConstSoAStorage<Velocity, 3>, usize) -> &[Real] = ConstSoAStorage::field_array;
+    let _: fn(&mut ConstSoAStorage<Mass, 1>, usize) -> &mut [Real] = ConstSoAStorage::field_array_mut;
+    let _: fn([Real; 3]) -> Position = <Position as SoAFields<3>>::from_fields;
+    let _: fn(&Mass) -> [Real; 1] = <Mass as SoAFields<1>>::to_fields;
+}
+
+#[test]
+fn test_world_live_entity_iteration() {
+    let mut world = World::new();
+    let entity = world.create_entity();
+    let _: Vec<Entity> = world.iter_entities().collect();
+    let _: Vec<Entity> = world.live_entities_snapshot();
+    world.compact();
+    assert!(world.is_entity_alive(entity));
+}
+
+#[test]
+fn test_acceleration_initialization() {
+    let _: fn(&mut Simulation) = Simulation::initialize_accelerations;
+
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let count: usize = initialize_accelerations(
+        [].iter(),
+        &mut ForceRegistry::new(),
+        &HashMapStorage::<Mass>::new(),
+        &mut accelerations,
+        false,
+    );
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_newtonian_state_bundle() {
+    use physics_engine::ecs::{NewtonianState, SoANewtonianState};
+
+    let mut state: NewtonianState = NewtonianState::with_capacity(4);
+    let entity = World::new().create_entity();
+    state.insert_body(entity, Position::zero(), Velocity::zero(), Mass::new(1.0));
+    let (_, _, _, _): (&mut HashMapStorage<Position>, &mut HashMapStorage<Velocity>, &HashMapStorage<Acceleration>, &HashMapStorage<Mass>) =
+        state.split_mut();
+
+    let mut registry = ForceRegistry::new();
+    let entities = [entity];
+    let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+    let mut forces = std::collections::HashMap::new();
+    let _: usize = gravity.compute_forces_for_state(&entities, &state, &mut forces);
+    let _: usize = apply_forces_to_state(entities.iter(), &registry, &mut state, false);
+    let _: usize = VelocityVerletIntegrator::new(0.1).integrate_state(entities.iter(), &mut state, &mut registry, false);
+    assert!(state.remove_body(entity));
+
+    let soa: SoANewtonianState = NewtonianState::soa();
+    let _: (PositionSoAStorage, _, _, MassSoAStorage) = soa.into_parts();
+}
+
+#[test]
+fn test_tolerances_accessors() {
+    use physics_engine::math::Tolerances;
+
+    let tolerances = Tolerances::default().with_max_coordinate(1.0).with_unit_norm(0.1).with_energy_drift(0.1);
+    let _: Real = tolerances.max_coordinate + tolerances.unit_norm + tolerances.energy_drift;
+    let _: fn(&Tolerances, &Position) -> bool = Tolerances::position_is_valid;
+    let _: fn(&Tolerances, &[Real]) -> bool = Tolerances::is_unit_norm;
+    let _: fn(&Tolerances, Real, Real) -> bool = Tolerances::energy_conserved;
+    let _: fn(&Position, &Tolerances) -> bool = Position::is_valid_with;
+}
+
+#[test]
+fn test_integrator_time_reversal() {
+    let mut integrator = IntegratorBuilder::new().method(IntegratorKind::Yoshida).timestep(0.01).build().unwrap();
+    let _: bool = integrator.supports_negative_dt();
+    integrator.reverse_time();
+    assert_eq!(integrator.timestep(), -0.01);
+}
+
+#[test]
+fn test_multirate_integrator_configuration() {
+    let predicate: FastPredicate = Box::new(|_| true);
+    let mut integrator = MultiRateIntegrator::new(0.1, 4, RatePartition::Predicate(predicate));
+    integrator.set_fast_substeps(8);
+    integrator.set_partition(RatePartition::AccelerationAbove(10.0));
+    assert_eq!(integrator.fast_substeps(), 8);
+    assert!(matches!(integrator.partition(), RatePartition::AccelerationAbove(_)));
+    let (_, _): (Vec<Entity>, Vec<Entity>) = integrator.split([].iter(), &HashMapStorage::<Acceleration>::new());
+}
+
+#[test]
+fn test_hierarchical_stepper_configuration() {
+    let mut stepper = HierarchicalStepper::new(RK4Integrator::new(0.1));
+    stepper.set_timestep(0.2);
+    stepper.inner_mut().set_reuse_initial_forces(true);
+    assert!(stepper.inner().reuse_initial_forces());
+    let _: HierarchicalStats = stepper.stats();
+    let mut classes = HashMapStorage::<TimestepClass>::new();
+    let finest: u8 = stepper.assign_classes([].iter(), &HashMapStorage::<Acceleration>::new(), 1.0, &mut classes);
+    assert_eq!(finest, 0);
+    let _: Vec<Vec<Entity>> = stepper.split([].iter(), &classes);
+    let class = TimestepClass::for_acceleration(16.0, 1.0);
+    assert_eq!((class.level(), class.subdivisions(), class.timestep(0.2)), (2, 4, 0.05));
+    let _: RK4Integrator = stepper.into_inner();
+}
+
+#[test]
+fn test_collision_energy_check() {
+    let mut collisions = CollisionSystem::new(1.0);
+    collisions.set_energy_check(Some(1e-9));
+    collisions.set_warning_handler(Box::new(|_| {}));
+    collisions.clear_warning_handler();
+    let _: Option<Real> = collisions.energy_check();
+    let _: Option<Real> = collisions.last_energy_change();
+
+    let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+    sim.set_collisions(Some(collisions));
+    let _: &[ContactEvent] = sim.contact_events();
+    let _: Vec<ContactEvent> = sim.take_contact_events();
+}
+
+#[test]
+fn test_verlet_acceleration_field() {
+    let entity = World::new().create_entity();
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut velocities = HashMapStorage::<Velocity>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let _: usize = VelocityVerletIntegrator::new(0.1).integrate_with_accelerations(
+        [entity].iter(),
+        &mut positions,
+        &mut velocities,
+        &mut accelerations,
+        false,
+        |_: &[Entity], _: &HashMapStorage<Position>, _: &mut HashMapStorage<Acceleration>| {},
+    );
+}
+
+#[test]
+fn test_verlet_reused_accelerations() {
+    let entity = World::new().create_entity();
+    let mut positions = HashMapStorage::<Position>::new();
+    let mut velocities = HashMapStorage::<Velocity>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let masses = HashMapStorage::<Mass>::new();
+    let mut registry = ForceRegistry::new();
+    let _: usize = VelocityVerletIntegrator::new(0.1).integrate_reusing_accelerations(
+        [entity].iter(),
+        &mut positions,
+        &mut velocities,
+        &mut accelerations,
+        &masses,
+        &mut registry,
+        false,
+        |_: &[Entity], _: &HashMapStorage<Position>, _: &mut ForceRegistry| {},
+    );
+}
+
+#[test]
+fn test_verlet_acceleration_cache() {
+    let entity = World::new().create_entity();
+    let mut integrator = VelocityVerletIntegrator::new(0.1);
+    integrator.set_acceleration_cache(true);
+    let _: bool = integrator.acceleration_cache_enabled();
+    let _: Option<Acceleration> = integrator.cached_acceleration(entity);
+    integrator.invalidate_cache(entity);
+    integrator.invalidate_all();
+}
+
+#[test]
+fn test_integrator_prime() {
+    let entity = World::new().create_entity();
+    let positions = HashMapStorage::<Position>::new();
+    let mut accelerations = HashMapStorage::<Acceleration>::new();
+    let masses = HashMapStorage::<Mass>::new();
+    let mut registry = ForceRegistry::new();
+    let eval = |_: &[Entity], _: &HashMapStorage<Position>, _: &mut ForceRegistry| {};
+    VelocityVerletIntegrator::new(0.1).prime([entity].iter(), &positions, &mut accelerations, &masses, &mut registry, eval);
+    RK4Integrator::new(0.1).prime([entity].iter(), &positions, &mut accelerations, &masses, &mut registry, eval);
+}
+
+#[test]
+fn test_change_tracking() {
+    use physics_engine::ecs::ChangeTracking;
+    use physics_engine::ecs::components::Position;
+
+    let entity = World::new().create_entity();
+    let mut positions = ChangeTracking::new(HashMapStorage::<Position>::new());
+    positions.insert(entity, Position::zero());
+    positions.mark_changed(entity);
+    let _: Vec<Entity> = positions.changed().collect();
+    let _: (usize, bool, bool) = (positions.changed_count(), positions.is_changed(entity), positions.cleared());
+    positions.clear_changes();
+    let _: &HashMapStorage<Position> = positions.inner();
+    let _: HashMapStorage<Position> = positions.into_inner();
+}
+
+#[test]
+fn test_soa_change_ticks() {
+    let entity = World::new().create_entity();
+    let mut positions = PositionSoAStorage::new();
+    positions.insert(entity, Position::zero());
+    let seen: u64 = positions.current_tick();
+    let _: u64 = positions.advance_tick();
+    positions.set_tick(seen + 2);
+    let _ = positions.field_arrays_mut_masked(&[true]);
+    let _: Option<u64> = positions.last_changed(entity);
+    let _: Vec<Entity> = positions.changed_since(seen).collect();
+    let _: u64 = Simulation::new(VelocityVerletIntegrator::new(0.1)).current_tick();
+}
+
+#[test]
+fn test_variable_mass() {
+    use physics_engine::ecs::components::ComponentError;
+    use physics_engine::plugins::{MassFlow, VariableMassSystem};
+
+    let mut mass = Mass::new(1.0);
+    let _: Result<(), ComponentError> = mass.try_set_value(2.0);
+
+    let entity = World::new().create_entity();
+    let mut masses = HashMapStorage::new();
+    masses.insert(entity, mass);
+    let flow = MassFlow::new(-1.0, 0.5).with_exhaust_velocity(Velocity::zero());
+    let _: (Real, Real, Option<Velocity>) = (flow.rate, flow.min_mass, flow.exhaust_velocity);
+    let _: Real = flow.mass_change(1.0, 0.1);
+
+    let mut system = VariableMassSystem::new();
+    system.set_flow(entity, flow);
+    let _: Option<&MassFlow> = system.flow(entity);
+    let _: (usize, bool) = (system.len(), system.is_empty());
+    let _: usize = system.compute_forces(0.1, &[entity], &masses, &mut ForceRegistry::new());
+    let _: usize = system.apply(0.1, &[entity], &mut masses);
+    let _: Option<MassFlow> = system.clear_flow(entity);
+}
+
+#[test]
+fn test_speed_limit_system() {
+    use physics_engine::plugins::{LimitEvent, LimitKind, MotionLimits, SpeedLimitSystem};
+
+    let limits = MotionLimits::new().with_max_speed(1.0).with_max_acceleration(2.0);
+    let _: (Option<Real>, Option<Real>) = (limits.max_speed, limits.max_acceleration);
+    let mut system = SpeedLimitSystem::new(limits);
+    system.set_global(system.global());
+    let (entities, masses, limit_storage) = ([], HashMapStorage::<Mass>::new(), HashMapStorage::<MotionLimits>::new());
+    let _: usize = system.limit_velocities(&entities, &mut HashMapStorage::<Velocity>::new(), &masses, &limit_storage);
+    let _: usize = system.limit_accelerations(&entities, &mut HashMapStorage::<Acceleration>::new(), &masses, &limit_storage);
+    let _: &[LimitEvent] = system.events();
+    let _: Vec<LimitEvent> = system.take_events();
+    system.clear_events();
+    let _ = |event: LimitEvent| -> (Entity, LimitKind, Real) { (event.entity, event.kind, event.original_magnitude) };
+
+    let mut sim = Simulation::new(VelocityVerletIntegrator::new(0.1));
+    sim.set_speed_limits(Some(system));
+    let _: Option<&SpeedLimitSystem> = sim.speed_limits();
+    let body = sim.spawn_body(Position::zero(), Velocity::zero(), Mass::new(1.0));
+    let _: bool = sim.set_motion_limits(body, limits);
+    let _: Vec<LimitEvent> = sim.take_limit_events();
+}
+
+#[test]
+fn test_gravity_progress() {
+    use physics_engine::plugins::GravityError;
+    use std::sync::atomic::AtomicBool;
+
+    let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+    let (positions, masses) = (HashMapStorage::<Position>::new(), HashMapStorage::<Mass>::new());
+    let mut forces = std::collections::HashMap::new();
+    let _: Result<usize, GravityError> =
+        gravity.compute_forces_with_progress(&[], &positions, &masses, GravityOptions::new(), &mut forces, &|_, _| {}, &AtomicBool::new(false));
+    let _: &dyn std::error::Error = &GravityError::Cancelled;
+}
+
+#[test]
+fn test_gravity_forces_on_targets() {
+    let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+    let (positions, masses) = (HashMapStorage::<Position>::new(), HashMapStorage::<Mass>::new());
+    let mut forces = std::collections::HashMap::new();
+    let _: usize = gravity.compute_forces_on(&[], &[], &positions, &masses, GravityOptions::new(), &mut forces);
+}
+
+#[test]
+fn test_gravity_tracers() {
+    use physics_engine::plugins::Tracer;
+
+    let entity = World::new().create_entity();
+    let positions = HashMapStorage::<Position>::new();
+    let masses = HashMapStorage::<Mass>::new();
+    let mut tracers = HashMapStorage::<Tracer>::new();
+    tracers.insert(entity, Tracer);
+    let mut forces = std::collections::HashMap::new();
+    let gravity = GravitySystem::new(GravityPlugin::new(1.0));
+    let options = GravityOptions::new().with_tracers(&tracers);
+    let _: usize = gravity.compute_forces_into_with(&[entity], &positions, &masses, options, &mut forces);
+}
+
+#[test]
+fn test_gravity_timestep_suggestion() {
+    use physics_engine::plugins::suggest_timestep;
+
+    let entity = World::new().create_entity();
+    let positions = HashMapStorage::<Position>::new();
+    let masses = HashMapStorage::<Mass>::new();
+    let suggestion: Option<Real> = suggest_timestep(&[entity], &positions, &masses, 1.0, 0.0, 0.01);
+    assert_eq!(suggestion, None);
+}
+
+#[test]
+fn test_neighbor_search() {
+    use physics_engine::ecs::components::Position;
+    use physics_engine::ecs::HashMapStorage;
+    use physics_engine::spatial::NeighborSearch;
+
+    let positions = HashMapStorage::<Position>::new();
+    let mut search = NeighborSearch::build([].iter(), &positions);
+    search.rebuild([].iter(), &positions);
+    search.shrink_to_fit();
+    let _: (usize, bool, usize) = (search.len(), search.is_empty(), search.capacity());
+    let _: Vec<Entity> = search.within_radius(&Position::zero(), 1.0);
+    search.within_radius_into(&Position::zero(), 1.0, &mut Vec::new());
+    let _: Vec<(Entity, Real)> = search.k_nearest(&Position::zero(), 3);
+}
+
+#[test]
+fn test_scenario_constructors() {
+    use physics_engine::scenarios::{
+        circular_two_body, figure_eight_three_body, random_cube, CubeRanges, DeterministicRng, Scenario,
+        FIGURE_EIGHT_PERIOD, STEPS_PER_PERIOD,
+    };
+
+    let _: fn(&mut World, Real, Real, Real) -> Scenario = circular_two_body;
+    let _: fn(&mut World) -> Scenario = figure_eight_three_body;
+    let _: fn(&mut World, usize, u64, &CubeRanges) -> Scenario = random_cube;
+    let _: fn(&Scenario) -> GravitySystem = Scenario::gravity_system;
+    let _: fn(&Scenario) -> ForceRegistry = Scenario::force_registry;
+    let _: Real = FIGURE_EIGHT_PERIOD * STEPS_PER_PERIOD;
+    let _: u64 = DeterministicRng::new(1).next_u64();
+
+    let scenario = random_cube(&mut World::new(), 3, 1, &CubeRanges::default().with_velocity(0.0));
+    let _: (&Vec<Entity>, Real, Real, Real, Option<Real>) =
+        (&scenario.entities, scenario.g, scenario.softening, scenario.suggested_timestep, scenario.period);
+    assert!(scenario.state.contains_body(scenario.entities[0]));
+}
+
+#[cfg(feature = "io")]
+#[test]
+fn test_io_entry_points() {
+    use physics_engine::io::{export, import, Body, UnitScaling};
+    use physics_engine::units::UnitSystem;
+
+    let mut table = Vec::new();
+    export::write_ascii(&mut table, &[], &UnitScaling::default()).unwrap();
+    let bodies: Vec<Body> = import::read_ascii(table.as_slice(), &UnitScaling::default()).unwrap();
+    assert!(bodies.is_empty());
+    let error: import::ImportError = import::read_binary(&[][..], &UnitScaling::default()).unwrap_err();
+    assert!(matches!(error, import::ImportError::Binary { record: None, .. }));
+    let scaling = UnitScaling::new(UnitSystem::si(), UnitSystem::astronomical());
+    assert_eq!(scaling.simulation_units(), UnitSystem::astronomical());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_world_json_entry_points() {
+    use physics_engine::ecs::WorldJsonError;
+
+    let json: String = World::new().to_json_string();
+    let restored: Result<World, WorldJsonError> = World::from_json_str(&json);
+    assert_eq!(restored.unwrap().entity_count(), 0);
+}
+
+#[test]
+fn test_running_stats_accessors() {
+    let mut stats = RunningStats::new();
+    stats.push(1.0);
+    stats.extend([2.0, 3.0]);
+    let _: u64 = stats.count();
+    let _: [Option<f64>; 5] = [stats.mean(), stats.variance(), stats.stddev(), stats.min(), stats.max()];
+}
+
+#[test]
+fn test_recorded_gravity_settings() {
+    let settings = GravitySettings::from(&GravityPlugin::new(1.0));
+    let _: Result<GravityPlugin, String> = settings.to_plugin();
+    let _ = InputEvent::SetGravity { gravity: Some(settings) };
+    let _: &GravityPlugin = GravitySystem::new(GravityPlugin::new(1.0)).plugin();
+}
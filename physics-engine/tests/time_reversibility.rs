@@ -0,0 +1,192 @@
+// Copyright 2025 John Brosnihan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Time-reversibility tests
+//!
+//! A three-body system is integrated forward, the timestep is negated with
+//! `reverse_time()`, and the same number of steps is taken back. The
+//! symplectic integrators refresh forces at every evaluation and must return
+//! to the initial state up to rounding; RK4 is not time-reversible and only
+//! gets back to within its truncation error.
+
+use physics_engine::Real;
+use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+use physics_engine::ecs::systems::{apply_forces_to_acceleration, Force, ForceRegistry};
+use physics_engine::ecs::{ComponentStorage, Entity, HashMapStorage, World};
+use physics_engine::integration::{Integrator, RK4Integrator, VelocityVerletIntegrator, YoshidaIntegrator};
+use physics_engine::plugins::gravity::{GravityPlugin, GravitySystem};
+use std::collections::HashMap;
+
+const STEPS: usize = 1000;
+const DT: Real = 1e-3;
+
+struct ThreeBody {
+    entities: Vec<Entity>,
+    positions: HashMapStorage<Position>,
+    velocities: HashMapStorage<Velocity>,
+    accelerations: HashMapStorage<Acceleration>,
+    masses: HashMapStorage<Mass>,
+    gravity: GravitySystem,
+    registry: ForceRegistry,
+    forces: HashMap<Entity, Force>,
+}
+
+impl ThreeBody {
+    /// Three unequal masses on a bound, non-periodic configuration (G = 1)
+    fn new() -> Self {
+        let mut world = World::new();
+        let bodies: [(Real, Position, Velocity); 3] = [
+            (1.0, Position::new(0.0, 0.0, 0.0), Velocity::new(0.0, -0.05, 0.0)),
+            (0.3, Position::new(1.0, 0.0, 0.1), Velocity::new(0.0, 0.9, 0.0)),
+            (0.2, Position::new(-0.8, 0.6, 0.0), Velocity::new(-0.5, -0.6, 0.1)),
+        ];
+        let mut system = ThreeBody {
+            entities: Vec::new(),
+            positions: HashMapStorage::new(),
+            velocities: HashMapStorage::new(),
+            accelerations: HashMapStorage::new(),
+            masses: HashMapStorage::new(),
+            gravity: {
+                let mut plugin = GravityPlugin::new(1.0);
+                plugin.set_softening(0.0);
+                GravitySystem::new(plugin)
+            },
+            registry: ForceRegistry::new(),
+            forces: HashMap::new(),
+        };
+        system.registry.set_warn_on_missing_components(false);
+        for (mass, position, velocity) in bodies {
+            let entity = world.create_entity();
+            system.entities.push(entity);
+            system.positions.insert(entity, position);
+            system.velocities.insert(entity, velocity);
+            system.masses.insert(entity, Mass::new(mass));
+        }
+        system.refresh_accelerations();
+        system
+    }
+
+    /// Register gravity at the current positions and convert it to accelerations
+    fn refresh_accelerations(&mut self) {
+        self.registry.clear();
+        self.gravity.compute_forces_into(&self.entities, &self.positions, &self.masses, &mut self.forces);
+        self.registry.register_forces(&self.forces);
+        apply_forces_to_acceleration(self.entities.iter(), &self.registry, &self.masses, &mut self.accelerations, false);
+    }
+
+    fn positions(&self) -> Vec<Position> {
+        self.entities.iter().map(|e| *self.positions.get(*e).unwrap()).collect()
+    }
+
+    fn max_position_error(&self, initial: &[Position]) -> Real {
+        self.positions()
+            .iter()
+            .zip(initial)
+            .flat_map(|(a, b)| a.as_array().into_iter().zip(b.as_array()).map(|(x, y)| (x - y).abs()))
+            .fold(0.0, Real::max)
+    }
+}
+
+/// Step with forces refreshed before every evaluation, as the symplectic
+/// integrators' `integrate_with()` allows
+macro_rules! run_with_refresh {
+    ($system:expr, $integrator:expr, $steps:expr) => {{
+        let system = &mut $system;
+        for _ in 0..$steps {
+            let (gravity, masses, forces) = (&system.gravity, &system.masses, &mut system.forces);
+            $integrator.integrate_with(
+                system.entities.iter(),
+                &mut system.positions,
+                &mut system.velocities,
+                &system.accelerations,
+                &system.masses,
+                &mut system.registry,
+                false,
+                |entities, positions, registry| {
+                    registry.clear();
+                    gravity.compute_forces_into(entities, positions, masses, forces);
+                    registry.register_forces(forces);
+                },
+            );
+            system.refresh_accelerations();
+        }
+    }};
+}
+
+#[test]
+#[cfg_attr(feature = "single-precision", ignore = "f32 rounding exceeds the 1e-8 bound")]
+fn test_verlet_retraces_three_body_run() {
+    let mut system = ThreeBody::new();
+    let initial = system.positions();
+    let mut integrator = VelocityVerletIntegrator::new(DT);
+
+    run_with_refresh!(system, integrator, STEPS);
+    let displacement = system.max_position_error(&initial);
+    assert!(displacement > 0.1, "Bodies barely moved: {}", displacement);
+
+    integrator.reverse_time();
+    assert_eq!(integrator.timestep(), -DT);
+    assert!(integrator.validate_timestep().is_ok());
+    run_with_refresh!(system, integrator, STEPS);
+
+    let error = system.max_position_error(&initial);
+    println!("Verlet round-trip error: {:.3e}", error);
+    assert!(error < 1e-8, "Verlet did not return to the start: {:.3e}", error);
+}
+
+#[test]
+#[cfg_attr(feature = "single-precision", ignore = "f32 rounding exceeds the 1e-8 bound")]
+fn test_yoshida_retraces_three_body_run() {
+    let mut system = ThreeBody::new();
+    let initial = system.positions();
+    let mut integrator = YoshidaIntegrator::new(DT);
+
+    run_with_refresh!(system, integrator, STEPS);
+    integrator.reverse_time();
+    run_with_refresh!(system, integrator, STEPS);
+
+    let error = system.max_position_error(&initial);
+    println!("Yoshida round-trip error: {:.3e}", error);
+    assert!(error < 1e-8, "Yoshida did not return to the start: {:.3e}", error);
+}
+
+#[test]
+#[cfg_attr(feature = "single-precision", ignore = "f32 rounding exceeds the RK4 truncation bound")]
+fn test_rk4_returns_within_truncation_error() {
+    let mut system = ThreeBody::new();
+    let initial = system.positions();
+    let mut integrator = RK4Integrator::new(DT);
+
+    run_with_refresh!(system, integrator, STEPS);
+    let displacement = system.max_position_error(&initial);
+    assert!(displacement > 0.1, "Bodies barely moved: {}", displacement);
+    integrator.reverse_time();
+    run_with_refresh!(system, integrator, STEPS);
+
+    // RK4 is not symmetric, but a forward and a backward step only disagree at
+    // O(dt^5), so the round trip must close to within O(dt^4)
+    let error = system.max_position_error(&initial);
+    let bound = DT.powi(4);
+    println!("RK4 round-trip error: {:.3e}", error);
+    assert!(error < bound, "RK4 strayed too far on the way back: {:.3e}", error);
+}
+
+#[test]
+fn test_reversal_restores_forward_direction() {
+    let mut integrator = VelocityVerletIntegrator::new(DT);
+    integrator.set_max_substep(Some(DT / 4.0));
+    integrator.reverse_time();
+    assert_eq!(integrator.substep_count(), 4);
+    integrator.reverse_time();
+    assert_eq!(integrator.timestep(), DT);
+}
@@ -0,0 +1,21 @@
+// NewtonianState rejects storages of the wrong component type
+use physics_engine::ecs::components::{Acceleration, Mass, Position, Velocity};
+use physics_engine::ecs::{HashMapStorage, NewtonianState};
+
+fn main() {
+    // Positions and velocities swapped
+    let _ = NewtonianState::new(
+        HashMapStorage::<Velocity>::new(),
+        HashMapStorage::<Position>::new(),
+        HashMapStorage::<Acceleration>::new(),
+        HashMapStorage::<Mass>::new(),
+    );
+
+    // Velocities and accelerations swapped
+    let _ = NewtonianState::new(
+        HashMapStorage::<Position>::new(),
+        HashMapStorage::<Acceleration>::new(),
+        HashMapStorage::<Velocity>::new(),
+        HashMapStorage::<Mass>::new(),
+    );
+}